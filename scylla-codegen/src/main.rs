@@ -0,0 +1,69 @@
+//! Generates Rust types for the tables and user defined types of a keyspace.
+//!
+//! The schema is fetched from the cluster, or read from a file with CQL DDL
+//! statements, e.g. the output of `DESCRIBE SCHEMA`:
+//!
+//! ```text
+//! scylla-codegen my_keyspace --node 127.0.0.1:9042 > src/schema.rs
+//! scylla-codegen my_keyspace --ddl schema.cql --derive-table > src/schema.rs
+//! ```
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use scylla::client::session::Session;
+use scylla::client::session_builder::SessionBuilder;
+use scylla::cluster::codegen::{generate_keyspace, parse_ddl, CodegenOptions};
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// Keyspace for which the types are generated.
+    #[clap(value_parser)]
+    keyspace: String,
+
+    /// Address of the node to fetch the schema from.
+    #[clap(short, long, value_parser, default_value = "127.0.0.1:9042")]
+    node: String,
+
+    /// File with CQL DDL statements to read the schema from, instead of connecting to a node.
+    #[clap(short, long, value_parser)]
+    ddl: Option<String>,
+
+    /// Derive `Table` for structs representing tables.
+    #[clap(long, value_parser)]
+    derive_table: bool,
+
+    /// Path of the driver crate in the generated code.
+    #[clap(long, value_parser, default_value = "scylla")]
+    crate_path: String,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let options = CodegenOptions::new()
+        .crate_path(args.crate_path)
+        .derive_table(args.derive_table);
+
+    let code = match args.ddl {
+        Some(path) => {
+            let ddl = std::fs::read_to_string(&path)?;
+            let keyspaces = parse_ddl(&ddl).map_err(|err| anyhow!("{path}: {err}"))?;
+            let keyspace = keyspaces
+                .get(&args.keyspace)
+                .ok_or_else(|| anyhow!("keyspace {} is not defined in {path}", args.keyspace))?;
+            generate_keyspace(&args.keyspace, keyspace, &options)
+        }
+        None => {
+            let session: Session = SessionBuilder::new().known_node(args.node).build().await?;
+            let cluster_state = session.get_cluster_state();
+            let keyspace = cluster_state
+                .get_keyspace(&args.keyspace)
+                .ok_or_else(|| anyhow!("keyspace {} does not exist", args.keyspace))?;
+            generate_keyspace(&args.keyspace, keyspace, &options)
+        }
+    };
+
+    print!("{code}");
+    Ok(())
+}
@@ -5,7 +5,7 @@ use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use scylla_cql::frame::request::query::PagingState;
 use scylla_cql::frame::request::SerializableRequest;
 use scylla_cql::frame::response::result::{ColumnType, NativeType};
-use scylla_cql::frame::{request::query, Compression, SerializedRequest};
+use scylla_cql::frame::{request::query, Compression, ProtocolVersion, SerializedRequest};
 use scylla_cql::serialize::row::SerializedValues;
 
 fn make_query(contents: &str, values: SerializedValues) -> query::Query<'_> {
@@ -19,6 +19,7 @@ fn make_query(contents: &str, values: SerializedValues) -> query::Query<'_> {
             page_size: None,
             paging_state: PagingState::start(),
             timestamp: None,
+            keyspace: None,
         },
     }
 }
@@ -47,7 +48,7 @@ fn serialized_request_make_bench(c: &mut Criterion) {
     let queries = query_args.map(|(q, v)| make_query(q, v));
 
     for query in queries {
-        let query_size = query.to_bytes().unwrap().len();
+        let query_size = query.to_bytes(ProtocolVersion::V4).unwrap().len();
         group.bench_with_input(
             BenchmarkId::new("SerializedRequest::make", query_size),
             &query,
@@ -55,8 +56,10 @@ fn serialized_request_make_bench(c: &mut Criterion) {
                 b.iter(|| {
                     let _ = criterion::black_box(SerializedRequest::make(
                         query,
+                        ProtocolVersion::V4,
                         Some(Compression::Lz4),
                         false,
+                        None,
                     ));
                 })
             },
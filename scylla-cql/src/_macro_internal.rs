@@ -1,5 +1,12 @@
-pub use crate::frame::response::result::{ColumnSpec, ColumnType};
+pub use crate::frame::response::result::{
+    CollectionType, ColumnSpec, ColumnType, NativeType, UserDefinedType,
+};
+pub use crate::value::{
+    Counter, CqlDate, CqlDecimal, CqlDuration, CqlTime, CqlTimestamp, CqlTimeuuid, CqlValue,
+    CqlVarint,
+};
 pub use crate::{DeserializeRow, DeserializeValue, SerializeRow, SerializeValue};
+pub use uuid::Uuid;
 
 pub use crate::deserialize::row::{
     deser_error_replace_rust_name as row_deser_error_replace_rust_name,
@@ -7,6 +14,7 @@ pub use crate::deserialize::row::{
     BuiltinDeserializationError as BuiltinRowDeserializationError,
     BuiltinDeserializationErrorKind as BuiltinRowDeserializationErrorKind,
     BuiltinTypeCheckErrorKind as DeserBuiltinRowTypeCheckErrorKind, ColumnIterator, DeserializeRow,
+    RowSelectors,
 };
 pub use crate::deserialize::value::{
     deser_error_replace_rust_name as value_deser_error_replace_rust_name,
@@ -17,6 +25,7 @@ pub use crate::deserialize::value::{
     UdtDeserializationErrorKind, UdtIterator, UdtTypeCheckErrorKind as DeserUdtTypeCheckErrorKind,
 };
 pub use crate::deserialize::{DeserializationError, FrameSlice, TypeCheckError};
+pub use crate::serialize::checked::{check_serialize_as, cql as cql_type, SerializeAs};
 pub use crate::serialize::row::{
     BuiltinSerializationError as BuiltinRowSerializationError,
     BuiltinSerializationErrorKind as BuiltinRowSerializationErrorKind,
@@ -33,6 +42,7 @@ pub use crate::serialize::value::{
 };
 pub use crate::serialize::writers::WrittenCellProof;
 pub use crate::serialize::{CellValueBuilder, CellWriter, RowWriter, SerializationError};
+pub use crate::utils::quote_identifier;
 
 /// Represents a set of values that can be sent along a CQL statement when serializing by name
 ///
@@ -115,7 +125,88 @@ impl<T: SerializeRowInOrder + ?Sized> SerializeRowInOrder for &T {
     }
 }
 
+/// Represents a struct that can be deserialized from the columns of a row matched by name,
+/// ignoring the columns that don't belong to it.
+///
+/// For now this trait is an implementation detail of `#[derive(DeserializeRow)]` when
+/// deserializing by name
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be flattened here",
+    label = "`{Self}` is not a struct that derives `DeserializeRow` with `match_by_name` flavor",
+    note = "There are two common reasons for that:
+- `{Self}` does not use `#[derive(DeserializeRow)]`
+- `{Self}` uses `#[scylla(flavor = \"enforce_order\")]`"
+)]
+pub trait DeserializeRowByName<'frame, 'metadata>: DeserializeRow<'frame, 'metadata> {
+    /// Returns whether a column with given name belongs to this struct
+    /// (possibly to one of its flattened fields).
+    fn has_column(name: &str) -> bool;
+
+    /// Deserializes the struct from the columns that belong to it, skipping other columns.
+    ///
+    /// The columns are assumed to be type checked with [DeserializeRow::type_check]
+    /// beforehand.
+    fn deserialize_by_name(
+        row: ColumnIterator<'frame, 'metadata>,
+    ) -> Result<Self, DeserializationError>;
+}
+
+/// Represents a struct that can be deserialized from a fixed number of consecutive columns.
+///
+/// For now this trait is an implementation detail of `#[derive(DeserializeRow)]` when
+/// deserializing in order
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be flattened here",
+    label = "`{Self}` is not a struct that derives `DeserializeRow` with `enforce_order` flavor",
+    note = "There are two common reasons for that:
+- `{Self}` does not use `#[derive(DeserializeRow)]`
+- `{Self}` uses `#[scylla(flavor = \"match_by_name\")]` (which is the default)"
+)]
+pub trait DeserializeRowInOrder<'frame, 'metadata>: DeserializeRow<'frame, 'metadata> {
+    /// The number of columns that this struct (including its flattened fields) consumes.
+    const COLUMN_COUNT: usize;
+
+    /// Deserializes the struct from the next [Self::COLUMN_COUNT] columns of the row.
+    ///
+    /// The columns are assumed to be type checked with [DeserializeRow::type_check]
+    /// beforehand.
+    fn deserialize_in_order(
+        row: &mut ColumnIterator<'frame, 'metadata>,
+    ) -> Result<Self, DeserializationError>;
+}
+
 pub mod ser {
+    pub mod value {
+        use crate::frame::response::result::ColumnType;
+        use crate::serialize::value::SerializeValue;
+        use crate::serialize::writers::WrittenCellProof;
+        use crate::serialize::{CellWriter, SerializationError};
+
+        /// Signature of the `serialize` function of a `#[scylla(with = "...")]` module.
+        pub type SerializeFn<T> = for<'b> fn(
+            &T,
+            &ColumnType,
+            CellWriter<'b>,
+        )
+            -> Result<WrittenCellProof<'b>, SerializationError>;
+
+        /// Serializes a field with a custom function instead of its [SerializeValue] impl.
+        ///
+        /// Implementation detail of `#[scylla(with = "...")]` field attribute.
+        pub struct SerializeWith<'t, T: ?Sized>(pub &'t T, pub SerializeFn<T>);
+
+        impl<T: ?Sized> SerializeValue for SerializeWith<'_, T> {
+            #[inline]
+            fn serialize<'b>(
+                &self,
+                typ: &ColumnType,
+                writer: CellWriter<'b>,
+            ) -> Result<WrittenCellProof<'b>, SerializationError> {
+                (self.1)(self.0, typ, writer)
+            }
+        }
+    }
+
     pub mod row {
         use super::super::{PartialSerializeRowByName, SerializeRowByName, SerializeRowInOrder};
         use crate::{
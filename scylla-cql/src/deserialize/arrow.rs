@@ -0,0 +1,1067 @@
+//! Conversion of query results into Apache Arrow [RecordBatch]es.
+//!
+//! Rows are decoded straight from the serialized response, column by column,
+//! into Arrow array builders - no intermediate [CqlValue](crate::value::CqlValue)
+//! is created for any cell.
+//!
+//! The Arrow schema is derived from the column specifications of the result:
+//!
+//! | CQL type                       | Arrow data type                                  |
+//! |--------------------------------|--------------------------------------------------|
+//! | `boolean`                      | `Boolean`                                        |
+//! | `tinyint`                      | `Int8`                                           |
+//! | `smallint`                     | `Int16`                                          |
+//! | `int`                          | `Int32`                                          |
+//! | `bigint`, `counter`            | `Int64`                                          |
+//! | `float`                        | `Float32`                                        |
+//! | `double`                       | `Float64`                                        |
+//! | `ascii`, `text`                | `Utf8`                                           |
+//! | `blob`                         | `Binary`                                         |
+//! | `uuid`, `timeuuid`             | `FixedSizeBinary(16)`                            |
+//! | `inet`                         | `Utf8`                                           |
+//! | `varint`, `decimal`            | `Utf8`, e.g. `"-12.345"`                         |
+//! | `date`                         | `Date32`                                         |
+//! | `time`                         | `Time64(Nanosecond)`                             |
+//! | `timestamp`                    | `Timestamp(Millisecond, "UTC")`                  |
+//! | `duration`                     | `Interval(MonthDayNano)`                         |
+//! | `list<T>`, `set<T>`            | `List(T)`                                        |
+//! | `map<K, V>`                    | `Map(Struct(key: K, value: V))`                  |
+//! | `vector<T, N>`                 | `FixedSizeList(T, N)`                            |
+//! | user defined type              | `Struct`, with the fields of the UDT             |
+//! | `tuple<...>`                   | `Struct`, with fields named `"0"`, `"1"`, ...    |
+//!
+//! All columns and nested fields are nullable, except for map keys.
+//! Empty values of types other than `ascii`, `text` and `blob` are converted to nulls.
+
+use std::fmt::Write;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use arrow_array_53::builder::{
+    BinaryBuilder, BooleanBuilder, Date32Builder, FixedSizeBinaryBuilder, Float32Builder,
+    Float64Builder, Int16Builder, Int32Builder, Int64Builder, Int8Builder,
+    IntervalMonthDayNanoBuilder, StringBuilder, Time64NanosecondBuilder,
+    TimestampMillisecondBuilder,
+};
+use arrow_array_53::{
+    ArrayRef, FixedSizeListArray, ListArray, MapArray, RecordBatch, RecordBatchOptions, StructArray,
+};
+use arrow_buffer_53::{IntervalMonthDayNano, NullBufferBuilder, OffsetBuffer};
+use arrow_schema_53::{
+    ArrowError, DataType, Field, FieldRef, Fields, IntervalUnit, Schema, SchemaRef, TimeUnit,
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::row::ColumnIterator;
+use super::value::DeserializeValue;
+use super::{DeserializationError, FrameSlice};
+use crate::frame::frame_errors::LowLevelDeserializationError;
+use crate::frame::response::result::{
+    CollectionType, ColumnSpec, ColumnType, DeserializedMetadataAndRawRows, NativeType,
+};
+use crate::frame::types;
+use crate::value::{
+    Counter, CqlDate, CqlDecimalBorrowed, CqlDuration, CqlTime, CqlTimestamp, CqlTimeuuid,
+    CqlVarintBorrowed,
+};
+
+/// An error that occurred while converting rows into a [RecordBatch].
+#[derive(Error, Debug, Clone)]
+#[non_exhaustive]
+pub enum ArrowConversionError {
+    /// Failed to read a row from the serialized response.
+    #[error("Failed to deserialize a row: {0}")]
+    RowDeserializationFailed(DeserializationError),
+
+    /// Failed to deserialize a value of a column.
+    #[error("Failed to deserialize column {column_name} (index {column_index}): {err}")]
+    ColumnDeserializationFailed {
+        /// Index of the column.
+        column_index: usize,
+        /// Name of the column.
+        column_name: String,
+        /// The deserialization error.
+        err: DeserializationError,
+    },
+
+    /// A row had a different number of columns than the schema.
+    #[error("Row has {actual} columns, but the schema has {expected}")]
+    ColumnCountMismatch {
+        /// Number of columns in the schema.
+        expected: usize,
+        /// Number of columns in the row.
+        actual: usize,
+    },
+
+    /// Arrow refused to construct an array or a record batch.
+    #[error("Failed to build Arrow arrays: {0}")]
+    ArrowError(Arc<ArrowError>),
+}
+
+impl From<ArrowError> for ArrowConversionError {
+    fn from(err: ArrowError) -> Self {
+        Self::ArrowError(Arc::new(err))
+    }
+}
+
+/// Returns the Arrow data type corresponding to given CQL type.
+///
+/// See the [module-level documentation](self) for the mapping.
+pub fn arrow_data_type(typ: &ColumnType) -> DataType {
+    match typ {
+        ColumnType::Native(native) => match native {
+            NativeType::Boolean => DataType::Boolean,
+            NativeType::TinyInt => DataType::Int8,
+            NativeType::SmallInt => DataType::Int16,
+            NativeType::Int => DataType::Int32,
+            NativeType::BigInt | NativeType::Counter => DataType::Int64,
+            NativeType::Float => DataType::Float32,
+            NativeType::Double => DataType::Float64,
+            NativeType::Ascii
+            | NativeType::Text
+            | NativeType::Inet
+            | NativeType::Varint
+            | NativeType::Decimal => DataType::Utf8,
+            NativeType::Blob => DataType::Binary,
+            NativeType::Uuid | NativeType::Timeuuid => DataType::FixedSizeBinary(16),
+            NativeType::Date => DataType::Date32,
+            NativeType::Time => DataType::Time64(TimeUnit::Nanosecond),
+            NativeType::Timestamp => DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            NativeType::Duration => DataType::Interval(IntervalUnit::MonthDayNano),
+        },
+        ColumnType::Collection { typ, .. } => match typ {
+            CollectionType::List(elem) | CollectionType::Set(elem) => {
+                DataType::List(list_item_field(elem))
+            }
+            CollectionType::Map(key, value) => DataType::Map(map_entries_field(key, value), false),
+        },
+        ColumnType::Vector { typ, dimensions } => {
+            DataType::FixedSizeList(list_item_field(typ), i32::from(*dimensions))
+        }
+        ColumnType::UserDefinedType { definition, .. } => DataType::Struct(
+            definition
+                .field_types
+                .iter()
+                .map(|(name, typ)| Field::new(name.as_ref(), arrow_data_type(typ), true))
+                .collect(),
+        ),
+        ColumnType::Tuple(types) => DataType::Struct(tuple_fields(types)),
+    }
+}
+
+/// Returns the Arrow schema of rows with given columns.
+pub fn arrow_schema(specs: &[ColumnSpec<'_>]) -> Schema {
+    Schema::new(
+        specs
+            .iter()
+            .map(|spec| Field::new(spec.name(), arrow_data_type(spec.typ()), true))
+            .collect::<Fields>(),
+    )
+}
+
+/// Converts all rows of a RESULT:Rows response into a [RecordBatch].
+pub fn record_batch_from_rows(
+    rows: &DeserializedMetadataAndRawRows,
+) -> Result<RecordBatch, ArrowConversionError> {
+    let mut builder = RecordBatchBuilder::new(rows.metadata().col_specs());
+    // Type check of ColumnIterator always succeeds.
+    let rows_iter = rows
+        .rows_iter::<ColumnIterator>()
+        .expect("ColumnIterator type check cannot fail");
+    for row in rows_iter {
+        builder.append_row(row.map_err(ArrowConversionError::RowDeserializationFailed)?)?;
+    }
+    builder.finish()
+}
+
+/// Accumulates rows, decoding them into Arrow arrays, and produces [RecordBatch]es.
+///
+/// If appending a row fails, some of its columns may have already been
+/// appended, so the builder should not be used anymore.
+pub struct RecordBatchBuilder {
+    schema: SchemaRef,
+    decoders: Vec<ColumnDecoder>,
+    rows: usize,
+}
+
+impl std::fmt::Debug for RecordBatchBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordBatchBuilder")
+            .field("schema", &self.schema)
+            .field("rows", &self.rows)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RecordBatchBuilder {
+    /// Creates a builder for rows with given columns.
+    pub fn new(specs: &[ColumnSpec<'_>]) -> Self {
+        Self {
+            schema: Arc::new(arrow_schema(specs)),
+            decoders: specs
+                .iter()
+                .map(|spec| ColumnDecoder::new(spec.typ()))
+                .collect(),
+            rows: 0,
+        }
+    }
+
+    /// Returns the schema of produced record batches.
+    #[inline]
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    /// Returns the number of rows appended since the last [finish](Self::finish).
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns whether no rows were appended since the last [finish](Self::finish).
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0
+    }
+
+    /// Decodes a row and appends it to the batch being built.
+    pub fn append_row(&mut self, row: ColumnIterator<'_, '_>) -> Result<(), ArrowConversionError> {
+        let actual = row.columns_remaining();
+        if actual != self.decoders.len() {
+            return Err(ArrowConversionError::ColumnCountMismatch {
+                expected: self.decoders.len(),
+                actual,
+            });
+        }
+
+        for (decoder, column) in self.decoders.iter_mut().zip(row) {
+            let column = column.map_err(ArrowConversionError::RowDeserializationFailed)?;
+            decoder
+                .append(column.spec.typ(), column.slice)
+                .map_err(|err| ArrowConversionError::ColumnDeserializationFailed {
+                    column_index: column.index,
+                    column_name: column.spec.name().to_owned(),
+                    err,
+                })?;
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Builds a record batch from the rows appended so far, and resets the builder.
+    pub fn finish(&mut self) -> Result<RecordBatch, ArrowConversionError> {
+        let columns = self
+            .decoders
+            .iter_mut()
+            .map(ColumnDecoder::finish)
+            .collect::<Result<Vec<_>, _>>()?;
+        let options = RecordBatchOptions::new().with_row_count(Some(self.rows));
+        self.rows = 0;
+        Ok(RecordBatch::try_new_with_options(
+            self.schema.clone(),
+            columns,
+            &options,
+        )?)
+    }
+}
+
+fn list_item_field(elem: &ColumnType) -> FieldRef {
+    Arc::new(Field::new_list_field(arrow_data_type(elem), true))
+}
+
+fn map_entries_field(key: &ColumnType, value: &ColumnType) -> FieldRef {
+    Arc::new(Field::new(
+        "entries",
+        DataType::Struct(map_entry_fields(key, value)),
+        false,
+    ))
+}
+
+fn map_entry_fields(key: &ColumnType, value: &ColumnType) -> Fields {
+    Fields::from(vec![
+        Field::new("key", arrow_data_type(key), false),
+        Field::new("value", arrow_data_type(value), true),
+    ])
+}
+
+fn tuple_fields(types: &[ColumnType]) -> Fields {
+    types
+        .iter()
+        .enumerate()
+        .map(|(i, typ)| Field::new(i.to_string(), arrow_data_type(typ), true))
+        .collect()
+}
+
+/// Decodes serialized values of a single CQL type into an Arrow array.
+///
+/// The decoder's variant always corresponds to the type passed to [ColumnDecoder::append].
+enum ColumnDecoder {
+    Boolean(BooleanBuilder),
+    TinyInt(Int8Builder),
+    SmallInt(Int16Builder),
+    Int(Int32Builder),
+    BigInt(Int64Builder),
+    Float(Float32Builder),
+    Double(Float64Builder),
+    Utf8(StringBuilder),
+    Binary(BinaryBuilder),
+    Uuid(FixedSizeBinaryBuilder),
+    Date(Date32Builder),
+    Time(Time64NanosecondBuilder),
+    Timestamp(TimestampMillisecondBuilder),
+    Duration(IntervalMonthDayNanoBuilder),
+    List {
+        item_field: FieldRef,
+        offsets: Vec<i32>,
+        nulls: NullBufferBuilder,
+        items: Box<ColumnDecoder>,
+    },
+    Map {
+        entries_fields: Fields,
+        offsets: Vec<i32>,
+        nulls: NullBufferBuilder,
+        keys: Box<ColumnDecoder>,
+        values: Box<ColumnDecoder>,
+    },
+    Vector {
+        item_field: FieldRef,
+        dimensions: i32,
+        nulls: NullBufferBuilder,
+        items: Box<ColumnDecoder>,
+    },
+    Struct {
+        fields: Fields,
+        nulls: NullBufferBuilder,
+        children: Vec<ColumnDecoder>,
+    },
+}
+
+impl ColumnDecoder {
+    fn new(typ: &ColumnType) -> Self {
+        match typ {
+            ColumnType::Native(native) => match native {
+                NativeType::Boolean => Self::Boolean(BooleanBuilder::new()),
+                NativeType::TinyInt => Self::TinyInt(Int8Builder::new()),
+                NativeType::SmallInt => Self::SmallInt(Int16Builder::new()),
+                NativeType::Int => Self::Int(Int32Builder::new()),
+                NativeType::BigInt | NativeType::Counter => Self::BigInt(Int64Builder::new()),
+                NativeType::Float => Self::Float(Float32Builder::new()),
+                NativeType::Double => Self::Double(Float64Builder::new()),
+                NativeType::Ascii
+                | NativeType::Text
+                | NativeType::Inet
+                | NativeType::Varint
+                | NativeType::Decimal => Self::Utf8(StringBuilder::new()),
+                NativeType::Blob => Self::Binary(BinaryBuilder::new()),
+                NativeType::Uuid | NativeType::Timeuuid => {
+                    Self::Uuid(FixedSizeBinaryBuilder::new(16))
+                }
+                NativeType::Date => Self::Date(Date32Builder::new()),
+                NativeType::Time => Self::Time(Time64NanosecondBuilder::new()),
+                NativeType::Timestamp => {
+                    Self::Timestamp(TimestampMillisecondBuilder::new().with_timezone("UTC"))
+                }
+                NativeType::Duration => Self::Duration(IntervalMonthDayNanoBuilder::new()),
+            },
+            ColumnType::Collection { typ, .. } => match typ {
+                CollectionType::List(elem) | CollectionType::Set(elem) => Self::List {
+                    item_field: list_item_field(elem),
+                    offsets: vec![0],
+                    nulls: NullBufferBuilder::new(0),
+                    items: Box::new(Self::new(elem)),
+                },
+                CollectionType::Map(key, value) => Self::Map {
+                    entries_fields: map_entry_fields(key, value),
+                    offsets: vec![0],
+                    nulls: NullBufferBuilder::new(0),
+                    keys: Box::new(Self::new(key)),
+                    values: Box::new(Self::new(value)),
+                },
+            },
+            ColumnType::Vector { typ, dimensions } => Self::Vector {
+                item_field: list_item_field(typ),
+                dimensions: i32::from(*dimensions),
+                nulls: NullBufferBuilder::new(0),
+                items: Box::new(Self::new(typ)),
+            },
+            ColumnType::UserDefinedType { definition, .. } => Self::Struct {
+                fields: match arrow_data_type(typ) {
+                    DataType::Struct(fields) => fields,
+                    _ => unreachable!(),
+                },
+                nulls: NullBufferBuilder::new(0),
+                children: definition
+                    .field_types
+                    .iter()
+                    .map(|(_, typ)| Self::new(typ))
+                    .collect(),
+            },
+            ColumnType::Tuple(types) => Self::Struct {
+                fields: tuple_fields(types),
+                nulls: NullBufferBuilder::new(0),
+                children: types.iter().map(Self::new).collect(),
+            },
+        }
+    }
+
+    fn append_null(&mut self) {
+        match self {
+            Self::Boolean(b) => b.append_null(),
+            Self::TinyInt(b) => b.append_null(),
+            Self::SmallInt(b) => b.append_null(),
+            Self::Int(b) => b.append_null(),
+            Self::BigInt(b) => b.append_null(),
+            Self::Float(b) => b.append_null(),
+            Self::Double(b) => b.append_null(),
+            Self::Utf8(b) => b.append_null(),
+            Self::Binary(b) => b.append_null(),
+            Self::Uuid(b) => b.append_null(),
+            Self::Date(b) => b.append_null(),
+            Self::Time(b) => b.append_null(),
+            Self::Timestamp(b) => b.append_null(),
+            Self::Duration(b) => b.append_null(),
+            Self::List { offsets, nulls, .. } | Self::Map { offsets, nulls, .. } => {
+                offsets.push(*offsets.last().unwrap());
+                nulls.append_null();
+            }
+            Self::Vector {
+                dimensions,
+                nulls,
+                items,
+                ..
+            } => {
+                // Fixed size lists reserve space for the elements of null entries.
+                for _ in 0..*dimensions {
+                    items.append_null();
+                }
+                nulls.append_null();
+            }
+            Self::Struct {
+                nulls, children, ..
+            } => {
+                for child in children {
+                    child.append_null();
+                }
+                nulls.append_null();
+            }
+        }
+    }
+
+    fn append<'frame, 'metadata>(
+        &mut self,
+        typ: &'metadata ColumnType<'metadata>,
+        v: Option<FrameSlice<'frame>>,
+    ) -> Result<(), DeserializationError> {
+        let Some(mut v) = v else {
+            self.append_null();
+            return Ok(());
+        };
+        let is_textual = matches!(
+            typ,
+            ColumnType::Native(NativeType::Ascii | NativeType::Text | NativeType::Blob)
+        );
+        if v.is_empty() && !is_textual {
+            self.append_null();
+            return Ok(());
+        }
+
+        fn deser<'frame, 'metadata, T: DeserializeValue<'frame, 'metadata>>(
+            typ: &'metadata ColumnType<'metadata>,
+            v: FrameSlice<'frame>,
+        ) -> Result<T, DeserializationError> {
+            T::deserialize(typ, Some(v))
+        }
+
+        match self {
+            Self::Boolean(b) => b.append_value(deser(typ, v)?),
+            Self::TinyInt(b) => b.append_value(deser(typ, v)?),
+            Self::SmallInt(b) => b.append_value(deser(typ, v)?),
+            Self::Int(b) => b.append_value(deser(typ, v)?),
+            Self::BigInt(b) => match typ {
+                ColumnType::Native(NativeType::Counter) => {
+                    b.append_value(deser::<Counter>(typ, v)?.0)
+                }
+                _ => b.append_value(deser(typ, v)?),
+            },
+            Self::Float(b) => b.append_value(deser(typ, v)?),
+            Self::Double(b) => b.append_value(deser(typ, v)?),
+            Self::Utf8(b) => match typ {
+                ColumnType::Native(NativeType::Inet) => {
+                    write!(b, "{}", deser::<IpAddr>(typ, v)?).unwrap();
+                    b.append_value("");
+                }
+                ColumnType::Native(NativeType::Varint) => {
+                    let varint = deser::<CqlVarintBorrowed>(typ, v)?;
+                    write_decimal(b, varint.as_signed_bytes_be_slice(), 0);
+                    b.append_value("");
+                }
+                ColumnType::Native(NativeType::Decimal) => {
+                    let decimal = deser::<CqlDecimalBorrowed>(typ, v)?;
+                    let (bytes, scale) = decimal.as_signed_be_bytes_slice_and_exponent();
+                    write_decimal(b, bytes, scale);
+                    b.append_value("");
+                }
+                _ => b.append_value(deser::<&str>(typ, v)?),
+            },
+            Self::Binary(b) => b.append_value(deser::<&[u8]>(typ, v)?),
+            Self::Uuid(b) => {
+                let uuid = match typ {
+                    ColumnType::Native(NativeType::Timeuuid) => {
+                        *deser::<CqlTimeuuid>(typ, v)?.as_ref()
+                    }
+                    _ => deser::<Uuid>(typ, v)?,
+                };
+                b.append_value(uuid.as_bytes())
+                    .map_err(DeserializationError::new)?;
+            }
+            Self::Date(b) => {
+                let CqlDate(days) = deser(typ, v)?;
+                // Shifting the unsigned representation by 2^31 always fits in an i32.
+                b.append_value((i64::from(days) - (1 << 31)) as i32);
+            }
+            Self::Time(b) => b.append_value(deser::<CqlTime>(typ, v)?.0),
+            Self::Timestamp(b) => b.append_value(deser::<CqlTimestamp>(typ, v)?.0),
+            Self::Duration(b) => {
+                let CqlDuration {
+                    months,
+                    days,
+                    nanoseconds,
+                } = deser(typ, v)?;
+                b.append_value(IntervalMonthDayNano::new(months, days, nanoseconds));
+            }
+            Self::List {
+                offsets,
+                nulls,
+                items,
+                ..
+            } => {
+                let elem_typ = match typ {
+                    ColumnType::Collection {
+                        typ: CollectionType::List(elem) | CollectionType::Set(elem),
+                        ..
+                    } => elem,
+                    _ => unreachable!("Decoder does not match the type"),
+                };
+                let count = types::read_int_length(v.as_slice_mut()).map_err(mk_low_level_err)?;
+                for _ in 0..count {
+                    items.append(elem_typ, v.read_cql_bytes().map_err(mk_low_level_err)?)?;
+                }
+                push_offset(offsets, count)?;
+                nulls.append_non_null();
+            }
+            Self::Map {
+                offsets,
+                nulls,
+                keys,
+                values,
+                ..
+            } => {
+                let (key_typ, value_typ) = match typ {
+                    ColumnType::Collection {
+                        typ: CollectionType::Map(key, value),
+                        ..
+                    } => (key, value),
+                    _ => unreachable!("Decoder does not match the type"),
+                };
+                let count = types::read_int_length(v.as_slice_mut()).map_err(mk_low_level_err)?;
+                for _ in 0..count {
+                    let key = v.read_cql_bytes().map_err(mk_low_level_err)?;
+                    if key.is_none() {
+                        return Err(DeserializationError::new(NullMapKeyError));
+                    }
+                    keys.append(key_typ, key)?;
+                    values.append(value_typ, v.read_cql_bytes().map_err(mk_low_level_err)?)?;
+                }
+                push_offset(offsets, count)?;
+                nulls.append_non_null();
+            }
+            Self::Vector {
+                dimensions,
+                nulls,
+                items,
+                ..
+            } => {
+                let elem_typ = match typ {
+                    ColumnType::Vector { typ, .. } => typ,
+                    _ => unreachable!("Decoder does not match the type"),
+                };
+                // See `VectorIterator` for the description of the vector format.
+                let element_length = elem_typ.type_size();
+                for _ in 0..*dimensions {
+                    let element = match element_length {
+                        Some(length) => {
+                            let element = v.read_n_bytes(length).map_err(mk_low_level_err)?;
+                            Some(element.ok_or_else(|| {
+                                mk_low_level_err(
+                                    LowLevelDeserializationError::TooFewBytesReceived {
+                                        expected: length,
+                                        received: 0,
+                                    },
+                                )
+                            })?)
+                        }
+                        None => {
+                            let length = types::unsigned_vint_decode(v.as_slice_mut())
+                                .map_err(mk_low_level_err)?;
+                            let length = usize::try_from(length).map_err(mk_low_level_err)?;
+                            v.read_n_bytes(length).map_err(mk_low_level_err)?
+                        }
+                    };
+                    items.append(elem_typ, element)?;
+                }
+                nulls.append_non_null();
+            }
+            Self::Struct {
+                nulls, children, ..
+            } => {
+                let field_types: Vec<&ColumnType> = match typ {
+                    ColumnType::UserDefinedType { definition, .. } => {
+                        definition.field_types.iter().map(|(_, typ)| typ).collect()
+                    }
+                    ColumnType::Tuple(types) => types.iter().collect(),
+                    _ => unreachable!("Decoder does not match the type"),
+                };
+                for (child, field_typ) in children.iter_mut().zip(field_types) {
+                    // Trailing fields may be missing from the serialized form.
+                    if v.is_empty() {
+                        child.append_null();
+                    } else {
+                        child.append(field_typ, v.read_cql_bytes().map_err(mk_low_level_err)?)?;
+                    }
+                }
+                nulls.append_non_null();
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<ArrayRef, ArrowError> {
+        let array: ArrayRef = match self {
+            Self::Boolean(b) => Arc::new(b.finish()),
+            Self::TinyInt(b) => Arc::new(b.finish()),
+            Self::SmallInt(b) => Arc::new(b.finish()),
+            Self::Int(b) => Arc::new(b.finish()),
+            Self::BigInt(b) => Arc::new(b.finish()),
+            Self::Float(b) => Arc::new(b.finish()),
+            Self::Double(b) => Arc::new(b.finish()),
+            Self::Utf8(b) => Arc::new(b.finish()),
+            Self::Binary(b) => Arc::new(b.finish()),
+            Self::Uuid(b) => Arc::new(b.finish()),
+            Self::Date(b) => Arc::new(b.finish()),
+            Self::Time(b) => Arc::new(b.finish()),
+            Self::Timestamp(b) => Arc::new(b.finish()),
+            Self::Duration(b) => Arc::new(b.finish()),
+            Self::List {
+                item_field,
+                offsets,
+                nulls,
+                items,
+            } => Arc::new(ListArray::try_new(
+                item_field.clone(),
+                take_offsets(offsets),
+                items.finish()?,
+                nulls.finish(),
+            )?),
+            Self::Map {
+                entries_fields,
+                offsets,
+                nulls,
+                keys,
+                values,
+            } => {
+                let entries = StructArray::try_new(
+                    entries_fields.clone(),
+                    vec![keys.finish()?, values.finish()?],
+                    None,
+                )?;
+                let entries_field = Arc::new(Field::new(
+                    "entries",
+                    DataType::Struct(entries_fields.clone()),
+                    false,
+                ));
+                Arc::new(MapArray::try_new(
+                    entries_field,
+                    take_offsets(offsets),
+                    entries,
+                    nulls.finish(),
+                    false,
+                )?)
+            }
+            Self::Vector {
+                item_field,
+                dimensions,
+                nulls,
+                items,
+            } => Arc::new(FixedSizeListArray::try_new(
+                item_field.clone(),
+                *dimensions,
+                items.finish()?,
+                nulls.finish(),
+            )?),
+            Self::Struct {
+                fields,
+                nulls,
+                children,
+            } => Arc::new(StructArray::try_new(
+                fields.clone(),
+                children
+                    .iter_mut()
+                    .map(Self::finish)
+                    .collect::<Result<_, _>>()?,
+                nulls.finish(),
+            )?),
+        };
+        Ok(array)
+    }
+}
+
+/// A null key was found in a serialized map.
+#[derive(Error, Debug, Clone)]
+#[error("Map keys must not be null")]
+struct NullMapKeyError;
+
+fn mk_low_level_err(err: impl Into<LowLevelDeserializationError>) -> DeserializationError {
+    DeserializationError::new(err.into())
+}
+
+fn push_offset(offsets: &mut Vec<i32>, count: usize) -> Result<(), DeserializationError> {
+    let last = *offsets.last().unwrap() as usize;
+    let next = i32::try_from(last + count).map_err(|_| {
+        DeserializationError::new(ArrowError::InvalidArgumentError(
+            "Too many elements for a 32-bit offset list".to_owned(),
+        ))
+    })?;
+    offsets.push(next);
+    Ok(())
+}
+
+fn take_offsets(offsets: &mut Vec<i32>) -> OffsetBuffer<i32> {
+    let taken = std::mem::replace(offsets, vec![0]);
+    OffsetBuffer::new(taken.into())
+}
+
+/// Writes a decimal representation of `unscaled * 10^(-scale)`, where `unscaled`
+/// is a big-endian two's complement integer of arbitrary length.
+fn write_decimal(out: &mut impl Write, bytes: &[u8], scale: i32) {
+    let negative = bytes.first().is_some_and(|b| b & 0x80 != 0);
+    let mut magnitude = bytes.to_vec();
+    if negative {
+        // Two's complement negation: invert and add one.
+        for b in magnitude.iter_mut() {
+            *b = !*b;
+        }
+        for b in magnitude.iter_mut().rev() {
+            let (sum, overflow) = b.overflowing_add(1);
+            *b = sum;
+            if !overflow {
+                break;
+            }
+        }
+    }
+
+    // Repeatedly divide by 10^9, collecting 9-digit chunks, least significant first.
+    const CHUNK: u64 = 1_000_000_000;
+    let mut chunks = Vec::new();
+    while magnitude.iter().any(|b| *b != 0) {
+        let mut rem = 0u64;
+        for b in magnitude.iter_mut() {
+            let cur = (rem << 8) | u64::from(*b);
+            *b = (cur / CHUNK) as u8;
+            rem = cur % CHUNK;
+        }
+        chunks.push(rem);
+    }
+    let mut digits = match chunks.split_last() {
+        Some((most_significant, rest)) => {
+            let mut digits = most_significant.to_string();
+            for chunk in rest.iter().rev() {
+                write!(digits, "{chunk:09}").unwrap();
+            }
+            digits
+        }
+        None => "0".to_owned(),
+    };
+
+    if digits != "0" {
+        match usize::try_from(scale) {
+            Ok(scale) if scale > 0 => {
+                if digits.len() <= scale {
+                    digits.insert_str(0, &"0".repeat(scale - digits.len() + 1));
+                }
+                digits.insert(digits.len() - scale, '.');
+            }
+            Ok(_) => {}
+            Err(_) => digits.push_str(&"0".repeat(scale.unsigned_abs() as usize)),
+        }
+        if negative {
+            digits.insert(0, '-');
+        }
+    }
+    out.write_str(&digits).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array_53::cast::AsArray;
+    use arrow_array_53::types::{
+        Date32Type, Int32Type, Int64Type, IntervalMonthDayNanoType, TimestampMillisecondType,
+    };
+    use arrow_array_53::Array;
+    use arrow_buffer_53::IntervalMonthDayNano;
+    use arrow_schema_53::{DataType, TimeUnit};
+    use bytes::Bytes;
+
+    use super::{arrow_data_type, write_decimal, ArrowConversionError, RecordBatchBuilder};
+    use crate::deserialize::row::ColumnIterator;
+    use crate::deserialize::FrameSlice;
+    use crate::frame::response::result::{
+        CollectionType, ColumnSpec, ColumnType, NativeType, TableSpec, UserDefinedType,
+    };
+    use crate::serialize::value::SerializeValue;
+    use crate::serialize::writers::CellWriter;
+    use crate::value::{CqlDate, CqlDuration, CqlTimestamp, CqlValue};
+
+    fn spec(name: &'static str, typ: ColumnType<'static>) -> ColumnSpec<'static> {
+        ColumnSpec::borrowed(name, typ, TableSpec::borrowed("ks", "tab"))
+    }
+
+    fn serialize_row(specs: &[ColumnSpec], values: &[Option<CqlValue>]) -> Bytes {
+        let mut buf = Vec::new();
+        for (spec, value) in specs.iter().zip(values) {
+            let writer = CellWriter::new(&mut buf);
+            match value {
+                Some(value) => {
+                    <CqlValue as SerializeValue>::serialize(value, spec.typ(), writer).unwrap();
+                }
+                None => {
+                    writer.set_null();
+                }
+            }
+        }
+        Bytes::from(buf)
+    }
+
+    fn append(builder: &mut RecordBatchBuilder, specs: &[ColumnSpec], values: &[Option<CqlValue>]) {
+        let bytes = serialize_row(specs, values);
+        builder
+            .append_row(ColumnIterator::new(specs, FrameSlice::new(&bytes)))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_native_columns() {
+        let specs = [
+            spec("id", ColumnType::Native(NativeType::Int)),
+            spec("name", ColumnType::Native(NativeType::Text)),
+            spec("day", ColumnType::Native(NativeType::Date)),
+            spec("at", ColumnType::Native(NativeType::Timestamp)),
+            spec("span", ColumnType::Native(NativeType::Duration)),
+            spec("amount", ColumnType::Native(NativeType::Decimal)),
+        ];
+        let mut builder = RecordBatchBuilder::new(&specs);
+        append(
+            &mut builder,
+            &specs,
+            &[
+                Some(CqlValue::Int(1)),
+                Some(CqlValue::Text("foo".to_owned())),
+                Some(CqlValue::Date(CqlDate((1 << 31) - 3))),
+                Some(CqlValue::Timestamp(CqlTimestamp(1_000))),
+                Some(CqlValue::Duration(CqlDuration {
+                    months: 1,
+                    days: 2,
+                    nanoseconds: 3,
+                })),
+                Some(CqlValue::Decimal(
+                    crate::value::CqlDecimal::from_signed_be_bytes_and_exponent(
+                        vec![0xcf, 0xc7],
+                        2,
+                    ),
+                )),
+            ],
+        );
+        append(&mut builder, &specs, &[None, None, None, None, None, None]);
+        assert_eq!(builder.len(), 2);
+
+        let batch = builder.finish().unwrap();
+        assert!(builder.is_empty());
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(
+            batch.schema().field(3).data_type(),
+            &DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()))
+        );
+
+        let ids = batch.column(0).as_primitive::<Int32Type>();
+        assert_eq!(ids.value(0), 1);
+        assert!(ids.is_null(1));
+        assert_eq!(batch.column(1).as_string::<i32>().value(0), "foo");
+        assert_eq!(batch.column(2).as_primitive::<Date32Type>().value(0), -3);
+        assert_eq!(
+            batch
+                .column(3)
+                .as_primitive::<TimestampMillisecondType>()
+                .value(0),
+            1_000
+        );
+        assert_eq!(
+            batch
+                .column(4)
+                .as_primitive::<IntervalMonthDayNanoType>()
+                .value(0),
+            IntervalMonthDayNano::new(1, 2, 3)
+        );
+        assert_eq!(batch.column(5).as_string::<i32>().value(0), "-123.45");
+        for column in batch.columns() {
+            assert!(column.is_null(1));
+        }
+    }
+
+    #[test]
+    fn test_nested_columns() {
+        let udt = ColumnType::UserDefinedType {
+            frozen: false,
+            definition: Arc::new(UserDefinedType {
+                name: "point".into(),
+                keyspace: "ks".into(),
+                field_types: vec![
+                    ("x".into(), ColumnType::Native(NativeType::Int)),
+                    ("label".into(), ColumnType::Native(NativeType::Text)),
+                ],
+            }),
+        };
+        let specs = [
+            spec(
+                "tags",
+                ColumnType::Collection {
+                    frozen: false,
+                    typ: CollectionType::List(Box::new(ColumnType::Native(NativeType::Text))),
+                },
+            ),
+            spec(
+                "counts",
+                ColumnType::Collection {
+                    frozen: false,
+                    typ: CollectionType::Map(
+                        Box::new(ColumnType::Native(NativeType::Text)),
+                        Box::new(ColumnType::Native(NativeType::BigInt)),
+                    ),
+                },
+            ),
+            spec(
+                "embedding",
+                ColumnType::Vector {
+                    typ: Box::new(ColumnType::Native(NativeType::Int)),
+                    dimensions: 2,
+                },
+            ),
+            spec("point", udt),
+        ];
+        let mut builder = RecordBatchBuilder::new(&specs);
+        append(
+            &mut builder,
+            &specs,
+            &[
+                Some(CqlValue::List(vec![
+                    CqlValue::Text("a".to_owned()),
+                    CqlValue::Text("b".to_owned()),
+                ])),
+                Some(CqlValue::Map(vec![(
+                    CqlValue::Text("k".to_owned()),
+                    CqlValue::BigInt(5),
+                )])),
+                Some(CqlValue::Vector(vec![CqlValue::Int(1), CqlValue::Int(2)])),
+                Some(CqlValue::UserDefinedType {
+                    keyspace: "ks".to_owned(),
+                    name: "point".to_owned(),
+                    fields: vec![("x".to_owned(), Some(CqlValue::Int(7)))],
+                }),
+            ],
+        );
+        append(&mut builder, &specs, &[None, None, None, None]);
+
+        let batch = builder.finish().unwrap();
+        assert_eq!(batch.num_rows(), 2);
+
+        let tags = batch.column(0).as_list::<i32>();
+        assert_eq!(tags.value_length(0), 2);
+        assert_eq!(tags.value(0).as_string::<i32>().value(1), "b");
+        assert!(tags.is_null(1));
+
+        let counts = batch.column(1).as_map();
+        assert_eq!(counts.keys().as_string::<i32>().value(0), "k");
+        assert_eq!(counts.values().as_primitive::<Int64Type>().value(0), 5);
+        assert!(counts.is_null(1));
+
+        let embedding = batch.column(2).as_fixed_size_list();
+        assert_eq!(
+            embedding.value(0).as_primitive::<Int32Type>().values(),
+            &[1, 2]
+        );
+        assert!(embedding.is_null(1));
+        assert_eq!(embedding.values().len(), 4);
+
+        let point = batch.column(3).as_struct();
+        assert_eq!(point.column(0).as_primitive::<Int32Type>().value(0), 7);
+        // The trailing field was missing from the serialized form.
+        assert!(point.column(1).is_null(0));
+        assert!(point.is_null(1));
+    }
+
+    #[test]
+    fn test_column_count_mismatch() {
+        let specs = [spec("a", ColumnType::Native(NativeType::Int))];
+        let mut builder = RecordBatchBuilder::new(&[]);
+        let bytes = serialize_row(&specs, &[Some(CqlValue::Int(1))]);
+        let err = builder
+            .append_row(ColumnIterator::new(&specs, FrameSlice::new(&bytes)))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ArrowConversionError::ColumnCountMismatch {
+                expected: 0,
+                actual: 1
+            }
+        ));
+    }
+
+    #[test]
+    fn test_tuple_data_type() {
+        let typ = ColumnType::Tuple(vec![
+            ColumnType::Native(NativeType::Uuid),
+            ColumnType::Native(NativeType::Inet),
+        ]);
+        let DataType::Struct(fields) = arrow_data_type(&typ) else {
+            panic!("tuple should be a struct");
+        };
+        assert_eq!(fields[0].name(), "0");
+        assert_eq!(fields[0].data_type(), &DataType::FixedSizeBinary(16));
+        assert_eq!(fields[1].data_type(), &DataType::Utf8);
+    }
+
+    #[test]
+    fn test_write_decimal() {
+        let cases: &[(&[u8], i32, &str)] = &[
+            (&[], 0, "0"),
+            (&[0x00], 3, "0"),
+            (&[0x30, 0x39], 2, "123.45"),
+            (&[0xcf, 0xc7], 2, "-123.45"),
+            (&[0x05], 3, "0.005"),
+            (&[0x05], -2, "500"),
+            (&[0xff], 0, "-1"),
+            (&[0x80], 0, "-128"),
+            (
+                &[0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+                0,
+                "4722366482869645213696",
+            ),
+        ];
+        for (bytes, scale, expected) in cases {
+            let mut out = String::new();
+            write_decimal(&mut out, bytes, *scale);
+            assert_eq!(&out, expected, "{bytes:?} {scale}");
+        }
+    }
+}
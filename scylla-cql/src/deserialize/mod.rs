@@ -210,9 +210,13 @@
 //! }
 //! ```
 
+#[cfg(feature = "arrow-53")]
+pub mod arrow;
 pub mod frame_slice;
 pub mod result;
 pub mod row;
+#[cfg(feature = "serde")]
+pub mod serde_bridge;
 pub mod value;
 
 pub use frame_slice::FrameSlice;
@@ -289,6 +293,27 @@ impl DeserializationError {
     }
 }
 
+/// An error returned when a column of a [`row::NamedRow`] or a field
+/// of a [`value::NamedUdt`] is accessed by name.
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum GetByNameError {
+    /// There is no column or field with the given name.
+    #[error("No column or field named {name}")]
+    NotFound { name: String },
+
+    /// The requested Rust type can't be deserialized from the CQL type of the column or field.
+    #[error("Failed to type check {name}: {err}")]
+    TypeCheckFailed { name: String, err: TypeCheckError },
+
+    /// The value of the column or field could not be deserialized.
+    #[error("Failed to deserialize {name}: {err}")]
+    DeserializationFailed {
+        name: String,
+        err: DeserializationError,
+    },
+}
+
 // This is a hack to enable setting the proper Rust type name in error messages,
 // even though the error originates from some helper type used underneath.
 // ASSUMPTION: This should be used:
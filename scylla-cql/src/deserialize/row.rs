@@ -1,11 +1,15 @@
 //! Provides types for dealing with row deserialization.
 
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
+use std::hash::BuildHasher;
 
 use thiserror::Error;
 
-use super::value::DeserializeValue;
-use super::{make_error_replace_rust_name, DeserializationError, FrameSlice, TypeCheckError};
+use super::value::{deserialize_by_name, DeserializeValue};
+use super::{
+    make_error_replace_rust_name, DeserializationError, FrameSlice, GetByNameError, TypeCheckError,
+};
 use crate::frame::response::result::{ColumnSpec, ColumnType};
 use crate::value::{CqlValue, Row};
 
@@ -104,6 +108,37 @@ where
     fn deserialize(row: ColumnIterator<'frame, 'metadata>) -> Result<Self, DeserializationError>;
 }
 
+/// A row type which knows the selectors that fetch its columns.
+///
+/// The trait is implemented by the `DeserializeRow` derive macro. Each field
+/// contributes the quoted name of its column, or a `writetime(col)` /
+/// `ttl(col)` selector if annotated with `writetime_of` / `ttl_of`. Flattened fields
+/// contribute the selectors of their own fields, and skipped fields are omitted.
+///
+/// ```rust
+/// # use scylla_cql::deserialize::row::RowSelectors;
+/// # use scylla_cql::DeserializeRow;
+/// #[derive(DeserializeRow)]
+/// # #[scylla(crate = scylla_cql)]
+/// struct Versioned {
+///     value: String,
+///     #[scylla(writetime_of = "value")]
+///     value_writetime: Option<i64>,
+/// }
+///
+/// assert_eq!(Versioned::selector_list(), r#""value", writetime("value")"#);
+/// ```
+pub trait RowSelectors {
+    /// Returns the selectors, in the order of the struct fields.
+    fn selectors() -> Vec<String>;
+
+    /// Returns the selectors joined with commas, ready to be put
+    /// after `SELECT` in a statement.
+    fn selector_list() -> String {
+        Self::selectors().join(", ")
+    }
+}
+
 // raw deserialization as ColumnIterator
 
 // What is the purpose of implementing DeserializeRow for ColumnIterator?
@@ -178,6 +213,178 @@ impl<'frame, 'metadata> DeserializeRow<'frame, 'metadata> for Row {
     }
 }
 
+// dynamic deserialization by column name
+
+/// A row whose columns are accessed by name.
+///
+/// Deserializing a row into `NamedRow` only finds the boundaries of the column values
+/// in the frame. The values are deserialized lazily, by [`NamedRow::get`],
+/// into any type implementing [`DeserializeValue`], which makes `NamedRow`
+/// useful for dynamic tools which don't know the columns of the rows in advance.
+///
+/// ```rust
+/// # use scylla_cql::deserialize::row::NamedRow;
+/// # use scylla_cql::deserialize::GetByNameError;
+/// fn print_user(row: &NamedRow) -> Result<(), GetByNameError> {
+///     let id: i32 = row.get("id")?;
+///     let name: Option<&str> = row.get("name")?;
+///     println!("{id}: {name:?}");
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct NamedRow<'frame, 'metadata> {
+    columns: Vec<(&'metadata ColumnSpec<'metadata>, Option<FrameSlice<'frame>>)>,
+}
+
+impl<'frame, 'metadata> NamedRow<'frame, 'metadata> {
+    /// Deserializes the value of the column with the given name.
+    ///
+    /// Null values can be deserialized only into `Option`. If several columns
+    /// have the same name, the first one is used.
+    pub fn get<T>(&self, name: &str) -> Result<T, GetByNameError>
+    where
+        T: DeserializeValue<'frame, 'metadata>,
+    {
+        let (spec, slice) = self
+            .columns
+            .iter()
+            .find(|(spec, _)| spec.name() == name)
+            .ok_or_else(|| GetByNameError::NotFound {
+                name: name.to_owned(),
+            })?;
+        deserialize_by_name(name, spec.typ(), *slice)
+    }
+
+    /// Returns whether the row contains a column with the given name.
+    #[inline]
+    pub fn contains(&self, name: &str) -> bool {
+        self.columns.iter().any(|(spec, _)| spec.name() == name)
+    }
+
+    /// Returns the specifications of the columns, in the order of the row.
+    #[inline]
+    pub fn specs(&self) -> impl Iterator<Item = &'metadata ColumnSpec<'metadata>> + '_ {
+        self.columns.iter().map(|(spec, _)| *spec)
+    }
+
+    /// Returns the raw, serialized values of the columns, in the order of the row.
+    #[inline]
+    pub fn raw_columns(
+        &self,
+    ) -> impl Iterator<Item = (&'metadata ColumnSpec<'metadata>, Option<FrameSlice<'frame>>)> + '_
+    {
+        self.columns.iter().copied()
+    }
+
+    /// Returns the number of columns in the row.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Returns whether the row has no columns.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+}
+
+impl<'frame, 'metadata> DeserializeRow<'frame, 'metadata> for NamedRow<'frame, 'metadata> {
+    #[inline]
+    fn type_check(_specs: &[ColumnSpec]) -> Result<(), TypeCheckError> {
+        // The columns are type checked when they are accessed.
+        Ok(())
+    }
+
+    fn deserialize(row: ColumnIterator<'frame, 'metadata>) -> Result<Self, DeserializationError> {
+        let columns = row
+            .map(|column| column.map(|column| (column.spec, column.slice)))
+            .collect::<Result<_, _>>()
+            .map_err(deser_error_replace_rust_name::<Self>)?;
+        Ok(Self { columns })
+    }
+}
+
+// maps from column names to values
+
+fn type_check_columns<'frame, 'metadata, M, T>(specs: &[ColumnSpec]) -> Result<(), TypeCheckError>
+where
+    T: DeserializeValue<'frame, 'metadata>,
+{
+    for (column_index, spec) in specs.iter().enumerate() {
+        T::type_check(spec.typ()).map_err(|err| {
+            mk_typck_err::<M>(
+                specs.iter().map(|spec| spec.typ().clone().into_owned()),
+                BuiltinTypeCheckErrorKind::ColumnTypeCheckFailed {
+                    column_index,
+                    column_name: spec.name().to_owned(),
+                    err,
+                },
+            )
+        })?;
+    }
+    Ok(())
+}
+
+fn deserialize_column<'frame, 'metadata, M, T>(
+    column: Result<RawColumn<'frame, 'metadata>, DeserializationError>,
+) -> Result<(String, T), DeserializationError>
+where
+    T: DeserializeValue<'frame, 'metadata>,
+{
+    let column = column.map_err(deser_error_replace_rust_name::<M>)?;
+    let value = T::deserialize(column.spec.typ(), column.slice).map_err(|err| {
+        mk_deser_err::<M>(
+            BuiltinDeserializationErrorKind::ColumnDeserializationFailed {
+                column_index: column.index,
+                column_name: column.spec.name().to_owned(),
+                err,
+            },
+        )
+    })?;
+    Ok((column.spec.name().to_owned(), value))
+}
+
+/// Deserializes a row into a map from column names to values, e.g.
+/// `HashMap<String, Option<CqlValue>>` for rows with any columns.
+///
+/// Values of all columns are deserialized into the same type, so columns which may be null
+/// require `Option`. If several columns have the same name, the last one is kept.
+impl<'frame, 'metadata, T, S> DeserializeRow<'frame, 'metadata> for HashMap<String, T, S>
+where
+    T: DeserializeValue<'frame, 'metadata>,
+    S: BuildHasher + Default,
+{
+    fn type_check(specs: &[ColumnSpec]) -> Result<(), TypeCheckError> {
+        type_check_columns::<Self, T>(specs)
+    }
+
+    fn deserialize(row: ColumnIterator<'frame, 'metadata>) -> Result<Self, DeserializationError> {
+        let mut map = HashMap::with_capacity_and_hasher(row.size_hint().0, S::default());
+        for column in row {
+            let (name, value) = deserialize_column::<Self, T>(column)?;
+            map.insert(name, value);
+        }
+        Ok(map)
+    }
+}
+
+/// Deserializes a row into a map from column names to values, ordered by the names.
+/// Works in the same way as the impl for `HashMap`.
+impl<'frame, 'metadata, T> DeserializeRow<'frame, 'metadata> for BTreeMap<String, T>
+where
+    T: DeserializeValue<'frame, 'metadata>,
+{
+    fn type_check(specs: &[ColumnSpec]) -> Result<(), TypeCheckError> {
+        type_check_columns::<Self, T>(specs)
+    }
+
+    fn deserialize(row: ColumnIterator<'frame, 'metadata>) -> Result<Self, DeserializationError> {
+        row.map(deserialize_column::<Self, T>).collect()
+    }
+}
+
 // tuples
 //
 /// This is the new encouraged way for deserializing a row.
@@ -541,3 +748,74 @@ fn _test_struct_deserialization_skip_rename_collision_with_field() {}
 /// }
 /// ```
 fn _test_struct_deserialization_rename_collision_with_another_rename() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeRow)]
+/// #[scylla(crate = scylla_cql)]
+/// struct Inner {
+///     b: i32,
+/// }
+///
+/// #[derive(scylla_macros::DeserializeRow)]
+/// #[scylla(crate = scylla_cql)]
+/// struct TestRow {
+///     a: i32,
+///     #[scylla(flatten, rename = "c")]
+///     inner: Inner,
+/// }
+/// ```
+fn _test_struct_deserialization_flatten_conflicts_with_rename() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeRow)]
+/// #[scylla(crate = scylla_cql, flavor = "enforce_order")]
+/// struct Inner {
+///     b: i32,
+/// }
+///
+/// #[derive(scylla_macros::DeserializeRow)]
+/// #[scylla(crate = scylla_cql)]
+/// struct TestRow {
+///     a: i32,
+///     #[scylla(flatten)]
+///     inner: Inner,
+/// }
+/// ```
+fn _test_struct_deserialization_flatten_requires_same_flavor() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeRow)]
+/// #[scylla(crate = scylla_cql)]
+/// struct TestRow {
+///     #[scylla(with = "some::module")]
+///     a: i32,
+/// }
+/// ```
+fn _test_struct_deserialization_with_requires_existing_module() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeRow)]
+/// #[scylla(crate = scylla_cql)]
+/// struct TestRow {
+///     a: i32,
+///     #[scylla(rename = "a_wt", writetime_of = "a")]
+///     a_writetime: i64,
+/// }
+/// ```
+fn _test_struct_deserialization_writetime_of_conflicts_with_rename() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeRow)]
+/// #[scylla(crate = scylla_cql, flavor = "enforce_order", skip_name_checks)]
+/// struct TestRow {
+///     a: i32,
+///     #[scylla(ttl_of = "a")]
+///     a_ttl: Option<i32>,
+/// }
+/// ```
+fn _test_struct_deserialization_ttl_of_conflicts_with_skip_name_checks() {}
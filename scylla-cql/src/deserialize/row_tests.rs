@@ -268,6 +268,482 @@ fn test_struct_deserialization_cross_rename_fields() {
     }
 }
 
+#[test]
+fn test_struct_deserialization_flatten_loose_ordering() {
+    #[derive(DeserializeRow, PartialEq, Eq, Debug)]
+    #[scylla(crate = crate)]
+    struct Inner<'a> {
+        b: &'a str,
+        #[scylla(flatten)]
+        innermost: Innermost,
+    }
+
+    #[derive(DeserializeRow, PartialEq, Eq, Debug)]
+    #[scylla(crate = crate)]
+    struct Innermost {
+        #[scylla(rename = "z")]
+        c: i32,
+    }
+
+    #[derive(DeserializeRow, PartialEq, Eq, Debug)]
+    #[scylla(crate = crate)]
+    struct MyRow<'a> {
+        a: i32,
+        #[scylla(flatten)]
+        inner: Inner<'a>,
+        d: i32,
+    }
+
+    let expected = MyRow {
+        a: 1,
+        inner: Inner {
+            b: "abc",
+            innermost: Innermost { c: 3 },
+        },
+        d: 4,
+    };
+
+    // Columns of flattened fields can be interleaved with other columns
+    let specs = &[
+        spec("z", ColumnType::Native(NativeType::Int)),
+        spec("a", ColumnType::Native(NativeType::Int)),
+        spec("d", ColumnType::Native(NativeType::Int)),
+        spec("b", ColumnType::Native(NativeType::Text)),
+    ];
+    let byts = serialize_cells([val_int(3), val_int(1), val_int(4), val_str("abc")]);
+    let row = deserialize::<MyRow<'_>>(specs, &byts).unwrap();
+    assert_eq!(row, expected);
+
+    // Unknown column
+    let specs = &[
+        spec("a", ColumnType::Native(NativeType::Int)),
+        spec("b", ColumnType::Native(NativeType::Text)),
+        spec("z", ColumnType::Native(NativeType::Int)),
+        spec("d", ColumnType::Native(NativeType::Int)),
+        spec("x", ColumnType::Native(NativeType::Int)),
+    ];
+    let err = MyRow::type_check(specs).unwrap_err();
+    let err = get_typck_err_inner(err.0.as_ref());
+    assert_eq!(err.rust_name, std::any::type_name::<MyRow<'_>>());
+    assert_matches!(
+        err.kind,
+        BuiltinTypeCheckErrorKind::ColumnWithUnknownName { column_index: 4, ref column_name }
+            if column_name == "x"
+    );
+
+    // Missing column of a flattened field is reported by the flattened struct
+    let specs = &[
+        spec("a", ColumnType::Native(NativeType::Int)),
+        spec("b", ColumnType::Native(NativeType::Text)),
+        spec("d", ColumnType::Native(NativeType::Int)),
+    ];
+    let err = MyRow::type_check(specs).unwrap_err();
+    let err = get_typck_err_inner(err.0.as_ref());
+    assert_eq!(err.rust_name, std::any::type_name::<Innermost>());
+    assert_matches!(
+        err.kind,
+        BuiltinTypeCheckErrorKind::ValuesMissingForColumns { ref column_names }
+            if column_names == &["z"]
+    );
+
+    // Wrong column type in a flattened field
+    let specs = &[
+        spec("a", ColumnType::Native(NativeType::Int)),
+        spec("b", ColumnType::Native(NativeType::Int)),
+        spec("z", ColumnType::Native(NativeType::Int)),
+        spec("d", ColumnType::Native(NativeType::Int)),
+    ];
+    let err = MyRow::type_check(specs).unwrap_err();
+    let err = get_typck_err_inner(err.0.as_ref());
+    assert_eq!(err.rust_name, std::any::type_name::<Inner<'_>>());
+    assert_matches!(
+        err.kind,
+        BuiltinTypeCheckErrorKind::ColumnTypeCheckFailed {
+            column_index: 0,
+            ..
+        }
+    );
+}
+
+#[test]
+fn test_struct_deserialization_flatten_strict_ordering() {
+    #[derive(DeserializeRow, PartialEq, Eq, Debug)]
+    #[scylla(crate = crate, flavor = "enforce_order")]
+    struct Inner<'a> {
+        b: &'a str,
+        #[scylla(skip)]
+        x: i32,
+        c: i32,
+    }
+
+    #[derive(DeserializeRow, PartialEq, Eq, Debug)]
+    #[scylla(crate = crate, flavor = "enforce_order")]
+    struct MyRow<'a> {
+        a: i32,
+        #[scylla(flatten)]
+        inner: Inner<'a>,
+        d: i32,
+    }
+
+    // Correct order of columns
+    let specs = &[
+        spec("a", ColumnType::Native(NativeType::Int)),
+        spec("b", ColumnType::Native(NativeType::Text)),
+        spec("c", ColumnType::Native(NativeType::Int)),
+        spec("d", ColumnType::Native(NativeType::Int)),
+    ];
+    let byts = serialize_cells([val_int(1), val_str("abc"), val_int(3), val_int(4)]);
+    let row = deserialize::<MyRow<'_>>(specs, &byts).unwrap();
+    assert_eq!(
+        row,
+        MyRow {
+            a: 1,
+            inner: Inner {
+                b: "abc",
+                x: 0,
+                c: 3
+            },
+            d: 4,
+        }
+    );
+
+    // Wrong order of columns within the flattened struct
+    let specs = &[
+        spec("a", ColumnType::Native(NativeType::Int)),
+        spec("c", ColumnType::Native(NativeType::Int)),
+        spec("b", ColumnType::Native(NativeType::Text)),
+        spec("d", ColumnType::Native(NativeType::Int)),
+    ];
+    let err = MyRow::type_check(specs).unwrap_err();
+    let err = get_typck_err_inner(err.0.as_ref());
+    assert_eq!(err.rust_name, std::any::type_name::<Inner<'_>>());
+    assert_matches!(
+        err.kind,
+        BuiltinTypeCheckErrorKind::ColumnNameMismatch {
+            field_index: 0,
+            column_index: 0,
+            rust_column_name: "b",
+            ref db_column_name,
+        } if db_column_name == "c"
+    );
+
+    // Wrong column after the flattened struct
+    let specs = &[
+        spec("a", ColumnType::Native(NativeType::Int)),
+        spec("b", ColumnType::Native(NativeType::Text)),
+        spec("c", ColumnType::Native(NativeType::Int)),
+        spec("e", ColumnType::Native(NativeType::Int)),
+    ];
+    let err = MyRow::type_check(specs).unwrap_err();
+    let err = get_typck_err_inner(err.0.as_ref());
+    assert_eq!(err.rust_name, std::any::type_name::<MyRow<'_>>());
+    assert_matches!(
+        err.kind,
+        BuiltinTypeCheckErrorKind::ColumnNameMismatch {
+            field_index: 2,
+            column_index: 3,
+            rust_column_name: "d",
+            ..
+        }
+    );
+
+    // Wrong column count
+    let specs = &[
+        spec("a", ColumnType::Native(NativeType::Int)),
+        spec("b", ColumnType::Native(NativeType::Text)),
+        spec("c", ColumnType::Native(NativeType::Int)),
+    ];
+    let err = MyRow::type_check(specs).unwrap_err();
+    let err = get_typck_err_inner(err.0.as_ref());
+    assert_matches!(
+        err.kind,
+        BuiltinTypeCheckErrorKind::WrongColumnCount {
+            rust_cols: 4,
+            cql_cols: 3
+        }
+    );
+}
+
+#[test]
+fn test_struct_deserialization_custom_field_codec() {
+    use crate::deserialize::value::tests::u32_as_text;
+
+    #[derive(DeserializeRow, PartialEq, Eq, Debug)]
+    #[scylla(crate = crate)]
+    struct MyRow {
+        #[scylla(with = u32_as_text)]
+        a: u32,
+        b: i32,
+    }
+
+    #[derive(DeserializeRow, PartialEq, Eq, Debug)]
+    #[scylla(crate = crate, flavor = "enforce_order")]
+    struct MyOrderedRow {
+        #[scylla(with = "u32_as_text")]
+        a: u32,
+        b: i32,
+    }
+
+    let specs = &[
+        spec("a", ColumnType::Native(NativeType::Text)),
+        spec("b", ColumnType::Native(NativeType::Int)),
+    ];
+    let byts = serialize_cells([val_str("42"), val_int(7)]);
+    assert_eq!(
+        deserialize::<MyRow>(specs, &byts).unwrap(),
+        MyRow { a: 42, b: 7 }
+    );
+    assert_eq!(
+        deserialize::<MyOrderedRow>(specs, &byts).unwrap(),
+        MyOrderedRow { a: 42, b: 7 }
+    );
+
+    // Errors from the custom deserializer are propagated
+    let byts = serialize_cells([val_str("abc"), val_int(7)]);
+    let err = deserialize::<MyRow>(specs, &byts).unwrap_err();
+    let err = get_deser_err(&err);
+    assert_matches!(
+        err.kind,
+        BuiltinDeserializationErrorKind::ColumnDeserializationFailed { ref column_name, .. }
+            if column_name == "a"
+    );
+
+    // Type check is done by the custom module
+    let specs = &[
+        spec("a", ColumnType::Native(NativeType::Int)),
+        spec("b", ColumnType::Native(NativeType::Int)),
+    ];
+    MyRow::type_check(specs).unwrap_err();
+    MyOrderedRow::type_check(specs).unwrap_err();
+}
+
+#[test]
+fn test_struct_deserialization_writetime_and_ttl() {
+    #[derive(DeserializeRow, PartialEq, Eq, Debug)]
+    #[scylla(crate = crate)]
+    struct MyRow<'a> {
+        a: i32,
+        b: Option<&'a str>,
+        #[scylla(writetime_of = "b")]
+        b_writetime: Option<i64>,
+        #[scylla(ttl_of = "b")]
+        b_ttl: Option<i32>,
+    }
+
+    #[derive(DeserializeRow, PartialEq, Eq, Debug)]
+    #[scylla(crate = crate, flavor = "enforce_order")]
+    struct MyOrderedRow {
+        #[scylla(writetime_of = "b")]
+        b_writetime: i64,
+        #[scylla(ttl_of = "b")]
+        b_ttl: Option<i32>,
+    }
+
+    let specs = &[
+        spec("writetime(b)", ColumnType::Native(NativeType::BigInt)),
+        spec("b", ColumnType::Native(NativeType::Text)),
+        spec("ttl(b)", ColumnType::Native(NativeType::Int)),
+        spec("a", ColumnType::Native(NativeType::Int)),
+    ];
+    let byts = serialize_cells([
+        Some(1234_i64.to_be_bytes().to_vec()),
+        val_str("abc"),
+        None,
+        val_int(1),
+    ]);
+    let row = deserialize::<MyRow<'_>>(specs, &byts).unwrap();
+    assert_eq!(
+        row,
+        MyRow {
+            a: 1,
+            b: Some("abc"),
+            b_writetime: Some(1234),
+            b_ttl: None,
+        }
+    );
+
+    let specs = &[
+        spec("writetime(b)", ColumnType::Native(NativeType::BigInt)),
+        spec("ttl(b)", ColumnType::Native(NativeType::Int)),
+    ];
+    let byts = serialize_cells([Some(1234_i64.to_be_bytes().to_vec()), val_int(60)]);
+    let row = deserialize::<MyOrderedRow>(specs, &byts).unwrap();
+    assert_eq!(
+        row,
+        MyOrderedRow {
+            b_writetime: 1234,
+            b_ttl: Some(60),
+        }
+    );
+
+    // The column itself is not mistaken for its writetime
+    let specs = &[
+        spec("b", ColumnType::Native(NativeType::BigInt)),
+        spec("ttl(b)", ColumnType::Native(NativeType::Int)),
+    ];
+    let err = MyOrderedRow::type_check(specs).unwrap_err();
+    let err = get_typck_err_inner(err.0.as_ref());
+    assert_matches!(
+        err.kind,
+        BuiltinTypeCheckErrorKind::ColumnNameMismatch {
+            rust_column_name: "writetime(b)",
+            ..
+        }
+    );
+}
+
+#[test]
+fn test_struct_row_selectors() {
+    use super::RowSelectors;
+
+    #[derive(DeserializeRow)]
+    #[scylla(crate = crate)]
+    #[expect(dead_code)]
+    struct Inner {
+        #[scylla(rename = "userId")]
+        user_id: i32,
+        #[scylla(writetime_of = "userId")]
+        user_id_writetime: i64,
+    }
+
+    #[derive(DeserializeRow)]
+    #[scylla(crate = crate)]
+    #[expect(dead_code)]
+    struct MyRow {
+        a: i32,
+        #[scylla(skip)]
+        b: i32,
+        #[scylla(flatten)]
+        inner: Inner,
+        #[scylla(ttl_of = "a")]
+        a_ttl: Option<i32>,
+    }
+
+    assert_eq!(
+        MyRow::selectors(),
+        ["\"a\"", "\"userId\"", "writetime(\"userId\")", "ttl(\"a\")"]
+    );
+    assert_eq!(
+        MyRow::selector_list(),
+        "\"a\", \"userId\", writetime(\"userId\"), ttl(\"a\")"
+    );
+
+    // Reserved keywords are usable as column names.
+    #[derive(DeserializeRow)]
+    #[scylla(crate = crate)]
+    #[expect(dead_code)]
+    struct Keywords {
+        order: i32,
+        #[scylla(writetime_of = "order")]
+        order_writetime: i64,
+        #[scylla(rename = "token")]
+        token_value: i32,
+    }
+
+    assert_eq!(
+        Keywords::selector_list(),
+        "\"order\", writetime(\"order\"), \"token\""
+    );
+}
+
+#[test]
+fn test_named_row_deserialization() {
+    use super::NamedRow;
+    use crate::deserialize::GetByNameError;
+
+    let specs = [
+        spec("id", ColumnType::Native(NativeType::Int)),
+        spec("name", ColumnType::Native(NativeType::Text)),
+        spec("score", ColumnType::Native(NativeType::Int)),
+    ];
+    let byts = serialize_cells([val_int(1), val_str("alice"), None]);
+    let row = deserialize::<NamedRow>(&specs, &byts).unwrap();
+
+    assert_eq!(row.len(), 3);
+    assert!(row.contains("name"));
+    assert!(!row.contains("age"));
+    assert_eq!(
+        row.specs().map(|spec| spec.name()).collect::<Vec<_>>(),
+        ["id", "name", "score"]
+    );
+
+    assert_eq!(row.get::<i32>("id").unwrap(), 1);
+    assert_eq!(row.get::<&str>("name").unwrap(), "alice");
+    assert_eq!(row.get::<String>("name").unwrap(), "alice");
+    assert_eq!(
+        row.get::<CqlValue>("name").unwrap(),
+        CqlValue::Text("alice".to_owned())
+    );
+    assert_eq!(row.get::<Option<i32>>("score").unwrap(), None);
+
+    assert_matches!(
+        row.get::<i32>("age"),
+        Err(GetByNameError::NotFound { name }) if name == "age"
+    );
+    assert_matches!(
+        row.get::<i64>("id"),
+        Err(GetByNameError::TypeCheckFailed { name, .. }) if name == "id"
+    );
+    assert_matches!(
+        row.get::<i32>("score"),
+        Err(GetByNameError::DeserializationFailed { name, .. }) if name == "score"
+    );
+}
+
+#[test]
+fn test_map_row_deserialization() {
+    use std::collections::{BTreeMap, HashMap};
+
+    let specs = [
+        spec("b", ColumnType::Native(NativeType::Int)),
+        spec("a", ColumnType::Native(NativeType::Text)),
+        spec("c", ColumnType::Native(NativeType::Int)),
+    ];
+    let byts = serialize_cells([val_int(1), val_str("alice"), None]);
+
+    let map = deserialize::<HashMap<String, Option<CqlValue>>>(&specs, &byts).unwrap();
+    assert_eq!(
+        map,
+        HashMap::from([
+            ("a".to_owned(), Some(CqlValue::Text("alice".to_owned()))),
+            ("b".to_owned(), Some(CqlValue::Int(1))),
+            ("c".to_owned(), None),
+        ])
+    );
+
+    let map = deserialize::<BTreeMap<String, Option<CqlValue>>>(&specs, &byts).unwrap();
+    assert_eq!(
+        map.keys().map(String::as_str).collect::<Vec<_>>(),
+        ["a", "b", "c"]
+    );
+
+    // All columns must be compatible with the value type.
+    let err = deserialize::<HashMap<String, Option<i32>>>(&specs, &byts).unwrap_err();
+    let err = get_typck_err(&err);
+    assert_matches!(
+        &err.kind,
+        BuiltinTypeCheckErrorKind::ColumnTypeCheckFailed {
+            column_index: 1,
+            column_name,
+            ..
+        } if column_name == "a"
+    );
+
+    // Null values require `Option`.
+    let specs = [spec("b", ColumnType::Native(NativeType::Int))];
+    let byts = serialize_cells([None::<Vec<u8>>]);
+    let err = deserialize::<BTreeMap<String, i32>>(&specs, &byts).unwrap_err();
+    let err = get_deser_err(&err);
+    assert_matches!(
+        &err.kind,
+        BuiltinDeserializationErrorKind::ColumnDeserializationFailed {
+            column_index: 0,
+            column_name,
+            ..
+        } if column_name == "b"
+    );
+}
+
 fn val_int(i: i32) -> Option<Vec<u8>> {
     Some(i.to_be_bytes().to_vec())
 }
@@ -0,0 +1,516 @@
+//! Bridge between `serde` and the driver's deserialization framework.
+//!
+//! Wrapping a type in [Serde] makes it deserializable from a CQL value
+//! (with [DeserializeValue]) or from a whole row (with [DeserializeRow]),
+//! as long as it implements [serde::Deserialize]. A row is presented to `serde`
+//! as a map from column names to column values, so a struct deriving
+//! `serde::Deserialize` is matched with the columns by name, and all `serde`
+//! attributes (`rename`, `default`, `deny_unknown_fields`, ...) are respected.
+//!
+//! CQL values are presented to `serde` as follows:
+//!
+//! | CQL type                            | `serde` data model                              |
+//! |-------------------------------------|-------------------------------------------------|
+//! | `ascii`, `text`                     | string                                          |
+//! | `boolean`                           | bool                                            |
+//! | `blob`                              | byte array                                      |
+//! | `tinyint`, `smallint`, `int`, `bigint`, `counter` | integer                           |
+//! | `varint`                            | integer (up to 128 bits)                        |
+//! | `decimal`                           | string, e.g. `"-12.345"` (unscaled value up to 128 bits) |
+//! | `float`, `double`                   | floating point number                           |
+//! | `date`                              | i64 - days since the Unix epoch                 |
+//! | `timestamp`                         | i64 - milliseconds since the Unix epoch         |
+//! | `time`                              | i64 - nanoseconds since midnight                |
+//! | `duration`                          | map with `months`, `days` and `nanoseconds` keys |
+//! | `uuid`, `timeuuid`, `inet`          | string                                          |
+//! | `list`, `set`, `vector`, `tuple`    | sequence                                        |
+//! | `map`                               | map                                             |
+//! | user defined type                   | map from field names to field values            |
+//! | null, empty value                   | unit (or `None`, when deserializing an `Option`) |
+//!
+//! Unit-only enums are deserialized from strings holding the variant name.
+//!
+//! Type checking is performed by `serde` during deserialization, so
+//! the [type_check](DeserializeValue::type_check) methods of [Serde] always succeed.
+
+use std::fmt::Display;
+
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{DeserializeOwned, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+use thiserror::Error;
+
+use super::row::{ColumnIterator, DeserializeRow};
+use super::value::DeserializeValue;
+use super::{DeserializationError, FrameSlice, TypeCheckError};
+use crate::frame::response::result::{ColumnSpec, ColumnType};
+use crate::value::{CqlDecimal, CqlValue};
+
+/// Wrapper which bridges `serde` with the driver's (de)serialization framework.
+///
+/// `Serde<T>` implements [DeserializeValue] and [DeserializeRow] for any
+/// `T: serde::de::DeserializeOwned`, and
+/// [SerializeValue](crate::serialize::value::SerializeValue) and
+/// [SerializeRow](crate::serialize::row::SerializeRow) for any `T: serde::Serialize`.
+///
+/// See the [module-level documentation](self) for the mapping of CQL values
+/// to the `serde` data model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Serde<T>(pub T);
+
+impl<T> Serde<T> {
+    /// Returns the wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// An error returned by `serde` when deserializing a value through [Serde].
+#[derive(Error, Debug, Clone)]
+#[error("Serde deserialization failed: {0}")]
+pub struct SerdeDeserializationError(String);
+
+impl serde::de::Error for SerdeDeserializationError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl<'frame, 'metadata, T> DeserializeValue<'frame, 'metadata> for Serde<T>
+where
+    T: DeserializeOwned,
+{
+    fn type_check(_typ: &ColumnType) -> Result<(), TypeCheckError> {
+        Ok(())
+    }
+
+    fn deserialize(
+        typ: &'metadata ColumnType<'metadata>,
+        v: Option<FrameSlice<'frame>>,
+    ) -> Result<Self, DeserializationError> {
+        let value = <Option<CqlValue>>::deserialize(typ, v)?;
+        T::deserialize(ValueDeserializer(value))
+            .map(Serde)
+            .map_err(DeserializationError::new)
+    }
+}
+
+impl<'frame, 'metadata, T> DeserializeRow<'frame, 'metadata> for Serde<T>
+where
+    T: DeserializeOwned,
+{
+    fn type_check(_specs: &[ColumnSpec]) -> Result<(), TypeCheckError> {
+        Ok(())
+    }
+
+    fn deserialize(row: ColumnIterator<'frame, 'metadata>) -> Result<Self, DeserializationError> {
+        let columns = row
+            .map(|column| {
+                let column = column?;
+                let value = <Option<CqlValue>>::deserialize(column.spec.typ(), column.slice)?;
+                Ok((column.spec.name().to_owned(), ValueDeserializer(value)))
+            })
+            .collect::<Result<Vec<_>, DeserializationError>>()?;
+
+        T::deserialize(MapDeserializer::new(columns.into_iter()))
+            .map(Serde)
+            .map_err(DeserializationError::new)
+    }
+}
+
+/// Presents a (possibly null) [CqlValue] to `serde`.
+struct ValueDeserializer(Option<CqlValue>);
+
+impl<'de> IntoDeserializer<'de, SerdeDeserializationError> for ValueDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+fn visit_seq<'de, V: Visitor<'de>>(
+    visitor: V,
+    elements: impl Iterator<Item = ValueDeserializer>,
+) -> Result<V::Value, SerdeDeserializationError> {
+    let mut seq = SeqDeserializer::new(elements);
+    let value = visitor.visit_seq(&mut seq)?;
+    seq.end()?;
+    Ok(value)
+}
+
+fn visit_map<'de, V, K>(
+    visitor: V,
+    entries: impl Iterator<Item = (K, ValueDeserializer)>,
+) -> Result<V::Value, SerdeDeserializationError>
+where
+    V: Visitor<'de>,
+    K: IntoDeserializer<'de, SerdeDeserializationError>,
+{
+    let mut map = MapDeserializer::new(entries);
+    let value = visitor.visit_map(&mut map)?;
+    map.end()?;
+    Ok(value)
+}
+
+impl<'de> serde::Deserializer<'de> for ValueDeserializer {
+    type Error = SerdeDeserializationError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let Some(value) = self.0 else {
+            return visitor.visit_unit();
+        };
+
+        match value {
+            CqlValue::Ascii(s) | CqlValue::Text(s) => visitor.visit_string(s),
+            CqlValue::Boolean(b) => visitor.visit_bool(b),
+            CqlValue::Blob(b) => visitor.visit_byte_buf(b),
+            CqlValue::Counter(c) => visitor.visit_i64(c.0),
+            CqlValue::Decimal(d) => visitor.visit_string(decimal_to_string(&d)?),
+            CqlValue::Date(d) => visitor.visit_i64(d.0 as i64 - (1 << 31)),
+            CqlValue::Double(d) => visitor.visit_f64(d),
+            CqlValue::Duration(d) => visit_map(
+                visitor,
+                [
+                    ("months", CqlValue::Int(d.months)),
+                    ("days", CqlValue::Int(d.days)),
+                    ("nanoseconds", CqlValue::BigInt(d.nanoseconds)),
+                ]
+                .into_iter()
+                .map(|(name, value)| (name, ValueDeserializer(Some(value)))),
+            ),
+            CqlValue::Empty => visitor.visit_unit(),
+            CqlValue::Float(f) => visitor.visit_f32(f),
+            CqlValue::Int(i) => visitor.visit_i32(i),
+            CqlValue::BigInt(i) => visitor.visit_i64(i),
+            CqlValue::Timestamp(t) => visitor.visit_i64(t.0),
+            CqlValue::Inet(ip) => visitor.visit_string(ip.to_string()),
+            CqlValue::List(v) | CqlValue::Set(v) | CqlValue::Vector(v) => visit_seq(
+                visitor,
+                v.into_iter().map(|elem| ValueDeserializer(Some(elem))),
+            ),
+            CqlValue::Map(m) => visit_map(
+                visitor,
+                m.into_iter()
+                    .map(|(k, v)| (ValueDeserializer(Some(k)), ValueDeserializer(Some(v)))),
+            ),
+            CqlValue::UserDefinedType { fields, .. } => visit_map(
+                visitor,
+                fields
+                    .into_iter()
+                    .map(|(name, value)| (name, ValueDeserializer(value))),
+            ),
+            CqlValue::SmallInt(i) => visitor.visit_i16(i),
+            CqlValue::TinyInt(i) => visitor.visit_i8(i),
+            CqlValue::Time(t) => visitor.visit_i64(t.0),
+            CqlValue::Timeuuid(t) => visitor.visit_string(t.to_string()),
+            CqlValue::Tuple(t) => visit_seq(visitor, t.into_iter().map(ValueDeserializer)),
+            CqlValue::Uuid(u) => visitor.visit_string(u.to_string()),
+            CqlValue::Varint(v) => match signed_be_bytes_to_i128(v.as_signed_bytes_be_slice()) {
+                Some(i) => match i64::try_from(i) {
+                    Ok(i) => visitor.visit_i64(i),
+                    Err(_) => visitor.visit_i128(i),
+                },
+                None => Err(serde::de::Error::custom("varint does not fit in 128 bits")),
+            },
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            None => visitor.visit_none(),
+            Some(_) => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Some(CqlValue::Ascii(s) | CqlValue::Text(s)) => {
+                visitor
+                    .visit_enum(IntoDeserializer::<SerdeDeserializationError>::into_deserializer(s))
+            }
+            other => ValueDeserializer(other).deserialize_any(visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+/// Converts a signed big-endian two's complement number to `i128`,
+/// if it fits.
+pub(crate) fn signed_be_bytes_to_i128(mut bytes: &[u8]) -> Option<i128> {
+    // Skip redundant sign-extension bytes of non-normalized representations.
+    while bytes.len() > 16
+        && ((bytes[0] == 0x00 && bytes[1] & 0x80 == 0)
+            || (bytes[0] == 0xff && bytes[1] & 0x80 != 0))
+    {
+        bytes = &bytes[1..];
+    }
+    if bytes.len() > 16 {
+        return None;
+    }
+
+    let fill = match bytes.first() {
+        Some(b) if b & 0x80 != 0 => 0xff,
+        _ => 0x00,
+    };
+    let mut buf = [fill; 16];
+    buf[16 - bytes.len()..].copy_from_slice(bytes);
+    Some(i128::from_be_bytes(buf))
+}
+
+fn decimal_to_string(decimal: &CqlDecimal) -> Result<String, SerdeDeserializationError> {
+    let (bytes, scale) = decimal.as_signed_be_bytes_slice_and_exponent();
+    let unscaled = signed_be_bytes_to_i128(bytes)
+        .ok_or_else(|| SerdeDeserializationError("decimal does not fit in 128 bits".to_owned()))?;
+
+    let sign = if unscaled < 0 { "-" } else { "" };
+    let digits = unscaled.unsigned_abs().to_string();
+    let Ok(scale) = usize::try_from(scale) else {
+        // Negative scale - the number is an integer multiplied by a power of 10.
+        let zeros = "0".repeat(scale.unsigned_abs() as usize);
+        return Ok(if unscaled == 0 {
+            "0".to_owned()
+        } else {
+            format!("{sign}{digits}{zeros}")
+        });
+    };
+    if scale == 0 {
+        return Ok(format!("{sign}{digits}"));
+    }
+
+    let digits = format!("{digits:0>width$}", width = scale + 1);
+    let (integer_part, fractional_part) = digits.split_at(digits.len() - scale);
+    Ok(format!("{sign}{integer_part}.{fractional_part}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use bytes::{BufMut, Bytes, BytesMut};
+    use serde::Deserialize;
+
+    use super::{signed_be_bytes_to_i128, Serde};
+    use crate::deserialize::row::{ColumnIterator, DeserializeRow};
+    use crate::deserialize::value::DeserializeValue;
+    use crate::deserialize::FrameSlice;
+    use crate::frame::response::result::{ColumnSpec, ColumnType, NativeType, TableSpec};
+    use crate::frame::types;
+    use crate::value::CqlDecimal;
+
+    fn serialize_cells(cells: &[Option<&[u8]>]) -> Bytes {
+        let mut buf = BytesMut::new();
+        for cell in cells {
+            types::write_bytes_opt(*cell, &mut buf).unwrap();
+        }
+        buf.freeze()
+    }
+
+    fn deserialize_value<T: serde::de::DeserializeOwned>(
+        typ: &ColumnType,
+        bytes: Option<&[u8]>,
+    ) -> Result<T, crate::deserialize::DeserializationError> {
+        let bytes = bytes.map(Bytes::copy_from_slice);
+        let slice = bytes.as_ref().map(FrameSlice::new);
+        <Serde<T> as DeserializeValue>::deserialize(typ, slice).map(Serde::into_inner)
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Kind {
+        Small,
+        Large,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Row {
+        id: i64,
+        #[serde(rename = "label")]
+        name: String,
+        kind: Kind,
+        tags: Option<Vec<String>>,
+        #[serde(default)]
+        missing: i32,
+    }
+
+    #[test]
+    fn test_row_deserialization_by_name() {
+        let spec = |name: &'static str, typ| {
+            ColumnSpec::borrowed(name, typ, TableSpec::borrowed("ks", "tab"))
+        };
+        let specs = [
+            spec("label", ColumnType::Native(NativeType::Text)),
+            spec("kind", ColumnType::Native(NativeType::Ascii)),
+            spec("id", ColumnType::Native(NativeType::BigInt)),
+            spec(
+                "tags",
+                ColumnType::Collection {
+                    frozen: false,
+                    typ: crate::frame::response::result::CollectionType::List(Box::new(
+                        ColumnType::Native(NativeType::Text),
+                    )),
+                },
+            ),
+            spec("ignored", ColumnType::Native(NativeType::Int)),
+        ];
+        let row_bytes = serialize_cells(&[
+            Some(b"foo"),
+            Some(b"large"),
+            Some(&42_i64.to_be_bytes()),
+            None,
+            Some(&7_i32.to_be_bytes()),
+        ]);
+
+        <Serde<Row> as DeserializeRow>::type_check(&specs).unwrap();
+        let iter = ColumnIterator::new(&specs, FrameSlice::new(&row_bytes));
+        let row = <Serde<Row> as DeserializeRow>::deserialize(iter).unwrap().0;
+        assert_eq!(
+            row,
+            Row {
+                id: 42,
+                name: "foo".to_owned(),
+                kind: Kind::Large,
+                tags: None,
+                missing: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_row_deserialization_missing_column() {
+        let specs = [ColumnSpec::borrowed(
+            "id",
+            ColumnType::Native(NativeType::BigInt),
+            TableSpec::borrowed("ks", "tab"),
+        )];
+        let row_bytes = serialize_cells(&[Some(&42_i64.to_be_bytes())]);
+
+        let iter = ColumnIterator::new(&specs, FrameSlice::new(&row_bytes));
+        let err = <Serde<Row> as DeserializeRow>::deserialize(iter).unwrap_err();
+        let err = err
+            .downcast_ref::<super::SerdeDeserializationError>()
+            .unwrap();
+        assert!(err.to_string().contains("missing field `label`"));
+    }
+
+    #[test]
+    fn test_scalar_values() {
+        let int = ColumnType::Native(NativeType::Int);
+        assert_eq!(
+            deserialize_value::<i64>(&int, Some(&7_i32.to_be_bytes())).unwrap(),
+            7
+        );
+        assert_eq!(deserialize_value::<Option<i32>>(&int, None).unwrap(), None);
+        deserialize_value::<i8>(&int, Some(&1000_i32.to_be_bytes())).unwrap_err();
+
+        let uuid = ColumnType::Native(NativeType::Uuid);
+        let id = uuid::Uuid::from_u128(0x1234);
+        assert_eq!(
+            deserialize_value::<String>(&uuid, Some(id.as_bytes())).unwrap(),
+            id.to_string()
+        );
+
+        let varint = ColumnType::Native(NativeType::Varint);
+        assert_eq!(
+            deserialize_value::<i128>(&varint, Some(&[0xff, 0x00])).unwrap(),
+            -256
+        );
+    }
+
+    #[test]
+    fn test_udt_and_map_values() {
+        use crate::frame::response::result::{CollectionType, UserDefinedType};
+        use std::sync::Arc;
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Address {
+            street: String,
+            number: Option<i32>,
+        }
+
+        let udt = ColumnType::UserDefinedType {
+            frozen: false,
+            definition: Arc::new(UserDefinedType {
+                name: "address".into(),
+                keyspace: "ks".into(),
+                field_types: vec![
+                    ("street".into(), ColumnType::Native(NativeType::Text)),
+                    ("number".into(), ColumnType::Native(NativeType::Int)),
+                ],
+            }),
+        };
+        let udt_bytes = serialize_cells(&[Some(b"Main"), None]);
+        assert_eq!(
+            deserialize_value::<Address>(&udt, Some(&udt_bytes)).unwrap(),
+            Address {
+                street: "Main".to_owned(),
+                number: None,
+            }
+        );
+
+        let map = ColumnType::Collection {
+            frozen: false,
+            typ: CollectionType::Map(
+                Box::new(ColumnType::Native(NativeType::Text)),
+                Box::new(ColumnType::Native(NativeType::Int)),
+            ),
+        };
+        let mut map_bytes = BytesMut::new();
+        map_bytes.put_i32(2);
+        map_bytes.put_slice(&serialize_cells(&[
+            Some(b"a"),
+            Some(&1_i32.to_be_bytes()),
+            Some(b"b"),
+            Some(&2_i32.to_be_bytes()),
+        ]));
+        assert_eq!(
+            deserialize_value::<BTreeMap<String, i32>>(&map, Some(&map_bytes)).unwrap(),
+            BTreeMap::from([("a".to_owned(), 1), ("b".to_owned(), 2)])
+        );
+    }
+
+    #[test]
+    fn test_decimal_to_string() {
+        let cases: &[(&[u8], i32, &str)] = &[
+            (&[0x30, 0x39], 2, "123.45"),
+            (&[0xcf, 0xc7], 2, "-123.45"),
+            (&[0x05], 3, "0.005"),
+            (&[0x05], -2, "500"),
+            (&[0x00], 0, "0"),
+        ];
+        for (bytes, scale, expected) in cases {
+            let decimal = CqlDecimal::from_signed_be_bytes_slice_and_exponent(bytes, *scale);
+            assert_eq!(super::decimal_to_string(&decimal).unwrap(), *expected);
+        }
+    }
+
+    #[test]
+    fn test_signed_be_bytes_to_i128() {
+        assert_eq!(signed_be_bytes_to_i128(&[]), Some(0));
+        assert_eq!(signed_be_bytes_to_i128(&[0x80]), Some(-128));
+        assert_eq!(signed_be_bytes_to_i128(&[0x00, 0x80]), Some(128));
+        let mut long = vec![0xff; 20];
+        long.push(0x80);
+        assert_eq!(signed_be_bytes_to_i128(&long), Some(-128));
+        assert_eq!(signed_be_bytes_to_i128(&[0x01; 17]), None);
+    }
+}
@@ -15,7 +15,9 @@ use std::fmt::Display;
 
 use thiserror::Error;
 
-use super::{make_error_replace_rust_name, DeserializationError, FrameSlice, TypeCheckError};
+use super::{
+    make_error_replace_rust_name, DeserializationError, FrameSlice, GetByNameError, TypeCheckError,
+};
 use crate::frame::frame_errors::LowLevelDeserializationError;
 use crate::frame::response::result::CollectionType;
 use crate::frame::response::result::UserDefinedType;
@@ -863,6 +865,70 @@ where
     }
 }
 
+/// Arrays can be deserialized only from CQL vectors with matching number of dimensions.
+impl<'frame, 'metadata, T, const N: usize> DeserializeValue<'frame, 'metadata> for [T; N]
+where
+    T: DeserializeValue<'frame, 'metadata>,
+{
+    fn type_check(typ: &ColumnType) -> Result<(), TypeCheckError> {
+        match typ {
+            ColumnType::Vector { dimensions, .. } => {
+                if usize::from(*dimensions) != N {
+                    return Err(mk_typck_err::<Self>(
+                        typ,
+                        VectorTypeCheckErrorKind::WrongDimensions {
+                            rust_type_dimensions: N,
+                            cql_type_dimensions: *dimensions,
+                        },
+                    ));
+                }
+                VectorIterator::<'frame, 'metadata, T>::type_check(typ)
+                    .map_err(typck_error_replace_rust_name::<Self>)
+            }
+            _ => Err(mk_typck_err::<Self>(
+                typ,
+                VectorTypeCheckErrorKind::NotVector,
+            )),
+        }
+    }
+
+    fn deserialize(
+        typ: &'metadata ColumnType<'metadata>,
+        v: Option<FrameSlice<'frame>>,
+    ) -> Result<Self, DeserializationError> {
+        let dimensions = match typ {
+            ColumnType::Vector { dimensions, .. } => *dimensions,
+            _ => unreachable!("Should be prevented by typecheck"),
+        };
+        if usize::from(dimensions) != N {
+            return Err(mk_deser_err::<Self>(
+                typ,
+                VectorDeserializationErrorKind::WrongDimensions {
+                    rust_type_dimensions: N,
+                    cql_type_dimensions: dimensions,
+                },
+            ));
+        }
+
+        let mut iter = VectorIterator::<'frame, 'metadata, T>::deserialize(typ, v)
+            .map_err(deser_error_replace_rust_name::<Self>)?;
+        // Collect into an array of options first, so that no allocation is needed.
+        let mut error = None;
+        let elements: [Option<T>; N] = std::array::from_fn(|_| match iter.next()? {
+            Ok(element) => Some(element),
+            Err(err) => {
+                error.get_or_insert(err);
+                None
+            }
+        });
+        if let Some(err) = error {
+            return Err(deser_error_replace_rust_name::<Self>(err));
+        }
+        // The iterator yields exactly `dimensions` elements, all of which succeeded.
+        Ok(elements.map(|element| element.expect("all elements were deserialized")))
+    }
+}
+
 impl<'frame, 'metadata, T> DeserializeValue<'frame, 'metadata> for BTreeSet<T>
 where
     T: DeserializeValue<'frame, 'metadata> + Ord,
@@ -1100,6 +1166,147 @@ where
     }
 }
 
+/// A borrowed view over a CQL `vector<float, N>`, which reads the elements
+/// directly from the serialized frame, without allocating.
+///
+/// Since `float` is a fixed-length type, the serialized vector is just
+/// a sequence of big-endian encoded `f32`s.
+#[derive(Debug, Clone, Copy)]
+pub struct FloatVectorView<'frame> {
+    bytes: &'frame [u8],
+}
+
+impl<'frame> FloatVectorView<'frame> {
+    /// Returns the number of elements in the vector.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bytes.len() / 4
+    }
+
+    /// Returns `true` if the vector has no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Returns the element at given index, or `None` if the index is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<f32> {
+        let bytes = self.bytes.get(index * 4..index * 4 + 4)?;
+        Some(f32::from_be_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Returns an iterator over the elements of the vector.
+    #[inline]
+    pub fn iter(&self) -> FloatVectorIterator<'frame> {
+        FloatVectorIterator {
+            chunks: self.bytes.chunks_exact(4),
+        }
+    }
+
+    /// Returns the serialized elements - big-endian encoded `f32`s.
+    #[inline]
+    pub fn as_be_bytes(&self) -> &'frame [u8] {
+        self.bytes
+    }
+
+    /// Copies the elements into the given slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the slice is different than the length of the vector.
+    pub fn copy_to_slice(&self, out: &mut [f32]) {
+        assert_eq!(
+            out.len(),
+            self.len(),
+            "destination slice length does not match the vector length"
+        );
+        for (out, element) in out.iter_mut().zip(self.iter()) {
+            *out = element;
+        }
+    }
+}
+
+impl<'frame> IntoIterator for FloatVectorView<'frame> {
+    type Item = f32;
+    type IntoIter = FloatVectorIterator<'frame>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'frame, 'metadata> DeserializeValue<'frame, 'metadata> for FloatVectorView<'frame> {
+    fn type_check(typ: &ColumnType) -> Result<(), TypeCheckError> {
+        match typ {
+            ColumnType::Vector { typ: element, .. } => {
+                <f32 as DeserializeValue<'frame, 'metadata>>::type_check(element).map_err(|err| {
+                    mk_typck_err::<Self>(typ, VectorTypeCheckErrorKind::ElementTypeCheckFailed(err))
+                })
+            }
+            _ => Err(mk_typck_err::<Self>(
+                typ,
+                VectorTypeCheckErrorKind::NotVector,
+            )),
+        }
+    }
+
+    fn deserialize(
+        typ: &'metadata ColumnType<'metadata>,
+        v: Option<FrameSlice<'frame>>,
+    ) -> Result<Self, DeserializationError> {
+        let dimensions = match typ {
+            ColumnType::Vector { dimensions, .. } => usize::from(*dimensions),
+            _ => unreachable!("Should be prevented by typecheck"),
+        };
+        let bytes = ensure_not_null_slice::<Self>(typ, v)?;
+        if bytes.len() != dimensions * 4 {
+            return Err(mk_deser_err::<Self>(
+                typ,
+                BuiltinDeserializationErrorKind::ByteLengthMismatch {
+                    expected: dimensions * 4,
+                    got: bytes.len(),
+                },
+            ));
+        }
+        Ok(Self { bytes })
+    }
+}
+
+/// An iterator over the elements of a [FloatVectorView].
+#[derive(Debug, Clone)]
+pub struct FloatVectorIterator<'frame> {
+    chunks: std::slice::ChunksExact<'frame, u8>,
+}
+
+impl Iterator for FloatVectorIterator<'_> {
+    type Item = f32;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks
+            .next()
+            .map(|chunk| f32::from_be_bytes(chunk.try_into().unwrap()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for FloatVectorIterator<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.chunks
+            .next_back()
+            .map(|chunk| f32::from_be_bytes(chunk.try_into().unwrap()))
+    }
+}
+
+impl ExactSizeIterator for FloatVectorIterator<'_> {}
+
 /// An iterator over a CQL map.
 pub struct MapIterator<'frame, 'metadata, K, V> {
     coll_typ: &'metadata ColumnType<'metadata>,
@@ -1513,8 +1720,116 @@ impl<'frame, 'metadata> Iterator for UdtIterator<'frame, 'metadata> {
     }
 }
 
+/// A User Defined Type value whose fields are accessed by name.
+///
+/// Deserializing a UDT into `NamedUdt` only finds the boundaries of the field values
+/// in the frame. The values are deserialized lazily, by [`NamedUdt::get`],
+/// into any type implementing [`DeserializeValue`].
+///
+/// Fields missing from the serialized form are treated as null.
+#[derive(Clone, Debug)]
+pub struct NamedUdt<'frame, 'metadata> {
+    type_name: &'metadata str,
+    keyspace: &'metadata str,
+    fields: Vec<(&'metadata UdtField<'metadata>, Option<FrameSlice<'frame>>)>,
+}
+
+type UdtField<'metadata> = (Cow<'metadata, str>, ColumnType<'metadata>);
+
+impl<'frame, 'metadata> NamedUdt<'frame, 'metadata> {
+    /// Deserializes the value of the field with the given name.
+    ///
+    /// Null and missing values can be deserialized only into `Option`.
+    pub fn get<T>(&self, name: &str) -> Result<T, GetByNameError>
+    where
+        T: DeserializeValue<'frame, 'metadata>,
+    {
+        let ((_, typ), slice) = self
+            .fields
+            .iter()
+            .find(|((field_name, _), _)| field_name == name)
+            .ok_or_else(|| GetByNameError::NotFound {
+                name: name.to_owned(),
+            })?;
+        deserialize_by_name(name, typ, *slice)
+    }
+
+    /// Returns whether the UDT has a field with the given name.
+    #[inline]
+    pub fn contains(&self, name: &str) -> bool {
+        self.fields
+            .iter()
+            .any(|((field_name, _), _)| field_name == name)
+    }
+
+    /// Returns the names and types of the fields, in the order of the definition.
+    #[inline]
+    pub fn fields(
+        &self,
+    ) -> impl Iterator<Item = &'metadata (Cow<'metadata, str>, ColumnType<'metadata>)> + '_ {
+        self.fields.iter().map(|(field, _)| *field)
+    }
+
+    /// Returns the name of the UDT.
+    #[inline]
+    pub fn type_name(&self) -> &'metadata str {
+        self.type_name
+    }
+
+    /// Returns the keyspace of the UDT.
+    #[inline]
+    pub fn keyspace(&self) -> &'metadata str {
+        self.keyspace
+    }
+}
+
+impl<'frame, 'metadata> DeserializeValue<'frame, 'metadata> for NamedUdt<'frame, 'metadata> {
+    fn type_check(typ: &ColumnType) -> Result<(), TypeCheckError> {
+        UdtIterator::type_check(typ).map_err(typck_error_replace_rust_name::<Self>)
+    }
+
+    fn deserialize(
+        typ: &'metadata ColumnType<'metadata>,
+        v: Option<FrameSlice<'frame>>,
+    ) -> Result<Self, DeserializationError> {
+        let iter =
+            UdtIterator::deserialize(typ, v).map_err(deser_error_replace_rust_name::<Self>)?;
+        let (type_name, keyspace) = (iter.type_name, iter.keyspace);
+        let fields = iter
+            .map(|(field, raw)| {
+                raw.map(|raw| (field, raw.flatten()))
+                    .map_err(deser_error_replace_rust_name::<Self>)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            type_name,
+            keyspace,
+            fields,
+        })
+    }
+}
+
 // Utilities
 
+// Used by the types which deserialize their columns or fields lazily, by name.
+pub(super) fn deserialize_by_name<'frame, 'metadata, T>(
+    name: &str,
+    typ: &'metadata ColumnType<'metadata>,
+    v: Option<FrameSlice<'frame>>,
+) -> Result<T, GetByNameError>
+where
+    T: DeserializeValue<'frame, 'metadata>,
+{
+    T::type_check(typ).map_err(|err| GetByNameError::TypeCheckFailed {
+        name: name.to_owned(),
+        err,
+    })?;
+    T::deserialize(typ, v).map_err(|err| GetByNameError::DeserializationFailed {
+        name: name.to_owned(),
+        err,
+    })
+}
+
 fn ensure_not_null_frame_slice<'frame, T>(
     typ: &ColumnType,
     v: Option<FrameSlice<'frame>>,
@@ -1814,6 +2129,14 @@ pub enum VectorTypeCheckErrorKind {
     /// Incompatible element types.
     #[error("the vector element types between the CQL type and the Rust type failed to type check against each other: {0}")]
     ElementTypeCheckFailed(TypeCheckError),
+    /// The number of dimensions of the CQL vector does not match the Rust type.
+    #[error("the CQL vector has {cql_type_dimensions} dimensions, but the Rust type has {rust_type_dimensions}")]
+    WrongDimensions {
+        /// The number of elements of the Rust type.
+        rust_type_dimensions: usize,
+        /// The number of dimensions of the CQL vector.
+        cql_type_dimensions: u16,
+    },
 }
 
 /// Describes why type checking of a map type failed.
@@ -2076,6 +2399,12 @@ pub enum BuiltinDeserializationErrorKind {
 
     /// Deserialization of this CQL type is not supported by the driver.
     Unsupported,
+
+    /// The read value does not correspond to any variant of the Rust enum.
+    UnknownEnumValue {
+        /// The read value, formatted as a string.
+        value: String,
+    },
 }
 
 impl Display for BuiltinDeserializationErrorKind {
@@ -2113,6 +2442,10 @@ impl Display for BuiltinDeserializationErrorKind {
             BuiltinDeserializationErrorKind::Unsupported => {
                 f.write_str("deserialization of this CQL type is not supported by the driver")
             }
+            BuiltinDeserializationErrorKind::UnknownEnumValue { value } => write!(
+                f,
+                "the value {value} does not correspond to any variant of the Rust enum"
+            ),
         }
     }
 }
@@ -2155,6 +2488,14 @@ pub enum VectorDeserializationErrorKind {
     /// One of the elements of the vector failed to deserialize.
     #[error("failed to deserialize one of the elements: {0}")]
     ElementDeserializationFailed(DeserializationError),
+    /// The number of dimensions of the CQL vector does not match the Rust type.
+    #[error("the CQL vector has {cql_type_dimensions} dimensions, but the Rust type has {rust_type_dimensions}")]
+    WrongDimensions {
+        /// The number of elements of the Rust type.
+        rust_type_dimensions: usize,
+        /// The number of dimensions of the CQL vector.
+        cql_type_dimensions: u16,
+    },
 }
 
 impl From<VectorDeserializationErrorKind> for BuiltinDeserializationErrorKind {
@@ -2338,3 +2679,82 @@ fn _test_udt_bad_attributes_name_skip_name_checks_limitations_on_allow_missing()
 /// }
 /// ```
 fn _test_udt_unordered_flavour_no_limitations_on_allow_missing() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeValue)]
+/// #[scylla(crate = scylla_cql)]
+/// enum TestEnum {
+///     A,
+///     B,
+/// }
+/// ```
+fn _test_enum_bad_attributes_repr_is_required() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeValue)]
+/// #[scylla(crate = scylla_cql, repr = "text")]
+/// enum TestEnum {
+///     A,
+///     B(i32),
+/// }
+/// ```
+fn _test_enum_variant_with_fields_requires_catch_all() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeValue)]
+/// #[scylla(crate = scylla_cql, repr = "text")]
+/// enum TestEnum {
+///     A,
+///     #[scylla(rename = "A")]
+///     B,
+/// }
+/// ```
+fn _test_enum_bad_attributes_rename_collision() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeValue)]
+/// #[scylla(crate = scylla_cql, repr = "int")]
+/// enum TestEnum {
+///     A,
+///     #[scylla(rename = "b")]
+///     B,
+/// }
+/// ```
+fn _test_enum_bad_attributes_rename_type_mismatch() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeValue)]
+/// #[scylla(crate = scylla_cql, repr = "tinyint")]
+/// enum TestEnum {
+///     A = 127,
+///     B,
+/// }
+/// ```
+fn _test_enum_value_out_of_range() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla_macros::DeserializeValue)]
+/// #[scylla(crate = scylla_cql, transparent)]
+/// struct TestNewtype(i32, i32);
+/// ```
+fn _test_transparent_requires_single_field() {}
+
+/// ```
+/// #[derive(scylla_macros::DeserializeValue)]
+/// #[scylla(crate = scylla_cql, repr = "int")]
+/// enum TestEnum {
+///     #[scylla(rename = 1)]
+///     A,
+///     #[scylla(rename = -1)]
+///     B,
+///     #[scylla(catch_all)]
+///     Unknown(i32),
+/// }
+/// ```
+fn _test_enum_int_repr_with_negative_rename_and_catch_all() {}
@@ -26,10 +26,10 @@ use crate::value::{
 
 use super::{
     mk_deser_err, BuiltinDeserializationError, BuiltinDeserializationErrorKind,
-    BuiltinTypeCheckError, BuiltinTypeCheckErrorKind, DeserializeValue, ListlikeIterator,
-    MapDeserializationErrorKind, MapIterator, MapTypeCheckErrorKind, MaybeEmpty,
+    BuiltinTypeCheckError, BuiltinTypeCheckErrorKind, DeserializeValue, FloatVectorView,
+    ListlikeIterator, MapDeserializationErrorKind, MapIterator, MapTypeCheckErrorKind, MaybeEmpty,
     SetOrListDeserializationErrorKind, SetOrListTypeCheckErrorKind, UdtDeserializationErrorKind,
-    UdtTypeCheckErrorKind,
+    UdtTypeCheckErrorKind, VectorTypeCheckErrorKind,
 };
 
 #[test]
@@ -500,6 +500,104 @@ fn test_deserialize_vector() {
     );
 }
 
+#[test]
+fn test_deserialize_vector_array() {
+    let float_vector = ColumnType::Vector {
+        typ: Box::new(ColumnType::Native(NativeType::Float)),
+        dimensions: 3,
+    };
+    let text_vector = ColumnType::Vector {
+        typ: Box::new(ColumnType::Native(NativeType::Text)),
+        dimensions: 2,
+    };
+
+    // ser/de identity
+    assert_ser_de_identity(&float_vector, &[1.5_f32, -2.0, 3.25], &mut Bytes::new());
+    assert_ser_de_identity(
+        &text_vector,
+        &["ala".to_owned(), "kota".to_owned()],
+        &mut Bytes::new(),
+    );
+
+    // interop with Vec<T>
+    let bytes = serialize(&float_vector, &vec![1.0_f32, 2.0, 3.0]);
+    let decoded = deserialize::<[f32; 3]>(&float_vector, &bytes).unwrap();
+    assert_eq!(decoded, [1.0, 2.0, 3.0]);
+
+    // dimensions mismatch is detected at type check
+    let err = deserialize::<[f32; 4]>(&float_vector, &bytes).unwrap_err();
+    let err = get_typeck_err(&err);
+    assert_matches!(
+        err.kind,
+        BuiltinTypeCheckErrorKind::VectorError(VectorTypeCheckErrorKind::WrongDimensions {
+            rust_type_dimensions: 4,
+            cql_type_dimensions: 3,
+        })
+    );
+
+    // arrays are not vectors of other element types
+    let err = deserialize::<[i32; 3]>(&float_vector, &bytes).unwrap_err();
+    let err = get_typeck_err(&err);
+    assert_matches!(
+        err.kind,
+        BuiltinTypeCheckErrorKind::VectorError(VectorTypeCheckErrorKind::ElementTypeCheckFailed(_))
+    );
+
+    // serializing an array of wrong length into a vector fails
+    let mut v = Vec::new();
+    let writer = CellWriter::new(&mut v);
+    [1.0_f32, 2.0].serialize(&float_vector, writer).unwrap_err();
+}
+
+#[test]
+fn test_float_vector_view() {
+    let float_vector = ColumnType::Vector {
+        typ: Box::new(ColumnType::Native(NativeType::Float)),
+        dimensions: 4,
+    };
+    let values = [0.5_f32, -1.0, f32::MAX, 42.0];
+    let bytes = serialize(&float_vector, &values);
+
+    let view = deserialize::<FloatVectorView>(&float_vector, &bytes).unwrap();
+    assert_eq!(view.len(), 4);
+    assert!(!view.is_empty());
+    assert_eq!(view.get(2), Some(f32::MAX));
+    assert_eq!(view.get(4), None);
+    assert_eq!(view.iter().collect::<Vec<_>>(), values);
+    assert_eq!(view.iter().next_back(), Some(42.0));
+    assert_eq!(view.iter().len(), 4);
+    assert_eq!(view.as_be_bytes().len(), 16);
+
+    let mut out = [0_f32; 4];
+    view.copy_to_slice(&mut out);
+    assert_eq!(out, values);
+    assert_eq!(view.into_iter().sum::<f32>(), values.iter().sum::<f32>());
+
+    // Only vectors of floats are accepted
+    let int_vector = ColumnType::Vector {
+        typ: Box::new(ColumnType::Native(NativeType::Int)),
+        dimensions: 4,
+    };
+    let err = deserialize::<FloatVectorView>(&int_vector, &bytes).unwrap_err();
+    let err = get_typeck_err(&err);
+    assert_matches!(
+        err.kind,
+        BuiltinTypeCheckErrorKind::VectorError(VectorTypeCheckErrorKind::ElementTypeCheckFailed(_))
+    );
+
+    // Byte length must match the declared dimensions
+    let short = make_bytes(&[0, 0, 0, 0, 0, 0, 0, 0]);
+    let err = deserialize::<FloatVectorView>(&float_vector, &short).unwrap_err();
+    let err = get_deser_err(&err);
+    assert_matches!(
+        err.kind,
+        BuiltinDeserializationErrorKind::ByteLengthMismatch {
+            expected: 16,
+            got: 8
+        }
+    );
+}
+
 #[test]
 fn test_deserialize_ascii() {
     const ASCII_TEXT: &str = "The quick brown fox jumps over the lazy dog";
@@ -1611,6 +1709,57 @@ fn test_udt_cross_rename_fields() {
     }
 }
 
+#[test]
+fn test_named_udt() {
+    use super::NamedUdt;
+    use crate::deserialize::GetByNameError;
+
+    let typ = udt_def_with_fields([
+        ("a", ColumnType::Native(NativeType::Text)),
+        ("b", ColumnType::Native(NativeType::Int)),
+        ("c", ColumnType::Native(NativeType::BigInt)),
+    ]);
+    // The last field is missing from the serialized form.
+    let udt_bytes = UdtSerializer::new()
+        .field("The quick brown fox".as_bytes())
+        .null_field()
+        .finalize();
+
+    let udt = deserialize::<NamedUdt>(&typ, &udt_bytes).unwrap();
+    assert_eq!(udt.type_name(), "udt");
+    assert_eq!(udt.keyspace(), "ks");
+    assert!(udt.contains("c"));
+    assert_eq!(
+        udt.fields()
+            .map(|(name, _)| name.as_ref())
+            .collect::<Vec<_>>(),
+        ["a", "b", "c"]
+    );
+
+    assert_eq!(udt.get::<&str>("a").unwrap(), "The quick brown fox");
+    assert_eq!(udt.get::<Option<i32>>("b").unwrap(), None);
+    assert_eq!(udt.get::<Option<i64>>("c").unwrap(), None);
+    assert_matches!(
+        udt.get::<i32>("d"),
+        Err(GetByNameError::NotFound { name }) if name == "d"
+    );
+    assert_matches!(
+        udt.get::<String>("b"),
+        Err(GetByNameError::TypeCheckFailed { name, .. }) if name == "b"
+    );
+    assert_matches!(
+        udt.get::<i64>("c"),
+        Err(GetByNameError::DeserializationFailed { name, .. }) if name == "c"
+    );
+
+    // Only UDTs can be deserialized into `NamedUdt`.
+    let err = NamedUdt::type_check(&ColumnType::Native(NativeType::Int)).unwrap_err();
+    assert_matches!(
+        get_typeck_err_inner(err.0.as_ref()).kind,
+        BuiltinTypeCheckErrorKind::UdtError(UdtTypeCheckErrorKind::NotUdt)
+    );
+}
+
 #[test]
 fn test_custom_type_parser() {
     #[derive(Default, Debug, PartialEq, Eq)]
@@ -2801,6 +2950,240 @@ fn metadata_does_not_bound_deserialized_values() {
     };
 }
 
+#[test]
+fn test_enum() {
+    #[derive(scylla_macros::DeserializeValue, scylla_macros::SerializeValue, PartialEq, Debug)]
+    #[scylla(crate = crate, repr = "text")]
+    enum Color {
+        Red,
+        #[scylla(rename = "GREEN")]
+        Green,
+        #[scylla(catch_all)]
+        Other(String),
+    }
+
+    #[derive(scylla_macros::DeserializeValue, scylla_macros::SerializeValue, PartialEq, Debug)]
+    #[scylla(crate = crate, repr = "int")]
+    enum Priority {
+        Low = 1,
+        High = 10,
+        Highest,
+    }
+
+    #[derive(scylla_macros::DeserializeValue, scylla_macros::SerializeValue, PartialEq, Debug)]
+    #[scylla(crate = crate, repr = "tinyint")]
+    enum Flag {
+        Off,
+        On,
+    }
+
+    let text = ColumnType::Native(NativeType::Text);
+    let ascii = ColumnType::Native(NativeType::Ascii);
+    let int = ColumnType::Native(NativeType::Int);
+    let tinyint = ColumnType::Native(NativeType::TinyInt);
+
+    // ser/de identity
+    for typ in [&text, &ascii] {
+        assert_ser_de_identity(typ, &Color::Red, &mut Bytes::new());
+        assert_ser_de_identity(typ, &Color::Green, &mut Bytes::new());
+        assert_ser_de_identity(typ, &Color::Other("blue".to_owned()), &mut Bytes::new());
+    }
+    assert_ser_de_identity(&int, &Priority::Low, &mut Bytes::new());
+    assert_ser_de_identity(&int, &Priority::Highest, &mut Bytes::new());
+    assert_ser_de_identity(&tinyint, &Flag::On, &mut Bytes::new());
+
+    // Values are mapped to variants
+    let green = make_bytes(b"GREEN");
+    assert_eq!(deserialize::<Color>(&text, &green).unwrap(), Color::Green);
+    let unknown = make_bytes(b"Green");
+    assert_eq!(
+        deserialize::<Color>(&text, &unknown).unwrap(),
+        Color::Other("Green".to_owned())
+    );
+    let eleven = make_bytes(&11i32.to_be_bytes());
+    assert_eq!(
+        deserialize::<Priority>(&int, &eleven).unwrap(),
+        Priority::Highest
+    );
+
+    // Type check errors
+    let err = deserialize::<Priority>(&text, &green).unwrap_err();
+    let err = get_typeck_err(&err);
+    assert_eq!(err.rust_name, std::any::type_name::<Priority>());
+    assert_matches!(
+        err.kind,
+        BuiltinTypeCheckErrorKind::MismatchedType {
+            expected: &[ColumnType::Native(NativeType::Int)],
+        }
+    );
+    let err = deserialize::<Flag>(&int, &eleven).unwrap_err();
+    let err = get_typeck_err(&err);
+    assert_eq!(err.rust_name, std::any::type_name::<Flag>());
+
+    // Unknown value without a catch-all variant
+    let two = make_bytes(&2i32.to_be_bytes());
+    let err = deserialize::<Priority>(&int, &two).unwrap_err();
+    let err = get_deser_err(&err);
+    assert_eq!(err.rust_name, std::any::type_name::<Priority>());
+    assert_matches!(
+        &err.kind,
+        BuiltinDeserializationErrorKind::UnknownEnumValue { value } if value == "2"
+    );
+
+    // Errors of the underlying type are reported with the enum's name
+    let err = deserialize::<Color>(&text, &make_null()).unwrap_err();
+    let err = get_deser_err(&err);
+    assert_eq!(err.rust_name, std::any::type_name::<Color>());
+    assert_matches!(err.kind, BuiltinDeserializationErrorKind::ExpectedNonNull);
+}
+
+#[test]
+fn test_transparent() {
+    #[derive(scylla_macros::DeserializeValue, scylla_macros::SerializeValue, PartialEq, Debug)]
+    #[scylla(crate = crate, transparent)]
+    struct UserId(Uuid);
+
+    #[derive(scylla_macros::DeserializeValue, scylla_macros::SerializeValue, PartialEq, Debug)]
+    #[scylla(crate = crate, transparent)]
+    struct Name<'a> {
+        name: &'a str,
+    }
+
+    #[derive(scylla_macros::DeserializeValue, PartialEq, Debug)]
+    #[scylla(crate = crate, transparent)]
+    struct Wrapper<T>(Option<T>);
+
+    let uuid = ColumnType::Native(NativeType::Uuid);
+    let text = ColumnType::Native(NativeType::Text);
+
+    assert_ser_de_identity(&uuid, &UserId(Uuid::from_u128(42)), &mut Bytes::new());
+    assert_ser_de_identity(&text, &Name { name: "foo" }, &mut Bytes::new());
+    assert_eq!(
+        deserialize::<Wrapper<i32>>(&ColumnType::Native(NativeType::Int), &make_null()).unwrap(),
+        Wrapper(None)
+    );
+
+    // Type check is delegated to the inner type
+    let err = deserialize::<UserId>(&text, &make_bytes(b"foo")).unwrap_err();
+    let err = get_typeck_err(&err);
+    assert_eq!(err.rust_name, std::any::type_name::<Uuid>());
+}
+
+/// Stores an `u32` in a `text` column. Used to test `#[scylla(with = ...)]`.
+pub(crate) mod u32_as_text {
+    use crate::deserialize::value::DeserializeValue;
+    use crate::deserialize::{DeserializationError, FrameSlice, TypeCheckError};
+    use crate::frame::response::result::ColumnType;
+    use crate::serialize::value::SerializeValue;
+    use crate::serialize::writers::WrittenCellProof;
+    use crate::serialize::{CellWriter, SerializationError};
+
+    pub(crate) fn serialize<'b>(
+        v: &u32,
+        typ: &ColumnType,
+        writer: CellWriter<'b>,
+    ) -> Result<WrittenCellProof<'b>, SerializationError> {
+        <String as SerializeValue>::serialize(&v.to_string(), typ, writer)
+    }
+
+    pub(crate) fn type_check(typ: &ColumnType) -> Result<(), TypeCheckError> {
+        <&str as DeserializeValue>::type_check(typ)
+    }
+
+    pub(crate) fn deserialize<'frame, 'metadata>(
+        typ: &'metadata ColumnType<'metadata>,
+        v: Option<FrameSlice<'frame>>,
+    ) -> Result<u32, DeserializationError> {
+        let s = <&str as DeserializeValue>::deserialize(typ, v)?;
+        s.parse().map_err(DeserializationError::new)
+    }
+}
+
+#[test]
+fn test_udt_custom_field_codec() {
+    #[derive(scylla_macros::DeserializeValue, scylla_macros::SerializeValue, PartialEq, Debug)]
+    #[scylla(crate = crate)]
+    struct Udt {
+        #[scylla(with = "u32_as_text")]
+        a: u32,
+        b: i32,
+    }
+
+    #[derive(scylla_macros::DeserializeValue, scylla_macros::SerializeValue, PartialEq, Debug)]
+    #[scylla(crate = crate, flavor = "enforce_order")]
+    struct OrderedUdt {
+        #[scylla(with = u32_as_text)]
+        a: u32,
+        b: i32,
+    }
+
+    #[derive(scylla_macros::DeserializeValue, PartialEq, Debug)]
+    #[scylla(crate = crate)]
+    struct RawUdt {
+        a: String,
+        b: i32,
+    }
+
+    #[derive(scylla_macros::SerializeValue)]
+    #[scylla(crate = crate)]
+    struct RawUdtRef<'a> {
+        a: &'a str,
+        b: i32,
+    }
+
+    let typ = udt_def_with_fields([
+        ("a", ColumnType::Native(NativeType::Text)),
+        ("b", ColumnType::Native(NativeType::Int)),
+    ]);
+
+    assert_ser_de_identity(&typ, &Udt { a: 42, b: 7 }, &mut Bytes::new());
+    assert_ser_de_identity(&typ, &OrderedUdt { a: 42, b: 7 }, &mut Bytes::new());
+
+    // The field is really stored as text
+    let bytes = serialize(&typ, &Udt { a: 42, b: 7 });
+    assert_eq!(
+        deserialize::<RawUdt>(&typ, &bytes).unwrap(),
+        RawUdt {
+            a: "42".to_owned(),
+            b: 7
+        }
+    );
+
+    // Type check is done by the custom module
+    let typ = udt_def_with_fields([
+        ("a", ColumnType::Native(NativeType::Int)),
+        ("b", ColumnType::Native(NativeType::Int)),
+    ]);
+    let err = Udt::type_check(&typ).unwrap_err();
+    let err = get_typeck_err_inner(err.0.as_ref());
+    let BuiltinTypeCheckErrorKind::UdtError(UdtTypeCheckErrorKind::FieldTypeCheckFailed {
+        ref field_name,
+        ref err,
+    }) = err.kind
+    else {
+        panic!("unexpected error kind: {:?}", err.kind)
+    };
+    assert_eq!(field_name, "a");
+    assert_eq!(
+        get_typeck_err_inner(err.0.as_ref()).rust_name,
+        std::any::type_name::<&str>()
+    );
+
+    // Errors from the custom deserializer are propagated
+    let typ = udt_def_with_fields([
+        ("a", ColumnType::Native(NativeType::Text)),
+        ("b", ColumnType::Native(NativeType::Int)),
+    ]);
+    let bytes = serialize(
+        &typ,
+        &RawUdtRef {
+            a: "not a number",
+            b: 7,
+        },
+    );
+    deserialize::<Udt>(&typ, &bytes).unwrap_err();
+}
+
 // Tests migrated from old frame/value_tests.rs file
 
 #[test]
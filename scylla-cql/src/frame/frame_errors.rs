@@ -10,9 +10,10 @@ pub use super::request::{
     register::RegisterSerializationError,
     startup::StartupSerializationError,
 };
+pub use super::response::{error::ErrorSerializationError, result::ResultSerializationError};
 
 use super::response::CqlResponseKind;
-use super::TryFromPrimitiveError;
+use super::{Compression, ProtocolVersion, TryFromPrimitiveError};
 use crate::utils::parse::ParseErrorCause;
 use thiserror::Error;
 
@@ -73,7 +74,7 @@ pub enum FrameHeaderParseError {
     FrameFromServer,
 
     /// Received a frame with unsupported version.
-    #[error("Received a frame from version {0}, but only 4 is supported")]
+    #[error("Received a frame from unsupported protocol version {0}")]
     VersionNotSupported(u8),
 
     /// Received unknown response opcode.
@@ -87,6 +88,68 @@ pub enum FrameHeaderParseError {
     /// Connection was closed before whole frame was read.
     #[error("Connection was closed before body was read: missing {0} out of {1}")]
     ConnectionClosed(usize, usize),
+
+    /// Length of a frame extracted from a segment does not match its header.
+    #[error("Frame length mismatch: header announces {expected} bytes, but got {actual} bytes")]
+    FrameLengthMismatch { expected: usize, actual: usize },
+}
+
+/// An error that occurred while reading a segment (CQL v5 framing).
+#[derive(Debug, Error, Clone)]
+#[non_exhaustive]
+pub enum SegmentParseError {
+    /// Failed to read the segment header from the socket.
+    #[error("Failed to read the segment header: {0}")]
+    HeaderIoError(Arc<std::io::Error>),
+
+    /// Failed to read the segment payload from the socket.
+    #[error("Failed to read the segment payload: {0}")]
+    PayloadIoError(Arc<std::io::Error>),
+
+    /// CRC24 checksum of the segment header does not match.
+    #[error(
+        "Segment header checksum mismatch: received {received:#08x}, computed {computed:#08x}"
+    )]
+    HeaderChecksumMismatch { received: u32, computed: u32 },
+
+    /// CRC32 checksum of the segment payload does not match.
+    #[error(
+        "Segment payload checksum mismatch: received {received:#010x}, computed {computed:#010x}"
+    )]
+    PayloadChecksumMismatch { received: u32, computed: u32 },
+
+    /// Failed to decompress the segment payload.
+    #[error("Error decompressing lz4 segment payload: {0}")]
+    Lz4DecompressError(Arc<dyn Error + Sync + Send>),
+
+    /// Compression that is not allowed in segments was negotiated.
+    #[error("Compression {0} is not supported in protocol v5 segments")]
+    UnsupportedCompression(Compression),
+
+    /// A self-contained segment did not end at a frame boundary.
+    #[error("Self-contained segment contains an incomplete frame")]
+    IncompleteFrameInSelfContainedSegment,
+
+    /// A self-contained segment arrived while a frame spanning multiple segments was incomplete.
+    #[error("Received a self-contained segment in the middle of a large frame")]
+    UnexpectedSelfContainedSegment,
+
+    /// Non-self-contained segments carried more data than the frame they were part of.
+    #[error("Non-self-contained segments contain data beyond a single frame")]
+    MultipleFramesInNonSelfContainedSegment,
+}
+
+/// An error that occurred while wrapping frames into segments (CQL v5 framing).
+#[derive(Debug, Error, Clone)]
+#[non_exhaustive]
+pub enum SegmentSerializationError {
+    /// Segment payload exceeds the maximum length allowed by the protocol.
+    #[error("Segment payload too large: {0} bytes")]
+    PayloadTooLarge(usize),
+
+    /// Compression that is not allowed in segments was negotiated.
+    #[error("Compression {0} is not supported in protocol v5 segments")]
+    UnsupportedCompression(Compression),
 }
 
 /// An error that occurred during CQL request serialization.
@@ -121,11 +184,55 @@ pub enum CqlRequestSerializationError {
     #[error("Failed to serialize QUERY request: {0}")]
     QuerySerialization(#[from] QuerySerializationError),
 
+    /// Failed to serialize the custom payload of the request.
+    #[error("Failed to serialize custom payload: {0}")]
+    CustomPayloadSerialization(std::num::TryFromIntError),
+
+    /// A custom payload was given, but the protocol version doesn't allow
+    /// sending it along with the request.
+    #[error("Custom payloads are not supported in protocol {0}, they require protocol v4")]
+    CustomPayloadNotSupported(ProtocolVersion),
+
     /// Request body compression failed.
     #[error("Snap compression error: {0}")]
     SnapCompressError(Arc<dyn Error + Sync + Send>),
 }
 
+/// An error that occurred during CQL response serialization.
+#[non_exhaustive]
+#[derive(Error, Debug, Clone)]
+// Check triggers because all variants end with "Serialization".
+#[expect(clippy::enum_variant_names)]
+pub enum CqlResponseSerializationError {
+    /// Failed to serialize ERROR response.
+    #[error("Failed to serialize ERROR response: {0}")]
+    ErrorSerialization(#[from] ErrorSerializationError),
+
+    /// Failed to serialize AUTHENTICATE response.
+    #[error("Failed to serialize AUTHENTICATE response: {0}")]
+    AuthenticateSerialization(std::num::TryFromIntError),
+
+    /// Failed to serialize SUPPORTED response.
+    #[error("Failed to serialize SUPPORTED response: {0}")]
+    SupportedSerialization(std::num::TryFromIntError),
+
+    /// Failed to serialize RESULT response.
+    #[error("Failed to serialize RESULT response: {0}")]
+    ResultSerialization(#[from] ResultSerializationError),
+
+    /// Failed to serialize EVENT response.
+    #[error("Failed to serialize EVENT response: {0}")]
+    EventSerialization(std::num::TryFromIntError),
+
+    /// Failed to serialize AUTH_CHALLENGE response.
+    #[error("Failed to serialize AUTH_CHALLENGE response: {0}")]
+    AuthChallengeSerialization(std::num::TryFromIntError),
+
+    /// Failed to serialize AUTH_SUCCESS response.
+    #[error("Failed to serialize AUTH_SUCCESS response: {0}")]
+    AuthSuccessSerialization(std::num::TryFromIntError),
+}
+
 /// An error type returned when deserialization of CQL
 /// server response fails.
 #[non_exhaustive]
@@ -300,6 +407,8 @@ pub enum ClusterChangeEventParseError {
 pub enum PreparedParseError {
     #[error("Malformed prepared statement's id length: {0}")]
     IdLengthParseError(LowLevelDeserializationError),
+    #[error("Malformed result metadata id: {0}")]
+    ResultMetadataIdParseError(LowLevelDeserializationError),
     #[error("Invalid result metadata: {0}")]
     ResultMetadataParseError(ResultMetadataParseError),
     #[error("Invalid prepared metadata: {0}")]
@@ -331,6 +440,10 @@ pub enum RawRowsAndPagingStateResponseParseError {
     /// Failed to parse paging state response.
     #[error("Malformed paging state: {0}")]
     PagingStateParseError(LowLevelDeserializationError),
+
+    /// Failed to parse new result metadata id.
+    #[error("Malformed new result metadata id: {0}")]
+    NewMetadataIdParseError(LowLevelDeserializationError),
 }
 
 /// An error type returned when deserialization
@@ -400,6 +513,10 @@ pub enum ResultMetadataParseError {
     #[error("Malformed paging state: {0}")]
     PagingStateParseError(LowLevelDeserializationError),
 
+    /// Failed to parse new result metadata id.
+    #[error("Malformed new result metadata id: {0}")]
+    NewMetadataIdParseError(LowLevelDeserializationError),
+
     /// Failed to parse global table spec.
     #[error("Invalid global table spec: {0}")]
     GlobalTableSpecParseError(#[from] TableSpecParseError),
@@ -2,6 +2,7 @@ pub mod frame_errors;
 pub mod protocol_features;
 pub mod request;
 pub mod response;
+pub mod segment;
 pub mod server_event_type;
 pub mod types;
 
@@ -23,6 +24,85 @@ use response::ResponseOpcode;
 
 const HEADER_SIZE: usize = 9;
 
+/// Version of the CQL native protocol.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum ProtocolVersion {
+    /// Version 3 of the protocol.
+    ///
+    /// Supported for compatibility with older Cassandra deployments.
+    /// Prepared statement metadata doesn't carry partition key indexes,
+    /// so token-aware routing of prepared statements is not available.
+    /// Requests with unset values or custom payloads fail to serialize.
+    V3,
+
+    /// Version 4 of the protocol.
+    #[default]
+    V4,
+
+    /// Version 5 of the protocol.
+    ///
+    /// Once the connection is established, frames are wrapped in checksummed
+    /// segments (see the [segment] module).
+    V5,
+}
+
+impl ProtocolVersion {
+    /// The highest protocol version implemented by the driver.
+    pub const HIGHEST: ProtocolVersion = ProtocolVersion::V5;
+
+    /// The lowest protocol version implemented by the driver.
+    pub const LOWEST: ProtocolVersion = ProtocolVersion::V3;
+
+    /// Returns the version number, as it is written in the frame header.
+    pub fn as_u8(self) -> u8 {
+        match self {
+            ProtocolVersion::V3 => 0x03,
+            ProtocolVersion::V4 => 0x04,
+            ProtocolVersion::V5 => 0x05,
+        }
+    }
+
+    /// Returns true if frames are wrapped in segments after the handshake.
+    ///
+    /// If so, compression is applied per segment, not per frame.
+    pub fn uses_segments(self) -> bool {
+        self >= ProtocolVersion::V5
+    }
+
+    /// Returns the next lower version implemented by the driver,
+    /// or `None` if this is the lowest one.
+    pub fn lower(self) -> Option<ProtocolVersion> {
+        match self {
+            ProtocolVersion::V3 => None,
+            ProtocolVersion::V4 => Some(ProtocolVersion::V3),
+            ProtocolVersion::V5 => Some(ProtocolVersion::V4),
+        }
+    }
+}
+
+impl TryFrom<u8> for ProtocolVersion {
+    type Error = TryFromPrimitiveError<u8>;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x03 => Ok(Self::V3),
+            0x04 => Ok(Self::V4),
+            0x05 => Ok(Self::V5),
+            _ => Err(TryFromPrimitiveError {
+                enum_name: "ProtocolVersion",
+                primitive: value,
+            }),
+        }
+    }
+}
+
+impl Display for ProtocolVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "v{}", self.as_u8())
+    }
+}
+
 pub mod flag {
     //! Frame flags
     pub const COMPRESSION: u8 = 0x01;
@@ -94,27 +174,55 @@ pub struct SerializedRequest {
 }
 
 impl SerializedRequest {
+    /// Serializes the request into a frame of the given protocol version.
+    ///
+    /// `compression` is applied to the frame body. Protocol versions which
+    /// [use segments](ProtocolVersion::uses_segments) compress segments instead,
+    /// so `None` should be passed for them.
+    ///
+    /// If `custom_payload` is given, it is written in front of the request body
+    /// and the [CUSTOM_PAYLOAD](flag::CUSTOM_PAYLOAD) flag is set. Custom payloads
+    /// require protocol v4, so this fails for older versions.
     pub fn make<R: SerializableRequest>(
         req: &R,
+        version: ProtocolVersion,
         compression: Option<Compression>,
         tracing: bool,
+        custom_payload: Option<&HashMap<String, Bytes>>,
     ) -> Result<SerializedRequest, CqlRequestSerializationError> {
+        if custom_payload.is_some() && version < ProtocolVersion::V4 {
+            return Err(CqlRequestSerializationError::CustomPayloadNotSupported(
+                version,
+            ));
+        }
+
         let mut flags = 0;
         let mut data = vec![0; HEADER_SIZE];
 
         if let Some(compression) = compression {
             flags |= flag::COMPRESSION;
-            let body = req.to_bytes()?;
+            let mut body = Vec::new();
+            if let Some(custom_payload) = custom_payload {
+                flags |= flag::CUSTOM_PAYLOAD;
+                types::write_bytes_map(custom_payload, &mut body)
+                    .map_err(CqlRequestSerializationError::CustomPayloadSerialization)?;
+            }
+            req.serialize(&mut body, version)?;
             compress_append(&body, compression, &mut data)?;
         } else {
-            req.serialize(&mut data)?;
+            if let Some(custom_payload) = custom_payload {
+                flags |= flag::CUSTOM_PAYLOAD;
+                types::write_bytes_map(custom_payload, &mut data)
+                    .map_err(CqlRequestSerializationError::CustomPayloadSerialization)?;
+            }
+            req.serialize(&mut data, version)?;
         }
 
         if tracing {
             flags |= flag::TRACING;
         }
 
-        data[0] = 4; // We only support version 4 for now
+        data[0] = version.as_u8();
         data[1] = flags;
         // Leave space for the stream number
         data[4] = R::OPCODE as u8;
@@ -152,23 +260,19 @@ impl Default for FrameParams {
     }
 }
 
-pub async fn read_response_frame(
-    reader: &mut (impl AsyncRead + Unpin),
-) -> Result<(FrameParams, ResponseOpcode, Bytes), FrameHeaderParseError> {
-    let mut raw_header = [0u8; HEADER_SIZE];
-    reader
-        .read_exact(&mut raw_header[..])
-        .await
-        .map_err(FrameHeaderParseError::HeaderIoError)?;
-
+/// Parses the header of a response frame.
+///
+/// Returns the frame parameters, the opcode and the length of the frame body.
+fn parse_response_frame_header(
+    raw_header: &[u8; HEADER_SIZE],
+) -> Result<(FrameParams, ResponseOpcode, usize), FrameHeaderParseError> {
     let mut buf = &raw_header[..];
 
-    // TODO: Validate version
     let version = buf.get_u8();
     if version & 0x80 != 0x80 {
         return Err(FrameHeaderParseError::FrameFromClient);
     }
-    if version & 0x7F != 0x04 {
+    if ProtocolVersion::try_from(version & 0x7F).is_err() {
         return Err(FrameHeaderParseError::VersionNotSupported(version & 0x7f));
     }
 
@@ -186,6 +290,45 @@ pub async fn read_response_frame(
     // TODO: Guard from frames that are too large
     let length = buf.get_u32() as usize;
 
+    Ok((frame_params, opcode, length))
+}
+
+/// Parses a response frame which has already been read into memory,
+/// e.g. extracted from a segment by [segment::SegmentDecoder].
+pub fn parse_response_frame(
+    mut frame: Bytes,
+) -> Result<(FrameParams, ResponseOpcode, Bytes), FrameHeaderParseError> {
+    let raw_header: &[u8; HEADER_SIZE] = frame
+        .get(..HEADER_SIZE)
+        .and_then(|header| header.try_into().ok())
+        .ok_or(FrameHeaderParseError::FrameLengthMismatch {
+            expected: HEADER_SIZE,
+            actual: frame.len(),
+        })?;
+    let (frame_params, opcode, length) = parse_response_frame_header(raw_header)?;
+
+    if frame.len() != HEADER_SIZE + length {
+        return Err(FrameHeaderParseError::FrameLengthMismatch {
+            expected: HEADER_SIZE + length,
+            actual: frame.len(),
+        });
+    }
+    frame.advance(HEADER_SIZE);
+
+    Ok((frame_params, opcode, frame))
+}
+
+pub async fn read_response_frame(
+    reader: &mut (impl AsyncRead + Unpin),
+) -> Result<(FrameParams, ResponseOpcode, Bytes), FrameHeaderParseError> {
+    let mut raw_header = [0u8; HEADER_SIZE];
+    reader
+        .read_exact(&mut raw_header[..])
+        .await
+        .map_err(FrameHeaderParseError::HeaderIoError)?;
+
+    let (frame_params, opcode, length) = parse_response_frame_header(&raw_header)?;
+
     let mut raw_body = Vec::with_capacity(length).limit(length);
     while raw_body.has_remaining_mut() {
         let n = reader.read_buf(&mut raw_body).await.map_err(|err| {
@@ -343,4 +486,67 @@ mod test {
         assert_eq!(32, comp_body.len());
         assert_eq!(uncomp_body.as_bytes(), result);
     }
+
+    #[test]
+    fn protocol_version_downgrade_path() {
+        let mut versions = vec![ProtocolVersion::HIGHEST];
+        while let Some(lower) = versions.last().unwrap().lower() {
+            versions.push(lower);
+        }
+        assert_eq!(
+            versions,
+            [
+                ProtocolVersion::V5,
+                ProtocolVersion::V4,
+                ProtocolVersion::V3
+            ]
+        );
+        assert_eq!(*versions.last().unwrap(), ProtocolVersion::LOWEST);
+
+        for version in versions {
+            assert_eq!(ProtocolVersion::try_from(version.as_u8()).unwrap(), version);
+        }
+        assert!(ProtocolVersion::try_from(0x02).is_err());
+        assert!(ProtocolVersion::try_from(0x06).is_err());
+    }
+
+    #[test]
+    fn custom_payload_is_written_before_body() {
+        let query = request::query::Query {
+            contents: std::borrow::Cow::Borrowed("SELECT * FROM ks.t"),
+            parameters: Default::default(),
+        };
+        let payload = HashMap::from([("key".to_owned(), Bytes::from_static(b"value"))]);
+
+        for compression in [None, Some(Compression::Lz4), Some(Compression::Snappy)] {
+            let request = SerializedRequest::make(
+                &query,
+                ProtocolVersion::V4,
+                compression,
+                false,
+                Some(&payload),
+            )
+            .unwrap();
+            let data = request.get_data();
+            assert_ne!(data[1] & flag::CUSTOM_PAYLOAD, 0);
+
+            let body = match compression {
+                Some(compression) => decompress(&data[HEADER_SIZE..], compression).unwrap(),
+                None => data[HEADER_SIZE..].to_vec(),
+            };
+            let mut buf = &body[..];
+            assert_eq!(types::read_bytes_map(&mut buf).unwrap(), payload);
+            assert_eq!(buf, query.to_bytes(ProtocolVersion::V4).unwrap());
+        }
+
+        let request =
+            SerializedRequest::make(&query, ProtocolVersion::V4, None, false, None).unwrap();
+        assert_eq!(request.get_data()[1] & flag::CUSTOM_PAYLOAD, 0);
+
+        // Protocol v3 has no custom payloads.
+        assert!(
+            SerializedRequest::make(&query, ProtocolVersion::V3, None, false, Some(&payload))
+                .is_err()
+        );
+    }
 }
@@ -1,10 +1,13 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
 
+use super::ProtocolVersion;
+
 const RATE_LIMIT_ERROR_EXTENSION: &str = "SCYLLA_RATE_LIMIT_ERROR";
 pub const SCYLLA_LWT_ADD_METADATA_MARK_EXTENSION: &str = "SCYLLA_LWT_ADD_METADATA_MARK";
 pub const LWT_OPTIMIZATION_META_BIT_MASK_KEY: &str = "LWT_OPTIMIZATION_META_BIT_MASK";
 const TABLETS_ROUTING_V1_KEY: &str = "TABLETS_ROUTING_V1";
+pub const SCYLLA_USE_METADATA_ID_KEY: &str = "SCYLLA_USE_METADATA_ID";
 
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
 #[non_exhaustive]
@@ -12,6 +15,9 @@ pub struct ProtocolFeatures {
     pub rate_limit_error: Option<i32>,
     pub lwt_optimization_meta_bit_mask: Option<u32>,
     pub tablets_v1_supported: bool,
+    /// Scylla extension which brings result metadata ids, introduced
+    /// in protocol v5, to older protocol versions.
+    pub scylla_metadata_id_supported: bool,
 }
 
 // TODO: Log information about options which failed to parse
@@ -24,6 +30,7 @@ impl ProtocolFeatures {
                 supported,
             ),
             tablets_v1_supported: Self::check_tablets_routing_v1_support(supported),
+            scylla_metadata_id_supported: Self::check_scylla_metadata_id_support(supported),
         }
     }
 
@@ -46,6 +53,10 @@ impl ProtocolFeatures {
         supported.contains_key(TABLETS_ROUTING_V1_KEY)
     }
 
+    fn check_scylla_metadata_id_support(supported: &HashMap<String, Vec<String>>) -> bool {
+        supported.contains_key(SCYLLA_USE_METADATA_ID_KEY)
+    }
+
     // Looks up a field which starts with `key=` and returns the rest
     fn get_cql_extension_field<'a>(vals: &'a [String], key: &str) -> Option<&'a str> {
         vals.iter()
@@ -66,6 +77,17 @@ impl ProtocolFeatures {
         if self.tablets_v1_supported {
             options.insert(Cow::Borrowed(TABLETS_ROUTING_V1_KEY), Cow::Borrowed(""));
         }
+
+        if self.scylla_metadata_id_supported {
+            options.insert(Cow::Borrowed(SCYLLA_USE_METADATA_ID_KEY), Cow::Borrowed(""));
+        }
+    }
+
+    /// Returns true if result metadata ids are exchanged in RESULT:Prepared
+    /// and EXECUTE messages: since protocol v5, or earlier if the Scylla
+    /// extension was negotiated.
+    pub fn uses_result_metadata_id(&self, version: ProtocolVersion) -> bool {
+        version >= ProtocolVersion::V5 || self.scylla_metadata_id_supported
     }
 
     pub fn prepared_flags_contain_lwt_mark(&self, flags: u32) -> bool {
@@ -2,12 +2,15 @@ use std::num::TryFromIntError;
 
 use thiserror::Error;
 
-use crate::frame::frame_errors::CqlRequestSerializationError;
+use crate::frame::{frame_errors::CqlRequestSerializationError, ProtocolVersion};
 
-use crate::frame::request::{RequestOpcode, SerializableRequest};
-use crate::frame::types::write_bytes_opt;
+use crate::frame::request::{
+    DeserializableRequest, RequestDeserializationError, RequestOpcode, SerializableRequest,
+};
+use crate::frame::types::{read_bytes_opt, write_bytes_opt};
 
 // Implements Authenticate Response
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
 pub struct AuthResponse {
     pub response: Option<Vec<u8>>,
 }
@@ -15,12 +18,26 @@ pub struct AuthResponse {
 impl SerializableRequest for AuthResponse {
     const OPCODE: RequestOpcode = RequestOpcode::AuthResponse;
 
-    fn serialize(&self, buf: &mut Vec<u8>) -> Result<(), CqlRequestSerializationError> {
+    fn serialize(
+        &self,
+        buf: &mut Vec<u8>,
+        _version: ProtocolVersion,
+    ) -> Result<(), CqlRequestSerializationError> {
         Ok(write_bytes_opt(self.response.as_ref(), buf)
             .map_err(AuthResponseSerializationError::ResponseSerialization)?)
     }
 }
 
+impl DeserializableRequest for AuthResponse {
+    fn deserialize(
+        buf: &mut &[u8],
+        _version: ProtocolVersion,
+    ) -> Result<Self, RequestDeserializationError> {
+        let response = read_bytes_opt(buf)?.map(|bytes| bytes.to_vec());
+        Ok(Self { response })
+    }
+}
+
 /// An error type returned when serialization of AUTH_RESPONSE request fails.
 #[non_exhaustive]
 #[derive(Error, Debug, Clone)]
@@ -6,19 +6,23 @@ use crate::frame::{
     frame_errors::CqlRequestSerializationError,
     request::{RequestOpcode, SerializableRequest},
     types::{self, SerialConsistency},
+    ProtocolVersion,
 };
 use crate::serialize::{
     raw_batch::{RawBatchValues, RawBatchValuesIterator},
-    row::SerializedValues,
+    row::{self, SerializedValues},
     RowWriter, SerializationError,
 };
 
-use super::{DeserializableRequest, RequestDeserializationError};
+use super::{
+    query::{read_flags, write_flags, FLAG_WITH_KEYSPACE},
+    DeserializableRequest, RequestDeserializationError,
+};
 
 // Batch flags
-const FLAG_WITH_SERIAL_CONSISTENCY: u8 = 0x10;
-const FLAG_WITH_DEFAULT_TIMESTAMP: u8 = 0x20;
-const ALL_FLAGS: u8 = FLAG_WITH_SERIAL_CONSISTENCY | FLAG_WITH_DEFAULT_TIMESTAMP;
+const FLAG_WITH_SERIAL_CONSISTENCY: u32 = 0x10;
+const FLAG_WITH_DEFAULT_TIMESTAMP: u32 = 0x20;
+const ALL_FLAGS: u32 = FLAG_WITH_SERIAL_CONSISTENCY | FLAG_WITH_DEFAULT_TIMESTAMP;
 
 #[cfg_attr(test, derive(Debug, PartialEq, Eq))]
 pub struct Batch<'b, Statement, Values>
@@ -33,6 +37,9 @@ where
     pub serial_consistency: Option<types::SerialConsistency>,
     pub timestamp: Option<i64>,
     pub values: Values,
+    /// Keyspace in which the batch is executed, instead of the keyspace
+    /// set on the connection with `USE`. Supported since protocol v5.
+    pub keyspace: Option<Cow<'b, str>>,
 }
 
 /// The type of a batch.
@@ -75,7 +82,11 @@ where
     Statement: Clone,
     Values: RawBatchValues,
 {
-    fn do_serialize(&self, buf: &mut Vec<u8>) -> Result<(), BatchSerializationError> {
+    fn do_serialize(
+        &self,
+        buf: &mut Vec<u8>,
+        version: ProtocolVersion,
+    ) -> Result<(), BatchSerializationError> {
         // Serializing type of batch
         buf.put_u8(self.batch_type as u8);
 
@@ -130,6 +141,12 @@ where
                 }
             };
             buf[length_pos..length_pos + 2].copy_from_slice(&count.to_be_bytes());
+            if version < ProtocolVersion::V4 && row::contains_unset(&buf[length_pos + 2..]) {
+                return Err(BatchSerializationError::StatementSerialization {
+                    statement_idx: idx,
+                    error: BatchStatementSerializationError::UnsetValuesNotSupported(version),
+                });
+            }
 
             n_serialized_statements += 1;
         }
@@ -160,8 +177,14 @@ where
         if self.timestamp.is_some() {
             flags |= FLAG_WITH_DEFAULT_TIMESTAMP;
         }
+        if self.keyspace.is_some() {
+            if version < ProtocolVersion::V5 {
+                return Err(BatchSerializationError::KeyspaceNotSupported(version));
+            }
+            flags |= FLAG_WITH_KEYSPACE;
+        }
 
-        buf.put_u8(flags);
+        write_flags(flags, version, buf);
 
         if let Some(serial_consistency) = self.serial_consistency {
             types::write_serial_consistency(serial_consistency, buf);
@@ -169,6 +192,10 @@ where
         if let Some(timestamp) = self.timestamp {
             types::write_long(timestamp, buf);
         }
+        if let Some(keyspace) = &self.keyspace {
+            types::write_string(keyspace, buf)
+                .map_err(BatchSerializationError::KeyspaceSerialization)?;
+        }
 
         Ok(())
     }
@@ -182,8 +209,12 @@ where
 {
     const OPCODE: RequestOpcode = RequestOpcode::Batch;
 
-    fn serialize(&self, buf: &mut Vec<u8>) -> Result<(), CqlRequestSerializationError> {
-        self.do_serialize(buf)?;
+    fn serialize(
+        &self,
+        buf: &mut Vec<u8>,
+        version: ProtocolVersion,
+    ) -> Result<(), CqlRequestSerializationError> {
+        self.do_serialize(buf, version)?;
         Ok(())
     }
 }
@@ -236,7 +267,10 @@ impl<'s, 'b> From<&'s BatchStatement<'b>> for BatchStatement<'s> {
 }
 
 impl<'b> DeserializableRequest for Batch<'b, BatchStatement<'b>, Vec<SerializedValues>> {
-    fn deserialize(buf: &mut &[u8]) -> Result<Self, RequestDeserializationError> {
+    fn deserialize(
+        buf: &mut &[u8],
+        version: ProtocolVersion,
+    ) -> Result<Self, RequestDeserializationError> {
         let batch_type = buf.get_u8().try_into()?;
 
         let statements_count: usize = types::read_short(buf)?.into();
@@ -253,8 +287,13 @@ impl<'b> DeserializableRequest for Batch<'b, BatchStatement<'b>, Vec<SerializedV
 
         let consistency = types::read_consistency(buf)?;
 
-        let flags = buf.get_u8();
-        let unknown_flags = flags & (!ALL_FLAGS);
+        let flags = read_flags(buf, version)?;
+        let known_flags = if version >= ProtocolVersion::V5 {
+            ALL_FLAGS | FLAG_WITH_KEYSPACE
+        } else {
+            ALL_FLAGS
+        };
+        let unknown_flags = flags & (!known_flags);
         if unknown_flags != 0 {
             return Err(RequestDeserializationError::UnknownFlags {
                 flags: unknown_flags,
@@ -262,6 +301,7 @@ impl<'b> DeserializableRequest for Batch<'b, BatchStatement<'b>, Vec<SerializedV
         }
         let serial_consistency_flag = (flags & FLAG_WITH_SERIAL_CONSISTENCY) != 0;
         let default_timestamp_flag = (flags & FLAG_WITH_DEFAULT_TIMESTAMP) != 0;
+        let keyspace_flag = (flags & FLAG_WITH_KEYSPACE) != 0;
 
         let serial_consistency = serial_consistency_flag
             .then(|| types::read_consistency(buf))
@@ -280,6 +320,10 @@ impl<'b> DeserializableRequest for Batch<'b, BatchStatement<'b>, Vec<SerializedV
             .then(|| types::read_long(buf))
             .transpose()?;
 
+        let keyspace = keyspace_flag
+            .then(|| types::read_string(buf).map(|keyspace| Cow::Owned(keyspace.to_owned())))
+            .transpose()?;
+
         let (statements, values): (Vec<BatchStatement>, Vec<SerializedValues>) =
             statements_with_values.into_iter().unzip();
 
@@ -290,6 +334,7 @@ impl<'b> DeserializableRequest for Batch<'b, BatchStatement<'b>, Vec<SerializedV
             timestamp,
             statements: Cow::Owned(statements),
             values,
+            keyspace,
         })
     }
 }
@@ -322,6 +367,15 @@ pub enum BatchSerializationError {
         n_announced_statements: usize,
         n_serialized_statements: usize,
     },
+
+    /// Failed to serialize the keyspace name.
+    #[error("Failed to serialize keyspace name: {0}")]
+    KeyspaceSerialization(TryFromIntError),
+
+    /// A keyspace was set, but the protocol version doesn't allow
+    /// sending it along with the request.
+    #[error("Per-request keyspace is not supported in protocol {0}, it requires protocol v5")]
+    KeyspaceNotSupported(ProtocolVersion),
 }
 
 /// An error type returned when serialization of one of the
@@ -344,4 +398,9 @@ pub enum BatchStatementSerializationError {
     /// Too many bound values provided.
     #[error("Too many values provided for the statement: {0}")]
     TooManyValues(usize),
+
+    /// Some of the bound values are unset, but the protocol version
+    /// doesn't allow sending unset values.
+    #[error("Unset values are not supported in protocol {0}, they require protocol v4")]
+    UnsetValuesNotSupported(ProtocolVersion),
 }
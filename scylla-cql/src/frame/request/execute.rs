@@ -1,6 +1,7 @@
 use std::num::TryFromIntError;
 
-use crate::frame::frame_errors::CqlRequestSerializationError;
+use crate::frame::protocol_features::ProtocolFeatures;
+use crate::frame::{frame_errors::CqlRequestSerializationError, ProtocolVersion};
 use bytes::Bytes;
 use thiserror::Error;
 
@@ -11,43 +12,79 @@ use crate::{
 
 use super::{
     query::{QueryParameters, QueryParametersSerializationError},
-    DeserializableRequest, RequestDeserializationError,
+    RequestDeserializationError,
 };
 
 #[cfg_attr(test, derive(Debug, PartialEq, Eq))]
 pub struct Execute<'a> {
     pub id: Bytes,
+    /// Id of the result metadata that the driver has cached for the statement.
+    ///
+    /// Always sent in protocol v5 and later, where `None` is sent as empty bytes.
+    /// In older versions it is only sent if present, so it must be `None`
+    /// unless the Scylla metadata id extension was negotiated on the connection
+    /// (see [ProtocolFeatures::uses_result_metadata_id]).
+    pub result_metadata_id: Option<Bytes>,
     pub parameters: query::QueryParameters<'a>,
 }
 
 impl SerializableRequest for Execute<'_> {
     const OPCODE: RequestOpcode = RequestOpcode::Execute;
 
-    fn serialize(&self, buf: &mut Vec<u8>) -> Result<(), CqlRequestSerializationError> {
+    fn serialize(
+        &self,
+        buf: &mut Vec<u8>,
+        version: ProtocolVersion,
+    ) -> Result<(), CqlRequestSerializationError> {
         // Serializing statement id
         types::write_short_bytes(&self.id[..], buf)
             .map_err(ExecuteSerializationError::StatementIdSerialization)?;
 
+        // Serializing result metadata id
+        if version >= ProtocolVersion::V5 || self.result_metadata_id.is_some() {
+            let result_metadata_id = self.result_metadata_id.as_deref().unwrap_or(&[]);
+            types::write_short_bytes(result_metadata_id, buf)
+                .map_err(ExecuteSerializationError::ResultMetadataIdSerialization)?;
+        }
+
         // Serializing params
         self.parameters
-            .serialize(buf)
+            .serialize(buf, version)
             .map_err(ExecuteSerializationError::QueryParametersSerialization)?;
         Ok(())
     }
 }
 
-impl DeserializableRequest for Execute<'_> {
-    fn deserialize(buf: &mut &[u8]) -> Result<Self, RequestDeserializationError> {
+impl Execute<'_> {
+    /// Deserializes the request sent over a connection with the given
+    /// protocol version and features, which determine whether
+    /// the request carries a result metadata id.
+    pub fn deserialize(
+        buf: &mut &[u8],
+        version: ProtocolVersion,
+        features: &ProtocolFeatures,
+    ) -> Result<Self, RequestDeserializationError> {
         let id = types::read_short_bytes(buf)?.to_vec().into();
-        let parameters = QueryParameters::deserialize(buf)?;
+        let result_metadata_id = if features.uses_result_metadata_id(version) {
+            Some(types::read_short_bytes(buf)?.to_vec().into())
+        } else {
+            None
+        };
+        let parameters = QueryParameters::deserialize(buf, version)?;
 
-        Ok(Self { id, parameters })
+        Ok(Self {
+            id,
+            result_metadata_id,
+            parameters,
+        })
     }
 }
 
 /// An error type returned when serialization of EXECUTE request fails.
 #[non_exhaustive]
 #[derive(Error, Debug, Clone)]
+// Check triggers because all variants end with "Serialization".
+#[expect(clippy::enum_variant_names)]
 pub enum ExecuteSerializationError {
     /// Failed to serialize query parameters.
     #[error("Malformed query parameters: {0}")]
@@ -56,4 +93,8 @@ pub enum ExecuteSerializationError {
     /// Failed to serialize prepared statement id.
     #[error("Malformed statement id: {0}")]
     StatementIdSerialization(TryFromIntError),
+
+    /// Failed to serialize result metadata id.
+    #[error("Malformed result metadata id: {0}")]
+    ResultMetadataIdSerialization(TryFromIntError),
 }
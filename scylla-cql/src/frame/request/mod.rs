@@ -20,13 +20,17 @@ pub use execute::Execute;
 pub use options::Options;
 pub use prepare::Prepare;
 pub use query::Query;
+pub use register::Register;
 pub use startup::Startup;
 
 use self::batch::BatchStatement;
 
-use super::frame_errors::{CqlRequestSerializationError, LowLevelDeserializationError};
+use super::frame_errors::{
+    CqlEventParseError, CqlRequestSerializationError, LowLevelDeserializationError,
+};
+use super::protocol_features::ProtocolFeatures;
 use super::types::SerialConsistency;
-use super::TryFromPrimitiveError;
+use super::{ProtocolVersion, TryFromPrimitiveError};
 
 /// Possible requests sent by the client.
 #[derive(Debug, Copy, Clone)]
@@ -96,11 +100,15 @@ impl TryFrom<u8> for RequestOpcode {
 pub trait SerializableRequest {
     const OPCODE: RequestOpcode;
 
-    fn serialize(&self, buf: &mut Vec<u8>) -> Result<(), CqlRequestSerializationError>;
+    fn serialize(
+        &self,
+        buf: &mut Vec<u8>,
+        version: ProtocolVersion,
+    ) -> Result<(), CqlRequestSerializationError>;
 
-    fn to_bytes(&self) -> Result<Bytes, CqlRequestSerializationError> {
+    fn to_bytes(&self, version: ProtocolVersion) -> Result<Bytes, CqlRequestSerializationError> {
         let mut v = Vec::new();
-        self.serialize(&mut v)?;
+        self.serialize(&mut v, version)?;
         Ok(v.into())
     }
 }
@@ -108,7 +116,10 @@ pub trait SerializableRequest {
 /// Not intended for driver's direct usage (as driver has no interest in deserialising CQL requests),
 /// but very useful for testing (e.g. asserting that the sent requests have proper parameters set).
 pub trait DeserializableRequest: SerializableRequest + Sized {
-    fn deserialize(buf: &mut &[u8]) -> Result<Self, RequestDeserializationError>;
+    fn deserialize(
+        buf: &mut &[u8],
+        version: ProtocolVersion,
+    ) -> Result<Self, RequestDeserializationError>;
 }
 
 /// An error type returned by [`DeserializableRequest::deserialize`].
@@ -122,7 +133,7 @@ pub enum RequestDeserializationError {
     #[error("Io error: {0}")]
     IoError(#[from] std::io::Error),
     #[error("Specified flags are not recognised: {:02x}", flags)]
-    UnknownFlags { flags: u8 },
+    UnknownFlags { flags: u32 },
     #[error("Named values in frame are currently unsupported")]
     NamedValuesUnsupported,
     #[error("Expected SerialConsistency, got regular Consistency: {0}")]
@@ -131,28 +142,60 @@ pub enum RequestDeserializationError {
     BatchTypeParse(#[from] BatchTypeParseError),
     #[error("Unexpected batch statement kind: {0}")]
     UnexpectedBatchStatementKind(u8),
+    #[error("Failed to parse event type: {0}")]
+    EventTypeParse(#[from] CqlEventParseError),
+    #[error("Unsupported protocol version: {0}")]
+    UnsupportedProtocolVersion(u8),
 }
 
-#[non_exhaustive] // TODO: add remaining request types
+#[non_exhaustive]
 pub enum Request<'r> {
+    Startup(Startup<'r>),
+    AuthResponse(AuthResponse),
+    Options(Options),
     Query(Query<'r>),
+    Prepare(Prepare<'r>),
     Execute(Execute<'r>),
     Batch(Batch<'r, BatchStatement<'r>, Vec<SerializedValues>>),
+    Register(Register),
 }
 
-impl Request<'_> {
+impl<'r> Request<'r> {
+    /// Deserializes a request sent over a connection with the given
+    /// protocol version and negotiated protocol features.
     pub fn deserialize(
-        buf: &mut &[u8],
+        buf: &mut &'r [u8],
         opcode: RequestOpcode,
+        version: ProtocolVersion,
+        features: &ProtocolFeatures,
     ) -> Result<Self, RequestDeserializationError> {
         match opcode {
-            RequestOpcode::Query => Query::deserialize(buf).map(Self::Query),
-            RequestOpcode::Execute => Execute::deserialize(buf).map(Self::Execute),
-            RequestOpcode::Batch => Batch::deserialize(buf).map(Self::Batch),
-            _ => unimplemented!(
-                "Deserialization of opcode {:?} is not yet supported",
-                opcode
-            ),
+            RequestOpcode::Startup => Startup::deserialize(buf, version).map(Self::Startup),
+            RequestOpcode::AuthResponse => {
+                AuthResponse::deserialize(buf, version).map(Self::AuthResponse)
+            }
+            RequestOpcode::Options => Options::deserialize(buf, version).map(Self::Options),
+            RequestOpcode::Query => Query::deserialize(buf, version).map(Self::Query),
+            RequestOpcode::Prepare => Prepare::deserialize(buf, version).map(Self::Prepare),
+            RequestOpcode::Execute => {
+                Execute::deserialize(buf, version, features).map(Self::Execute)
+            }
+            RequestOpcode::Batch => Batch::deserialize(buf, version).map(Self::Batch),
+            RequestOpcode::Register => Register::deserialize(buf, version).map(Self::Register),
+        }
+    }
+
+    /// Returns the opcode of the request.
+    pub fn opcode(&self) -> RequestOpcode {
+        match self {
+            Request::Startup(_) => RequestOpcode::Startup,
+            Request::AuthResponse(_) => RequestOpcode::AuthResponse,
+            Request::Options(_) => RequestOpcode::Options,
+            Request::Query(_) => RequestOpcode::Query,
+            Request::Prepare(_) => RequestOpcode::Prepare,
+            Request::Execute(_) => RequestOpcode::Execute,
+            Request::Batch(_) => RequestOpcode::Batch,
+            Request::Register(_) => RequestOpcode::Register,
         }
     }
 
@@ -162,8 +205,11 @@ impl Request<'_> {
             Request::Query(q) => Some(q.parameters.consistency),
             Request::Execute(e) => Some(e.parameters.consistency),
             Request::Batch(b) => Some(b.consistency),
-            #[expect(unreachable_patterns)] // until other opcodes are supported
-            _ => None,
+            Request::Startup(_)
+            | Request::AuthResponse(_)
+            | Request::Options(_)
+            | Request::Prepare(_)
+            | Request::Register(_) => None,
         }
     }
 
@@ -173,37 +219,62 @@ impl Request<'_> {
             Request::Query(q) => Some(q.parameters.serial_consistency),
             Request::Execute(e) => Some(e.parameters.serial_consistency),
             Request::Batch(b) => Some(b.serial_consistency),
-            #[expect(unreachable_patterns)] // until other opcodes are supported
-            _ => None,
+            Request::Startup(_)
+            | Request::AuthResponse(_)
+            | Request::Options(_)
+            | Request::Prepare(_)
+            | Request::Register(_) => None,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{borrow::Cow, ops::Deref};
+    use std::{borrow::Cow, collections::HashMap, ops::Deref};
 
     use bytes::Bytes;
 
     use crate::serialize::row::SerializedValues;
     use crate::{
         frame::{
+            protocol_features::ProtocolFeatures,
             request::{
+                auth_response::AuthResponse,
                 batch::{Batch, BatchStatement, BatchType},
                 execute::Execute,
+                options::Options,
+                prepare::Prepare,
                 query::{Query, QueryParameters},
-                DeserializableRequest, SerializableRequest,
+                register::Register,
+                startup::Startup,
+                DeserializableRequest, Request, RequestOpcode, SerializableRequest,
             },
             response::result::{ColumnType, NativeType},
+            server_event_type::EventType,
             types::{self, SerialConsistency},
+            ProtocolVersion,
         },
         Consistency,
     };
 
     use super::query::PagingState;
+    use crate::value::Unset;
 
     #[test]
     fn request_ser_de_identity() {
+        for version in [
+            ProtocolVersion::V3,
+            ProtocolVersion::V4,
+            ProtocolVersion::V5,
+        ] {
+            check_request_ser_de_identity(version);
+        }
+    }
+
+    fn check_request_ser_de_identity(version: ProtocolVersion) {
+        // Per-request keyspace can only be sent since protocol v5.
+        let keyspace = (version >= ProtocolVersion::V5).then_some(Cow::Borrowed("tenant_ks"));
+
         // Query
         let contents = Cow::Borrowed("SELECT host_id from system.peers");
         let parameters = QueryParameters {
@@ -219,6 +290,7 @@ mod tests {
                     .unwrap();
                 Cow::Owned(vals)
             },
+            keyspace: keyspace.clone(),
         };
         let query = Query {
             contents,
@@ -227,9 +299,9 @@ mod tests {
 
         {
             let mut buf = Vec::new();
-            query.serialize(&mut buf).unwrap();
+            query.serialize(&mut buf, version).unwrap();
 
-            let query_deserialized = Query::deserialize(&mut &buf[..]).unwrap();
+            let query_deserialized = Query::deserialize(&mut &buf[..], version).unwrap();
             assert_eq!(&query_deserialized, &query);
         }
 
@@ -250,13 +322,22 @@ mod tests {
                     .unwrap();
                 Cow::Owned(vals)
             },
+            keyspace: None,
+        };
+        // Without protocol extensions, result metadata id is only sent since protocol v5.
+        let result_metadata_id =
+            (version >= ProtocolVersion::V5).then(|| Bytes::from_static(&[1, 9, 8, 4]));
+        let execute = Execute {
+            id,
+            result_metadata_id,
+            parameters,
         };
-        let execute = Execute { id, parameters };
         {
             let mut buf = Vec::new();
-            execute.serialize(&mut buf).unwrap();
+            execute.serialize(&mut buf, version).unwrap();
 
-            let execute_deserialized = Execute::deserialize(&mut &buf[..]).unwrap();
+            let execute_deserialized =
+                Execute::deserialize(&mut &buf[..], version, &ProtocolFeatures::default()).unwrap();
             assert_eq!(&execute_deserialized, &execute);
         }
 
@@ -281,16 +362,141 @@ mod tests {
                 query.parameters.values.deref().clone(),
                 query.parameters.values.deref().clone(),
             ],
+            keyspace,
         };
         {
             let mut buf = Vec::new();
-            batch.serialize(&mut buf).unwrap();
+            batch.serialize(&mut buf, version).unwrap();
 
-            let batch_deserialized = Batch::deserialize(&mut &buf[..]).unwrap();
+            let batch_deserialized = Batch::deserialize(&mut &buf[..], version).unwrap();
             assert_eq!(&batch_deserialized, &batch);
         }
     }
 
+    fn check_ser_de_identity<R>(request: &R, version: ProtocolVersion)
+    where
+        R: DeserializableRequest + std::fmt::Debug + PartialEq,
+    {
+        let mut buf = Vec::new();
+        request.serialize(&mut buf, version).unwrap();
+        let mut buf_ptr = &buf[..];
+        let deserialized = R::deserialize(&mut buf_ptr, version).unwrap();
+        assert_eq!(&deserialized, request);
+        assert!(buf_ptr.is_empty());
+
+        // The generic entry point should pick the same request type.
+        let request =
+            Request::deserialize(&mut &buf[..], R::OPCODE, version, &Default::default()).unwrap();
+        assert_eq!(request.opcode(), R::OPCODE);
+    }
+
+    #[test]
+    fn control_request_ser_de_identity() {
+        for version in [
+            ProtocolVersion::V3,
+            ProtocolVersion::V4,
+            ProtocolVersion::V5,
+        ] {
+            check_ser_de_identity(
+                &Startup {
+                    options: HashMap::from([(
+                        Cow::Borrowed("CQL_VERSION"),
+                        Cow::Borrowed("4.0.0"),
+                    )]),
+                },
+                version,
+            );
+            check_ser_de_identity(&Options, version);
+            {
+                // PREPARE borrows the statement string from the buffer,
+                // so it is not a `DeserializableRequest`.
+                let prepare = Prepare {
+                    query: "SELECT * FROM tab WHERE a = ?",
+                    keyspace: (version >= ProtocolVersion::V5).then_some(Cow::Borrowed("ks")),
+                };
+                let mut buf = Vec::new();
+                prepare.serialize(&mut buf, version).unwrap();
+                let mut buf_ptr = &buf[..];
+                let deserialized = Prepare::deserialize(&mut buf_ptr, version).unwrap();
+                assert_eq!(deserialized, prepare);
+                assert!(buf_ptr.is_empty());
+                let request = Request::deserialize(
+                    &mut &buf[..],
+                    RequestOpcode::Prepare,
+                    version,
+                    &Default::default(),
+                )
+                .unwrap();
+                assert_eq!(request.opcode(), RequestOpcode::Prepare);
+            }
+            check_ser_de_identity(
+                &Register {
+                    event_types_to_register_for: vec![
+                        EventType::TopologyChange,
+                        EventType::StatusChange,
+                        EventType::SchemaChange,
+                    ],
+                },
+                version,
+            );
+            check_ser_de_identity(
+                &AuthResponse {
+                    response: Some(b"\0cassandra\0cassandra".to_vec()),
+                },
+                version,
+            );
+            check_ser_de_identity(&AuthResponse { response: None }, version);
+        }
+    }
+
+    #[test]
+    fn register_rejects_unknown_event_type() {
+        let mut buf = Vec::new();
+        types::write_string_list(&["SOMETHING_CHANGE".to_owned()], &mut buf).unwrap();
+        Request::deserialize(
+            &mut &buf[..],
+            RequestOpcode::Register,
+            ProtocolVersion::V4,
+            &Default::default(),
+        )
+        .err()
+        .unwrap();
+    }
+
+    #[test]
+    fn execute_result_metadata_id_with_extension() {
+        let features = ProtocolFeatures {
+            scylla_metadata_id_supported: true,
+            ..Default::default()
+        };
+
+        // With the Scylla extension, result metadata id is sent in protocol v4.
+        let execute = Execute {
+            id: Bytes::from_static(&[2, 1, 3, 7]),
+            result_metadata_id: Some(Bytes::from_static(&[1, 9, 8, 4])),
+            parameters: QueryParameters::default(),
+        };
+        let mut buf = Vec::new();
+        execute.serialize(&mut buf, ProtocolVersion::V4).unwrap();
+        let mut buf_ptr = &buf[..];
+        let execute_deserialized =
+            Execute::deserialize(&mut buf_ptr, ProtocolVersion::V4, &features).unwrap();
+        assert_eq!(execute_deserialized, execute);
+        assert!(buf_ptr.is_empty());
+
+        let request = Request::deserialize(
+            &mut &buf[..],
+            RequestOpcode::Execute,
+            ProtocolVersion::V4,
+            &features,
+        )
+        .unwrap();
+        let Request::Execute(execute_deserialized) = request else {
+            panic!("Expected EXECUTE request");
+        };
+        assert_eq!(execute_deserialized, execute);
+    }
+
     #[test]
     fn deser_rejects_unknown_flags() {
         // Query
@@ -303,6 +509,7 @@ mod tests {
             paging_state: PagingState::start(),
             skip_metadata: false,
             values: Cow::Borrowed(SerializedValues::EMPTY),
+            keyspace: None,
         };
         let query = Query {
             contents: contents.clone(),
@@ -311,10 +518,11 @@ mod tests {
 
         {
             let mut buf = Vec::new();
-            query.serialize(&mut buf).unwrap();
+            query.serialize(&mut buf, ProtocolVersion::V4).unwrap();
 
             // Sanity check: query deserializes to the equivalent.
-            let query_deserialized = Query::deserialize(&mut &buf[..]).unwrap();
+            let query_deserialized =
+                Query::deserialize(&mut &buf[..], ProtocolVersion::V4).unwrap();
             assert_eq!(&query_deserialized.contents, &query.contents);
             assert_eq!(&query_deserialized.parameters, &query.parameters);
 
@@ -337,7 +545,7 @@ mod tests {
 
             // Unknown flag should lead to frame rejection, as unknown flags can be new protocol extensions
             // leading to different semantics.
-            let _parse_error = Query::deserialize(&mut &buf[..]).unwrap_err();
+            let _parse_error = Query::deserialize(&mut &buf[..], ProtocolVersion::V4).unwrap_err();
         }
 
         // Batch
@@ -352,13 +560,15 @@ mod tests {
             timestamp: None,
 
             values: vec![query.parameters.values.deref().clone()],
+            keyspace: None,
         };
         {
             let mut buf = Vec::new();
-            batch.serialize(&mut buf).unwrap();
+            batch.serialize(&mut buf, ProtocolVersion::V4).unwrap();
 
             // Sanity check: batch deserializes to the equivalent.
-            let batch_deserialized = Batch::deserialize(&mut &buf[..]).unwrap();
+            let batch_deserialized =
+                Batch::deserialize(&mut &buf[..], ProtocolVersion::V4).unwrap();
             assert_eq!(batch, batch_deserialized);
 
             // Now modify flags by adding an unknown one.
@@ -370,7 +580,110 @@ mod tests {
 
             // Unknown flag should lead to frame rejection, as unknown flags can be new protocol extensions
             // leading to different semantics.
-            let _parse_error = Batch::deserialize(&mut &buf[..]).unwrap_err();
+            let _parse_error = Batch::deserialize(&mut &buf[..], ProtocolVersion::V4).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn keyspace_requires_protocol_v5() {
+        let query = Query {
+            contents: Cow::Borrowed("SELECT * FROM tab"),
+            parameters: QueryParameters {
+                keyspace: Some(Cow::Borrowed("tenant_ks")),
+                ..Default::default()
+            },
+        };
+        let prepare = Prepare {
+            query: "SELECT * FROM tab",
+            keyspace: Some(Cow::Borrowed("tenant_ks")),
+        };
+        let batch = Batch {
+            statements: Cow::Owned(vec![BatchStatement::Query {
+                text: Cow::Borrowed("INSERT INTO tab (a) VALUES (1)"),
+            }]),
+            batch_type: BatchType::Unlogged,
+            consistency: Consistency::One,
+            serial_consistency: None,
+            timestamp: None,
+            values: vec![SerializedValues::new()],
+            keyspace: Some(Cow::Borrowed("tenant_ks")),
+        };
+
+        for version in [ProtocolVersion::V3, ProtocolVersion::V4] {
+            assert!(query.serialize(&mut Vec::new(), version).is_err());
+            assert!(prepare.serialize(&mut Vec::new(), version).is_err());
+            assert!(batch.serialize(&mut Vec::new(), version).is_err());
+        }
+
+        // In v5 the keyspace is written at the end of the request.
+        let mut buf = Vec::new();
+        prepare.serialize(&mut buf, ProtocolVersion::V5).unwrap();
+        let mut expected = Vec::new();
+        types::write_long_string("SELECT * FROM tab", &mut expected).unwrap();
+        types::write_int(0x01, &mut expected);
+        types::write_string("tenant_ks", &mut expected).unwrap();
+        assert_eq!(buf, expected);
+
+        let mut buf = Vec::new();
+        query.serialize(&mut buf, ProtocolVersion::V5).unwrap();
+        assert_eq!(
+            Query::deserialize(&mut &buf[..], ProtocolVersion::V5)
+                .unwrap()
+                .parameters
+                .keyspace
+                .as_deref(),
+            Some("tenant_ks")
+        );
+    }
+
+    #[test]
+    fn unset_values_require_protocol_v4() {
+        let mut values = SerializedValues::new();
+        values
+            .add_value(&Unset, &ColumnType::Native(NativeType::Int))
+            .unwrap();
+
+        let query = Query {
+            contents: Cow::Borrowed("INSERT INTO tab (a) VALUES (?)"),
+            parameters: QueryParameters {
+                values: Cow::Borrowed(&values),
+                ..Default::default()
+            },
+        };
+        let execute = Execute {
+            id: Bytes::from_static(&[2, 3, 4]),
+            parameters: QueryParameters {
+                values: Cow::Borrowed(&values),
+                ..Default::default()
+            },
+            result_metadata_id: None,
+        };
+        let batch = Batch {
+            statements: Cow::Owned(vec![BatchStatement::Prepared {
+                id: Cow::Borrowed(&[2, 3, 4]),
+            }]),
+            batch_type: BatchType::Unlogged,
+            consistency: Consistency::One,
+            serial_consistency: None,
+            timestamp: None,
+            values: vec![values.clone()],
+            keyspace: None,
+        };
+
+        assert!(query
+            .serialize(&mut Vec::new(), ProtocolVersion::V3)
+            .is_err());
+        assert!(execute
+            .serialize(&mut Vec::new(), ProtocolVersion::V3)
+            .is_err());
+        assert!(batch
+            .serialize(&mut Vec::new(), ProtocolVersion::V3)
+            .is_err());
+
+        for version in [ProtocolVersion::V4, ProtocolVersion::V5] {
+            query.serialize(&mut Vec::new(), version).unwrap();
+            execute.serialize(&mut Vec::new(), version).unwrap();
+            batch.serialize(&mut Vec::new(), version).unwrap();
         }
     }
 }
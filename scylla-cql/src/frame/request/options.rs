@@ -1,17 +1,33 @@
-use crate::frame::frame_errors::CqlRequestSerializationError;
+use crate::frame::{frame_errors::CqlRequestSerializationError, ProtocolVersion};
 
-use crate::frame::request::{RequestOpcode, SerializableRequest};
+use crate::frame::request::{
+    DeserializableRequest, RequestDeserializationError, RequestOpcode, SerializableRequest,
+};
 
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
 pub struct Options;
 
 impl SerializableRequest for Options {
     const OPCODE: RequestOpcode = RequestOpcode::Options;
 
-    fn serialize(&self, _buf: &mut Vec<u8>) -> Result<(), CqlRequestSerializationError> {
+    fn serialize(
+        &self,
+        _buf: &mut Vec<u8>,
+        _version: ProtocolVersion,
+    ) -> Result<(), CqlRequestSerializationError> {
         Ok(())
     }
 }
 
+impl DeserializableRequest for Options {
+    fn deserialize(
+        _buf: &mut &[u8],
+        _version: ProtocolVersion,
+    ) -> Result<Self, RequestDeserializationError> {
+        Ok(Self)
+    }
+}
+
 /* Key names for options in SUPPORTED/STARTUP */
 pub const SCYLLA_SHARD_AWARE_PORT: &str = "SCYLLA_SHARD_AWARE_PORT";
 pub const SCYLLA_SHARD_AWARE_PORT_SSL: &str = "SCYLLA_SHARD_AWARE_PORT_SSL";
@@ -1,28 +1,79 @@
-use std::num::TryFromIntError;
+use std::{borrow::Cow, num::TryFromIntError};
 
 use thiserror::Error;
 
-use crate::frame::frame_errors::CqlRequestSerializationError;
+use crate::frame::{frame_errors::CqlRequestSerializationError, ProtocolVersion};
 
 use crate::{
     frame::request::{RequestOpcode, SerializableRequest},
     frame::types,
 };
 
+use super::RequestDeserializationError;
+
+// Prepare flags, since protocol v5.
+const FLAG_WITH_KEYSPACE: u32 = 0x01;
+
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
 pub struct Prepare<'a> {
     pub query: &'a str,
+    /// Keyspace in which the statement is prepared, instead of the keyspace
+    /// set on the connection with `USE`. Supported since protocol v5.
+    pub keyspace: Option<Cow<'a, str>>,
 }
 
 impl SerializableRequest for Prepare<'_> {
     const OPCODE: RequestOpcode = RequestOpcode::Prepare;
 
-    fn serialize(&self, buf: &mut Vec<u8>) -> Result<(), CqlRequestSerializationError> {
+    fn serialize(
+        &self,
+        buf: &mut Vec<u8>,
+        version: ProtocolVersion,
+    ) -> Result<(), CqlRequestSerializationError> {
         types::write_long_string(self.query, buf)
             .map_err(PrepareSerializationError::StatementStringSerialization)?;
+        if version >= ProtocolVersion::V5 {
+            let flags = if self.keyspace.is_some() {
+                FLAG_WITH_KEYSPACE
+            } else {
+                0
+            };
+            types::write_int(flags as i32, buf);
+            if let Some(keyspace) = &self.keyspace {
+                types::write_string(keyspace, buf)
+                    .map_err(PrepareSerializationError::KeyspaceSerialization)?;
+            }
+        } else if self.keyspace.is_some() {
+            return Err(PrepareSerializationError::KeyspaceNotSupported(version).into());
+        }
         Ok(())
     }
 }
 
+impl<'a> Prepare<'a> {
+    /// Deserializes the request, borrowing the statement string from `buf`.
+    pub fn deserialize(
+        buf: &mut &'a [u8],
+        version: ProtocolVersion,
+    ) -> Result<Self, RequestDeserializationError> {
+        let query = types::read_long_string(buf)?;
+        let mut keyspace = None;
+        if version >= ProtocolVersion::V5 {
+            let flags = types::read_int(buf)? as u32;
+            let unknown_flags = flags & !FLAG_WITH_KEYSPACE;
+            if unknown_flags != 0 {
+                return Err(RequestDeserializationError::UnknownFlags {
+                    flags: unknown_flags,
+                });
+            }
+            if flags & FLAG_WITH_KEYSPACE != 0 {
+                keyspace = Some(types::read_string(buf)?.to_owned().into());
+            }
+        }
+        Ok(Self { query, keyspace })
+    }
+}
+
 /// An error type returned when serialization of PREPARE request fails.
 #[non_exhaustive]
 #[derive(Error, Debug, Clone)]
@@ -30,4 +81,13 @@ pub enum PrepareSerializationError {
     /// Failed to serialize the CQL statement string.
     #[error("Failed to serialize statement contents: {0}")]
     StatementStringSerialization(TryFromIntError),
+
+    /// Failed to serialize the keyspace name.
+    #[error("Failed to serialize keyspace name: {0}")]
+    KeyspaceSerialization(TryFromIntError),
+
+    /// A keyspace was set, but the protocol version doesn't allow
+    /// sending it along with the request.
+    #[error("Per-request keyspace is not supported in protocol {0}, it requires protocol v5")]
+    KeyspaceNotSupported(ProtocolVersion),
 }
@@ -1,6 +1,8 @@
 use std::{borrow::Cow, num::TryFromIntError, ops::ControlFlow, sync::Arc};
 
-use crate::frame::{frame_errors::CqlRequestSerializationError, types::SerialConsistency};
+use crate::frame::{
+    frame_errors::CqlRequestSerializationError, types::SerialConsistency, ProtocolVersion,
+};
 use crate::serialize::row::SerializedValues;
 use bytes::{Buf, BufMut};
 use thiserror::Error;
@@ -13,14 +15,17 @@ use crate::{
 use super::{DeserializableRequest, RequestDeserializationError};
 
 // Query flags
-const FLAG_VALUES: u8 = 0x01;
-const FLAG_SKIP_METADATA: u8 = 0x02;
-const FLAG_PAGE_SIZE: u8 = 0x04;
-const FLAG_WITH_PAGING_STATE: u8 = 0x08;
-const FLAG_WITH_SERIAL_CONSISTENCY: u8 = 0x10;
-const FLAG_WITH_DEFAULT_TIMESTAMP: u8 = 0x20;
-const FLAG_WITH_NAMES_FOR_VALUES: u8 = 0x40;
-const ALL_FLAGS: u8 = FLAG_VALUES
+// Flags are sent as [byte] in protocol v4 and as [int] since protocol v5.
+const FLAG_VALUES: u32 = 0x01;
+const FLAG_SKIP_METADATA: u32 = 0x02;
+const FLAG_PAGE_SIZE: u32 = 0x04;
+const FLAG_WITH_PAGING_STATE: u32 = 0x08;
+const FLAG_WITH_SERIAL_CONSISTENCY: u32 = 0x10;
+const FLAG_WITH_DEFAULT_TIMESTAMP: u32 = 0x20;
+const FLAG_WITH_NAMES_FOR_VALUES: u32 = 0x40;
+// Since protocol v5.
+pub(super) const FLAG_WITH_KEYSPACE: u32 = 0x80;
+const ALL_FLAGS: u32 = FLAG_VALUES
     | FLAG_SKIP_METADATA
     | FLAG_PAGE_SIZE
     | FLAG_WITH_PAGING_STATE
@@ -37,20 +42,27 @@ pub struct Query<'q> {
 impl SerializableRequest for Query<'_> {
     const OPCODE: RequestOpcode = RequestOpcode::Query;
 
-    fn serialize(&self, buf: &mut Vec<u8>) -> Result<(), CqlRequestSerializationError> {
+    fn serialize(
+        &self,
+        buf: &mut Vec<u8>,
+        version: ProtocolVersion,
+    ) -> Result<(), CqlRequestSerializationError> {
         types::write_long_string(&self.contents, buf)
             .map_err(QuerySerializationError::StatementStringSerialization)?;
         self.parameters
-            .serialize(buf)
+            .serialize(buf, version)
             .map_err(QuerySerializationError::QueryParametersSerialization)?;
         Ok(())
     }
 }
 
 impl DeserializableRequest for Query<'_> {
-    fn deserialize(buf: &mut &[u8]) -> Result<Self, RequestDeserializationError> {
+    fn deserialize(
+        buf: &mut &[u8],
+        version: ProtocolVersion,
+    ) -> Result<Self, RequestDeserializationError> {
         let contents = Cow::Owned(types::read_long_string(buf)?.to_owned());
-        let parameters = QueryParameters::deserialize(buf)?;
+        let parameters = QueryParameters::deserialize(buf, version)?;
 
         Ok(Self {
             contents,
@@ -68,6 +80,9 @@ pub struct QueryParameters<'a> {
     pub paging_state: PagingState,
     pub skip_metadata: bool,
     pub values: Cow<'a, SerializedValues>,
+    /// Keyspace in which the statement is executed, instead of the keyspace
+    /// set on the connection with `USE`. Supported since protocol v5.
+    pub keyspace: Option<Cow<'a, str>>,
 }
 
 impl Default for QueryParameters<'_> {
@@ -80,6 +95,7 @@ impl Default for QueryParameters<'_> {
             paging_state: PagingState::start(),
             skip_metadata: false,
             values: Cow::Borrowed(SerializedValues::EMPTY),
+            keyspace: None,
         }
     }
 }
@@ -88,6 +104,7 @@ impl QueryParameters<'_> {
     pub fn serialize(
         &self,
         buf: &mut impl BufMut,
+        version: ProtocolVersion,
     ) -> Result<(), QueryParametersSerializationError> {
         types::write_consistency(self.consistency, buf);
 
@@ -95,6 +112,11 @@ impl QueryParameters<'_> {
 
         let mut flags = 0;
         if !self.values.is_empty() {
+            if version < ProtocolVersion::V4 && self.values.contains_unset() {
+                return Err(QueryParametersSerializationError::UnsetValuesNotSupported(
+                    version,
+                ));
+            }
             flags |= FLAG_VALUES;
         }
 
@@ -118,7 +140,16 @@ impl QueryParameters<'_> {
             flags |= FLAG_WITH_DEFAULT_TIMESTAMP;
         }
 
-        buf.put_u8(flags);
+        if self.keyspace.is_some() {
+            if version < ProtocolVersion::V5 {
+                return Err(QueryParametersSerializationError::KeyspaceNotSupported(
+                    version,
+                ));
+            }
+            flags |= FLAG_WITH_KEYSPACE;
+        }
+
+        write_flags(flags, version, buf);
 
         if !self.values.is_empty() {
             self.values.write_to_request(buf);
@@ -140,16 +171,29 @@ impl QueryParameters<'_> {
             types::write_long(timestamp, buf);
         }
 
+        if let Some(keyspace) = &self.keyspace {
+            types::write_string(keyspace, buf)
+                .map_err(QueryParametersSerializationError::BadKeyspaceName)?;
+        }
+
         Ok(())
     }
 }
 
 impl QueryParameters<'_> {
-    pub fn deserialize(buf: &mut &[u8]) -> Result<Self, RequestDeserializationError> {
+    pub fn deserialize(
+        buf: &mut &[u8],
+        version: ProtocolVersion,
+    ) -> Result<Self, RequestDeserializationError> {
         let consistency = types::read_consistency(buf)?;
 
-        let flags = buf.get_u8();
-        let unknown_flags = flags & (!ALL_FLAGS);
+        let flags = read_flags(buf, version)?;
+        let known_flags = if version >= ProtocolVersion::V5 {
+            ALL_FLAGS | FLAG_WITH_KEYSPACE
+        } else {
+            ALL_FLAGS
+        };
+        let unknown_flags = flags & (!known_flags);
         if unknown_flags != 0 {
             return Err(RequestDeserializationError::UnknownFlags {
                 flags: unknown_flags,
@@ -162,6 +206,7 @@ impl QueryParameters<'_> {
         let serial_consistency_flag = (flags & FLAG_WITH_SERIAL_CONSISTENCY) != 0;
         let default_timestamp_flag = (flags & FLAG_WITH_DEFAULT_TIMESTAMP) != 0;
         let values_have_names_flag = (flags & FLAG_WITH_NAMES_FOR_VALUES) != 0;
+        let keyspace_flag = (flags & FLAG_WITH_KEYSPACE) != 0;
 
         if values_have_names_flag {
             return Err(RequestDeserializationError::NamedValuesUnsupported);
@@ -196,6 +241,9 @@ impl QueryParameters<'_> {
         } else {
             None
         };
+        let keyspace = keyspace_flag
+            .then(|| types::read_string(buf).map(|keyspace| Cow::Owned(keyspace.to_owned())))
+            .transpose()?;
 
         Ok(Self {
             consistency,
@@ -205,10 +253,32 @@ impl QueryParameters<'_> {
             paging_state,
             skip_metadata,
             values,
+            keyspace,
         })
     }
 }
 
+/// Writes request flags, which are a [byte] before protocol v5 and an [int] since then.
+pub(super) fn write_flags(flags: u32, version: ProtocolVersion, buf: &mut impl BufMut) {
+    if version >= ProtocolVersion::V5 {
+        buf.put_u32(flags);
+    } else {
+        buf.put_u8(flags as u8);
+    }
+}
+
+/// Reads request flags, which are a [byte] before protocol v5 and an [int] since then.
+pub(super) fn read_flags(
+    buf: &mut &[u8],
+    version: ProtocolVersion,
+) -> Result<u32, RequestDeserializationError> {
+    if version >= ProtocolVersion::V5 {
+        Ok(types::read_int(buf)? as u32)
+    } else {
+        Ok(buf.get_u8() as u32)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum PagingStateResponse {
     HasMorePages { state: PagingState },
@@ -304,4 +374,18 @@ pub enum QueryParametersSerializationError {
     /// Failed to serialize paging state.
     #[error("Malformed paging state: {0}")]
     BadPagingState(#[from] TryFromIntError),
+
+    /// Failed to serialize the keyspace name.
+    #[error("Malformed keyspace name: {0}")]
+    BadKeyspaceName(TryFromIntError),
+
+    /// A keyspace was set, but the protocol version doesn't allow
+    /// sending it along with the request.
+    #[error("Per-request keyspace is not supported in protocol {0}, it requires protocol v5")]
+    KeyspaceNotSupported(ProtocolVersion),
+
+    /// Some of the bound values are unset, but the protocol version
+    /// doesn't allow sending unset values.
+    #[error("Unset values are not supported in protocol {0}, they require protocol v4")]
+    UnsetValuesNotSupported(ProtocolVersion),
 }
@@ -4,11 +4,14 @@ use thiserror::Error;
 
 use crate::frame::{
     frame_errors::CqlRequestSerializationError,
-    request::{RequestOpcode, SerializableRequest},
+    request::{
+        DeserializableRequest, RequestDeserializationError, RequestOpcode, SerializableRequest,
+    },
     server_event_type::EventType,
-    types,
+    types, ProtocolVersion,
 };
 
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
 pub struct Register {
     pub event_types_to_register_for: Vec<EventType>,
 }
@@ -16,7 +19,11 @@ pub struct Register {
 impl SerializableRequest for Register {
     const OPCODE: RequestOpcode = RequestOpcode::Register;
 
-    fn serialize(&self, buf: &mut Vec<u8>) -> Result<(), CqlRequestSerializationError> {
+    fn serialize(
+        &self,
+        buf: &mut Vec<u8>,
+        _version: ProtocolVersion,
+    ) -> Result<(), CqlRequestSerializationError> {
         let event_types_list = self
             .event_types_to_register_for
             .iter()
@@ -29,6 +36,21 @@ impl SerializableRequest for Register {
     }
 }
 
+impl DeserializableRequest for Register {
+    fn deserialize(
+        buf: &mut &[u8],
+        _version: ProtocolVersion,
+    ) -> Result<Self, RequestDeserializationError> {
+        let event_types_to_register_for = types::read_string_list(buf)?
+            .iter()
+            .map(|event| event.parse())
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            event_types_to_register_for,
+        })
+    }
+}
+
 /// An error type returned when serialization of REGISTER request fails.
 #[non_exhaustive]
 #[derive(Error, Debug, Clone)]
@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use crate::frame::frame_errors::CqlRequestSerializationError;
+use crate::frame::{frame_errors::CqlRequestSerializationError, ProtocolVersion};
 
 use std::{borrow::Cow, collections::HashMap, num::TryFromIntError};
 
@@ -11,6 +11,7 @@ use crate::{
 
 use super::DeserializableRequest;
 
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
 pub struct Startup<'a> {
     pub options: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
@@ -18,7 +19,11 @@ pub struct Startup<'a> {
 impl SerializableRequest for Startup<'_> {
     const OPCODE: RequestOpcode = RequestOpcode::Startup;
 
-    fn serialize(&self, buf: &mut Vec<u8>) -> Result<(), CqlRequestSerializationError> {
+    fn serialize(
+        &self,
+        buf: &mut Vec<u8>,
+        _version: ProtocolVersion,
+    ) -> Result<(), CqlRequestSerializationError> {
         types::write_string_map(&self.options, buf)
             .map_err(StartupSerializationError::OptionsSerialization)?;
         Ok(())
@@ -35,7 +40,10 @@ pub enum StartupSerializationError {
 }
 
 impl DeserializableRequest for Startup<'_> {
-    fn deserialize(buf: &mut &[u8]) -> Result<Self, super::RequestDeserializationError> {
+    fn deserialize(
+        buf: &mut &[u8],
+        _version: ProtocolVersion,
+    ) -> Result<Self, super::RequestDeserializationError> {
         // Note: this is inefficient, but it's only used for tests and it's not common
         // to deserialize STARTUP frames anyway.
         let options = types::read_string_map(buf)?
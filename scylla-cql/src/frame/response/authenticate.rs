@@ -2,6 +2,8 @@ use crate::frame::frame_errors::{
     CqlAuthChallengeParseError, CqlAuthSuccessParseError, CqlAuthenticateParseError,
 };
 use crate::frame::types;
+use bytes::BufMut;
+use std::num::TryFromIntError;
 
 // Implements Authenticate message.
 #[derive(Debug)]
@@ -17,6 +19,10 @@ impl Authenticate {
 
         Ok(Authenticate { authenticator_name })
     }
+
+    pub fn serialize(&self, buf: &mut impl BufMut) -> Result<(), TryFromIntError> {
+        types::write_string(&self.authenticator_name, buf)
+    }
 }
 
 #[derive(Debug)]
@@ -32,6 +38,10 @@ impl AuthSuccess {
 
         Ok(AuthSuccess { success_message })
     }
+
+    pub fn serialize(&self, buf: &mut impl BufMut) -> Result<(), TryFromIntError> {
+        types::write_bytes_opt(self.success_message.as_ref(), buf)
+    }
 }
 
 #[derive(Debug)]
@@ -49,4 +59,8 @@ impl AuthChallenge {
             authenticate_message,
         })
     }
+
+    pub fn serialize(&self, buf: &mut impl BufMut) -> Result<(), TryFromIntError> {
+        types::write_bytes_opt(self.authenticate_message.as_ref(), buf)
+    }
 }
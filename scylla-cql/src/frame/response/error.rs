@@ -1,9 +1,11 @@
 use crate::frame::frame_errors::{CqlErrorParseError, LowLevelDeserializationError};
 use crate::frame::protocol_features::ProtocolFeatures;
-use crate::frame::types;
+use crate::frame::{types, ProtocolVersion};
 use crate::Consistency;
 use byteorder::ReadBytesExt;
-use bytes::Bytes;
+use bytes::{BufMut, Bytes};
+use std::net::{IpAddr, Ipv4Addr};
+use std::num::TryFromIntError;
 use thiserror::Error;
 
 #[derive(Debug, Clone)]
@@ -24,9 +26,50 @@ fn make_error_field_err(
     }
 }
 
+/// Reads the number of replicas that failed to execute a request.
+///
+/// Before protocol v5 it is sent directly as [int]. Since v5, the server sends
+/// a map from the failed replicas' addresses to failure codes instead,
+/// and the number of failures is the size of that map.
+fn read_num_failures(
+    buf: &mut &[u8],
+    version: ProtocolVersion,
+    db_error: &'static str,
+) -> Result<i32, CqlErrorParseError> {
+    if version < ProtocolVersion::V5 {
+        return types::read_int(buf)
+            .map_err(|err| make_error_field_err(db_error, "NUM_FAILURES", err));
+    }
+
+    let num_failures =
+        types::read_int(buf).map_err(|err| make_error_field_err(db_error, "REASON_MAP", err))?;
+    for _ in 0..num_failures {
+        types::read_inetaddr(buf)
+            .map_err(|err| make_error_field_err(db_error, "REASON_MAP", err))?;
+        types::read_short(buf).map_err(|err| make_error_field_err(db_error, "REASON_MAP", err))?;
+    }
+    Ok(num_failures)
+}
+
+/// Writes the number of replicas that failed to execute a request.
+///
+/// Since protocol v5 a reason map is expected instead. [DbError] only keeps
+/// the number of failures, so the map is filled with placeholder entries
+/// (unspecified address, `UNKNOWN` failure code) to preserve the count.
+fn write_num_failures(num_failures: i32, buf: &mut impl BufMut, version: ProtocolVersion) {
+    types::write_int(num_failures, buf);
+    if version >= ProtocolVersion::V5 {
+        for _ in 0..num_failures {
+            types::write_inetaddr(IpAddr::V4(Ipv4Addr::UNSPECIFIED), buf);
+            types::write_short(0x0000, buf);
+        }
+    }
+}
+
 impl Error {
     pub fn deserialize(
         features: &ProtocolFeatures,
+        version: ProtocolVersion,
         buf: &mut &[u8],
     ) -> Result<Self, CqlErrorParseError> {
         let code = types::read_int(buf)
@@ -81,8 +124,7 @@ impl Error {
                     .map_err(|err| make_error_field_err("READ_FAILURE", "RECEIVED", err))?,
                 required: types::read_int(buf)
                     .map_err(|err| make_error_field_err("READ_FAILURE", "REQUIRED", err))?,
-                numfailures: types::read_int(buf)
-                    .map_err(|err| make_error_field_err("READ_FAILURE", "NUM_FAILURES", err))?,
+                numfailures: read_num_failures(buf, version, "READ_FAILURE")?,
                 data_present: buf
                     .read_u8()
                     .map_err(|err| make_error_field_err("READ_FAILURE", "DATA_PRESENT", err))?
@@ -105,8 +147,7 @@ impl Error {
                     .map_err(|err| make_error_field_err("WRITE_FAILURE", "RECEIVED", err))?,
                 required: types::read_int(buf)
                     .map_err(|err| make_error_field_err("WRITE_FAILURE", "REQUIRED", err))?,
-                numfailures: types::read_int(buf)
-                    .map_err(|err| make_error_field_err("WRITE_FAILURE", "NUM_FAILURES", err))?,
+                numfailures: read_num_failures(buf, version, "WRITE_FAILURE")?,
                 write_type: WriteType::from(
                     types::read_string(buf)
                         .map_err(|err| make_error_field_err("WRITE_FAILURE", "WRITE_TYPE", err))?,
@@ -146,6 +187,149 @@ impl Error {
 
         Ok(Error { error, reason })
     }
+
+    /// Serializes the body of an ERROR response.
+    ///
+    /// `features` are needed to determine the code of the Scylla-specific
+    /// rate limit error, which is negotiated per connection.
+    pub fn serialize(
+        &self,
+        features: &ProtocolFeatures,
+        version: ProtocolVersion,
+        buf: &mut impl BufMut,
+    ) -> Result<(), ErrorSerializationError> {
+        let code = match self.error {
+            DbError::RateLimitReached { .. } => features
+                .rate_limit_error
+                .ok_or(ErrorSerializationError::RateLimitErrorNotNegotiated)?,
+            ref error => error.code(features),
+        };
+        types::write_int(code, buf);
+        types::write_string(&self.reason, buf)
+            .map_err(ErrorSerializationError::ReasonSerialization)?;
+
+        match &self.error {
+            DbError::Unavailable {
+                consistency,
+                required,
+                alive,
+            } => {
+                types::write_consistency(*consistency, buf);
+                types::write_int(*required, buf);
+                types::write_int(*alive, buf);
+            }
+            DbError::WriteTimeout {
+                consistency,
+                received,
+                required,
+                write_type,
+            } => {
+                types::write_consistency(*consistency, buf);
+                types::write_int(*received, buf);
+                types::write_int(*required, buf);
+                types::write_string(write_type.as_str(), buf)
+                    .map_err(ErrorSerializationError::FieldSerialization)?;
+            }
+            DbError::ReadTimeout {
+                consistency,
+                received,
+                required,
+                data_present,
+            } => {
+                types::write_consistency(*consistency, buf);
+                types::write_int(*received, buf);
+                types::write_int(*required, buf);
+                buf.put_u8(u8::from(*data_present));
+            }
+            DbError::ReadFailure {
+                consistency,
+                received,
+                required,
+                numfailures,
+                data_present,
+            } => {
+                types::write_consistency(*consistency, buf);
+                types::write_int(*received, buf);
+                types::write_int(*required, buf);
+                write_num_failures(*numfailures, buf, version);
+                buf.put_u8(u8::from(*data_present));
+            }
+            DbError::FunctionFailure {
+                keyspace,
+                function,
+                arg_types,
+            } => {
+                types::write_string(keyspace, buf)
+                    .map_err(ErrorSerializationError::FieldSerialization)?;
+                types::write_string(function, buf)
+                    .map_err(ErrorSerializationError::FieldSerialization)?;
+                types::write_string_list(arg_types, buf)
+                    .map_err(ErrorSerializationError::FieldSerialization)?;
+            }
+            DbError::WriteFailure {
+                consistency,
+                received,
+                required,
+                numfailures,
+                write_type,
+            } => {
+                types::write_consistency(*consistency, buf);
+                types::write_int(*received, buf);
+                types::write_int(*required, buf);
+                write_num_failures(*numfailures, buf, version);
+                types::write_string(write_type.as_str(), buf)
+                    .map_err(ErrorSerializationError::FieldSerialization)?;
+            }
+            DbError::AlreadyExists { keyspace, table } => {
+                types::write_string(keyspace, buf)
+                    .map_err(ErrorSerializationError::FieldSerialization)?;
+                types::write_string(table, buf)
+                    .map_err(ErrorSerializationError::FieldSerialization)?;
+            }
+            DbError::Unprepared { statement_id } => {
+                types::write_short_bytes(statement_id, buf)
+                    .map_err(ErrorSerializationError::FieldSerialization)?;
+            }
+            DbError::RateLimitReached {
+                op_type,
+                rejected_by_coordinator,
+            } => {
+                buf.put_u8(op_type.as_u8());
+                buf.put_u8(u8::from(*rejected_by_coordinator));
+            }
+            DbError::SyntaxError
+            | DbError::Invalid
+            | DbError::AuthenticationError
+            | DbError::Unauthorized
+            | DbError::ConfigError
+            | DbError::Overloaded
+            | DbError::IsBootstrapping
+            | DbError::TruncateError
+            | DbError::ServerError
+            | DbError::ProtocolError
+            | DbError::Other(_) => (),
+        }
+
+        Ok(())
+    }
+}
+
+/// An error type returned when serialization of ERROR response fails.
+#[non_exhaustive]
+#[derive(Error, Debug, Clone)]
+pub enum ErrorSerializationError {
+    /// Failed to serialize the error message.
+    #[error("Failed to serialize error message: {0}")]
+    ReasonSerialization(TryFromIntError),
+
+    /// Failed to serialize one of the fields specific to the error kind.
+    #[error("Failed to serialize error-specific field: {0}")]
+    FieldSerialization(TryFromIntError),
+
+    /// The rate limit error is a protocol extension, so its code
+    /// is only known if the extension was negotiated.
+    #[error("Rate limit error code was not negotiated for the connection")]
+    RateLimitErrorNotNegotiated,
 }
 
 /// An error sent from the database in response to a query
@@ -468,6 +652,17 @@ impl std::fmt::Display for WriteType {
     }
 }
 
+impl OperationType {
+    /// Returns the operation type as it is written in the RATE_LIMIT_ERROR response.
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            OperationType::Read => 0,
+            OperationType::Write => 1,
+            OperationType::Other(other) => *other,
+        }
+    }
+}
+
 impl From<u8> for OperationType {
     fn from(operation_type: u8) -> OperationType {
         match operation_type {
@@ -514,6 +709,7 @@ impl WriteType {
 mod tests {
     use super::{DbError, Error, OperationType, WriteType};
     use crate::frame::protocol_features::ProtocolFeatures;
+    use crate::frame::ProtocolVersion;
     use crate::Consistency;
     use bytes::Bytes;
     use std::convert::TryInto;
@@ -552,7 +748,8 @@ mod tests {
 
         for (error_code, expected_error) in &simple_error_mappings {
             let bytes: Vec<u8> = make_error_request_bytes(*error_code, "simple message");
-            let error: Error = Error::deserialize(&features, &mut bytes.as_slice()).unwrap();
+            let error: Error =
+                Error::deserialize(&features, ProtocolVersion::V4, &mut bytes.as_slice()).unwrap();
             assert_eq!(error.error, *expected_error);
             assert_eq!(error.reason, "simple message");
         }
@@ -567,7 +764,8 @@ mod tests {
         bytes.extend(2_i32.to_be_bytes());
         bytes.extend(3_i32.to_be_bytes());
 
-        let error: Error = Error::deserialize(&features, &mut bytes.as_slice()).unwrap();
+        let error: Error =
+            Error::deserialize(&features, ProtocolVersion::V4, &mut bytes.as_slice()).unwrap();
 
         assert_eq!(
             error.error,
@@ -594,7 +792,8 @@ mod tests {
         bytes.extend(write_type_str_len.to_be_bytes());
         bytes.extend(write_type_str.as_bytes());
 
-        let error: Error = Error::deserialize(&features, &mut bytes.as_slice()).unwrap();
+        let error: Error =
+            Error::deserialize(&features, ProtocolVersion::V4, &mut bytes.as_slice()).unwrap();
 
         assert_eq!(
             error.error,
@@ -618,7 +817,8 @@ mod tests {
         bytes.extend(32_i32.to_be_bytes());
         bytes.push(0_u8);
 
-        let error: Error = Error::deserialize(&features, &mut bytes.as_slice()).unwrap();
+        let error: Error =
+            Error::deserialize(&features, ProtocolVersion::V4, &mut bytes.as_slice()).unwrap();
 
         assert_eq!(
             error.error,
@@ -643,7 +843,8 @@ mod tests {
         bytes.extend(6_i32.to_be_bytes());
         bytes.push(123_u8); // Any non-zero value means data_present is true
 
-        let error: Error = Error::deserialize(&features, &mut bytes.as_slice()).unwrap();
+        let error: Error =
+            Error::deserialize(&features, ProtocolVersion::V4, &mut bytes.as_slice()).unwrap();
 
         assert_eq!(
             error.error,
@@ -658,6 +859,39 @@ mod tests {
         assert_eq!(error.reason, "message 2");
     }
 
+    #[test]
+    fn deserialize_read_failure_v5() {
+        let features = ProtocolFeatures::default();
+
+        let mut bytes = make_error_request_bytes(0x1300, "message 2");
+        bytes.extend(0x0003_i16.to_be_bytes());
+        bytes.extend(4_i32.to_be_bytes());
+        bytes.extend(5_i32.to_be_bytes());
+        // Reason map with two entries: an IPv4 and an IPv6 replica.
+        bytes.extend(2_i32.to_be_bytes());
+        bytes.push(4);
+        bytes.extend([127, 0, 0, 1]);
+        bytes.extend(0x0001_i16.to_be_bytes());
+        bytes.push(16);
+        bytes.extend([0; 16]);
+        bytes.extend(0x0002_i16.to_be_bytes());
+        bytes.push(0);
+
+        let error: Error =
+            Error::deserialize(&features, ProtocolVersion::V5, &mut bytes.as_slice()).unwrap();
+
+        assert_eq!(
+            error.error,
+            DbError::ReadFailure {
+                consistency: Consistency::Three,
+                received: 4,
+                required: 5,
+                numfailures: 2,
+                data_present: false,
+            }
+        );
+    }
+
     #[test]
     fn deserialize_function_failure() {
         let features = ProtocolFeatures::default();
@@ -686,7 +920,8 @@ mod tests {
         bytes.extend(type2_len.to_be_bytes());
         bytes.extend(type2.as_bytes());
 
-        let error: Error = Error::deserialize(&features, &mut bytes.as_slice()).unwrap();
+        let error: Error =
+            Error::deserialize(&features, ProtocolVersion::V4, &mut bytes.as_slice()).unwrap();
 
         assert_eq!(
             error.error,
@@ -715,7 +950,8 @@ mod tests {
         bytes.extend(write_type_str_len.to_be_bytes());
         bytes.extend(write_type_str.as_bytes());
 
-        let error: Error = Error::deserialize(&features, &mut bytes.as_slice()).unwrap();
+        let error: Error =
+            Error::deserialize(&features, ProtocolVersion::V4, &mut bytes.as_slice()).unwrap();
 
         assert_eq!(
             error.error,
@@ -747,7 +983,8 @@ mod tests {
         bytes.extend(table_name_len.to_be_bytes());
         bytes.extend(table_name.as_bytes());
 
-        let error: Error = Error::deserialize(&features, &mut bytes.as_slice()).unwrap();
+        let error: Error =
+            Error::deserialize(&features, ProtocolVersion::V4, &mut bytes.as_slice()).unwrap();
 
         assert_eq!(
             error.error,
@@ -768,7 +1005,8 @@ mod tests {
         bytes.extend((statement_id.len() as i16).to_be_bytes());
         bytes.extend(statement_id);
 
-        let error: Error = Error::deserialize(&features, &mut bytes.as_slice()).unwrap();
+        let error: Error =
+            Error::deserialize(&features, ProtocolVersion::V4, &mut bytes.as_slice()).unwrap();
 
         assert_eq!(
             error.error,
@@ -788,7 +1026,8 @@ mod tests {
         let mut bytes = make_error_request_bytes(0x4321, "message 1");
         bytes.extend([0u8]); // Read type
         bytes.extend([1u8]); // Rejected by coordinator
-        let error = Error::deserialize(&features, &mut bytes.as_slice()).unwrap();
+        let error =
+            Error::deserialize(&features, ProtocolVersion::V4, &mut bytes.as_slice()).unwrap();
 
         assert_eq!(
             error.error,
@@ -806,7 +1045,8 @@ mod tests {
         let mut bytes = make_error_request_bytes(0x8765, "message 2");
         bytes.extend([1u8]); // Write type
         bytes.extend([0u8]); // Not rejected by coordinator
-        let error = Error::deserialize(&features, &mut bytes.as_slice()).unwrap();
+        let error =
+            Error::deserialize(&features, ProtocolVersion::V4, &mut bytes.as_slice()).unwrap();
 
         assert_eq!(
             error.error,
@@ -3,7 +3,9 @@ use crate::frame::frame_errors::{
 };
 use crate::frame::server_event_type::EventType;
 use crate::frame::types;
+use bytes::BufMut;
 use std::net::SocketAddr;
+use std::num::TryFromIntError;
 
 #[derive(Debug)]
 // Check triggers because all variants end with "Change".
@@ -85,6 +87,35 @@ impl Event {
             EventType::SchemaChange => Ok(Self::SchemaChange(SchemaChangeEvent::deserialize(buf)?)),
         }
     }
+
+    pub fn serialize(&self, buf: &mut impl BufMut) -> Result<(), TryFromIntError> {
+        let event_type = match self {
+            Self::TopologyChange(_) => EventType::TopologyChange,
+            Self::StatusChange(_) => EventType::StatusChange,
+            Self::SchemaChange(_) => EventType::SchemaChange,
+        };
+        types::write_string(&event_type.to_string(), buf)?;
+        match self {
+            Self::TopologyChange(event) => event.serialize(buf),
+            Self::StatusChange(event) => event.serialize(buf),
+            Self::SchemaChange(event) => event.serialize(buf),
+        }
+    }
+}
+
+impl SchemaChangeType {
+    /// Returns the change type as it is written in the protocol.
+    ///
+    /// [SchemaChangeType::Invalid] is written as `INVALID`, which is not
+    /// recognised by the protocol and deserializes back to `Invalid`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SchemaChangeType::Created => "CREATED",
+            SchemaChangeType::Updated => "UPDATED",
+            SchemaChangeType::Dropped => "DROPPED",
+            SchemaChangeType::Invalid => "INVALID",
+        }
+    }
 }
 
 impl SchemaChangeEvent {
@@ -185,6 +216,64 @@ impl SchemaChangeEvent {
             )),
         }
     }
+
+    pub fn serialize(&self, buf: &mut impl BufMut) -> Result<(), TryFromIntError> {
+        match self {
+            Self::KeyspaceChange {
+                change_type,
+                keyspace_name,
+            } => {
+                types::write_string(change_type.as_str(), buf)?;
+                types::write_string("KEYSPACE", buf)?;
+                types::write_string(keyspace_name, buf)?;
+            }
+            Self::TableChange {
+                change_type,
+                keyspace_name,
+                object_name,
+            } => {
+                types::write_string(change_type.as_str(), buf)?;
+                types::write_string("TABLE", buf)?;
+                types::write_string(keyspace_name, buf)?;
+                types::write_string(object_name, buf)?;
+            }
+            Self::TypeChange {
+                change_type,
+                keyspace_name,
+                type_name,
+            } => {
+                types::write_string(change_type.as_str(), buf)?;
+                types::write_string("TYPE", buf)?;
+                types::write_string(keyspace_name, buf)?;
+                types::write_string(type_name, buf)?;
+            }
+            Self::FunctionChange {
+                change_type,
+                keyspace_name,
+                function_name,
+                arguments,
+            } => {
+                types::write_string(change_type.as_str(), buf)?;
+                types::write_string("FUNCTION", buf)?;
+                types::write_string(keyspace_name, buf)?;
+                types::write_string(function_name, buf)?;
+                types::write_string_list(arguments, buf)?;
+            }
+            Self::AggregateChange {
+                change_type,
+                keyspace_name,
+                aggregate_name,
+                arguments,
+            } => {
+                types::write_string(change_type.as_str(), buf)?;
+                types::write_string("AGGREGATE", buf)?;
+                types::write_string(keyspace_name, buf)?;
+                types::write_string(aggregate_name, buf)?;
+                types::write_string_list(arguments, buf)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl TopologyChangeEvent {
@@ -202,6 +291,16 @@ impl TopologyChangeEvent {
             )),
         }
     }
+
+    pub fn serialize(&self, buf: &mut impl BufMut) -> Result<(), TryFromIntError> {
+        let (type_of_change, addr) = match self {
+            Self::NewNode(addr) => ("NEW_NODE", addr),
+            Self::RemovedNode(addr) => ("REMOVED_NODE", addr),
+        };
+        types::write_string(type_of_change, buf)?;
+        types::write_inet(*addr, buf);
+        Ok(())
+    }
 }
 
 impl StatusChangeEvent {
@@ -219,4 +318,14 @@ impl StatusChangeEvent {
             )),
         }
     }
+
+    pub fn serialize(&self, buf: &mut impl BufMut) -> Result<(), TryFromIntError> {
+        let (type_of_change, addr) = match self {
+            Self::Up(addr) => ("UP", addr),
+            Self::Down(addr) => ("DOWN", addr),
+        };
+        types::write_string(type_of_change, buf)?;
+        types::write_inet(*addr, buf);
+        Ok(())
+    }
 }
@@ -12,9 +12,9 @@ pub use supported::Supported;
 
 use crate::frame::protocol_features::ProtocolFeatures;
 use crate::frame::response::result::ResultMetadata;
-use crate::frame::TryFromPrimitiveError;
+use crate::frame::{ProtocolVersion, TryFromPrimitiveError};
 
-use super::frame_errors::CqlResponseParseError;
+use super::frame_errors::{CqlResponseParseError, CqlResponseSerializationError};
 
 /// Possible CQL responses received from the server
 #[derive(Debug, Copy, Clone)]
@@ -109,21 +109,25 @@ impl Response {
 
     pub fn deserialize(
         features: &ProtocolFeatures,
+        version: ProtocolVersion,
         opcode: ResponseOpcode,
         buf_bytes: bytes::Bytes,
         cached_metadata: Option<&Arc<ResultMetadata<'static>>>,
     ) -> Result<Response, CqlResponseParseError> {
         let buf = &mut &*buf_bytes;
         let response = match opcode {
-            ResponseOpcode::Error => Response::Error(Error::deserialize(features, buf)?),
+            ResponseOpcode::Error => Response::Error(Error::deserialize(features, version, buf)?),
             ResponseOpcode::Ready => Response::Ready,
             ResponseOpcode::Authenticate => {
                 Response::Authenticate(authenticate::Authenticate::deserialize(buf)?)
             }
             ResponseOpcode::Supported => Response::Supported(Supported::deserialize(buf)?),
-            ResponseOpcode::Result => {
-                Response::Result(result::deserialize(buf_bytes, cached_metadata)?)
-            }
+            ResponseOpcode::Result => Response::Result(result::deserialize(
+                buf_bytes,
+                features,
+                version,
+                cached_metadata,
+            )?),
             ResponseOpcode::Event => Response::Event(event::Event::deserialize(buf)?),
             ResponseOpcode::AuthChallenge => {
                 Response::AuthChallenge(authenticate::AuthChallenge::deserialize(buf)?)
@@ -136,6 +140,55 @@ impl Response {
         Ok(response)
     }
 
+    /// Returns the opcode of the response.
+    pub fn opcode(&self) -> ResponseOpcode {
+        match self {
+            Response::Error(_) => ResponseOpcode::Error,
+            Response::Ready => ResponseOpcode::Ready,
+            Response::Result(_) => ResponseOpcode::Result,
+            Response::Authenticate(_) => ResponseOpcode::Authenticate,
+            Response::AuthSuccess(_) => ResponseOpcode::AuthSuccess,
+            Response::AuthChallenge(_) => ResponseOpcode::AuthChallenge,
+            Response::Supported(_) => ResponseOpcode::Supported,
+            Response::Event(_) => ResponseOpcode::Event,
+        }
+    }
+
+    /// Serializes the response body, so that it can be sent to a client,
+    /// e.g. by a mock server or a proxy.
+    ///
+    /// This is the inverse of [Response::deserialize]. The frame header
+    /// is not written; [Response::opcode] can be used to fill it in.
+    pub fn serialize(
+        &self,
+        features: &ProtocolFeatures,
+        version: ProtocolVersion,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), CqlResponseSerializationError> {
+        match self {
+            Response::Error(error) => error.serialize(features, version, buf)?,
+            Response::Ready => (),
+            Response::Result(result) => result.serialize(features, version, buf)?,
+            Response::Authenticate(authenticate) => authenticate
+                .serialize(buf)
+                .map_err(CqlResponseSerializationError::AuthenticateSerialization)?,
+            Response::AuthSuccess(auth_success) => auth_success
+                .serialize(buf)
+                .map_err(CqlResponseSerializationError::AuthSuccessSerialization)?,
+            Response::AuthChallenge(auth_challenge) => auth_challenge
+                .serialize(buf)
+                .map_err(CqlResponseSerializationError::AuthChallengeSerialization)?,
+            Response::Supported(supported) => supported
+                .serialize(buf)
+                .map_err(CqlResponseSerializationError::SupportedSerialization)?,
+            Response::Event(event) => event
+                .serialize(buf)
+                .map_err(CqlResponseSerializationError::EventSerialization)?,
+        }
+
+        Ok(())
+    }
+
     pub fn into_non_error_response(self) -> Result<NonErrorResponse, error::Error> {
         let non_error_response = match self {
             Response::Error(e) => return Err(e),
@@ -177,3 +230,369 @@ impl NonErrorResponse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::collections::HashMap;
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+
+    use bytes::Bytes;
+
+    use super::event::{Event, SchemaChangeEvent, SchemaChangeType, StatusChangeEvent};
+    use super::result::{
+        CollectionType, ColumnSpec, ColumnType, NativeType, PartitionKeyIndex, Prepared,
+        PreparedMetadata, RawMetadataAndRawRows, ResultMetadata, SchemaChange, SetKeyspace,
+        TableSpec, UserDefinedType,
+    };
+    use super::{authenticate, error::DbError, Error, Response, Supported};
+    use crate::frame::protocol_features::ProtocolFeatures;
+    use crate::frame::request::query::{PagingState, PagingStateResponse};
+    use crate::frame::ProtocolVersion;
+    use crate::Consistency;
+
+    const VERSIONS: [ProtocolVersion; 3] = [
+        ProtocolVersion::V3,
+        ProtocolVersion::V4,
+        ProtocolVersion::V5,
+    ];
+
+    fn features() -> ProtocolFeatures {
+        ProtocolFeatures {
+            rate_limit_error: Some(0x4321),
+            ..Default::default()
+        }
+    }
+
+    fn round_trip(response: &Response, version: ProtocolVersion) -> Response {
+        let mut buf = Vec::new();
+        response.serialize(&features(), version, &mut buf).unwrap();
+        Response::deserialize(&features(), version, response.opcode(), buf.into(), None).unwrap()
+    }
+
+    fn assert_round_trip(response: Response, version: ProtocolVersion) {
+        let deserialized = round_trip(&response, version);
+        assert_eq!(format!("{:?}", deserialized), format!("{:?}", response));
+    }
+
+    fn table_spec() -> TableSpec<'static> {
+        TableSpec::borrowed("ks", "tab")
+    }
+
+    #[test]
+    fn simple_responses_round_trip() {
+        for version in VERSIONS {
+            assert_round_trip(Response::Ready, version);
+            assert_round_trip(
+                Response::Supported(Supported {
+                    options: HashMap::from([(
+                        "COMPRESSION".to_owned(),
+                        vec!["lz4".to_owned(), "snappy".to_owned()],
+                    )]),
+                }),
+                version,
+            );
+            assert_round_trip(
+                Response::Authenticate(authenticate::Authenticate {
+                    authenticator_name: "PasswordAuthenticator".to_owned(),
+                }),
+                version,
+            );
+            assert_round_trip(
+                Response::AuthChallenge(authenticate::AuthChallenge {
+                    authenticate_message: Some(b"challenge".to_vec()),
+                }),
+                version,
+            );
+            assert_round_trip(
+                Response::AuthSuccess(authenticate::AuthSuccess {
+                    success_message: None,
+                }),
+                version,
+            );
+        }
+    }
+
+    #[test]
+    fn error_responses_round_trip() {
+        let errors = [
+            DbError::SyntaxError,
+            DbError::Unavailable {
+                consistency: Consistency::Quorum,
+                required: 2,
+                alive: 1,
+            },
+            DbError::ReadFailure {
+                consistency: Consistency::One,
+                received: 0,
+                required: 1,
+                numfailures: 2,
+                data_present: true,
+            },
+            DbError::WriteFailure {
+                consistency: Consistency::All,
+                received: 1,
+                required: 3,
+                numfailures: 2,
+                write_type: super::error::WriteType::BatchLog,
+            },
+            DbError::FunctionFailure {
+                keyspace: "ks".to_owned(),
+                function: "fun".to_owned(),
+                arg_types: vec!["int".to_owned()],
+            },
+            DbError::Unprepared {
+                statement_id: Bytes::from_static(&[1, 2, 3]),
+            },
+            DbError::RateLimitReached {
+                op_type: super::error::OperationType::Write,
+                rejected_by_coordinator: true,
+            },
+            DbError::Other(0x7777),
+        ];
+
+        for version in VERSIONS {
+            for error in errors.iter() {
+                let response = Response::Error(Error {
+                    error: error.clone(),
+                    reason: "something went wrong".to_owned(),
+                });
+                let Response::Error(deserialized) = round_trip(&response, version) else {
+                    panic!("Expected ERROR response");
+                };
+                assert_eq!(&deserialized.error, error);
+                assert_eq!(deserialized.reason, "something went wrong");
+            }
+        }
+    }
+
+    #[test]
+    fn rate_limit_error_requires_negotiated_code() {
+        let error = Error {
+            error: DbError::RateLimitReached {
+                op_type: super::error::OperationType::Read,
+                rejected_by_coordinator: false,
+            },
+            reason: String::new(),
+        };
+        error
+            .serialize(
+                &ProtocolFeatures::default(),
+                ProtocolVersion::V4,
+                &mut Vec::new(),
+            )
+            .unwrap_err();
+    }
+
+    #[test]
+    fn event_responses_round_trip() {
+        let addr: SocketAddr = "127.0.0.1:9042".parse().unwrap();
+        for version in VERSIONS {
+            assert_round_trip(
+                Response::Event(Event::StatusChange(StatusChangeEvent::Down(addr))),
+                version,
+            );
+            assert_round_trip(
+                Response::Event(Event::SchemaChange(SchemaChangeEvent::FunctionChange {
+                    change_type: SchemaChangeType::Created,
+                    keyspace_name: "ks".to_owned(),
+                    function_name: "fun".to_owned(),
+                    arguments: vec!["int".to_owned(), "text".to_owned()],
+                })),
+                version,
+            );
+        }
+    }
+
+    #[test]
+    fn result_responses_round_trip() {
+        for version in VERSIONS {
+            assert_round_trip(Response::Result(super::result::Result::Void), version);
+            assert_round_trip(
+                Response::Result(super::result::Result::SetKeyspace(SetKeyspace {
+                    keyspace_name: "ks".to_owned(),
+                })),
+                version,
+            );
+            assert_round_trip(
+                Response::Result(super::result::Result::SchemaChange(SchemaChange {
+                    event: SchemaChangeEvent::TableChange {
+                        change_type: SchemaChangeType::Dropped,
+                        keyspace_name: "ks".to_owned(),
+                        object_name: "tab".to_owned(),
+                    },
+                })),
+                version,
+            );
+        }
+    }
+
+    #[test]
+    fn prepared_result_round_trip() {
+        let udt = ColumnType::UserDefinedType {
+            frozen: false,
+            definition: Arc::new(UserDefinedType {
+                name: Cow::Borrowed("point"),
+                keyspace: Cow::Borrowed("ks"),
+                field_types: vec![
+                    (Cow::Borrowed("x"), ColumnType::Native(NativeType::Float)),
+                    (Cow::Borrowed("y"), ColumnType::Native(NativeType::Float)),
+                ],
+            }),
+        };
+        let col_specs = vec![
+            ColumnSpec::borrowed("a", ColumnType::Native(NativeType::Int), table_spec()),
+            ColumnSpec::borrowed("b", ColumnType::Native(NativeType::Text), table_spec()),
+            ColumnSpec::borrowed(
+                "v",
+                ColumnType::Vector {
+                    typ: Box::new(ColumnType::Native(NativeType::Float)),
+                    dimensions: 3,
+                },
+                table_spec(),
+            ),
+            ColumnSpec::borrowed(
+                "m",
+                ColumnType::Collection {
+                    frozen: false,
+                    typ: CollectionType::Map(
+                        Box::new(ColumnType::Native(NativeType::Text)),
+                        Box::new(ColumnType::Vector {
+                            typ: Box::new(udt),
+                            dimensions: 2,
+                        }),
+                    ),
+                },
+                table_spec(),
+            ),
+        ];
+
+        for version in VERSIONS {
+            // Partition key indexes are only sent since protocol v4.
+            let pk_indexes = if version >= ProtocolVersion::V4 {
+                vec![
+                    PartitionKeyIndex {
+                        index: 0,
+                        sequence: 1,
+                    },
+                    PartitionKeyIndex {
+                        index: 1,
+                        sequence: 0,
+                    },
+                ]
+            } else {
+                Vec::new()
+            };
+            let prepared = Prepared {
+                id: Bytes::from_static(&[1, 2, 3]),
+                result_metadata_id: (version >= ProtocolVersion::V5)
+                    .then(|| Bytes::from_static(&[4, 5])),
+                prepared_metadata: PreparedMetadata {
+                    flags: 0x0001,
+                    col_count: 2,
+                    pk_indexes,
+                    col_specs: col_specs[..2].to_vec(),
+                },
+                result_metadata: ResultMetadata::new(col_specs.clone()),
+            };
+            assert_round_trip(
+                Response::Result(super::result::Result::Prepared(prepared)),
+                version,
+            );
+        }
+
+        // Protocol v5 requires the result metadata id.
+        let prepared = Prepared {
+            id: Bytes::from_static(&[1, 2, 3]),
+            result_metadata_id: None,
+            prepared_metadata: PreparedMetadata {
+                flags: 0,
+                col_count: 0,
+                pk_indexes: Vec::new(),
+                col_specs: Vec::new(),
+            },
+            result_metadata: ResultMetadata::mock_empty(),
+        };
+        Response::Result(super::result::Result::Prepared(prepared))
+            .serialize(&features(), ProtocolVersion::V5, &mut Vec::new())
+            .unwrap_err();
+    }
+
+    #[test]
+    fn rows_result_round_trip() {
+        let metadata = ResultMetadata::new(vec![
+            ColumnSpec::borrowed("a", ColumnType::Native(NativeType::Int), table_spec()),
+            ColumnSpec::borrowed(
+                "b",
+                ColumnType::Native(NativeType::Text),
+                TableSpec::borrowed("ks", "other_tab"),
+            ),
+        ]);
+        let mut raw_rows = Vec::new();
+        for (a, b) in [(1_i32, "one"), (2, "two")] {
+            raw_rows.extend_from_slice(&4_i32.to_be_bytes());
+            raw_rows.extend_from_slice(&a.to_be_bytes());
+            raw_rows.extend_from_slice(&(b.len() as i32).to_be_bytes());
+            raw_rows.extend_from_slice(b.as_bytes());
+        }
+        let paging_state = PagingStateResponse::HasMorePages {
+            state: PagingState::new_from_raw_bytes(&[7_u8, 7, 7] as &[u8]),
+        };
+
+        for version in VERSIONS {
+            let raw = RawMetadataAndRawRows::new(&metadata, 2, &raw_rows).unwrap();
+            let response =
+                Response::Result(super::result::Result::Rows((raw, paging_state.clone())));
+            let Response::Result(super::result::Result::Rows((raw, deserialized_paging_state))) =
+                round_trip(&response, version)
+            else {
+                panic!("Expected RESULT:Rows response");
+            };
+            assert_eq!(
+                format!("{:?}", deserialized_paging_state),
+                format!("{:?}", paging_state)
+            );
+
+            let deserialized = raw.deserialize_metadata().unwrap();
+            assert_eq!(deserialized.rows_count(), 2);
+            let col_specs = deserialized.metadata().col_specs();
+            assert_eq!(col_specs.len(), 2);
+            assert_eq!(col_specs[1].table_spec().table_name(), "other_tab");
+            let rows = deserialized
+                .rows_iter::<(i32, String)>()
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(rows, [(1, "one".to_owned()), (2, "two".to_owned())]);
+        }
+    }
+
+    #[test]
+    fn rows_result_with_changed_metadata_round_trip() {
+        let metadata = ResultMetadata::new(vec![ColumnSpec::borrowed(
+            "a",
+            ColumnType::Native(NativeType::Int),
+            table_spec(),
+        )]);
+        let raw = RawMetadataAndRawRows::new(&metadata, 0, &[])
+            .unwrap()
+            .with_new_metadata_id(Bytes::from_static(&[4, 2]));
+        let response = Response::Result(super::result::Result::Rows((
+            raw,
+            PagingStateResponse::NoMorePages,
+        )));
+
+        let Response::Result(super::result::Result::Rows((raw, _))) =
+            round_trip(&response, ProtocolVersion::V5)
+        else {
+            panic!("Expected RESULT:Rows response");
+        };
+        assert_eq!(raw.new_metadata_id().map(|id| &id[..]), Some(&[4, 2][..]));
+        let new_metadata = raw.deserialize_owned_metadata().unwrap().unwrap();
+        assert_eq!(
+            format!("{:?}", new_metadata.col_specs()),
+            format!("{:?}", metadata.col_specs())
+        );
+    }
+}
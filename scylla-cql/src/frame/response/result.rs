@@ -9,14 +9,17 @@ use crate::frame::frame_errors::{
     ResultMetadataParseError, SchemaChangeEventParseError, SetKeyspaceParseError,
     TableSpecParseError,
 };
+use crate::frame::protocol_features::ProtocolFeatures;
 use crate::frame::request::query::PagingStateResponse;
 use crate::frame::response::event::SchemaChangeEvent;
-use crate::frame::types;
-use bytes::{Buf, Bytes};
+use crate::frame::{types, ProtocolVersion};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use std::borrow::Cow;
 use std::fmt::Debug;
+use std::num::TryFromIntError;
 use std::sync::Arc;
 use std::{result::Result as StdResult, str};
+use thiserror::Error;
 
 #[derive(Debug)]
 pub struct SetKeyspace {
@@ -26,6 +29,8 @@ pub struct SetKeyspace {
 #[derive(Debug)]
 pub struct Prepared {
     pub id: Bytes,
+    /// Id of the result metadata, sent by the server since protocol v5.
+    pub result_metadata_id: Option<Bytes>,
     pub prepared_metadata: PreparedMetadata,
     pub result_metadata: ResultMetadata<'static>,
 }
@@ -408,6 +413,10 @@ pub struct RawMetadataAndRawRows {
     global_tables_spec: bool,
     no_metadata: bool,
 
+    /// Id of the new result metadata, sent by the server (since protocol v5)
+    /// if the metadata has changed since the statement was prepared.
+    new_metadata_id: Option<Bytes>,
+
     /// The remaining part of the RESULT frame.
     raw_metadata_and_rows: Bytes,
 
@@ -430,16 +439,58 @@ impl RawMetadataAndRawRows {
             col_count: 0,
             global_tables_spec: false,
             no_metadata: false,
+            new_metadata_id: None,
             raw_metadata_and_rows,
             cached_metadata: None,
         }
     }
 
+    /// Returns the id of the new result metadata, if the server signalled
+    /// that the result metadata of the prepared statement has changed.
+    #[inline]
+    pub fn new_metadata_id(&self) -> Option<&Bytes> {
+        self.new_metadata_id.as_ref()
+    }
+
     /// Returns the serialized size of the raw metadata + raw rows.
     #[inline]
     pub fn metadata_and_rows_bytes_size(&self) -> usize {
         self.raw_metadata_and_rows.len()
     }
+
+    /// Marks the result metadata as changed, attaching the id of the new metadata.
+    ///
+    /// Useful when forging RESULT:Rows responses, e.g. in mock servers.
+    #[inline]
+    pub fn with_new_metadata_id(mut self, new_metadata_id: Bytes) -> Self {
+        self.new_metadata_id = Some(new_metadata_id);
+        self
+    }
+
+    /// Deserializes result metadata sent along with the rows into an owned form,
+    /// so that it can be cached, e.g. after the server reported that
+    /// the result metadata of a prepared statement has changed.
+    ///
+    /// Returns `None` if the server did not send the metadata.
+    pub fn deserialize_owned_metadata(
+        &self,
+    ) -> StdResult<Option<ResultMetadata<'static>>, ResultMetadataParseError> {
+        if self.no_metadata {
+            return Ok(None);
+        }
+
+        let buf = &mut &*self.raw_metadata_and_rows;
+        let global_table_spec = self
+            .global_tables_spec
+            .then(|| deser_table_spec(buf))
+            .transpose()?;
+        let col_specs = deser_col_specs_owned(buf, global_table_spec, self.col_count)?;
+
+        Ok(Some(ResultMetadata {
+            col_count: self.col_count,
+            col_specs,
+        }))
+    }
 }
 
 mod self_borrowed_metadata {
@@ -850,6 +901,7 @@ fn deser_result_metadata(
     let global_tables_spec = flags & 0x0001 != 0;
     let has_more_pages = flags & 0x0002 != 0;
     let no_metadata = flags & 0x0004 != 0;
+    let metadata_changed = flags & 0x0008 != 0;
 
     let col_count =
         types::read_int_length(buf).map_err(ResultMetadataParseError::ColumnCountParseError)?;
@@ -858,6 +910,11 @@ fn deser_result_metadata(
         .then(|| types::read_bytes(buf).map_err(ResultMetadataParseError::PagingStateParseError))
         .transpose()?;
 
+    if metadata_changed {
+        // Only sent in RESULT:Rows, so we don't need to keep it here.
+        types::read_short_bytes(buf).map_err(ResultMetadataParseError::NewMetadataIdParseError)?;
+    }
+
     let paging_state = PagingStateResponse::new_from_raw_bytes(raw_paging_state);
 
     let col_specs = if no_metadata {
@@ -889,6 +946,7 @@ impl RawMetadataAndRawRows {
         let global_tables_spec = flags & 0x0001 != 0;
         let has_more_pages = flags & 0x0002 != 0;
         let no_metadata = flags & 0x0004 != 0;
+        let metadata_changed = flags & 0x0008 != 0;
 
         let col_count = types::read_int_length(frame.as_slice_mut())
             .map_err(RawRowsAndPagingStateResponseParseError::ColumnCountParseError)?;
@@ -902,10 +960,19 @@ impl RawMetadataAndRawRows {
 
         let paging_state = PagingStateResponse::new_from_raw_bytes(raw_paging_state);
 
+        let new_metadata_id = metadata_changed
+            .then(|| {
+                types::read_short_bytes(frame.as_slice_mut())
+                    .map(Bytes::copy_from_slice)
+                    .map_err(RawRowsAndPagingStateResponseParseError::NewMetadataIdParseError)
+            })
+            .transpose()?;
+
         let raw_rows = Self {
             col_count,
             global_tables_spec,
             no_metadata,
+            new_metadata_id,
             raw_metadata_and_rows: frame.to_bytes(),
             cached_metadata,
         };
@@ -1010,6 +1077,7 @@ impl RawMetadataAndRawRows {
 
 fn deser_prepared_metadata(
     buf: &mut &[u8],
+    version: ProtocolVersion,
 ) -> StdResult<PreparedMetadata, PreparedMetadataParseError> {
     let flags = types::read_int(buf)
         .map_err(|err| PreparedMetadataParseError::FlagsParseError(err.into()))?;
@@ -1018,8 +1086,12 @@ fn deser_prepared_metadata(
     let col_count =
         types::read_int_length(buf).map_err(PreparedMetadataParseError::ColumnCountParseError)?;
 
-    let pk_count: usize =
-        types::read_int_length(buf).map_err(PreparedMetadataParseError::PkCountParseError)?;
+    // Partition key indexes were introduced in protocol v4.
+    let pk_count: usize = if version >= ProtocolVersion::V4 {
+        types::read_int_length(buf).map_err(PreparedMetadataParseError::PkCountParseError)?
+    } else {
+        0
+    };
 
     let mut pk_indexes = Vec::with_capacity(pk_count);
     for i in 0..pk_count {
@@ -1061,14 +1133,25 @@ fn deser_set_keyspace(buf: &mut &[u8]) -> StdResult<SetKeyspace, SetKeyspacePars
     Ok(SetKeyspace { keyspace_name })
 }
 
-fn deser_prepared(buf: &mut &[u8]) -> StdResult<Prepared, PreparedParseError> {
+fn deser_prepared(
+    buf: &mut &[u8],
+    features: &ProtocolFeatures,
+    version: ProtocolVersion,
+) -> StdResult<Prepared, PreparedParseError> {
     let id_len = types::read_short(buf)
         .map_err(|err| PreparedParseError::IdLengthParseError(err.into()))?
         as usize;
     let id: Bytes = buf[0..id_len].to_owned().into();
     buf.advance(id_len);
-    let prepared_metadata =
-        deser_prepared_metadata(buf).map_err(PreparedParseError::PreparedMetadataParseError)?;
+    let result_metadata_id = if features.uses_result_metadata_id(version) {
+        let result_metadata_id =
+            types::read_short_bytes(buf).map_err(PreparedParseError::ResultMetadataIdParseError)?;
+        Some(Bytes::copy_from_slice(result_metadata_id))
+    } else {
+        None
+    };
+    let prepared_metadata = deser_prepared_metadata(buf, version)
+        .map_err(PreparedParseError::PreparedMetadataParseError)?;
     let (result_metadata, paging_state_response) =
         deser_result_metadata(buf).map_err(PreparedParseError::ResultMetadataParseError)?;
     if let PagingStateResponse::HasMorePages { state } = paging_state_response {
@@ -1082,6 +1165,7 @@ fn deser_prepared(buf: &mut &[u8]) -> StdResult<Prepared, PreparedParseError> {
 
     Ok(Prepared {
         id,
+        result_metadata_id,
         prepared_metadata,
         result_metadata,
     })
@@ -1095,6 +1179,8 @@ fn deser_schema_change(buf: &mut &[u8]) -> StdResult<SchemaChange, SchemaChangeE
 
 pub fn deserialize(
     buf_bytes: Bytes,
+    features: &ProtocolFeatures,
+    version: ProtocolVersion,
     cached_metadata: Option<&Arc<ResultMetadata<'static>>>,
 ) -> StdResult<Result, CqlResultParseError> {
     let buf = &mut &*buf_bytes;
@@ -1106,192 +1192,515 @@ pub fn deserialize(
             0x0001 => Void,
             0x0002 => Rows(deser_rows(buf_bytes.slice_ref(buf), cached_metadata)?),
             0x0003 => SetKeyspace(deser_set_keyspace(buf)?),
-            0x0004 => Prepared(deser_prepared(buf)?),
+            0x0004 => Prepared(deser_prepared(buf, features, version)?),
             0x0005 => SchemaChange(deser_schema_change(buf)?),
             id => return Err(CqlResultParseError::UnknownResultId(id)),
         },
     )
 }
 
-// This is not #[cfg(test)], because it is used by scylla crate.
-// Unfortunately, this attribute does not apply recursively to
-// children item. Therefore, every `pub` item here must use have
-// the specifier, too.
-#[doc(hidden)]
-mod test_utils {
-    use std::num::TryFromIntError;
-
-    use bytes::{BufMut, BytesMut};
-
-    use super::*;
+impl TableSpec<'_> {
+    pub(crate) fn serialize(&self, buf: &mut impl BufMut) -> StdResult<(), TryFromIntError> {
+        types::write_string(&self.ks_name, buf)?;
+        types::write_string(&self.table_name, buf)?;
 
-    impl TableSpec<'_> {
-        pub(crate) fn serialize(&self, buf: &mut impl BufMut) -> StdResult<(), TryFromIntError> {
-            types::write_string(&self.ks_name, buf)?;
-            types::write_string(&self.table_name, buf)?;
+        Ok(())
+    }
+}
 
-            Ok(())
+impl NativeType {
+    /// Name of the Cassandra marshal class of the type, used in custom type names.
+    fn marshal_class_name(&self) -> &'static str {
+        match self {
+            NativeType::Ascii => "AsciiType",
+            NativeType::Boolean => "BooleanType",
+            NativeType::Blob => "BytesType",
+            NativeType::Counter => "CounterColumnType",
+            NativeType::Date => "SimpleDateType",
+            NativeType::Decimal => "DecimalType",
+            NativeType::Double => "DoubleType",
+            NativeType::Duration => "DurationType",
+            NativeType::Float => "FloatType",
+            NativeType::Int => "Int32Type",
+            NativeType::BigInt => "LongType",
+            NativeType::Text => "UTF8Type",
+            NativeType::Timestamp => "TimestampType",
+            NativeType::Inet => "InetAddressType",
+            NativeType::SmallInt => "ShortType",
+            NativeType::TinyInt => "ByteType",
+            NativeType::Time => "TimeType",
+            NativeType::Timeuuid => "TimeUUIDType",
+            NativeType::Uuid => "UUIDType",
+            NativeType::Varint => "IntegerType",
         }
     }
+}
 
-    impl ColumnType<'_> {
-        fn id(&self) -> u16 {
-            use NativeType::*;
-            match self {
-                Self::Native(Ascii) => 0x0001,
-                Self::Native(BigInt) => 0x0002,
-                Self::Native(Blob) => 0x0003,
-                Self::Native(Boolean) => 0x0004,
-                Self::Native(Counter) => 0x0005,
-                Self::Native(Decimal) => 0x0006,
-                Self::Native(Double) => 0x0007,
-                Self::Native(Float) => 0x0008,
-                Self::Native(Int) => 0x0009,
-                Self::Native(Timestamp) => 0x000B,
-                Self::Native(Uuid) => 0x000C,
-                Self::Native(Text) => 0x000D,
-                Self::Native(Varint) => 0x000E,
-                Self::Native(Timeuuid) => 0x000F,
-                Self::Native(Inet) => 0x0010,
-                Self::Native(Date) => 0x0011,
-                Self::Native(Time) => 0x0012,
-                Self::Native(SmallInt) => 0x0013,
-                Self::Native(TinyInt) => 0x0014,
-                Self::Native(Duration) => 0x0015,
-                Self::Collection {
-                    typ: CollectionType::List(_),
-                    ..
-                } => 0x0020,
-                Self::Collection {
-                    typ: CollectionType::Map(_, _),
-                    ..
-                } => 0x0021,
-                Self::Collection {
-                    typ: CollectionType::Set(_),
-                    ..
-                } => 0x0022,
-                Self::Vector { .. } => {
-                    unimplemented!();
-                }
-                Self::UserDefinedType { .. } => 0x0030,
-                Self::Tuple(_) => 0x0031,
-            }
+impl ColumnType<'_> {
+    fn id(&self) -> u16 {
+        use NativeType::*;
+        match self {
+            Self::Native(Ascii) => 0x0001,
+            Self::Native(BigInt) => 0x0002,
+            Self::Native(Blob) => 0x0003,
+            Self::Native(Boolean) => 0x0004,
+            Self::Native(Counter) => 0x0005,
+            Self::Native(Decimal) => 0x0006,
+            Self::Native(Double) => 0x0007,
+            Self::Native(Float) => 0x0008,
+            Self::Native(Int) => 0x0009,
+            Self::Native(Timestamp) => 0x000B,
+            Self::Native(Uuid) => 0x000C,
+            Self::Native(Text) => 0x000D,
+            Self::Native(Varint) => 0x000E,
+            Self::Native(Timeuuid) => 0x000F,
+            Self::Native(Inet) => 0x0010,
+            Self::Native(Date) => 0x0011,
+            Self::Native(Time) => 0x0012,
+            Self::Native(SmallInt) => 0x0013,
+            Self::Native(TinyInt) => 0x0014,
+            Self::Native(Duration) => 0x0015,
+            Self::Collection {
+                typ: CollectionType::List(_),
+                ..
+            } => 0x0020,
+            Self::Collection {
+                typ: CollectionType::Map(_, _),
+                ..
+            } => 0x0021,
+            Self::Collection {
+                typ: CollectionType::Set(_),
+                ..
+            } => 0x0022,
+            // Vectors have no dedicated id, they are sent as custom types.
+            Self::Vector { .. } => 0x0000,
+            Self::UserDefinedType { .. } => 0x0030,
+            Self::Tuple(_) => 0x0031,
         }
+    }
 
-        /// Returns the size of the type in bytes, as it is seen by the vector type if it is treated as fixed size.
-        pub(crate) fn type_size(&self) -> Option<usize> {
-            match self {
-                ColumnType::Native(n) => n.type_size(),
-                ColumnType::Tuple(_) => None,
-                ColumnType::Collection { .. } => None,
-                ColumnType::Vector { typ, dimensions } => {
-                    typ.type_size().map(|size| size * usize::from(*dimensions))
-                }
-                ColumnType::UserDefinedType { .. } => None,
+    /// Returns the size of the type in bytes, as it is seen by the vector type if it is treated as fixed size.
+    pub(crate) fn type_size(&self) -> Option<usize> {
+        match self {
+            ColumnType::Native(n) => n.type_size(),
+            ColumnType::Tuple(_) => None,
+            ColumnType::Collection { .. } => None,
+            ColumnType::Vector { typ, dimensions } => {
+                typ.type_size().map(|size| size * usize::from(*dimensions))
             }
+            ColumnType::UserDefinedType { .. } => None,
         }
+    }
 
-        // Only for use in tests
-        pub(crate) fn serialize(&self, buf: &mut impl BufMut) -> StdResult<(), TryFromIntError> {
-            let id = self.id();
-            types::write_short(id, buf);
+    /// Writes the Cassandra marshal class name of the type, as understood
+    /// by the custom type parser.
+    fn write_marshal_class_name(&self, out: &mut String) {
+        const PREFIX: &str = "org.apache.cassandra.db.marshal.";
 
-            match self {
-                // Simple types
-                ColumnType::Native(_) => (),
+        fn write_hex(s: &str, out: &mut String) {
+            use std::fmt::Write as _;
+            for byte in s.bytes() {
+                // Writing to a String never fails.
+                let _ = write!(out, "{:02x}", byte);
+            }
+        }
 
-                ColumnType::Collection {
-                    typ: CollectionType::List(elem_type),
-                    ..
+        out.push_str(PREFIX);
+        match self {
+            ColumnType::Native(native) => out.push_str(native.marshal_class_name()),
+            ColumnType::Collection { typ, .. } => match typ {
+                CollectionType::List(elem_type) => {
+                    out.push_str("ListType(");
+                    elem_type.write_marshal_class_name(out);
+                    out.push(')');
                 }
-                | ColumnType::Collection {
-                    typ: CollectionType::Set(elem_type),
-                    ..
-                } => {
-                    elem_type.serialize(buf)?;
+                CollectionType::Set(elem_type) => {
+                    out.push_str("SetType(");
+                    elem_type.write_marshal_class_name(out);
+                    out.push(')');
                 }
-                ColumnType::Collection {
-                    typ: CollectionType::Map(key_type, value_type),
-                    ..
-                } => {
-                    key_type.serialize(buf)?;
-                    value_type.serialize(buf)?;
+                CollectionType::Map(key_type, value_type) => {
+                    out.push_str("MapType(");
+                    key_type.write_marshal_class_name(out);
+                    out.push(',');
+                    value_type.write_marshal_class_name(out);
+                    out.push(')');
                 }
-                ColumnType::Tuple(types) => {
-                    types::write_short_length(types.len(), buf)?;
-                    for typ in types.iter() {
-                        typ.serialize(buf)?;
+            },
+            ColumnType::Vector { typ, dimensions } => {
+                out.push_str("VectorType(");
+                typ.write_marshal_class_name(out);
+                out.push(',');
+                out.push_str(&dimensions.to_string());
+                out.push(')');
+            }
+            ColumnType::UserDefinedType { definition, .. } => {
+                out.push_str("UserType(");
+                out.push_str(&definition.keyspace);
+                out.push(',');
+                write_hex(&definition.name, out);
+                for (field_name, field_type) in definition.field_types.iter() {
+                    out.push(',');
+                    write_hex(field_name, out);
+                    out.push(':');
+                    field_type.write_marshal_class_name(out);
+                }
+                out.push(')');
+            }
+            ColumnType::Tuple(types) => {
+                out.push_str("TupleType(");
+                for (i, typ) in types.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
                     }
+                    typ.write_marshal_class_name(out);
                 }
-                ColumnType::Vector { .. } => {
-                    unimplemented!()
+                out.push(')');
+            }
+        }
+    }
+
+    pub(crate) fn serialize(&self, buf: &mut impl BufMut) -> StdResult<(), TryFromIntError> {
+        let id = self.id();
+        types::write_short(id, buf);
+
+        match self {
+            // Simple types
+            ColumnType::Native(_) => (),
+
+            ColumnType::Collection {
+                typ: CollectionType::List(elem_type),
+                ..
+            }
+            | ColumnType::Collection {
+                typ: CollectionType::Set(elem_type),
+                ..
+            } => {
+                elem_type.serialize(buf)?;
+            }
+            ColumnType::Collection {
+                typ: CollectionType::Map(key_type, value_type),
+                ..
+            } => {
+                key_type.serialize(buf)?;
+                value_type.serialize(buf)?;
+            }
+            ColumnType::Tuple(types) => {
+                types::write_short_length(types.len(), buf)?;
+                for typ in types.iter() {
+                    typ.serialize(buf)?;
                 }
-                ColumnType::UserDefinedType {
-                    definition: udt, ..
-                } => {
-                    types::write_string(&udt.keyspace, buf)?;
-                    types::write_string(&udt.name, buf)?;
-                    types::write_short_length(udt.field_types.len(), buf)?;
-                    for (field_name, field_type) in udt.field_types.iter() {
-                        types::write_string(field_name, buf)?;
-                        field_type.serialize(buf)?;
-                    }
+            }
+            ColumnType::Vector { .. } => {
+                let mut class_name = String::new();
+                self.write_marshal_class_name(&mut class_name);
+                types::write_string(&class_name, buf)?;
+            }
+            ColumnType::UserDefinedType {
+                definition: udt, ..
+            } => {
+                types::write_string(&udt.keyspace, buf)?;
+                types::write_string(&udt.name, buf)?;
+                types::write_short_length(udt.field_types.len(), buf)?;
+                for (field_name, field_type) in udt.field_types.iter() {
+                    types::write_string(field_name, buf)?;
+                    field_type.serialize(buf)?;
                 }
             }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the table spec shared by all columns, if there is one.
+///
+/// If so, it is sent once as the global table spec
+/// instead of being repeated for every column.
+fn common_table_spec<'a, 'spec>(
+    col_specs: &'a [ColumnSpec<'spec>],
+) -> Option<&'a TableSpec<'spec>> {
+    let (first, rest) = col_specs.split_first()?;
+    rest.iter()
+        .all(|col_spec| col_spec.table_spec() == first.table_spec())
+        .then(|| first.table_spec())
+}
 
-            Ok(())
+fn serialize_col_specs(
+    col_specs: &[ColumnSpec<'_>],
+    global_table_spec: Option<&TableSpec<'_>>,
+    buf: &mut impl BufMut,
+) -> StdResult<(), TryFromIntError> {
+    if let Some(spec) = global_table_spec {
+        spec.serialize(buf)?;
+    }
+
+    for col_spec in col_specs {
+        if global_table_spec.is_none() {
+            col_spec.table_spec().serialize(buf)?;
         }
+
+        types::write_string(col_spec.name(), buf)?;
+        col_spec.typ().serialize(buf)?;
     }
 
-    impl<'a> ResultMetadata<'a> {
-        #[inline]
-        #[doc(hidden)]
-        pub fn new_for_test(col_count: usize, col_specs: Vec<ColumnSpec<'a>>) -> Self {
-            Self {
-                col_count,
-                col_specs,
-            }
+    Ok(())
+}
+
+impl<'a> ResultMetadata<'a> {
+    /// Creates result metadata describing the given columns,
+    /// e.g. in order to send it in a RESULT response.
+    #[inline]
+    pub fn new(col_specs: Vec<ColumnSpec<'a>>) -> Self {
+        Self {
+            col_count: col_specs.len(),
+            col_specs,
         }
+    }
 
-        pub(crate) fn serialize(
-            &self,
-            buf: &mut impl BufMut,
-            no_metadata: bool,
-            global_tables_spec: bool,
-        ) -> StdResult<(), TryFromIntError> {
-            let global_table_spec = global_tables_spec
-                .then(|| self.col_specs.first().map(|col_spec| col_spec.table_spec()))
-                .flatten();
-
-            let mut flags = 0;
-            if global_table_spec.is_some() {
-                flags |= 0x0001;
+    pub(crate) fn serialize(
+        &self,
+        buf: &mut impl BufMut,
+        no_metadata: bool,
+        global_tables_spec: bool,
+    ) -> StdResult<(), TryFromIntError> {
+        let global_table_spec = global_tables_spec
+            .then(|| self.col_specs.first().map(|col_spec| col_spec.table_spec()))
+            .flatten();
+
+        let mut flags = 0;
+        if global_table_spec.is_some() {
+            flags |= 0x0001;
+        }
+        if no_metadata {
+            flags |= 0x0004;
+        }
+        types::write_int(flags, buf);
+
+        types::write_int_length(self.col_count, buf)?;
+
+        // No paging state.
+
+        if !no_metadata {
+            serialize_col_specs(&self.col_specs, global_table_spec, buf)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PreparedMetadata {
+    fn serialize(
+        &self,
+        buf: &mut impl BufMut,
+        version: ProtocolVersion,
+    ) -> StdResult<(), TryFromIntError> {
+        let global_table_spec = common_table_spec(&self.col_specs);
+
+        let mut flags = self.flags & !0x0001;
+        if global_table_spec.is_some() {
+            flags |= 0x0001;
+        }
+        types::write_int(flags, buf);
+
+        types::write_int_length(self.col_count, buf)?;
+
+        // Partition key indexes were introduced in protocol v4.
+        if version >= ProtocolVersion::V4 {
+            types::write_int_length(self.pk_indexes.len(), buf)?;
+            let mut pk_indexes = self.pk_indexes.clone();
+            pk_indexes.sort_unstable_by_key(|pki| pki.sequence);
+            for pki in pk_indexes {
+                types::write_short(pki.index, buf);
             }
-            if no_metadata {
-                flags |= 0x0004;
+        }
+
+        serialize_col_specs(&self.col_specs, global_table_spec, buf)
+    }
+}
+
+impl RawMetadataAndRawRows {
+    /// Creates [RawMetadataAndRawRows] from result metadata and serialized rows,
+    /// e.g. in order to send it in a RESULT:Rows response.
+    ///
+    /// `raw_rows` must contain `rows_count` rows, serialized according to `metadata`.
+    pub fn new(
+        metadata: &ResultMetadata<'_>,
+        rows_count: usize,
+        raw_rows: &[u8],
+    ) -> StdResult<Self, TryFromIntError> {
+        let global_table_spec = common_table_spec(&metadata.col_specs);
+
+        let mut buf = BytesMut::new();
+        serialize_col_specs(&metadata.col_specs, global_table_spec, &mut buf)?;
+        types::write_int_length(rows_count, &mut buf)?;
+        buf.extend_from_slice(raw_rows);
+
+        Ok(Self {
+            col_count: metadata.col_count,
+            global_tables_spec: global_table_spec.is_some(),
+            no_metadata: false,
+            new_metadata_id: None,
+            raw_metadata_and_rows: buf.freeze(),
+            cached_metadata: None,
+        })
+    }
+
+    fn serialize(
+        &self,
+        paging_state_response: &PagingStateResponse,
+        buf: &mut impl BufMut,
+    ) -> StdResult<(), TryFromIntError> {
+        let raw_paging_state = match paging_state_response {
+            PagingStateResponse::HasMorePages { state } => {
+                Some(state.as_bytes_slice().map_or(&[][..], |state| &state[..]))
             }
-            types::write_int(flags, buf);
+            PagingStateResponse::NoMorePages => None,
+        };
 
-            types::write_int_length(self.col_count, buf)?;
+        let mut flags = 0;
+        if self.global_tables_spec {
+            flags |= 0x0001;
+        }
+        if raw_paging_state.is_some() {
+            flags |= 0x0002;
+        }
+        if self.no_metadata {
+            flags |= 0x0004;
+        }
+        if self.new_metadata_id.is_some() {
+            flags |= 0x0008;
+        }
+        types::write_int(flags, buf);
 
-            // No paging state.
+        types::write_int_length(self.col_count, buf)?;
 
-            if !no_metadata {
-                if let Some(spec) = global_table_spec {
-                    spec.serialize(buf)?;
-                }
+        if let Some(raw_paging_state) = raw_paging_state {
+            types::write_bytes(raw_paging_state, buf)?;
+        }
 
-                for col_spec in self.col_specs() {
-                    if global_table_spec.is_none() {
-                        col_spec.table_spec().serialize(buf)?;
-                    }
+        if let Some(new_metadata_id) = &self.new_metadata_id {
+            types::write_short_bytes(new_metadata_id, buf)?;
+        }
 
-                    types::write_string(col_spec.name(), buf)?;
-                    col_spec.typ().serialize(buf)?;
-                }
+        buf.put_slice(&self.raw_metadata_and_rows);
+
+        Ok(())
+    }
+}
+
+impl Prepared {
+    fn serialize(
+        &self,
+        buf: &mut impl BufMut,
+        features: &ProtocolFeatures,
+        version: ProtocolVersion,
+    ) -> StdResult<(), ResultSerializationError> {
+        types::write_short_bytes(&self.id, buf)
+            .map_err(ResultSerializationError::PreparedSerialization)?;
+        if features.uses_result_metadata_id(version) {
+            let result_metadata_id = self
+                .result_metadata_id
+                .as_ref()
+                .ok_or(ResultSerializationError::MissingResultMetadataId)?;
+            types::write_short_bytes(result_metadata_id, buf)
+                .map_err(ResultSerializationError::PreparedSerialization)?;
+        }
+        self.prepared_metadata
+            .serialize(buf, version)
+            .map_err(ResultSerializationError::PreparedSerialization)?;
+
+        // Statements which return no rows are sent without result metadata.
+        let no_metadata = self.result_metadata.col_specs.is_empty();
+        let global_tables_spec = common_table_spec(&self.result_metadata.col_specs).is_some();
+        self.result_metadata
+            .serialize(buf, no_metadata, global_tables_spec)
+            .map_err(ResultSerializationError::PreparedSerialization)?;
+
+        Ok(())
+    }
+}
+
+impl Result {
+    /// Serializes the body of a RESULT response.
+    pub fn serialize(
+        &self,
+        features: &ProtocolFeatures,
+        version: ProtocolVersion,
+        buf: &mut impl BufMut,
+    ) -> StdResult<(), ResultSerializationError> {
+        match self {
+            Result::Void => types::write_int(0x0001, buf),
+            Result::Rows((raw_rows, paging_state_response)) => {
+                types::write_int(0x0002, buf);
+                raw_rows
+                    .serialize(paging_state_response, buf)
+                    .map_err(ResultSerializationError::RowsSerialization)?;
+            }
+            Result::SetKeyspace(set_keyspace) => {
+                types::write_int(0x0003, buf);
+                types::write_string(&set_keyspace.keyspace_name, buf)
+                    .map_err(ResultSerializationError::SetKeyspaceSerialization)?;
+            }
+            Result::Prepared(prepared) => {
+                types::write_int(0x0004, buf);
+                prepared.serialize(buf, features, version)?;
             }
+            Result::SchemaChange(schema_change) => {
+                types::write_int(0x0005, buf);
+                schema_change
+                    .event
+                    .serialize(buf)
+                    .map_err(ResultSerializationError::SchemaChangeSerialization)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// An error type returned when serialization of RESULT response fails.
+#[non_exhaustive]
+#[derive(Error, Debug, Clone)]
+pub enum ResultSerializationError {
+    /// Failed to serialize RESULT:Rows.
+    #[error("Failed to serialize RESULT:Rows: {0}")]
+    RowsSerialization(TryFromIntError),
+
+    /// Failed to serialize RESULT:SetKeyspace.
+    #[error("Failed to serialize RESULT:SetKeyspace: {0}")]
+    SetKeyspaceSerialization(TryFromIntError),
+
+    /// Failed to serialize RESULT:Prepared.
+    #[error("Failed to serialize RESULT:Prepared: {0}")]
+    PreparedSerialization(TryFromIntError),
+
+    /// Since protocol v5, or if the Scylla metadata id extension was negotiated,
+    /// RESULT:Prepared must carry the result metadata id.
+    #[error("RESULT:Prepared has no result metadata id, which is required by the protocol")]
+    MissingResultMetadataId,
+
+    /// Failed to serialize RESULT:SchemaChange.
+    #[error("Failed to serialize RESULT:SchemaChange: {0}")]
+    SchemaChangeSerialization(TryFromIntError),
+}
 
-            Ok(())
+// This is not #[cfg(test)], because it is used by scylla crate.
+// Unfortunately, this attribute does not apply recursively to
+// children item. Therefore, every `pub` item here must use have
+// the specifier, too.
+#[doc(hidden)]
+mod test_utils {
+    use super::*;
+
+    impl<'a> ResultMetadata<'a> {
+        #[inline]
+        #[doc(hidden)]
+        pub fn new_for_test(col_count: usize, col_specs: Vec<ColumnSpec<'a>>) -> Self {
+            Self {
+                col_count,
+                col_specs,
+            }
         }
     }
 
@@ -1314,7 +1723,7 @@ mod test_utils {
 
             let raw_result_rows = {
                 let mut buf = BytesMut::new();
-                used_metadata.serialize(&mut buf, global_tables_spec, no_metadata)?;
+                used_metadata.serialize(&mut buf, no_metadata, global_tables_spec)?;
                 types::write_int_length(rows_count, &mut buf)?;
                 buf.extend_from_slice(raw_rows);
 
@@ -1346,3 +1755,71 @@ mod test_utils {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BufMut;
+
+    fn serialized_prepared(with_pk_indexes: bool) -> Vec<u8> {
+        let mut buf = Vec::new();
+        // id
+        types::write_short_bytes(&[1, 2, 3], &mut buf).unwrap();
+
+        // prepared metadata: global table spec, one column
+        buf.put_i32(0x0001);
+        buf.put_i32(1);
+        if with_pk_indexes {
+            buf.put_i32(1);
+            buf.put_i16(0);
+        }
+        types::write_string("ks", &mut buf).unwrap();
+        types::write_string("tab", &mut buf).unwrap();
+        types::write_string("pk", &mut buf).unwrap();
+        buf.put_i16(0x0009); // int
+
+        // result metadata: no metadata
+        buf.put_i32(0x0004);
+        buf.put_i32(0);
+
+        buf
+    }
+
+    #[test]
+    fn deserialize_prepared_v3_without_pk_indexes() {
+        let buf = serialized_prepared(false);
+        let prepared =
+            deser_prepared(&mut &buf[..], &Default::default(), ProtocolVersion::V3).unwrap();
+        assert_eq!(&prepared.id[..], &[1, 2, 3]);
+        assert_eq!(prepared.prepared_metadata.col_count, 1);
+        assert!(prepared.prepared_metadata.pk_indexes.is_empty());
+        assert_eq!(prepared.prepared_metadata.col_specs[0].name(), "pk");
+    }
+
+    #[test]
+    fn deserialize_prepared_v4_with_pk_indexes() {
+        let buf = serialized_prepared(true);
+        let prepared =
+            deser_prepared(&mut &buf[..], &Default::default(), ProtocolVersion::V4).unwrap();
+        assert_eq!(prepared.prepared_metadata.pk_indexes.len(), 1);
+        assert_eq!(prepared.prepared_metadata.col_specs[0].name(), "pk");
+    }
+
+    #[test]
+    fn deserialize_prepared_v4_with_scylla_metadata_id() {
+        let features = ProtocolFeatures {
+            scylla_metadata_id_supported: true,
+            ..Default::default()
+        };
+        let plain = serialized_prepared(true);
+        // The result metadata id follows the statement id.
+        let mut buf = plain[..5].to_vec();
+        types::write_short_bytes(&[9, 9], &mut buf).unwrap();
+        buf.extend_from_slice(&plain[5..]);
+
+        let prepared = deser_prepared(&mut &buf[..], &features, ProtocolVersion::V4).unwrap();
+        assert_eq!(&prepared.id[..], &[1, 2, 3]);
+        assert_eq!(prepared.result_metadata_id.as_deref(), Some(&[9, 9][..]));
+        assert_eq!(prepared.prepared_metadata.col_specs[0].name(), "pk");
+    }
+}
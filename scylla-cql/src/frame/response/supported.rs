@@ -1,6 +1,8 @@
 use crate::frame::frame_errors::CqlSupportedParseError;
 use crate::frame::types;
+use bytes::BufMut;
 use std::collections::HashMap;
+use std::num::TryFromIntError;
 
 #[derive(Debug)]
 pub struct Supported {
@@ -14,4 +16,8 @@ impl Supported {
 
         Ok(Supported { options })
     }
+
+    pub fn serialize(&self, buf: &mut impl BufMut) -> Result<(), TryFromIntError> {
+        types::write_string_multimap(&self.options, buf)
+    }
 }
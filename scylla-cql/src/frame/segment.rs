@@ -0,0 +1,478 @@
+//! Segment-based framing, introduced in CQL native protocol v5.
+//!
+//! After the handshake (once the server sends READY or AUTHENTICATE),
+//! frames are no longer written directly to the connection. Instead, they
+//! are wrapped in segments, which carry CRC24 checksum of their header and
+//! CRC32 checksum of their payload. Compression (only LZ4 is allowed)
+//! is applied per segment, not per frame.
+//!
+//! A segment is either self-contained - which means that it contains one
+//! or more complete frames - or it contains a part of a single frame which
+//! is too large to fit in one segment.
+//!
+//! See section 2 of the [specification](https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v5.spec)
+//! for details.
+
+use std::sync::Arc;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use super::frame_errors::{SegmentParseError, SegmentSerializationError};
+use super::{Compression, HEADER_SIZE};
+
+/// Maximum length of a segment payload (before compression).
+pub const MAX_SEGMENT_PAYLOAD_LENGTH: usize = (1 << 17) - 1;
+
+const UNCOMPRESSED_HEADER_LENGTH: usize = 3;
+const COMPRESSED_HEADER_LENGTH: usize = 5;
+const HEADER_CRC_LENGTH: usize = 3;
+const PAYLOAD_CRC_LENGTH: usize = 4;
+
+const CRC24_INIT: u32 = 0x875060;
+const CRC24_POLY: u32 = 0x1974F0B;
+
+// The payload CRC32 is computed as if these bytes preceded the payload.
+const CRC32_INITIAL_BYTES: [u8; 4] = [0xFA, 0x2D, 0x55, 0xCA];
+
+/// Computes the CRC24 checksum of a segment header, as defined in the v5 spec.
+pub fn crc24(bytes: &[u8]) -> u32 {
+    let mut crc = CRC24_INIT;
+    for &byte in bytes {
+        crc ^= (byte as u32) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x1000000 != 0 {
+                crc ^= CRC24_POLY;
+            }
+        }
+    }
+    crc & 0xFFFFFF
+}
+
+/// Computes the CRC32 checksum of a segment payload, as defined in the v5 spec.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(&CRC32_INITIAL_BYTES);
+    hasher.update(bytes);
+    hasher.finalize()
+}
+
+fn header_length(compression: Option<Compression>) -> usize {
+    match compression {
+        Some(_) => COMPRESSED_HEADER_LENGTH,
+        None => UNCOMPRESSED_HEADER_LENGTH,
+    }
+}
+
+/// A single segment received from the server, with its payload already
+/// verified and decompressed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    /// Payload of the segment: one or more frames, or a part of a frame.
+    pub payload: Bytes,
+
+    /// Whether the payload consists of complete frames.
+    pub self_contained: bool,
+}
+
+/// Serializes a single segment and appends it to `out`.
+///
+/// `compression` must be the one negotiated for the connection. If the compressed
+/// payload would not be smaller than the original, it is sent uncompressed,
+/// as permitted by the specification.
+pub fn write_segment(
+    payload: &[u8],
+    self_contained: bool,
+    compression: Option<Compression>,
+    out: &mut Vec<u8>,
+) -> Result<(), SegmentSerializationError> {
+    if payload.len() > MAX_SEGMENT_PAYLOAD_LENGTH {
+        return Err(SegmentSerializationError::PayloadTooLarge(payload.len()));
+    }
+    let self_contained_bit = self_contained as u64;
+
+    match compression {
+        None => {
+            let header = payload.len() as u64 | self_contained_bit << 17;
+            write_header(header, UNCOMPRESSED_HEADER_LENGTH, out);
+            write_payload(payload, out);
+        }
+        Some(Compression::Lz4) => {
+            let compressed = lz4_flex::compress(payload);
+            // Uncompressed length of 0 means that the payload is not compressed.
+            let (payload, uncompressed_length) = if compressed.len() < payload.len() {
+                (&compressed[..], payload.len())
+            } else {
+                (payload, 0)
+            };
+            let header = payload.len() as u64
+                | (uncompressed_length as u64) << 17
+                | self_contained_bit << 34;
+            write_header(header, COMPRESSED_HEADER_LENGTH, out);
+            write_payload(payload, out);
+        }
+        Some(compression @ Compression::Snappy) => {
+            return Err(SegmentSerializationError::UnsupportedCompression(
+                compression,
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+fn write_header(header: u64, header_length: usize, out: &mut Vec<u8>) {
+    let header_bytes = &header.to_le_bytes()[..header_length];
+    out.put_slice(header_bytes);
+    out.put_slice(&crc24(header_bytes).to_le_bytes()[..HEADER_CRC_LENGTH]);
+}
+
+fn write_payload(payload: &[u8], out: &mut Vec<u8>) {
+    out.put_slice(payload);
+    out.put_u32_le(crc32(payload));
+}
+
+/// Reads a single segment from the stream, verifies its checksums
+/// and decompresses it, if needed.
+pub async fn read_segment(
+    reader: &mut (impl AsyncRead + Unpin),
+    compression: Option<Compression>,
+) -> Result<Segment, SegmentParseError> {
+    if let Some(compression @ Compression::Snappy) = compression {
+        return Err(SegmentParseError::UnsupportedCompression(compression));
+    }
+
+    let header_length = header_length(compression);
+    let mut raw_header = [0u8; COMPRESSED_HEADER_LENGTH + HEADER_CRC_LENGTH];
+    let raw_header = &mut raw_header[..header_length + HEADER_CRC_LENGTH];
+    reader
+        .read_exact(raw_header)
+        .await
+        .map_err(|err| SegmentParseError::HeaderIoError(Arc::new(err)))?;
+
+    let (header_bytes, crc_bytes) = raw_header.split_at(header_length);
+    let received_crc = read_le_uint(crc_bytes) as u32;
+    let computed_crc = crc24(header_bytes);
+    if received_crc != computed_crc {
+        return Err(SegmentParseError::HeaderChecksumMismatch {
+            received: received_crc,
+            computed: computed_crc,
+        });
+    }
+
+    let header = read_le_uint(header_bytes);
+    let payload_length = (header & 0x1FFFF) as usize;
+    let (uncompressed_length, self_contained) = match compression {
+        Some(_) => (((header >> 17) & 0x1FFFF) as usize, header & (1 << 34) != 0),
+        None => (0, header & (1 << 17) != 0),
+    };
+
+    let mut raw_payload = vec![0u8; payload_length + PAYLOAD_CRC_LENGTH];
+    reader
+        .read_exact(&mut raw_payload)
+        .await
+        .map_err(|err| SegmentParseError::PayloadIoError(Arc::new(err)))?;
+
+    let (payload, crc_bytes) = raw_payload.split_at(payload_length);
+    let received_crc = read_le_uint(crc_bytes) as u32;
+    let computed_crc = crc32(payload);
+    if received_crc != computed_crc {
+        return Err(SegmentParseError::PayloadChecksumMismatch {
+            received: received_crc,
+            computed: computed_crc,
+        });
+    }
+
+    let payload = if uncompressed_length != 0 {
+        lz4_flex::decompress(payload, uncompressed_length)
+            .map_err(|err| SegmentParseError::Lz4DecompressError(Arc::new(err)))?
+    } else {
+        raw_payload.truncate(payload_length);
+        raw_payload
+    };
+
+    Ok(Segment {
+        payload: payload.into(),
+        self_contained,
+    })
+}
+
+fn read_le_uint(bytes: &[u8]) -> u64 {
+    let mut buf = [0u8; 8];
+    buf[..bytes.len()].copy_from_slice(bytes);
+    u64::from_le_bytes(buf)
+}
+
+/// Wraps outgoing frames into segments.
+///
+/// Small frames are coalesced into a single self-contained segment until
+/// [SegmentEncoder::flush] is called or the segment is full. Frames that do not fit
+/// in a single segment are split into multiple non-self-contained segments.
+#[derive(Debug)]
+pub struct SegmentEncoder {
+    compression: Option<Compression>,
+    pending: Vec<u8>,
+}
+
+impl SegmentEncoder {
+    /// Creates an encoder which uses the compression negotiated for the connection.
+    pub fn new(compression: Option<Compression>) -> Self {
+        Self {
+            compression,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Adds a serialized frame to be sent. Segments which are complete
+    /// are appended to `out`.
+    pub fn push_frame(
+        &mut self,
+        frame: &[u8],
+        out: &mut Vec<u8>,
+    ) -> Result<(), SegmentSerializationError> {
+        if self.pending.len() + frame.len() > MAX_SEGMENT_PAYLOAD_LENGTH {
+            self.flush(out)?;
+        }
+
+        if frame.len() <= MAX_SEGMENT_PAYLOAD_LENGTH {
+            self.pending.extend_from_slice(frame);
+        } else {
+            for chunk in frame.chunks(MAX_SEGMENT_PAYLOAD_LENGTH) {
+                write_segment(chunk, false, self.compression, out)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Appends a self-contained segment with all pending frames to `out`.
+    pub fn flush(&mut self, out: &mut Vec<u8>) -> Result<(), SegmentSerializationError> {
+        if !self.pending.is_empty() {
+            let result = write_segment(&self.pending, true, self.compression, out);
+            self.pending.clear();
+            result?;
+        }
+        Ok(())
+    }
+}
+
+/// Extracts frames from incoming segments.
+#[derive(Debug, Default)]
+pub struct SegmentDecoder {
+    // Parts of a frame spanning multiple segments, received so far.
+    partial_frame: BytesMut,
+}
+
+impl SegmentDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Processes a segment and appends all frames completed by it to `frames`.
+    ///
+    /// The frames are complete, i.e. they consist of a header and the body,
+    /// and can be parsed with [parse_response_frame](super::parse_response_frame).
+    pub fn push_segment(
+        &mut self,
+        segment: Segment,
+        frames: &mut Vec<Bytes>,
+    ) -> Result<(), SegmentParseError> {
+        let Segment {
+            mut payload,
+            self_contained,
+        } = segment;
+
+        if self_contained {
+            if !self.partial_frame.is_empty() {
+                return Err(SegmentParseError::UnexpectedSelfContainedSegment);
+            }
+            while !payload.is_empty() {
+                let frame_length = frame_length(&payload)
+                    .filter(|length| *length <= payload.len())
+                    .ok_or(SegmentParseError::IncompleteFrameInSelfContainedSegment)?;
+                frames.push(payload.split_to(frame_length));
+            }
+        } else {
+            self.partial_frame.extend_from_slice(&payload);
+            if let Some(frame_length) = frame_length(&self.partial_frame) {
+                match self.partial_frame.len().cmp(&frame_length) {
+                    std::cmp::Ordering::Less => {}
+                    std::cmp::Ordering::Equal => {
+                        frames.push(std::mem::take(&mut self.partial_frame).freeze());
+                    }
+                    std::cmp::Ordering::Greater => {
+                        return Err(SegmentParseError::MultipleFramesInNonSelfContainedSegment)
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Returns the length of the whole frame (header included), if the header is complete.
+fn frame_length(buf: &[u8]) -> Option<usize> {
+    let mut length_bytes = buf.get(5..HEADER_SIZE)?;
+    Some(HEADER_SIZE + length_bytes.get_u32() as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{
+        crc24, read_segment, write_segment, Segment, SegmentDecoder, SegmentEncoder,
+        MAX_SEGMENT_PAYLOAD_LENGTH,
+    };
+    use crate::frame::frame_errors::SegmentParseError;
+    use crate::frame::{Compression, HEADER_SIZE};
+
+    fn make_frame(stream: i16, body_len: usize) -> Vec<u8> {
+        let mut frame = vec![0x85, 0x00];
+        frame.extend_from_slice(&stream.to_be_bytes());
+        frame.push(0x08);
+        frame.extend_from_slice(&(body_len as u32).to_be_bytes());
+        frame.extend((0..body_len).map(|i| (i % 251) as u8));
+        frame
+    }
+
+    async fn read_all_segments(mut buf: &[u8], compression: Option<Compression>) -> Vec<Segment> {
+        let mut segments = Vec::new();
+        while !buf.is_empty() {
+            segments.push(read_segment(&mut buf, compression).await.unwrap());
+        }
+        segments
+    }
+
+    #[test]
+    fn test_crc24() {
+        assert!(crc24(&[0xFF; 5]) <= 0xFFFFFF);
+        assert_ne!(crc24(&[0x00, 0x00, 0x02]), crc24(&[0x00, 0x00, 0x03]));
+        // Empty input yields the initial value.
+        assert_eq!(crc24(&[]), 0x875060);
+    }
+
+    #[tokio::test]
+    async fn test_segment_roundtrip() {
+        for compression in [None, Some(Compression::Lz4)] {
+            for payload in [
+                Vec::new(),
+                b"Hello, World!".to_vec(),
+                "Hello, World!".repeat(1000).into_bytes(),
+            ] {
+                let mut buf = Vec::new();
+                write_segment(&payload, true, compression, &mut buf).unwrap();
+                let segment = read_segment(&mut &buf[..], compression).await.unwrap();
+                assert_eq!(
+                    segment,
+                    Segment {
+                        payload: payload.into(),
+                        self_contained: true
+                    }
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_segment_lz4_compresses_payload() {
+        let payload = "Hello, World!".repeat(1000);
+        let mut buf = Vec::new();
+        write_segment(payload.as_bytes(), true, Some(Compression::Lz4), &mut buf).unwrap();
+        assert!(buf.len() < payload.len());
+    }
+
+    #[tokio::test]
+    async fn test_segment_corruption_is_detected() {
+        let mut buf = Vec::new();
+        write_segment(b"Hello, World!", true, None, &mut buf).unwrap();
+
+        let mut corrupted_header = buf.clone();
+        corrupted_header[0] ^= 0x01;
+        assert_matches::assert_matches!(
+            read_segment(&mut &corrupted_header[..], None).await,
+            Err(SegmentParseError::HeaderChecksumMismatch { .. })
+        );
+
+        let mut corrupted_payload = buf.clone();
+        corrupted_payload[7] ^= 0x01;
+        assert_matches::assert_matches!(
+            read_segment(&mut &corrupted_payload[..], None).await,
+            Err(SegmentParseError::PayloadChecksumMismatch { .. })
+        );
+    }
+
+    #[test]
+    fn test_segment_payload_too_large() {
+        let payload = vec![0u8; MAX_SEGMENT_PAYLOAD_LENGTH + 1];
+        write_segment(&payload, true, None, &mut Vec::new()).unwrap_err();
+        write_segment(&payload, true, Some(Compression::Snappy), &mut Vec::new()).unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_encoder_decoder_roundtrip() {
+        for compression in [None, Some(Compression::Lz4)] {
+            let frames = [
+                make_frame(1, 0),
+                make_frame(2, 100),
+                make_frame(3, MAX_SEGMENT_PAYLOAD_LENGTH - HEADER_SIZE),
+                make_frame(4, 3 * MAX_SEGMENT_PAYLOAD_LENGTH),
+                make_frame(5, 10),
+            ];
+
+            let mut encoder = SegmentEncoder::new(compression);
+            let mut buf = Vec::new();
+            for frame in &frames {
+                encoder.push_frame(frame, &mut buf).unwrap();
+            }
+            encoder.flush(&mut buf).unwrap();
+
+            let segments = read_all_segments(&buf, compression).await;
+            // [1, 2], [3], 4 chunks of frame 4, [5]
+            assert_eq!(segments.len(), 7);
+
+            let mut decoder = SegmentDecoder::new();
+            let mut decoded = Vec::new();
+            for segment in segments {
+                decoder.push_segment(segment, &mut decoded).unwrap();
+            }
+            let expected: Vec<Bytes> = frames.into_iter().map(Bytes::from).collect();
+            assert_eq!(decoded, expected);
+        }
+    }
+
+    #[test]
+    fn test_decoder_rejects_malformed_segments() {
+        let frame = Bytes::from(make_frame(1, 10));
+
+        // Self-contained segment with a truncated frame.
+        let mut decoder = SegmentDecoder::new();
+        assert_matches::assert_matches!(
+            decoder.push_segment(
+                Segment {
+                    payload: frame.slice(..frame.len() - 1),
+                    self_contained: true,
+                },
+                &mut Vec::new(),
+            ),
+            Err(SegmentParseError::IncompleteFrameInSelfContainedSegment)
+        );
+
+        // Non-self-contained segment with more than one frame.
+        let mut two_frames = frame.to_vec();
+        two_frames.extend_from_slice(&frame);
+        let mut decoder = SegmentDecoder::new();
+        assert_matches::assert_matches!(
+            decoder.push_segment(
+                Segment {
+                    payload: two_frames.into(),
+                    self_contained: false,
+                },
+                &mut Vec::new(),
+            ),
+            Err(SegmentParseError::MultipleFramesInNonSelfContainedSegment)
+        );
+    }
+}
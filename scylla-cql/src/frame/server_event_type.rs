@@ -6,6 +6,7 @@ use super::frame_errors::CqlEventParseError;
 // Check triggers because all variants end with "Change".
 // TODO(2.0): Remove the "Change" postfix from variants.
 #[expect(clippy::enum_variant_names)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EventType {
     TopologyChange,
     StatusChange,
@@ -571,7 +571,7 @@ fn type_consistency() {
     assert!(err_str.contains(&format!("{}", c)));
 }
 
-pub fn read_inet(buf: &mut &[u8]) -> Result<SocketAddr, LowLevelDeserializationError> {
+pub fn read_inetaddr(buf: &mut &[u8]) -> Result<IpAddr, LowLevelDeserializationError> {
     let len = buf.read_u8()?;
     let ip_addr = match len {
         4 => {
@@ -586,13 +586,12 @@ pub fn read_inet(buf: &mut &[u8]) -> Result<SocketAddr, LowLevelDeserializationE
         }
         v => return Err(LowLevelDeserializationError::InvalidInetLength(v)),
     };
-    let port = read_int(buf)?;
 
-    Ok(SocketAddr::new(ip_addr, port as u16))
+    Ok(ip_addr)
 }
 
-pub fn write_inet(addr: SocketAddr, buf: &mut impl BufMut) {
-    match addr.ip() {
+pub fn write_inetaddr(addr: IpAddr, buf: &mut impl BufMut) {
+    match addr {
         IpAddr::V4(v4) => {
             buf.put_u8(4);
             buf.put_slice(&v4.octets());
@@ -602,7 +601,17 @@ pub fn write_inet(addr: SocketAddr, buf: &mut impl BufMut) {
             buf.put_slice(&v6.octets());
         }
     }
+}
+
+pub fn read_inet(buf: &mut &[u8]) -> Result<SocketAddr, LowLevelDeserializationError> {
+    let ip_addr = read_inetaddr(buf)?;
+    let port = read_int(buf)?;
+
+    Ok(SocketAddr::new(ip_addr, port as u16))
+}
 
+pub fn write_inet(addr: SocketAddr, buf: &mut impl BufMut) {
+    write_inetaddr(addr.ip(), buf);
     write_int(addr.port() as i32, buf)
 }
 
@@ -2,10 +2,12 @@ pub(crate) mod pretty;
 
 pub mod frame;
 
+pub use scylla_macros::query;
 pub use scylla_macros::DeserializeRow;
 pub use scylla_macros::DeserializeValue;
 pub use scylla_macros::SerializeRow;
 pub use scylla_macros::SerializeValue;
+pub use scylla_macros::Table;
 
 pub mod deserialize;
 pub mod serialize;
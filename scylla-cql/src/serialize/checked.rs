@@ -0,0 +1,265 @@
+//! Compile-time compatibility of Rust types with CQL types.
+//!
+//! The `query!` macro (available in the `scylla` crate) knows the CQL type of
+//! every bind marker of the checked statement, and uses the [`SerializeAs`]
+//! trait to verify at compile time that the bound Rust values can be
+//! serialized to those types. CQL types are represented by the marker types
+//! from the [`cql`] module.
+//!
+//! The trait is implemented for the same Rust types as [`SerializeValue`](super::value::SerializeValue),
+//! and by the `SerializeValue` derive macro. For custom implementations of
+//! `SerializeValue`, `SerializeAs` has to be implemented by hand - it is a marker
+//! trait without any methods.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::net::IpAddr;
+
+use uuid::Uuid;
+
+use crate::value::{
+    Counter, CqlDate, CqlDecimal, CqlDecimalBorrowed, CqlDuration, CqlTime, CqlTimestamp,
+    CqlTimeuuid, CqlValue, CqlVarint, CqlVarintBorrowed, MaybeUnset, Unset,
+};
+
+/// Marker types representing CQL types in the [`SerializeAs`] trait.
+pub mod cql {
+    use std::marker::PhantomData;
+
+    macro_rules! native_markers {
+        ($($name:ident => $cql:literal),* $(,)?) => {
+            $(
+                #[doc = concat!("The CQL `", $cql, "` type.")]
+                #[derive(Debug, Clone, Copy)]
+                pub enum $name {}
+            )*
+        };
+    }
+
+    native_markers!(
+        Ascii => "ascii",
+        Boolean => "boolean",
+        Blob => "blob",
+        Counter => "counter",
+        Date => "date",
+        Decimal => "decimal",
+        Double => "double",
+        Duration => "duration",
+        Float => "float",
+        Int => "int",
+        BigInt => "bigint",
+        Text => "text",
+        Timestamp => "timestamp",
+        Inet => "inet",
+        SmallInt => "smallint",
+        TinyInt => "tinyint",
+        Time => "time",
+        Timeuuid => "timeuuid",
+        Uuid => "uuid",
+        Varint => "varint",
+        Udt => "user defined type",
+    );
+
+    /// The CQL `list<T>` type.
+    #[derive(Debug, Clone, Copy)]
+    pub struct List<T>(PhantomData<T>);
+
+    /// The CQL `set<T>` type.
+    #[derive(Debug, Clone, Copy)]
+    pub struct Set<T>(PhantomData<T>);
+
+    /// The CQL `map<K, V>` type.
+    #[derive(Debug, Clone, Copy)]
+    pub struct Map<K, V>(PhantomData<(K, V)>);
+
+    /// The CQL `vector<T, N>` type.
+    #[derive(Debug, Clone, Copy)]
+    pub struct Vector<T>(PhantomData<T>);
+
+    /// The CQL `tuple<...>` type. `T` is a Rust tuple of the element markers.
+    #[derive(Debug, Clone, Copy)]
+    pub struct Tuple<T>(PhantomData<T>);
+}
+
+/// A Rust type that can be serialized to the CQL type represented by `C`,
+/// one of the marker types from the [`cql`] module.
+///
+/// This is a marker trait used for compile-time checks of bind values.
+/// The actual serialization is performed by [`SerializeValue`](super::value::SerializeValue).
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be bound to a value of CQL type `{C}`",
+    label = "incompatible with the type of the bind marker",
+    note = "If `{Self}` has a custom `SerializeValue` implementation, implement `SerializeAs` for it as well"
+)]
+pub trait SerializeAs<C> {}
+
+macro_rules! impl_serialize_as {
+    ($cql:ident: $($t:ty),* $(,)?) => {
+        $(impl SerializeAs<cql::$cql> for $t {})*
+    };
+}
+
+impl_serialize_as!(TinyInt: i8);
+impl_serialize_as!(SmallInt: i16);
+impl_serialize_as!(Int: i32);
+impl_serialize_as!(BigInt: i64);
+impl_serialize_as!(Float: f32);
+impl_serialize_as!(Double: f64);
+impl_serialize_as!(Boolean: bool);
+impl_serialize_as!(Ascii: String, str);
+impl_serialize_as!(Text: String, str);
+impl_serialize_as!(Blob: Vec<u8>, [u8]);
+impl_serialize_as!(Uuid: Uuid);
+impl_serialize_as!(Timeuuid: CqlTimeuuid);
+impl_serialize_as!(Inet: IpAddr);
+impl_serialize_as!(Date: CqlDate);
+impl_serialize_as!(Timestamp: CqlTimestamp);
+impl_serialize_as!(Time: CqlTime);
+impl_serialize_as!(Decimal: CqlDecimal, CqlDecimalBorrowed<'_>);
+impl_serialize_as!(Varint: CqlVarint, CqlVarintBorrowed<'_>);
+impl_serialize_as!(Counter: Counter);
+impl_serialize_as!(Duration: CqlDuration);
+
+#[cfg(feature = "chrono-04")]
+impl_serialize_as!(Date: chrono_04::NaiveDate);
+#[cfg(feature = "chrono-04")]
+impl_serialize_as!(Timestamp: chrono_04::DateTime<chrono_04::Utc>);
+#[cfg(feature = "chrono-04")]
+impl_serialize_as!(Time: chrono_04::NaiveTime);
+
+#[cfg(feature = "time-03")]
+impl_serialize_as!(Date: time_03::Date);
+#[cfg(feature = "time-03")]
+impl_serialize_as!(Timestamp: time_03::OffsetDateTime);
+#[cfg(feature = "time-03")]
+impl_serialize_as!(Time: time_03::Time);
+
+#[cfg(feature = "bigdecimal-04")]
+impl_serialize_as!(Decimal: bigdecimal_04::BigDecimal);
+
+#[cfg(feature = "num-bigint-03")]
+impl_serialize_as!(Varint: num_bigint_03::BigInt);
+#[cfg(feature = "num-bigint-04")]
+impl_serialize_as!(Varint: num_bigint_04::BigInt);
+
+impl<const N: usize> SerializeAs<cql::Blob> for [u8; N] {}
+
+// Wrappers which serialize to the same type as the wrapped value.
+impl<C, T: SerializeAs<C> + ?Sized> SerializeAs<C> for &T {}
+impl<C, T: SerializeAs<C> + ?Sized> SerializeAs<C> for Box<T> {}
+impl<C, T: SerializeAs<C>> SerializeAs<C> for Option<T> {}
+impl<C, T: SerializeAs<C>> SerializeAs<C> for MaybeUnset<T> {}
+#[cfg(feature = "secrecy-08")]
+impl<C, T: SerializeAs<C> + secrecy_08::Zeroize> SerializeAs<C> for secrecy_08::Secret<T> {}
+
+// Values whose compatibility can only be checked at runtime.
+impl<C> SerializeAs<C> for Unset {}
+impl<C> SerializeAs<C> for CqlValue {}
+
+impl<C, T: SerializeAs<C>> SerializeAs<cql::List<C>> for Vec<T> {}
+impl<C, T: SerializeAs<C>> SerializeAs<cql::List<C>> for [T] {}
+impl<C, T: SerializeAs<C>, const N: usize> SerializeAs<cql::List<C>> for [T; N] {}
+
+impl<C, T: SerializeAs<C>> SerializeAs<cql::Set<C>> for Vec<T> {}
+impl<C, T: SerializeAs<C>> SerializeAs<cql::Set<C>> for [T] {}
+impl<C, T: SerializeAs<C>, const N: usize> SerializeAs<cql::Set<C>> for [T; N] {}
+impl<C, T: SerializeAs<C>, S> SerializeAs<cql::Set<C>> for HashSet<T, S> {}
+impl<C, T: SerializeAs<C>> SerializeAs<cql::Set<C>> for BTreeSet<T> {}
+
+impl<CK, CV, K: SerializeAs<CK>, V: SerializeAs<CV>, S> SerializeAs<cql::Map<CK, CV>>
+    for HashMap<K, V, S>
+{
+}
+impl<CK, CV, K: SerializeAs<CK>, V: SerializeAs<CV>> SerializeAs<cql::Map<CK, CV>>
+    for BTreeMap<K, V>
+{
+}
+
+impl<C, T: SerializeAs<C>> SerializeAs<cql::Vector<C>> for Vec<T> {}
+impl<C, T: SerializeAs<C>> SerializeAs<cql::Vector<C>> for [T] {}
+impl<C, T: SerializeAs<C>, const N: usize> SerializeAs<cql::Vector<C>> for [T; N] {}
+
+macro_rules! impl_tuples {
+    ($(($($c:ident: $t:ident),+)),* $(,)?) => {
+        $(
+            impl<$($c, $t: SerializeAs<$c>),+> SerializeAs<cql::Tuple<($($c,)+)>> for ($($t,)+) {}
+        )*
+    };
+}
+
+impl_tuples!(
+    (C0: T0),
+    (C0: T0, C1: T1),
+    (C0: T0, C1: T1, C2: T2),
+    (C0: T0, C1: T1, C2: T2, C3: T3),
+    (C0: T0, C1: T1, C2: T2, C3: T3, C4: T4),
+    (C0: T0, C1: T1, C2: T2, C3: T3, C4: T4, C5: T5),
+    (C0: T0, C1: T1, C2: T2, C3: T3, C4: T4, C5: T5, C6: T6),
+    (C0: T0, C1: T1, C2: T2, C3: T3, C4: T4, C5: T5, C6: T6, C7: T7),
+    (C0: T0, C1: T1, C2: T2, C3: T3, C4: T4, C5: T5, C6: T6, C7: T7, C8: T8),
+    (C0: T0, C1: T1, C2: T2, C3: T3, C4: T4, C5: T5, C6: T6, C7: T7, C8: T8, C9: T9),
+    (C0: T0, C1: T1, C2: T2, C3: T3, C4: T4, C5: T5, C6: T6, C7: T7, C8: T8, C9: T9, C10: T10),
+    (C0: T0, C1: T1, C2: T2, C3: T3, C4: T4, C5: T5, C6: T6, C7: T7, C8: T8, C9: T9, C10: T10, C11: T11),
+    (C0: T0, C1: T1, C2: T2, C3: T3, C4: T4, C5: T5, C6: T6, C7: T7, C8: T8, C9: T9, C10: T10, C11: T11, C12: T12),
+    (C0: T0, C1: T1, C2: T2, C3: T3, C4: T4, C5: T5, C6: T6, C7: T7, C8: T8, C9: T9, C10: T10, C11: T11, C12: T12, C13: T13),
+    (C0: T0, C1: T1, C2: T2, C3: T3, C4: T4, C5: T5, C6: T6, C7: T7, C8: T8, C9: T9, C10: T10, C11: T11, C12: T12, C13: T13, C14: T14),
+    (C0: T0, C1: T1, C2: T2, C3: T3, C4: T4, C5: T5, C6: T6, C7: T7, C8: T8, C9: T9, C10: T10, C11: T11, C12: T12, C13: T13, C14: T14, C15: T15),
+);
+
+/// Checks at compile time that `T` can be serialized to the CQL type `C`.
+///
+/// Implementation detail of the `query!` macro.
+#[doc(hidden)]
+#[inline(always)]
+pub fn check_serialize_as<C, T: SerializeAs<C> + ?Sized>(_value: &T) {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashSet};
+
+    use super::{check_serialize_as, cql};
+    use crate::value::{CqlTimestamp, MaybeUnset};
+    use crate::SerializeValue;
+
+    #[test]
+    fn test_serialize_as_compatible_types() {
+        check_serialize_as::<cql::Int, _>(&42_i32);
+        check_serialize_as::<cql::Text, _>("text");
+        check_serialize_as::<cql::Ascii, _>(&String::new());
+        check_serialize_as::<cql::Blob, _>(&[1_u8, 2, 3]);
+        check_serialize_as::<cql::Timestamp, _>(&Some(CqlTimestamp(0)));
+        check_serialize_as::<cql::BigInt, _>(&MaybeUnset::Set(1_i64));
+        check_serialize_as::<cql::List<cql::Text>, _>(&vec!["a", "b"]);
+        check_serialize_as::<cql::Set<cql::Int>, _>(&HashSet::<i32>::new());
+        check_serialize_as::<cql::Map<cql::Text, cql::List<cql::Double>>, _>(&BTreeMap::<
+            String,
+            Vec<f64>,
+        >::new());
+        check_serialize_as::<cql::Tuple<(cql::Int, cql::Text)>, _>(&(1_i32, "a"));
+        check_serialize_as::<cql::Vector<cql::Float>, _>(&[1.0_f32; 3]);
+    }
+
+    #[test]
+    fn test_serialize_as_derived_types() {
+        #[derive(SerializeValue)]
+        #[scylla(crate = crate)]
+        struct Address {
+            street: String,
+        }
+
+        #[derive(SerializeValue)]
+        #[scylla(crate = crate, repr = "tinyint")]
+        enum Priority {
+            Low,
+        }
+
+        #[derive(SerializeValue)]
+        #[scylla(crate = crate, transparent)]
+        struct UserId(i64);
+
+        check_serialize_as::<cql::Udt, _>(&Address {
+            street: String::new(),
+        });
+        check_serialize_as::<cql::TinyInt, _>(&Priority::Low);
+        check_serialize_as::<cql::List<cql::BigInt>, _>(&vec![UserId(1)]);
+    }
+}
@@ -7,8 +7,11 @@ use std::{error::Error, sync::Arc};
 use thiserror::Error;
 
 pub mod batch;
+pub mod checked;
 pub mod raw_batch;
 pub mod row;
+#[cfg(feature = "serde")]
+pub mod serde_bridge;
 pub mod value;
 pub mod writers;
 
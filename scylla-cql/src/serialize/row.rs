@@ -548,6 +548,11 @@ impl SerializedValues {
         self.serialized_values.len()
     }
 
+    // Checks whether any of the values is unset, which protocol v3 can't express.
+    pub(crate) fn contains_unset(&self) -> bool {
+        contains_unset(&self.serialized_values)
+    }
+
     pub(crate) fn write_to_request(&self, buf: &mut impl BufMut) {
         buf.put_u16(self.element_count);
         buf.put(self.serialized_values.as_slice())
@@ -606,6 +611,12 @@ impl Default for SerializedValues {
     }
 }
 
+// Checks whether any of the values serialized into `serialized_values`,
+// without the preceding u16 length, is unset.
+pub(crate) fn contains_unset(serialized_values: &[u8]) -> bool {
+    SerializedValuesIterator { serialized_values }.any(|value| matches!(value, RawValue::Unset))
+}
+
 /// An iterator over raw values in some [`SerializedValues`].
 #[derive(Clone, Copy)]
 pub struct SerializedValuesIterator<'a> {
@@ -669,6 +680,26 @@ mod doctests {
     /// }
     /// ```
     fn _test_struct_deserialization_rename_collision_with_another_rename() {}
+
+    /// ```compile_fail
+    ///
+    /// #[derive(scylla_macros::SerializeRow)]
+    /// #[scylla(crate = scylla_cql)]
+    /// struct Inner {
+    ///     b: i32,
+    /// }
+    ///
+    /// mod codec {}
+    ///
+    /// #[derive(scylla_macros::SerializeRow)]
+    /// #[scylla(crate = scylla_cql)]
+    /// struct TestRow {
+    ///     a: i32,
+    ///     #[scylla(flatten, with = "codec")]
+    ///     inner: Inner,
+    /// }
+    /// ```
+    fn _test_struct_serialization_flatten_conflicts_with_with() {}
 }
 
 #[cfg(test)]
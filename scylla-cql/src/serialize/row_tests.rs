@@ -1206,3 +1206,53 @@ fn test_ordered_flatten_skip_name_check_with_lifetimes() {
 
     assert_eq!(reference, row);
 }
+
+#[test]
+fn test_row_serialization_with_custom_field_codec() {
+    use crate::deserialize::value::tests::u32_as_text;
+
+    #[derive(SerializeRow)]
+    #[scylla(crate = crate)]
+    struct TestRow {
+        #[scylla(with = u32_as_text)]
+        a: u32,
+        b: i32,
+    }
+
+    #[derive(SerializeRow)]
+    #[scylla(crate = crate, flavor = "enforce_order")]
+    struct TestOrderedRow {
+        #[scylla(with = "u32_as_text")]
+        a: u32,
+        b: i32,
+    }
+
+    let spec = [
+        col("b", ColumnType::Native(NativeType::Int)),
+        col("a", ColumnType::Native(NativeType::Text)),
+    ];
+    let reference = do_serialize((7i32, "42"), &spec);
+    let row = do_serialize(TestRow { a: 42, b: 7 }, &spec);
+    assert_eq!(reference, row);
+
+    let spec = [
+        col("a", ColumnType::Native(NativeType::Text)),
+        col("b", ColumnType::Native(NativeType::Int)),
+    ];
+    let reference = do_serialize(("42", 7i32), &spec);
+    let row = do_serialize(TestOrderedRow { a: 42, b: 7 }, &spec);
+    assert_eq!(reference, row);
+
+    // Errors from the custom serializer are reported for the column
+    let spec = [
+        col("b", ColumnType::Native(NativeType::Int)),
+        col("a", ColumnType::Native(NativeType::Int)),
+    ];
+    let err = do_serialize_err(TestRow { a: 42, b: 7 }, &spec);
+    let err = get_ser_err(&err);
+    assert_eq!(err.rust_name, std::any::type_name::<TestRow>());
+    assert_matches!(
+        err.kind,
+        BuiltinSerializationErrorKind::ColumnSerializationFailed { ref name, .. } if name == "a"
+    );
+}
@@ -0,0 +1,1184 @@
+//! Bridge between `serde` and the driver's serialization framework.
+//!
+//! Wrapping a type in [Serde] makes it serializable as a CQL value
+//! (with [SerializeValue]) or as a list of bound values (with [SerializeRow]),
+//! as long as it implements [serde::Serialize]. When serializing a row,
+//! the type must serialize into a struct or a map, whose keys are matched
+//! with the names of the bind markers.
+//!
+//! Serialization is driven by the CQL type of the target column, and follows
+//! the mapping described in the [deserialization counterpart](crate::deserialize::serde_bridge):
+//! e.g. an integer can be written to any integer column type (if it fits),
+//! as well as to a `varint`, `decimal`, `date`, `timestamp` or `time` column,
+//! a string can be written to a `text`, `ascii`, `uuid`, `timeuuid`, `inet`
+//! or `decimal` column, and a struct to a user defined type, a `map<text, ...>`
+//! or a `duration` column. `None` and unit values are written as null.
+
+use std::fmt::Display;
+use std::net::IpAddr;
+
+use serde::ser::{
+    Impossible, SerializeMap, SerializeSeq, SerializeStruct, SerializeTuple, SerializeTupleStruct,
+};
+use serde::Serialize;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::row::{RowSerializationContext, SerializeRow};
+use super::value::SerializeValue;
+use super::writers::{CellWriter, RowWriter, WrittenCellProof};
+use super::SerializationError;
+use crate::frame::response::result::{CollectionType, ColumnSpec, ColumnType, NativeType};
+use crate::value::{
+    strip_redundant_signed_be_bytes, Counter, CqlDate, CqlDecimal, CqlDuration, CqlTime,
+    CqlTimestamp, CqlTimeuuid, CqlValue, CqlVarint,
+};
+
+pub use crate::deserialize::serde_bridge::Serde;
+
+/// An error returned when serializing a value through [Serde].
+#[derive(Error, Debug, Clone)]
+#[error("Serde serialization failed: {0}")]
+pub struct SerdeSerializationError(String);
+
+impl serde::ser::Error for SerdeSerializationError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl SerdeSerializationError {
+    fn mismatch(rust_kind: &str, typ: &ColumnType) -> Self {
+        Self(format!("cannot serialize {rust_kind} as {typ:?}"))
+    }
+}
+
+impl<T: Serialize> SerializeValue for Serde<T> {
+    fn serialize<'b>(
+        &self,
+        typ: &ColumnType,
+        writer: CellWriter<'b>,
+    ) -> Result<WrittenCellProof<'b>, SerializationError> {
+        let value = self
+            .0
+            .serialize(ValueSerializer { typ })
+            .map_err(SerializationError::new)?;
+        match value {
+            Some(value) => <CqlValue as SerializeValue>::serialize(&value, typ, writer),
+            None => Ok(writer.set_null()),
+        }
+    }
+}
+
+impl<T: Serialize> SerializeRow for Serde<T> {
+    fn serialize(
+        &self,
+        ctx: &RowSerializationContext<'_>,
+        writer: &mut RowWriter,
+    ) -> Result<(), SerializationError> {
+        let columns = ctx.columns();
+        let values = self
+            .0
+            .serialize(RowSerializer { columns })
+            .map_err(SerializationError::new)?;
+
+        for column in columns {
+            let Some((_, value)) = values.iter().find(|(name, _)| name == column.name()) else {
+                return Err(SerializationError::new(SerdeSerializationError(format!(
+                    "value for column {} is missing",
+                    column.name()
+                ))));
+            };
+            let cell_writer = writer.make_cell_writer();
+            match value {
+                Some(value) => {
+                    <CqlValue as SerializeValue>::serialize(value, column.typ(), cell_writer)?;
+                }
+                None => {
+                    cell_writer.set_null();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn is_empty(&self) -> bool {
+        // Without any bind markers, serialization stops with an error at the first
+        // field, so only an empty struct or map (or a unit) serializes successfully.
+        matches!(
+            self.0.serialize(RowSerializer { columns: &[] }),
+            Ok(values) if values.is_empty()
+        )
+    }
+}
+
+/// Serializes a value into a [CqlValue] suitable for the given CQL type.
+/// `None` stands for null.
+struct ValueSerializer<'a, 'b> {
+    typ: &'a ColumnType<'b>,
+}
+
+impl<'a, 'b> ValueSerializer<'a, 'b> {
+    fn serialize_integer(&self, v: i128) -> Result<Option<CqlValue>, SerdeSerializationError> {
+        let out_of_range =
+            || SerdeSerializationError(format!("{v} is out of range of {:?}", self.typ));
+        let value = match self.typ {
+            ColumnType::Native(NativeType::TinyInt) => {
+                CqlValue::TinyInt(v.try_into().map_err(|_| out_of_range())?)
+            }
+            ColumnType::Native(NativeType::SmallInt) => {
+                CqlValue::SmallInt(v.try_into().map_err(|_| out_of_range())?)
+            }
+            ColumnType::Native(NativeType::Int) => {
+                CqlValue::Int(v.try_into().map_err(|_| out_of_range())?)
+            }
+            ColumnType::Native(NativeType::BigInt) => {
+                CqlValue::BigInt(v.try_into().map_err(|_| out_of_range())?)
+            }
+            ColumnType::Native(NativeType::Counter) => {
+                CqlValue::Counter(Counter(v.try_into().map_err(|_| out_of_range())?))
+            }
+            ColumnType::Native(NativeType::Varint) => {
+                CqlValue::Varint(CqlVarint::from_signed_bytes_be(i128_to_signed_be_bytes(v)))
+            }
+            ColumnType::Native(NativeType::Decimal) => CqlValue::Decimal(
+                CqlDecimal::from_signed_be_bytes_and_exponent(i128_to_signed_be_bytes(v), 0),
+            ),
+            ColumnType::Native(NativeType::Date) => {
+                let days: i64 = v.try_into().map_err(|_| out_of_range())?;
+                CqlValue::Date(CqlDate(
+                    (days + (1 << 31)).try_into().map_err(|_| out_of_range())?,
+                ))
+            }
+            ColumnType::Native(NativeType::Timestamp) => {
+                CqlValue::Timestamp(CqlTimestamp(v.try_into().map_err(|_| out_of_range())?))
+            }
+            ColumnType::Native(NativeType::Time) => {
+                CqlValue::Time(CqlTime(v.try_into().map_err(|_| out_of_range())?))
+            }
+            typ => return Err(SerdeSerializationError::mismatch("an integer", typ)),
+        };
+        Ok(Some(value))
+    }
+
+    fn serialize_float(&self, v: f64) -> Result<Option<CqlValue>, SerdeSerializationError> {
+        match self.typ {
+            ColumnType::Native(NativeType::Float) => Ok(Some(CqlValue::Float(v as f32))),
+            ColumnType::Native(NativeType::Double) => Ok(Some(CqlValue::Double(v))),
+            typ => Err(SerdeSerializationError::mismatch(
+                "a floating point number",
+                typ,
+            )),
+        }
+    }
+
+    fn sequence(self) -> Result<SeqSerializer<'a, 'b>, SerdeSerializationError> {
+        match self.typ {
+            ColumnType::Collection {
+                typ: CollectionType::List(_) | CollectionType::Set(_),
+                ..
+            }
+            | ColumnType::Vector { .. }
+            | ColumnType::Tuple(_) => Ok(SeqSerializer {
+                typ: self.typ,
+                elements: Vec::new(),
+            }),
+            typ => Err(SerdeSerializationError::mismatch("a sequence", typ)),
+        }
+    }
+
+    fn mapping(self) -> Result<MapSerializer<'a, 'b>, SerdeSerializationError> {
+        match self.typ {
+            ColumnType::Collection {
+                typ: CollectionType::Map(_, _),
+                ..
+            }
+            | ColumnType::UserDefinedType { .. }
+            | ColumnType::Native(NativeType::Duration) => Ok(MapSerializer {
+                typ: self.typ,
+                entries: Vec::new(),
+                next_key: None,
+            }),
+            typ => Err(SerdeSerializationError::mismatch("a map or a struct", typ)),
+        }
+    }
+}
+
+impl<'a, 'b> serde::Serializer for ValueSerializer<'a, 'b> {
+    type Ok = Option<CqlValue>;
+    type Error = SerdeSerializationError;
+
+    type SerializeSeq = SeqSerializer<'a, 'b>;
+    type SerializeTuple = SeqSerializer<'a, 'b>;
+    type SerializeTupleStruct = SeqSerializer<'a, 'b>;
+    type SerializeTupleVariant = Impossible<Self::Ok, Self::Error>;
+    type SerializeMap = MapSerializer<'a, 'b>;
+    type SerializeStruct = MapSerializer<'a, 'b>;
+    type SerializeStructVariant = Impossible<Self::Ok, Self::Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        match self.typ {
+            ColumnType::Native(NativeType::Boolean) => Ok(Some(CqlValue::Boolean(v))),
+            typ => Err(SerdeSerializationError::mismatch("a bool", typ)),
+        }
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        self.serialize_integer(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        self.serialize_integer(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        self.serialize_integer(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        self.serialize_integer(v.into())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        self.serialize_integer(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        self.serialize_integer(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        self.serialize_integer(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        self.serialize_integer(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        self.serialize_integer(v.into())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        let v = i128::try_from(v)
+            .map_err(|_| SerdeSerializationError(format!("{v} does not fit in 128 bits")))?;
+        self.serialize_integer(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        self.serialize_float(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        self.serialize_float(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        let invalid = |what: &str| SerdeSerializationError(format!("{v:?} is not a valid {what}"));
+        let value = match self.typ {
+            ColumnType::Native(NativeType::Ascii) => CqlValue::Ascii(v.to_owned()),
+            ColumnType::Native(NativeType::Text) => CqlValue::Text(v.to_owned()),
+            ColumnType::Native(NativeType::Uuid) => {
+                CqlValue::Uuid(Uuid::parse_str(v).map_err(|_| invalid("uuid"))?)
+            }
+            ColumnType::Native(NativeType::Timeuuid) => CqlValue::Timeuuid(CqlTimeuuid::from(
+                Uuid::parse_str(v).map_err(|_| invalid("timeuuid"))?,
+            )),
+            ColumnType::Native(NativeType::Inet) => {
+                CqlValue::Inet(v.parse::<IpAddr>().map_err(|_| invalid("inet"))?)
+            }
+            ColumnType::Native(NativeType::Decimal) => {
+                CqlValue::Decimal(parse_decimal(v).ok_or_else(|| invalid("decimal"))?)
+            }
+            typ => return Err(SerdeSerializationError::mismatch("a string", typ)),
+        };
+        Ok(Some(value))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        match self.typ {
+            ColumnType::Native(NativeType::Blob) => Ok(Some(CqlValue::Blob(v.to_owned()))),
+            typ => Err(SerdeSerializationError::mismatch("bytes", typ)),
+        }
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Ok(None)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(None)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        match self.typ {
+            ColumnType::Native(NativeType::Ascii | NativeType::Text) => self.serialize_str(variant),
+            typ => Err(SerdeSerializationError::mismatch("an enum", typ)),
+        }
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Err(SerdeSerializationError(format!(
+            "enum variants with data ({name}::{variant}) are not supported"
+        )))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.sequence()
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.sequence()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.sequence()
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(SerdeSerializationError(format!(
+            "enum variants with data ({name}::{variant}) are not supported"
+        )))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        self.mapping()
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.mapping()
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(SerdeSerializationError(format!(
+            "enum variants with data ({name}::{variant}) are not supported"
+        )))
+    }
+}
+
+/// Collects elements of a list, set, vector or tuple.
+struct SeqSerializer<'a, 'b> {
+    typ: &'a ColumnType<'b>,
+    elements: Vec<Option<CqlValue>>,
+}
+
+impl SeqSerializer<'_, '_> {
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SerdeSerializationError> {
+        let element_typ = match self.typ {
+            ColumnType::Collection {
+                typ: CollectionType::List(typ) | CollectionType::Set(typ),
+                ..
+            }
+            | ColumnType::Vector { typ, .. } => typ,
+            ColumnType::Tuple(types) => types.get(self.elements.len()).ok_or_else(|| {
+                SerdeSerializationError(format!("too many elements for {:?}", self.typ))
+            })?,
+            // Checked when creating the serializer.
+            _ => unreachable!(),
+        };
+        let element = value.serialize(ValueSerializer { typ: element_typ })?;
+        self.elements.push(element);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Option<CqlValue>, SerdeSerializationError> {
+        if let ColumnType::Tuple(_) = self.typ {
+            return Ok(Some(CqlValue::Tuple(self.elements)));
+        }
+
+        let elements = self
+            .elements
+            .into_iter()
+            .map(|element| {
+                element.ok_or_else(|| {
+                    SerdeSerializationError("collection elements must not be null".to_owned())
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let value = match self.typ {
+            ColumnType::Collection {
+                typ: CollectionType::Set(_),
+                ..
+            } => CqlValue::Set(elements),
+            ColumnType::Vector { .. } => CqlValue::Vector(elements),
+            _ => CqlValue::List(elements),
+        };
+        Ok(Some(value))
+    }
+}
+
+impl SerializeSeq for SeqSerializer<'_, '_> {
+    type Ok = Option<CqlValue>;
+    type Error = SerdeSerializationError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl SerializeTuple for SeqSerializer<'_, '_> {
+    type Ok = Option<CqlValue>;
+    type Error = SerdeSerializationError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl SerializeTupleStruct for SeqSerializer<'_, '_> {
+    type Ok = Option<CqlValue>;
+    type Error = SerdeSerializationError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+/// Collects entries of a map, fields of a user defined type, or components of a duration.
+struct MapSerializer<'a, 'b> {
+    typ: &'a ColumnType<'b>,
+    entries: Vec<(CqlValue, Option<CqlValue>)>,
+    next_key: Option<CqlValue>,
+}
+
+impl MapSerializer<'_, '_> {
+    // For types other than maps, keys are names, serialized as text.
+    fn key_type(&self) -> &ColumnType<'_> {
+        match self.typ {
+            ColumnType::Collection {
+                typ: CollectionType::Map(key_typ, _),
+                ..
+            } => key_typ,
+            _ => &ColumnType::Native(NativeType::Text),
+        }
+    }
+
+    fn value_type(&self, key: &CqlValue) -> Result<&ColumnType<'_>, SerdeSerializationError> {
+        match (self.typ, key) {
+            (
+                ColumnType::Collection {
+                    typ: CollectionType::Map(_, value_typ),
+                    ..
+                },
+                _,
+            ) => Ok(value_typ),
+            (ColumnType::UserDefinedType { definition, .. }, CqlValue::Text(name)) => definition
+                .field_types
+                .iter()
+                .find_map(|(field_name, typ)| (field_name == name).then_some(typ))
+                .ok_or_else(|| {
+                    SerdeSerializationError(format!(
+                        "no field named {name} in user defined type {}.{}",
+                        definition.keyspace, definition.name
+                    ))
+                }),
+            (ColumnType::Native(NativeType::Duration), CqlValue::Text(name)) => {
+                match name.as_str() {
+                    "months" | "days" => Ok(&ColumnType::Native(NativeType::Int)),
+                    "nanoseconds" => Ok(&ColumnType::Native(NativeType::BigInt)),
+                    _ => Err(SerdeSerializationError(format!(
+                        "no component named {name} in duration"
+                    ))),
+                }
+            }
+            // Checked when creating the serializer and serializing the key.
+            _ => unreachable!(),
+        }
+    }
+
+    fn push_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), SerdeSerializationError> {
+        let key = key
+            .serialize(ValueSerializer {
+                typ: self.key_type(),
+            })?
+            .ok_or_else(|| SerdeSerializationError("map keys must not be null".to_owned()))?;
+        self.next_key = Some(key);
+        Ok(())
+    }
+
+    fn push_value<T: ?Sized + Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), SerdeSerializationError> {
+        let key = self
+            .next_key
+            .take()
+            .expect("serialize_value called before serialize_key");
+        let value = value.serialize(ValueSerializer {
+            typ: self.value_type(&key)?,
+        })?;
+        self.entries.push((key, value));
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Option<CqlValue>, SerdeSerializationError> {
+        let value = match self.typ {
+            ColumnType::UserDefinedType { definition, .. } => CqlValue::UserDefinedType {
+                keyspace: definition.keyspace.clone().into_owned(),
+                name: definition.name.clone().into_owned(),
+                fields: self
+                    .entries
+                    .into_iter()
+                    .map(|(name, value)| match name {
+                        CqlValue::Text(name) => (name, value),
+                        _ => unreachable!(),
+                    })
+                    .collect(),
+            },
+            ColumnType::Native(NativeType::Duration) => {
+                let mut duration = CqlDuration {
+                    months: 0,
+                    days: 0,
+                    nanoseconds: 0,
+                };
+                for (name, value) in self.entries {
+                    match (name.as_text().map(String::as_str), value) {
+                        (Some("months"), Some(CqlValue::Int(months))) => duration.months = months,
+                        (Some("days"), Some(CqlValue::Int(days))) => duration.days = days,
+                        (Some("nanoseconds"), Some(CqlValue::BigInt(nanoseconds))) => {
+                            duration.nanoseconds = nanoseconds
+                        }
+                        _ => {
+                            return Err(SerdeSerializationError(
+                                "duration components must not be null".to_owned(),
+                            ))
+                        }
+                    }
+                }
+                CqlValue::Duration(duration)
+            }
+            _ => CqlValue::Map(
+                self.entries
+                    .into_iter()
+                    .map(|(key, value)| {
+                        value.map(|value| (key, value)).ok_or_else(|| {
+                            SerdeSerializationError("map values must not be null".to_owned())
+                        })
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+        };
+        Ok(Some(value))
+    }
+}
+
+impl SerializeMap for MapSerializer<'_, '_> {
+    type Ok = Option<CqlValue>;
+    type Error = SerdeSerializationError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.push_key(key)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push_value(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl SerializeStruct for MapSerializer<'_, '_> {
+    type Ok = Option<CqlValue>;
+    type Error = SerdeSerializationError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.push_key(key)?;
+        self.push_value(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+/// Serializes a struct or a map into values of bind markers, matched by name.
+struct RowSerializer<'a> {
+    columns: &'a [ColumnSpec<'a>],
+}
+
+impl<'a> RowSerializer<'a> {
+    fn not_a_row(kind: &str) -> SerdeSerializationError {
+        SerdeSerializationError(format!(
+            "cannot serialize {kind} as a row, expected a struct or a map"
+        ))
+    }
+}
+
+/// Values of bind markers, keyed by column name. `None` stands for null.
+type RowValues = Vec<(String, Option<CqlValue>)>;
+
+impl<'a> serde::Serializer for RowSerializer<'a> {
+    type Ok = RowValues;
+    type Error = SerdeSerializationError;
+
+    type SerializeSeq = Impossible<Self::Ok, Self::Error>;
+    type SerializeTuple = Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleStruct = Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleVariant = Impossible<Self::Ok, Self::Error>;
+    type SerializeMap = RowMapSerializer<'a>;
+    type SerializeStruct = RowMapSerializer<'a>;
+    type SerializeStructVariant = Impossible<Self::Ok, Self::Error>;
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(RowMapSerializer {
+            columns: self.columns,
+            values: Vec::new(),
+            next_name: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Vec::new())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(Vec::new())
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_row("a bool"))
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_row("an integer"))
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_row("an integer"))
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_row("an integer"))
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_row("an integer"))
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_row("an integer"))
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_row("an integer"))
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_row("an integer"))
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_row("an integer"))
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_row("a floating point number"))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_row("a floating point number"))
+    }
+
+    fn serialize_char(self, _v: char) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_row("a char"))
+    }
+
+    fn serialize_str(self, _v: &str) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_row("a string"))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_row("bytes"))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_row("None"))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_row("an enum"))
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Err(Self::not_a_row("an enum"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(Self::not_a_row("a sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Err(Self::not_a_row("a tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Err(Self::not_a_row("a tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(Self::not_a_row("an enum"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(Self::not_a_row("an enum"))
+    }
+}
+
+/// Collects values of bind markers, serializing each of them according to
+/// the type of the first bind marker with the same name.
+struct RowMapSerializer<'a> {
+    columns: &'a [ColumnSpec<'a>],
+    values: RowValues,
+    next_name: Option<String>,
+}
+
+impl RowMapSerializer<'_> {
+    fn push_name<T: ?Sized + Serialize>(
+        &mut self,
+        name: &T,
+    ) -> Result<(), SerdeSerializationError> {
+        let name = match name.serialize(ValueSerializer {
+            typ: &ColumnType::Native(NativeType::Text),
+        })? {
+            Some(CqlValue::Text(name)) => name,
+            _ => {
+                return Err(SerdeSerializationError(
+                    "column names must be strings".to_owned(),
+                ))
+            }
+        };
+        self.next_name = Some(name);
+        Ok(())
+    }
+
+    fn push_value<T: ?Sized + Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), SerdeSerializationError> {
+        let name = self
+            .next_name
+            .take()
+            .expect("serialize_value called before serialize_key");
+        let column = self
+            .columns
+            .iter()
+            .find(|column| column.name() == name)
+            .ok_or_else(|| SerdeSerializationError(format!("no bind marker with name {name}")))?;
+        let value = value.serialize(ValueSerializer { typ: column.typ() })?;
+        self.values.push((name, value));
+        Ok(())
+    }
+}
+
+impl SerializeMap for RowMapSerializer<'_> {
+    type Ok = RowValues;
+    type Error = SerdeSerializationError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.push_name(key)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push_value(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.values)
+    }
+}
+
+impl SerializeStruct for RowMapSerializer<'_> {
+    type Ok = RowValues;
+    type Error = SerdeSerializationError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.push_name(key)?;
+        self.push_value(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.values)
+    }
+}
+
+/// Converts an `i128` to the shortest signed big-endian two's complement representation.
+fn i128_to_signed_be_bytes(v: i128) -> Vec<u8> {
+    strip_redundant_signed_be_bytes(&v.to_be_bytes()).to_vec()
+}
+
+/// Parses a decimal number, such as `-12.345`, with the unscaled value fitting in 128 bits.
+fn parse_decimal(s: &str) -> Option<CqlDecimal> {
+    let (integer_part, fractional_part) = s.split_once('.').unwrap_or((s, ""));
+    let digits = integer_part.trim_start_matches(['-', '+']);
+    if digits.is_empty() && fractional_part.is_empty()
+        || !digits.chars().all(|c| c.is_ascii_digit())
+        || !fractional_part.chars().all(|c| c.is_ascii_digit())
+        || integer_part.len() - digits.len() > 1
+    {
+        return None;
+    }
+
+    let unscaled: i128 = format!("{integer_part}{fractional_part}").parse().ok()?;
+    let scale = i32::try_from(fractional_part.len()).ok()?;
+    Some(CqlDecimal::from_signed_be_bytes_and_exponent(
+        i128_to_signed_be_bytes(unscaled),
+        scale,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use bytes::Bytes;
+    use serde::Serialize;
+
+    use super::{i128_to_signed_be_bytes, parse_decimal, Serde};
+    use crate::deserialize::serde_bridge::signed_be_bytes_to_i128;
+    use crate::deserialize::value::DeserializeValue;
+    use crate::deserialize::FrameSlice;
+    use crate::frame::response::result::{
+        CollectionType, ColumnSpec, ColumnType, NativeType, TableSpec, UserDefinedType,
+    };
+    use crate::serialize::row::{RowSerializationContext, SerializeRow};
+    use crate::serialize::value::SerializeValue;
+    use crate::serialize::writers::CellWriter;
+    use crate::serialize::RowWriter;
+    use crate::value::{CqlDecimal, CqlValue};
+
+    fn serialize_value<T: Serialize>(value: T, typ: &ColumnType) -> Result<Vec<u8>, String> {
+        let mut buf = Vec::new();
+        let writer = CellWriter::new(&mut buf);
+        <Serde<T> as SerializeValue>::serialize(&Serde(value), typ, writer)
+            .map_err(|err| err.to_string())?;
+        Ok(buf)
+    }
+
+    fn serialize_to_cql_value<T: Serialize>(value: T, typ: &ColumnType) -> Option<CqlValue> {
+        let buf = Bytes::from(serialize_value(value, typ).unwrap());
+        let mut slice = FrameSlice::new(&buf);
+        let cell = slice.read_cql_bytes().unwrap();
+        <Option<CqlValue>>::deserialize(typ, cell).unwrap()
+    }
+
+    #[test]
+    fn test_scalar_values() {
+        let int = ColumnType::Native(NativeType::Int);
+        assert_eq!(serialize_to_cql_value(7_u8, &int), Some(CqlValue::Int(7)));
+        assert_eq!(serialize_to_cql_value(None::<i32>, &int), None);
+        assert!(serialize_value(i64::MAX, &int).is_err());
+        assert!(serialize_value("7", &int).is_err());
+
+        let uuid = ColumnType::Native(NativeType::Uuid);
+        let id = uuid::Uuid::from_u128(0x1234);
+        assert_eq!(
+            serialize_to_cql_value(id.to_string(), &uuid),
+            Some(CqlValue::Uuid(id))
+        );
+
+        let decimal = ColumnType::Native(NativeType::Decimal);
+        assert_eq!(
+            serialize_to_cql_value("-123.45", &decimal),
+            Some(CqlValue::Decimal(
+                CqlDecimal::from_signed_be_bytes_and_exponent(vec![0xcf, 0xc7], 2)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_collections_and_udts() {
+        #[derive(Serialize)]
+        struct Address {
+            street: &'static str,
+            number: Option<i32>,
+        }
+
+        let udt = ColumnType::UserDefinedType {
+            frozen: false,
+            definition: Arc::new(UserDefinedType {
+                name: "address".into(),
+                keyspace: "ks".into(),
+                field_types: vec![
+                    ("street".into(), ColumnType::Native(NativeType::Text)),
+                    ("number".into(), ColumnType::Native(NativeType::Int)),
+                ],
+            }),
+        };
+        assert_eq!(
+            serialize_to_cql_value(
+                Address {
+                    street: "Main",
+                    number: None,
+                },
+                &udt
+            ),
+            Some(CqlValue::UserDefinedType {
+                keyspace: "ks".to_owned(),
+                name: "address".to_owned(),
+                fields: vec![
+                    ("street".to_owned(), Some(CqlValue::Text("Main".to_owned()))),
+                    ("number".to_owned(), None),
+                ],
+            })
+        );
+
+        let map = ColumnType::Collection {
+            frozen: false,
+            typ: CollectionType::Map(
+                Box::new(ColumnType::Native(NativeType::Text)),
+                Box::new(ColumnType::Collection {
+                    frozen: false,
+                    typ: CollectionType::Set(Box::new(ColumnType::Native(NativeType::BigInt))),
+                }),
+            ),
+        };
+        let value = BTreeMap::from([("a", vec![1_i32, 2])]);
+        assert_eq!(
+            serialize_to_cql_value(value, &map),
+            Some(CqlValue::Map(vec![(
+                CqlValue::Text("a".to_owned()),
+                CqlValue::Set(vec![CqlValue::BigInt(1), CqlValue::BigInt(2)])
+            )]))
+        );
+        assert!(serialize_value(BTreeMap::from([("a", None::<Vec<i32>>)]), &map).is_err());
+    }
+
+    #[test]
+    fn test_row_serialization_by_name() {
+        #[derive(Serialize)]
+        #[serde(rename_all = "lowercase")]
+        enum Kind {
+            Large,
+        }
+
+        #[derive(Serialize)]
+        struct Row {
+            id: i64,
+            #[serde(rename = "label")]
+            name: &'static str,
+            kind: Kind,
+        }
+
+        let spec = |name: &'static str, typ| {
+            ColumnSpec::borrowed(name, typ, TableSpec::borrowed("ks", "tab"))
+        };
+        let specs = [
+            spec("label", ColumnType::Native(NativeType::Text)),
+            spec("id", ColumnType::Native(NativeType::BigInt)),
+            spec("kind", ColumnType::Native(NativeType::Text)),
+            spec("id", ColumnType::Native(NativeType::BigInt)),
+        ];
+        let ctx = RowSerializationContext::from_specs(&specs);
+
+        let row = Serde(Row {
+            id: 42,
+            name: "foo",
+            kind: Kind::Large,
+        });
+        let mut buf = Vec::new();
+        let mut writer = RowWriter::new(&mut buf);
+        <_ as SerializeRow>::serialize(&row, &ctx, &mut writer).unwrap();
+        assert_eq!(writer.value_count(), 4);
+
+        let mut expected = Vec::new();
+        for cell in [
+            &b"foo"[..],
+            &42_i64.to_be_bytes(),
+            b"large",
+            &42_i64.to_be_bytes(),
+        ] {
+            expected.extend_from_slice(&(cell.len() as i32).to_be_bytes());
+            expected.extend_from_slice(cell);
+        }
+        assert_eq!(buf, expected);
+
+        // A bind marker without a value.
+        let specs = [spec("missing", ColumnType::Native(NativeType::Int))];
+        let ctx = RowSerializationContext::from_specs(&specs);
+        let mut buf = Vec::new();
+        let mut writer = RowWriter::new(&mut buf);
+        let err = <_ as SerializeRow>::serialize(
+            &Serde(BTreeMap::<String, i32>::new()),
+            &ctx,
+            &mut writer,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("missing"));
+    }
+
+    #[test]
+    fn test_row_is_empty() {
+        #[derive(Serialize)]
+        struct Empty {}
+
+        #[derive(Serialize)]
+        struct Row {
+            id: i64,
+        }
+
+        assert!(Serde(()).is_empty());
+        assert!(Serde(Empty {}).is_empty());
+        assert!(Serde(BTreeMap::<String, i32>::new()).is_empty());
+        assert!(!Serde(Row { id: 42 }).is_empty());
+        assert!(!Serde(BTreeMap::from([("id", 42)])).is_empty());
+        // Not a row at all; serialization reports the error.
+        assert!(!Serde(42).is_empty());
+    }
+
+    #[test]
+    fn test_decimal_parsing() {
+        assert_eq!(
+            parse_decimal("0.005"),
+            Some(CqlDecimal::from_signed_be_bytes_and_exponent(vec![0x05], 3))
+        );
+        assert_eq!(
+            parse_decimal("12"),
+            Some(CqlDecimal::from_signed_be_bytes_and_exponent(vec![0x0c], 0))
+        );
+        for invalid in ["", ".", "1.2.3", "--1", "1e5", "abc"] {
+            assert_eq!(parse_decimal(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_varint_bytes_round_trip() {
+        for v in [
+            0,
+            1,
+            -1,
+            127,
+            128,
+            -128,
+            -129,
+            i64::MIN.into(),
+            i128::MAX,
+            i128::MIN,
+        ] {
+            assert_eq!(
+                signed_be_bytes_to_i128(&i128_to_signed_be_bytes(v)),
+                Some(v)
+            );
+        }
+    }
+}
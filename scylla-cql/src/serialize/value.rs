@@ -543,6 +543,39 @@ impl<'a, T: SerializeValue + 'a> SerializeValue for &'a [T] {
         }
     }
 }
+impl<T: SerializeValue, const N: usize> SerializeValue for [T; N] {
+    fn serialize<'b>(
+        &self,
+        typ: &ColumnType,
+        writer: CellWriter<'b>,
+    ) -> Result<WrittenCellProof<'b>, SerializationError> {
+        match typ {
+            ColumnType::Collection {
+                typ: CollectionType::List(_) | CollectionType::Set(_),
+                ..
+            } => serialize_sequence(std::any::type_name::<Self>(), N, self.iter(), typ, writer),
+
+            ColumnType::Vector {
+                typ: element_type,
+                dimensions,
+            } => serialize_vector(
+                std::any::type_name::<Self>(),
+                N,
+                self.iter(),
+                element_type,
+                *dimensions,
+                typ,
+                writer,
+            ),
+
+            _ => Err(mk_typck_err_named(
+                std::any::type_name::<Self>(),
+                typ,
+                SetOrListTypeCheckErrorKind::NotSetOrList,
+            )),
+        }
+    }
+}
 impl SerializeValue for CqlValue {
     fn serialize<'b>(
         &self,
@@ -1407,6 +1407,126 @@ fn test_udt_with_non_rust_ident() {
     assert_eq!(reference, udt);
 }
 
+#[test]
+fn test_enum_serialization() {
+    #[derive(SerializeValue, Debug)]
+    #[scylla(crate = crate, repr = "text")]
+    enum Color {
+        Red,
+        #[scylla(rename = "GREEN")]
+        Green,
+        #[scylla(catch_all)]
+        Other(String),
+    }
+
+    #[derive(SerializeValue, Debug)]
+    #[scylla(crate = crate, repr = "int")]
+    enum Priority {
+        Low = 1,
+        High = 10,
+        Highest,
+        #[scylla(rename = -1)]
+        Unset,
+    }
+
+    #[derive(SerializeValue, Debug)]
+    #[scylla(crate = crate, repr = "tinyint")]
+    enum Flag {
+        Off,
+        On,
+    }
+
+    let text = ColumnType::Native(NativeType::Text);
+    assert_eq!(do_serialize(Color::Red, &text), do_serialize("Red", &text));
+    assert_eq!(
+        do_serialize(Color::Green, &text),
+        do_serialize("GREEN", &text)
+    );
+    assert_eq!(
+        do_serialize(Color::Other("blue".to_owned()), &text),
+        do_serialize("blue", &text)
+    );
+
+    let int = ColumnType::Native(NativeType::Int);
+    assert_eq!(do_serialize(Priority::Low, &int), do_serialize(1i32, &int));
+    assert_eq!(
+        do_serialize(Priority::High, &int),
+        do_serialize(10i32, &int)
+    );
+    assert_eq!(
+        do_serialize(Priority::Highest, &int),
+        do_serialize(11i32, &int)
+    );
+    assert_eq!(
+        do_serialize(Priority::Unset, &int),
+        do_serialize(-1i32, &int)
+    );
+
+    let tinyint = ColumnType::Native(NativeType::TinyInt);
+    assert_eq!(
+        do_serialize(Flag::Off, &tinyint),
+        do_serialize(0i8, &tinyint)
+    );
+    assert_eq!(
+        do_serialize(Flag::On, &tinyint),
+        do_serialize(1i8, &tinyint)
+    );
+
+    // Type mismatch is reported with the enum's name
+    let err = do_serialize_err(Priority::Low, &text);
+    let err = get_typeck_err(&err);
+    assert_eq!(err.rust_name, std::any::type_name::<Priority>());
+    assert_matches!(
+        err.kind,
+        BuiltinTypeCheckErrorKind::MismatchedType {
+            expected: &[ColumnType::Native(NativeType::Int)],
+        }
+    );
+
+    let err = do_serialize_err(Color::Red, &int);
+    let err = get_typeck_err(&err);
+    assert_eq!(err.rust_name, std::any::type_name::<Color>());
+    assert_matches!(
+        err.kind,
+        BuiltinTypeCheckErrorKind::MismatchedType {
+            expected: &[
+                ColumnType::Native(NativeType::Ascii),
+                ColumnType::Native(NativeType::Text)
+            ],
+        }
+    );
+}
+
+#[test]
+fn test_transparent_serialization() {
+    #[derive(SerializeValue, Debug)]
+    #[scylla(crate = crate, transparent)]
+    struct UserId(i64);
+
+    #[derive(SerializeValue, Debug)]
+    #[scylla(crate = crate, transparent)]
+    struct Wrapper<T> {
+        inner: T,
+    }
+
+    let bigint = ColumnType::Native(NativeType::BigInt);
+    assert_eq!(
+        do_serialize(UserId(42), &bigint),
+        do_serialize(42i64, &bigint)
+    );
+
+    let text = ColumnType::Native(NativeType::Text);
+    assert_eq!(
+        do_serialize(Wrapper { inner: "foo" }, &text),
+        do_serialize("foo", &text)
+    );
+
+    // Errors come from the inner type
+    let err = do_serialize_err(UserId(42), &text);
+    let err = get_typeck_err(&err);
+    assert_eq!(err.rust_name, std::any::type_name::<i64>());
+}
+
 // Tests migrated from old frame/value_tests.rs file
 
 fn compute_hash<T: Hash>(x: &T) -> u64 {
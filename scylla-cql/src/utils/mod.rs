@@ -1 +1,17 @@
 pub mod parse;
+
+/// Quotes a CQL identifier, so that it can be safely put in a statement.
+///
+/// The identifier is always double-quoted, which preserves its case and makes
+/// reserved keywords (e.g. `order` or `token`) usable as names.
+/// Double quotes inside of it are escaped by doubling them.
+///
+/// ```rust
+/// # use scylla_cql::utils::quote_identifier;
+/// assert_eq!(quote_identifier("order"), "\"order\"");
+/// assert_eq!(quote_identifier("userId"), "\"userId\"");
+/// assert_eq!(quote_identifier("a\"b"), "\"a\"\"b\"");
+/// ```
+pub fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
@@ -13,6 +13,9 @@ use crate::deserialize::FrameSlice;
 use crate::frame::response::result::{CollectionType, ColumnType};
 use crate::frame::types;
 
+pub mod literal;
+pub mod ordering;
+
 #[derive(Debug, Error, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[error("Value is too large to fit in the CQL type")]
 pub struct ValueOverflow;
@@ -275,6 +278,18 @@ impl<'b> CqlVarintBorrowed<'b> {
     }
 }
 
+/// Strips the redundant leading bytes of a number in two's complement
+/// big-endian representation, i.e. `0x00` bytes followed by a byte
+/// with the most significant bit unset and `0xff` bytes followed by a byte
+/// with the most significant bit set.
+pub(crate) fn strip_redundant_signed_be_bytes(digits: &[u8]) -> &[u8] {
+    let redundant = digits
+        .windows(2)
+        .take_while(|w| (w[0] == 0x00 && w[1] & 0x80 == 0) || (w[0] == 0xff && w[1] & 0x80 != 0))
+        .count();
+    &digits[redundant..]
+}
+
 /// Conversion to bytes
 impl CqlVarint {
     /// Converts [`CqlVarint`] to an array of bytes in two's
@@ -1106,6 +1121,7 @@ impl CqlValue {
 
 /// Displays a CqlValue. The syntax should resemble the CQL literal syntax
 /// (but no guarantee is given that it's always the same).
+/// Exact CQL literals can be produced with [`literal::format_literal`].
 impl std::fmt::Display for CqlValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use crate::pretty::{
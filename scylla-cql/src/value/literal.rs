@@ -0,0 +1,1462 @@
+//! Conversion between [`CqlValue`]s and the CQL literal syntax.
+//!
+//! [`format_literal`] renders a value as a literal of the given CQL type,
+//! ready to be pasted into a CQL statement. [`parse_literal`] parses literal
+//! text, e.g. typed by a user, into a value of the given CQL type.
+//! Formatted literals are always parsed back into equal values.
+//!
+//! ```rust
+//! # use scylla_cql::frame::response::result::{CollectionType, ColumnType, NativeType};
+//! # use scylla_cql::value::literal::{format_literal, parse_literal};
+//! let typ = ColumnType::Collection {
+//!     frozen: false,
+//!     typ: CollectionType::Map(
+//!         Box::new(ColumnType::Native(NativeType::Text)),
+//!         Box::new(ColumnType::Native(NativeType::Duration)),
+//!     ),
+//! };
+//! let value = parse_literal("{ 'it''s' : 90m, 'b': 1d }", &typ).unwrap().unwrap();
+//! assert_eq!(
+//!     format_literal(&value, &typ).unwrap(),
+//!     "{'it''s': 1h30m, 'b': 1d}"
+//! );
+//! ```
+//!
+//! # Syntax
+//!
+//! The syntax follows the literals accepted by Scylla and Cassandra, in particular:
+//! - strings are enclosed in single quotes (`'it''s'`) or in double dollars (`$$it's$$`),
+//! - blobs are hexadecimal numbers (`0xcafe`),
+//! - `date`, `time`, `timestamp` and `inet` values are strings, e.g. `'2024-01-31'`,
+//!   `'12:30:00.5'`, `'2024-01-31 12:30:00.000+0000'` or `'127.0.0.1'`.
+//!   Timestamps without a time zone are interpreted as UTC,
+//! - durations consist of a number and a unit (`y`, `mo`, `w`, `d`, `h`, `m`, `s`,
+//!   `ms`, `us` or `µs`, `ns`) pairs, e.g. `-1h30m`,
+//! - lists and vectors are written as `[1, 2]`, sets as `{1, 2}`, maps as `{1: 'a'}`,
+//!   tuples as `(1, 'a')` and UDTs as `{field: 1}`,
+//! - `blobAs<type>(0x...)` function calls, e.g. `blobAsInt(0x)`, represent values
+//!   of native types by their serialized form, which is needed for empty values.
+//!
+//! `null` is accepted in place of the whole literal, tuple elements and UDT fields.
+
+use std::fmt::Write as _;
+use std::net::IpAddr;
+
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::{
+    deser_cql_value, strip_redundant_signed_be_bytes, Counter, CqlDate, CqlDecimal, CqlDuration,
+    CqlTime, CqlTimestamp, CqlTimeuuid, CqlValue, CqlVarint,
+};
+use crate::frame::response::result::{CollectionType, ColumnType, NativeType};
+use crate::pretty::{CqlStringLiteralDisplayer, HexBytes};
+use crate::utils::parse::{ParseError, ParseErrorCause, ParseResult, ParserState};
+
+/// An error that occurred while formatting a value as a CQL literal.
+#[derive(Debug, Clone, Error)]
+#[non_exhaustive]
+pub enum CqlLiteralFormatError {
+    /// The value does not belong to the CQL type.
+    #[error("Value {value} does not match the CQL type {typ:?}")]
+    TypeMismatch {
+        value: CqlValue,
+        typ: ColumnType<'static>,
+    },
+
+    /// The value can't be represented by a CQL literal.
+    #[error("Value {value} can't be represented by a CQL literal: {reason}")]
+    InvalidValue {
+        value: CqlValue,
+        reason: &'static str,
+    },
+}
+
+/// An error that occurred while parsing a CQL literal.
+#[derive(Debug, Clone, Error)]
+#[error("Failed to parse the CQL literal at position {position}: {cause}")]
+pub struct CqlLiteralParseError {
+    position: usize,
+    cause: ParseErrorCause,
+}
+
+impl CqlLiteralParseError {
+    fn new(err: ParseError, literal: &str) -> Self {
+        Self {
+            position: err.calculate_position(literal).unwrap_or(0),
+            cause: err.get_cause(),
+        }
+    }
+
+    /// Returns the 1-based position of the error in characters.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the error cause.
+    pub fn cause(&self) -> ParseErrorCause {
+        self.cause
+    }
+}
+
+/// Formats the value as a CQL literal of the given type.
+pub fn format_literal(value: &CqlValue, typ: &ColumnType) -> Result<String, CqlLiteralFormatError> {
+    let mut literal = String::new();
+    write_literal(&mut literal, value, typ)?;
+    Ok(literal)
+}
+
+/// Parses a CQL literal of the given type. Returns `None` for `null`.
+pub fn parse_literal(
+    literal: &str,
+    typ: &ColumnType,
+) -> Result<Option<CqlValue>, CqlLiteralParseError> {
+    let p = ParserState::new(literal).skip_white();
+    let (value, p) = parse_nullable(p, typ)
+        .and_then(|(value, p)| {
+            let p = p.skip_white();
+            if p.is_at_eof() {
+                Ok((value, p))
+            } else {
+                Err(p.error(ParseErrorCause::Other("Unexpected input after the literal")))
+            }
+        })
+        .map_err(|err| CqlLiteralParseError::new(err, literal))?;
+    debug_assert!(p.is_at_eof());
+    Ok(value)
+}
+
+// Formatting
+
+fn write_literal(
+    out: &mut String,
+    value: &CqlValue,
+    typ: &ColumnType,
+) -> Result<(), CqlLiteralFormatError> {
+    let mismatch = || CqlLiteralFormatError::TypeMismatch {
+        value: value.clone(),
+        typ: typ.clone().into_owned(),
+    };
+    let invalid = |reason| CqlLiteralFormatError::InvalidValue {
+        value: value.clone(),
+        reason,
+    };
+
+    // Writing to a String never fails.
+    match (typ, value) {
+        (ColumnType::Native(NativeType::Ascii | NativeType::Text), CqlValue::Empty) => {
+            out.push_str("''")
+        }
+        (ColumnType::Native(NativeType::Blob), CqlValue::Empty) => out.push_str("0x"),
+        (ColumnType::Native(native), CqlValue::Empty) => {
+            let name = native_type_name(native);
+            write!(out, "blobAs{}{}(0x)", name[..1].to_uppercase(), &name[1..]).unwrap()
+        }
+        (
+            ColumnType::Native(NativeType::Ascii | NativeType::Text),
+            CqlValue::Ascii(s) | CqlValue::Text(s),
+        ) => write!(out, "{}", CqlStringLiteralDisplayer(s)).unwrap(),
+        (ColumnType::Native(NativeType::Boolean), CqlValue::Boolean(b)) => {
+            write!(out, "{}", b).unwrap()
+        }
+        (ColumnType::Native(NativeType::Blob), CqlValue::Blob(b)) => {
+            write!(out, "0x{:x}", HexBytes(b)).unwrap()
+        }
+        (ColumnType::Native(NativeType::Counter), CqlValue::Counter(c)) => {
+            write!(out, "{}", c.0).unwrap()
+        }
+        (ColumnType::Native(NativeType::Decimal), CqlValue::Decimal(d)) => {
+            let (bytes, scale) = d.as_signed_be_bytes_slice_and_exponent();
+            write_decimal(out, bytes, scale)
+        }
+        (ColumnType::Native(NativeType::Double), CqlValue::Double(d)) => write_float(out, *d),
+        (ColumnType::Native(NativeType::Float), CqlValue::Float(f)) => write_float(out, *f),
+        (ColumnType::Native(NativeType::Int), CqlValue::Int(i)) => write!(out, "{}", i).unwrap(),
+        (ColumnType::Native(NativeType::BigInt), CqlValue::BigInt(i)) => {
+            write!(out, "{}", i).unwrap()
+        }
+        (ColumnType::Native(NativeType::SmallInt), CqlValue::SmallInt(i)) => {
+            write!(out, "{}", i).unwrap()
+        }
+        (ColumnType::Native(NativeType::TinyInt), CqlValue::TinyInt(i)) => {
+            write!(out, "{}", i).unwrap()
+        }
+        (ColumnType::Native(NativeType::Varint), CqlValue::Varint(v)) => {
+            out.push_str(&signed_bytes_to_digits(v.as_signed_bytes_be_slice()))
+        }
+        (ColumnType::Native(NativeType::Date), CqlValue::Date(d)) => {
+            match d.try_to_chrono_04_naive_date() {
+                Ok(date) if is_four_digit_year(chrono_04::Datelike::year(&date)) => {
+                    write!(out, "'{}'", date.format("%Y-%m-%d")).unwrap()
+                }
+                // Dates outside of the range are written as the raw number of days,
+                // with the epoch at 2^31.
+                _ => write!(out, "'{}'", d.0).unwrap(),
+            }
+        }
+        (ColumnType::Native(NativeType::Timestamp), CqlValue::Timestamp(ts)) => {
+            match ts.try_to_chrono_04_datetime_utc() {
+                Ok(datetime) if is_four_digit_year(chrono_04::Datelike::year(&datetime)) => {
+                    write!(out, "{}", datetime.format("'%Y-%m-%d %H:%M:%S%.3f%z'")).unwrap()
+                }
+                // Timestamps outside of the range are written as milliseconds since the epoch.
+                _ => write!(out, "{}", ts.0).unwrap(),
+            }
+        }
+        (ColumnType::Native(NativeType::Time), CqlValue::Time(CqlTime(t))) => {
+            if !(0..NANOS_PER_DAY).contains(t) {
+                return Err(invalid("time out of range"));
+            }
+            write!(
+                out,
+                "'{:02}:{:02}:{:02}.{:09}'",
+                t / NANOS_PER_HOUR,
+                t / NANOS_PER_MINUTE % 60,
+                t / NANOS_PER_SECOND % 60,
+                t % NANOS_PER_SECOND,
+            )
+            .unwrap()
+        }
+        (ColumnType::Native(NativeType::Duration), CqlValue::Duration(d)) => {
+            write_duration(out, d).map_err(|_| invalid("components of different signs"))?
+        }
+        (ColumnType::Native(NativeType::Inet), CqlValue::Inet(ip)) => {
+            write!(out, "'{}'", ip).unwrap()
+        }
+        (ColumnType::Native(NativeType::Timeuuid), CqlValue::Timeuuid(t)) => {
+            write!(out, "{}", t).unwrap()
+        }
+        (ColumnType::Native(NativeType::Uuid), CqlValue::Uuid(u)) => write!(out, "{}", u).unwrap(),
+        (
+            ColumnType::Collection {
+                typ: CollectionType::List(elem_typ),
+                ..
+            },
+            CqlValue::List(elems),
+        ) => write_elements(out, "[", "]", elems, elem_typ)?,
+        (ColumnType::Vector { typ, dimensions }, CqlValue::Vector(elems)) => {
+            if elems.len() != usize::from(*dimensions) {
+                return Err(mismatch());
+            }
+            write_elements(out, "[", "]", elems, typ)?
+        }
+        (
+            ColumnType::Collection {
+                typ: CollectionType::Set(elem_typ),
+                ..
+            },
+            CqlValue::Set(elems),
+        ) => write_elements(out, "{", "}", elems, elem_typ)?,
+        (
+            ColumnType::Collection {
+                typ: CollectionType::Map(key_typ, value_typ),
+                ..
+            },
+            CqlValue::Map(entries),
+        ) => {
+            out.push('{');
+            for (i, (key, value)) in entries.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_literal(out, key, key_typ)?;
+                out.push_str(": ");
+                write_literal(out, value, value_typ)?;
+            }
+            out.push('}');
+        }
+        (ColumnType::Tuple(typs), CqlValue::Tuple(elems)) => {
+            if elems.len() > typs.len() {
+                return Err(mismatch());
+            }
+            out.push('(');
+            for (i, (elem, typ)) in elems.iter().zip(typs).enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_nullable(out, elem.as_ref(), typ)?;
+            }
+            out.push(')');
+        }
+        (
+            ColumnType::UserDefinedType { definition, .. },
+            CqlValue::UserDefinedType { fields, .. },
+        ) => {
+            out.push('{');
+            for (i, (name, value)) in fields.iter().enumerate() {
+                let (_, typ) = definition
+                    .field_types
+                    .iter()
+                    .find(|(field_name, _)| field_name == name)
+                    .ok_or_else(mismatch)?;
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_identifier(out, name);
+                out.push_str(": ");
+                write_nullable(out, value.as_ref(), typ)?;
+            }
+            out.push('}');
+        }
+        _ => return Err(mismatch()),
+    }
+    Ok(())
+}
+
+fn write_nullable(
+    out: &mut String,
+    value: Option<&CqlValue>,
+    typ: &ColumnType,
+) -> Result<(), CqlLiteralFormatError> {
+    match value {
+        Some(value) => write_literal(out, value, typ),
+        None => {
+            out.push_str("null");
+            Ok(())
+        }
+    }
+}
+
+fn write_elements(
+    out: &mut String,
+    open: &str,
+    close: &str,
+    elems: &[CqlValue],
+    typ: &ColumnType,
+) -> Result<(), CqlLiteralFormatError> {
+    out.push_str(open);
+    for (i, elem) in elems.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        write_literal(out, elem, typ)?;
+    }
+    out.push_str(close);
+    Ok(())
+}
+
+// Quotes the identifier unless it would be preserved by case folding.
+fn write_identifier(out: &mut String, name: &str) {
+    let is_unquoted_identifier = name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if is_unquoted_identifier {
+        out.push_str(name);
+    } else {
+        write!(out, "\"{}\"", name.replace('"', "\"\"")).unwrap();
+    }
+}
+
+fn write_float<F: Into<f64> + std::fmt::Debug + Copy>(out: &mut String, f: F) {
+    let as_f64: f64 = f.into();
+    if as_f64.is_nan() {
+        out.push_str("NaN");
+    } else if as_f64.is_infinite() {
+        out.push_str(if as_f64 > 0.0 {
+            "Infinity"
+        } else {
+            "-Infinity"
+        });
+    } else {
+        // Debug formatting is the shortest one which is parsed back into the same number.
+        write!(out, "{:?}", f).unwrap();
+    }
+}
+
+// Uses the same notation as `java.math.BigDecimal::toString`:
+// the scientific notation is used for negative scales and for small numbers.
+fn write_decimal(out: &mut String, unscaled: &[u8], scale: i32) {
+    let digits = signed_bytes_to_digits(unscaled);
+    let (sign, digits) = match digits.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", digits.as_str()),
+    };
+    out.push_str(sign);
+
+    let adjusted_exponent = digits.len() as i64 - 1 - i64::from(scale);
+    match usize::try_from(scale) {
+        Ok(0) => out.push_str(digits),
+        Ok(scale) if adjusted_exponent >= -6 => {
+            let digits = format!("{digits:0>width$}", width = scale + 1);
+            let (integer_part, fractional_part) = digits.split_at(digits.len() - scale);
+            write!(out, "{integer_part}.{fractional_part}").unwrap();
+        }
+        _ => {
+            let (first, rest) = digits.split_at(1);
+            out.push_str(first);
+            if !rest.is_empty() {
+                write!(out, ".{rest}").unwrap();
+            }
+            write!(out, "E{adjusted_exponent:+}").unwrap();
+        }
+    }
+}
+
+// Returns Err if the components have different signs.
+fn write_duration(out: &mut String, d: &CqlDuration) -> Result<(), ()> {
+    let negative = d.months < 0 || d.days < 0 || d.nanoseconds < 0;
+    let positive = d.months > 0 || d.days > 0 || d.nanoseconds > 0;
+    if negative && positive {
+        return Err(());
+    }
+    if negative {
+        out.push('-');
+    }
+
+    let months = d.months.unsigned_abs();
+    let nanos = d.nanoseconds.unsigned_abs();
+    let components = [
+        (u64::from(months / 12), "y"),
+        (u64::from(months % 12), "mo"),
+        (u64::from(d.days.unsigned_abs()), "d"),
+        (nanos / NANOS_PER_HOUR as u64, "h"),
+        (nanos / NANOS_PER_MINUTE as u64 % 60, "m"),
+        (nanos / NANOS_PER_SECOND as u64 % 60, "s"),
+        (nanos / 1_000_000 % 1000, "ms"),
+        (nanos / 1000 % 1000, "us"),
+        (nanos % 1000, "ns"),
+    ];
+    let mut empty = true;
+    for (count, unit) in components {
+        if count > 0 {
+            write!(out, "{count}{unit}").unwrap();
+            empty = false;
+        }
+    }
+    if empty {
+        out.push_str("0s");
+    }
+    Ok(())
+}
+
+fn is_four_digit_year(year: i32) -> bool {
+    (1..=9999).contains(&year)
+}
+
+// Parsing
+
+fn parse_nullable<'s>(
+    p: ParserState<'s>,
+    typ: &ColumnType,
+) -> ParseResult<(Option<CqlValue>, ParserState<'s>)> {
+    match accept_keyword(p, "null") {
+        Some(p) => Ok((None, p)),
+        None => parse_value(p, typ).map(|(value, p)| (Some(value), p)),
+    }
+}
+
+fn parse_value<'s>(
+    p: ParserState<'s>,
+    typ: &ColumnType,
+) -> ParseResult<(CqlValue, ParserState<'s>)> {
+    if let ColumnType::Native(native) = typ {
+        if let Some(p) = accept_blob_as_function(p, native) {
+            return parse_blob_as_function(p, typ);
+        }
+    }
+
+    let value = match typ {
+        ColumnType::Native(NativeType::Ascii) => {
+            let (s, new_p) = parse_string(p)?;
+            if !s.is_ascii() {
+                return Err(p.error(ParseErrorCause::Other("Expected an ASCII string")));
+            }
+            (CqlValue::Ascii(s), new_p)
+        }
+        ColumnType::Native(NativeType::Text) => {
+            let (s, p) = parse_string(p)?;
+            (CqlValue::Text(s), p)
+        }
+        ColumnType::Native(NativeType::Boolean) => {
+            if let Some(p) = accept_keyword(p, "true") {
+                (CqlValue::Boolean(true), p)
+            } else if let Some(p) = accept_keyword(p, "false") {
+                (CqlValue::Boolean(false), p)
+            } else {
+                return Err(p.error(ParseErrorCause::Expected("boolean")));
+            }
+        }
+        ColumnType::Native(NativeType::Blob) => {
+            let (b, p) = parse_blob(p)?;
+            (CqlValue::Blob(b), p)
+        }
+        ColumnType::Native(NativeType::Counter) => {
+            let (i, p) = parse_integer(p)?;
+            (CqlValue::Counter(Counter(i)), p)
+        }
+        ColumnType::Native(NativeType::Int) => {
+            let (i, p) = parse_integer(p)?;
+            (CqlValue::Int(i), p)
+        }
+        ColumnType::Native(NativeType::BigInt) => {
+            let (i, p) = parse_integer(p)?;
+            (CqlValue::BigInt(i), p)
+        }
+        ColumnType::Native(NativeType::SmallInt) => {
+            let (i, p) = parse_integer(p)?;
+            (CqlValue::SmallInt(i), p)
+        }
+        ColumnType::Native(NativeType::TinyInt) => {
+            let (i, p) = parse_integer(p)?;
+            (CqlValue::TinyInt(i), p)
+        }
+        ColumnType::Native(NativeType::Varint) => {
+            let (negative, digits, p) = take_integer(p)?;
+            let bytes = digits_to_signed_bytes(negative, digits);
+            (CqlValue::Varint(CqlVarint::from_signed_bytes_be(bytes)), p)
+        }
+        ColumnType::Native(NativeType::Decimal) => {
+            let (d, p) = parse_decimal(p)?;
+            (CqlValue::Decimal(d), p)
+        }
+        ColumnType::Native(NativeType::Double) => {
+            let (f, p) = parse_float(p)?;
+            (CqlValue::Double(f), p)
+        }
+        ColumnType::Native(NativeType::Float) => {
+            let (f, p) = parse_float(p)?;
+            (CqlValue::Float(f), p)
+        }
+        ColumnType::Native(NativeType::Date) => {
+            let (s, new_p) = parse_string(p)?;
+            let date = parse_date(&s).ok_or_else(|| p.error(ParseErrorCause::Expected("date")))?;
+            (CqlValue::Date(date), new_p)
+        }
+        ColumnType::Native(NativeType::Timestamp) => match parse_string(p) {
+            Ok((s, new_p)) => {
+                let ts = parse_timestamp(&s)
+                    .ok_or_else(|| p.error(ParseErrorCause::Expected("timestamp")))?;
+                (CqlValue::Timestamp(ts), new_p)
+            }
+            Err(_) => {
+                let (millis, p) = parse_integer(p)?;
+                (CqlValue::Timestamp(CqlTimestamp(millis)), p)
+            }
+        },
+        ColumnType::Native(NativeType::Time) => match parse_string(p) {
+            Ok((s, new_p)) => {
+                let time =
+                    parse_time(&s).ok_or_else(|| p.error(ParseErrorCause::Expected("time")))?;
+                (CqlValue::Time(time), new_p)
+            }
+            Err(_) => {
+                let (nanos, new_p) = parse_integer(p)?;
+                if !(0..NANOS_PER_DAY).contains(&nanos) {
+                    return Err(p.error(ParseErrorCause::Other("Time out of range")));
+                }
+                (CqlValue::Time(CqlTime(nanos)), new_p)
+            }
+        },
+        ColumnType::Native(NativeType::Duration) => {
+            let (d, p) = parse_duration(p)?;
+            (CqlValue::Duration(d), p)
+        }
+        ColumnType::Native(NativeType::Inet) => {
+            let (s, new_p) = parse_string(p)?;
+            let ip = s
+                .parse::<IpAddr>()
+                .map_err(|_| p.error(ParseErrorCause::Expected("IP address")))?;
+            (CqlValue::Inet(ip), new_p)
+        }
+        ColumnType::Native(NativeType::Uuid) => {
+            let (u, p) = parse_uuid(p)?;
+            (CqlValue::Uuid(u), p)
+        }
+        ColumnType::Native(NativeType::Timeuuid) => {
+            let (u, new_p) = parse_uuid(p)?;
+            if u.get_version_num() != 1 {
+                return Err(p.error(ParseErrorCause::Expected("version 1 UUID")));
+            }
+            (CqlValue::Timeuuid(CqlTimeuuid::from(u)), new_p)
+        }
+        ColumnType::Collection {
+            typ: CollectionType::List(elem_typ),
+            ..
+        } => {
+            let (elems, p) = parse_elements(p, "[", "]", elem_typ)?;
+            (CqlValue::List(elems), p)
+        }
+        ColumnType::Vector { typ, dimensions } => {
+            let (elems, new_p) = parse_elements(p, "[", "]", typ)?;
+            if elems.len() != usize::from(*dimensions) {
+                return Err(p.error(ParseErrorCause::Other(
+                    "Wrong number of elements of the vector",
+                )));
+            }
+            (CqlValue::Vector(elems), new_p)
+        }
+        ColumnType::Collection {
+            typ: CollectionType::Set(elem_typ),
+            ..
+        } => {
+            let (elems, p) = parse_elements(p, "{", "}", elem_typ)?;
+            (CqlValue::Set(elems), p)
+        }
+        ColumnType::Collection {
+            typ: CollectionType::Map(key_typ, value_typ),
+            ..
+        } => {
+            let mut entries = Vec::new();
+            let p = parse_sequence(p, "{", "}", |p| {
+                let (key, p) = parse_value(p, key_typ)?;
+                let p = p.skip_white().accept(":")?.skip_white();
+                let (value, p) = parse_value(p, value_typ)?;
+                entries.push((key, value));
+                Ok(p)
+            })?;
+            (CqlValue::Map(entries), p)
+        }
+        ColumnType::Tuple(typs) => {
+            let mut elems = Vec::new();
+            let p = parse_sequence(p, "(", ")", |p| {
+                let typ = typs
+                    .get(elems.len())
+                    .ok_or_else(|| p.error(ParseErrorCause::Expected(")")))?;
+                let (elem, p) = parse_nullable(p, typ)?;
+                elems.push(elem);
+                Ok(p)
+            })?;
+            (CqlValue::Tuple(elems), p)
+        }
+        ColumnType::UserDefinedType { definition, .. } => {
+            let mut fields: Vec<(String, Option<CqlValue>)> = definition
+                .field_types
+                .iter()
+                .map(|(name, _)| (name.clone().into_owned(), None))
+                .collect();
+            let mut seen = vec![false; fields.len()];
+            let p = parse_sequence(p, "{", "}", |p| {
+                let (name, new_p) = parse_identifier(p)?;
+                let idx = definition
+                    .field_types
+                    .iter()
+                    .position(|(field_name, _)| *field_name == name)
+                    .ok_or_else(|| p.error(ParseErrorCause::Other("No such field in the UDT")))?;
+                if std::mem::replace(&mut seen[idx], true) {
+                    return Err(p.error(ParseErrorCause::Other("Duplicated field of the UDT")));
+                }
+                let p = new_p.skip_white().accept(":")?.skip_white();
+                let (value, p) = parse_nullable(p, &definition.field_types[idx].1)?;
+                fields[idx].1 = value;
+                Ok(p)
+            })?;
+            (
+                CqlValue::UserDefinedType {
+                    keyspace: definition.keyspace.clone().into_owned(),
+                    name: definition.name.clone().into_owned(),
+                    fields,
+                },
+                p,
+            )
+        }
+    };
+    Ok(value)
+}
+
+fn parse_blob_as_function<'s>(
+    p: ParserState<'s>,
+    typ: &ColumnType,
+) -> ParseResult<(CqlValue, ParserState<'s>)> {
+    let p = p.skip_white().accept("(")?.skip_white();
+    let (bytes, new_p) = parse_blob(p)?;
+    let value = deser_cql_value(typ, &mut bytes.as_slice()).map_err(|_| {
+        p.error(ParseErrorCause::Other(
+            "The blob is not a valid serialized value of the type",
+        ))
+    })?;
+    let p = new_p.skip_white().accept(")")?;
+    Ok((value, p))
+}
+
+// Parses a comma-separated sequence of items enclosed in the given brackets.
+fn parse_sequence<'s>(
+    p: ParserState<'s>,
+    open: &'static str,
+    close: &'static str,
+    mut parse_item: impl FnMut(ParserState<'s>) -> ParseResult<ParserState<'s>>,
+) -> ParseResult<ParserState<'s>> {
+    let mut p = p.accept(open)?.skip_white();
+    if let Ok(p) = p.accept(close) {
+        return Ok(p);
+    }
+    loop {
+        p = parse_item(p)?.skip_white();
+        match p.accept(",") {
+            Ok(new_p) => p = new_p.skip_white(),
+            Err(_) => return p.accept(close),
+        }
+    }
+}
+
+fn parse_elements<'s>(
+    p: ParserState<'s>,
+    open: &'static str,
+    close: &'static str,
+    typ: &ColumnType,
+) -> ParseResult<(Vec<CqlValue>, ParserState<'s>)> {
+    let mut elems = Vec::new();
+    let p = parse_sequence(p, open, close, |p| {
+        let (elem, p) = parse_value(p, typ)?;
+        elems.push(elem);
+        Ok(p)
+    })?;
+    Ok((elems, p))
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+// Accepts a case-insensitive keyword, which must not be followed by an identifier character.
+fn accept_keyword<'s>(p: ParserState<'s>, keyword: &str) -> Option<ParserState<'s>> {
+    let (word, new_p) = p.take_while(is_identifier_char);
+    word.eq_ignore_ascii_case(keyword).then_some(new_p)
+}
+
+fn accept_blob_as_function<'s>(p: ParserState<'s>, typ: &NativeType) -> Option<ParserState<'s>> {
+    let (word, new_p) = p.take_while(is_identifier_char);
+    let type_name = word
+        .get(..6)?
+        .eq_ignore_ascii_case("blobas")
+        .then(|| &word[6..])?;
+    let matches = type_name.eq_ignore_ascii_case(native_type_name(typ))
+        || *typ == NativeType::Text && type_name.eq_ignore_ascii_case("varchar");
+    matches.then_some(new_p)
+}
+
+fn parse_identifier(p: ParserState<'_>) -> ParseResult<(String, ParserState<'_>)> {
+    if let Ok(mut p) = p.accept("\"") {
+        let mut name = String::new();
+        loop {
+            let (part, new_p) = p.take_while(|c| c != '"');
+            name.push_str(part);
+            p = new_p.accept("\"")?;
+            match p.accept("\"") {
+                Ok(new_p) => {
+                    name.push('"');
+                    p = new_p;
+                }
+                Err(_) => return Ok((name, p)),
+            }
+        }
+    }
+    let (name, new_p) = p.take_while(is_identifier_char);
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return Err(p.error(ParseErrorCause::Expected("identifier")));
+    }
+    Ok((name.to_ascii_lowercase(), new_p))
+}
+
+fn parse_string(p: ParserState<'_>) -> ParseResult<(String, ParserState<'_>)> {
+    if let Ok(p) = p.accept("$$") {
+        let end =
+            p.s.find("$$")
+                .ok_or_else(|| p.error(ParseErrorCause::Expected("$$")))?;
+        let s = p.s[..end].to_owned();
+        return Ok((s, ParserState::new(&p.s[end + 2..])));
+    }
+
+    let mut p = p
+        .accept("'")
+        .map_err(|_| p.error(ParseErrorCause::Expected("string literal")))?;
+    let mut s = String::new();
+    loop {
+        let (part, new_p) = p.take_while(|c| c != '\'');
+        s.push_str(part);
+        p = new_p.accept("'")?;
+        // A quote is escaped by another quote.
+        match p.accept("'") {
+            Ok(new_p) => {
+                s.push('\'');
+                p = new_p;
+            }
+            Err(_) => return Ok((s, p)),
+        }
+    }
+}
+
+fn parse_blob(p: ParserState<'_>) -> ParseResult<(Vec<u8>, ParserState<'_>)> {
+    let p = p
+        .accept("0x")
+        .or_else(|_| p.accept("0X"))
+        .map_err(|_| p.error(ParseErrorCause::Expected("0x")))?;
+    let (digits, new_p) = p.take_while(|c| c.is_ascii_hexdigit());
+    if digits.len() % 2 != 0 {
+        return Err(p.error(ParseErrorCause::Other(
+            "Expected an even number of hexadecimal digits",
+        )));
+    }
+    let bytes = (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap())
+        .collect();
+    Ok((bytes, new_p))
+}
+
+// Takes an optionally negative integer, returning the sign and the digits.
+fn take_integer(p: ParserState<'_>) -> ParseResult<(bool, &str, ParserState<'_>)> {
+    let (negative, p) = match p.accept("-") {
+        Ok(p) => (true, p),
+        Err(_) => (false, p),
+    };
+    let (digits, new_p) = p.take_while(|c| c.is_ascii_digit());
+    if digits.is_empty() {
+        return Err(p.error(ParseErrorCause::Expected("integer")));
+    }
+    Ok((negative, digits, new_p))
+}
+
+fn parse_integer<T: std::str::FromStr>(p: ParserState<'_>) -> ParseResult<(T, ParserState<'_>)> {
+    let (_, _, new_p) = take_integer(p)?;
+    let literal = &p.s[..p.get_remaining() - new_p.get_remaining()];
+    let i = literal
+        .parse()
+        .map_err(|_| p.error(ParseErrorCause::Other("Integer out of range of the type")))?;
+    Ok((i, new_p))
+}
+
+fn parse_float<T: std::str::FromStr + From<f32>>(
+    p: ParserState<'_>,
+) -> ParseResult<(T, ParserState<'_>)> {
+    if let Some(p) = accept_keyword(p, "nan") {
+        return Ok((T::from(f32::NAN), p));
+    }
+    if let Some(p) = accept_keyword(p, "infinity") {
+        return Ok((T::from(f32::INFINITY), p));
+    }
+    if let Some(p) = p
+        .accept("-")
+        .ok()
+        .and_then(|p| accept_keyword(p, "infinity"))
+    {
+        return Ok((T::from(f32::NEG_INFINITY), p));
+    }
+    let (literal, new_p) =
+        p.take_while(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'));
+    let f = literal
+        .parse()
+        .map_err(|_| p.error(ParseErrorCause::Expected("floating point number")))?;
+    Ok((f, new_p))
+}
+
+fn parse_decimal(p: ParserState<'_>) -> ParseResult<(CqlDecimal, ParserState<'_>)> {
+    let (negative, integer_part, new_p) = take_integer(p)?;
+    let (fractional_part, new_p) = match new_p.accept(".") {
+        Ok(new_p) => new_p.take_while(|c| c.is_ascii_digit()),
+        Err(_) => ("", new_p),
+    };
+    let (exponent, new_p) = match new_p.accept("e").or_else(|_| new_p.accept("E")) {
+        Ok(new_p) => {
+            let new_p = new_p.accept("+").unwrap_or(new_p);
+            parse_integer::<i64>(new_p)?
+        }
+        Err(_) => (0, new_p),
+    };
+
+    let scale = i32::try_from(fractional_part.len() as i64 - exponent)
+        .map_err(|_| p.error(ParseErrorCause::Other("Decimal scale out of range")))?;
+    let bytes = digits_to_signed_bytes(negative, &format!("{integer_part}{fractional_part}"));
+    Ok((
+        CqlDecimal::from_signed_be_bytes_and_exponent(bytes, scale),
+        new_p,
+    ))
+}
+
+fn parse_uuid(p: ParserState<'_>) -> ParseResult<(Uuid, ParserState<'_>)> {
+    let (literal, new_p) = p.take_while(|c| c.is_ascii_hexdigit() || c == '-');
+    let u = Uuid::try_parse(literal).map_err(|_| p.error(ParseErrorCause::Expected("UUID")))?;
+    Ok((u, new_p))
+}
+
+fn parse_duration(p: ParserState<'_>) -> ParseResult<(CqlDuration, ParserState<'_>)> {
+    let start = p;
+    let (negative, mut p) = match p.accept("-") {
+        Ok(p) => (true, p),
+        Err(_) => (false, p),
+    };
+    let out_of_range = || start.error(ParseErrorCause::Other("Duration out of range"));
+
+    let (mut months, mut days, mut nanos) = (0i128, 0i128, 0i128);
+    loop {
+        let (count, new_p) = p.take_while(|c| c.is_ascii_digit());
+        if count.is_empty() {
+            if p.get_remaining() == start.get_remaining() - usize::from(negative) {
+                return Err(p.error(ParseErrorCause::Expected("duration")));
+            }
+            break;
+        }
+        let count: i128 = count.parse().map_err(|_| out_of_range())?;
+        let (unit, new_p) = new_p.take_while(|c| c.is_alphabetic());
+        let (total, multiplier) = match unit.to_lowercase().as_str() {
+            "y" => (&mut months, 12),
+            "mo" => (&mut months, 1),
+            "w" => (&mut days, 7),
+            "d" => (&mut days, 1),
+            "h" => (&mut nanos, i128::from(NANOS_PER_HOUR)),
+            "m" => (&mut nanos, i128::from(NANOS_PER_MINUTE)),
+            "s" => (&mut nanos, i128::from(NANOS_PER_SECOND)),
+            "ms" => (&mut nanos, 1_000_000),
+            "us" | "µs" => (&mut nanos, 1000),
+            "ns" => (&mut nanos, 1),
+            _ => return Err(new_p.error(ParseErrorCause::Expected("duration unit"))),
+        };
+        *total = count
+            .checked_mul(multiplier)
+            .and_then(|count| total.checked_add(count))
+            .ok_or_else(out_of_range)?;
+        p = new_p;
+    }
+
+    let sign = if negative { -1 } else { 1 };
+    let duration = CqlDuration {
+        months: i32::try_from(sign * months).map_err(|_| out_of_range())?,
+        days: i32::try_from(sign * days).map_err(|_| out_of_range())?,
+        nanoseconds: i64::try_from(sign * nanos).map_err(|_| out_of_range())?,
+    };
+    Ok((duration, p))
+}
+
+fn parse_date(s: &str) -> Option<CqlDate> {
+    if s.chars().all(|c| c.is_ascii_digit()) {
+        return s.parse().ok().map(CqlDate);
+    }
+    let date = chrono_04::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?;
+    let unix_epoch = chrono_04::NaiveDate::from_yo_opt(1970, 1).unwrap();
+    let days = (1 << 31) + date.signed_duration_since(unix_epoch).num_days();
+    u32::try_from(days).ok().map(CqlDate)
+}
+
+fn parse_timestamp(s: &str) -> Option<CqlTimestamp> {
+    use chrono_04::{DateTime, NaiveDate, NaiveDateTime};
+
+    if let Ok(millis) = s.parse() {
+        return Some(CqlTimestamp(millis));
+    }
+
+    // Accept both ' ' and 'T' as the separator of the date and the time,
+    // and 'Z' as the UTC time zone.
+    let mut s = s.replacen('T', " ", 1);
+    if let Some(stripped) = s.strip_suffix('Z') {
+        s = format!("{stripped}+0000");
+    }
+    let with_zone = ["%Y-%m-%d %H:%M:%S%.f%#z", "%Y-%m-%d %H:%M%#z"]
+        .into_iter()
+        .find_map(|format| DateTime::parse_from_str(&s, format).ok())
+        .map(|datetime| datetime.naive_utc());
+    let datetime = with_zone
+        .or_else(|| {
+            ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%d %H:%M"]
+                .into_iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(&s, format).ok())
+        })
+        .or_else(|| {
+            NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+    Some(CqlTimestamp(datetime.and_utc().timestamp_millis()))
+}
+
+fn parse_time(s: &str) -> Option<CqlTime> {
+    let nanos = if s.chars().all(|c| c.is_ascii_digit()) {
+        s.parse().ok()?
+    } else {
+        let (hms, fraction) = s.split_once('.').unwrap_or((s, ""));
+        let mut parts = hms.split(':');
+        let mut next_part = |max: i64| -> Option<i64> {
+            let part = parts.next()?;
+            let value: i64 = part.parse().ok()?;
+            (part.len() == 2 && value < max).then_some(value)
+        };
+        let (hours, minutes, seconds) = (next_part(24)?, next_part(60)?, next_part(60)?);
+        if parts.next().is_some()
+            || fraction.len() > 9
+            || !fraction.chars().all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        let fraction: i64 = format!("{fraction:0<9}").parse().ok()?;
+        hours * NANOS_PER_HOUR + minutes * NANOS_PER_MINUTE + seconds * NANOS_PER_SECOND + fraction
+    };
+    (0..NANOS_PER_DAY)
+        .contains(&nanos)
+        .then_some(CqlTime(nanos))
+}
+
+// Utilities
+
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+const NANOS_PER_MINUTE: i64 = 60 * NANOS_PER_SECOND;
+const NANOS_PER_HOUR: i64 = 60 * NANOS_PER_MINUTE;
+const NANOS_PER_DAY: i64 = 24 * NANOS_PER_HOUR;
+
+fn native_type_name(typ: &NativeType) -> &'static str {
+    match typ {
+        NativeType::Ascii => "ascii",
+        NativeType::Boolean => "boolean",
+        NativeType::Blob => "blob",
+        NativeType::Counter => "counter",
+        NativeType::Date => "date",
+        NativeType::Decimal => "decimal",
+        NativeType::Double => "double",
+        NativeType::Duration => "duration",
+        NativeType::Float => "float",
+        NativeType::Int => "int",
+        NativeType::BigInt => "bigint",
+        NativeType::Text => "text",
+        NativeType::Timestamp => "timestamp",
+        NativeType::Inet => "inet",
+        NativeType::SmallInt => "smallint",
+        NativeType::TinyInt => "tinyint",
+        NativeType::Time => "time",
+        NativeType::Timeuuid => "timeuuid",
+        NativeType::Uuid => "uuid",
+        NativeType::Varint => "varint",
+    }
+}
+
+// Converts a two's complement, big-endian integer of any size to decimal digits.
+pub(super) fn signed_bytes_to_digits(bytes: &[u8]) -> String {
+    let negative = bytes.first().is_some_and(|b| b & 0x80 != 0);
+    let mut magnitude = bytes.to_vec();
+    if negative {
+        // Two's complement negation: invert and add one.
+        for b in magnitude.iter_mut() {
+            *b = !*b;
+        }
+        for b in magnitude.iter_mut().rev() {
+            let (sum, overflow) = b.overflowing_add(1);
+            *b = sum;
+            if !overflow {
+                break;
+            }
+        }
+    }
+
+    // Repeatedly divide by 10^9, collecting 9-digit chunks, least significant first.
+    const CHUNK: u64 = 1_000_000_000;
+    let mut chunks = Vec::new();
+    while magnitude.iter().any(|b| *b != 0) {
+        let mut rem = 0u64;
+        for b in magnitude.iter_mut() {
+            let cur = (rem << 8) | u64::from(*b);
+            *b = (cur / CHUNK) as u8;
+            rem = cur % CHUNK;
+        }
+        chunks.push(rem);
+    }
+
+    let mut digits = String::from(if negative { "-" } else { "" });
+    match chunks.split_last() {
+        Some((most_significant, rest)) => {
+            write!(digits, "{most_significant}").unwrap();
+            for chunk in rest.iter().rev() {
+                write!(digits, "{chunk:09}").unwrap();
+            }
+        }
+        None => digits.push('0'),
+    }
+    digits
+}
+
+// Converts decimal digits to the shortest two's complement, big-endian representation.
+fn digits_to_signed_bytes(negative: bool, digits: &str) -> Vec<u8> {
+    // The leading zero byte leaves room for the sign bit.
+    let mut bytes = vec![0u8];
+    for digit in digits.bytes() {
+        let mut carry = u32::from(digit - b'0');
+        for b in bytes.iter_mut().rev() {
+            let cur = u32::from(*b) * 10 + carry;
+            *b = cur as u8;
+            carry = cur >> 8;
+        }
+        if carry > 0 {
+            bytes.insert(0, carry as u8);
+        }
+        if bytes[0] & 0x80 != 0 {
+            bytes.insert(0, 0);
+        }
+    }
+    if negative {
+        for b in bytes.iter_mut() {
+            *b = !*b;
+        }
+        for b in bytes.iter_mut().rev() {
+            let (sum, overflow) = b.overflowing_add(1);
+            *b = sum;
+            if !overflow {
+                break;
+            }
+        }
+    }
+
+    strip_redundant_signed_be_bytes(&bytes).to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use assert_matches::assert_matches;
+
+    use super::{format_literal, parse_literal, CqlLiteralFormatError};
+    use crate::frame::response::result::{CollectionType, ColumnType, NativeType, UserDefinedType};
+    use crate::utils::parse::ParseErrorCause;
+    use crate::value::{
+        Counter, CqlDate, CqlDecimal, CqlDuration, CqlTime, CqlTimestamp, CqlValue, CqlVarint,
+    };
+
+    fn native(typ: NativeType) -> ColumnType<'static> {
+        ColumnType::Native(typ)
+    }
+
+    fn collection(typ: CollectionType<'static>) -> ColumnType<'static> {
+        ColumnType::Collection { frozen: false, typ }
+    }
+
+    fn udt_type() -> ColumnType<'static> {
+        ColumnType::UserDefinedType {
+            frozen: false,
+            definition: Arc::new(UserDefinedType {
+                name: "address".into(),
+                keyspace: "ks".into(),
+                field_types: vec![
+                    ("street".into(), native(NativeType::Text)),
+                    ("Number".into(), native(NativeType::Int)),
+                    (
+                        "tags".into(),
+                        collection(CollectionType::Set(Box::new(native(NativeType::Text)))),
+                    ),
+                ],
+            }),
+        }
+    }
+
+    // Checks the formatted literal and that it is parsed back into the same value.
+    #[track_caller]
+    fn check(value: CqlValue, typ: &ColumnType, literal: &str) {
+        assert_eq!(format_literal(&value, typ).unwrap(), literal);
+        assert_eq!(parse_literal(literal, typ).unwrap(), Some(value));
+    }
+
+    #[track_caller]
+    fn parse(literal: &str, typ: &ColumnType) -> CqlValue {
+        parse_literal(literal, typ).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_native_literals() {
+        check(
+            CqlValue::Text("it's".into()),
+            &native(NativeType::Text),
+            "'it''s'",
+        );
+        check(
+            CqlValue::Ascii("abc".into()),
+            &native(NativeType::Ascii),
+            "'abc'",
+        );
+        check(
+            CqlValue::Boolean(true),
+            &native(NativeType::Boolean),
+            "true",
+        );
+        check(
+            CqlValue::Blob(vec![0xca, 0xfe]),
+            &native(NativeType::Blob),
+            "0xcafe",
+        );
+        check(
+            CqlValue::Counter(Counter(-7)),
+            &native(NativeType::Counter),
+            "-7",
+        );
+        check(
+            CqlValue::Int(i32::MIN),
+            &native(NativeType::Int),
+            "-2147483648",
+        );
+        check(
+            CqlValue::BigInt(i64::MAX),
+            &native(NativeType::BigInt),
+            "9223372036854775807",
+        );
+        check(CqlValue::SmallInt(-5), &native(NativeType::SmallInt), "-5");
+        check(CqlValue::TinyInt(5), &native(NativeType::TinyInt), "5");
+        check(CqlValue::Double(1.5), &native(NativeType::Double), "1.5");
+        check(
+            CqlValue::Double(1e300),
+            &native(NativeType::Double),
+            "1e300",
+        );
+        check(CqlValue::Float(-0.25), &native(NativeType::Float), "-0.25");
+        check(CqlValue::Float(0.1), &native(NativeType::Float), "0.1");
+        check(
+            CqlValue::Double(f64::NEG_INFINITY),
+            &native(NativeType::Double),
+            "-Infinity",
+        );
+        check(
+            CqlValue::Inet("::1".parse().unwrap()),
+            &native(NativeType::Inet),
+            "'::1'",
+        );
+        check(
+            CqlValue::Uuid("8e14e760-7fa8-11eb-bc66-000000000001".parse().unwrap()),
+            &native(NativeType::Uuid),
+            "8e14e760-7fa8-11eb-bc66-000000000001",
+        );
+        check(
+            CqlValue::Timeuuid("8e14e760-7fa8-11eb-bc66-000000000001".parse().unwrap()),
+            &native(NativeType::Timeuuid),
+            "8e14e760-7fa8-11eb-bc66-000000000001",
+        );
+
+        assert_matches!(
+            parse("nan", &native(NativeType::Float)),
+            CqlValue::Float(f) if f.is_nan()
+        );
+        assert_eq!(
+            parse("$$it's$$", &native(NativeType::Text)),
+            CqlValue::Text("it's".into())
+        );
+        assert_eq!(
+            parse("FALSE", &native(NativeType::Boolean)),
+            CqlValue::Boolean(false)
+        );
+        assert_eq!(
+            parse_literal(" NULL ", &native(NativeType::Int)).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_numeric_literals() {
+        let varint = native(NativeType::Varint);
+        check(
+            CqlValue::Varint(CqlVarint::from_signed_bytes_be(vec![0x00])),
+            &varint,
+            "0",
+        );
+        check(
+            CqlValue::Varint(CqlVarint::from_signed_bytes_be(vec![0x00, 0x80])),
+            &varint,
+            "128",
+        );
+        check(
+            CqlValue::Varint(CqlVarint::from_signed_bytes_be(vec![0xff, 0x7f])),
+            &varint,
+            "-129",
+        );
+        check(
+            CqlValue::Varint(CqlVarint::from_signed_bytes_be(vec![
+                0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x00,
+            ])),
+            &varint,
+            "340282366920938463463374607431768211456",
+        );
+
+        let decimal = native(NativeType::Decimal);
+        // The minimal encoding is needed, because `CqlVarint` normalizes only leading zeros.
+        let dec = |unscaled: i64, scale| {
+            let bytes =
+                super::digits_to_signed_bytes(unscaled < 0, &unscaled.unsigned_abs().to_string());
+            CqlValue::Decimal(CqlDecimal::from_signed_be_bytes_and_exponent(bytes, scale))
+        };
+        check(dec(12345, 2), &decimal, "123.45");
+        check(dec(-5, 3), &decimal, "-0.005");
+        check(dec(150, 2), &decimal, "1.50");
+        check(dec(123, 12), &decimal, "1.23E-10");
+        check(dec(123, -5), &decimal, "1.23E+7");
+        check(dec(0, -3), &decimal, "0E+3");
+        assert_eq!(parse("12e3", &decimal), dec(12, -3));
+    }
+
+    #[test]
+    fn test_date_and_time_literals() {
+        check(
+            CqlValue::Date(CqlDate((1 << 31) + 19753)),
+            &native(NativeType::Date),
+            "'2024-01-31'",
+        );
+        check(CqlValue::Date(CqlDate(0)), &native(NativeType::Date), "'0'");
+
+        let timestamp = native(NativeType::Timestamp);
+        check(
+            CqlValue::Timestamp(CqlTimestamp(1706704200500)),
+            &timestamp,
+            "'2024-01-31 12:30:00.500+0000'",
+        );
+        check(
+            CqlValue::Timestamp(CqlTimestamp(i64::MIN)),
+            &timestamp,
+            "-9223372036854775808",
+        );
+        for literal in [
+            "'2024-01-31T14:30:00.5+02:00'",
+            "'2024-01-31 12:30:00.500Z'",
+            "'2024-01-31 12:30:00.5'",
+            "1706704200500",
+        ] {
+            assert_eq!(
+                parse(literal, &timestamp),
+                CqlValue::Timestamp(CqlTimestamp(1706704200500))
+            );
+        }
+        assert_eq!(
+            parse("'2024-01-31'", &timestamp),
+            CqlValue::Timestamp(CqlTimestamp(1706659200000))
+        );
+
+        let time = native(NativeType::Time);
+        check(
+            CqlValue::Time(CqlTime(45_000_000_000_123)),
+            &time,
+            "'12:30:00.000000123'",
+        );
+        assert_eq!(
+            parse("'12:30:00.5'", &time),
+            CqlValue::Time(CqlTime(45_000_500_000_000))
+        );
+        assert!(parse_literal("'24:00:00'", &time).is_err());
+
+        let duration = native(NativeType::Duration);
+        let dur = |months, days, nanoseconds| {
+            CqlValue::Duration(CqlDuration {
+                months,
+                days,
+                nanoseconds,
+            })
+        };
+        check(dur(14, 3, 5_400_000_001_000), &duration, "1y2mo3d1h30m1us");
+        check(dur(0, -1, -1), &duration, "-1d1ns");
+        check(dur(0, 0, 0), &duration, "0s");
+        assert_eq!(parse("1W90m", &duration), dur(0, 7, 5_400_000_000_000));
+        assert_matches!(
+            format_literal(&dur(1, -1, 0), &duration),
+            Err(CqlLiteralFormatError::InvalidValue { .. })
+        );
+    }
+
+    #[test]
+    fn test_compound_literals() {
+        let list = collection(CollectionType::List(Box::new(native(NativeType::Int))));
+        check(
+            CqlValue::List(vec![CqlValue::Int(1), CqlValue::Int(2)]),
+            &list,
+            "[1, 2]",
+        );
+        check(CqlValue::List(vec![]), &list, "[]");
+
+        let set = collection(CollectionType::Set(Box::new(native(NativeType::Text))));
+        check(
+            CqlValue::Set(vec![CqlValue::Text("a".into())]),
+            &set,
+            "{'a'}",
+        );
+
+        let map = collection(CollectionType::Map(
+            Box::new(native(NativeType::Int)),
+            Box::new(list.clone()),
+        ));
+        check(
+            CqlValue::Map(vec![(
+                CqlValue::Int(1),
+                CqlValue::List(vec![CqlValue::Int(2)]),
+            )]),
+            &map,
+            "{1: [2]}",
+        );
+
+        let vector = ColumnType::Vector {
+            typ: Box::new(native(NativeType::Float)),
+            dimensions: 2,
+        };
+        check(
+            CqlValue::Vector(vec![CqlValue::Float(1.0), CqlValue::Float(0.5)]),
+            &vector,
+            "[1.0, 0.5]",
+        );
+        assert!(parse_literal("[1.0]", &vector).is_err());
+
+        let tuple = ColumnType::Tuple(vec![native(NativeType::Int), native(NativeType::Text)]);
+        check(
+            CqlValue::Tuple(vec![Some(CqlValue::Int(1)), None]),
+            &tuple,
+            "(1, null)",
+        );
+
+        let udt = udt_type();
+        let value = CqlValue::UserDefinedType {
+            keyspace: "ks".into(),
+            name: "address".into(),
+            fields: vec![
+                ("street".into(), Some(CqlValue::Text("Main".into()))),
+                ("Number".into(), Some(CqlValue::Int(5))),
+                ("tags".into(), None),
+            ],
+        };
+        check(
+            value.clone(),
+            &udt,
+            "{street: 'Main', \"Number\": 5, tags: null}",
+        );
+        assert_eq!(parse("{ \"Number\" : 5, STREET: 'Main' }", &udt), value);
+    }
+
+    #[test]
+    fn test_empty_values() {
+        check(CqlValue::Empty, &native(NativeType::Int), "blobAsInt(0x)");
+        check(
+            CqlValue::Empty,
+            &native(NativeType::BigInt),
+            "blobAsBigint(0x)",
+        );
+        assert_eq!(
+            parse("''", &native(NativeType::Text)),
+            CqlValue::Text("".into())
+        );
+        assert_eq!(
+            parse("blobasint(0x0000002a)", &native(NativeType::Int)),
+            CqlValue::Int(42)
+        );
+    }
+
+    #[test]
+    fn test_literal_errors() {
+        assert_matches!(
+            format_literal(&CqlValue::Int(1), &native(NativeType::Text)),
+            Err(CqlLiteralFormatError::TypeMismatch { .. })
+        );
+
+        let err = parse_literal(
+            "[1, 'a']",
+            &collection(CollectionType::List(Box::new(native(NativeType::Int)))),
+        )
+        .unwrap_err();
+        assert_eq!(err.position(), 5);
+        assert_eq!(err.cause(), ParseErrorCause::Expected("integer"));
+
+        let err = parse_literal("128", &native(NativeType::TinyInt)).unwrap_err();
+        assert_eq!(err.position(), 1);
+
+        let err = parse_literal("1 2", &native(NativeType::Int)).unwrap_err();
+        assert_eq!(err.position(), 3);
+
+        let err = parse_literal("{nope: 1}", &udt_type()).unwrap_err();
+        assert_eq!(
+            err.cause(),
+            ParseErrorCause::Other("No such field in the UDT")
+        );
+
+        assert!(parse_literal("'é'", &native(NativeType::Ascii)).is_err());
+        assert!(parse_literal("0xabc", &native(NativeType::Blob)).is_err());
+        assert!(parse_literal(
+            "[null]",
+            &collection(CollectionType::List(Box::new(native(NativeType::Int,))))
+        )
+        .is_err());
+    }
+}
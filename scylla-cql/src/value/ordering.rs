@@ -0,0 +1,887 @@
+//! Ordering and hashing of [`CqlValue`]s, consistent with the database.
+//!
+//! The order of values depends on their CQL type, e.g. `timeuuid`s are ordered
+//! by their timestamps, `blob`s by their unsigned bytes and `decimal`s by their numeric
+//! values. [`compare_values`] orders values of the given type in the same way
+//! as Scylla orders clustering keys, which allows to merge the results of several
+//! queries client-side. [`hash_value`] is consistent with the ordering: values which
+//! compare as equal have equal hashes, e.g. `decimal`s `1.0` and `1.00`.
+//!
+//! [`OrderedCqlValue`] implements [`Ord`] and [`Hash`] with the above functions,
+//! so that the values can be sorted or put into sets and maps:
+//!
+//! ```rust
+//! # use std::collections::HashSet;
+//! # use scylla_cql::frame::response::result::{ColumnType, NativeType};
+//! # use scylla_cql::value::{CqlDecimal, CqlValue};
+//! # use scylla_cql::value::ordering::OrderedCqlValue;
+//! let typ = ColumnType::Native(NativeType::Decimal);
+//! let values = [
+//!     CqlValue::Decimal(CqlDecimal::from_signed_be_bytes_and_exponent(vec![10], 1)),
+//!     CqlValue::Decimal(CqlDecimal::from_signed_be_bytes_and_exponent(vec![100], 2)),
+//! ];
+//! let unique = values
+//!     .iter()
+//!     .map(|value| OrderedCqlValue::new(value, &typ))
+//!     .collect::<Result<HashSet<_>, _>>()
+//!     .unwrap();
+//! assert_eq!(unique.len(), 1);
+//! ```
+//!
+//! # Order of values
+//!
+//! - Empty values are ordered before all other values of the type.
+//!   Empty `ascii`, `text` and `blob` values are equal to empty strings and blobs.
+//! - `ascii`, `text`, `blob` and `inet` values are ordered by their unsigned bytes.
+//! - `float` and `double` values are ordered numerically, with `-0.0` ordered
+//!   before `0.0`, and NaNs equal to each other and ordered after all other values.
+//! - `timeuuid`s are ordered by their timestamps, then by the remaining bytes.
+//!   `uuid`s are ordered by their versions, then as `timeuuid`s for version 1,
+//!   and by their unsigned bytes for other versions.
+//! - `duration`s, which can't be clustering keys, are ordered by the months,
+//!   the days and the nanoseconds.
+//! - Lists, sets, maps, vectors, tuples and UDTs are ordered lexicographically
+//!   by their elements, in the order in which they are stored in the value.
+//!   Sets and maps received from the database are always sorted.
+//!   Nulls in tuples and UDTs are ordered before other values.
+
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::net::IpAddr;
+
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::literal::signed_bytes_to_digits;
+use super::{strip_redundant_signed_be_bytes, CqlDecimal, CqlTimeuuid, CqlValue, CqlVarint};
+use crate::frame::response::result::{CollectionType, ColumnType, NativeType};
+
+/// A value does not belong to the CQL type it was compared or hashed as.
+#[derive(Debug, Clone, Error)]
+#[error("Value {value} does not match the CQL type {typ:?}")]
+pub struct CqlValueTypeMismatch {
+    pub value: CqlValue,
+    pub typ: ColumnType<'static>,
+}
+
+impl CqlValueTypeMismatch {
+    fn new(value: &CqlValue, typ: &ColumnType) -> Self {
+        Self {
+            value: value.clone(),
+            typ: typ.clone().into_owned(),
+        }
+    }
+}
+
+/// Compares two values of the given CQL type in the same way as the database does.
+///
+/// Only the parts of the values needed to determine the order are checked
+/// against the type, e.g. elements of lists after the first difference are not checked.
+pub fn compare_values(
+    a: &CqlValue,
+    b: &CqlValue,
+    typ: &ColumnType,
+) -> Result<Ordering, CqlValueTypeMismatch> {
+    use CqlValue as V;
+
+    for value in [a, b] {
+        if !check_type_shallow(value, typ) {
+            return Err(CqlValueTypeMismatch::new(value, typ));
+        }
+    }
+    if let (Some(a), Some(b)) = (as_bytes(a, typ), as_bytes(b, typ)) {
+        return Ok(a.cmp(b));
+    }
+
+    let ord = match (typ, a, b) {
+        (_, V::Empty, V::Empty) => Ordering::Equal,
+        (_, V::Empty, _) => Ordering::Less,
+        (_, _, V::Empty) => Ordering::Greater,
+        (ColumnType::Native(NativeType::Boolean), V::Boolean(a), V::Boolean(b)) => a.cmp(b),
+        (ColumnType::Native(NativeType::Counter), V::Counter(a), V::Counter(b)) => a.0.cmp(&b.0),
+        (ColumnType::Native(NativeType::Decimal), V::Decimal(a), V::Decimal(b)) => {
+            NormalizedDecimal::new(a).cmp(&NormalizedDecimal::new(b))
+        }
+        (ColumnType::Native(NativeType::Double), V::Double(a), V::Double(b)) => {
+            compare_floats(*a, *b)
+        }
+        (ColumnType::Native(NativeType::Float), V::Float(a), V::Float(b)) => {
+            compare_floats(f64::from(*a), f64::from(*b))
+        }
+        (ColumnType::Native(NativeType::Int), V::Int(a), V::Int(b)) => a.cmp(b),
+        (ColumnType::Native(NativeType::BigInt), V::BigInt(a), V::BigInt(b)) => a.cmp(b),
+        (ColumnType::Native(NativeType::SmallInt), V::SmallInt(a), V::SmallInt(b)) => a.cmp(b),
+        (ColumnType::Native(NativeType::TinyInt), V::TinyInt(a), V::TinyInt(b)) => a.cmp(b),
+        (ColumnType::Native(NativeType::Varint), V::Varint(a), V::Varint(b)) => {
+            compare_varints(a, b)
+        }
+        (ColumnType::Native(NativeType::Date), V::Date(a), V::Date(b)) => a.0.cmp(&b.0),
+        (ColumnType::Native(NativeType::Time), V::Time(a), V::Time(b)) => a.0.cmp(&b.0),
+        (ColumnType::Native(NativeType::Timestamp), V::Timestamp(a), V::Timestamp(b)) => {
+            a.0.cmp(&b.0)
+        }
+        (ColumnType::Native(NativeType::Duration), V::Duration(a), V::Duration(b)) => {
+            (a.months, a.days, a.nanoseconds).cmp(&(b.months, b.days, b.nanoseconds))
+        }
+        (ColumnType::Native(NativeType::Inet), V::Inet(a), V::Inet(b)) => {
+            inet_octets(a).cmp(&inet_octets(b))
+        }
+        (ColumnType::Native(NativeType::Uuid), V::Uuid(a), V::Uuid(b)) => compare_uuids(a, b),
+        (ColumnType::Native(NativeType::Timeuuid), V::Timeuuid(a), V::Timeuuid(b)) => a.cmp(b),
+        (
+            ColumnType::Collection {
+                typ: CollectionType::List(typ) | CollectionType::Set(typ),
+                ..
+            },
+            V::List(a) | V::Set(a),
+            V::List(b) | V::Set(b),
+        )
+        | (ColumnType::Vector { typ, .. }, V::Vector(a), V::Vector(b)) => {
+            compare_sequences(a.iter(), b.iter(), |a, b| compare_values(a, b, typ))?
+        }
+        (
+            ColumnType::Collection {
+                typ: CollectionType::Map(key_typ, value_typ),
+                ..
+            },
+            V::Map(a),
+            V::Map(b),
+        ) => compare_sequences(a.iter(), b.iter(), |(a_key, a_value), (b_key, b_value)| {
+            match compare_values(a_key, b_key, key_typ)? {
+                Ordering::Equal => compare_values(a_value, b_value, value_typ),
+                ord => Ok(ord),
+            }
+        })?,
+        (ColumnType::Tuple(typs), V::Tuple(a), V::Tuple(b)) => compare_sequences(
+            a.iter().zip(typs),
+            b.iter().zip(typs),
+            |(a, typ), (b, _)| compare_nullable(a.as_ref(), b.as_ref(), typ),
+        )?,
+        (
+            ColumnType::UserDefinedType { definition, .. },
+            V::UserDefinedType { fields: a, .. },
+            V::UserDefinedType { fields: b, .. },
+        ) => {
+            let typs = &definition.field_types;
+            compare_sequences(
+                a.iter().zip(typs),
+                b.iter().zip(typs),
+                |(a, (_, typ)), (b, _)| compare_nullable(a.1.as_ref(), b.1.as_ref(), typ),
+            )?
+        }
+        _ => unreachable!("The values were checked against the type"),
+    };
+    Ok(ord)
+}
+
+/// Feeds the value of the given CQL type into the hasher, consistently with [`compare_values`]:
+/// values which compare as equal are hashed in the same way.
+pub fn hash_value<H: Hasher>(
+    value: &CqlValue,
+    typ: &ColumnType,
+    state: &mut H,
+) -> Result<(), CqlValueTypeMismatch> {
+    use CqlValue as V;
+
+    if !check_type_shallow(value, typ) {
+        return Err(CqlValueTypeMismatch::new(value, typ));
+    }
+    if let Some(bytes) = as_bytes(value, typ) {
+        bytes.hash(state);
+        return Ok(());
+    }
+
+    match (typ, value) {
+        (_, V::Empty) => state.write_u8(0),
+        (ColumnType::Native(NativeType::Boolean), V::Boolean(v)) => v.hash(state),
+        (ColumnType::Native(NativeType::Counter), V::Counter(v)) => v.0.hash(state),
+        (ColumnType::Native(NativeType::Decimal), V::Decimal(v)) => {
+            NormalizedDecimal::new(v).hash(state)
+        }
+        (ColumnType::Native(NativeType::Double), V::Double(v)) => hash_float(*v, state),
+        (ColumnType::Native(NativeType::Float), V::Float(v)) => hash_float(f64::from(*v), state),
+        (ColumnType::Native(NativeType::Int), V::Int(v)) => v.hash(state),
+        (ColumnType::Native(NativeType::BigInt), V::BigInt(v)) => v.hash(state),
+        (ColumnType::Native(NativeType::SmallInt), V::SmallInt(v)) => v.hash(state),
+        (ColumnType::Native(NativeType::TinyInt), V::TinyInt(v)) => v.hash(state),
+        (ColumnType::Native(NativeType::Varint), V::Varint(v)) => {
+            normalized_varint(v.as_signed_bytes_be_slice()).hash(state)
+        }
+        (ColumnType::Native(NativeType::Date), V::Date(v)) => v.0.hash(state),
+        (ColumnType::Native(NativeType::Time), V::Time(v)) => v.0.hash(state),
+        (ColumnType::Native(NativeType::Timestamp), V::Timestamp(v)) => v.0.hash(state),
+        (ColumnType::Native(NativeType::Duration), V::Duration(v)) => {
+            (v.months, v.days, v.nanoseconds).hash(state)
+        }
+        (ColumnType::Native(NativeType::Inet), V::Inet(v)) => inet_octets(v).hash(state),
+        (ColumnType::Native(NativeType::Uuid), V::Uuid(v)) => v.hash(state),
+        (ColumnType::Native(NativeType::Timeuuid), V::Timeuuid(v)) => v.hash(state),
+        (
+            ColumnType::Collection {
+                typ: CollectionType::List(typ) | CollectionType::Set(typ),
+                ..
+            },
+            V::List(elems) | V::Set(elems),
+        )
+        | (ColumnType::Vector { typ, .. }, V::Vector(elems)) => {
+            state.write_usize(elems.len());
+            for elem in elems {
+                hash_value(elem, typ, state)?;
+            }
+        }
+        (
+            ColumnType::Collection {
+                typ: CollectionType::Map(key_typ, value_typ),
+                ..
+            },
+            V::Map(entries),
+        ) => {
+            state.write_usize(entries.len());
+            for (key, value) in entries {
+                hash_value(key, key_typ, state)?;
+                hash_value(value, value_typ, state)?;
+            }
+        }
+        (ColumnType::Tuple(typs), V::Tuple(elems)) => {
+            state.write_usize(elems.len());
+            for (elem, typ) in elems.iter().zip(typs) {
+                hash_nullable(elem.as_ref(), typ, state)?;
+            }
+        }
+        (ColumnType::UserDefinedType { definition, .. }, V::UserDefinedType { fields, .. }) => {
+            state.write_usize(fields.len());
+            for ((_, field), (_, typ)) in fields.iter().zip(&definition.field_types) {
+                hash_nullable(field.as_ref(), typ, state)?;
+            }
+        }
+        _ => unreachable!("The value was checked against the type"),
+    }
+    Ok(())
+}
+
+/// A [`CqlValue`] of a CQL type, ordered and hashed in the same way as in the database,
+/// with [`compare_values`] and [`hash_value`].
+///
+/// The whole value is checked against the type on construction.
+///
+/// # Panics
+///
+/// Comparing values of different types panics if they don't belong to the type
+/// of the left hand side value.
+#[derive(Clone, Copy, Debug)]
+pub struct OrderedCqlValue<'a> {
+    value: &'a CqlValue,
+    typ: &'a ColumnType<'a>,
+}
+
+impl<'a> OrderedCqlValue<'a> {
+    /// Checks that the value belongs to the type.
+    pub fn new(value: &'a CqlValue, typ: &'a ColumnType<'a>) -> Result<Self, CqlValueTypeMismatch> {
+        check_type(value, typ)?;
+        Ok(Self { value, typ })
+    }
+
+    /// Returns the value.
+    #[inline]
+    pub fn value(&self) -> &'a CqlValue {
+        self.value
+    }
+
+    /// Returns the CQL type of the value.
+    #[inline]
+    pub fn typ(&self) -> &'a ColumnType<'a> {
+        self.typ
+    }
+}
+
+impl Ord for OrderedCqlValue<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_values(self.value, other.value, self.typ)
+            .expect("OrderedCqlValues of different types were compared")
+    }
+}
+
+impl PartialOrd for OrderedCqlValue<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for OrderedCqlValue<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OrderedCqlValue<'_> {}
+
+impl Hash for OrderedCqlValue<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_value(self.value, self.typ, state)
+            .expect("The value was checked against the type on construction")
+    }
+}
+
+// Utilities
+
+// Values of types which are ordered by their serialized bytes.
+fn as_bytes<'a>(value: &'a CqlValue, typ: &ColumnType) -> Option<&'a [u8]> {
+    match (typ, value) {
+        (
+            ColumnType::Native(NativeType::Ascii | NativeType::Text | NativeType::Blob),
+            CqlValue::Empty,
+        ) => Some(&[]),
+        (
+            ColumnType::Native(NativeType::Ascii | NativeType::Text),
+            CqlValue::Ascii(s) | CqlValue::Text(s),
+        ) => Some(s.as_bytes()),
+        (ColumnType::Native(NativeType::Blob), CqlValue::Blob(b)) => Some(b),
+        _ => None,
+    }
+}
+
+fn compare_sequences<I, T>(
+    a: I,
+    b: I,
+    mut compare: impl FnMut(T, T) -> Result<Ordering, CqlValueTypeMismatch>,
+) -> Result<Ordering, CqlValueTypeMismatch>
+where
+    I: Iterator<Item = T>,
+{
+    let (mut a, mut b) = (a, b);
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return Ok(Ordering::Equal),
+            (None, Some(_)) => return Ok(Ordering::Less),
+            (Some(_), None) => return Ok(Ordering::Greater),
+            (Some(a), Some(b)) => match compare(a, b)? {
+                Ordering::Equal => {}
+                ord => return Ok(ord),
+            },
+        }
+    }
+}
+
+fn compare_nullable(
+    a: Option<&CqlValue>,
+    b: Option<&CqlValue>,
+    typ: &ColumnType,
+) -> Result<Ordering, CqlValueTypeMismatch> {
+    match (a, b) {
+        (Some(a), Some(b)) => compare_values(a, b, typ),
+        (a, b) => Ok(a.is_some().cmp(&b.is_some())),
+    }
+}
+
+fn hash_nullable<H: Hasher>(
+    value: Option<&CqlValue>,
+    typ: &ColumnType,
+    state: &mut H,
+) -> Result<(), CqlValueTypeMismatch> {
+    state.write_u8(value.is_some().into());
+    match value {
+        Some(value) => hash_value(value, typ, state),
+        None => Ok(()),
+    }
+}
+
+// The same order as Java's `Double::compare`, used by Cassandra.
+fn compare_floats(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.total_cmp(&b),
+    }
+}
+
+fn hash_float<H: Hasher>(f: f64, state: &mut H) {
+    let bits = if f.is_nan() {
+        f64::NAN.to_bits()
+    } else {
+        f.to_bits()
+    };
+    bits.hash(state);
+}
+
+fn inet_octets(ip: &IpAddr) -> Vec<u8> {
+    match ip {
+        IpAddr::V4(ip) => ip.octets().to_vec(),
+        IpAddr::V6(ip) => ip.octets().to_vec(),
+    }
+}
+
+// The same order as Scylla's `uuid` type.
+fn compare_uuids(a: &Uuid, b: &Uuid) -> Ordering {
+    let version = a.get_version_num();
+    version.cmp(&b.get_version_num()).then_with(|| {
+        if version == 1 {
+            CqlTimeuuid::from(*a).cmp(&CqlTimeuuid::from(*b))
+        } else {
+            a.as_bytes().cmp(b.as_bytes())
+        }
+    })
+}
+
+// Strips redundant leading bytes of a two's complement integer.
+fn normalized_varint(bytes: &[u8]) -> &[u8] {
+    if bytes.is_empty() {
+        return &[0];
+    }
+    strip_redundant_signed_be_bytes(bytes)
+}
+
+fn compare_varints(a: &CqlVarint, b: &CqlVarint) -> Ordering {
+    let a = normalized_varint(a.as_signed_bytes_be_slice());
+    let b = normalized_varint(b.as_signed_bytes_be_slice());
+    let (a_negative, b_negative) = (a[0] & 0x80 != 0, b[0] & 0x80 != 0);
+    // Normalized numbers of the same sign and length are ordered by their bytes,
+    // longer positive numbers are greater and longer negative numbers are smaller.
+    b_negative.cmp(&a_negative).then_with(|| {
+        let by_length = if a_negative {
+            b.len().cmp(&a.len())
+        } else {
+            a.len().cmp(&b.len())
+        };
+        by_length.then_with(|| a.cmp(b))
+    })
+}
+
+// A decimal number in the form of `sign * 0.digits * 10^exponent`,
+// without leading and trailing zeros of the digits.
+#[derive(PartialEq, Eq, Hash)]
+struct NormalizedDecimal {
+    sign: Ordering,
+    digits: String,
+    exponent: i64,
+}
+
+impl NormalizedDecimal {
+    fn new(decimal: &CqlDecimal) -> Self {
+        let (bytes, scale) = decimal.as_signed_be_bytes_slice_and_exponent();
+        let digits = signed_bytes_to_digits(bytes);
+        let (negative, digits) = match digits.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, digits.as_str()),
+        };
+        let digits = digits.trim_start_matches('0');
+        if digits.is_empty() {
+            return Self {
+                sign: Ordering::Equal,
+                digits: String::new(),
+                exponent: 0,
+            };
+        }
+        Self {
+            sign: if negative {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            },
+            exponent: digits.len() as i64 - i64::from(scale),
+            digits: digits.trim_end_matches('0').to_owned(),
+        }
+    }
+}
+
+impl Ord for NormalizedDecimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sign.cmp(&other.sign).then_with(|| {
+            let magnitude = self
+                .exponent
+                .cmp(&other.exponent)
+                .then_with(|| self.digits.cmp(&other.digits));
+            match self.sign {
+                Ordering::Less => magnitude.reverse(),
+                _ => magnitude,
+            }
+        })
+    }
+}
+
+impl PartialOrd for NormalizedDecimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Checks whether the top level of the value matches the type.
+fn check_type_shallow(value: &CqlValue, typ: &ColumnType) -> bool {
+    use CqlValue as V;
+
+    match (typ, value) {
+        (_, V::Empty) => true,
+        (ColumnType::Native(native), value) => matches!(
+            (native, value),
+            (
+                NativeType::Ascii | NativeType::Text,
+                V::Ascii(_) | V::Text(_)
+            ) | (NativeType::Boolean, V::Boolean(_))
+                | (NativeType::Blob, V::Blob(_))
+                | (NativeType::Counter, V::Counter(_))
+                | (NativeType::Date, V::Date(_))
+                | (NativeType::Decimal, V::Decimal(_))
+                | (NativeType::Double, V::Double(_))
+                | (NativeType::Duration, V::Duration(_))
+                | (NativeType::Float, V::Float(_))
+                | (NativeType::Int, V::Int(_))
+                | (NativeType::BigInt, V::BigInt(_))
+                | (NativeType::Timestamp, V::Timestamp(_))
+                | (NativeType::Inet, V::Inet(_))
+                | (NativeType::SmallInt, V::SmallInt(_))
+                | (NativeType::TinyInt, V::TinyInt(_))
+                | (NativeType::Time, V::Time(_))
+                | (NativeType::Timeuuid, V::Timeuuid(_))
+                | (NativeType::Uuid, V::Uuid(_))
+                | (NativeType::Varint, V::Varint(_))
+        ),
+        (
+            ColumnType::Collection {
+                typ: CollectionType::List(_),
+                ..
+            },
+            V::List(_),
+        )
+        | (
+            ColumnType::Collection {
+                typ: CollectionType::Set(_),
+                ..
+            },
+            V::Set(_),
+        )
+        | (
+            ColumnType::Collection {
+                typ: CollectionType::Map(_, _),
+                ..
+            },
+            V::Map(_),
+        ) => true,
+        (ColumnType::Vector { dimensions, .. }, V::Vector(elems)) => {
+            elems.len() == usize::from(*dimensions)
+        }
+        (ColumnType::Tuple(typs), V::Tuple(elems)) => elems.len() <= typs.len(),
+        (ColumnType::UserDefinedType { definition, .. }, V::UserDefinedType { fields, .. }) => {
+            fields.len() <= definition.field_types.len()
+                && fields
+                    .iter()
+                    .zip(&definition.field_types)
+                    .all(|((name, _), (def_name, _))| name == def_name)
+        }
+        _ => false,
+    }
+}
+
+fn check_type(value: &CqlValue, typ: &ColumnType) -> Result<(), CqlValueTypeMismatch> {
+    if !check_type_shallow(value, typ) {
+        return Err(CqlValueTypeMismatch::new(value, typ));
+    }
+    match (typ, value) {
+        (
+            ColumnType::Collection {
+                typ: CollectionType::List(typ) | CollectionType::Set(typ),
+                ..
+            },
+            CqlValue::List(elems) | CqlValue::Set(elems),
+        )
+        | (ColumnType::Vector { typ, .. }, CqlValue::Vector(elems)) => {
+            elems.iter().try_for_each(|elem| check_type(elem, typ))
+        }
+        (
+            ColumnType::Collection {
+                typ: CollectionType::Map(key_typ, value_typ),
+                ..
+            },
+            CqlValue::Map(entries),
+        ) => entries.iter().try_for_each(|(key, value)| {
+            check_type(key, key_typ)?;
+            check_type(value, value_typ)
+        }),
+        (ColumnType::Tuple(typs), CqlValue::Tuple(elems)) => elems
+            .iter()
+            .zip(typs)
+            .filter_map(|(elem, typ)| elem.as_ref().map(|elem| (elem, typ)))
+            .try_for_each(|(elem, typ)| check_type(elem, typ)),
+        (
+            ColumnType::UserDefinedType { definition, .. },
+            CqlValue::UserDefinedType { fields, .. },
+        ) => fields
+            .iter()
+            .zip(&definition.field_types)
+            .filter_map(|((_, field), (_, typ))| field.as_ref().map(|field| (field, typ)))
+            .try_for_each(|(field, typ)| check_type(field, typ)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashSet;
+    use std::hash::Hasher;
+    use std::sync::Arc;
+
+    use assert_matches::assert_matches;
+    use uuid::Uuid;
+
+    use super::{compare_values, hash_value, CqlValueTypeMismatch, OrderedCqlValue};
+    use crate::frame::response::result::{CollectionType, ColumnType, NativeType, UserDefinedType};
+    use crate::value::literal::parse_literal;
+    use crate::value::{CqlDecimal, CqlValue, CqlVarint};
+
+    fn native(typ: NativeType) -> ColumnType<'static> {
+        ColumnType::Native(typ)
+    }
+
+    fn hash(value: &CqlValue, typ: &ColumnType) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hash_value(value, typ, &mut hasher).unwrap();
+        hasher.finish()
+    }
+
+    // Checks that the literals are sorted in the ascending order of the type,
+    // with equal neighbours separated by `=`.
+    #[track_caller]
+    fn check_order(typ: &ColumnType, literals: &[&str]) {
+        let mut values = vec![];
+        let mut equal_to_previous = vec![];
+        let mut next_equal = false;
+        for literal in literals {
+            if *literal == "=" {
+                next_equal = true;
+                continue;
+            }
+            values.push(parse_literal(literal, typ).unwrap().unwrap());
+            equal_to_previous.push(std::mem::take(&mut next_equal));
+        }
+
+        for (i, a) in values.iter().enumerate() {
+            for (j, b) in values.iter().enumerate() {
+                let equal = (i.min(j) + 1..=i.max(j)).all(|k| equal_to_previous[k]);
+                let expected = if equal { Ordering::Equal } else { i.cmp(&j) };
+                assert_eq!(
+                    compare_values(a, b, typ).unwrap(),
+                    expected,
+                    "{} vs {}",
+                    literals.iter().filter(|l| **l != "=").nth(i).unwrap(),
+                    literals.iter().filter(|l| **l != "=").nth(j).unwrap(),
+                );
+                if equal {
+                    assert_eq!(hash(a, typ), hash(b, typ));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_native_ordering() {
+        check_order(&native(NativeType::Int), &["blobAsInt(0x)", "-5", "0", "7"]);
+        check_order(
+            &native(NativeType::Text),
+            &["''", "=", "blobAsText(0x)", "'B'", "'a'", "'ab'", "'ą'"],
+        );
+        check_order(
+            &native(NativeType::Blob),
+            &["0x", "0x00", "0x01ff", "0x7f", "0x80"],
+        );
+        check_order(
+            &native(NativeType::Double),
+            &[
+                "-Infinity",
+                "-1.5",
+                "-0.0",
+                "0.0",
+                "1e300",
+                "Infinity",
+                "NaN",
+            ],
+        );
+        check_order(
+            &native(NativeType::Varint),
+            &[
+                "-100000000000000000000",
+                "-129",
+                "-128",
+                "-1",
+                "0",
+                "127",
+                "128",
+                "100000000000000000000",
+            ],
+        );
+        check_order(
+            &native(NativeType::Decimal),
+            &[
+                "-10",
+                "-1.5",
+                "-0.001",
+                "0",
+                "=",
+                "0.000",
+                "=",
+                "0E+5",
+                "0.0011",
+                "1",
+                "=",
+                "1.00",
+                "=",
+                "0.1E+1",
+                "12E-1",
+                "=",
+                "1.2E+0",
+                "1.5",
+                "123456789012345678901234567890",
+            ],
+        );
+        check_order(
+            &native(NativeType::Date),
+            &["'0'", "'1969-12-31'", "'1970-01-01'", "'4294967295'"],
+        );
+        check_order(
+            &native(NativeType::Timestamp),
+            &["'1969-12-31 23:59:59Z'", "0", "'2024-01-31'"],
+        );
+        check_order(
+            &native(NativeType::Inet),
+            &["'::1'", "'ff::'", "'1.2.3.4'", "'10.0.0.1'"],
+        );
+    }
+
+    #[test]
+    fn test_uuid_ordering() {
+        // Timeuuids are ordered by their timestamps, then by signed bytes of the rest.
+        let timeuuid = native(NativeType::Timeuuid);
+        check_order(
+            &timeuuid,
+            &[
+                "00000000-0000-1000-8000-000000000000",
+                "00000000-0000-1000-0000-000000000001",
+                "00000001-0000-1000-7fff-ffffffffffff",
+                "ffffffff-ffff-1fff-ffff-ffffffffffff",
+            ],
+        );
+
+        // Uuids are ordered by their versions first, and by unsigned bytes
+        // for versions other than 1.
+        let uuid = native(NativeType::Uuid);
+        check_order(
+            &uuid,
+            &[
+                "00000000-0000-1000-8000-000000000000",
+                "ffffffff-ffff-1fff-ffff-ffffffffffff",
+                "00000000-0000-4000-0000-000000000000",
+                "00000000-0000-4000-8000-000000000000",
+                "ffffffff-ffff-4fff-ffff-ffffffffffff",
+            ],
+        );
+        let nil = CqlValue::Uuid(Uuid::nil());
+        let v4 = CqlValue::Uuid(Uuid::from_u128(0x4000 << 64));
+        assert_eq!(compare_values(&nil, &v4, &uuid).unwrap(), Ordering::Less);
+    }
+
+    #[test]
+    fn test_compound_ordering() {
+        let int_list = ColumnType::Collection {
+            frozen: true,
+            typ: CollectionType::List(Box::new(native(NativeType::Int))),
+        };
+        check_order(&int_list, &["[]", "[-1]", "[-1, 5]", "[0]"]);
+
+        let map = ColumnType::Collection {
+            frozen: true,
+            typ: CollectionType::Map(
+                Box::new(native(NativeType::Text)),
+                Box::new(native(NativeType::Decimal)),
+            ),
+        };
+        check_order(
+            &map,
+            &[
+                "{}",
+                "{'a': 1}",
+                "=",
+                "{'a': 1.0}",
+                "{'a': 2}",
+                "{'a': 2, 'b': 0}",
+                "{'b': 0}",
+            ],
+        );
+
+        let tuple = ColumnType::Tuple(vec![native(NativeType::Int), native(NativeType::Text)]);
+        check_order(
+            &tuple,
+            &[
+                "()",
+                "(null)",
+                "(null, 'a')",
+                "(1)",
+                "(1, null)",
+                "(1, '')",
+                "(2, null)",
+            ],
+        );
+
+        let udt = ColumnType::UserDefinedType {
+            frozen: true,
+            definition: Arc::new(UserDefinedType {
+                name: "udt".into(),
+                keyspace: "ks".into(),
+                field_types: vec![
+                    ("a".into(), native(NativeType::Int)),
+                    ("b".into(), int_list.clone()),
+                ],
+            }),
+        };
+        check_order(
+            &udt,
+            &["{}", "=", "{a: null}", "{a: 1}", "{a: 1, b: [1]}", "{a: 2}"],
+        );
+    }
+
+    #[test]
+    fn test_ordered_cql_value() {
+        let typ = native(NativeType::Varint);
+        let values = [
+            CqlValue::Varint(CqlVarint::from_signed_bytes_be(vec![0xff, 0xff])),
+            CqlValue::Varint(CqlVarint::from_signed_bytes_be(vec![0x01])),
+            CqlValue::Varint(CqlVarint::from_signed_bytes_be(vec![0xff])),
+            CqlValue::Varint(CqlVarint::from_signed_bytes_be(vec![0x00, 0x01])),
+        ];
+        let mut ordered = values
+            .iter()
+            .map(|value| OrderedCqlValue::new(value, &typ).unwrap())
+            .collect::<Vec<_>>();
+        ordered.sort();
+        ordered.dedup();
+        assert_eq!(
+            ordered.iter().map(|v| v.value()).collect::<Vec<_>>(),
+            [&values[0], &values[1]]
+        );
+        assert_eq!(ordered.into_iter().collect::<HashSet<_>>().len(), 2);
+
+        let list = ColumnType::Collection {
+            frozen: true,
+            typ: CollectionType::List(Box::new(native(NativeType::Int))),
+        };
+        let invalid = CqlValue::List(vec![CqlValue::Int(1), CqlValue::Text("a".into())]);
+        assert_matches!(
+            OrderedCqlValue::new(&invalid, &list),
+            Err(CqlValueTypeMismatch {
+                value: CqlValue::Text(_),
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn test_type_mismatch() {
+        let decimal = native(NativeType::Decimal);
+        let one = CqlValue::Decimal(CqlDecimal::from_signed_be_bytes_and_exponent(vec![1], 0));
+        let err = compare_values(&one, &CqlValue::Int(1), &decimal).unwrap_err();
+        assert_eq!(err.value, CqlValue::Int(1));
+
+        let mut hasher = DefaultHasher::new();
+        let err = hash_value(&CqlValue::Int(1), &decimal, &mut hasher).unwrap_err();
+        assert_eq!(err.typ, decimal);
+    }
+}
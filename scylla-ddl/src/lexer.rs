@@ -0,0 +1,323 @@
+//! A tokenizer for the subset of CQL used in schema files and checked statements.
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Token {
+    /// An unquoted identifier or keyword, as written.
+    Ident(String),
+    /// A double-quoted identifier, with the quotes removed.
+    QuotedIdent(String),
+    /// A string literal (single-quoted or dollar-quoted), with the quotes removed.
+    Str(String),
+    /// A numeric literal, or another constant starting with a digit (e.g. a UUID).
+    Number(String),
+    /// A positional bind marker.
+    Marker,
+    /// A named bind marker, e.g. `:name`.
+    NamedMarker(String),
+    /// Punctuation or an operator.
+    Symbol(&'static str),
+}
+
+#[derive(Clone, Debug)]
+pub struct Spanned {
+    pub token: Token,
+    /// Byte offset of the token in the source.
+    pub offset: usize,
+}
+
+impl Token {
+    /// Returns whether the token is the given keyword (case insensitive).
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Ident(ident) if ident.eq_ignore_ascii_case(keyword))
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Token::Ident(ident) => format!("`{ident}`"),
+            Token::QuotedIdent(ident) => format!("`\"{ident}\"`"),
+            Token::Str(s) => format!("string literal '{s}'"),
+            Token::Number(n) => format!("`{n}`"),
+            Token::Marker => "`?`".to_owned(),
+            Token::NamedMarker(name) => format!("`:{name}`"),
+            Token::Symbol(s) => format!("`{s}`"),
+        }
+    }
+}
+
+const SYMBOLS: &[&str] = &[
+    "<=", ">=", "!=", "(", ")", "<", ">", "=", ",", ";", ".", "*", "+", "-", "[", "]", "{", "}",
+    ":", "/", "%",
+];
+
+pub fn tokenize(source: &str) -> Result<Vec<Spanned>, (usize, String)> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        let c = bytes[pos];
+        let start = pos;
+
+        if c.is_ascii_whitespace() {
+            pos += 1;
+            continue;
+        }
+
+        // Comments: `-- ...`, `// ...` and `/* ... */`.
+        if source[pos..].starts_with("--") || source[pos..].starts_with("//") {
+            pos = source[pos..]
+                .find('\n')
+                .map_or(bytes.len(), |end| pos + end);
+            continue;
+        }
+        if source[pos..].starts_with("/*") {
+            let end = source[pos + 2..]
+                .find("*/")
+                .ok_or((pos, "unterminated comment".to_owned()))?;
+            pos += 2 + end + 2;
+            continue;
+        }
+
+        let token = if c.is_ascii_alphabetic() || c == b'_' {
+            while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_') {
+                pos += 1;
+            }
+            Token::Ident(source[start..pos].to_owned())
+        } else if c.is_ascii_digit() {
+            // Also covers UUIDs, floats and durations - they are never interpreted.
+            while pos < bytes.len()
+                && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'.' || bytes[pos] == b'-')
+            {
+                pos += 1;
+            }
+            Token::Number(source[start..pos].to_owned())
+        } else if c == b'"' || c == b'\'' {
+            let (content, end) = quoted(source, pos, c as char)?;
+            pos = end;
+            if c == b'"' {
+                Token::QuotedIdent(content)
+            } else {
+                Token::Str(content)
+            }
+        } else if source[pos..].starts_with("$$") {
+            let end = source[pos + 2..]
+                .find("$$")
+                .ok_or((pos, "unterminated string literal".to_owned()))?;
+            pos += 2 + end + 2;
+            Token::Str(source[start + 2..pos - 2].to_owned())
+        } else if c == b'?' {
+            pos += 1;
+            Token::Marker
+        } else if c == b':'
+            && bytes
+                .get(pos + 1)
+                .is_some_and(|c| c.is_ascii_alphabetic() || *c == b'_')
+        {
+            pos += 1;
+            while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_') {
+                pos += 1;
+            }
+            Token::NamedMarker(source[start + 1..pos].to_owned())
+        } else if let Some(symbol) = SYMBOLS.iter().find(|s| source[pos..].starts_with(**s)) {
+            pos += symbol.len();
+            Token::Symbol(symbol)
+        } else {
+            let c = source[pos..].chars().next().unwrap();
+            return Err((pos, format!("unexpected character `{c}`")));
+        };
+        tokens.push(Spanned {
+            token,
+            offset: start,
+        });
+    }
+
+    Ok(tokens)
+}
+
+/// Reads a literal quoted with `quote`, in which the quote is escaped by doubling it.
+/// Returns the unescaped content and the position right after the literal.
+fn quoted(source: &str, start: usize, quote: char) -> Result<(String, usize), (usize, String)> {
+    let mut content = String::new();
+    let mut chars = source[start + 1..].char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == quote {
+            if chars.peek().is_some_and(|(_, next)| *next == quote) {
+                chars.next();
+            } else {
+                return Ok((content, start + 1 + i + 1));
+            }
+        }
+        content.push(c);
+    }
+    Err((start, "unterminated quoted literal".to_owned()))
+}
+
+/// Converts a byte offset into a 1-based line and column, for error messages.
+pub fn line_and_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rfind('\n')
+        .map_or(before.len(), |nl| before.len() - nl - 1)
+        + 1;
+    (line, column)
+}
+
+/// A cursor over a sequence of tokens, shared by the schema and statement parsers.
+#[derive(Clone)]
+pub struct Cursor<'t> {
+    tokens: &'t [Spanned],
+    pos: usize,
+    /// Offset reported for errors at the end of input.
+    end: usize,
+}
+
+pub type ParseResult<T> = Result<T, (usize, String)>;
+
+impl<'t> Cursor<'t> {
+    pub fn new(tokens: &'t [Spanned], end: usize) -> Self {
+        Self {
+            tokens,
+            pos: 0,
+            end,
+        }
+    }
+
+    pub fn peek(&self) -> Option<&'t Token> {
+        self.tokens.get(self.pos).map(|t| &t.token)
+    }
+
+    pub fn peek_nth(&self, n: usize) -> Option<&'t Token> {
+        self.tokens.get(self.pos + n).map(|t| &t.token)
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    pub fn offset(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |t| t.offset)
+    }
+
+    pub fn error<T>(&self, message: impl Into<String>) -> ParseResult<T> {
+        Err((self.offset(), message.into()))
+    }
+
+    /// Reports the current token as unexpected.
+    pub fn unexpected<T>(&self, expected: &str) -> ParseResult<T> {
+        match self.peek() {
+            Some(token) => self.error(format!("expected {expected}, found {}", token.describe())),
+            None => self.error(format!("expected {expected}, found end of input")),
+        }
+    }
+
+    // Cursor is not an iterator, as it can also look ahead and backtrack.
+    #[expect(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&'t Token> {
+        let token = self.peek();
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    pub fn peek_keyword(&self, keyword: &str) -> bool {
+        self.peek().is_some_and(|t| t.is_keyword(keyword))
+    }
+
+    pub fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let matches = self.peek_keyword(keyword);
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    /// Consumes a sequence of keywords, e.g. `IF NOT EXISTS`, if it is present.
+    pub fn eat_keywords(&mut self, keywords: &[&str]) -> bool {
+        let matches = keywords
+            .iter()
+            .enumerate()
+            .all(|(i, keyword)| self.peek_nth(i).is_some_and(|t| t.is_keyword(keyword)));
+        if matches {
+            self.pos += keywords.len();
+        }
+        matches
+    }
+
+    pub fn expect_keyword(&mut self, keyword: &str) -> ParseResult<()> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            self.unexpected(&format!("`{}`", keyword.to_ascii_uppercase()))
+        }
+    }
+
+    pub fn peek_symbol(&self, symbol: &str) -> bool {
+        self.peek() == Some(&Token::Symbol(symbol_str(symbol)))
+    }
+
+    pub fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let matches = self.peek_symbol(symbol);
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    pub fn expect_symbol(&mut self, symbol: &str) -> ParseResult<()> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            self.unexpected(&format!("`{symbol}`"))
+        }
+    }
+
+    /// Parses an identifier. Unquoted identifiers are case insensitive,
+    /// so they are normalized to lowercase.
+    pub fn identifier(&mut self) -> ParseResult<String> {
+        match self.peek() {
+            Some(Token::Ident(ident)) => {
+                self.pos += 1;
+                Ok(ident.to_ascii_lowercase())
+            }
+            Some(Token::QuotedIdent(ident)) => {
+                self.pos += 1;
+                Ok(ident.clone())
+            }
+            _ => self.unexpected("an identifier"),
+        }
+    }
+
+    /// Parses an optionally keyspace-qualified name, e.g. `ks.table`.
+    pub fn qualified_name(&mut self) -> ParseResult<(Option<String>, String)> {
+        let first = self.identifier()?;
+        if self.eat_symbol(".") {
+            let second = self.identifier()?;
+            Ok((Some(first), second))
+        } else {
+            Ok((None, first))
+        }
+    }
+
+    /// Skips tokens up to the end of the current statement, i.e. the next
+    /// top-level `;` (which is consumed) or the end of input.
+    pub fn skip_statement(&mut self) {
+        let mut depth = 0_usize;
+        while let Some(token) = self.next() {
+            match token {
+                Token::Symbol("(" | "[" | "{") => depth += 1,
+                Token::Symbol(")" | "]" | "}") => depth = depth.saturating_sub(1),
+                Token::Symbol(";") if depth == 0 => return,
+                _ => {}
+            }
+        }
+    }
+}
+
+fn symbol_str(symbol: &str) -> &'static str {
+    SYMBOLS
+        .iter()
+        .find(|s| **s == symbol)
+        .expect("unknown symbol")
+}
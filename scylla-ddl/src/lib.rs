@@ -0,0 +1,9 @@
+//! Parser of CQL DDL statements, describing the schema of a database.
+//!
+//! It is shared by the `query!` macro from `scylla-macros`, which checks
+//! statements against a schema file, and by the code generator in `scylla`.
+//! This crate is an implementation detail of those crates, and its API
+//! is not stable.
+
+pub mod lexer;
+pub mod schema;
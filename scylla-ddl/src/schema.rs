@@ -0,0 +1,588 @@
+//! A model of the database schema, parsed from a file with CQL DDL statements.
+
+use std::collections::HashMap;
+
+use crate::lexer::{Cursor, ParseResult, Token};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CqlType {
+    /// One of the native types, named as in `NativeType` from `scylla-cql`.
+    Native(&'static str),
+    List {
+        frozen: bool,
+        element: Box<CqlType>,
+    },
+    Set {
+        frozen: bool,
+        element: Box<CqlType>,
+    },
+    Map {
+        frozen: bool,
+        key: Box<CqlType>,
+        value: Box<CqlType>,
+    },
+    Tuple(Vec<CqlType>),
+    Vector {
+        element: Box<CqlType>,
+        dimensions: u16,
+    },
+    Udt {
+        frozen: bool,
+        definition: Udt,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Udt {
+    pub keyspace: Option<String>,
+    pub name: String,
+    pub fields: Vec<(String, CqlType)>,
+    /// Byte offset of the name in the source, for error messages.
+    pub offset: usize,
+}
+
+const NATIVE_TYPES: &[(&str, &str)] = &[
+    ("ascii", "Ascii"),
+    ("boolean", "Boolean"),
+    ("blob", "Blob"),
+    ("counter", "Counter"),
+    ("date", "Date"),
+    ("decimal", "Decimal"),
+    ("double", "Double"),
+    ("duration", "Duration"),
+    ("float", "Float"),
+    ("int", "Int"),
+    ("bigint", "BigInt"),
+    ("text", "Text"),
+    ("varchar", "Text"),
+    ("timestamp", "Timestamp"),
+    ("inet", "Inet"),
+    ("smallint", "SmallInt"),
+    ("tinyint", "TinyInt"),
+    ("time", "Time"),
+    ("timeuuid", "Timeuuid"),
+    ("uuid", "Uuid"),
+    ("varint", "Varint"),
+];
+
+impl CqlType {
+    pub fn int() -> Self {
+        CqlType::Native("Int")
+    }
+
+    pub fn bigint() -> Self {
+        CqlType::Native("BigInt")
+    }
+
+    /// The type of a collection passed as a whole, e.g. for `col IN ?`.
+    pub fn list_of(element: CqlType) -> Self {
+        CqlType::List {
+            frozen: false,
+            element: Box::new(element),
+        }
+    }
+
+    pub fn set_of(element: CqlType) -> Self {
+        CqlType::Set {
+            frozen: false,
+            element: Box::new(element),
+        }
+    }
+
+    /// Returns the CQL name of the type, for error messages.
+    pub fn cql_name(&self) -> String {
+        let frozen = |frozen: bool, name: String| {
+            if frozen {
+                format!("frozen<{name}>")
+            } else {
+                name
+            }
+        };
+        match self {
+            CqlType::Native(name) => NATIVE_TYPES
+                .iter()
+                .find(|(_, n)| n == name)
+                .map(|(cql, _)| cql.to_string())
+                .unwrap(),
+            CqlType::List { frozen: f, element } => {
+                frozen(*f, format!("list<{}>", element.cql_name()))
+            }
+            CqlType::Set { frozen: f, element } => {
+                frozen(*f, format!("set<{}>", element.cql_name()))
+            }
+            CqlType::Map {
+                frozen: f,
+                key,
+                value,
+            } => frozen(*f, format!("map<{}, {}>", key.cql_name(), value.cql_name())),
+            CqlType::Tuple(elements) => format!(
+                "tuple<{}>",
+                elements
+                    .iter()
+                    .map(|e| e.cql_name())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            CqlType::Vector {
+                element,
+                dimensions,
+            } => format!("vector<{}, {}>", element.cql_name(), dimensions),
+            CqlType::Udt {
+                frozen: f,
+                definition,
+            } => frozen(*f, definition.name.clone()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnKind {
+    PartitionKey,
+    Clustering,
+    Static,
+    Regular,
+}
+
+#[derive(Clone, Debug)]
+pub struct Column {
+    pub name: String,
+    pub typ: CqlType,
+    pub kind: ColumnKind,
+}
+
+#[derive(Clone, Debug)]
+pub struct Table {
+    pub keyspace: Option<String>,
+    pub name: String,
+    /// Byte offset of the name in the source, for error messages.
+    pub offset: usize,
+    /// Partition key columns first, then clustering columns (both in the
+    /// primary key order), then the other columns in declaration order.
+    pub columns: Vec<Column>,
+}
+
+impl Table {
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+
+    pub fn display_name(&self) -> String {
+        match &self.keyspace {
+            Some(keyspace) => format!("{}.{}", keyspace, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Columns in the order in which `SELECT *` returns them: the primary key,
+    /// then static columns and regular columns, each group sorted by name.
+    pub fn wildcard_columns(&self) -> Vec<&Column> {
+        fn group(c: &Column) -> u8 {
+            match c.kind {
+                ColumnKind::PartitionKey => 0,
+                ColumnKind::Clustering => 1,
+                ColumnKind::Static => 2,
+                ColumnKind::Regular => 3,
+            }
+        }
+        // Key columns keep their position in the primary key.
+        fn name_order(c: &Column) -> Option<&[u8]> {
+            match c.kind {
+                ColumnKind::PartitionKey | ColumnKind::Clustering => None,
+                ColumnKind::Static | ColumnKind::Regular => Some(c.name.as_bytes()),
+            }
+        }
+
+        let mut columns: Vec<&Column> = self.columns.iter().collect();
+        columns.sort_by(|a, b| {
+            group(a)
+                .cmp(&group(b))
+                .then_with(|| name_order(a).cmp(&name_order(b)))
+        });
+        columns
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Keyspace {
+    pub name: String,
+    /// Byte offset of the name in the source, for error messages.
+    pub offset: usize,
+    /// The `replication` option, e.g. `{'class': 'SimpleStrategy', 'replication_factor': 3}`.
+    pub replication: HashMap<String, String>,
+}
+
+#[derive(Default, Debug)]
+pub struct Schema {
+    /// Keyspaces created in the schema file. Tables and types may also
+    /// belong to keyspaces which are not created there.
+    pub keyspaces: Vec<Keyspace>,
+    pub tables: Vec<Table>,
+    pub udts: Vec<Udt>,
+}
+
+impl Schema {
+    /// Finds a table by its name. Unqualified names must be unambiguous.
+    pub fn table(&self, keyspace: Option<&str>, name: &str) -> Result<&Table, String> {
+        let mut candidates = self.tables.iter().filter(|t| {
+            t.name == name && (keyspace.is_none() || t.keyspace.as_deref() == keyspace)
+        });
+        let display_name = match keyspace {
+            Some(keyspace) => format!("{keyspace}.{name}"),
+            None => name.to_owned(),
+        };
+        match (candidates.next(), candidates.next()) {
+            (Some(table), None) => Ok(table),
+            (None, _) => Err(format!(
+                "table `{display_name}` is not defined in the schema file"
+            )),
+            (Some(_), Some(_)) => Err(format!(
+                "table name `{name}` is ambiguous, qualify it with a keyspace"
+            )),
+        }
+    }
+
+    fn udt(&self, keyspace: Option<&str>, name: &str) -> Option<&Udt> {
+        self.udts
+            .iter()
+            .find(|u| u.name == name && (keyspace.is_none() || u.keyspace.as_deref() == keyspace))
+    }
+}
+
+/// Parses the schema file. Statements other than `CREATE KEYSPACE`, `CREATE TABLE`,
+/// `CREATE TYPE` and `USE` are ignored.
+pub fn parse_schema(cursor: &mut Cursor) -> ParseResult<Schema> {
+    let mut schema = Schema::default();
+    let mut current_keyspace: Option<String> = None;
+
+    while !cursor.is_at_end() {
+        if cursor.eat_symbol(";") {
+            continue;
+        }
+        if cursor.eat_keyword("use") {
+            current_keyspace = Some(cursor.identifier()?);
+            cursor.skip_statement();
+        } else if cursor.eat_keywords(&["create", "keyspace"]) {
+            let keyspace = parse_create_keyspace(cursor)?;
+            schema.keyspaces.push(keyspace);
+            cursor.skip_statement();
+        } else if cursor.eat_keywords(&["create", "table"])
+            || cursor.eat_keywords(&["create", "columnfamily"])
+        {
+            let table = parse_create_table(cursor, &schema, current_keyspace.as_deref())?;
+            schema.tables.push(table);
+            cursor.skip_statement();
+        } else if cursor.eat_keywords(&["create", "type"]) {
+            let udt = parse_create_type(cursor, &schema, current_keyspace.as_deref())?;
+            schema.udts.push(udt);
+            cursor.skip_statement();
+        } else {
+            cursor.skip_statement();
+        }
+    }
+
+    Ok(schema)
+}
+
+fn parse_create_keyspace(cursor: &mut Cursor) -> ParseResult<Keyspace> {
+    cursor.eat_keywords(&["if", "not", "exists"]);
+    let offset = cursor.offset();
+    let name = cursor.identifier()?;
+
+    let mut replication = None;
+    cursor.expect_keyword("with")?;
+    loop {
+        let option = cursor.identifier()?;
+        cursor.expect_symbol("=")?;
+        if cursor.peek_symbol("{") {
+            let map = parse_string_map(cursor)?;
+            if option == "replication" {
+                replication = Some(map);
+            }
+        } else {
+            // Options other than replication are not needed.
+            parse_literal(cursor)?;
+        }
+        if !cursor.eat_keyword("and") {
+            break;
+        }
+    }
+
+    match replication {
+        Some(replication) => Ok(Keyspace {
+            name,
+            offset,
+            replication,
+        }),
+        None => cursor.error("expected the `replication` option"),
+    }
+}
+
+fn parse_string_map(cursor: &mut Cursor) -> ParseResult<HashMap<String, String>> {
+    let mut map = HashMap::new();
+    cursor.expect_symbol("{")?;
+    while !cursor.eat_symbol("}") {
+        if !map.is_empty() {
+            cursor.expect_symbol(",")?;
+        }
+        let key = parse_literal(cursor)?;
+        cursor.expect_symbol(":")?;
+        let value = parse_literal(cursor)?;
+        map.insert(key, value);
+    }
+    Ok(map)
+}
+
+fn parse_literal(cursor: &mut Cursor) -> ParseResult<String> {
+    match cursor.peek() {
+        Some(Token::Str(s) | Token::Number(s) | Token::Ident(s)) => {
+            cursor.next();
+            Ok(s.clone())
+        }
+        _ => cursor.unexpected("a literal"),
+    }
+}
+
+fn parse_create_type(
+    cursor: &mut Cursor,
+    schema: &Schema,
+    current_keyspace: Option<&str>,
+) -> ParseResult<Udt> {
+    cursor.eat_keywords(&["if", "not", "exists"]);
+    let offset = cursor.offset();
+    let (keyspace, name) = cursor.qualified_name()?;
+    let keyspace = keyspace.or_else(|| current_keyspace.map(ToOwned::to_owned));
+
+    let mut fields = Vec::new();
+    cursor.expect_symbol("(")?;
+    loop {
+        let field_name = cursor.identifier()?;
+        let typ = parse_type(cursor, schema, keyspace.as_deref())?;
+        fields.push((field_name, typ));
+        if !cursor.eat_symbol(",") {
+            break;
+        }
+    }
+    cursor.expect_symbol(")")?;
+
+    Ok(Udt {
+        keyspace,
+        name,
+        fields,
+        offset,
+    })
+}
+
+fn parse_create_table(
+    cursor: &mut Cursor,
+    schema: &Schema,
+    current_keyspace: Option<&str>,
+) -> ParseResult<Table> {
+    cursor.eat_keywords(&["if", "not", "exists"]);
+    let offset = cursor.offset();
+    let (keyspace, name) = cursor.qualified_name()?;
+    let keyspace = keyspace.or_else(|| current_keyspace.map(ToOwned::to_owned));
+
+    let mut columns = Vec::new();
+    let mut static_columns = Vec::new();
+    let mut partition_key: Vec<String> = Vec::new();
+    let mut clustering_key: Vec<String> = Vec::new();
+
+    cursor.expect_symbol("(")?;
+    loop {
+        if cursor.eat_keywords(&["primary", "key"]) {
+            cursor.expect_symbol("(")?;
+            if cursor.eat_symbol("(") {
+                loop {
+                    partition_key.push(cursor.identifier()?);
+                    if !cursor.eat_symbol(",") {
+                        break;
+                    }
+                }
+                cursor.expect_symbol(")")?;
+            } else {
+                partition_key.push(cursor.identifier()?);
+            }
+            while cursor.eat_symbol(",") {
+                clustering_key.push(cursor.identifier()?);
+            }
+            cursor.expect_symbol(")")?;
+        } else {
+            let offset = cursor.offset();
+            let column_name = cursor.identifier()?;
+            if columns.iter().any(|(name, _)| *name == column_name) {
+                return Err((offset, format!("column `{column_name}` is defined twice")));
+            }
+            let typ = parse_type(cursor, schema, keyspace.as_deref())?;
+            if cursor.eat_keyword("static") {
+                static_columns.push(column_name.clone());
+            }
+            if cursor.eat_keywords(&["primary", "key"]) {
+                if !partition_key.is_empty() {
+                    return Err((offset, "multiple primary keys defined".to_owned()));
+                }
+                partition_key.push(column_name.clone());
+            }
+            columns.push((column_name, typ));
+        }
+        if !cursor.eat_symbol(",") {
+            break;
+        }
+    }
+    cursor.expect_symbol(")")?;
+
+    if partition_key.is_empty() {
+        return cursor.error(format!("table `{name}` has no primary key"));
+    }
+
+    let mut table = Table {
+        keyspace,
+        name,
+        offset,
+        columns: Vec::new(),
+    };
+    let key_columns = partition_key
+        .iter()
+        .map(|name| (name, ColumnKind::PartitionKey))
+        .chain(
+            clustering_key
+                .iter()
+                .map(|name| (name, ColumnKind::Clustering)),
+        );
+    for (key_name, kind) in key_columns {
+        let (_, typ) = columns
+            .iter()
+            .find(|(name, _)| name == key_name)
+            .ok_or_else(|| {
+                (
+                    cursor.offset(),
+                    format!("primary key column `{key_name}` is not defined"),
+                )
+            })?;
+        table.columns.push(Column {
+            name: key_name.clone(),
+            typ: typ.clone(),
+            kind,
+        });
+    }
+    for (column_name, typ) in columns {
+        if table.column(&column_name).is_some() {
+            // Already added as a primary key column.
+            continue;
+        }
+        let kind = if static_columns.contains(&column_name) {
+            ColumnKind::Static
+        } else {
+            ColumnKind::Regular
+        };
+        table.columns.push(Column {
+            name: column_name,
+            typ,
+            kind,
+        });
+    }
+
+    Ok(table)
+}
+
+fn parse_type(
+    cursor: &mut Cursor,
+    schema: &Schema,
+    keyspace: Option<&str>,
+) -> ParseResult<CqlType> {
+    let offset = cursor.offset();
+    let (type_keyspace, name) = match cursor.peek() {
+        Some(Token::QuotedIdent(_)) => cursor.qualified_name()?,
+        Some(Token::Ident(_)) => cursor.qualified_name()?,
+        _ => return cursor.unexpected("a type"),
+    };
+
+    if type_keyspace.is_none() {
+        if let Some((_, native)) = NATIVE_TYPES.iter().find(|(cql, _)| *cql == name) {
+            return Ok(CqlType::Native(native));
+        }
+
+        match name.as_str() {
+            "frozen" => {
+                cursor.expect_symbol("<")?;
+                let mut typ = parse_type(cursor, schema, keyspace)?;
+                cursor.expect_symbol(">")?;
+                match &mut typ {
+                    CqlType::List { frozen, .. }
+                    | CqlType::Set { frozen, .. }
+                    | CqlType::Map { frozen, .. }
+                    | CqlType::Udt { frozen, .. } => *frozen = true,
+                    // Tuples and vectors are always frozen.
+                    CqlType::Tuple(_) | CqlType::Vector { .. } => {}
+                    CqlType::Native(_) => {
+                        return Err((offset, "native types cannot be frozen".to_owned()))
+                    }
+                }
+                return Ok(typ);
+            }
+            "list" | "set" => {
+                cursor.expect_symbol("<")?;
+                let element = Box::new(parse_type(cursor, schema, keyspace)?);
+                cursor.expect_symbol(">")?;
+                return Ok(if name == "list" {
+                    CqlType::List {
+                        frozen: false,
+                        element,
+                    }
+                } else {
+                    CqlType::Set {
+                        frozen: false,
+                        element,
+                    }
+                });
+            }
+            "map" => {
+                cursor.expect_symbol("<")?;
+                let key = Box::new(parse_type(cursor, schema, keyspace)?);
+                cursor.expect_symbol(",")?;
+                let value = Box::new(parse_type(cursor, schema, keyspace)?);
+                cursor.expect_symbol(">")?;
+                return Ok(CqlType::Map {
+                    frozen: false,
+                    key,
+                    value,
+                });
+            }
+            "tuple" => {
+                cursor.expect_symbol("<")?;
+                let mut elements = vec![parse_type(cursor, schema, keyspace)?];
+                while cursor.eat_symbol(",") {
+                    elements.push(parse_type(cursor, schema, keyspace)?);
+                }
+                cursor.expect_symbol(">")?;
+                return Ok(CqlType::Tuple(elements));
+            }
+            "vector" => {
+                cursor.expect_symbol("<")?;
+                let element = Box::new(parse_type(cursor, schema, keyspace)?);
+                cursor.expect_symbol(",")?;
+                let dimensions = match cursor.next() {
+                    Some(Token::Number(n)) => n.parse::<u16>().ok(),
+                    _ => None,
+                };
+                let Some(dimensions) = dimensions else {
+                    return Err((offset, "invalid vector dimensions".to_owned()));
+                };
+                cursor.expect_symbol(">")?;
+                return Ok(CqlType::Vector {
+                    element,
+                    dimensions,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    let udt_keyspace = type_keyspace.as_deref().or(keyspace);
+    match schema.udt(udt_keyspace, &name) {
+        Some(definition) => Ok(CqlType::Udt {
+            frozen: false,
+            definition: definition.clone(),
+        }),
+        None => Err((offset, format!("unknown type `{name}`"))),
+    }
+}
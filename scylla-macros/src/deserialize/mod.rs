@@ -28,6 +28,40 @@ trait DeserializeCommonFieldAttrs {
 
     /// The type of the field, i.e. what this field deserializes to.
     fn deserialize_target(&self) -> &syn::Type;
+
+    /// The path to a module with custom `type_check` and `deserialize`
+    /// functions, used instead of the `DeserializeValue` impl of the field type.
+    fn with(&self) -> Option<&syn::Path>;
+
+    /// The function which type checks this field.
+    fn type_check_fn(
+        &self,
+        macro_internal: &syn::Path,
+        (frame_lifetime, metadata_lifetime): &(syn::Lifetime, syn::Lifetime),
+    ) -> syn::Expr {
+        match self.with() {
+            Some(with) => parse_quote!(#with::type_check),
+            None => {
+                let t = self.deserialize_target();
+                parse_quote!(<#t as #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>>::type_check)
+            }
+        }
+    }
+
+    /// The function which deserializes this field.
+    fn deserialize_fn(
+        &self,
+        macro_internal: &syn::Path,
+        (frame_lifetime, metadata_lifetime): &(syn::Lifetime, syn::Lifetime),
+    ) -> syn::Expr {
+        match self.with() {
+            Some(with) => parse_quote!(#with::deserialize),
+            None => {
+                let t = self.deserialize_target();
+                parse_quote!(<#t as #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>>::deserialize)
+            }
+        }
+    }
 }
 
 /// A structure helpful in implementing DeserializeValue and DeserializeRow.
@@ -24,6 +24,16 @@ struct StructAttrs {
     // This annotation only works if `enforce_order` is specified.
     #[darling(default)]
     skip_name_checks: bool,
+
+    // Used by derive(Table) only. Ignored here.
+    #[darling(default)]
+    #[darling(rename = "table")]
+    _table: Option<String>,
+
+    // Used by derive(Table) only. Ignored here.
+    #[darling(default)]
+    #[darling(rename = "keyspace")]
+    _keyspace: Option<String>,
 }
 
 impl DeserializeCommonStructAttrs for StructAttrs {
@@ -46,6 +56,37 @@ struct Field {
     #[darling(default)]
     rename: Option<String>,
 
+    // If true, then the field is deserialized using `DeserializeRow`
+    // from (possibly multiple) columns, as if its fields were inlined
+    // into the parent struct.
+    #[darling(default)]
+    flatten: bool,
+
+    // If set, then the field is type checked and deserialized with
+    // `type_check` and `deserialize` functions from the given module.
+    #[darling(default)]
+    with: Option<syn::Path>,
+
+    // If set, then the field is deserialized from the `writetime(col)`
+    // column, i.e. the write timestamp of the given column.
+    #[darling(default)]
+    writetime_of: Option<String>,
+
+    // If set, then the field is deserialized from the `ttl(col)`
+    // column, i.e. the remaining time to live of the given column.
+    #[darling(default)]
+    ttl_of: Option<String>,
+
+    // Used by derive(Table) only. Ignored here.
+    #[darling(default)]
+    #[darling(rename = "partition_key")]
+    _partition_key: bool,
+
+    // Used by derive(Table) only. Ignored here.
+    #[darling(default)]
+    #[darling(rename = "clustering_key")]
+    _clustering_key: bool,
+
     ident: Option<syn::Ident>,
     ty: syn::Type,
 }
@@ -58,12 +99,16 @@ impl DeserializeCommonFieldAttrs for Field {
     fn deserialize_target(&self) -> &syn::Type {
         &self.ty
     }
+
+    fn with(&self) -> Option<&syn::Path> {
+        self.with.as_ref()
+    }
 }
 
 // derive(DeserializeRow) for the new DeserializeRow trait
 pub(crate) fn deserialize_row_derive(
     tokens_input: proc_macro::TokenStream,
-) -> Result<syn::ItemImpl, syn::Error> {
+) -> Result<proc_macro2::TokenStream, syn::Error> {
     let input = syn::parse(tokens_input)?;
 
     let implemented_trait: syn::Path = parse_quote! { DeserializeRow };
@@ -83,8 +128,15 @@ pub(crate) fn deserialize_row_derive(
         s.generate_type_check_method().into(),
         s.generate_deserialize_method().into(),
     ];
-
-    Ok(s.generate_impl(implemented_trait, items))
+    let deserialize_row_impl = s.generate_impl(implemented_trait, items);
+    let helper_impl = s.generate_helper_impl();
+    let selectors_impl = s.generate_selectors_impl();
+
+    Ok(quote::quote! {
+        #deserialize_row_impl
+        #helper_impl
+        #selectors_impl
+    })
 }
 
 fn validate_attrs(attrs: &StructAttrs, fields: &[Field]) -> Result<(), darling::Error> {
@@ -107,11 +159,18 @@ fn validate_attrs(attrs: &StructAttrs, fields: &[Field]) -> Result<(), darling::
                 .with_span(&field.ident);
                 errors.push(err);
             }
+            if field.writetime_of.is_some() || field.ttl_of.is_some() {
+                let err = darling::Error::custom(
+                    "<writetime_of> and <ttl_of> annotations don't make sense with <skip_name_checks> attribute",
+                )
+                .with_span(&field.ident);
+                errors.push(err);
+            }
         }
     } else {
         // Detect name collisions caused by `rename`.
         let mut used_names = HashMap::<String, &Field>::new();
-        for field in fields {
+        for field in fields.iter().filter(|f| !f.flatten) {
             let column_name = field.column_name();
             if let Some(other_field) = used_names.get(&column_name) {
                 let other_field_ident = other_field.ident.as_ref().unwrap();
@@ -124,6 +183,47 @@ fn validate_attrs(attrs: &StructAttrs, fields: &[Field]) -> Result<(), darling::
         }
     }
 
+    // <rename>, <writetime_of> and <ttl_of> all determine the column name
+    for field in fields {
+        let name_attrs = [
+            field.rename.is_some(),
+            field.writetime_of.is_some(),
+            field.ttl_of.is_some(),
+        ];
+        if name_attrs.into_iter().filter(|&set| set).count() > 1 {
+            let err = darling::Error::custom(
+                "at most one of <rename>, <writetime_of> and <ttl_of> annotations can be used on a field",
+            )
+            .with_span(&field.ident);
+            errors.push(err);
+        }
+    }
+
+    // <flatten> fields don't correspond to a single column
+    for field in fields.iter().filter(|f| f.flatten) {
+        if field.writetime_of.is_some() || field.ttl_of.is_some() {
+            let err = darling::Error::custom(
+                "<writetime_of> and <ttl_of> annotations do not make sense with <flatten>",
+            )
+            .with_span(&field.ident);
+            errors.push(err);
+        }
+        if field.rename.is_some() {
+            let err = darling::Error::custom(
+                "<rename> and <flatten> annotations do not make sense together",
+            )
+            .with_span(&field.ident);
+            errors.push(err);
+        }
+        if field.with.is_some() {
+            let err = darling::Error::custom(
+                "<with> and <flatten> annotations do not make sense together",
+            )
+            .with_span(&field.ident);
+            errors.push(err);
+        }
+    }
+
     errors.finish()
 }
 
@@ -133,11 +233,36 @@ impl Field {
         !self.skip
     }
 
+    // Returns whether this field is deserialized from exactly one column.
+    fn is_column(&self) -> bool {
+        !self.skip && !self.flatten
+    }
+
     // The name of the column corresponding to this Rust struct field
     fn column_name(&self) -> String {
-        match self.rename.as_ref() {
-            Some(rename) => rename.to_owned(),
-            None => self.ident.as_ref().unwrap().unraw().to_string(),
+        if let Some(rename) = self.rename.as_ref() {
+            rename.to_owned()
+        } else if let Some(column) = self.writetime_of.as_ref() {
+            format!("writetime({column})")
+        } else if let Some(column) = self.ttl_of.as_ref() {
+            format!("ttl({column})")
+        } else {
+            self.ident.as_ref().unwrap().unraw().to_string()
+        }
+    }
+
+    // An expression evaluating to the selector which fetches the column
+    // corresponding to this Rust struct field
+    fn selector(&self, macro_internal: &syn::Path) -> syn::Expr {
+        if let Some(column) = self.writetime_of.as_ref() {
+            parse_quote!(
+                ::std::format!("writetime({})", #macro_internal::quote_identifier(#column))
+            )
+        } else if let Some(column) = self.ttl_of.as_ref() {
+            parse_quote!(::std::format!("ttl({})", #macro_internal::quote_identifier(#column)))
+        } else {
+            let column = self.cql_name_literal();
+            parse_quote!(#macro_internal::quote_identifier(#column))
         }
     }
 
@@ -163,6 +288,98 @@ impl StructDesc {
             Flavor::EnforceOrder => DeserializeAssumeOrderGenerator(self).generate(),
         }
     }
+
+    // Generates the impl of a helper trait which allows this struct
+    // to be flattened into other structs of the same flavor.
+    fn generate_helper_impl(&self) -> syn::ItemImpl {
+        match self.attrs.flavor {
+            Flavor::MatchByName => {
+                let items = [
+                    DeserializeUnorderedGenerator(self)
+                        .generate_has_column()
+                        .into(),
+                    DeserializeUnorderedGenerator(self)
+                        .generate_deserialize_by_name()
+                        .into(),
+                ];
+                self.generate_impl(parse_quote!(DeserializeRowByName), items)
+            }
+            Flavor::EnforceOrder => {
+                let items = [
+                    DeserializeAssumeOrderGenerator(self)
+                        .generate_column_count()
+                        .into(),
+                    DeserializeAssumeOrderGenerator(self)
+                        .generate_deserialize_in_order()
+                        .into(),
+                ];
+                self.generate_impl(parse_quote!(DeserializeRowInOrder), items)
+            }
+        }
+    }
+
+    // Generates the impl of `RowSelectors`, which lists selectors of the columns
+    // in the order of the fields.
+    fn generate_selectors_impl(&self) -> syn::ItemImpl {
+        let macro_internal = self.struct_attrs().macro_internal_path();
+        let struct_name = &self.name;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        let predicates = where_clause
+            .iter()
+            .flat_map(|w| w.predicates.iter().cloned())
+            .chain(self.fields().iter().filter(|f| f.flatten && !f.skip).map(
+                |f| -> syn::WherePredicate {
+                    let typ = &f.ty;
+                    parse_quote!(#typ: #macro_internal::RowSelectors)
+                },
+            ));
+        let append_selectors = self
+            .fields()
+            .iter()
+            .filter(|f| !f.skip)
+            .map(|f| -> syn::Stmt {
+                if f.flatten {
+                    let typ = &f.ty;
+                    parse_quote! {
+                        selectors.extend(<#typ as #macro_internal::RowSelectors>::selectors());
+                    }
+                } else {
+                    let selector = f.selector(&macro_internal);
+                    parse_quote! {
+                        selectors.push(#selector);
+                    }
+                }
+            });
+
+        parse_quote! {
+            #[automatically_derived]
+            impl #impl_generics #macro_internal::RowSelectors for #struct_name #ty_generics
+            where #(#predicates),*
+            {
+                fn selectors() -> ::std::vec::Vec<::std::string::String> {
+                    let mut selectors = ::std::vec::Vec::new();
+                    #(#append_selectors)*
+                    selectors
+                }
+            }
+        }
+    }
+
+    // An expression evaluating to the number of columns consumed by the fields
+    // preceding the field with given index. Only meaningful in `enforce_order` flavor.
+    fn column_offset(&self, field_index: usize) -> syn::Expr {
+        let macro_internal = self.struct_attrs().macro_internal_path();
+        let (frame_lifetime, metadata_lifetime) = self.constraint_lifetimes();
+        let preceding = &self.fields()[..field_index];
+        let column_count = preceding.iter().filter(|f| f.is_column()).count();
+        let flattened_types = preceding
+            .iter()
+            .filter(|f| f.flatten && !f.skip)
+            .map(|f| &f.ty);
+        parse_quote! {
+            #column_count #(+ <#flattened_types as #macro_internal::DeserializeRowInOrder<#frame_lifetime, #metadata_lifetime>>::COLUMN_COUNT)*
+        }
+    }
 }
 
 struct TypeCheckAssumeOrderGenerator<'sd>(&'sd StructDesc);
@@ -170,25 +387,23 @@ struct TypeCheckAssumeOrderGenerator<'sd>(&'sd StructDesc);
 impl TypeCheckAssumeOrderGenerator<'_> {
     fn generate_name_verification(
         &self,
-        field_index: usize, //  These two indices can be different because of `skip` attribute
-        column_index: usize, // applied to some field.
+        field_index: usize, // The column index can be different because of `skip` and `flatten`
         field: &Field,
-        column_spec: &syn::Ident,
     ) -> Option<syn::Expr> {
         (!self.0.attrs.skip_name_checks).then(|| {
             let macro_internal = self.0.struct_attrs().macro_internal_path();
             let rust_field_name = field.cql_name_literal();
 
             parse_quote! {
-                if #column_spec.name() != #rust_field_name {
+                if spec.name() != #rust_field_name {
                     return ::std::result::Result::Err(
                         #macro_internal::mk_row_typck_err::<Self>(
                             column_types_iter(),
                             #macro_internal::DeserBuiltinRowTypeCheckErrorKind::ColumnNameMismatch {
                                 field_index: #field_index,
-                                column_index: #column_index,
+                                column_index,
                                 rust_column_name: #rust_field_name,
-                                db_column_name: ::std::borrow::ToOwned::to_owned(#column_spec.name()),
+                                db_column_name: ::std::borrow::ToOwned::to_owned(spec.name()),
                             }
                         )
                     );
@@ -197,6 +412,50 @@ impl TypeCheckAssumeOrderGenerator<'_> {
         })
     }
 
+    // Generates code that type checks the column(s) corresponding to given field.
+    fn generate_field_check(&self, field_index: usize, field: &Field) -> syn::Block {
+        let macro_internal = self.0.struct_attrs().macro_internal_path();
+        let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
+        let column_offset = self.0.column_offset(field_index);
+
+        if field.flatten {
+            let typ = &field.ty;
+            return parse_quote! {
+                {
+                    let column_index: ::std::primitive::usize = #column_offset;
+                    let column_count = <#typ as #macro_internal::DeserializeRowInOrder<#frame_lifetime, #metadata_lifetime>>::COLUMN_COUNT;
+                    <#typ as #macro_internal::DeserializeRow<#frame_lifetime, #metadata_lifetime>>::type_check(
+                        &specs[column_index..column_index + column_count],
+                    )?;
+                }
+            };
+        }
+
+        // Verify the name (unless `skip_name_checks' is specified)
+        let name_verification = self.generate_name_verification(field_index, field);
+        let type_check_fn = field.type_check_fn(&macro_internal, self.0.constraint_lifetimes());
+
+        parse_quote! {
+            {
+                let column_index: ::std::primitive::usize = #column_offset;
+                let spec = &specs[column_index];
+
+                #name_verification
+
+                // Verify the type
+                #type_check_fn(spec.typ())
+                    .map_err(|err| #macro_internal::mk_row_typck_err::<Self>(
+                        column_types_iter(),
+                        #macro_internal::DeserBuiltinRowTypeCheckErrorKind::ColumnTypeCheckFailed {
+                            column_index,
+                            column_name: ::std::borrow::ToOwned::to_owned(spec.name()),
+                            err,
+                        }
+                    ))?;
+            }
+        }
+    }
+
     fn generate(&self) -> syn::ImplItemFn {
         // The generated method will check that the order and the types
         // of the columns correspond fields' names/types.
@@ -204,26 +463,13 @@ impl TypeCheckAssumeOrderGenerator<'_> {
         let macro_internal = self.0.struct_attrs().macro_internal_path();
         let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
 
-        let required_fields_iter = || {
-            self.0
-                .fields()
-                .iter()
-                .enumerate()
-                .filter(|(_, f)| f.is_required())
-        };
-        let required_fields_count = required_fields_iter().count();
-        let required_fields_idents: Vec<_> = (0..required_fields_count)
-            .map(|i| quote::format_ident!("f_{}", i))
-            .collect();
-        let name_verifications = required_fields_iter()
-            .zip(required_fields_idents.iter().enumerate())
-            .map(|((field_idx, field), (col_idx, fidents))| {
-                self.generate_name_verification(field_idx, col_idx, field, fidents)
-            });
-
-        let required_fields_deserializers =
-            required_fields_iter().map(|(_, f)| f.deserialize_target());
-        let numbers = 0usize..;
+        let field_checks = self
+            .0
+            .fields()
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.is_required())
+            .map(|(field_index, f)| self.generate_field_check(field_index, f));
 
         parse_quote! {
             fn type_check(
@@ -231,35 +477,22 @@ impl TypeCheckAssumeOrderGenerator<'_> {
             ) -> ::std::result::Result<(), #macro_internal::TypeCheckError> {
                 let column_types_iter = || ::std::iter::Iterator::map(specs.iter(), |spec| ::std::clone::Clone::clone(spec.typ()).into_owned());
 
-                match specs {
-                    [#(#required_fields_idents),*] => {
-                        #(
-                            // Verify the name (unless `skip_name_checks' is specified)
-                            #name_verifications
-
-                            // Verify the type
-                            <#required_fields_deserializers as #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>>::type_check(#required_fields_idents.typ())
-                                .map_err(|err| #macro_internal::mk_row_typck_err::<Self>(
-                                    column_types_iter(),
-                                    #macro_internal::DeserBuiltinRowTypeCheckErrorKind::ColumnTypeCheckFailed {
-                                        column_index: #numbers,
-                                        column_name: ::std::borrow::ToOwned::to_owned(#required_fields_idents.name()),
-                                        err,
-                                    }
-                                ))?;
-                        )*
-                        ::std::result::Result::Ok(())
-                    },
-                    _ => ::std::result::Result::Err(
+                let column_count = <Self as #macro_internal::DeserializeRowInOrder<#frame_lifetime, #metadata_lifetime>>::COLUMN_COUNT;
+                if specs.len() != column_count {
+                    return ::std::result::Result::Err(
                         #macro_internal::mk_row_typck_err::<Self>(
                             column_types_iter(),
                             #macro_internal::DeserBuiltinRowTypeCheckErrorKind::WrongColumnCount {
-                                rust_cols: #required_fields_count,
+                                rust_cols: column_count,
                                 cql_cols: specs.len(),
                             }
                         ),
-                    ),
+                    );
                 }
+
+                #(#field_checks)*
+
+                ::std::result::Result::Ok(())
             }
         }
     }
@@ -275,10 +508,18 @@ impl DeserializeAssumeOrderGenerator<'_> {
         }
 
         let macro_internal = self.0.struct_attrs().macro_internal_path();
-        let cql_name_literal = field.cql_name_literal();
-        let deserializer = field.deserialize_target();
         let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
 
+        if field.flatten {
+            let typ = &field.ty;
+            return parse_quote! {
+                <#typ as #macro_internal::DeserializeRowInOrder<#frame_lifetime, #metadata_lifetime>>::deserialize_in_order(row)?
+            };
+        }
+
+        let cql_name_literal = field.cql_name_literal();
+        let deserialize_fn = field.deserialize_fn(&macro_internal, self.0.constraint_lifetimes());
+
         let name_check: Option<syn::Stmt> = (!self.0.struct_attrs().skip_name_checks).then(|| parse_quote! {
             if col.spec.name() != #cql_name_literal {
                 ::std::panic!(
@@ -291,13 +532,13 @@ impl DeserializeAssumeOrderGenerator<'_> {
 
         parse_quote!(
             {
-                let col = ::std::iter::Iterator::next(&mut row)
+                let col = ::std::iter::Iterator::next(row)
                     .expect("Typecheck should have prevented this scenario! Too few columns in the serialized data.")
                     .map_err(#macro_internal::row_deser_error_replace_rust_name::<Self>)?;
 
                 #name_check
 
-                <#deserializer as #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>>::deserialize(col.spec.typ(), col.slice)
+                #deserialize_fn(col.spec.typ(), col.slice)
                     .map_err(|err| #macro_internal::mk_row_deser_err::<Self>(
                         #macro_internal::BuiltinRowDeserializationErrorKind::ColumnDeserializationFailed {
                             column_index: #field_index,
@@ -313,6 +554,27 @@ impl DeserializeAssumeOrderGenerator<'_> {
         let macro_internal = self.0.struct_attrs().macro_internal_path();
         let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
 
+        parse_quote! {
+            fn deserialize(
+                mut row: #macro_internal::ColumnIterator<#frame_lifetime, #metadata_lifetime>,
+            ) -> ::std::result::Result<Self, #macro_internal::DeserializationError> {
+                <Self as #macro_internal::DeserializeRowInOrder<#frame_lifetime, #metadata_lifetime>>::deserialize_in_order(&mut row)
+            }
+        }
+    }
+
+    fn generate_column_count(&self) -> syn::ImplItemConst {
+        let column_count = self.0.column_offset(self.0.fields().len());
+
+        parse_quote! {
+            const COLUMN_COUNT: ::std::primitive::usize = #column_count;
+        }
+    }
+
+    fn generate_deserialize_in_order(&self) -> syn::ImplItemFn {
+        let macro_internal = self.0.struct_attrs().macro_internal_path();
+        let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
+
         let fields = self.0.fields();
         let field_idents = fields.iter().map(|f| f.ident.as_ref().unwrap());
         let field_finalizers = fields
@@ -321,8 +583,8 @@ impl DeserializeAssumeOrderGenerator<'_> {
             .map(|(field_idx, f)| self.generate_finalize_field(field_idx, f));
 
         parse_quote! {
-            fn deserialize(
-                mut row: #macro_internal::ColumnIterator<#frame_lifetime, #metadata_lifetime>,
+            fn deserialize_in_order(
+                row: &mut #macro_internal::ColumnIterator<#frame_lifetime, #metadata_lifetime>,
             ) -> ::std::result::Result<Self, #macro_internal::DeserializationError> {
                 ::std::result::Result::Ok(Self {
                     #(#field_idents: #field_finalizers,)*
@@ -341,11 +603,17 @@ impl TypeCheckUnorderedGenerator<'_> {
         quote::format_ident!("visited_{}", field.ident.as_ref().unwrap().unraw())
     }
 
+    // An identifier for a variable that collects specs of the columns
+    // which belong to given flattened field
+    fn flattened_specs_variable(field: &Field) -> syn::Ident {
+        quote::format_ident!("specs_{}", field.ident.as_ref().unwrap().unraw())
+    }
+
     // Generates a declaration of a "visited" flag for the purpose of type check.
     // We generate it even if the flag is not required in order to protect
     // from fields appearing more than once
     fn generate_visited_flag_decl(field: &Field) -> Option<syn::Stmt> {
-        (!field.skip).then(|| {
+        field.is_column().then(|| {
             let visited_flag = Self::visited_flag_variable(field);
             parse_quote! {
                 let mut #visited_flag = false;
@@ -355,11 +623,10 @@ impl TypeCheckUnorderedGenerator<'_> {
 
     // Generates code that, given variable `typ`, type-checks given field
     fn generate_type_check(&self, field: &Field) -> Option<syn::Block> {
-        (!field.skip).then(|| {
+        field.is_column().then(|| {
             let macro_internal = self.0.struct_attrs().macro_internal_path();
-            let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
             let visited_flag = Self::visited_flag_variable(field);
-            let typ = field.deserialize_target();
+            let type_check_fn = field.type_check_fn(&macro_internal, self.0.constraint_lifetimes());
             let cql_name_literal = field.cql_name_literal();
             let decrement_if_required: Option::<syn::Stmt> = field.is_required().then(|| parse_quote! {
                 remaining_required_fields -= 1;
@@ -368,7 +635,7 @@ impl TypeCheckUnorderedGenerator<'_> {
             parse_quote! {
                 {
                     if !#visited_flag {
-                        <#typ as #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>>::type_check(spec.typ())
+                        #type_check_fn(spec.typ())
                             .map_err(|err| {
                                 #macro_internal::mk_row_typck_err::<Self>(
                                     column_types_iter(),
@@ -400,7 +667,7 @@ impl TypeCheckUnorderedGenerator<'_> {
     // Generates code that appends the flag name if it is missing.
     // The generated code is used to construct a nice error message.
     fn generate_append_name(field: &Field) -> Option<syn::Block> {
-        field.is_required().then(|| {
+        (field.is_required() && field.is_column()).then(|| {
             let visited_flag = Self::visited_flag_variable(field);
             let cql_name_literal = field.cql_name_literal();
             parse_quote! {
@@ -415,16 +682,27 @@ impl TypeCheckUnorderedGenerator<'_> {
 
     fn generate(&self) -> syn::ImplItemFn {
         let macro_internal = self.0.struct_attrs().macro_internal_path();
+        let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
 
         let fields = self.0.fields();
         let visited_field_declarations = fields.iter().flat_map(Self::generate_visited_flag_decl);
         let type_check_blocks = fields.iter().flat_map(|f| self.generate_type_check(f));
         let append_name_blocks = fields.iter().flat_map(Self::generate_append_name);
-        let nonskipped_field_names = fields
+        let column_field_names = fields
             .iter()
-            .filter(|f| !f.skip)
+            .filter(|f| f.is_column())
             .map(|f| f.cql_name_literal());
-        let field_count_lit = fields.iter().filter(|f| f.is_required()).count();
+        let field_count_lit = fields
+            .iter()
+            .filter(|f| f.is_required() && f.is_column())
+            .count();
+
+        let flattened_fields: Vec<_> = fields.iter().filter(|f| f.flatten && !f.skip).collect();
+        let flattened_types: Vec<_> = flattened_fields.iter().map(|f| &f.ty).collect();
+        let flattened_specs: Vec<_> = flattened_fields
+            .iter()
+            .map(|f| Self::flattened_specs_variable(f))
+            .collect();
 
         parse_quote! {
             fn type_check(
@@ -436,12 +714,22 @@ impl TypeCheckUnorderedGenerator<'_> {
                 // For each required field, generate a "visited" boolean flag
                 #(#visited_field_declarations)*
 
+                // For each flattened field, collect the columns that belong to it
+                #(
+                    let mut #flattened_specs = ::std::vec::Vec::<#macro_internal::ColumnSpec>::new();
+                )*
+
                 let column_types_iter = || ::std::iter::Iterator::map(specs.iter(), |spec| ::std::clone::Clone::clone(spec.typ()).into_owned());
 
                 for (column_index, spec) in ::std::iter::Iterator::enumerate(specs.iter()) {
                     // Pattern match on the name and verify that the type is correct.
                     match spec.name() {
-                        #(#nonskipped_field_names => #type_check_blocks,)*
+                        #(#column_field_names => #type_check_blocks,)*
+                        #(
+                            name if <#flattened_types as #macro_internal::DeserializeRowByName<#frame_lifetime, #metadata_lifetime>>::has_column(name) => {
+                                #flattened_specs.push(::std::clone::Clone::clone(spec));
+                            }
+                        )*
                         _unknown => {
                             return ::std::result::Result::Err(
                                 #macro_internal::mk_row_typck_err::<Self>(
@@ -471,6 +759,11 @@ impl TypeCheckUnorderedGenerator<'_> {
                     )
                 }
 
+                // Type check the flattened fields against their columns
+                #(
+                    <#flattened_types as #macro_internal::DeserializeRow<#frame_lifetime, #metadata_lifetime>>::type_check(&#flattened_specs)?;
+                )*
+
                 ::std::result::Result::Ok(())
             }
         }
@@ -497,6 +790,11 @@ impl DeserializeUnorderedGenerator<'_> {
         }
 
         let deserialize_field = Self::deserialize_field_variable(field);
+        if field.flatten {
+            // Flattened fields are deserialized before other fields
+            return parse_quote!(#deserialize_field);
+        }
+
         let cql_name_literal = field.cql_name_literal();
         parse_quote! {
             #deserialize_field.unwrap_or_else(|| ::std::panic!(
@@ -509,11 +807,10 @@ impl DeserializeUnorderedGenerator<'_> {
     // Generated code that performs deserialization when the raw field
     // is being processed
     fn generate_deserialization(&self, column_index: usize, field: &Field) -> syn::Expr {
-        assert!(!field.skip);
+        assert!(field.is_column());
         let macro_internal = self.0.struct_attrs().macro_internal_path();
-        let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
         let deserialize_field = Self::deserialize_field_variable(field);
-        let deserializer = field.deserialize_target();
+        let deserialize_fn = field.deserialize_fn(&macro_internal, self.0.constraint_lifetimes());
 
         parse_quote! {
             {
@@ -524,7 +821,7 @@ impl DeserializeUnorderedGenerator<'_> {
                 );
 
                 #deserialize_field = ::std::option::Option::Some(
-                    <#deserializer as #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>>::deserialize(col.spec.typ(), col.slice)
+                    #deserialize_fn(col.spec.typ(), col.slice)
                         .map_err(|err| {
                             #macro_internal::mk_row_deser_err::<Self>(
                                 #macro_internal::BuiltinRowDeserializationErrorKind::ColumnDeserializationFailed {
@@ -541,32 +838,82 @@ impl DeserializeUnorderedGenerator<'_> {
 
     // Generate a declaration of a variable that temporarily keeps
     // the deserialized value
-    fn generate_deserialize_field_decl(field: &Field) -> Option<syn::Stmt> {
-        (!field.skip).then(|| {
-            let deserialize_field = Self::deserialize_field_variable(field);
-            parse_quote! {
-                let mut #deserialize_field = ::std::option::Option::None;
-            }
+    fn generate_deserialize_field_decl(&self, field: &Field) -> Option<syn::Stmt> {
+        if field.skip {
+            return None;
+        }
+
+        let deserialize_field = Self::deserialize_field_variable(field);
+        if field.flatten {
+            let macro_internal = self.0.struct_attrs().macro_internal_path();
+            let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
+            let typ = &field.ty;
+            return Some(parse_quote! {
+                let #deserialize_field = <#typ as #macro_internal::DeserializeRowByName<#frame_lifetime, #metadata_lifetime>>::deserialize_by_name(
+                    ::std::clone::Clone::clone(&row),
+                )?;
+            });
+        }
+
+        Some(parse_quote! {
+            let mut #deserialize_field = ::std::option::Option::None;
         })
     }
 
     fn generate(&self) -> syn::ImplItemFn {
         let macro_internal = self.0.struct_attrs().macro_internal_path();
         let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
+
+        parse_quote! {
+            fn deserialize(
+                row: #macro_internal::ColumnIterator<#frame_lifetime, #metadata_lifetime>,
+            ) -> ::std::result::Result<Self, #macro_internal::DeserializationError> {
+                <Self as #macro_internal::DeserializeRowByName<#frame_lifetime, #metadata_lifetime>>::deserialize_by_name(row)
+            }
+        }
+    }
+
+    fn generate_has_column(&self) -> syn::ImplItemFn {
+        let macro_internal = self.0.struct_attrs().macro_internal_path();
+        let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
+        let fields = self.0.fields();
+
+        let column_field_names = fields
+            .iter()
+            .filter(|f| f.is_column())
+            .map(|f| f.cql_name_literal());
+        let flattened_types = fields
+            .iter()
+            .filter(|f| f.flatten && !f.skip)
+            .map(|f| &f.ty);
+
+        parse_quote! {
+            fn has_column(name: &::std::primitive::str) -> ::std::primitive::bool {
+                match name {
+                    #(#column_field_names => true,)*
+                    _ => false #(|| <#flattened_types as #macro_internal::DeserializeRowByName<#frame_lifetime, #metadata_lifetime>>::has_column(name))*,
+                }
+            }
+        }
+    }
+
+    fn generate_deserialize_by_name(&self) -> syn::ImplItemFn {
+        let macro_internal = self.0.struct_attrs().macro_internal_path();
+        let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
         let fields = self.0.fields();
 
         let deserialize_field_decls = fields
             .iter()
-            .flat_map(Self::generate_deserialize_field_decl);
+            .flat_map(|f| self.generate_deserialize_field_decl(f));
         let deserialize_blocks = fields
             .iter()
-            .filter(|f| !f.skip)
+            .filter(|f| f.is_column())
             .enumerate()
             .map(|(col_idx, f)| self.generate_deserialization(col_idx, f));
         let field_idents = fields.iter().map(|f| f.ident.as_ref().unwrap());
-        let nonskipped_field_names = fields
+        let column_field_names = fields
             .iter()
-            .filter(|&f| (!f.skip))
+            .filter(|f| f.is_column())
             .map(|f| f.cql_name_literal());
 
         let field_finalizers = fields.iter().map(|f| self.generate_finalize_field(f));
@@ -574,20 +921,23 @@ impl DeserializeUnorderedGenerator<'_> {
         // TODO: Allow collecting unrecognized fields into some special field
 
         parse_quote! {
-            fn deserialize(
+            fn deserialize_by_name(
                 row: #macro_internal::ColumnIterator<#frame_lifetime, #metadata_lifetime>,
             ) -> ::std::result::Result<Self, #macro_internal::DeserializationError> {
 
                 // Generate fields that will serve as temporary storage
                 // for the fields' values. Those are of type Option<FieldType>.
+                // Flattened fields are deserialized right away.
                 #(#deserialize_field_decls)*
 
                 for col in row {
                     let col = col.map_err(#macro_internal::row_deser_error_replace_rust_name::<Self>)?;
                     // Pattern match on the field name and deserialize.
                     match col.spec.name() {
-                        #(#nonskipped_field_names => #deserialize_blocks,)*
-                        unknown => ::std::unreachable!("Typecheck should have prevented this scenario! Unknown column name: {}", unknown),
+                        #(#column_field_names => #deserialize_blocks,)*
+                        // The column belongs to a flattened field,
+                        // or to a struct that this one is flattened into.
+                        _ => {}
                     }
                 }
 
@@ -5,6 +5,7 @@ use proc_macro::TokenStream;
 use proc_macro2::Span;
 use syn::{ext::IdentExt, parse_quote};
 
+use crate::enums::{EnumDesc, TransparentField};
 use crate::Flavor;
 
 use super::{DeserializeCommonFieldAttrs, DeserializeCommonStructAttrs};
@@ -31,6 +32,11 @@ struct StructAttrs {
     // they will be ignored. With true, an error will be raised.
     #[darling(default)]
     forbid_excess_udt_fields: bool,
+
+    // If true, then the struct must have exactly one field
+    // and it is deserialized as if it was that field.
+    #[darling(default)]
+    transparent: bool,
 }
 
 impl DeserializeCommonStructAttrs for StructAttrs {
@@ -64,6 +70,11 @@ struct Field {
     #[darling(default)]
     rename: Option<String>,
 
+    // If set, then the field is type checked and deserialized with
+    // `type_check` and `deserialize` functions from the given module.
+    #[darling(default)]
+    with: Option<syn::Path>,
+
     ident: Option<syn::Ident>,
     ty: syn::Type,
 }
@@ -76,13 +87,25 @@ impl DeserializeCommonFieldAttrs for Field {
     fn deserialize_target(&self) -> &syn::Type {
         &self.ty
     }
+
+    fn with(&self) -> Option<&syn::Path> {
+        self.with.as_ref()
+    }
 }
 
 // derive(DeserializeValue) for the DeserializeValue trait
 pub(crate) fn deserialize_value_derive(
     tokens_input: TokenStream,
 ) -> Result<syn::ItemImpl, syn::Error> {
-    let input = syn::parse(tokens_input)?;
+    let input: syn::DeriveInput = syn::parse(tokens_input)?;
+    if let syn::Data::Enum(data) = &input.data {
+        let desc = EnumDesc::new(&input, data, "DeserializeValue")?;
+        return Ok(generate_enum_impl(&desc));
+    }
+    let attrs = StructAttrs::from_attributes(&input.attrs)?;
+    if attrs.transparent {
+        return generate_transparent_impl(&input, &attrs);
+    }
 
     let implemented_trait: syn::Path = parse_quote!(DeserializeValue);
     let implemented_trait_name = implemented_trait
@@ -105,6 +128,114 @@ pub(crate) fn deserialize_value_derive(
     Ok(s.generate_impl(implemented_trait, items))
 }
 
+fn generate_enum_impl(desc: &EnumDesc) -> syn::ItemImpl {
+    let macro_internal = desc.attrs.macro_internal_path();
+    let enum_name = &desc.name;
+    let repr_type = desc.attrs.repr.rust_type();
+    let accepted_types = desc.accepted_types_pattern();
+    let expected_types = desc.expected_types();
+
+    let variant_idents = desc.variants.iter().map(|v| &v.ident);
+    let variant_values = desc.variants.iter().map(|v| &v.value);
+    let unknown_value_arm: syn::Arm = match &desc.catch_all {
+        Some(c) => {
+            let ident = &c.ident;
+            let ty = &c.ty;
+            parse_quote! {
+                other => ::std::result::Result::Ok(
+                    Self::#ident(<#ty as ::std::convert::From<#repr_type>>::from(other))
+                ),
+            }
+        }
+        None => parse_quote! {
+            other => ::std::result::Result::Err(#macro_internal::mk_value_deser_err::<Self>(
+                typ,
+                #macro_internal::BuiltinTypeDeserializationErrorKind::UnknownEnumValue {
+                    value: <_ as ::std::string::ToString>::to_string(&other),
+                },
+            )),
+        },
+    };
+
+    parse_quote! {
+        #[automatically_derived]
+        impl<'frame, 'metadata> #macro_internal::DeserializeValue<'frame, 'metadata> for #enum_name {
+            fn type_check(
+                typ: &#macro_internal::ColumnType,
+            ) -> ::std::result::Result<(), #macro_internal::TypeCheckError> {
+                match typ {
+                    #accepted_types => ::std::result::Result::Ok(()),
+                    _ => ::std::result::Result::Err(#macro_internal::mk_value_typck_err::<Self>(
+                        typ,
+                        #macro_internal::DeserBuiltinTypeTypeCheckErrorKind::MismatchedType {
+                            expected: #expected_types,
+                        },
+                    )),
+                }
+            }
+
+            fn deserialize(
+                typ: &'metadata #macro_internal::ColumnType<'metadata>,
+                v: ::std::option::Option<#macro_internal::FrameSlice<'frame>>,
+            ) -> ::std::result::Result<Self, #macro_internal::DeserializationError> {
+                let value = <#repr_type as #macro_internal::DeserializeValue<'frame, 'metadata>>::deserialize(typ, v)
+                    .map_err(#macro_internal::value_deser_error_replace_rust_name::<Self>)?;
+                match value {
+                    #(#variant_values => ::std::result::Result::Ok(Self::#variant_idents),)*
+                    #unknown_value_arm
+                }
+            }
+        }
+    }
+}
+
+fn generate_transparent_impl(
+    input: &syn::DeriveInput,
+    attrs: &StructAttrs,
+) -> Result<syn::ItemImpl, syn::Error> {
+    if attrs.skip_name_checks || attrs.forbid_excess_udt_fields {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "the `transparent` attribute cannot be combined with UDT-specific attributes",
+        ));
+    }
+    let field = TransparentField::new(input, "DeserializeValue")?;
+
+    let macro_internal = attrs.macro_internal_path();
+    let struct_name = &input.ident;
+    let member = &field.member;
+    let ty = &field.ty;
+    let (frame_lifetime, metadata_lifetime) =
+        super::generate_pair_of_unique_lifetimes_for_impl(&input.generics);
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let impl_generics = &input.generics.params;
+    let predicates = where_clause.map(|w| &w.predicates);
+
+    Ok(parse_quote! {
+        #[automatically_derived]
+        impl<#frame_lifetime, #metadata_lifetime, #impl_generics>
+            #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime> for #struct_name #ty_generics
+        where
+            #ty: #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>,
+            #predicates
+        {
+            fn type_check(
+                typ: &#macro_internal::ColumnType,
+            ) -> ::std::result::Result<(), #macro_internal::TypeCheckError> {
+                <#ty as #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>>::type_check(typ)
+            }
+
+            fn deserialize(
+                typ: &#metadata_lifetime #macro_internal::ColumnType<#metadata_lifetime>,
+                v: ::std::option::Option<#macro_internal::FrameSlice<#frame_lifetime>>,
+            ) -> ::std::result::Result<Self, #macro_internal::DeserializationError> {
+                let value = <#ty as #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>>::deserialize(typ, v)?;
+                ::std::result::Result::Ok(Self { #member: value })
+            }
+        }
+    })
+}
+
 fn validate_attrs(attrs: &StructAttrs, fields: &[Field]) -> Result<(), darling::Error> {
     let mut errors = darling::Error::accumulator();
 
@@ -226,9 +357,8 @@ impl TypeCheckAssumeOrderGenerator<'_> {
     // Generates name and type validation for given Rust struct's field.
     fn generate_field_validation(&self, rust_field_idx: usize, field: &Field) -> syn::Expr {
         let macro_internal = self.0.struct_attrs().macro_internal_path();
-        let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
         let rust_field_name = field.cql_name_literal();
-        let rust_field_typ = field.deserialize_target();
+        let type_check_fn = field.type_check_fn(&macro_internal, self.0.constraint_lifetimes());
         let default_when_missing = field.default_when_missing;
         let skip_name_checks = self.0.attrs.skip_name_checks;
 
@@ -293,7 +423,7 @@ impl TypeCheckAssumeOrderGenerator<'_> {
                     #name_verification
 
                     // Verify the type
-                    <#rust_field_typ as #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>>::type_check(cql_field_typ)
+                    #type_check_fn(cql_field_typ)
                         .map_err(|err| #macro_internal::mk_value_typck_err::<Self>(
                             typ,
                             #macro_internal::DeserUdtTypeCheckErrorKind::FieldTypeCheckFailed {
@@ -409,14 +539,13 @@ impl DeserializeAssumeOrderGenerator<'_> {
 
         let macro_internal = self.0.struct_attrs().macro_internal_path();
         let cql_name_literal = field.cql_name_literal();
-        let deserializer = field.deserialize_target();
-        let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
+        let deserialize_fn = field.deserialize_fn(&macro_internal, self.0.constraint_lifetimes());
         let default_when_missing = field.default_when_missing;
         let default_when_null = field.default_when_null;
         let skip_name_checks = self.0.attrs.skip_name_checks;
 
         let deserialize: syn::Expr = parse_quote! {
-            <#deserializer as #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>>::deserialize(cql_field_typ, value)
+            #deserialize_fn(cql_field_typ, value)
                 .map_err(|err| #macro_internal::mk_value_deser_err::<Self>(
                     typ,
                     #macro_internal::UdtDeserializationErrorKind::FieldDeserializationFailed {
@@ -589,9 +718,8 @@ impl TypeCheckUnorderedGenerator<'_> {
     fn generate_type_check(&self, field: &Field) -> Option<syn::Block> {
         (!field.skip).then(|| {
             let macro_internal = self.0.struct_attrs().macro_internal_path();
-            let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
             let visited_flag = Self::visited_flag_variable(field);
-            let typ = field.deserialize_target();
+            let type_check_fn = field.type_check_fn(&macro_internal, self.0.constraint_lifetimes());
             let cql_name_literal = field.cql_name_literal();
             let decrement_if_required: Option<syn::Stmt> = field
                 .is_required()
@@ -600,7 +728,7 @@ impl TypeCheckUnorderedGenerator<'_> {
             parse_quote! {
                 {
                     if !#visited_flag {
-                        <#typ as #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>>::type_check(cql_field_typ)
+                        #type_check_fn(cql_field_typ)
                             .map_err(|err| #macro_internal::mk_value_typck_err::<Self>(
                                 typ,
                                 #macro_internal::DeserUdtTypeCheckErrorKind::FieldTypeCheckFailed {
@@ -767,13 +895,13 @@ impl DeserializeUnorderedGenerator<'_> {
     fn generate_deserialization(&self, field: &Field) -> Option<syn::Expr> {
         (!field.skip).then(|| {
             let macro_internal = self.0.struct_attrs().macro_internal_path();
-            let (frame_lifetime, metadata_lifetime) = self.0.constraint_lifetimes();
             let deserialize_field = Self::deserialize_field_variable(field);
             let cql_name_literal = field.cql_name_literal();
-            let deserializer = field.deserialize_target();
+            let deserialize_fn =
+                field.deserialize_fn(&macro_internal, self.0.constraint_lifetimes());
 
             let do_deserialize: syn::Expr = parse_quote! {
-                <#deserializer as #macro_internal::DeserializeValue<#frame_lifetime, #metadata_lifetime>>::deserialize(cql_field_typ, value)
+                #deserialize_fn(cql_field_typ, value)
                 .map_err(|err| #macro_internal::mk_value_deser_err::<Self>(
                     typ,
                     #macro_internal::UdtDeserializationErrorKind::FieldDeserializationFailed {
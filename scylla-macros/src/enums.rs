@@ -0,0 +1,374 @@
+//! Parsing logic shared by `SerializeValue` and `DeserializeValue` derives
+//! for Rust enums and `#[scylla(transparent)]` newtype structs.
+
+use std::collections::HashMap;
+
+use darling::{FromAttributes, FromMeta};
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use syn::{parse_quote, spanned::Spanned};
+
+/// The CQL representation of a Rust enum.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub(crate) enum EnumRepr {
+    Text,
+    Int,
+    TinyInt,
+}
+
+impl FromMeta for EnumRepr {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        match value {
+            "text" => Ok(Self::Text),
+            "int" => Ok(Self::Int),
+            "tinyint" => Ok(Self::TinyInt),
+            _ => Err(darling::Error::unknown_value(value)),
+        }
+    }
+}
+
+impl EnumRepr {
+    /// The Rust type that the enum's value is converted to/from.
+    pub(crate) fn rust_type(&self) -> syn::Type {
+        match self {
+            EnumRepr::Text => parse_quote!(&str),
+            EnumRepr::Int => parse_quote!(i32),
+            EnumRepr::TinyInt => parse_quote!(i8),
+        }
+    }
+
+    /// Names of `NativeType` variants that the enum can be (de)serialized as.
+    pub(crate) fn native_types(&self) -> Vec<syn::Ident> {
+        let names: &[&str] = match self {
+            EnumRepr::Text => &["Ascii", "Text"],
+            EnumRepr::Int => &["Int"],
+            EnumRepr::TinyInt => &["TinyInt"],
+        };
+        names
+            .iter()
+            .map(|name| syn::Ident::new(name, proc_macro2::Span::call_site()))
+            .collect()
+    }
+}
+
+#[derive(FromAttributes)]
+#[darling(attributes(scylla))]
+pub(crate) struct EnumAttrs {
+    #[darling(rename = "crate")]
+    crate_path: Option<syn::Path>,
+
+    // How the enum is represented in the database.
+    pub(crate) repr: EnumRepr,
+}
+
+impl EnumAttrs {
+    pub(crate) fn macro_internal_path(&self) -> syn::Path {
+        match &self.crate_path {
+            Some(path) => parse_quote!(#path::_macro_internal),
+            None => parse_quote!(::scylla::_macro_internal),
+        }
+    }
+}
+
+/// The value passed to `rename`, kept as an unparsed expression
+/// so that both string and (possibly negative) integer literals are accepted.
+struct RenameValue(syn::Expr);
+
+impl FromMeta for RenameValue {
+    fn from_expr(expr: &syn::Expr) -> darling::Result<Self> {
+        Ok(Self(expr.clone()))
+    }
+}
+
+#[derive(FromAttributes)]
+#[darling(attributes(scylla))]
+struct VariantAttrs {
+    // If set, then the variant is represented by this value
+    // instead of its Rust name (or discriminant for integer reprs).
+    rename: Option<RenameValue>,
+
+    // If true, the variant holds all values that don't correspond
+    // to any other variant.
+    #[darling(default)]
+    catch_all: bool,
+}
+
+/// A unit variant of the enum, along with the value that represents it.
+pub(crate) struct Variant {
+    pub(crate) ident: syn::Ident,
+    /// A literal of the repr's Rust type, usable both as an expression
+    /// and as a pattern.
+    pub(crate) value: TokenStream,
+}
+
+/// A single-field tuple variant that holds unknown values.
+pub(crate) struct CatchAllVariant {
+    pub(crate) ident: syn::Ident,
+    pub(crate) ty: syn::Type,
+}
+
+pub(crate) struct EnumDesc {
+    pub(crate) name: syn::Ident,
+    pub(crate) attrs: EnumAttrs,
+    pub(crate) variants: Vec<Variant>,
+    pub(crate) catch_all: Option<CatchAllVariant>,
+}
+
+impl EnumDesc {
+    pub(crate) fn new(
+        input: &syn::DeriveInput,
+        data: &syn::DataEnum,
+        current_derive: &str,
+    ) -> Result<Self, syn::Error> {
+        let attrs = EnumAttrs::from_attributes(&input.attrs)?;
+
+        if !input.generics.params.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &input.generics,
+                format!("derive({}) does not support generic enums", current_derive),
+            ));
+        }
+        if data.variants.is_empty() {
+            return Err(syn::Error::new_spanned(
+                data.enum_token,
+                format!(
+                    "derive({}) requires the enum to have at least one variant",
+                    current_derive
+                ),
+            ));
+        }
+
+        let mut errors = darling::Error::accumulator();
+        let mut variants = Vec::new();
+        let mut catch_all: Option<CatchAllVariant> = None;
+        let mut used_values = HashMap::<String, syn::Ident>::new();
+        // The implicit discriminant of the next variant, following Rust rules.
+        let mut next_discriminant: i128 = 0;
+
+        for variant in data.variants.iter() {
+            let Some(variant_attrs) = errors.handle(VariantAttrs::from_attributes(&variant.attrs))
+            else {
+                continue;
+            };
+
+            if variant_attrs.catch_all {
+                let field = match &variant.fields {
+                    syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                        fields.unnamed.first().unwrap()
+                    }
+                    _ => {
+                        errors.push(
+                            darling::Error::custom(
+                                "the `catch_all` variant must have exactly one unnamed field",
+                            )
+                            .with_span(&variant.ident),
+                        );
+                        continue;
+                    }
+                };
+                if variant_attrs.rename.is_some() {
+                    errors.push(
+                        darling::Error::custom(
+                            "the `rename` attribute doesn't make sense on the `catch_all` variant",
+                        )
+                        .with_span(&variant.ident),
+                    );
+                }
+                if catch_all.is_some() {
+                    errors.push(
+                        darling::Error::custom("only one variant can be marked as `catch_all`")
+                            .with_span(&variant.ident),
+                    );
+                }
+                catch_all = Some(CatchAllVariant {
+                    ident: variant.ident.clone(),
+                    ty: field.ty.clone(),
+                });
+                next_discriminant += 1;
+                continue;
+            }
+
+            if !matches!(variant.fields, syn::Fields::Unit) {
+                errors.push(
+                    darling::Error::custom(format!(
+                        "derive({}) supports only fieldless variants (apart from the `catch_all` variant)",
+                        current_derive
+                    ))
+                    .with_span(&variant.ident),
+                );
+                continue;
+            }
+
+            // Rust discriminants advance even if the CQL value is overridden with `rename`.
+            let discriminant = match &variant.discriminant {
+                Some((_, expr)) => match int_from_expr(expr) {
+                    Some(value) => value,
+                    None if attrs.repr == EnumRepr::Text || variant_attrs.rename.is_some() => {
+                        next_discriminant
+                    }
+                    None => {
+                        errors.push(
+                            darling::Error::custom(
+                                "only integer literals are supported as discriminants; use `#[scylla(rename = ...)]` instead",
+                            )
+                            .with_span(expr),
+                        );
+                        continue;
+                    }
+                },
+                None => next_discriminant,
+            };
+            next_discriminant = discriminant + 1;
+
+            let value = match (attrs.repr, variant_attrs.rename.as_ref().map(|r| &r.0)) {
+                (EnumRepr::Text, None) => Ok(Literal::string(
+                    &syn::ext::IdentExt::unraw(&variant.ident).to_string(),
+                )),
+                (EnumRepr::Text, Some(expr)) => match expr {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(s),
+                        ..
+                    }) => Ok(Literal::string(&s.value())),
+                    _ => Err(darling::Error::custom(
+                        "`rename` must be a string literal for `repr = \"text\"`",
+                    )
+                    .with_span(expr)),
+                },
+                (repr, rename) => {
+                    let value = match rename {
+                        Some(expr) => int_from_expr(expr).ok_or_else(|| {
+                            darling::Error::custom(
+                                "`rename` must be an integer literal for integer reprs",
+                            )
+                            .with_span(expr)
+                        }),
+                        None => Ok(discriminant),
+                    };
+                    value.and_then(|value| {
+                        int_literal(repr, value).ok_or_else(|| {
+                            darling::Error::custom(format!(
+                                "the value {} does not fit in the CQL type of the enum",
+                                value
+                            ))
+                            .with_span(&variant.ident)
+                        })
+                    })
+                }
+            };
+            let Some(value) = errors.handle(value) else {
+                continue;
+            };
+
+            let value_str = value.to_string();
+            if let Some(other) = used_values.get(&value_str) {
+                errors.push(
+                    darling::Error::custom(format!(
+                        "the value {} used by this variant is already used by variant `{}`",
+                        value_str, other
+                    ))
+                    .with_span(&variant.ident),
+                );
+            } else {
+                used_values.insert(value_str, variant.ident.clone());
+            }
+
+            variants.push(Variant {
+                ident: variant.ident.clone(),
+                value: quote!(#value),
+            });
+        }
+
+        errors.finish()?;
+
+        Ok(Self {
+            name: input.ident.clone(),
+            attrs,
+            variants,
+            catch_all,
+        })
+    }
+
+    /// An expression that evaluates to the list of CQL types that the enum
+    /// can be (de)serialized as, in the form expected by `MismatchedType`.
+    pub(crate) fn expected_types(&self) -> syn::Expr {
+        let macro_internal = self.attrs.macro_internal_path();
+        let native_types = self.attrs.repr.native_types();
+        parse_quote! {
+            &[#(#macro_internal::ColumnType::Native(#macro_internal::NativeType::#native_types)),*]
+        }
+    }
+
+    /// A pattern matching the CQL types that the enum can be (de)serialized as.
+    pub(crate) fn accepted_types_pattern(&self) -> syn::Pat {
+        let macro_internal = self.attrs.macro_internal_path();
+        let native_types = self.attrs.repr.native_types();
+        parse_quote! {
+            #macro_internal::ColumnType::Native(#(#macro_internal::NativeType::#native_types)|*)
+        }
+    }
+}
+
+/// Parses an (optionally negated) integer literal.
+fn int_from_expr(expr: &syn::Expr) -> Option<i128> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(lit),
+            ..
+        }) => lit.base10_parse().ok(),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => int_from_expr(expr).map(|v| -v),
+        syn::Expr::Group(syn::ExprGroup { expr, .. })
+        | syn::Expr::Paren(syn::ExprParen { expr, .. }) => int_from_expr(expr),
+        _ => None,
+    }
+}
+
+/// Creates a suffixed literal of the repr's Rust type, if the value fits in it.
+fn int_literal(repr: EnumRepr, value: i128) -> Option<Literal> {
+    match repr {
+        EnumRepr::Int => i32::try_from(value).ok().map(Literal::i32_suffixed),
+        EnumRepr::TinyInt => i8::try_from(value).ok().map(Literal::i8_suffixed),
+        EnumRepr::Text => None,
+    }
+}
+
+/// The single field of a `#[scylla(transparent)]` struct.
+pub(crate) struct TransparentField {
+    pub(crate) member: syn::Member,
+    pub(crate) ty: syn::Type,
+}
+
+impl TransparentField {
+    pub(crate) fn new(input: &syn::DeriveInput, current_derive: &str) -> Result<Self, syn::Error> {
+        let fields = match &input.data {
+            syn::Data::Struct(data) => &data.fields,
+            _ => {
+                return Err(syn::Error::new(
+                    input.ident.span(),
+                    "the `transparent` attribute is only supported on structs",
+                ))
+            }
+        };
+        if fields.len() != 1 {
+            return Err(syn::Error::new(
+                fields.span(),
+                format!(
+                    "derive({}) with the `transparent` attribute requires the struct to have exactly one field",
+                    current_derive
+                ),
+            ));
+        }
+        let field = fields.iter().next().unwrap();
+        let member = match &field.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(0.into()),
+        };
+        Ok(Self {
+            member,
+            ty: field.ty.clone(),
+        })
+    }
+}
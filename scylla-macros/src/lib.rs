@@ -20,6 +20,7 @@
 use darling::{FromMeta, ToTokens};
 use proc_macro::TokenStream;
 
+mod enums;
 mod parser;
 
 // Flavor of serialization/deserialization macros ({De,S}erialize{Value,Row}).
@@ -45,7 +46,10 @@ mod serialize;
 /// Derive macro for the [`SerializeValue`](./serialize/value/trait.SerializeValue.html) trait
 /// which serializes given Rust structure as a User Defined Type (UDT).
 ///
-/// At the moment, only structs with named fields are supported.
+/// At the moment, only structs with named fields are supported as UDTs.
+/// Additionally, fieldless enums can be serialized as `text`, `int` or `tinyint`
+/// (see [Enums](#enums)), and single-field structs can be serialized
+/// as their only field (see `#[scylla(transparent)]`).
 ///
 /// Serialization will fail if there are some fields in the Rust struct that don't match
 /// to any of the UDT fields.
@@ -151,6 +155,59 @@ mod serialize;
 ///
 /// Don't use the field during serialization.
 ///
+/// `#[scylla(with = "path::to::module")]`
+///
+/// Serializes the field with `path::to::module::serialize` instead of the
+/// field type's `SerializeValue` implementation. The function must have
+/// the same signature as [`SerializeValue::serialize`](../scylla/serialize/value/trait.SerializeValue.html#tymethod.serialize),
+/// but take the field by reference as the first argument. The module can be
+/// shared with `DeserializeValue`, which uses its `type_check` and `deserialize`
+/// functions.
+///
+/// # Newtype structs
+///
+/// `#[scylla(transparent)]`
+///
+/// The struct must have exactly one field, and it is serialized exactly like
+/// that field, e.g. `struct UserId(Uuid)` is serialized as a `uuid`.
+/// Type checking and serialization errors are the ones of the inner type.
+///
+/// # Enums
+///
+/// Enums whose variants have no fields can be serialized as a CQL value
+/// identifying the variant:
+///
+/// ```rust
+/// # use scylla::SerializeValue;
+/// #[derive(SerializeValue)]
+/// #[scylla(repr = "text")]
+/// enum Status {
+///     Active,
+///     #[scylla(rename = "disabled")]
+///     Inactive,
+///     #[scylla(catch_all)]
+///     Other(String),
+/// }
+/// ```
+///
+/// `#[scylla(repr = "text" | "int" | "tinyint")]`
+///
+/// Required. Chooses the CQL type of the column. With `"text"`, a variant is
+/// represented by its name (and the enum can be serialized to `text` and `ascii`).
+/// With `"int"` and `"tinyint"`, a variant is represented by its discriminant,
+/// which must be given as an integer literal or left implicit.
+///
+/// `#[scylla(rename = value)]` (variant attribute)
+///
+/// Represents the variant by the given string (for `"text"`) or integer
+/// (for `"int"` and `"tinyint"`) literal instead of its name or discriminant.
+///
+/// `#[scylla(catch_all)]` (variant attribute)
+///
+/// Marks a variant with a single unnamed field that holds values which do not
+/// correspond to any other variant. The field is serialized as is, so it should be
+/// `String` for `"text"`, `i32` for `"int"` and `i8` for `"tinyint"`.
+///
 #[proc_macro_derive(SerializeValue, attributes(scylla))]
 pub fn serialize_value_derive(tokens_input: TokenStream) -> TokenStream {
     match serialize::value::derive_serialize_value(tokens_input) {
@@ -263,6 +320,12 @@ pub fn serialize_value_derive(tokens_input: TokenStream) -> TokenStream {
 ///
 /// Note that the name of this field is ignored and hence the `rename` attribute does not make sense
 /// here and will cause a compilation error.
+///
+/// `#[scylla(with = "path::to::module")]`
+///
+/// Serializes the field with `path::to::module::serialize` instead of the
+/// field type's `SerializeValue` implementation. See the `SerializeValue`
+/// derive macro for the expected signature. Cannot be combined with `flatten`.
 #[proc_macro_derive(SerializeRow, attributes(scylla))]
 pub fn serialize_row_derive(tokens_input: TokenStream) -> TokenStream {
     match serialize::row::derive_serialize_row(tokens_input) {
@@ -387,6 +450,59 @@ mod deserialize;
 /// By default, the generated implementation will try to match the Rust field
 /// to a column with the same name. This attribute allows to match to a column
 /// with provided name.
+///
+/// `#[scylla(flatten)]`
+///
+/// Inline fields from a field into the parent struct. In other words, use this field's
+/// `DeserializeRow` implementation to deserialize from possibly multiple columns as part
+/// of the parent struct's deserialization process. The type of the field must also derive
+/// `DeserializeRow`, with the same flavor as the parent struct.
+///
+/// Note that the name of this field is ignored and hence the `rename` attribute does not make sense
+/// here and will cause a compilation error.
+///
+/// `#[scylla(with = "path::to::module")]`
+///
+/// Type checks and deserializes the column with `path::to::module::type_check`
+/// and `path::to::module::deserialize` instead of the field type's `DeserializeValue`
+/// implementation. See the `DeserializeValue` derive macro for the expected signatures.
+/// Cannot be combined with `flatten`.
+///
+/// `#[scylla(writetime_of = "column_name")]`
+///
+/// `#[scylla(ttl_of = "column_name")]`
+///
+/// Matches the field to the column produced by the `writetime(column_name)` or
+/// `ttl(column_name)` selector, respectively. Both selectors return null for null
+/// columns, and `ttl` also for columns without a TTL, so the field type usually
+/// is `Option<i64>` for write timestamps and `Option<i32>` for TTLs.
+///
+/// # Selectors
+///
+/// The macro also implements the [`RowSelectors`](../scylla/deserialize/row/trait.RowSelectors.html)
+/// trait, which lists the selectors that fetch the struct's columns.
+/// Column names are always quoted, so reserved keywords can be used as names:
+///
+/// ```rust
+/// # use scylla::DeserializeRow;
+/// use scylla::deserialize::row::RowSelectors;
+///
+/// #[derive(DeserializeRow)]
+/// struct Versioned {
+///     id: i32,
+///     value: Option<String>,
+///     #[scylla(writetime_of = "value")]
+///     value_writetime: Option<i64>,
+///     #[scylla(ttl_of = "value")]
+///     value_ttl: Option<i32>,
+/// }
+///
+/// let statement = format!("SELECT {} FROM ks.tab", Versioned::selector_list());
+/// assert_eq!(
+///     statement,
+///     r#"SELECT "id", "value", writetime("value"), ttl("value") FROM ks.tab"#
+/// );
+/// ```
 #[proc_macro_derive(DeserializeRow, attributes(scylla))]
 pub fn deserialize_row_derive(tokens_input: TokenStream) -> TokenStream {
     match deserialize::row::deserialize_row_derive(tokens_input) {
@@ -399,7 +515,10 @@ pub fn deserialize_row_derive(tokens_input: TokenStream) -> TokenStream {
 /// trait that generates an implementation which deserializes a User Defined Type
 /// with the same layout as the Rust struct.
 ///
-/// At the moment, only structs with named fields are supported.
+/// At the moment, only structs with named fields are supported as UDTs.
+/// Additionally, fieldless enums can be deserialized from `text`, `int` or `tinyint`
+/// (see [Enums](#enums)), and single-field structs can be deserialized
+/// as their only field (see `#[scylla(transparent)]`).
 ///
 /// This macro properly supports structs with lifetimes, meaning that you can
 /// deserialize UDTs with fields that borrow memory from the serialized response.
@@ -515,6 +634,96 @@ pub fn deserialize_row_derive(tokens_input: TokenStream) -> TokenStream {
 /// By default, the generated implementation will try to match the Rust field
 /// to a UDT field with the same name. This attribute instead allows to match
 /// to a UDT field with provided name.
+///
+/// `#[scylla(with = "path::to::module")]`
+///
+/// Type checks and deserializes the field with `path::to::module::type_check`
+/// and `path::to::module::deserialize` instead of the field type's `DeserializeValue`
+/// implementation. The functions must have the same signatures as
+/// [`DeserializeValue::type_check`](../scylla/deserialize/value/trait.DeserializeValue.html#tymethod.type_check)
+/// and [`DeserializeValue::deserialize`](../scylla/deserialize/value/trait.DeserializeValue.html#tymethod.deserialize),
+/// with `deserialize` returning the field type:
+///
+/// ```rust
+/// # use scylla::{DeserializeValue, SerializeValue};
+/// #[derive(DeserializeValue, SerializeValue)]
+/// struct Config {
+///     #[scylla(with = "u32_as_text")]
+///     version: u32,
+/// }
+///
+/// mod u32_as_text {
+///     use scylla::deserialize::{value::DeserializeValue, DeserializationError, FrameSlice, TypeCheckError};
+///     use scylla::frame::response::result::ColumnType;
+///     use scylla::serialize::{value::SerializeValue, writers::{CellWriter, WrittenCellProof}, SerializationError};
+///
+///     pub fn serialize<'b>(
+///         v: &u32,
+///         typ: &ColumnType,
+///         writer: CellWriter<'b>,
+///     ) -> Result<WrittenCellProof<'b>, SerializationError> {
+///         <String as SerializeValue>::serialize(&v.to_string(), typ, writer)
+///     }
+///
+///     pub fn type_check(typ: &ColumnType) -> Result<(), TypeCheckError> {
+///         <&str as DeserializeValue>::type_check(typ)
+///     }
+///
+///     pub fn deserialize<'frame, 'metadata>(
+///         typ: &'metadata ColumnType<'metadata>,
+///         v: Option<FrameSlice<'frame>>,
+///     ) -> Result<u32, DeserializationError> {
+///         let s = <&str as DeserializeValue>::deserialize(typ, v)?;
+///         s.parse().map_err(DeserializationError::new)
+///     }
+/// }
+/// ```
+///
+/// # Newtype structs
+///
+/// `#[scylla(transparent)]`
+///
+/// The struct must have exactly one field, and it is deserialized exactly like
+/// that field. The struct may borrow from the frame, e.g. `struct Name<'a>(&'a str)`.
+///
+/// # Enums
+///
+/// Enums whose variants have no fields can be deserialized from a CQL value
+/// identifying the variant:
+///
+/// ```rust
+/// # use scylla::DeserializeValue;
+/// #[derive(DeserializeValue)]
+/// # #[scylla(crate = "scylla_cql")]
+/// #[scylla(repr = "int")]
+/// enum Priority {
+///     Low = 1,
+///     Medium, // = 2
+///     High = 10,
+/// }
+/// ```
+///
+/// `#[scylla(repr = "text" | "int" | "tinyint")]`
+///
+/// Required. Chooses the CQL type of the column. With `"text"`, a variant is
+/// identified by its name (the enum can be deserialized from `text` and `ascii`).
+/// With `"int"` and `"tinyint"`, a variant is identified by its discriminant,
+/// which must be given as an integer literal or left implicit.
+///
+/// If the read value does not correspond to any variant, deserialization fails
+/// with [`BuiltinDeserializationErrorKind::UnknownEnumValue`](./deserialize/value/enum.BuiltinDeserializationErrorKind.html#variant.UnknownEnumValue),
+/// unless there is a `catch_all` variant.
+///
+/// `#[scylla(rename = value)]` (variant attribute)
+///
+/// Identifies the variant by the given string (for `"text"`) or integer
+/// (for `"int"` and `"tinyint"`) literal instead of its name or discriminant.
+///
+/// `#[scylla(catch_all)]` (variant attribute)
+///
+/// Marks a variant with a single unnamed field that receives values which do not
+/// correspond to any other variant. The field type must implement `From<&str>`
+/// for `"text"`, `From<i32>` for `"int"` and `From<i8>` for `"tinyint"`.
 #[proc_macro_derive(DeserializeValue, attributes(scylla))]
 pub fn deserialize_value_derive(tokens_input: TokenStream) -> TokenStream {
     match deserialize::value::deserialize_value_derive(tokens_input) {
@@ -522,3 +731,208 @@ pub fn deserialize_value_derive(tokens_input: TokenStream) -> TokenStream {
         Err(err) => err.into_compile_error().into(),
     }
 }
+
+mod table;
+
+/// Derive macro for the [`Table`](./client/table/trait.Table.html) trait
+/// that describes how the Rust struct maps onto the rows of a table,
+/// and provides typed `insert`, `get`, `update` and `delete` methods.
+///
+/// The generated statements are executed through a
+/// [`CachingSession`](./client/caching_session/struct.CachingSession.html),
+/// so each of them is prepared on first use and then served from the cache.
+/// Fetching rows with `get` additionally requires the struct to implement
+/// [`DeserializeRow`](./deserialize/row/trait.DeserializeRow.html), which can be
+/// derived alongside - both derives understand each other's attributes.
+///
+/// At the moment, only structs with named fields are supported.
+///
+/// # Example
+///
+/// Given a table:
+///
+/// ```text
+/// CREATE TABLE ks.users (id int, name text, email text, PRIMARY KEY (id, name));
+/// ```
+///
+/// ...the rows can be manipulated with the following struct:
+///
+/// ```rust
+/// # use scylla::client::caching_session::CachingSession;
+/// # use scylla::client::table::{InsertOptions, Table};
+/// #[derive(scylla::Table, scylla::DeserializeRow)]
+/// #[scylla(keyspace = "ks", table = "users")]
+/// struct User {
+///     #[scylla(partition_key)]
+///     id: i32,
+///     #[scylla(clustering_key)]
+///     name: String,
+///     email: Option<String>,
+/// }
+///
+/// # async fn example(session: &CachingSession) -> Result<(), Box<dyn std::error::Error>> {
+/// let user = User {
+///     id: 1,
+///     name: "John".to_owned(),
+///     email: None,
+/// };
+/// user.insert_with(session, InsertOptions::new().ttl(3600).if_not_exists(true))
+///     .await?;
+///
+/// let fetched = User::get(session, &(1, "John".to_owned())).await?;
+/// assert!(fetched.is_some());
+///
+/// User::delete(session, &(1, "John".to_owned())).await?;
+/// # Ok(())
+/// # }
+///
+/// assert_eq!(
+///     User::select_statement(),
+///     r#"SELECT "id", "name", "email" FROM "ks"."users" WHERE "id" = ? AND "name" = ?"#
+/// );
+/// ```
+///
+/// # Struct attributes
+///
+/// `#[scylla(table = "name")]`
+///
+/// Required. Name of the table.
+///
+/// `#[scylla(keyspace = "name")]`
+///
+/// Keyspace of the table. If not provided, the statements refer to the table
+/// in the keyspace set for the session.
+///
+/// `#[scylla(crate = crate_name)]`
+///
+/// Specify a path to the `scylla` crate to use from the generated code.
+/// Unlike with the (de)serialization derives, a path to `scylla-cql` is not
+/// enough, because the [`Table`](./client/table/trait.Table.html) trait is
+/// defined in `scylla`.
+///
+/// # Field attributes
+///
+/// `#[scylla(partition_key)]`
+///
+/// Marks the column as a part of the partition key. At least one field must
+/// be annotated with it. Partition key columns are ordered in the same way as
+/// the fields.
+///
+/// `#[scylla(clustering_key)]`
+///
+/// Marks the column as a part of the clustering key. Clustering key columns
+/// are ordered in the same way as the fields.
+///
+/// The primary key passed to `get` and `delete` is a tuple of the types of
+/// the partition key fields, followed by the clustering key fields.
+///
+/// `#[scylla(rename = "column_name")]`
+///
+/// The field represents the column with given name instead of its Rust name.
+///
+/// `#[scylla(skip)]`
+///
+/// The field does not represent a column of the table.
+///
+/// `#[scylla(with = "path::to::module")]`
+///
+/// Serializes the column with `path::to::module::serialize` instead of the
+/// field type's `SerializeValue` implementation. See the `SerializeValue`
+/// derive macro for the expected signature. Cannot be used on primary key columns.
+///
+/// `#[scylla(writetime_of = "column")]`, `#[scylla(ttl_of = "column")]`
+///
+/// The field holds the write timestamp or TTL of the given column, as described
+/// in the docs of the `DeserializeRow` derive macro. Such fields are fetched by
+/// `get`, but are not written by `insert` and `update`.
+///
+/// `#[scylla(flatten)]` is not supported.
+#[proc_macro_derive(Table, attributes(scylla))]
+pub fn table_derive(tokens_input: TokenStream) -> TokenStream {
+    match table::derive_table(tokens_input) {
+        Ok(t) => t.into_token_stream().into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+mod query;
+
+/// Checks a CQL statement against a schema file at compile time, and produces
+/// a [`CheckedStatement`](./statement/checked/struct.CheckedStatement.html)
+/// with typed bind values and typed result rows.
+///
+/// The schema is read from a file with CQL DDL statements (`CREATE TABLE`,
+/// `CREATE TYPE` and `USE`; other statements are ignored), e.g. the output
+/// of `DESCRIBE KEYSPACE`. The statement is parsed and every table, column
+/// and UDT field it refers to is resolved in the schema. Then:
+/// - the number of values must match the number of bind markers,
+/// - each value must be of a Rust type that can be serialized to the CQL type
+///   of its bind marker, as determined by the
+///   [`SerializeAs`](./serialize/checked/trait.SerializeAs.html) trait,
+/// - for `SELECT` statements, the returned rows are deserialized to a tuple
+///   of Rust types matching the selected columns.
+///
+/// Any violation is reported as a compile error. The types are also verified
+/// against the metadata returned by the database when the statement is prepared,
+/// in case the schema file is out of date.
+///
+/// # Example
+///
+/// Given a `schema.cql` file in the crate's root directory:
+///
+/// ```text
+/// CREATE TABLE ks.users (id int, name text, emails set<text>, PRIMARY KEY (id, name));
+/// ```
+///
+/// ...statements on the `users` table can be checked:
+///
+/// ```rust,ignore
+/// let select = scylla::query!("SELECT name, emails FROM ks.users WHERE id = ?", 42);
+/// // `rows` is a `Vec<(String, Option<Vec<String>>)>`.
+/// let rows = select.rows(&session).await?;
+///
+/// // Does not compile: `name` is a `text` column.
+/// let insert = scylla::query!("INSERT INTO ks.users (id, name) VALUES (?, ?)", 1, 2);
+/// ```
+///
+/// # Syntax
+///
+/// `query!([crate = path,] [schema = "path",] "statement" [, value]*)`
+///
+/// `schema = "path"`
+///
+/// Path of the schema file, relative to the directory of the crate's
+/// `Cargo.toml`. Defaults to `schema.cql`. Unqualified table names
+/// must be unambiguous in the schema.
+///
+/// `crate = path`
+///
+/// Specify a path to the `scylla` crate to use from the generated code.
+///
+/// # Supported statements
+///
+/// `SELECT`, `INSERT`, `UPDATE` and `DELETE`, with positional `?` bind markers
+/// (named markers are not supported). Bind markers may appear wherever a value
+/// of a known type is expected: in `WHERE` and `IF` relations (including `IN ?`,
+/// `CONTAINS ?`, `token(...) > ?` and multi-column relations), in assignments
+/// (including collection element and UDT field updates), in `LIMIT`,
+/// `PER PARTITION LIMIT` and in `USING TTL`/`TIMESTAMP`/`TIMEOUT`.
+///
+/// Besides columns, `SELECT` supports the `writetime`, `ttl`, `count(*)`,
+/// `token`, `min` and `max` selectors. `SELECT JSON` and `INSERT JSON`
+/// are not supported.
+///
+/// # Result types
+///
+/// Primary key columns are deserialized to the Rust type corresponding to the
+/// column type, e.g. `i32` for `int` or `String` for `text`. Other columns,
+/// which may be null, are wrapped in `Option`. Collections are represented by
+/// `Vec` (and `HashMap`, if the key type is hashable), vectors by arrays.
+/// Values of user defined types are represented by `CqlValue`.
+#[proc_macro]
+pub fn query(tokens_input: TokenStream) -> TokenStream {
+    match query::query(tokens_input) {
+        Ok(t) => t.into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
@@ -0,0 +1,387 @@
+use std::path::PathBuf;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{parse_quote, Token};
+
+mod statement;
+
+use scylla_ddl::{lexer, schema};
+
+use lexer::{line_and_column, tokenize, Cursor};
+use schema::{parse_schema, CqlType, Schema};
+use statement::{analyze, Analysis};
+
+// Path of the schema file used when none is given, relative to the directory
+// of the crate's manifest.
+const DEFAULT_SCHEMA_FILE: &str = "schema.cql";
+
+struct QueryInput {
+    crate_path: Option<syn::Path>,
+    schema: Option<syn::LitStr>,
+    statement: syn::LitStr,
+    args: Vec<syn::Expr>,
+}
+
+impl Parse for QueryInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut crate_path = None;
+        let mut schema = None;
+        loop {
+            if input.peek(Token![crate]) && input.peek2(Token![=]) {
+                input.parse::<Token![crate]>()?;
+                input.parse::<Token![=]>()?;
+                crate_path = Some(input.parse()?);
+            } else if input.peek(syn::Ident) && input.peek2(Token![=]) {
+                let ident: syn::Ident = input.parse()?;
+                if ident != "schema" {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("unknown option `{ident}`, expected `crate` or `schema`"),
+                    ));
+                }
+                input.parse::<Token![=]>()?;
+                schema = Some(input.parse()?);
+            } else {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        let statement = input.parse()?;
+        let mut args = Vec::new();
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            args.push(input.parse()?);
+        }
+
+        Ok(Self {
+            crate_path,
+            schema,
+            statement,
+            args,
+        })
+    }
+}
+
+pub(crate) fn query(tokens_input: proc_macro::TokenStream) -> Result<TokenStream, syn::Error> {
+    let input: QueryInput = syn::parse(tokens_input)?;
+    let crate_path: syn::Path = match &input.crate_path {
+        Some(path) => parse_quote!(#path::_macro_internal),
+        None => parse_quote!(::scylla::_macro_internal),
+    };
+
+    let (schema_path, schema_span) = match &input.schema {
+        Some(schema) => (schema.value(), schema.span()),
+        None => (DEFAULT_SCHEMA_FILE.to_owned(), input.statement.span()),
+    };
+    let (schema_path, schema) = load_schema(&schema_path, schema_span)?;
+
+    let statement = input.statement.value();
+    let analysis = analyze_statement(&statement, &schema).map_err(|(offset, message)| {
+        let (line, column) = line_and_column(&statement, offset);
+        syn::Error::new(
+            input.statement.span(),
+            format!("invalid statement (at {line}:{column}): {message}"),
+        )
+    })?;
+
+    if analysis.markers.len() != input.args.len() {
+        let expected = analysis
+            .markers
+            .iter()
+            .map(|m| format!("`{}` ({})", m.name, m.typ.cql_name()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(syn::Error::new(
+            input.statement.span(),
+            format!(
+                "the statement has {} bind markers, but {} values were provided; expected values for: {}",
+                analysis.markers.len(),
+                input.args.len(),
+                if expected.is_empty() { "none" } else { &expected },
+            ),
+        ));
+    }
+
+    let value_idents: Vec<_> = (0..input.args.len())
+        .map(|i| syn::Ident::new(&format!("__value_{i}"), Span::mixed_site()))
+        .collect();
+    let checks = input
+        .args
+        .iter()
+        .zip(&analysis.markers)
+        .zip(&value_idents)
+        .map(|((arg, marker), ident)| {
+            let marker_type = marker_type(&marker.typ, &crate_path);
+            quote_spanned! {arg.span()=>
+                let #ident = #arg;
+                #crate_path::check_serialize_as::<#marker_type, _>(&#ident);
+            }
+        });
+
+    let bind_types = analysis
+        .markers
+        .iter()
+        .map(|m| column_type(&m.typ, &crate_path));
+    let (row_type, result_types) = match &analysis.result {
+        Some(columns) => {
+            let rust_types = columns.iter().map(|c| {
+                let typ = rust_type(&c.typ, &crate_path);
+                if c.nullable {
+                    quote!(::std::option::Option<#typ>)
+                } else {
+                    typ
+                }
+            });
+            let column_types = columns.iter().map(|c| column_type(&c.typ, &crate_path));
+            (
+                quote!((#(#rust_types,)*)),
+                quote!(::std::option::Option::Some(
+                    || ::std::vec![#(#column_types),*]
+                )),
+            )
+        }
+        None => (quote!(()), quote!(::std::option::Option::None)),
+    };
+
+    let schema_path = schema_path.to_string_lossy();
+    let statement = &input.statement;
+    Ok(quote! {
+        {
+            // Makes the compiler rebuild the crate when the schema changes.
+            const _: &[::std::primitive::u8] = ::std::include_bytes!(#schema_path);
+            #(#checks)*
+            #crate_path::CheckedStatement::<_, #row_type>::new(
+                #statement,
+                (#(#value_idents,)*),
+                || ::std::vec![#(#bind_types),*],
+                #result_types,
+            )
+        }
+    })
+}
+
+fn load_schema(path: &str, span: Span) -> Result<(PathBuf, Schema), syn::Error> {
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default();
+    let path = manifest_dir.join(path);
+    let source = std::fs::read_to_string(&path).map_err(|err| {
+        syn::Error::new(
+            span,
+            format!("failed to read the schema file {}: {}", path.display(), err),
+        )
+    })?;
+
+    let schema = tokenize(&source)
+        .and_then(|tokens| parse_schema(&mut Cursor::new(&tokens, source.len())))
+        .map_err(|(offset, message)| {
+            let (line, column) = line_and_column(&source, offset);
+            syn::Error::new(
+                span,
+                format!("{}:{}:{}: {}", path.display(), line, column, message),
+            )
+        })?;
+    Ok((path, schema))
+}
+
+fn analyze_statement(statement: &str, schema: &Schema) -> lexer::ParseResult<Analysis> {
+    let tokens = tokenize(statement)?;
+    analyze(&mut Cursor::new(&tokens, statement.len()), schema)
+}
+
+// The marker type from `serialize::checked::cql` representing the CQL type.
+fn marker_type(typ: &CqlType, crate_path: &syn::Path) -> TokenStream {
+    match typ {
+        CqlType::Native(name) => {
+            let name = syn::Ident::new(name, Span::call_site());
+            quote!(#crate_path::cql_type::#name)
+        }
+        CqlType::List { element, .. } => {
+            let element = marker_type(element, crate_path);
+            quote!(#crate_path::cql_type::List<#element>)
+        }
+        CqlType::Set { element, .. } => {
+            let element = marker_type(element, crate_path);
+            quote!(#crate_path::cql_type::Set<#element>)
+        }
+        CqlType::Map { key, value, .. } => {
+            let key = marker_type(key, crate_path);
+            let value = marker_type(value, crate_path);
+            quote!(#crate_path::cql_type::Map<#key, #value>)
+        }
+        CqlType::Tuple(elements) => {
+            let elements = elements.iter().map(|e| marker_type(e, crate_path));
+            quote!(#crate_path::cql_type::Tuple<(#(#elements,)*)>)
+        }
+        CqlType::Vector { element, .. } => {
+            let element = marker_type(element, crate_path);
+            quote!(#crate_path::cql_type::Vector<#element>)
+        }
+        CqlType::Udt { .. } => quote!(#crate_path::cql_type::Udt),
+    }
+}
+
+// An expression which builds the `ColumnType` corresponding to the CQL type.
+fn column_type(typ: &CqlType, crate_path: &syn::Path) -> TokenStream {
+    let collection = |frozen: bool, collection: TokenStream| {
+        quote! {
+            #crate_path::ColumnType::Collection {
+                frozen: #frozen,
+                typ: #collection,
+            }
+        }
+    };
+    match typ {
+        CqlType::Native(name) => {
+            let name = syn::Ident::new(name, Span::call_site());
+            quote!(#crate_path::ColumnType::Native(#crate_path::NativeType::#name))
+        }
+        CqlType::List { frozen, element } => {
+            let element = column_type(element, crate_path);
+            collection(
+                *frozen,
+                quote!(#crate_path::CollectionType::List(::std::boxed::Box::new(#element))),
+            )
+        }
+        CqlType::Set { frozen, element } => {
+            let element = column_type(element, crate_path);
+            collection(
+                *frozen,
+                quote!(#crate_path::CollectionType::Set(::std::boxed::Box::new(#element))),
+            )
+        }
+        CqlType::Map { frozen, key, value } => {
+            let key = column_type(key, crate_path);
+            let value = column_type(value, crate_path);
+            collection(
+                *frozen,
+                quote!(#crate_path::CollectionType::Map(
+                    ::std::boxed::Box::new(#key),
+                    ::std::boxed::Box::new(#value),
+                )),
+            )
+        }
+        CqlType::Tuple(elements) => {
+            let elements = elements.iter().map(|e| column_type(e, crate_path));
+            quote!(#crate_path::ColumnType::Tuple(::std::vec![#(#elements),*]))
+        }
+        CqlType::Vector {
+            element,
+            dimensions,
+        } => {
+            let element = column_type(element, crate_path);
+            quote! {
+                #crate_path::ColumnType::Vector {
+                    typ: ::std::boxed::Box::new(#element),
+                    dimensions: #dimensions,
+                }
+            }
+        }
+        CqlType::Udt { frozen, definition } => {
+            // An empty keyspace matches the UDT from any keyspace.
+            let keyspace = definition.keyspace.as_deref().unwrap_or_default();
+            let name = &definition.name;
+            let fields = definition.fields.iter().map(|(field_name, field_type)| {
+                let field_type = column_type(field_type, crate_path);
+                quote!((::std::borrow::Cow::Borrowed(#field_name), #field_type))
+            });
+            quote! {
+                #crate_path::ColumnType::UserDefinedType {
+                    frozen: #frozen,
+                    definition: ::std::sync::Arc::new(#crate_path::UserDefinedType {
+                        name: ::std::borrow::Cow::Borrowed(#name),
+                        keyspace: ::std::borrow::Cow::Borrowed(#keyspace),
+                        field_types: ::std::vec![#(#fields),*],
+                    }),
+                }
+            }
+        }
+    }
+}
+
+// The Rust type to which values of the CQL type are deserialized in result rows.
+fn rust_type(typ: &CqlType, crate_path: &syn::Path) -> TokenStream {
+    match typ {
+        CqlType::Native(name) => match *name {
+            "TinyInt" => quote!(::std::primitive::i8),
+            "SmallInt" => quote!(::std::primitive::i16),
+            "Int" => quote!(::std::primitive::i32),
+            "BigInt" => quote!(::std::primitive::i64),
+            "Float" => quote!(::std::primitive::f32),
+            "Double" => quote!(::std::primitive::f64),
+            "Boolean" => quote!(::std::primitive::bool),
+            "Ascii" | "Text" => quote!(::std::string::String),
+            "Blob" => quote!(::std::vec::Vec<::std::primitive::u8>),
+            "Uuid" => quote!(#crate_path::Uuid),
+            "Timeuuid" => quote!(#crate_path::CqlTimeuuid),
+            "Inet" => quote!(::std::net::IpAddr),
+            "Date" => quote!(#crate_path::CqlDate),
+            "Timestamp" => quote!(#crate_path::CqlTimestamp),
+            "Time" => quote!(#crate_path::CqlTime),
+            "Decimal" => quote!(#crate_path::CqlDecimal),
+            "Varint" => quote!(#crate_path::CqlVarint),
+            "Counter" => quote!(#crate_path::Counter),
+            "Duration" => quote!(#crate_path::CqlDuration),
+            _ => unreachable!("unknown native type {name}"),
+        },
+        CqlType::List { element, .. } | CqlType::Set { element, .. } => {
+            let element = rust_type(element, crate_path);
+            quote!(::std::vec::Vec<#element>)
+        }
+        CqlType::Map { key, value, .. } if is_hashable(key) => {
+            let key = rust_type(key, crate_path);
+            let value = rust_type(value, crate_path);
+            quote!(::std::collections::HashMap<#key, #value>)
+        }
+        CqlType::Tuple(elements) => {
+            // Elements of tuples may be null.
+            let elements = elements.iter().map(|e| {
+                let element = rust_type(e, crate_path);
+                quote!(::std::option::Option<#element>)
+            });
+            quote!((#(#elements,)*))
+        }
+        CqlType::Vector {
+            element,
+            dimensions,
+        } => {
+            let element = rust_type(element, crate_path);
+            let dimensions = usize::from(*dimensions);
+            quote!([#element; #dimensions])
+        }
+        // There is no natural Rust counterpart of maps with keys which can't
+        // be hashed, and of user defined types.
+        CqlType::Map { .. } | CqlType::Udt { .. } => quote!(#crate_path::CqlValue),
+    }
+}
+
+// Whether the Rust counterpart of the CQL type implements `Hash` and `Eq`.
+fn is_hashable(typ: &CqlType) -> bool {
+    match typ {
+        CqlType::Native(name) => matches!(
+            *name,
+            "TinyInt"
+                | "SmallInt"
+                | "Int"
+                | "BigInt"
+                | "Boolean"
+                | "Ascii"
+                | "Text"
+                | "Blob"
+                | "Uuid"
+                | "Timeuuid"
+                | "Inet"
+                | "Varint"
+        ),
+        CqlType::List { element, .. } | CqlType::Set { element, .. } => is_hashable(element),
+        CqlType::Tuple(elements) => elements.iter().all(is_hashable),
+        CqlType::Map { .. } | CqlType::Vector { .. } | CqlType::Udt { .. } => false,
+    }
+}
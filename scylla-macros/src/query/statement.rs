@@ -0,0 +1,661 @@
+// Analysis of a checked statement against the schema: resolves the types
+// of its bind markers and of the columns it returns.
+
+use super::lexer::{Cursor, ParseResult, Token};
+use super::schema::{Column, ColumnKind, CqlType, Schema, Table};
+
+pub(super) struct BindMarker {
+    // Describes the marker in error messages, e.g. the name of its column.
+    pub(super) name: String,
+    pub(super) typ: CqlType,
+}
+
+pub(super) struct ResultColumn {
+    pub(super) typ: CqlType,
+    // Primary key columns and some selectors, like `count(*)`, can't be null.
+    pub(super) nullable: bool,
+}
+
+pub(super) struct Analysis {
+    pub(super) markers: Vec<BindMarker>,
+    // Columns of the returned rows, if the statement is a `SELECT`.
+    pub(super) result: Option<Vec<ResultColumn>>,
+}
+
+enum Selector {
+    Wildcard,
+    Column(String),
+    Writetime(String),
+    Ttl(String),
+    Count,
+    Token,
+    MinMax(String),
+}
+
+// Keywords which end a term that is not a bind marker.
+const TERM_TERMINATORS: &[&str] = &[
+    "and", "if", "where", "using", "limit", "allow", "order", "group", "per", "bypass", "set",
+    "from", "values",
+];
+
+struct Analyzer<'c, 't, 's> {
+    cursor: &'c mut Cursor<'t>,
+    schema: &'s Schema,
+    markers: Vec<BindMarker>,
+}
+
+pub(super) fn analyze(cursor: &mut Cursor, schema: &Schema) -> ParseResult<Analysis> {
+    let mut analyzer = Analyzer {
+        cursor,
+        schema,
+        markers: Vec::new(),
+    };
+
+    let result = if analyzer.cursor.eat_keyword("select") {
+        Some(analyzer.select()?)
+    } else if analyzer.cursor.eat_keyword("insert") {
+        analyzer.insert()?;
+        None
+    } else if analyzer.cursor.eat_keyword("update") {
+        analyzer.update()?;
+        None
+    } else if analyzer.cursor.eat_keyword("delete") {
+        analyzer.delete()?;
+        None
+    } else {
+        return analyzer
+            .cursor
+            .unexpected("`SELECT`, `INSERT`, `UPDATE` or `DELETE`");
+    };
+
+    analyzer.cursor.eat_symbol(";");
+    if !analyzer.cursor.is_at_end() {
+        return analyzer.cursor.unexpected("the end of the statement");
+    }
+
+    Ok(Analysis {
+        markers: analyzer.markers,
+        result,
+    })
+}
+
+impl<'s> Analyzer<'_, '_, 's> {
+    fn table(&mut self) -> ParseResult<&'s Table> {
+        let offset = self.cursor.offset();
+        let (keyspace, name) = self.cursor.qualified_name()?;
+        self.schema
+            .table(keyspace.as_deref(), &name)
+            .map_err(|err| (offset, err))
+    }
+
+    fn column(&mut self, table: &'s Table) -> ParseResult<&'s Column> {
+        let offset = self.cursor.offset();
+        let name = self.cursor.identifier()?;
+        resolve_column(table, &name).map_err(|err| (offset, err))
+    }
+
+    // Parses a term whose value has the given type. If the term is a bind
+    // marker, it is recorded; other terms (literals) are skipped.
+    fn term(&mut self, name: impl Into<String>, typ: CqlType) -> ParseResult<()> {
+        match self.cursor.peek() {
+            Some(Token::Marker) => {
+                self.cursor.next();
+                self.markers.push(BindMarker {
+                    name: name.into(),
+                    typ,
+                });
+                Ok(())
+            }
+            Some(Token::NamedMarker(_)) => self
+                .cursor
+                .error("named bind markers are not supported, use `?` instead"),
+            Some(_) => self.skip_literal(),
+            None => self.cursor.unexpected("a term"),
+        }
+    }
+
+    // Skips a literal term, possibly a collection or a function call.
+    fn skip_literal(&mut self) -> ParseResult<()> {
+        let start = self.cursor.offset();
+        let mut depth = 0_usize;
+        while let Some(token) = self.cursor.peek() {
+            match token {
+                Token::Symbol("(" | "[" | "{") => depth += 1,
+                Token::Symbol(")" | "]" | "}") if depth == 0 => break,
+                Token::Symbol(")" | "]" | "}") => depth -= 1,
+                Token::Symbol("," | ";") if depth == 0 => break,
+                Token::Ident(ident)
+                    if depth == 0
+                        && TERM_TERMINATORS
+                            .iter()
+                            .any(|t| ident.eq_ignore_ascii_case(t)) =>
+                {
+                    break
+                }
+                Token::Marker | Token::NamedMarker(_) => {
+                    return self.cursor.error(
+                        "bind markers nested in literals or function calls are not supported",
+                    )
+                }
+                _ => {}
+            }
+            self.cursor.next();
+        }
+        if self.cursor.offset() == start {
+            return self.cursor.unexpected("a term");
+        }
+        Ok(())
+    }
+
+    // Parses `USING TTL ? AND TIMESTAMP ? AND TIMEOUT ?`, if present.
+    fn using(&mut self) -> ParseResult<()> {
+        if !self.cursor.eat_keyword("using") {
+            return Ok(());
+        }
+        loop {
+            if self.cursor.eat_keyword("ttl") {
+                self.term("[ttl]", CqlType::int())?;
+            } else if self.cursor.eat_keyword("timestamp") {
+                self.term("[timestamp]", CqlType::bigint())?;
+            } else if self.cursor.eat_keyword("timeout") {
+                self.term("[timeout]", CqlType::Native("Duration"))?;
+            } else {
+                return self.cursor.unexpected("`TTL`, `TIMESTAMP` or `TIMEOUT`");
+            }
+            if !self.cursor.eat_keyword("and") {
+                return Ok(());
+            }
+        }
+    }
+
+    // Parses relations joined with `AND`, in `WHERE` and `IF` clauses.
+    fn relations(&mut self, table: &'s Table) -> ParseResult<()> {
+        loop {
+            self.relation(table)?;
+            if !self.cursor.eat_keyword("and") {
+                return Ok(());
+            }
+        }
+    }
+
+    fn relation(&mut self, table: &'s Table) -> ParseResult<()> {
+        // token(a, b) > ?
+        if self.cursor.peek_keyword("token") && self.cursor.peek_nth(1) == Some(&Token::Symbol("("))
+        {
+            self.cursor.next();
+            self.cursor.next();
+            loop {
+                self.column(table)?;
+                if !self.cursor.eat_symbol(",") {
+                    break;
+                }
+            }
+            self.cursor.expect_symbol(")")?;
+            self.operator()?;
+            return self.term("partition key token", CqlType::bigint());
+        }
+
+        // (a, b) > (?, ?)
+        if self.cursor.eat_symbol("(") {
+            let mut columns = Vec::new();
+            loop {
+                columns.push(self.column(table)?);
+                if !self.cursor.eat_symbol(",") {
+                    break;
+                }
+            }
+            self.cursor.expect_symbol(")")?;
+            let tuple_name = columns
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let tuple_type = CqlType::Tuple(columns.iter().map(|c| c.typ.clone()).collect());
+            if self.cursor.eat_keyword("in") {
+                if self.cursor.peek() == Some(&Token::Marker) {
+                    return self.term(format!("in({tuple_name})"), CqlType::list_of(tuple_type));
+                }
+                self.cursor.expect_symbol("(")?;
+                loop {
+                    self.tuple_term(&tuple_name, &columns)?;
+                    if !self.cursor.eat_symbol(",") {
+                        break;
+                    }
+                }
+                return self.cursor.expect_symbol(")");
+            }
+            self.operator()?;
+            return self.tuple_term(&tuple_name, &columns);
+        }
+
+        let column = self.column(table)?;
+
+        // m[?] = ?, in conditions
+        if self.cursor.eat_symbol("[") {
+            let element_type = self.element_access(column)?;
+            self.cursor.expect_symbol("]")?;
+            self.operator()?;
+            return self.term(column.name.clone(), element_type);
+        }
+
+        if self.cursor.eat_keyword("in") {
+            if self.cursor.peek() == Some(&Token::Marker) {
+                return self.term(
+                    format!("in({})", column.name),
+                    CqlType::list_of(column.typ.clone()),
+                );
+            }
+            self.cursor.expect_symbol("(")?;
+            if !self.cursor.peek_symbol(")") {
+                loop {
+                    self.term(column.name.clone(), column.typ.clone())?;
+                    if !self.cursor.eat_symbol(",") {
+                        break;
+                    }
+                }
+            }
+            return self.cursor.expect_symbol(")");
+        }
+
+        if self.cursor.eat_keyword("contains") {
+            let offset = self.cursor.offset();
+            let typ = if self.cursor.eat_keyword("key") {
+                match &column.typ {
+                    CqlType::Map { key, .. } => (**key).clone(),
+                    _ => return Err((offset, format!("column `{}` is not a map", column.name))),
+                }
+            } else {
+                match &column.typ {
+                    CqlType::List { element, .. } | CqlType::Set { element, .. } => {
+                        (**element).clone()
+                    }
+                    CqlType::Map { value, .. } => (**value).clone(),
+                    _ => {
+                        return Err((
+                            offset,
+                            format!("column `{}` is not a collection", column.name),
+                        ))
+                    }
+                }
+            };
+            return self.term(column.name.clone(), typ);
+        }
+
+        self.operator()?;
+        self.term(column.name.clone(), column.typ.clone())
+    }
+
+    fn operator(&mut self) -> ParseResult<()> {
+        const OPERATORS: &[&str] = &["=", "<", ">", "<=", ">=", "!="];
+        if OPERATORS.iter().any(|op| self.cursor.eat_symbol(op)) || self.cursor.eat_keyword("like")
+        {
+            Ok(())
+        } else {
+            self.cursor.unexpected("a relation operator")
+        }
+    }
+
+    // A tuple of values for a multi-column relation: either a single marker
+    // or a parenthesized list of terms.
+    fn tuple_term(&mut self, tuple_name: &str, columns: &[&'s Column]) -> ParseResult<()> {
+        if self.cursor.peek() == Some(&Token::Marker) {
+            let tuple_type = CqlType::Tuple(columns.iter().map(|c| c.typ.clone()).collect());
+            return self.term(tuple_name, tuple_type);
+        }
+        self.cursor.expect_symbol("(")?;
+        for (i, column) in columns.iter().enumerate() {
+            if i > 0 {
+                self.cursor.expect_symbol(",")?;
+            }
+            self.term(column.name.clone(), column.typ.clone())?;
+        }
+        self.cursor.expect_symbol(")")
+    }
+
+    // Parses the index in `col[index]`, and returns the type of the element.
+    fn element_access(&mut self, column: &'s Column) -> ParseResult<CqlType> {
+        match &column.typ {
+            CqlType::Map { key, value, .. } => {
+                self.term(format!("key({})", column.name), (**key).clone())?;
+                Ok((**value).clone())
+            }
+            CqlType::List { element, .. } => {
+                self.term(format!("idx({})", column.name), CqlType::int())?;
+                Ok((**element).clone())
+            }
+            _ => self
+                .cursor
+                .error(format!("column `{}` is not a list or a map", column.name)),
+        }
+    }
+
+    // Parses `IF EXISTS`, `IF NOT EXISTS` (if allowed) or `IF conditions`, if present.
+    fn conditions(&mut self, table: &'s Table, allow_not_exists: bool) -> ParseResult<()> {
+        if !self.cursor.eat_keyword("if") {
+            return Ok(());
+        }
+        if self.cursor.eat_keyword("exists") {
+            return Ok(());
+        }
+        if allow_not_exists && self.cursor.eat_keywords(&["not", "exists"]) {
+            return Ok(());
+        }
+        self.relations(table)
+    }
+
+    fn select(&mut self) -> ParseResult<Vec<ResultColumn>> {
+        if self.cursor.peek_keyword("json") {
+            return self.cursor.error("`SELECT JSON` is not supported");
+        }
+        self.cursor.eat_keyword("distinct");
+
+        let mut selectors = Vec::new();
+        loop {
+            let offset = self.cursor.offset();
+            selectors.push((self.selector()?, offset));
+            if self.cursor.eat_keyword("as") {
+                self.cursor.identifier()?;
+            }
+            if !self.cursor.eat_symbol(",") {
+                break;
+            }
+        }
+
+        self.cursor.expect_keyword("from")?;
+        let table = self.table()?;
+
+        let mut result = Vec::new();
+        for (selector, offset) in selectors {
+            let column = |name: &str| resolve_column(table, name).map_err(|err| (offset, err));
+            match selector {
+                Selector::Wildcard => {
+                    result.extend(table.wildcard_columns().into_iter().map(|c| ResultColumn {
+                        typ: c.typ.clone(),
+                        nullable: is_nullable(c),
+                    }))
+                }
+                Selector::Column(name) => {
+                    let column = column(&name)?;
+                    result.push(ResultColumn {
+                        typ: column.typ.clone(),
+                        nullable: is_nullable(column),
+                    });
+                }
+                Selector::Writetime(name) => {
+                    column(&name)?;
+                    result.push(ResultColumn {
+                        typ: CqlType::bigint(),
+                        nullable: true,
+                    });
+                }
+                Selector::Ttl(name) => {
+                    column(&name)?;
+                    result.push(ResultColumn {
+                        typ: CqlType::int(),
+                        nullable: true,
+                    });
+                }
+                Selector::Count | Selector::Token => result.push(ResultColumn {
+                    typ: CqlType::bigint(),
+                    nullable: false,
+                }),
+                Selector::MinMax(name) => result.push(ResultColumn {
+                    typ: column(&name)?.typ.clone(),
+                    nullable: true,
+                }),
+            }
+        }
+
+        if self.cursor.eat_keyword("where") {
+            self.relations(table)?;
+        }
+        if self.cursor.eat_keywords(&["group", "by"]) {
+            loop {
+                self.column(table)?;
+                if !self.cursor.eat_symbol(",") {
+                    break;
+                }
+            }
+        }
+        if self.cursor.eat_keywords(&["order", "by"]) {
+            loop {
+                self.column(table)?;
+                let _ = self.cursor.eat_keyword("asc") || self.cursor.eat_keyword("desc");
+                if !self.cursor.eat_symbol(",") {
+                    break;
+                }
+            }
+        }
+        if self.cursor.eat_keywords(&["per", "partition", "limit"]) {
+            self.term("[per_partition_limit]", CqlType::int())?;
+        }
+        if self.cursor.eat_keyword("limit") {
+            self.term("[limit]", CqlType::int())?;
+        }
+        self.cursor.eat_keywords(&["allow", "filtering"]);
+        self.cursor.eat_keywords(&["bypass", "cache"]);
+        self.using()?;
+
+        Ok(result)
+    }
+
+    fn selector(&mut self) -> ParseResult<Selector> {
+        if self.cursor.eat_symbol("*") {
+            return Ok(Selector::Wildcard);
+        }
+        let offset = self.cursor.offset();
+        let name = self.cursor.identifier()?;
+        if !self.cursor.eat_symbol("(") {
+            return Ok(Selector::Column(name));
+        }
+
+        let selector = match name.as_str() {
+            "writetime" => Selector::Writetime(self.cursor.identifier()?),
+            "ttl" => Selector::Ttl(self.cursor.identifier()?),
+            "min" | "max" => Selector::MinMax(self.cursor.identifier()?),
+            "count" => {
+                match self.cursor.next() {
+                    Some(Token::Symbol("*")) => {}
+                    Some(Token::Number(n)) if n == "1" => {}
+                    _ => return Err((offset, "only `count(*)` is supported".to_owned())),
+                }
+                Selector::Count
+            }
+            "token" => {
+                loop {
+                    self.cursor.identifier()?;
+                    if !self.cursor.eat_symbol(",") {
+                        break;
+                    }
+                }
+                Selector::Token
+            }
+            _ => {
+                return Err((
+                    offset,
+                    format!("selector function `{name}` is not supported"),
+                ))
+            }
+        };
+        self.cursor.expect_symbol(")")?;
+        Ok(selector)
+    }
+
+    fn insert(&mut self) -> ParseResult<()> {
+        self.cursor.expect_keyword("into")?;
+        let table = self.table()?;
+        if self.cursor.peek_keyword("json") {
+            return self.cursor.error("`INSERT JSON` is not supported");
+        }
+
+        let mut columns = Vec::new();
+        self.cursor.expect_symbol("(")?;
+        loop {
+            columns.push(self.column(table)?);
+            if !self.cursor.eat_symbol(",") {
+                break;
+            }
+        }
+        self.cursor.expect_symbol(")")?;
+
+        self.cursor.expect_keyword("values")?;
+        self.cursor.expect_symbol("(")?;
+        for (i, column) in columns.iter().enumerate() {
+            if i > 0 && !self.cursor.eat_symbol(",") {
+                return self.cursor.error(format!(
+                    "expected {} values, as many as the listed columns",
+                    columns.len()
+                ));
+            }
+            self.term(column.name.clone(), column.typ.clone())?;
+        }
+        if !self.cursor.eat_symbol(")") {
+            return self.cursor.error(format!(
+                "expected {} values, as many as the listed columns",
+                columns.len()
+            ));
+        }
+
+        self.cursor.eat_keywords(&["if", "not", "exists"]);
+        self.using()
+    }
+
+    fn update(&mut self) -> ParseResult<()> {
+        let table = self.table()?;
+        self.using()?;
+
+        self.cursor.expect_keyword("set")?;
+        loop {
+            self.assignment(table)?;
+            if !self.cursor.eat_symbol(",") {
+                break;
+            }
+        }
+
+        self.cursor.expect_keyword("where")?;
+        self.relations(table)?;
+        self.conditions(table, false)
+    }
+
+    fn assignment(&mut self, table: &'s Table) -> ParseResult<()> {
+        let column = self.column(table)?;
+
+        // m[?] = ?
+        if self.cursor.eat_symbol("[") {
+            let element_type = self.element_access(column)?;
+            self.cursor.expect_symbol("]")?;
+            self.cursor.expect_symbol("=")?;
+            return self.term(column.name.clone(), element_type);
+        }
+
+        // udt.field = ?
+        if self.cursor.eat_symbol(".") {
+            let offset = self.cursor.offset();
+            let field = self.cursor.identifier()?;
+            let field_type = udt_field_type(column, &field).map_err(|err| (offset, err))?;
+            self.cursor.expect_symbol("=")?;
+            return self.term(format!("{}.{}", column.name, field), field_type);
+        }
+
+        self.cursor.expect_symbol("=")?;
+
+        // c = c + ?, c = c - ?
+        let is_self_reference = match self.cursor.peek() {
+            Some(Token::Ident(ident)) => ident.to_ascii_lowercase() == column.name,
+            Some(Token::QuotedIdent(ident)) => *ident == column.name,
+            _ => false,
+        };
+        if is_self_reference && matches!(self.cursor.peek_nth(1), Some(Token::Symbol("+" | "-"))) {
+            self.cursor.next();
+            let removing = self.cursor.next() == Some(&Token::Symbol("-"));
+            let typ = match &column.typ {
+                // Removing keys from a map takes a set of the keys.
+                CqlType::Map { key, .. } if removing => CqlType::set_of((**key).clone()),
+                typ => typ.clone(),
+            };
+            return self.term(column.name.clone(), typ);
+        }
+
+        self.term(column.name.clone(), column.typ.clone())?;
+
+        // c = ? + c (prepending to a list)
+        if self.cursor.eat_symbol("+") {
+            let offset = self.cursor.offset();
+            let other = self.cursor.identifier()?;
+            if other != column.name {
+                return Err((
+                    offset,
+                    format!("expected `{}` on the right side of `+`", column.name),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn delete(&mut self) -> ParseResult<()> {
+        // The deleted columns are listed before the table name, so they are
+        // resolved only after the table is known.
+        let selection_start = self.cursor.clone();
+        while !self.cursor.is_at_end() && !self.cursor.peek_keyword("from") {
+            self.cursor.next();
+        }
+        self.cursor.expect_keyword("from")?;
+        let table = self.table()?;
+        let rest = std::mem::replace(self.cursor, selection_start);
+
+        if !self.cursor.peek_keyword("from") {
+            loop {
+                self.deletion(table)?;
+                if !self.cursor.eat_symbol(",") {
+                    break;
+                }
+            }
+        }
+        *self.cursor = rest;
+
+        self.using()?;
+        self.cursor.expect_keyword("where")?;
+        self.relations(table)?;
+        self.conditions(table, false)
+    }
+
+    // Parses a deleted column or element: `col`, `col[?]` or `col.field`.
+    fn deletion(&mut self, table: &'s Table) -> ParseResult<()> {
+        let column = self.column(table)?;
+        if self.cursor.eat_symbol("[") {
+            self.element_access(column)?;
+            return self.cursor.expect_symbol("]");
+        }
+        if self.cursor.eat_symbol(".") {
+            let offset = self.cursor.offset();
+            let field = self.cursor.identifier()?;
+            udt_field_type(column, &field).map_err(|err| (offset, err))?;
+        }
+        Ok(())
+    }
+}
+
+fn resolve_column<'s>(table: &'s Table, name: &str) -> Result<&'s Column, String> {
+    table
+        .column(name)
+        .ok_or_else(|| format!("no column `{}` in table `{}`", name, table.display_name()))
+}
+
+fn is_nullable(column: &Column) -> bool {
+    matches!(column.kind, ColumnKind::Static | ColumnKind::Regular)
+}
+
+fn udt_field_type(column: &Column, field: &str) -> Result<CqlType, String> {
+    match &column.typ {
+        CqlType::Udt { definition, .. } => definition
+            .fields
+            .iter()
+            .find(|(name, _)| name == field)
+            .map(|(_, typ)| typ.clone())
+            .ok_or_else(|| format!("no field `{}` in type `{}`", field, definition.name)),
+        _ => Err(format!(
+            "column `{}` is not a user defined type",
+            column.name
+        )),
+    }
+}
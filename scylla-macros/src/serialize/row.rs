@@ -22,6 +22,16 @@ struct Attributes {
     // This annotation only works if `enforce_order` flavor is specified.
     #[darling(default)]
     skip_name_checks: bool,
+
+    // Used by derive(Table) only. Ignored here.
+    #[darling(default)]
+    #[darling(rename = "table")]
+    _table: Option<String>,
+
+    // Used by derive(Table) only. Ignored here.
+    #[darling(default)]
+    #[darling(rename = "keyspace")]
+    _keyspace: Option<String>,
 }
 
 impl Attributes {
@@ -46,6 +56,17 @@ impl Field {
             None => self.ident.to_string(),
         }
     }
+
+    // Wraps a reference to the field's value so that it is serialized
+    // with the custom function, if one was provided with `with`.
+    fn serializable_value(&self, crate_path: &syn::Path, value: syn::Expr) -> syn::Expr {
+        match &self.attrs.with {
+            Some(with) => parse_quote! {
+                &#crate_path::ser::value::SerializeWith(#value, #with::serialize)
+            },
+            None => value,
+        }
+    }
 }
 
 #[derive(FromAttributes)]
@@ -65,6 +86,21 @@ struct FieldAttributes {
     // All other attributes are ignored.
     #[darling(default)]
     skip: bool,
+
+    // If set, then the field is serialized with the `serialize` function
+    // from the given module instead of its `SerializeValue` impl.
+    #[darling(default)]
+    with: Option<syn::Path>,
+
+    // Used by derive(Table) only. Ignored here.
+    #[darling(default)]
+    #[darling(rename = "partition_key")]
+    _partition_key: bool,
+
+    // Used by derive(Table) only. Ignored here.
+    #[darling(default)]
+    #[darling(rename = "clustering_key")]
+    _clustering_key: bool,
 }
 
 struct Context {
@@ -163,6 +199,19 @@ impl Context {
             });
         errors.extend(rename_flatten_errors);
 
+        // Check that no custom serializers are attempted on flattened fields
+        let with_flatten_errors = self
+            .fields
+            .iter()
+            .filter(|f| f.attrs.flatten && f.attrs.with.is_some())
+            .map(|f| {
+                darling::Error::custom(
+                    "`with` and `flatten` annotations do not make sense together",
+                )
+                .with_span(&f.ident)
+            });
+        errors.extend(with_flatten_errors);
+
         // Check for name collisions
         let mut used_names = HashMap::<String, &Field>::new();
         for field in self.fields.iter() {
@@ -264,6 +313,13 @@ impl Generator for ColumnSortingGenerator<'_> {
             .map(|ident| format_ident!("__visited_flag_{}", ident))
             .collect();
         let nonflattened_types: Vec<_> = nonflattened.iter().map(|f| &f.typ).collect();
+        let nonflattened_values: Vec<_> = nonflattened
+            .iter()
+            .map(|f| {
+                let field = &f.ident;
+                f.serializable_value(&crate_path, parse_quote!(self.#field))
+            })
+            .collect();
 
         let partial_struct: syn::ItemStruct = parse_quote! {
             pub struct #partial_struct_name #partial_generics {
@@ -288,7 +344,7 @@ impl Generator for ColumnSortingGenerator<'_> {
                     // first check if the spec name matches a non-flattened column
                     #(#nonflattened_columns => {
                         #crate_path::ser::row::serialize_column::<#struct_name #ty_generics>(
-                            &self.#nonflattened_fields, spec, writer,
+                            &#nonflattened_values, spec, writer,
                         )?;
                         if !self.#nonflattened_visited_flag_names {
                             self.#nonflattened_visited_flag_names = true;
@@ -435,8 +491,9 @@ impl Generator for ColumnOrderedGenerator<'_> {
             } else {
                 let column = f.column_name();
                 let enforce_name = !self.ctx.attributes.skip_name_checks;
+                let value = f.serializable_value(&crate_path, parse_quote!(&self.#field));
                 syn::parse_quote! {
-                    #crate_path::ser::row::NextColumnSerializer::serialize::<Self, #enforce_name>(columns, #column, &self.#field, writer)?;
+                    #crate_path::ser::row::NextColumnSerializer::serialize::<Self, #enforce_name>(columns, #column, #value, writer)?;
                 }
             }
         });
@@ -4,6 +4,7 @@ use darling::FromAttributes;
 use proc_macro::TokenStream;
 use syn::parse_quote;
 
+use crate::enums::{EnumDesc, TransparentField};
 use crate::Flavor;
 
 #[derive(FromAttributes)]
@@ -30,6 +31,11 @@ struct Attributes {
     // the DB will interpret them as NULLs anyway.
     #[darling(default)]
     forbid_excess_udt_fields: bool,
+
+    // If true, then the struct must have exactly one field
+    // and it is serialized as if it was that field.
+    #[darling(default)]
+    transparent: bool,
 }
 
 impl Attributes {
@@ -60,6 +66,17 @@ impl Field {
     fn is_required(&self) -> bool {
         !self.attrs.skip && !self.attrs.ignore_missing
     }
+
+    // The function which serializes this field.
+    fn serialize_fn(&self, crate_path: &syn::Path) -> syn::Expr {
+        match &self.attrs.with {
+            Some(with) => parse_quote!(#with::serialize),
+            None => {
+                let typ = &self.typ;
+                parse_quote!(<#typ as #crate_path::SerializeValue>::serialize)
+            }
+        }
+    }
 }
 
 #[derive(FromAttributes)]
@@ -80,6 +97,11 @@ struct FieldAttributes {
     #[darling(rename = "allow_missing")]
     ignore_missing: bool,
 
+    // If set, then the field is serialized with the `serialize` function
+    // from the given module instead of its `SerializeValue` impl.
+    #[darling(default)]
+    with: Option<syn::Path>,
+
     // Used for deserialization only. Ignored in serialization.
     #[darling(default)]
     #[darling(rename = "default_when_null")]
@@ -93,12 +115,26 @@ struct Context {
 
 pub(crate) fn derive_serialize_value(
     tokens_input: TokenStream,
-) -> Result<syn::ItemImpl, syn::Error> {
+) -> Result<proc_macro2::TokenStream, syn::Error> {
     let input: syn::DeriveInput = syn::parse(tokens_input)?;
+    if let syn::Data::Enum(data) = &input.data {
+        let desc = EnumDesc::new(&input, data, "SerializeValue")?;
+        let serialize_impl = generate_enum_impl(&desc);
+        let serialize_as_impls = generate_enum_serialize_as_impls(&desc);
+        return Ok(quote::quote! {
+            #serialize_impl
+            #(#serialize_as_impls)*
+        });
+    }
+
     let struct_name = input.ident.clone();
+    let attributes = Attributes::from_attributes(&input.attrs)?;
+    if attributes.transparent {
+        return generate_transparent_impl(&input, &attributes);
+    }
+
     let named_fields = crate::parser::parse_named_fields(&input, "SerializeValue")?;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
-    let attributes = Attributes::from_attributes(&input.attrs)?;
 
     let crate_path = attributes.crate_path();
     let implemented_trait: syn::Path = parse_quote!(#crate_path::SerializeValue);
@@ -127,15 +163,139 @@ pub(crate) fn derive_serialize_value(
 
     let serialize_item = gen.generate_serialize();
 
-    let res = parse_quote! {
+    let res = quote::quote! {
         #[automatically_derived]
         impl #impl_generics #implemented_trait for #struct_name #ty_generics #where_clause {
             #serialize_item
         }
+
+        #[automatically_derived]
+        impl #impl_generics #crate_path::SerializeAs<#crate_path::cql_type::Udt>
+            for #struct_name #ty_generics #where_clause {}
     };
     Ok(res)
 }
 
+// An enum can be bound to the same CQL types that it can be serialized to.
+fn generate_enum_serialize_as_impls(desc: &EnumDesc) -> Vec<syn::ItemImpl> {
+    let crate_path = desc.attrs.macro_internal_path();
+    let enum_name = &desc.name;
+    desc.attrs
+        .repr
+        .native_types()
+        .into_iter()
+        .map(|native_type| {
+            parse_quote! {
+                #[automatically_derived]
+                impl #crate_path::SerializeAs<#crate_path::cql_type::#native_type> for #enum_name {}
+            }
+        })
+        .collect()
+}
+
+fn generate_enum_impl(desc: &EnumDesc) -> syn::ItemImpl {
+    let crate_path = desc.attrs.macro_internal_path();
+    let enum_name = &desc.name;
+    let repr_type = desc.attrs.repr.rust_type();
+    let accepted_types = desc.accepted_types_pattern();
+    let expected_types = desc.expected_types();
+
+    let variant_idents = desc.variants.iter().map(|v| &v.ident);
+    let variant_values = desc.variants.iter().map(|v| &v.value);
+    let catch_all_arm: Option<syn::Arm> = desc.catch_all.as_ref().map(|c| {
+        let ident = &c.ident;
+        let ty = &c.ty;
+        parse_quote! {
+            Self::#ident(value) => {
+                return <#ty as #crate_path::SerializeValue>::serialize(value, typ, writer);
+            }
+        }
+    });
+
+    parse_quote! {
+        #[automatically_derived]
+        impl #crate_path::SerializeValue for #enum_name {
+            fn serialize<'b>(
+                &self,
+                typ: &#crate_path::ColumnType,
+                writer: #crate_path::CellWriter<'b>,
+            ) -> ::std::result::Result<#crate_path::WrittenCellProof<'b>, #crate_path::SerializationError> {
+                match typ {
+                    #accepted_types => {}
+                    _ => return ::std::result::Result::Err(#crate_path::SerializationError::new(
+                        #crate_path::BuiltinTypeTypeCheckError {
+                            rust_name: ::std::any::type_name::<Self>(),
+                            got: <_ as ::std::clone::Clone>::clone(typ).into_owned(),
+                            kind: #crate_path::BuiltinTypeTypeCheckErrorKind::MismatchedType {
+                                expected: #expected_types,
+                            },
+                        }
+                    )),
+                }
+                let value: #repr_type = match self {
+                    #(Self::#variant_idents => #variant_values,)*
+                    #catch_all_arm
+                };
+                <#repr_type as #crate_path::SerializeValue>::serialize(&value, typ, writer)
+            }
+        }
+    }
+}
+
+fn generate_transparent_impl(
+    input: &syn::DeriveInput,
+    attributes: &Attributes,
+) -> Result<proc_macro2::TokenStream, syn::Error> {
+    if attributes.skip_name_checks || attributes.forbid_excess_udt_fields {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "the `transparent` attribute cannot be combined with UDT-specific attributes",
+        ));
+    }
+    let field = TransparentField::new(input, "SerializeValue")?;
+
+    let crate_path = attributes.crate_path();
+    let struct_name = &input.ident;
+    let member = &field.member;
+    let ty = &field.ty;
+
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(#ty: #crate_path::SerializeValue));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // The wrapper can be bound to any CQL type that the wrapped value can be bound to.
+    let mut serialize_as_generics = input.generics.clone();
+    serialize_as_generics
+        .params
+        .push(parse_quote!(__SerializeAsCqlType));
+    serialize_as_generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(#ty: #crate_path::SerializeAs<__SerializeAsCqlType>));
+    let (serialize_as_impl_generics, _, serialize_as_where_clause) =
+        serialize_as_generics.split_for_impl();
+
+    Ok(quote::quote! {
+        #[automatically_derived]
+        impl #impl_generics #crate_path::SerializeValue for #struct_name #ty_generics #where_clause {
+            fn serialize<'b>(
+                &self,
+                typ: &#crate_path::ColumnType,
+                writer: #crate_path::CellWriter<'b>,
+            ) -> ::std::result::Result<#crate_path::WrittenCellProof<'b>, #crate_path::SerializationError> {
+                <#ty as #crate_path::SerializeValue>::serialize(&self.#member, typ, writer)
+            }
+        }
+
+        #[automatically_derived]
+        impl #serialize_as_impl_generics #crate_path::SerializeAs<__SerializeAsCqlType>
+            for #struct_name #ty_generics #serialize_as_where_clause {}
+    })
+}
+
 impl Context {
     fn validate(&self, struct_ident: &syn::Ident) -> Result<(), syn::Error> {
         let mut errors = darling::Error::accumulator();
@@ -280,7 +440,12 @@ impl Generator for FieldSortingGenerator<'_> {
         let rust_field_ignore_missing_flags =
             self.ctx.fields.iter().map(|f| f.attrs.ignore_missing);
         let udt_field_names = rust_field_names.clone(); // For now, it's the same
-        let field_types = self.ctx.fields.iter().map(|f| &f.typ).collect::<Vec<_>>();
+        let serialize_fns = self
+            .ctx
+            .fields
+            .iter()
+            .map(|f| f.serialize_fn(&crate_path))
+            .collect::<Vec<_>>();
 
         let missing_rust_field_expression: syn::Expr =
             if self.ctx.attributes.forbid_excess_udt_fields {
@@ -383,7 +548,7 @@ impl Generator for FieldSortingGenerator<'_> {
                         #udt_field_names => {
                             #serialize_missing_nulls_statement
                             let sub_builder = #crate_path::CellValueBuilder::make_sub_writer(&mut builder);
-                            match <#field_types as #crate_path::SerializeValue>::serialize(&self.#rust_field_idents, field_type, sub_builder) {
+                            match #serialize_fns(&self.#rust_field_idents, field_type, sub_builder) {
                                 ::std::result::Result::Ok(_proof) => {}
                                 ::std::result::Result::Err(err) => {
                                     return ::std::result::Result::Err(mk_ser_err(
@@ -480,7 +645,7 @@ impl Generator for FieldOrderedGenerator<'_> {
             let rust_field_ident = &field.ident;
             let rust_field_name = field.field_name();
             let field_can_be_ignored = field.attrs.ignore_missing;
-            let typ = &field.typ;
+            let serialize_fn = field.serialize_fn(&crate_path);
             let name_check_expression: syn::Expr = if !self.ctx.attributes.skip_name_checks {
                 parse_quote! { field_name == #rust_field_name }
             } else {
@@ -494,7 +659,7 @@ impl Generator for FieldOrderedGenerator<'_> {
                             ::std::iter::Iterator::next(&mut field_iter);
 
                             let sub_builder = #crate_path::CellValueBuilder::make_sub_writer(&mut builder);
-                            match <#typ as #crate_path::SerializeValue>::serialize(&self.#rust_field_ident, typ, sub_builder) {
+                            match #serialize_fn(&self.#rust_field_ident, typ, sub_builder) {
                                 ::std::result::Result::Ok(_proof) => {},
                                 ::std::result::Result::Err(err) => {
                                     return ::std::result::Result::Err(mk_ser_err(
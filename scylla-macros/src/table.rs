@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+
+use darling::FromAttributes;
+use proc_macro::TokenStream;
+use syn::parse_quote;
+
+use crate::Flavor;
+
+#[derive(FromAttributes)]
+#[darling(attributes(scylla))]
+struct Attributes {
+    #[darling(rename = "crate")]
+    crate_path: Option<syn::Path>,
+
+    // Name of the table.
+    table: String,
+
+    // If set, then the statements refer to the table in this particular
+    // keyspace instead of the session's current keyspace.
+    #[darling(default)]
+    keyspace: Option<String>,
+
+    // Used for (de)serialization only. Ignored here.
+    #[darling(default)]
+    #[darling(rename = "flavor")]
+    _flavor: Flavor,
+
+    // Used for (de)serialization only. Ignored here.
+    #[darling(default)]
+    #[darling(rename = "skip_name_checks")]
+    _skip_name_checks: bool,
+}
+
+impl Attributes {
+    fn crate_path(&self) -> syn::Path {
+        self.crate_path
+            .as_ref()
+            .map(|p| parse_quote!(#p::_macro_internal))
+            .unwrap_or_else(|| parse_quote!(::scylla::_macro_internal))
+    }
+}
+
+struct Field {
+    ident: syn::Ident,
+    typ: syn::Type,
+    attrs: FieldAttributes,
+}
+
+impl Field {
+    fn column_name(&self) -> String {
+        match &self.attrs.rename {
+            Some(name) => name.clone(),
+            None => self.ident.to_string(),
+        }
+    }
+
+    // Returns whether the field represents a column of the table, as opposed
+    // to a skipped field or metadata of some other column.
+    fn is_column(&self) -> bool {
+        !self.attrs.skip && self.attrs.writetime_of.is_none() && self.attrs.ttl_of.is_none()
+    }
+
+    fn is_key(&self) -> bool {
+        self.attrs.partition_key || self.attrs.clustering_key
+    }
+}
+
+#[derive(FromAttributes)]
+#[darling(attributes(scylla))]
+struct FieldAttributes {
+    // If set, then the field represents the column with this particular name
+    // instead of the Rust field name.
+    rename: Option<String>,
+
+    // If true, then the field is not a column of the table.
+    #[darling(default)]
+    skip: bool,
+
+    // If true, then the column is a part of the partition key. Partition key
+    // columns are ordered in the same way as the fields.
+    #[darling(default)]
+    partition_key: bool,
+
+    // If true, then the column is a part of the clustering key. Clustering key
+    // columns are ordered in the same way as the fields.
+    #[darling(default)]
+    clustering_key: bool,
+
+    // If set, then the column is serialized with the `serialize` function
+    // from the given module instead of its `SerializeValue` impl.
+    #[darling(default)]
+    with: Option<syn::Path>,
+
+    // Not supported, accepted only to produce a meaningful error.
+    #[darling(default)]
+    flatten: bool,
+
+    // If set, then the field holds the write timestamp of the given column.
+    // Such fields are only read, not written.
+    #[darling(default)]
+    writetime_of: Option<String>,
+
+    // If set, then the field holds the TTL of the given column.
+    // Such fields are only read, not written.
+    #[darling(default)]
+    ttl_of: Option<String>,
+}
+
+struct Context {
+    attributes: Attributes,
+    fields: Vec<Field>,
+}
+
+pub(crate) fn derive_table(tokens_input: TokenStream) -> Result<syn::ItemImpl, syn::Error> {
+    let input: syn::DeriveInput = syn::parse(tokens_input)?;
+    let struct_name = input.ident.clone();
+    let named_fields = crate::parser::parse_named_fields(&input, "Table")?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let attributes = Attributes::from_attributes(&input.attrs)?;
+
+    let fields = named_fields
+        .named
+        .iter()
+        .map(|f| {
+            FieldAttributes::from_attributes(&f.attrs).map(|attrs| Field {
+                ident: f.ident.clone().unwrap(),
+                typ: f.ty.clone(),
+                attrs,
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    let ctx = Context { attributes, fields };
+    ctx.validate(&struct_name)?;
+
+    let crate_path = ctx.attributes.crate_path();
+    let table = &ctx.attributes.table;
+    let keyspace = match &ctx.attributes.keyspace {
+        Some(keyspace) => quote::quote!(::std::option::Option::Some(#keyspace)),
+        None => quote::quote!(::std::option::Option::None),
+    };
+
+    let columns = ctx
+        .fields
+        .iter()
+        .filter(|f| f.is_column())
+        .collect::<Vec<_>>();
+    let column_names = columns.iter().map(|f| f.column_name());
+
+    let partition_key = columns.iter().filter(|f| f.attrs.partition_key);
+    let clustering_key = columns.iter().filter(|f| f.attrs.clustering_key);
+    let partition_key_names = partition_key.clone().map(|f| f.column_name());
+    let clustering_key_names = clustering_key.clone().map(|f| f.column_name());
+    let key_types = partition_key.chain(clustering_key).map(|f| &f.typ);
+
+    let serialize_arms = columns.iter().enumerate().map(|(index, field)| {
+        let ident = &field.ident;
+        let typ = &field.typ;
+        let serialize: syn::Expr = match &field.attrs.with {
+            Some(with) => parse_quote!(#with::serialize),
+            None => parse_quote!(<#typ as #crate_path::SerializeValue>::serialize),
+        };
+        quote::quote! {
+            #index => #serialize(&self.#ident, typ, writer),
+        }
+    });
+
+    let res = parse_quote! {
+        #[automatically_derived]
+        impl #impl_generics #crate_path::Table for #struct_name #ty_generics #where_clause {
+            type PrimaryKey = (#(#key_types,)*);
+
+            const KEYSPACE: ::std::option::Option<&'static ::std::primitive::str> = #keyspace;
+            const NAME: &'static ::std::primitive::str = #table;
+            const COLUMNS: &'static [&'static ::std::primitive::str] = &[#(#column_names),*];
+            const PARTITION_KEY: &'static [&'static ::std::primitive::str] =
+                &[#(#partition_key_names),*];
+            const CLUSTERING_KEY: &'static [&'static ::std::primitive::str] =
+                &[#(#clustering_key_names),*];
+
+            fn serialize_column<'b>(
+                &self,
+                index: ::std::primitive::usize,
+                typ: &#crate_path::ColumnType,
+                writer: #crate_path::CellWriter<'b>,
+            ) -> ::std::result::Result<#crate_path::WrittenCellProof<'b>, #crate_path::SerializationError> {
+                match index {
+                    #(#serialize_arms)*
+                    _ => ::std::panic!("column index {} is out of range", index),
+                }
+            }
+        }
+    };
+    Ok(res)
+}
+
+impl Context {
+    fn validate(&self, struct_ident: &syn::Ident) -> Result<(), syn::Error> {
+        let mut errors = darling::Error::accumulator();
+
+        for field in self.fields.iter() {
+            if field.attrs.flatten {
+                errors.push(
+                    darling::Error::custom("`flatten` is not supported by derive(Table)")
+                        .with_span(&field.ident),
+                );
+            }
+
+            if field.attrs.partition_key && field.attrs.clustering_key {
+                errors.push(
+                    darling::Error::custom(
+                        "a column cannot be a part of both the partition key and the clustering key",
+                    )
+                    .with_span(&field.ident),
+                );
+            }
+
+            if field.is_key() && !field.is_column() {
+                errors.push(
+                    darling::Error::custom(
+                        "primary key annotations cannot be combined with `skip`, `writetime_of` or `ttl_of`",
+                    )
+                    .with_span(&field.ident),
+                );
+            }
+
+            // Primary key values are passed as a tuple of the field types,
+            // so there is no place to plug a custom serializer in.
+            if field.is_key() && field.attrs.with.is_some() {
+                errors.push(
+                    darling::Error::custom(
+                        "`with` cannot be used on partition key or clustering key columns",
+                    )
+                    .with_span(&field.ident),
+                );
+            }
+        }
+
+        if !self.fields.iter().any(|f| f.attrs.partition_key) {
+            errors.push(
+                darling::Error::custom(
+                    "derive(Table) requires at least one field annotated with `partition_key`",
+                )
+                .with_span(struct_ident),
+            );
+        }
+
+        // Check for name collisions
+        let mut used_names = HashMap::<String, &Field>::new();
+        for field in self.fields.iter().filter(|f| f.is_column()) {
+            let column_name = field.column_name();
+            if let Some(other_field) = used_names.get(&column_name) {
+                let other_field_ident = &other_field.ident;
+                let msg = format!("the column name `{column_name}` used by this struct field is already used by field `{other_field_ident}`");
+                let err = darling::Error::custom(msg).with_span(&field.ident);
+                errors.push(err);
+            } else {
+                used_names.insert(column_name, field);
+            }
+        }
+
+        errors.finish()?;
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
 use std::net::SocketAddr;
 
 use scylla_cql::frame::frame_errors::{
-    FrameBodyExtensionsParseError, FrameHeaderParseError, LowLevelDeserializationError,
+    CqlResponseSerializationError, FrameBodyExtensionsParseError, FrameHeaderParseError,
+    LowLevelDeserializationError,
 };
 use thiserror::Error;
 
@@ -13,6 +14,14 @@ pub enum ReadFrameError {
     Compression(#[from] FrameBodyExtensionsParseError),
 }
 
+#[derive(Debug, Error)]
+pub enum ForgeFrameError {
+    #[error("Protocol version {0} is not supported by the proxy")]
+    UnsupportedProtocolVersion(u8),
+    #[error("Failed to serialize response: {0}")]
+    ResponseSerialization(#[from] CqlResponseSerializationError),
+}
+
 #[derive(Debug, Error)]
 pub enum DoorkeeperError {
     #[error("Listen on {0} failed with {1}")]
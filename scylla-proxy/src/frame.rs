@@ -1,17 +1,19 @@
 use std::collections::HashMap;
 
-use bytes::{Buf, BufMut, Bytes, BytesMut};
+use bytes::{Buf, BufMut, Bytes};
 use scylla_cql::frame::frame_errors::FrameHeaderParseError;
 use scylla_cql::frame::protocol_features::ProtocolFeatures;
 pub use scylla_cql::frame::request::RequestOpcode;
 use scylla_cql::frame::request::{Request, RequestDeserializationError};
+use scylla_cql::frame::response::error::{DbError, ErrorSerializationError};
 pub use scylla_cql::frame::response::ResponseOpcode;
-use scylla_cql::frame::{response::error::DbError, types};
+use scylla_cql::frame::response::{Error, Response, Supported};
+use scylla_cql::frame::ProtocolVersion;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 
 use tracing::warn;
 
-use crate::errors::ReadFrameError;
+use crate::errors::{ForgeFrameError, ReadFrameError};
 use crate::proxy::CompressionReader;
 
 const HEADER_SIZE: usize = 9;
@@ -74,9 +76,59 @@ impl RequestFrame {
         .await
     }
 
+    /// Deserializes the request, assuming that no protocol extensions
+    /// were negotiated on the connection.
     pub fn deserialize(&self) -> Result<Request, RequestDeserializationError> {
-        Request::deserialize(&mut &self.body[..], self.opcode)
+        self.deserialize_with_features(&ProtocolFeatures::default())
     }
+
+    /// Deserializes the request sent over a connection which negotiated
+    /// the given protocol extensions.
+    pub fn deserialize_with_features(
+        &self,
+        features: &ProtocolFeatures,
+    ) -> Result<Request<'_>, RequestDeserializationError> {
+        let version = protocol_version(self.params)
+            .map_err(RequestDeserializationError::UnsupportedProtocolVersion)?;
+        Request::deserialize(&mut &self.body[..], self.opcode, version, features)
+    }
+
+    /// If the frame uses a protocol version with segment framing, which the proxy
+    /// does not implement, returns the response of a node which does not support
+    /// that version: a protocol error sent in the highest supported version.
+    ///
+    /// Frames in unknown protocol versions are not rejected, so that they can
+    /// be passed through the proxy as they are.
+    pub(crate) fn reject_unsupported_version(&self) -> Option<ResponseFrame> {
+        let version = ProtocolVersion::try_from(self.params.version & 0x7F)
+            .ok()
+            .filter(|version| *version > HIGHEST_SUPPORTED_VERSION)?;
+        let params = FrameParams {
+            version: HIGHEST_SUPPORTED_VERSION.as_u8(),
+            ..self.params
+        };
+        let msg = format!(
+            "Invalid or unsupported protocol version: {}",
+            version.as_u8()
+        );
+        let frame = ResponseFrame::forged_error(params, DbError::ProtocolError, Some(&msg))
+            .expect("a protocol error should be serializable");
+        Some(frame)
+    }
+}
+
+/// The highest protocol version supported by the proxy. Newer versions
+/// use segment framing, which the proxy does not implement.
+const HIGHEST_SUPPORTED_VERSION: ProtocolVersion = ProtocolVersion::V4;
+
+/// Returns the protocol version of the frame, or the raw version number
+/// if it is unknown or newer than the versions supported by the proxy.
+fn protocol_version(params: FrameParams) -> Result<ProtocolVersion, u8> {
+    let version = params.version & 0x7F;
+    ProtocolVersion::try_from(version)
+        .ok()
+        .filter(|version| *version <= HIGHEST_SUPPORTED_VERSION)
+        .ok_or(version)
 }
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ResponseFrame {
@@ -86,47 +138,108 @@ pub struct ResponseFrame {
 }
 
 impl ResponseFrame {
+    /// Creates a response frame carrying the given response,
+    /// serialized according to the protocol version of the request.
+    pub fn forged(
+        request_params: FrameParams,
+        response: &Response,
+        features: &ProtocolFeatures,
+    ) -> Result<Self, ForgeFrameError> {
+        let version = protocol_version(request_params)
+            .map_err(ForgeFrameError::UnsupportedProtocolVersion)?;
+        let mut buf = Vec::new();
+        response.serialize(features, version, &mut buf)?;
+
+        Ok(ResponseFrame {
+            params: request_params.for_response(),
+            opcode: response.opcode(),
+            body: buf.into(),
+        })
+    }
+
     /// Creates a response frame that signifies the given DbError type.
     /// Useful for testing server-side error handling in drivers.
+    ///
+    /// If the proxy does not support the protocol version of the request,
+    /// the error is serialized as in protocol v4. Use [ResponseFrame::try_forged_error]
+    /// to get an error instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the error is [DbError::RateLimitReached], as its code
+    /// is only known if the rate limit extension was negotiated.
     pub fn forged_error(
         request_params: FrameParams,
         error: DbError,
         msg: Option<&str>,
     ) -> Result<Self, std::num::TryFromIntError> {
-        let msg = msg.unwrap_or("Proxy-triggered error.");
-        let len_bytes = (msg.len() as u16).to_be_bytes(); // string len is a short in CQL protocol
-        let code_bytes = error.code(&ProtocolFeatures::default()).to_be_bytes(); // TODO: configurable features
-        let body_len = msg.len() + code_bytes.len() + len_bytes.len();
-        let mut buf = BytesMut::with_capacity(body_len);
-
-        buf.extend_from_slice(&code_bytes);
-        buf.extend_from_slice(&len_bytes);
-        buf.extend_from_slice(msg.as_bytes());
-
-        serialize_error_specific_fields(&mut buf, error)?;
+        let version = protocol_version(request_params).unwrap_or(HIGHEST_SUPPORTED_VERSION);
+        let error = Error {
+            error,
+            reason: msg.unwrap_or("Proxy-triggered error.").to_owned(),
+        };
+        let mut buf = Vec::new();
+        // TODO: configurable features
+        error
+            .serialize(&ProtocolFeatures::default(), version, &mut buf)
+            .map_err(|err| match err {
+                ErrorSerializationError::ReasonSerialization(err)
+                | ErrorSerializationError::FieldSerialization(err) => err,
+                err => panic!("Failed to serialize error: {}", err),
+            })?;
 
         Ok(ResponseFrame {
             params: request_params.for_response(),
             opcode: ResponseOpcode::Error,
-            body: buf.freeze(),
+            body: buf.into(),
         })
     }
 
+    /// Creates a response frame that signifies the given DbError type,
+    /// serialized according to the protocol version of the request.
+    pub fn try_forged_error(
+        request_params: FrameParams,
+        error: DbError,
+        msg: Option<&str>,
+    ) -> Result<Self, ForgeFrameError> {
+        let response = Response::Error(Error {
+            error,
+            reason: msg.unwrap_or("Proxy-triggered error.").to_owned(),
+        });
+        // TODO: configurable features
+        Self::forged(request_params, &response, &ProtocolFeatures::default())
+    }
+
     /// Creates a Supported response frame with given supported options.
     pub fn forged_supported(
         request_params: FrameParams,
         options: &HashMap<String, Vec<String>>,
     ) -> Result<Self, std::num::TryFromIntError> {
-        let mut buf = BytesMut::new();
-        types::write_string_multimap(options, &mut buf)?;
+        let mut buf = Vec::new();
+        Supported {
+            options: options.clone(),
+        }
+        .serialize(&mut buf)?;
 
         Ok(ResponseFrame {
             params: request_params.for_response(),
             opcode: ResponseOpcode::Supported,
-            body: buf.freeze(),
+            body: buf.into(),
         })
     }
 
+    /// Creates a Supported response frame with given supported options,
+    /// failing if the proxy does not support the protocol version of the request.
+    pub fn try_forged_supported(
+        request_params: FrameParams,
+        options: &HashMap<String, Vec<String>>,
+    ) -> Result<Self, ForgeFrameError> {
+        let response = Response::Supported(Supported {
+            options: options.clone(),
+        });
+        Self::forged(request_params, &response, &ProtocolFeatures::default())
+    }
+
     pub fn forged_ready(request_params: FrameParams) -> Self {
         ResponseFrame {
             params: request_params.for_response(),
@@ -151,89 +264,6 @@ impl ResponseFrame {
     }
 }
 
-fn serialize_error_specific_fields(
-    buf: &mut BytesMut,
-    error: DbError,
-) -> Result<(), std::num::TryFromIntError> {
-    match error {
-        DbError::Unavailable {
-            consistency,
-            required,
-            alive,
-        } => {
-            types::write_consistency(consistency, buf);
-            types::write_int(required, buf);
-            types::write_int(alive, buf);
-        }
-        DbError::WriteTimeout {
-            consistency,
-            received,
-            required,
-            write_type,
-        } => {
-            types::write_consistency(consistency, buf);
-            types::write_int(received, buf);
-            types::write_int(required, buf);
-            types::write_string(write_type.as_str(), buf)?;
-        }
-        DbError::ReadTimeout {
-            consistency,
-            received,
-            required,
-            data_present,
-        } => {
-            types::write_consistency(consistency, buf);
-            types::write_int(received, buf);
-            types::write_int(required, buf);
-            buf.put_u8(u8::from(data_present));
-        }
-        DbError::ReadFailure {
-            consistency,
-            received,
-            required,
-            numfailures,
-            data_present,
-        } => {
-            types::write_consistency(consistency, buf);
-            types::write_int(received, buf);
-            types::write_int(required, buf);
-            types::write_int(numfailures, buf);
-            buf.put_u8(u8::from(data_present));
-        }
-        DbError::WriteFailure {
-            consistency,
-            received,
-            required,
-            numfailures,
-            write_type,
-        } => {
-            types::write_consistency(consistency, buf);
-            types::write_int(received, buf);
-            types::write_int(required, buf);
-            types::write_int(numfailures, buf);
-            types::write_string(write_type.as_str(), buf)?;
-        }
-        DbError::FunctionFailure {
-            keyspace,
-            function,
-            arg_types,
-        } => {
-            types::write_string(keyspace.as_str(), buf)?;
-            types::write_string(function.as_str(), buf)?;
-            types::write_string_list(&arg_types, buf)?;
-        }
-        DbError::AlreadyExists { keyspace, table } => {
-            types::write_string(keyspace.as_str(), buf)?;
-            types::write_string(table.as_str(), buf)?;
-        }
-        DbError::Unprepared { statement_id } => {
-            types::write_short_bytes(statement_id.as_ref(), buf)?;
-        }
-        _ => (),
-    }
-    Ok(())
-}
-
 pub(crate) async fn write_frame(
     params: FrameParams,
     opcode: FrameOpcode,
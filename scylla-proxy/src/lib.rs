@@ -9,7 +9,7 @@ pub use actions::{
     example_db_errors, Action, Condition, Reaction, RequestReaction, RequestRule, ResponseReaction,
     ResponseRule,
 };
-pub use errors::{DoorkeeperError, ProxyError, WorkerError};
+pub use errors::{DoorkeeperError, ForgeFrameError, ProxyError, WorkerError};
 pub use frame::{RequestFrame, RequestOpcode, ResponseFrame, ResponseOpcode};
 pub use proxy::{Node, Proxy, RunningProxy, ShardAwareness};
 
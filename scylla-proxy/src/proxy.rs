@@ -880,7 +880,7 @@ mod compression {
     use scylla_cql::frame::request::{
         options, DeserializableRequest as _, RequestDeserializationError, Startup,
     };
-    use scylla_cql::frame::{compress_append, decompress, flag, Compression};
+    use scylla_cql::frame::{compress_append, decompress, flag, Compression, ProtocolVersion};
     use tracing::{error, warn};
 
     #[derive(Debug, thiserror::Error)]
@@ -914,7 +914,7 @@ mod compression {
             &self,
             mut body: &[u8],
         ) -> Result<Option<Compression>, RequestDeserializationError> {
-            let startup = Startup::deserialize(&mut body)?;
+            let startup = Startup::deserialize(&mut body, ProtocolVersion::V4)?;
             let maybe_compression = startup.options.get(options::COMPRESSION);
             let maybe_compression = maybe_compression.and_then(|compression| {
                 compression
@@ -1245,6 +1245,17 @@ impl ProxyWorker {
             'mainloop: loop {
                 match requests_rx.recv().await {
                     Some(request) => {
+                        if let Some(rejection) = request.reject_unsupported_version() {
+                            info!(
+                                "Rejected request in unsupported protocol version ({} -> {} ({})).",
+                                driver_addr,
+                                DisplayableRealAddrOption(real_addr),
+                                DisplayableShard(shard),
+                            );
+                            let _ = driver_tx.send(rejection);
+                            continue 'mainloop;
+                        }
+
                         if request.opcode == RequestOpcode::Register {
                             event_registered_flag.store(true, Ordering::Relaxed);
                         } else if request.opcode == RequestOpcode::Startup {
@@ -1491,8 +1502,9 @@ mod tests {
     use rand::RngCore;
     use scylla_cql::frame::request::options;
     use scylla_cql::frame::request::{SerializableRequest as _, Startup};
-    use scylla_cql::frame::types::write_string_multimap;
-    use scylla_cql::frame::{flag, Compression};
+    use scylla_cql::frame::response::error::DbError;
+    use scylla_cql::frame::types::{read_int, read_string, write_string_multimap};
+    use scylla_cql::frame::{flag, Compression, ProtocolVersion};
     use std::collections::HashMap;
     use std::mem;
     use std::str::FromStr;
@@ -2324,6 +2336,54 @@ mod tests {
         running_proxy.finish().await.unwrap();
     }
 
+    #[tokio::test]
+    #[ntest::timeout(1000)]
+    async fn proxy_rejects_protocol_v5() {
+        setup_tracing();
+        let node1_proxy_addr = next_local_address_with_port(9876);
+        let proxy = Proxy::new([Node::new_dry_mode(node1_proxy_addr, None)]);
+        let running_proxy = proxy.run().await.unwrap();
+
+        let params = FrameParams {
+            flags: 0,
+            version: 0x05,
+            stream: 7,
+        };
+        let opcode = FrameOpcode::Request(RequestOpcode::Options);
+
+        let mut conn = TcpStream::connect(node1_proxy_addr).await.unwrap();
+
+        write_frame(params, opcode, &[], &mut conn, &no_compression())
+            .await
+            .unwrap();
+
+        // The proxy doesn't support segment framing, so it responds
+        // like a node which only supports protocol v4.
+        let (recvd_params, recvd_opcode, recvd_body) =
+            read_frame(&mut conn, FrameType::Response, &no_compression())
+                .await
+                .unwrap();
+        assert_eq!(
+            recvd_params,
+            FrameParams {
+                version: 0x84,
+                ..params
+            }
+        );
+        assert_eq!(recvd_opcode, FrameOpcode::Response(ResponseOpcode::Error));
+        let mut body = &recvd_body[..];
+        assert_eq!(
+            read_int(&mut body).unwrap(),
+            DbError::ProtocolError.code(&Default::default())
+        );
+        assert_eq!(
+            read_string(&mut body).unwrap(),
+            "Invalid or unsupported protocol version: 5"
+        );
+
+        running_proxy.finish().await.unwrap();
+    }
+
     #[tokio::test]
     #[ntest::timeout(1000)]
     async fn dry_mode_forger_proxy_forges_response() {
@@ -2848,7 +2908,7 @@ mod tests {
                 ))
                 .collect(),
             }
-            .to_bytes()
+            .to_bytes(ProtocolVersion::V4)
             .unwrap();
 
             let sent_frame = RequestFrame {
@@ -3,12 +3,12 @@ use crate::response::query_result::QueryResult;
 use crate::response::{PagingState, PagingStateResponse};
 use crate::routing::partitioner::PartitionerName;
 use crate::statement::batch::{Batch, BatchStatement};
-use crate::statement::prepared::PreparedStatement;
+use crate::statement::prepared::{PreparedStatement, SharedResultMetadata};
 use crate::statement::unprepared::Statement;
 use bytes::Bytes;
 use dashmap::DashMap;
 use futures::future::try_join_all;
-use scylla_cql::frame::response::result::{PreparedMetadata, ResultMetadata};
+use scylla_cql::frame::response::result::PreparedMetadata;
 use scylla_cql::serialize::batch::BatchValues;
 use scylla_cql::serialize::row::SerializeRow;
 use std::collections::hash_map::RandomState;
@@ -28,10 +28,31 @@ struct RawPreparedStatementData {
     id: Bytes,
     is_confirmed_lwt: bool,
     metadata: PreparedMetadata,
-    result_metadata: Arc<ResultMetadata<'static>>,
+    // Shared with the statements created from the cache entry,
+    // so that result metadata refreshes are reflected in the cache.
+    result_metadata: SharedResultMetadata,
     partitioner_name: PartitionerName,
 }
 
+/// Key of the prepared statement cache.
+///
+/// The same statement text prepared in different keyspaces
+/// results in different prepared statements.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    contents: String,
+    keyspace: Option<String>,
+}
+
+impl CacheKey {
+    fn new(statement: &Statement) -> Self {
+        Self {
+            contents: statement.contents.clone(),
+            keyspace: statement.get_keyspace().map(ToOwned::to_owned),
+        }
+    }
+}
+
 /// Provides auto caching while executing queries
 pub struct CachingSession<S = RandomState>
 where
@@ -42,7 +63,7 @@ where
     /// If a prepared statement is added while the limit is reached, the oldest prepared statement
     /// is removed from the cache
     max_capacity: usize,
-    cache: DashMap<String, RawPreparedStatementData, S>,
+    cache: DashMap<CacheKey, RawPreparedStatementData, S>,
     use_cached_metadata: bool,
 }
 
@@ -165,6 +186,7 @@ where
     /// Uses the prepared statements cache.
     pub async fn prepare_batch(&self, batch: &Batch) -> Result<Batch, ExecutionError> {
         let mut prepared_batch = batch.clone();
+        prepared_batch.apply_keyspace_to_statements();
 
         try_join_all(
             prepared_batch
@@ -198,7 +220,8 @@ where
     ) -> Result<PreparedStatement, PrepareError> {
         let query = query.into();
 
-        if let Some(raw) = self.cache.get(&query.contents) {
+        let cache_key = CacheKey::new(&query);
+        if let Some(raw) = self.cache.get(&cache_key) {
             let page_size = query.get_validated_page_size();
             let mut stmt = PreparedStatement::new(
                 raw.id.clone(),
@@ -206,6 +229,7 @@ where
                 raw.metadata.clone(),
                 raw.result_metadata.clone(),
                 query.contents,
+                cache_key.keyspace,
                 page_size,
                 query.config,
             );
@@ -213,7 +237,6 @@ where
             stmt.set_use_cached_result_metadata(self.use_cached_metadata);
             Ok(stmt)
         } else {
-            let query_contents = query.contents.clone();
             let prepared = {
                 let mut stmt = self.session.prepare(query).await?;
                 stmt.set_use_cached_result_metadata(self.use_cached_metadata);
@@ -225,7 +248,7 @@ where
                 // Don't hold a reference into the map (that's why the to_string() is called)
                 // This is because the documentation of the remove fn tells us that it may deadlock
                 // when holding some sort of reference into the map
-                let query = self.cache.iter().next().map(|c| c.key().to_string());
+                let query = self.cache.iter().next().map(|c| c.key().clone());
 
                 // Don't inline this: https://stackoverflow.com/questions/69873846/an-owned-value-is-still-references-somehow
                 if let Some(q) = query {
@@ -237,10 +260,10 @@ where
                 id: prepared.get_id().clone(),
                 is_confirmed_lwt: prepared.is_confirmed_lwt(),
                 metadata: prepared.get_prepared_metadata().clone(),
-                result_metadata: prepared.get_result_metadata().clone(),
+                result_metadata: prepared.get_shared_result_metadata().clone(),
                 partitioner_name: prepared.get_partitioner_name().clone(),
             };
-            self.cache.insert(query_contents, raw);
+            self.cache.insert(cache_key, raw);
 
             Ok(prepared)
         }
@@ -438,7 +461,7 @@ mod tests {
     use std::net::SocketAddr;
     use std::sync::Arc;
 
-    use super::CachingSession;
+    use super::{CacheKey, CachingSession};
 
     async fn new_for_test(with_tablet_support: bool) -> Session {
         let session = create_new_session_builder()
@@ -493,6 +516,19 @@ mod tests {
         session
     }
 
+    /// Test that the same statement text prepared in different keyspaces
+    /// is cached separately.
+    #[test]
+    fn test_cache_key_includes_keyspace() {
+        let statement = Statement::new("SELECT a FROM tab");
+        let in_ks1 = statement.clone().with_keyspace("ks1");
+        let in_ks2 = statement.clone().with_keyspace("ks2");
+
+        assert_eq!(CacheKey::new(&in_ks1), CacheKey::new(&in_ks1.clone()));
+        assert_ne!(CacheKey::new(&statement), CacheKey::new(&in_ks1));
+        assert_ne!(CacheKey::new(&in_ks1), CacheKey::new(&in_ks2));
+    }
+
     /// Test that when the cache is full and a different query comes in, that query will be added
     /// to the cache and a random query is removed
     #[tokio::test]
@@ -520,11 +556,20 @@ mod tests {
         assert_eq!(2, session.cache.len());
 
         // This query should be in the cache
-        assert!(session.cache.get(last_query).is_some());
+        assert!(session
+            .cache
+            .get(&CacheKey::new(&last_query.into()))
+            .is_some());
 
         // Either the first or middle query should be removed
-        let first_query_removed = session.cache.get(first_query).is_none();
-        let middle_query_removed = session.cache.get(middle_query).is_none();
+        let first_query_removed = session
+            .cache
+            .get(&CacheKey::new(&first_query.into()))
+            .is_none();
+        let middle_query_removed = session
+            .cache
+            .get(&CacheKey::new(&middle_query.into()))
+            .is_none();
 
         assert!(first_query_removed || middle_query_removed);
     }
@@ -162,22 +162,29 @@
 //! ```
 //!
 
-use std::{fmt::Debug, sync::Arc, time::Duration};
+use std::{collections::HashMap, fmt::Debug, sync::Arc, time::Duration};
 
 use arc_swap::ArcSwap;
+use bytes::Bytes;
 use scylla_cql::{frame::types::SerialConsistency, Consistency};
 
+use crate::policies::interceptor::RequestInterceptor;
 use crate::policies::load_balancing::LoadBalancingPolicy;
 use crate::policies::retry::RetryPolicy;
 use crate::policies::speculative_execution::SpeculativeExecutionPolicy;
+use crate::policies::throttler::RequestThrottler;
 
 pub(crate) mod defaults {
     use super::ExecutionProfileInner;
+    use crate::policies::interceptor::RequestInterceptor;
     use crate::policies::load_balancing::{self, LoadBalancingPolicy};
     use crate::policies::retry::{DefaultRetryPolicy, RetryPolicy};
     use crate::policies::speculative_execution::SpeculativeExecutionPolicy;
+    use crate::policies::throttler::RequestThrottler;
+    use bytes::Bytes;
     use scylla_cql::frame::types::SerialConsistency;
     use scylla_cql::Consistency;
+    use std::collections::HashMap;
     use std::sync::Arc;
     use std::time::Duration;
     pub(crate) fn consistency() -> Consistency {
@@ -198,6 +205,21 @@ pub(crate) mod defaults {
     pub(crate) fn speculative_execution_policy() -> Option<Arc<dyn SpeculativeExecutionPolicy>> {
         None
     }
+    pub(crate) fn custom_payload() -> Option<Arc<HashMap<String, Bytes>>> {
+        None
+    }
+    pub(crate) fn request_interceptor() -> Option<Arc<dyn RequestInterceptor>> {
+        None
+    }
+    pub(crate) fn request_throttler() -> Option<Arc<dyn RequestThrottler>> {
+        None
+    }
+    pub(crate) fn min_attempt_budget() -> Option<Duration> {
+        None
+    }
+    pub(crate) fn propagate_timeout_to_server() -> bool {
+        false
+    }
 
     impl Default for ExecutionProfileInner {
         fn default() -> Self {
@@ -208,6 +230,11 @@ pub(crate) mod defaults {
                 load_balancing_policy: load_balancing_policy(),
                 retry_policy: retry_policy(),
                 speculative_execution_policy: speculative_execution_policy(),
+                custom_payload: custom_payload(),
+                request_interceptor: request_interceptor(),
+                request_throttler: request_throttler(),
+                min_attempt_budget: min_attempt_budget(),
+                propagate_timeout_to_server: propagate_timeout_to_server(),
             }
         }
     }
@@ -237,6 +264,11 @@ pub struct ExecutionProfileBuilder {
     load_balancing_policy: Option<Arc<dyn LoadBalancingPolicy>>,
     retry_policy: Option<Arc<dyn RetryPolicy>>,
     speculative_execution_policy: Option<Option<Arc<dyn SpeculativeExecutionPolicy>>>,
+    custom_payload: Option<Option<Arc<HashMap<String, Bytes>>>>,
+    request_interceptor: Option<Option<Arc<dyn RequestInterceptor>>>,
+    request_throttler: Option<Option<Arc<dyn RequestThrottler>>>,
+    min_attempt_budget: Option<Option<Duration>>,
+    propagate_timeout_to_server: Option<bool>,
 }
 
 impl ExecutionProfileBuilder {
@@ -350,6 +382,138 @@ impl ExecutionProfileBuilder {
         self
     }
 
+    /// Sets the custom payload sent along with every request executed
+    /// with this profile. The default is None.
+    ///
+    /// Entries set on a statement override the ones with the same key
+    /// set here.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::execution_profile::ExecutionProfile;
+    /// # use bytes::Bytes;
+    /// # use std::collections::HashMap;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let payload = HashMap::from([("app".to_owned(), Bytes::from_static(b"billing"))]);
+    /// let profile: ExecutionProfile = ExecutionProfile::builder()
+    ///     .custom_payload(Some(payload))
+    ///     .build();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn custom_payload(mut self, custom_payload: Option<HashMap<String, Bytes>>) -> Self {
+        self.custom_payload = Some(custom_payload.map(Arc::new));
+        self
+    }
+
+    /// Sets the request interceptor used for requests executed with this profile,
+    /// instead of the one set on the `Session`.
+    /// The default is None, which means that the `Session`'s interceptor is used.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::execution_profile::ExecutionProfile;
+    /// # use scylla::policies::interceptor::RequestInterceptor;
+    /// # use std::sync::Arc;
+    /// #[derive(Debug)]
+    /// struct NoopInterceptor;
+    /// impl RequestInterceptor for NoopInterceptor {}
+    ///
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let profile: ExecutionProfile = ExecutionProfile::builder()
+    ///     .request_interceptor(Some(Arc::new(NoopInterceptor)))
+    ///     .build();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn request_interceptor(
+        mut self,
+        request_interceptor: Option<Arc<dyn RequestInterceptor>>,
+    ) -> Self {
+        self.request_interceptor = Some(request_interceptor);
+        self
+    }
+
+    /// Sets the request throttler used for requests executed with this profile,
+    /// instead of the one set on the `Session`.
+    /// The default is None, which means that the `Session`'s throttler is used.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::execution_profile::ExecutionProfile;
+    /// # use scylla::policies::throttler::DefaultRequestThrottler;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// // Background jobs don't get more than 10 requests in flight.
+    /// let profile: ExecutionProfile = ExecutionProfile::builder()
+    ///     .request_throttler(Some(
+    ///         DefaultRequestThrottler::builder()
+    ///             .max_concurrent_requests(10)
+    ///             .build(),
+    ///     ))
+    ///     .build();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn request_throttler(
+        mut self,
+        request_throttler: Option<Arc<dyn RequestThrottler>>,
+    ) -> Self {
+        self.request_throttler = Some(request_throttler);
+        self
+    }
+
+    /// Sets the minimal time left until the request timeout that is required
+    /// to start an attempt of a request, including retries and speculative executions.
+    /// Attempts that would be started with less time left are skipped, and the request
+    /// fails with the error of its last attempt, or with a request timeout if no attempt
+    /// has been made. Has no effect if the request has no request timeout.
+    /// The default is None, which means that attempts are never skipped.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::execution_profile::ExecutionProfile;
+    /// # use std::time::Duration;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// // Don't retry when less than 50 ms are left of the 1 s budget.
+    /// let profile: ExecutionProfile = ExecutionProfile::builder()
+    ///     .request_timeout(Some(Duration::from_secs(1)))
+    ///     .min_attempt_budget(Some(Duration::from_millis(50)))
+    ///     .build();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn min_attempt_budget(mut self, min_attempt_budget: Option<Duration>) -> Self {
+        self.min_attempt_budget = Some(min_attempt_budget);
+        self
+    }
+
+    /// Enables propagation of the time left until the request timeout to ScyllaDB,
+    /// so that coordinators stop working on requests which the driver has already abandoned.
+    ///
+    /// The time left is bound to the `USING TIMEOUT ?` bind marker of a prepared
+    /// statement (custom timeouts are a ScyllaDB-only feature), replacing the value
+    /// bound to it by the caller, separately for each attempt. The statement itself
+    /// is not modified. Statements without such a marker and batches are not affected,
+    /// and neither are requests without a request timeout, for which the bound
+    /// value is used as it is. The default is false.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::execution_profile::ExecutionProfile;
+    /// # use std::time::Duration;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let profile: ExecutionProfile = ExecutionProfile::builder()
+    ///     .request_timeout(Some(Duration::from_secs(1)))
+    ///     .propagate_timeout_to_server(true)
+    ///     .build();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn propagate_timeout_to_server(mut self, propagate: bool) -> Self {
+        self.propagate_timeout_to_server = Some(propagate);
+        self
+    }
+
     /// Builds the ExecutionProfile after setting all the options.
     ///
     /// # Example
@@ -380,6 +544,19 @@ impl ExecutionProfileBuilder {
             speculative_execution_policy: self
                 .speculative_execution_policy
                 .unwrap_or_else(defaults::speculative_execution_policy),
+            custom_payload: self.custom_payload.unwrap_or_else(defaults::custom_payload),
+            request_interceptor: self
+                .request_interceptor
+                .unwrap_or_else(defaults::request_interceptor),
+            request_throttler: self
+                .request_throttler
+                .unwrap_or_else(defaults::request_throttler),
+            min_attempt_budget: self
+                .min_attempt_budget
+                .unwrap_or_else(defaults::min_attempt_budget),
+            propagate_timeout_to_server: self
+                .propagate_timeout_to_server
+                .unwrap_or_else(defaults::propagate_timeout_to_server),
         }))
     }
 }
@@ -409,6 +586,15 @@ pub(crate) struct ExecutionProfileInner {
     pub(crate) load_balancing_policy: Arc<dyn LoadBalancingPolicy>,
     pub(crate) retry_policy: Arc<dyn RetryPolicy>,
     pub(crate) speculative_execution_policy: Option<Arc<dyn SpeculativeExecutionPolicy>>,
+
+    pub(crate) custom_payload: Option<Arc<HashMap<String, Bytes>>>,
+
+    pub(crate) request_interceptor: Option<Arc<dyn RequestInterceptor>>,
+
+    pub(crate) request_throttler: Option<Arc<dyn RequestThrottler>>,
+
+    pub(crate) min_attempt_budget: Option<Duration>,
+    pub(crate) propagate_timeout_to_server: bool,
 }
 
 impl ExecutionProfileInner {
@@ -421,6 +607,11 @@ impl ExecutionProfileInner {
             load_balancing_policy: Some(self.load_balancing_policy.clone()),
             retry_policy: Some(self.retry_policy.clone()),
             speculative_execution_policy: Some(self.speculative_execution_policy.clone()),
+            custom_payload: Some(self.custom_payload.clone()),
+            request_interceptor: Some(self.request_interceptor.clone()),
+            request_throttler: Some(self.request_throttler.clone()),
+            min_attempt_budget: Some(self.min_attempt_budget),
+            propagate_timeout_to_server: Some(self.propagate_timeout_to_server),
         }
     }
 }
@@ -439,6 +630,11 @@ impl ExecutionProfile {
             load_balancing_policy: None,
             retry_policy: None,
             speculative_execution_policy: None,
+            custom_payload: None,
+            request_interceptor: None,
+            request_throttler: None,
+            min_attempt_budget: None,
+            propagate_timeout_to_server: None,
         }
     }
 
@@ -489,6 +685,31 @@ impl ExecutionProfile {
     pub fn get_speculative_execution_policy(&self) -> Option<&Arc<dyn SpeculativeExecutionPolicy>> {
         self.0.speculative_execution_policy.as_ref()
     }
+
+    /// Gets custom payload (if set) associated with this profile.
+    pub fn get_custom_payload(&self) -> Option<&HashMap<String, Bytes>> {
+        self.0.custom_payload.as_deref()
+    }
+
+    /// Gets request interceptor (if set) associated with this profile.
+    pub fn get_request_interceptor(&self) -> Option<&Arc<dyn RequestInterceptor>> {
+        self.0.request_interceptor.as_ref()
+    }
+
+    /// Gets request throttler (if set) associated with this profile.
+    pub fn get_request_throttler(&self) -> Option<&Arc<dyn RequestThrottler>> {
+        self.0.request_throttler.as_ref()
+    }
+
+    /// Gets the minimal time left until the request timeout required to start an attempt.
+    pub fn get_min_attempt_budget(&self) -> Option<Duration> {
+        self.0.min_attempt_budget
+    }
+
+    /// Returns true if the time left until the request timeout is propagated to ScyllaDB.
+    pub fn get_propagate_timeout_to_server(&self) -> bool {
+        self.0.propagate_timeout_to_server
+    }
 }
 
 /// A handle that points to an ExecutionProfile.
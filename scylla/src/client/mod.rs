@@ -14,6 +14,8 @@
 //!   options relevant when executing a request against the DB.
 //! - [QueryPager](pager::QueryPager) and [TypedRowStream](pager::TypedRowStream) - entities that provide
 //!   automated transparent paging of a query.
+//! - [Table](table::Table) - a trait implemented with `#[derive(Table)]`, which provides typed
+//!   CRUD operations on a single table, executed through a [CachingSession](caching_session::CachingSession).
 
 pub mod execution_profile;
 
@@ -21,6 +23,8 @@ pub mod pager;
 
 pub mod caching_session;
 
+pub mod table;
+
 mod self_identity;
 pub use self_identity::SelfIdentity;
 
@@ -28,6 +32,9 @@ pub mod session;
 
 pub mod session_builder;
 
-pub use scylla_cql::frame::Compression;
+mod shutdown;
+pub use shutdown::ShutdownReport;
+
+pub use scylla_cql::frame::{Compression, ProtocolVersion};
 
 pub use crate::network::{PoolSize, WriteCoalescingDelay};
@@ -2,6 +2,7 @@
 //! They enable consuming result of a paged query as a stream over rows,
 //! which abstracts over page boundaries.
 
+use std::collections::HashMap;
 use std::future::Future;
 use std::net::SocketAddr;
 use std::ops::ControlFlow;
@@ -9,7 +10,12 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
+#[cfg(feature = "arrow-53")]
+use arrow_array_53::RecordBatch;
+use bytes::Bytes;
 use futures::Stream;
+#[cfg(feature = "arrow-53")]
+use scylla_cql::deserialize::arrow::{ArrowConversionError, RecordBatchBuilder};
 use scylla_cql::deserialize::result::RawRowLendingIterator;
 use scylla_cql::deserialize::row::{ColumnIterator, DeserializeRow};
 use scylla_cql::deserialize::{DeserializationError, TypeCheckError};
@@ -25,6 +31,7 @@ use thiserror::Error;
 use tokio::sync::mpsc;
 
 use crate::client::execution_profile::ExecutionProfileInner;
+use crate::client::shutdown::InFlightGuard;
 use crate::cluster::{ClusterState, NodeRef};
 use crate::deserialize::DeserializeOwnedRow;
 use crate::errors::{RequestAttemptError, RequestError};
@@ -34,8 +41,13 @@ use crate::observability::driver_tracing::RequestSpan;
 use crate::observability::history::{self, HistoryListener};
 #[cfg(feature = "metrics")]
 use crate::observability::metrics::Metrics;
+use crate::policies::interceptor::{
+    self, InterceptedRequest, InterceptedStatement, InterceptorDecision, RequestInterceptor,
+    RequestTemplate,
+};
 use crate::policies::load_balancing::{self, LoadBalancingPolicy, RoutingInfo};
 use crate::policies::retry::{RequestInfo, RetryDecision, RetrySession};
+use crate::policies::throttler::{self, RequestThrottler};
 use crate::response::query_result::ColumnSpecs;
 use crate::response::{NonErrorQueryResponse, QueryResponse};
 use crate::statement::prepared::{PartitionKeyError, PreparedStatement};
@@ -60,6 +72,7 @@ macro_rules! ready_some_ok {
 struct ReceivedPage {
     rows: RawMetadataAndRawRows,
     tracing_id: Option<Uuid>,
+    custom_payload: Option<HashMap<String, Bytes>>,
     request_coordinator: Option<Coordinator>,
 }
 
@@ -67,7 +80,10 @@ pub(crate) struct PreparedPagerConfig {
     pub(crate) prepared: PreparedStatement,
     pub(crate) values: SerializedValues,
     pub(crate) execution_profile: Arc<ExecutionProfileInner>,
+    pub(crate) request_interceptor: Option<Arc<dyn RequestInterceptor>>,
+    pub(crate) request_throttler: Option<Arc<dyn RequestThrottler>>,
     pub(crate) cluster_state: Arc<ClusterState>,
+    pub(crate) in_flight: InFlightGuard,
     #[cfg(feature = "metrics")]
     pub(crate) metrics: Arc<Metrics>,
 }
@@ -75,7 +91,9 @@ pub(crate) struct PreparedPagerConfig {
 // A separate module is used here so that the parent module cannot construct
 // SendAttemptedProof directly.
 mod checked_channel_sender {
+    use bytes::Bytes;
     use scylla_cql::frame::response::result::RawMetadataAndRawRows;
+    use std::collections::HashMap;
     use std::marker::PhantomData;
     use tokio::sync::mpsc;
     use uuid::Uuid;
@@ -113,6 +131,7 @@ mod checked_channel_sender {
         pub(crate) async fn send_empty_page(
             &self,
             tracing_id: Option<Uuid>,
+            custom_payload: Option<HashMap<String, Bytes>>,
             request_coordinator: Option<Coordinator>,
         ) -> (
             SendAttemptedProof<ResultPage>,
@@ -121,6 +140,7 @@ mod checked_channel_sender {
             let empty_page = ReceivedPage {
                 rows: RawMetadataAndRawRows::mock_empty(),
                 tracing_id,
+                custom_payload,
                 request_coordinator,
             };
             self.send(Ok(empty_page)).await
@@ -130,6 +150,22 @@ mod checked_channel_sender {
 
 use checked_channel_sender::{ProvingSender, SendAttemptedProof};
 
+// Runs the pager worker until it completes or until the session's shutdown deadline passes.
+async fn work_until_shutdown(
+    work: impl Future<Output = PageSendAttemptedProof>,
+    mut in_flight: InFlightGuard,
+    sender: ProvingSender<Result<ReceivedPage, NextPageError>>,
+) -> PageSendAttemptedProof {
+    let cancelled = tokio::select! {
+        proof = work => return proof,
+        () = in_flight.cancelled() => NextPageError::RequestFailure(RequestError::SessionShutDown),
+    };
+    // The pager is no longer in flight, even if nobody is going to receive the error.
+    drop(in_flight);
+    let (proof, _) = sender.send(Err(cancelled)).await;
+    proof
+}
+
 use crate::response::Coordinator;
 
 type PageSendAttemptedProof = SendAttemptedProof<Result<ReceivedPage, NextPageError>>;
@@ -140,9 +176,13 @@ struct PagerWorker<'a, QueryFunc, SpanCreatorFunc> {
     sender: ProvingSender<Result<ReceivedPage, NextPageError>>,
 
     // Closure used to perform a single page query
-    // AsyncFn(Arc<Connection>, Option<Arc<[u8]>>) -> Result<QueryResponse, RequestAttemptError>
+    // AsyncFn(Arc<Connection>, InterceptedRequest, PagingState) -> Result<QueryResponse, RequestAttemptError>
     page_query: QueryFunc,
 
+    request_template: RequestTemplate<'a>,
+    request_interceptor: Option<Arc<dyn RequestInterceptor>>,
+    request_throttler: Option<Arc<dyn RequestThrottler>>,
+
     load_balancing_policy: Arc<dyn LoadBalancingPolicy>,
     statement_info: RoutingInfo<'a>,
     query_is_idempotent: bool,
@@ -161,9 +201,9 @@ struct PagerWorker<'a, QueryFunc, SpanCreatorFunc> {
     span_creator: SpanCreatorFunc,
 }
 
-impl<QueryFunc, QueryFut, SpanCreator> PagerWorker<'_, QueryFunc, SpanCreator>
+impl<'a, QueryFunc, QueryFut, SpanCreator> PagerWorker<'a, QueryFunc, SpanCreator>
 where
-    QueryFunc: Fn(Arc<Connection>, Consistency, PagingState) -> QueryFut,
+    QueryFunc: Fn(Arc<Connection>, InterceptedRequest<'a>, PagingState) -> QueryFut,
     QueryFut: Future<Output = Result<QueryResponse, RequestAttemptError>>,
     SpanCreator: Fn() -> RequestSpan,
 {
@@ -208,7 +248,7 @@ where
                     Coordinator::new(node, node.sharder().is_some().then_some(shard), &connection);
 
                 // Query pages until an error occurs
-                let queries_result: Result<PageSendAttemptedProof, RequestAttemptError> = self
+                let queries_result: Result<PageSendAttemptedProof, RequestError> = self
                     .query_pages(&connection, current_consistency, node, coordinator.clone())
                     .instrument(span.clone())
                     .await;
@@ -221,7 +261,7 @@ where
                         // through self.sender and we can safely return now.
                         return proof;
                     }
-                    Err(error) => {
+                    Err(RequestError::LastAttemptError(error)) => {
                         trace!(
                             parent: &span,
                             error = %error,
@@ -229,6 +269,20 @@ where
                         );
                         error
                     }
+                    Err(error) => {
+                        // The request can't be retried, e.g. it was rejected by an interceptor.
+                        trace!(
+                            parent: &span,
+                            error = %error,
+                            "Request failed"
+                        );
+                        self.log_request_error(&error);
+                        let (proof, _) = self
+                            .sender
+                            .send(Err(NextPageError::RequestFailure(error)))
+                            .await;
+                        return proof;
+                    }
                 };
 
                 // Use retry policy to decide what to do next
@@ -236,6 +290,8 @@ where
                     error: &request_error,
                     is_idempotent: self.query_is_idempotent,
                     consistency: self.query_consistency,
+                    // Pages have no request timeout.
+                    remaining_time: None,
                 };
 
                 let retry_decision = self.retry_session.decide_should_retry(query_info);
@@ -261,6 +317,13 @@ where
                         current_consistency = cl.unwrap_or(current_consistency);
                         continue 'nodes_in_plan;
                     }
+                    RetryDecision::RetryNextTargetAfter(cl, delay) => {
+                        #[cfg(feature = "metrics")]
+                        self.metrics.inc_retries_num();
+                        current_consistency = cl.unwrap_or(current_consistency);
+                        tokio::time::sleep(delay).await;
+                        continue 'nodes_in_plan;
+                    }
                     RetryDecision::DontRetry => break 'nodes_in_plan,
                     RetryDecision::IgnoreWriteError => {
                         warn!("Ignoring error during fetching pages; stopping fetching.");
@@ -272,7 +335,7 @@ where
                         // the iterator expects it.
                         let (proof, _) = self
                             .sender
-                            .send_empty_page(None, Some(coordinator.clone()))
+                            .send_empty_page(None, None, Some(coordinator.clone()))
                             .await;
                         return proof;
                     }
@@ -299,7 +362,7 @@ where
         consistency: Consistency,
         node: NodeRef<'_>,
         coordinator: Coordinator,
-    ) -> Result<PageSendAttemptedProof, RequestAttemptError> {
+    ) -> Result<PageSendAttemptedProof, RequestError> {
         loop {
             let request_span = (self.span_creator)();
             match self
@@ -326,7 +389,38 @@ where
         node: NodeRef<'_>,
         coordinator: Coordinator,
         request_span: &RequestSpan,
-    ) -> Result<ControlFlow<PageSendAttemptedProof, ()>, RequestAttemptError> {
+    ) -> Result<ControlFlow<PageSendAttemptedProof, ()>, RequestError> {
+        // Each page is throttled separately, so that a slowly consumed pager
+        // doesn't hold a permit between its pages.
+        let _permit = match self.request_throttler.as_deref() {
+            Some(request_throttler) => Some(
+                throttler::acquire_permit(
+                    request_throttler,
+                    #[cfg(feature = "metrics")]
+                    &self.metrics,
+                )
+                .await
+                .map_err(RequestError::RequestThrottled)?,
+            ),
+            None => None,
+        };
+
+        let mut request = self
+            .request_template
+            .attempt(consistency, coordinator.clone());
+        let intercepted_error = match self
+            .request_interceptor
+            .as_deref()
+            .map(|interceptor| interceptor.before_attempt(&mut request))
+        {
+            Some(InterceptorDecision::Reject(reason)) => {
+                trace!(reason = %reason, "Request rejected");
+                return Err(RequestError::RejectedByInterceptor(reason));
+            }
+            Some(InterceptorDecision::FailAttempt(error)) => Some(error),
+            Some(InterceptorDecision::Proceed) | None => None,
+        };
+
         #[cfg(feature = "metrics")]
         self.metrics.inc_total_paged_queries();
         let query_start = std::time::Instant::now();
@@ -338,10 +432,22 @@ where
         );
         self.log_attempt_start(connect_address);
 
-        let query_response =
-            (self.page_query)(connection.clone(), consistency, self.paging_state.clone())
-                .await
-                .and_then(QueryResponse::into_non_error_query_response);
+        let query_response = match intercepted_error {
+            Some(error) => Err(error),
+            None => (self.page_query)(
+                connection.clone(),
+                request.clone(),
+                self.paging_state.clone(),
+            )
+            .await
+            .and_then(QueryResponse::into_non_error_query_response),
+        };
+        let query_response = match self.request_interceptor.as_deref() {
+            Some(interceptor) => {
+                interceptor::intercept_attempt_result(interceptor, &request, query_response)
+            }
+            None => query_response,
+        };
 
         let elapsed = query_start.elapsed();
 
@@ -352,6 +458,7 @@ where
                 response:
                     NonErrorResponse::Result(result::Result::Rows((rows, paging_state_response))),
                 tracing_id,
+                custom_payload,
                 ..
             }) => {
                 #[cfg(feature = "metrics")]
@@ -366,6 +473,7 @@ where
                 let received_page = ReceivedPage {
                     rows,
                     tracing_id,
+                    custom_payload,
                     request_coordinator: Some(coordinator),
                 };
 
@@ -394,18 +502,30 @@ where
             }
             Err(err) => {
                 #[cfg(feature = "metrics")]
-                self.metrics.inc_failed_paged_queries();
+                {
+                    self.metrics.inc_failed_paged_queries();
+                    if matches!(
+                        err,
+                        RequestAttemptError::DbError(
+                            crate::errors::DbError::RateLimitReached { .. },
+                            _
+                        )
+                    ) {
+                        self.metrics.inc_rate_limit_errors(node.host_id);
+                    }
+                }
                 self.load_balancing_policy.on_request_failure(
                     &self.statement_info,
                     elapsed,
                     node,
                     &err,
                 );
-                Err(err)
+                Err(err.into())
             }
             Ok(NonErrorQueryResponse {
                 response: NonErrorResponse::Result(_),
                 tracing_id,
+                custom_payload,
                 ..
             }) => {
                 // We have most probably sent a modification statement (e.g. INSERT or UPDATE),
@@ -414,7 +534,7 @@ where
                 // We must attempt to send something because the iterator expects it.
                 let (proof, _) = self
                     .sender
-                    .send_empty_page(tracing_id, Some(coordinator))
+                    .send_empty_page(tracing_id, custom_payload, Some(coordinator))
                     .await;
                 Ok(ControlFlow::Break(proof))
             }
@@ -429,7 +549,7 @@ where
                     node,
                     &err,
                 );
-                Err(err)
+                Err(err.into())
             }
         }
     }
@@ -555,6 +675,7 @@ where
                         .send(Ok(ReceivedPage {
                             rows,
                             tracing_id: response.tracing_id,
+                            custom_payload: response.custom_payload,
                             request_coordinator: None,
                         }))
                         .await;
@@ -579,7 +700,10 @@ where
                     // so let's return an empty iterator as suggested in #631.
 
                     // We must attempt to send something because the iterator expects it.
-                    let (proof, _) = self.sender.send_empty_page(response.tracing_id, None).await;
+                    let (proof, _) = self
+                        .sender
+                        .send_empty_page(response.tracing_id, response.custom_payload, None)
+                        .await;
                     return Ok(proof);
                 }
                 _ => {
@@ -604,6 +728,7 @@ pub struct QueryPager {
     current_page: RawRowLendingIterator,
     page_receiver: mpsc::Receiver<Result<ReceivedPage, NextPageError>>,
     tracing_ids: Vec<Uuid>,
+    custom_payloads: Vec<HashMap<String, Bytes>>,
     request_coordinators: Vec<Coordinator>,
 }
 
@@ -680,6 +805,8 @@ impl QueryPager {
             s.tracing_ids.push(tracing_id);
         }
 
+        s.custom_payloads.extend(received_page.custom_payload);
+
         s.request_coordinators
             .extend(received_page.request_coordinator);
 
@@ -708,10 +835,35 @@ impl QueryPager {
         TypedRowStream::<RowT>::new(self)
     }
 
+    /// Converts the pager into a [Stream] of Arrow [RecordBatch]es, one per received page.
+    ///
+    /// Rows are decoded directly from the received pages, see
+    /// [crate::deserialize::arrow] for the mapping of CQL types to Arrow types.
+    /// Empty pages are skipped.
+    #[cfg(feature = "arrow-53")]
+    #[inline]
+    pub fn record_batches(self) -> RecordBatchStream {
+        RecordBatchStream { pager: self }
+    }
+
+    /// Decodes the remaining rows of the current page into a record batch.
+    #[cfg(feature = "arrow-53")]
+    fn current_page_to_record_batch(&mut self) -> Result<RecordBatch, NextRowError> {
+        let mut builder = RecordBatchBuilder::new(self.current_page.metadata().col_specs());
+        while let Some(row) = self.current_page.next() {
+            let row = row.map_err(NextRowError::RowDeserializationError)?;
+            builder.append_row(row)?;
+        }
+        Ok(builder.finish()?)
+    }
+
     pub(crate) async fn new_for_query(
         statement: Statement,
         execution_profile: Arc<ExecutionProfileInner>,
+        request_interceptor: Option<Arc<dyn RequestInterceptor>>,
+        request_throttler: Option<Arc<dyn RequestThrottler>>,
         cluster_state: Arc<ClusterState>,
+        in_flight: InFlightGuard,
         #[cfg(feature = "metrics")] metrics: Arc<Metrics>,
     ) -> Result<Self, NextPageError> {
         let (sender, receiver) = mpsc::channel::<Result<ReceivedPage, NextPageError>>(1);
@@ -726,6 +878,9 @@ impl QueryPager {
             .unwrap_or(execution_profile.serial_consistency);
 
         let page_size = statement.get_validated_page_size();
+        let custom_payload = statement
+            .config
+            .determine_custom_payload(execution_profile.custom_payload.as_ref());
 
         let routing_info = RoutingInfo {
             consistency,
@@ -749,17 +904,29 @@ impl QueryPager {
         let worker_task = async move {
             let statement_ref = &statement;
 
+            let request_template = RequestTemplate {
+                statement: InterceptedStatement::Unprepared(statement_ref),
+                values: None,
+                serial_consistency,
+                custom_payload,
+            };
+
             let page_query = |connection: Arc<Connection>,
-                              consistency: Consistency,
+                              request: InterceptedRequest<'_>,
                               paging_state: PagingState| {
+                let modified_statement = request.modified_statement();
+                let (consistency, serial_consistency) =
+                    (request.consistency, request.serial_consistency);
+                let custom_payload = request.custom_payload;
                 async move {
                     connection
                         .query_raw_with_consistency(
-                            statement_ref,
+                            modified_statement.as_ref().unwrap_or(statement_ref),
                             consistency,
                             serial_consistency,
                             Some(page_size),
                             paging_state,
+                            custom_payload.as_deref(),
                         )
                         .await
                 }
@@ -773,9 +940,13 @@ impl QueryPager {
                 span
             };
 
+            let cancel_sender = ProvingSender::from(sender.clone());
             let worker = PagerWorker {
                 sender: sender.into(),
                 page_query,
+                request_template,
+                request_interceptor,
+                request_throttler,
                 statement_info: routing_info,
                 query_is_idempotent: statement.config.is_idempotent,
                 query_consistency: consistency,
@@ -791,7 +962,7 @@ impl QueryPager {
                 span_creator,
             };
 
-            worker.work(cluster_state).await
+            work_until_shutdown(worker.work(cluster_state), in_flight, cancel_sender).await
         };
 
         Self::new_from_worker_future(worker_task, receiver).await
@@ -814,6 +985,10 @@ impl QueryPager {
             .unwrap_or(config.execution_profile.serial_consistency);
 
         let page_size = config.prepared.get_validated_page_size();
+        let custom_payload = config
+            .prepared
+            .config
+            .determine_custom_payload(config.execution_profile.custom_payload.as_ref());
 
         let load_balancing_policy = Arc::clone(
             config
@@ -857,19 +1032,32 @@ impl QueryPager {
                 is_confirmed_lwt: config.prepared.is_confirmed_lwt(),
             };
 
+            let request_template = RequestTemplate {
+                statement: InterceptedStatement::Prepared(prepared_ref),
+                values: Some(values_ref),
+                serial_consistency,
+                custom_payload,
+            };
+
             let page_query = |connection: Arc<Connection>,
-                              consistency: Consistency,
-                              paging_state: PagingState| async move {
-                connection
-                    .execute_raw_with_consistency(
-                        prepared_ref,
-                        values_ref,
-                        consistency,
-                        serial_consistency,
-                        Some(page_size),
-                        paging_state,
-                    )
-                    .await
+                              request: InterceptedRequest<'_>,
+                              paging_state: PagingState| {
+                let (consistency, serial_consistency) =
+                    (request.consistency, request.serial_consistency);
+                let custom_payload = request.custom_payload;
+                async move {
+                    connection
+                        .execute_raw_with_consistency(
+                            prepared_ref,
+                            values_ref,
+                            consistency,
+                            serial_consistency,
+                            Some(page_size),
+                            paging_state,
+                            custom_payload.as_deref(),
+                        )
+                        .await
+                }
             };
 
             let serialized_values_size = config.values.buffer_size();
@@ -901,9 +1089,13 @@ impl QueryPager {
                 span
             };
 
+            let cancel_sender = ProvingSender::from(sender.clone());
             let worker = PagerWorker {
                 sender: sender.into(),
                 page_query,
+                request_template,
+                request_interceptor: config.request_interceptor,
+                request_throttler: config.request_throttler,
                 statement_info,
                 query_is_idempotent: config.prepared.config.is_idempotent,
                 query_consistency: consistency,
@@ -919,7 +1111,12 @@ impl QueryPager {
                 span_creator,
             };
 
-            worker.work(config.cluster_state).await
+            work_until_shutdown(
+                worker.work(config.cluster_state),
+                config.in_flight,
+                cancel_sender,
+            )
+            .await
         };
 
         Self::new_from_worker_future(worker_task, receiver).await
@@ -945,6 +1142,7 @@ impl QueryPager {
                         serial_consistency,
                         Some(page_size),
                         paging_state,
+                        query.get_custom_payload(),
                     )
                 },
             };
@@ -976,6 +1174,7 @@ impl QueryPager {
                         serial_consistency,
                         Some(page_size),
                         paging_state,
+                        prepared.get_custom_payload(),
                     )
                 },
             };
@@ -1007,6 +1206,7 @@ impl QueryPager {
             } else {
                 Vec::new()
             },
+            custom_payloads: Vec::from_iter(page_received.custom_payload),
             request_coordinators: Vec::from_iter(page_received.request_coordinator),
         })
     }
@@ -1017,6 +1217,13 @@ impl QueryPager {
         &self.tracing_ids
     }
 
+    /// Returns custom payloads sent back by the database with finished page queries,
+    /// in query order. Pages which came without a custom payload are skipped.
+    #[inline]
+    pub fn custom_payloads(&self) -> &[HashMap<String, Bytes>] {
+        &self.custom_payloads
+    }
+
     /// Returns the targets that served finished page queries, in query order.
     #[inline]
     pub fn request_coordinators(&self) -> impl Iterator<Item = &Coordinator> {
@@ -1077,6 +1284,13 @@ impl<RowT> TypedRowStream<RowT> {
         self.raw_row_lending_stream.tracing_ids()
     }
 
+    /// Returns custom payloads sent back by the database with finished page queries,
+    /// in query order. Pages which came without a custom payload are skipped.
+    #[inline]
+    pub fn custom_payloads(&self) -> &[HashMap<String, Bytes>] {
+        self.raw_row_lending_stream.custom_payloads()
+    }
+
     /// Returns the targets that served finished page queries, in query order.
     #[inline]
     pub fn request_coordinators(&self) -> impl Iterator<Item = &Coordinator> {
@@ -1115,6 +1329,53 @@ where
     }
 }
 
+/// Returned by [QueryPager::record_batches].
+///
+/// Implements [Stream], yielding an Arrow [RecordBatch] for each non-empty page.
+#[cfg(feature = "arrow-53")]
+#[derive(Debug)]
+pub struct RecordBatchStream {
+    pager: QueryPager,
+}
+
+#[cfg(feature = "arrow-53")]
+impl RecordBatchStream {
+    /// If tracing was enabled, returns tracing ids of all finished page queries.
+    #[inline]
+    pub fn tracing_ids(&self) -> &[Uuid] {
+        self.pager.tracing_ids()
+    }
+
+    /// Returns custom payloads sent back by the database with finished page queries,
+    /// in query order. Pages which came without a custom payload are skipped.
+    #[inline]
+    pub fn custom_payloads(&self) -> &[HashMap<String, Bytes>] {
+        self.pager.custom_payloads()
+    }
+
+    /// Returns the targets that served finished page queries, in query order.
+    #[inline]
+    pub fn request_coordinators(&self) -> impl Iterator<Item = &Coordinator> {
+        self.pager.request_coordinators()
+    }
+
+    /// Returns specification of row columns
+    #[inline]
+    pub fn column_specs(&self) -> ColumnSpecs<'_, '_> {
+        self.pager.column_specs()
+    }
+}
+
+#[cfg(feature = "arrow-53")]
+impl Stream for RecordBatchStream {
+    type Item = Result<RecordBatch, NextRowError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        ready_some_ok!(Pin::new(&mut self.pager).poll_fill_page(cx));
+        Poll::Ready(Some(self.pager.current_page_to_record_batch()))
+    }
+}
+
 /// An error returned that occurred during next page fetch.
 #[derive(Error, Debug, Clone)]
 #[non_exhaustive]
@@ -1143,4 +1404,8 @@ pub enum NextRowError {
     /// An error occurred during row deserialization.
     #[error("Row deserialization error: {0}")]
     RowDeserializationError(#[from] DeserializationError),
+    /// Failed to convert rows of a page into an Arrow record batch.
+    #[cfg(feature = "arrow-53")]
+    #[error("Arrow conversion error: {0}")]
+    ArrowConversionFailed(#[from] ArrowConversionError),
 }
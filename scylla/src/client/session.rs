@@ -2,14 +2,18 @@
 //! It manages all connections to the cluster and allows to execute CQL requests.
 
 use super::execution_profile::{ExecutionProfile, ExecutionProfileHandle, ExecutionProfileInner};
-use super::pager::{PreparedPagerConfig, QueryPager};
-use super::{Compression, PoolSize, SelfIdentity, WriteCoalescingDelay};
+use super::pager::{NextPageError, PreparedPagerConfig, QueryPager};
+use super::shutdown::{InFlightGuard, InFlightKind, RequestTracker};
+use super::{
+    Compression, PoolSize, ProtocolVersion, SelfIdentity, ShutdownReport, WriteCoalescingDelay,
+};
 use crate::authentication::AuthenticatorProvider;
 #[cfg(feature = "unstable-cloud")]
 use crate::cloud::CloudConfig;
 #[cfg(feature = "unstable-cloud")]
 use crate::cluster::node::CloudEndpoint;
 use crate::cluster::node::{InternalKnownNode, KnownNode, NodeRef};
+use crate::cluster::validation::{self, SchemaValidationError};
 use crate::cluster::{Cluster, ClusterNeatDebug, ClusterState};
 use crate::errors::{
     BadQuery, ExecutionError, MetadataError, NewSessionError, PagerExecutionError, PrepareError,
@@ -17,7 +21,9 @@ use crate::errors::{
 };
 use crate::frame::response::result;
 use crate::network::tls::TlsProvider;
-use crate::network::{Connection, ConnectionConfig, PoolConfig, VerifiedKeyspaceName};
+use crate::network::{
+    Connection, ConnectionConfig, PoolConfig, SharedProtocolVersion, VerifiedKeyspaceName,
+};
 use crate::observability::driver_tracing::RequestSpan;
 use crate::observability::history::{self, HistoryListener};
 #[cfg(feature = "metrics")]
@@ -25,10 +31,16 @@ use crate::observability::metrics::Metrics;
 use crate::observability::tracing::TracingInfo;
 use crate::policies::address_translator::AddressTranslator;
 use crate::policies::host_filter::HostFilter;
+use crate::policies::interceptor::{
+    self, InterceptedRequest, InterceptedStatement, InterceptorDecision, RequestInterceptor,
+    RequestTemplate,
+};
 use crate::policies::load_balancing::{self, RoutingInfo};
 use crate::policies::retry::{RequestInfo, RetryDecision, RetrySession};
 use crate::policies::speculative_execution;
+use crate::policies::throttler::{self, RequestThrottler, ThrottlingPermit};
 use crate::policies::timestamp_generator::TimestampGenerator;
+use crate::response::lwt::{self, CasError, CasOptions, CasOutcome, LwtError, LwtResult};
 use crate::response::query_result::{MaybeFirstRowError, QueryResult, RowsError};
 use crate::response::{
     Coordinator, NonErrorQueryResponse, PagingState, PagingStateResponse, QueryResponse,
@@ -39,14 +51,17 @@ use crate::statement::batch::batch_values;
 use crate::statement::batch::{Batch, BatchStatement};
 use crate::statement::prepared::{PartitionKeyError, PreparedStatement};
 use crate::statement::unprepared::Statement;
-use crate::statement::{Consistency, PageSize, StatementConfig};
+use crate::statement::{Consistency, PageSize, SerialConsistency, StatementConfig};
 use arc_swap::ArcSwapOption;
 use futures::future::join_all;
 use futures::future::try_join_all;
 use itertools::Itertools;
+use scylla_cql::deserialize::row::DeserializeRow;
+use scylla_cql::deserialize::value::DeserializeValue;
 use scylla_cql::frame::response::NonErrorResponse;
 use scylla_cql::serialize::batch::BatchValues;
 use scylla_cql::serialize::row::{SerializeRow, SerializedValues};
+use scylla_cql::serialize::value::SerializeValue;
 use std::borrow::Borrow;
 use std::future::Future;
 use std::net::{IpAddr, SocketAddr};
@@ -54,9 +69,7 @@ use std::num::NonZeroU32;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::timeout;
-#[cfg(feature = "unstable-cloud")]
-use tracing::warn;
-use tracing::{debug, error, trace, trace_span, Instrument};
+use tracing::{debug, error, trace, trace_span, warn, Instrument};
 use uuid::Uuid;
 
 pub(crate) const TABLET_CHANNEL_SIZE: usize = 8192;
@@ -77,6 +90,9 @@ pub struct Session {
     tracing_info_fetch_attempts: NonZeroU32,
     tracing_info_fetch_interval: Duration,
     tracing_info_fetch_consistency: Consistency,
+    request_interceptor: Option<Arc<dyn RequestInterceptor>>,
+    request_throttler: Option<Arc<dyn RequestThrottler>>,
+    request_tracker: Arc<RequestTracker>,
 }
 
 /// This implementation deliberately omits some details from Cluster in order
@@ -119,6 +135,8 @@ impl std::fmt::Debug for Session {
             "tracing_info_fetch_consistency",
             &self.tracing_info_fetch_consistency,
         )
+        .field("request_interceptor", &self.request_interceptor)
+        .field("request_throttler", &self.request_throttler)
         .finish()
     }
 }
@@ -169,6 +187,16 @@ pub struct SessionConfig {
     /// By default set to [`ShardAwarePortRange::EPHEMERAL_PORT_RANGE`].
     pub shard_aware_local_port_range: ShardAwarePortRange,
 
+    /// Version of the CQL native protocol to use on connections.
+    ///
+    /// If set to `None`, the version is negotiated: the driver starts with
+    /// [`ProtocolVersion::HIGHEST`] and downgrades whenever a node rejects
+    /// the version. The negotiated version is remembered for the whole cluster.
+    /// If set to `Some`, only the given version is used.
+    ///
+    /// By default set to `None`.
+    pub protocol_version: Option<ProtocolVersion>,
+
     /// Preferred compression algorithm to use on connections.
     /// If it's not supported by database server Session will fall back to no compression.
     pub compression: Option<Compression>,
@@ -243,6 +271,15 @@ pub struct SessionConfig {
     /// re-establishing the control connection.
     pub host_filter: Option<Arc<dyn HostFilter>>,
 
+    /// The request interceptor is called around every attempt of requests
+    /// executed by the session, and can inspect and modify them.
+    /// It can be overridden per execution profile.
+    pub request_interceptor: Option<Arc<dyn RequestInterceptor>>,
+
+    /// The request throttler bounds the concurrency and the rate of requests
+    /// executed by the session. It can be overridden per execution profile.
+    pub request_throttler: Option<Arc<dyn RequestThrottler>>,
+
     /// If the driver is to connect to ScyllaCloud, there is a config for it.
     #[cfg(feature = "unstable-cloud")]
     pub cloud_config: Option<Arc<CloudConfig>>,
@@ -309,6 +346,7 @@ impl SessionConfig {
             known_nodes: Vec::new(),
             local_ip_address: None,
             shard_aware_local_port_range: ShardAwarePortRange::EPHEMERAL_PORT_RANGE,
+            protocol_version: None,
             compression: None,
             tcp_nodelay: true,
             tcp_keepalive_interval: None,
@@ -332,6 +370,8 @@ impl SessionConfig {
             schema_agreement_automatic_waiting: true,
             address_translator: None,
             host_filter: None,
+            request_interceptor: None,
+            request_throttler: None,
             refresh_metadata_on_auto_schema_agreement: true,
             #[cfg(feature = "unstable-cloud")]
             cloud_config: None,
@@ -642,6 +682,173 @@ impl Session {
         self.do_execute_unpaged(prepared, values).await
     }
 
+    /// Executes a prepared lightweight transaction (a statement with an `IF` condition)
+    /// and reads its outcome from the `[applied]` column of the result.
+    ///
+    /// If the condition was not met, the current values of the row returned by the database
+    /// are deserialized into `T`. Statements confirmed to be LWTs
+    /// (see [`PreparedStatement::is_confirmed_lwt`]) are routed to their replicas
+    /// in a fixed order, which reduces contention between concurrent transactions.
+    ///
+    /// See [the book](https://rust-driver.docs.scylladb.com/stable/statements/lwt.html) for more information.
+    ///
+    /// # Arguments
+    /// * `prepared` - the prepared conditional statement to execute
+    /// * `values` - values bound to the statement
+    ///
+    /// # Example
+    /// ```rust
+    /// # use scylla::client::session::Session;
+    /// # use std::error::Error;
+    /// # async fn check_only_compiles(session: &Session) -> Result<(), Box<dyn Error>> {
+    /// use scylla::response::lwt::LwtResult;
+    ///
+    /// let prepared = session
+    ///     .prepare("UPDATE ks.accounts SET balance = ? WHERE id = ? IF balance = ?")
+    ///     .await?;
+    ///
+    /// match session.execute_lwt::<(i64,)>(&prepared, (90_i64, 1_i32, 100_i64)).await? {
+    ///     LwtResult::Applied => println!("Balance updated"),
+    ///     LwtResult::NotApplied(Some((balance,))) => println!("Balance is {}", balance),
+    ///     LwtResult::NotApplied(None) => println!("No such account"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_lwt<T>(
+        &self,
+        prepared: &PreparedStatement,
+        values: impl SerializeRow,
+    ) -> Result<LwtResult<T>, LwtError>
+    where
+        T: for<'frame, 'metadata> DeserializeRow<'frame, 'metadata>,
+    {
+        let result = self.execute_unpaged(prepared, values).await?;
+        LwtResult::from_query_result(result)
+    }
+
+    /// Performs a read-modify-write of a single row using a lightweight transaction.
+    ///
+    /// The current row is read with `read` at SERIAL (or LOCAL_SERIAL, following the serial
+    /// consistency of `update`) consistency and passed to `compute`, which returns the row to be
+    /// written along with the values bound to `update` - a conditional statement which
+    /// applies only if the row has not changed since it was read. If it didn't apply,
+    /// the row is read again and the whole cycle is repeated, at most
+    /// [`CasOptions::max_attempts`] times.
+    /// If `compute` returns None, the row is left unchanged.
+    ///
+    /// If the coordinator times out while performing the transaction (a `WriteTimeout`
+    /// with `WriteType::Cas`), it is unknown whether it applied. The row is then read
+    /// again at SERIAL consistency, which completes any unfinished transaction,
+    /// and the update is considered applied if the row is equal to the one to be written.
+    ///
+    /// # Arguments
+    /// * `read` - the prepared statement selecting the row
+    /// * `read_values` - values bound to `read`
+    /// * `update` - the prepared conditional statement updating the row
+    /// * `options` - options of the read-modify-write, such as the maximal number of attempts
+    /// * `compute` - computes the new row and the values bound to `update` from the current row
+    ///
+    /// # Example
+    /// ```rust
+    /// # use scylla::client::session::Session;
+    /// # use std::error::Error;
+    /// # async fn check_only_compiles(session: &Session) -> Result<(), Box<dyn Error>> {
+    /// use scylla::response::lwt::{CasOptions, CasOutcome};
+    ///
+    /// let read = session
+    ///     .prepare("SELECT balance FROM ks.accounts WHERE id = ?")
+    ///     .await?;
+    /// let update = session
+    ///     .prepare("UPDATE ks.accounts SET balance = ? WHERE id = ? IF balance = ?")
+    ///     .await?;
+    ///
+    /// // Withdraw 10 from the account, unless it would become overdrawn.
+    /// let outcome = session
+    ///     .compare_and_set(
+    ///         &read,
+    ///         (1_i32,),
+    ///         &update,
+    ///         CasOptions::new().max_attempts(5),
+    ///         |current: Option<&(i64,)>| {
+    ///             let &(balance,) = current?;
+    ///             (balance >= 10).then(|| ((balance - 10,), (balance - 10, 1_i32, balance)))
+    ///         },
+    ///     )
+    ///     .await?;
+    /// if let CasOutcome::Applied((balance,)) = outcome {
+    ///     println!("Balance is now {}", balance);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn compare_and_set<R, V>(
+        &self,
+        read: &PreparedStatement,
+        read_values: impl SerializeRow,
+        update: &PreparedStatement,
+        options: CasOptions,
+        mut compute: impl FnMut(Option<&R>) -> Option<(R, V)>,
+    ) -> Result<CasOutcome<R>, CasError>
+    where
+        R: for<'frame, 'metadata> DeserializeRow<'frame, 'metadata> + PartialEq,
+        V: SerializeRow,
+    {
+        let serial_consistency = update.get_serial_consistency().or_else(|| {
+            update
+                .get_execution_profile_handle()
+                .unwrap_or_else(|| self.get_default_execution_profile_handle())
+                .access()
+                .serial_consistency
+        });
+        let mut read = read.clone();
+        read.set_consistency(match serial_consistency {
+            Some(SerialConsistency::LocalSerial) => Consistency::LocalSerial,
+            Some(SerialConsistency::Serial) | None => Consistency::Serial,
+        });
+
+        let max_attempts = options.get_max_attempts();
+        let mut current: Option<R> = self.read_current_row(&read, &read_values).await?;
+        for _ in 0..max_attempts {
+            let Some((new_row, values)) = compute(current.as_ref()) else {
+                return Ok(CasOutcome::Aborted(current));
+            };
+            let outcome_unknown = match self.execute_unpaged(update, values).await {
+                Ok(result) => {
+                    // The current values of the row are not needed, as it is read again anyway.
+                    if lwt::is_applied(result)? {
+                        return Ok(CasOutcome::Applied(new_row));
+                    }
+                    false
+                }
+                Err(err) if lwt::is_cas_write_timeout(&err) => true,
+                Err(err) => return Err(LwtError::Execution(err).into()),
+            };
+            current = self.read_current_row(&read, &read_values).await?;
+            if outcome_unknown && current.as_ref() == Some(&new_row) {
+                return Ok(CasOutcome::Applied(new_row));
+            }
+        }
+        Err(CasError::AttemptsExhausted(max_attempts))
+    }
+
+    async fn read_current_row<R>(
+        &self,
+        read: &PreparedStatement,
+        values: impl SerializeRow,
+    ) -> Result<Option<R>, CasError>
+    where
+        R: for<'frame, 'metadata> DeserializeRow<'frame, 'metadata>,
+    {
+        self.execute_unpaged(read, values)
+            .await
+            .map_err(CasError::ReadExecution)?
+            .into_rows_result()
+            .map_err(CasError::ReadIntoRowsResult)?
+            .maybe_first_row::<R>()
+            .map_err(CasError::ReadRow)
+    }
+
     /// Executes a prepared statement, restricting results to single page.
     /// Optionally continues fetching results from a saved point.
     ///
@@ -913,6 +1120,10 @@ impl Session {
         let connection_config = ConnectionConfig {
             local_ip_address: config.local_ip_address,
             shard_aware_local_port_range: config.shard_aware_local_port_range,
+            protocol_version: Arc::new(match config.protocol_version {
+                Some(version) => SharedProtocolVersion::fixed(version),
+                None => SharedProtocolVersion::negotiable(),
+            }),
             compression: config.compression,
             tcp_nodelay: config.tcp_nodelay,
             tcp_keepalive_interval: config.tcp_keepalive_interval,
@@ -971,6 +1182,9 @@ impl Session {
             tracing_info_fetch_attempts: config.tracing_info_fetch_attempts,
             tracing_info_fetch_interval: config.tracing_info_fetch_interval,
             tracing_info_fetch_consistency: config.tracing_info_fetch_consistency,
+            request_interceptor: config.request_interceptor,
+            request_throttler: config.request_throttler,
+            request_tracker: Arc::new(RequestTracker::new()),
         };
 
         if let Some(keyspace_name) = config.used_keyspace {
@@ -1037,6 +1251,42 @@ impl Session {
             .unwrap_or_else(|| self.get_default_execution_profile_handle())
             .access();
 
+        let result = self
+            .request_tracker
+            .run(
+                async {
+                    let _permit = self.acquire_throttling_permit(&execution_profile).await?;
+                    self.query_with_profile(
+                        statement,
+                        values,
+                        page_size,
+                        paging_state,
+                        Arc::clone(&execution_profile),
+                    )
+                    .await
+                },
+                || ExecutionError::SessionShutDown,
+            )
+            .await;
+
+        match self.request_interceptor(&execution_profile) {
+            Some(interceptor) => interceptor::intercept_page_result(
+                interceptor.as_ref(),
+                InterceptedStatement::Unprepared(statement),
+                result,
+            ),
+            None => result,
+        }
+    }
+
+    async fn query_with_profile(
+        &self,
+        statement: &Statement,
+        values: impl SerializeRow,
+        page_size: Option<PageSize>,
+        paging_state: PagingState,
+        execution_profile: Arc<ExecutionProfileInner>,
+    ) -> Result<(QueryResult, PagingStateResponse), ExecutionError> {
         let statement_info = RoutingInfo {
             consistency: statement
                 .config
@@ -1049,6 +1299,17 @@ impl Session {
             ..Default::default()
         };
 
+        let custom_payload = statement
+            .config
+            .determine_custom_payload(execution_profile.custom_payload.as_ref());
+
+        let request_template = RequestTemplate {
+            statement: InterceptedStatement::Unprepared(statement),
+            values: None,
+            serial_consistency: statement_info.serial_consistency,
+            custom_payload,
+        };
+
         let span = RequestSpan::new_query(&statement.contents);
         let span_ref = &span;
         let (run_request_result, coordinator): (
@@ -1059,26 +1320,24 @@ impl Session {
                 statement_info,
                 &statement.config,
                 execution_profile,
-                |connection: Arc<Connection>,
-                 consistency: Consistency,
-                 execution_profile: &ExecutionProfileInner| {
-                    let serial_consistency = statement
-                        .config
-                        .serial_consistency
-                        .unwrap_or(execution_profile.serial_consistency);
+                request_template,
+                |connection: Arc<Connection>, request: InterceptedRequest| {
                     // Needed to avoid moving query and values into async move block
                     let values_ref = &values;
                     let paging_state_ref = &paging_state;
                     async move {
+                        let modified_statement = request.modified_statement();
+                        let statement = modified_statement.as_ref().unwrap_or(statement);
                         if values_ref.is_empty() {
                             span_ref.record_request_size(0);
                             connection
                                 .query_raw_with_consistency(
                                     statement,
-                                    consistency,
-                                    serial_consistency,
+                                    request.consistency,
+                                    request.serial_consistency,
                                     page_size,
                                     paging_state_ref.clone(),
+                                    request.custom_payload.as_deref(),
                                 )
                                 .await
                                 .and_then(QueryResponse::into_non_error_query_response)
@@ -1090,10 +1349,11 @@ impl Session {
                                 .execute_raw_with_consistency(
                                     &prepared,
                                     &serialized,
-                                    consistency,
-                                    serial_consistency,
+                                    request.consistency,
+                                    request.serial_consistency,
                                     page_size,
                                     paging_state_ref.clone(),
+                                    request.custom_payload.as_deref(),
                                 )
                                 .await
                                 .and_then(QueryResponse::into_non_error_query_response)
@@ -1110,6 +1370,7 @@ impl Session {
                 response: NonErrorResponse::Result(result::Result::Void),
                 tracing_id: None,
                 warnings: Vec::new(),
+                custom_payload: None,
             },
             RunRequestResult::Completed(response) => response,
         };
@@ -1168,12 +1429,18 @@ impl Session {
             .get_execution_profile_handle()
             .unwrap_or_else(|| self.get_default_execution_profile_handle())
             .access();
+        let request_interceptor = self.request_interceptor(&execution_profile).cloned();
+        let request_throttler = self.request_throttler(&execution_profile).cloned();
+        let in_flight = self.track_pager()?;
 
         if values.is_empty() {
             QueryPager::new_for_query(
                 statement,
                 execution_profile,
+                request_interceptor,
+                request_throttler,
                 self.cluster.get_state(),
+                in_flight,
                 #[cfg(feature = "metrics")]
                 Arc::clone(&self.metrics),
             )
@@ -1189,7 +1456,10 @@ impl Session {
                 prepared,
                 values,
                 execution_profile,
+                request_interceptor,
+                request_throttler,
                 cluster_state: self.cluster.get_state(),
+                in_flight,
                 #[cfg(feature = "metrics")]
                 metrics: Arc::clone(&self.metrics),
             })
@@ -1218,6 +1488,8 @@ impl Session {
     ///
     /// # Arguments
     /// * `statement` - statement to prepare, can be just a `&str` or the [`Statement`] struct.
+    ///   If the keyspace is set on the [`Statement`] (see [`Statement::set_keyspace`]),
+    ///   the statement is prepared in that keyspace (requires protocol v5).
     ///
     /// # Example
     /// ```rust
@@ -1400,6 +1672,47 @@ impl Session {
         serialized_values: &SerializedValues,
         page_size: Option<PageSize>,
         paging_state: PagingState,
+    ) -> Result<(QueryResult, PagingStateResponse), ExecutionError> {
+        let execution_profile = prepared
+            .get_execution_profile_handle()
+            .unwrap_or_else(|| self.get_default_execution_profile_handle())
+            .access();
+
+        let result = self
+            .request_tracker
+            .run(
+                async {
+                    let _permit = self.acquire_throttling_permit(&execution_profile).await?;
+                    self.execute_with_profile(
+                        prepared,
+                        serialized_values,
+                        page_size,
+                        paging_state,
+                        Arc::clone(&execution_profile),
+                    )
+                    .await
+                },
+                || ExecutionError::SessionShutDown,
+            )
+            .await;
+
+        match self.request_interceptor(&execution_profile) {
+            Some(interceptor) => interceptor::intercept_page_result(
+                interceptor.as_ref(),
+                InterceptedStatement::Prepared(prepared),
+                result,
+            ),
+            None => result,
+        }
+    }
+
+    async fn execute_with_profile(
+        &self,
+        prepared: &PreparedStatement,
+        serialized_values: &SerializedValues,
+        page_size: Option<PageSize>,
+        paging_state: PagingState,
+        execution_profile: Arc<ExecutionProfileInner>,
     ) -> Result<(QueryResult, PagingStateResponse), ExecutionError> {
         let values_ref = &serialized_values;
         let paging_state_ref = &paging_state;
@@ -1409,13 +1722,12 @@ impl Session {
             .map_err(PartitionKeyError::into_execution_error)?
             .unzip();
 
-        let execution_profile = prepared
-            .get_execution_profile_handle()
-            .unwrap_or_else(|| self.get_default_execution_profile_handle())
-            .access();
-
         let table_spec = prepared.get_table_spec();
 
+        let custom_payload = prepared
+            .config
+            .determine_custom_payload(execution_profile.custom_payload.as_ref());
+
         let statement_info = RoutingInfo {
             consistency: prepared
                 .config
@@ -1430,6 +1742,13 @@ impl Session {
             is_confirmed_lwt: prepared.is_confirmed_lwt(),
         };
 
+        let request_template = RequestTemplate {
+            statement: InterceptedStatement::Prepared(prepared),
+            values: Some(serialized_values),
+            serial_consistency: statement_info.serial_consistency,
+            custom_payload,
+        };
+
         let span = RequestSpan::new_prepared(
             partition_key.as_ref().map(|pk| pk.iter()),
             token,
@@ -1452,26 +1771,21 @@ impl Session {
                 statement_info,
                 &prepared.config,
                 execution_profile,
-                |connection: Arc<Connection>,
-                 consistency: Consistency,
-                 execution_profile: &ExecutionProfileInner| {
-                    let serial_consistency = prepared
-                        .config
-                        .serial_consistency
-                        .unwrap_or(execution_profile.serial_consistency);
-                    async move {
-                        connection
-                            .execute_raw_with_consistency(
-                                prepared,
-                                values_ref,
-                                consistency,
-                                serial_consistency,
-                                page_size,
-                                paging_state_ref.clone(),
-                            )
-                            .await
-                            .and_then(QueryResponse::into_non_error_query_response)
-                    }
+                request_template,
+                |connection: Arc<Connection>, request: InterceptedRequest| async move {
+                    connection
+                        .execute_raw_with_consistency(
+                            prepared,
+                            // Contains the server-side timeout bound for this attempt, if any.
+                            request.values().unwrap_or(values_ref),
+                            request.consistency,
+                            request.serial_consistency,
+                            page_size,
+                            paging_state_ref.clone(),
+                            request.custom_payload.as_deref(),
+                        )
+                        .await
+                        .and_then(QueryResponse::into_non_error_query_response)
                 },
                 &span,
             )
@@ -1483,6 +1797,7 @@ impl Session {
                 response: NonErrorResponse::Result(result::Result::Void),
                 tracing_id: None,
                 warnings: Vec::new(),
+                custom_payload: None,
             },
             RunRequestResult::Completed(response) => response,
         };
@@ -1509,11 +1824,18 @@ impl Session {
             .unwrap_or_else(|| self.get_default_execution_profile_handle())
             .access();
 
+        let request_interceptor = self.request_interceptor(&execution_profile).cloned();
+        let request_throttler = self.request_throttler(&execution_profile).cloned();
+        let in_flight = self.track_pager()?;
+
         QueryPager::new_for_prepared_statement(PreparedPagerConfig {
             prepared,
             values: serialized_values,
             execution_profile,
+            request_interceptor,
+            request_throttler,
             cluster_state: self.cluster.get_state(),
+            in_flight,
             #[cfg(feature = "metrics")]
             metrics: Arc::clone(&self.metrics),
         })
@@ -1525,6 +1847,37 @@ impl Session {
         &self,
         batch: &Batch,
         values: impl BatchValues,
+    ) -> Result<QueryResult, ExecutionError> {
+        let execution_profile = batch
+            .get_execution_profile_handle()
+            .unwrap_or_else(|| self.get_default_execution_profile_handle())
+            .access();
+
+        let result = self
+            .request_tracker
+            .run(
+                async {
+                    let _permit = self.acquire_throttling_permit(&execution_profile).await?;
+                    self.batch_with_profile(batch, values, Arc::clone(&execution_profile))
+                        .await
+                },
+                || ExecutionError::SessionShutDown,
+            )
+            .await;
+
+        match self.request_interceptor(&execution_profile) {
+            Some(interceptor) => {
+                interceptor.after_request(InterceptedStatement::Batch(batch), result)
+            }
+            None => result,
+        }
+    }
+
+    async fn batch_with_profile(
+        &self,
+        batch: &Batch,
+        values: impl BatchValues,
+        execution_profile: Arc<ExecutionProfileInner>,
     ) -> Result<QueryResult, ExecutionError> {
         // Shard-awareness behavior for batch will be to pick shard based on first batch statement's shard
         // If users batch statements by shard, they will be rewarded with full shard awareness
@@ -1537,11 +1890,6 @@ impl Session {
             ));
         }
 
-        let execution_profile = batch
-            .get_execution_profile_handle()
-            .unwrap_or_else(|| self.get_default_execution_profile_handle())
-            .access();
-
         let consistency = batch
             .config
             .consistency
@@ -1571,6 +1919,17 @@ impl Session {
             is_confirmed_lwt: false,
         };
 
+        let custom_payload = batch
+            .config
+            .determine_custom_payload(execution_profile.custom_payload.as_ref());
+
+        let request_template = RequestTemplate {
+            statement: InterceptedStatement::Batch(batch),
+            values: None,
+            serial_consistency,
+            custom_payload,
+        };
+
         let span = RequestSpan::new_batch();
 
         let (run_request_result, coordinator): (
@@ -1581,24 +1940,18 @@ impl Session {
                 statement_info,
                 &batch.config,
                 execution_profile,
-                |connection: Arc<Connection>,
-                 consistency: Consistency,
-                 execution_profile: &ExecutionProfileInner| {
-                    let serial_consistency = batch
-                        .config
-                        .serial_consistency
-                        .unwrap_or(execution_profile.serial_consistency);
-                    async move {
-                        connection
-                            .batch_with_consistency(
-                                batch,
-                                values_ref,
-                                consistency,
-                                serial_consistency,
-                            )
-                            .await
-                            .and_then(QueryResponse::into_non_error_query_response)
-                    }
+                request_template,
+                |connection: Arc<Connection>, request: InterceptedRequest| async move {
+                    connection
+                        .batch_with_consistency(
+                            batch,
+                            values_ref,
+                            request.consistency,
+                            request.serial_consistency,
+                            request.custom_payload.as_deref(),
+                        )
+                        .await
+                        .and_then(QueryResponse::into_non_error_query_response)
                 },
                 &span,
             )
@@ -1646,6 +1999,7 @@ impl Session {
     /// ```
     pub async fn prepare_batch(&self, batch: &Batch) -> Result<Batch, PrepareError> {
         let mut prepared_batch = batch.clone();
+        prepared_batch.apply_keyspace_to_statements();
 
         try_join_all(
             prepared_batch
@@ -1729,6 +2083,62 @@ impl Session {
         self.cluster.refresh_metadata().await
     }
 
+    /// Shuts the session down gracefully.
+    ///
+    /// The session stops accepting new requests immediately: they fail with
+    /// [`ExecutionError::SessionShutDown`]. Requests and pagers already in flight are given
+    /// `timeout` to complete; those still running after that are cancelled.
+    /// Finally, the control connection and all connection pools are closed
+    /// and the metadata is no longer refreshed.
+    ///
+    /// Dropping a session without shutting it down closes the connections abruptly,
+    /// failing the requests in flight.
+    ///
+    /// ```rust
+    /// # use scylla::client::session::Session;
+    /// # async fn check_only_compiles(session: &Session) {
+    /// use std::time::Duration;
+    ///
+    /// let report = session.shutdown(Duration::from_secs(10)).await;
+    /// if !report.is_clean() {
+    ///     println!(
+    ///         "Cancelled {} requests and {} pagers",
+    ///         report.cancelled_requests, report.cancelled_pagers
+    ///     );
+    /// }
+    /// # }
+    /// ```
+    pub async fn shutdown(&self, timeout: Duration) -> ShutdownReport {
+        let start = tokio::time::Instant::now();
+        let (in_flight_requests, in_flight_pagers) = self.request_tracker.stop_accepting();
+        debug!(
+            "Shutting down the session, {} requests and {} pagers in flight",
+            in_flight_requests, in_flight_pagers
+        );
+
+        let (cancelled_requests, cancelled_pagers) =
+            match tokio::time::timeout(timeout, self.request_tracker.wait_until_drained()).await {
+                Ok(()) => (0, 0),
+                Err(_) => self.request_tracker.cancel(),
+            };
+        if cancelled_requests > 0 || cancelled_pagers > 0 {
+            warn!(
+                "Session shutdown deadline passed, cancelled {} requests and {} pagers",
+                cancelled_requests, cancelled_pagers
+            );
+        }
+
+        self.cluster.shutdown().await;
+
+        ShutdownReport {
+            in_flight_requests,
+            in_flight_pagers,
+            cancelled_requests,
+            cancelled_pagers,
+            elapsed: start.elapsed(),
+        }
+    }
+
     /// Access metrics collected by the driver\
     /// Driver collects various metrics like number of queries or query latencies.
     /// They can be read using this method
@@ -1745,6 +2155,107 @@ impl Session {
         self.cluster.get_state()
     }
 
+    /// Checks that rows of the table can be deserialized to `RowT`, using the schema
+    /// metadata fetched from the cluster.
+    ///
+    /// Columns which `RowT` doesn't deserialize are allowed, as they can be omitted
+    /// from the selected columns. Unlike the type check performed when a statement is
+    /// executed, the validation doesn't stop at the first error, but reports
+    /// every mismatch found. Types which match columns by position, e.g. tuples,
+    /// are checked against all the columns of the table, in the order of `SELECT *`.
+    ///
+    /// ```rust
+    /// # use scylla::client::session::Session;
+    /// # async fn check_only_compiles(session: &Session) -> Result<(), Box<dyn std::error::Error>> {
+    /// use scylla::{DeserializeRow, DeserializeValue};
+    ///
+    /// #[derive(DeserializeValue)]
+    /// struct Address {
+    ///     street: Option<String>,
+    ///     city: Option<String>,
+    /// }
+    ///
+    /// #[derive(DeserializeRow)]
+    /// struct User {
+    ///     id: i32,
+    ///     address: Option<Address>,
+    /// }
+    ///
+    /// session.refresh_metadata().await?;
+    /// session.validate_udt::<Address>("ks", "address")?;
+    /// session.validate_row_type::<User>("ks", "users")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate_row_type<RowT>(
+        &self,
+        keyspace: &str,
+        table: &str,
+    ) -> Result<(), SchemaValidationError>
+    where
+        RowT: for<'frame, 'metadata> DeserializeRow<'frame, 'metadata>,
+    {
+        validation::validate_row_type::<RowT>(&self.get_cluster_state().keyspaces, keyspace, table)
+    }
+
+    /// Checks that `values` can be used to write rows of the table, using the schema
+    /// metadata fetched from the cluster.
+    ///
+    /// The check serializes `values` to the columns of the table, so it has to be given
+    /// a sample value. Values which are `None` are serialized as nulls, so their types
+    /// are not checked. Every column of the primary key has to be provided.
+    /// Otherwise, the validation works in the same way as [Session::validate_row_type],
+    /// except that fields without a column are reported only up to the first field
+    /// whose type doesn't match, as serialization doesn't get past that field.
+    pub fn validate_row_values(
+        &self,
+        keyspace: &str,
+        table: &str,
+        values: &(impl SerializeRow + ?Sized),
+    ) -> Result<(), SchemaValidationError> {
+        validation::validate_row_values(
+            &self.get_cluster_state().keyspaces,
+            keyspace,
+            table,
+            values,
+        )
+    }
+
+    /// Checks that values of the user defined type can be deserialized to `T`,
+    /// using the schema metadata fetched from the cluster.
+    ///
+    /// The validation reports every mismatch found, see [Session::validate_row_type].
+    pub fn validate_udt<T>(
+        &self,
+        keyspace: &str,
+        type_name: &str,
+    ) -> Result<(), SchemaValidationError>
+    where
+        T: for<'frame, 'metadata> DeserializeValue<'frame, 'metadata>,
+    {
+        validation::validate_udt::<T>(&self.get_cluster_state().keyspaces, keyspace, type_name)
+    }
+
+    /// Checks that `value` can be serialized as a value of the user defined type,
+    /// using the schema metadata fetched from the cluster.
+    ///
+    /// As with [Session::validate_row_values], fields which are `None` are not type checked,
+    /// and fields without a counterpart in the user defined type are reported only up
+    /// to the first field whose type doesn't match.
+    pub fn validate_udt_value(
+        &self,
+        keyspace: &str,
+        type_name: &str,
+        value: &(impl SerializeValue + ?Sized),
+    ) -> Result<(), SchemaValidationError> {
+        validation::validate_udt_value(
+            &self.get_cluster_state().keyspaces,
+            keyspace,
+            type_name,
+            value,
+        )
+    }
+
     /// Get [`TracingInfo`] of a traced query performed earlier
     ///
     /// See [the book](https://rust-driver.docs.scylladb.com/stable/tracing/tracing.html)
@@ -1853,12 +2364,13 @@ impl Session {
     /// On success, this request's result is returned.
     // I tried to make this closures take a reference instead of an Arc but failed
     // maybe once async closures get stabilized this can be fixed
-    async fn run_request<'a, QueryFut, ResT>(
+    async fn run_request<'a, 'r, QueryFut, ResT>(
         &'a self,
         statement_info: RoutingInfo<'a>,
         statement_config: &'a StatementConfig,
         execution_profile: Arc<ExecutionProfileInner>,
-        run_request_once: impl Fn(Arc<Connection>, Consistency, &ExecutionProfileInner) -> QueryFut,
+        request_template: RequestTemplate<'r>,
+        run_request_once: impl Fn(Arc<Connection>, InterceptedRequest<'r>) -> QueryFut,
         request_span: &'a RequestSpan,
     ) -> Result<(RunRequestResult<ResT>, Coordinator), ExecutionError>
     where
@@ -1876,6 +2388,16 @@ impl Session {
             .as_deref()
             .unwrap_or(execution_profile.load_balancing_policy.as_ref());
 
+        let request_interceptor = self
+            .request_interceptor(&execution_profile)
+            .map(|interceptor| interceptor.as_ref());
+
+        let effective_timeout = statement_config
+            .request_timeout
+            .or(execution_profile.request_timeout);
+        let deadline = effective_timeout
+            .map(|timeout| RequestDeadline::new(timeout, execution_profile.min_attempt_budget));
+
         let runner = async {
             let cluster_state = self.cluster.get_state();
             let request_plan =
@@ -1950,6 +2472,10 @@ impl Session {
                                 load_balancing_policy: load_balancer,
                                 query_info: &statement_info,
                                 request_span,
+                                request_template: &request_template,
+                                request_interceptor,
+                                deadline,
+                                is_speculative,
                             },
                         )
                     };
@@ -1987,6 +2513,10 @@ impl Session {
                             load_balancing_policy: load_balancer,
                             query_info: &statement_info,
                             request_span,
+                            request_template: &request_template,
+                            request_interceptor,
+                            deadline,
+                            is_speculative: false,
                         },
                     )
                     .await
@@ -1995,9 +2525,6 @@ impl Session {
             }
         };
 
-        let effective_timeout = statement_config
-            .request_timeout
-            .or(execution_profile.request_timeout);
         let result = match effective_timeout {
             Some(timeout) => tokio::time::timeout(timeout, runner).await.unwrap_or_else(
                 |_: tokio::time::error::Elapsed| {
@@ -2024,12 +2551,12 @@ impl Session {
     /// If request fails, retry session is used to perform retries.
     ///
     /// Returns None, if provided plan is empty.
-    async fn run_request_speculative_fiber<'a, QueryFut, ResT>(
+    async fn run_request_speculative_fiber<'a, 'r, QueryFut, ResT>(
         &'a self,
         request_plan: impl Iterator<Item = (NodeRef<'a>, Shard)>,
-        run_request_once: impl Fn(Arc<Connection>, Consistency, &ExecutionProfileInner) -> QueryFut,
+        run_request_once: impl Fn(Arc<Connection>, InterceptedRequest<'r>) -> QueryFut,
         execution_profile: &ExecutionProfileInner,
-        mut context: ExecuteRequestContext<'a>,
+        mut context: ExecuteRequestContext<'a, 'r>,
     ) -> Option<Result<(RunRequestResult<ResT>, Coordinator), RequestError>>
     where
         QueryFut: Future<Output = Result<ResT, RequestAttemptError>>,
@@ -2043,6 +2570,22 @@ impl Session {
         'nodes_in_plan: for (node, shard) in request_plan {
             let span = trace_span!("Executing request", node = %node.address, shard = %shard);
             'same_node_retries: loop {
+                if let Some(deadline) = &context.deadline {
+                    if !deadline.allows_attempt() {
+                        trace!(
+                            parent: &span,
+                            remaining_time = ?deadline.remaining(),
+                            "Not enough time left to start an attempt"
+                        );
+                        return match last_error {
+                            Some(error) => Some(Err(error)),
+                            // Let the other fibers complete the request.
+                            None if context.is_speculative => None,
+                            None => Some(Err(RequestError::RequestTimeout(deadline.timeout))),
+                        };
+                    }
+                }
+
                 trace!(parent: &span, "Execution started");
                 let connection = match node.connection_for_shard(shard).await {
                     Ok(connection) => connection,
@@ -2059,6 +2602,29 @@ impl Session {
                 };
                 context.request_span.record_shard_id(&connection);
 
+                let coordinator =
+                    Coordinator::new(node, node.sharder().is_some().then_some(shard), &connection);
+
+                let mut request = context
+                    .request_template
+                    .attempt(current_consistency, coordinator.clone());
+                if let Some(deadline) = &context.deadline {
+                    if execution_profile.propagate_timeout_to_server {
+                        request.bind_server_side_timeout(deadline.remaining());
+                    }
+                }
+                let intercepted_error = match context
+                    .request_interceptor
+                    .map(|interceptor| interceptor.before_attempt(&mut request))
+                {
+                    Some(InterceptorDecision::Reject(reason)) => {
+                        trace!(parent: &span, reason = %reason, "Request rejected");
+                        return Some(Err(RequestError::RejectedByInterceptor(reason)));
+                    }
+                    Some(InterceptorDecision::FailAttempt(error)) => Some(error),
+                    Some(InterceptorDecision::Proceed) | None => None,
+                };
+
                 #[cfg(feature = "metrics")]
                 self.metrics.inc_total_nonpaged_queries();
                 let request_start = std::time::Instant::now();
@@ -2069,15 +2635,23 @@ impl Session {
                     connection = %connect_address,
                     "Sending"
                 );
-                let coordinator =
-                    Coordinator::new(node, node.sharder().is_some().then_some(shard), &connection);
 
                 let attempt_id: Option<history::AttemptId> =
                     context.log_attempt_start(connect_address);
-                let request_result: Result<ResT, RequestAttemptError> =
-                    run_request_once(connection, current_consistency, execution_profile)
-                        .instrument(span.clone())
-                        .await;
+                let request_result: Result<ResT, RequestAttemptError> = match intercepted_error {
+                    Some(error) => Err(error),
+                    None => {
+                        run_request_once(connection, request.clone())
+                            .instrument(span.clone())
+                            .await
+                    }
+                };
+                let request_result = match context.request_interceptor {
+                    Some(interceptor) => {
+                        interceptor::intercept_attempt_result(interceptor, &request, request_result)
+                    }
+                    None => request_result,
+                };
 
                 let elapsed = request_start.elapsed();
                 let request_error: RequestAttemptError = match request_result {
@@ -2100,7 +2674,18 @@ impl Session {
                             "Request failed"
                         );
                         #[cfg(feature = "metrics")]
-                        self.metrics.inc_failed_nonpaged_queries();
+                        {
+                            self.metrics.inc_failed_nonpaged_queries();
+                            if matches!(
+                                e,
+                                RequestAttemptError::DbError(
+                                    crate::errors::DbError::RateLimitReached { .. },
+                                    _
+                                )
+                            ) {
+                                self.metrics.inc_rate_limit_errors(node.host_id);
+                            }
+                        }
                         context.load_balancing_policy.on_request_failure(
                             context.query_info,
                             elapsed,
@@ -2118,6 +2703,7 @@ impl Session {
                     consistency: context
                         .consistency_set_on_statement
                         .unwrap_or(execution_profile.consistency),
+                    remaining_time: context.deadline.as_ref().map(RequestDeadline::remaining),
                 };
 
                 let retry_decision = context.retry_session.decide_should_retry(query_info);
@@ -2143,6 +2729,13 @@ impl Session {
                         current_consistency = new_cl.unwrap_or(current_consistency);
                         continue 'nodes_in_plan;
                     }
+                    RetryDecision::RetryNextTargetAfter(new_cl, delay) => {
+                        #[cfg(feature = "metrics")]
+                        self.metrics.inc_retries_num();
+                        current_consistency = new_cl.unwrap_or(current_consistency);
+                        tokio::time::sleep(delay).await;
+                        continue 'nodes_in_plan;
+                    }
                     RetryDecision::DontRetry => break 'nodes_in_plan,
 
                     RetryDecision::IgnoreWriteError => {
@@ -2224,6 +2817,56 @@ impl Session {
     pub fn get_default_execution_profile_handle(&self) -> &ExecutionProfileHandle {
         &self.default_execution_profile_handle
     }
+
+    /// Registers a new pager, unless the session is being shut down.
+    fn track_pager(&self) -> Result<InFlightGuard, PagerExecutionError> {
+        self.request_tracker
+            .track(InFlightKind::Pager)
+            .ok_or(PagerExecutionError::NextPageError(
+                NextPageError::RequestFailure(RequestError::SessionShutDown),
+            ))
+    }
+
+    /// Returns the request interceptor to use with the given execution profile:
+    /// the profile's one if set, or the session's one otherwise.
+    fn request_interceptor<'a>(
+        &'a self,
+        execution_profile: &'a ExecutionProfileInner,
+    ) -> Option<&'a Arc<dyn RequestInterceptor>> {
+        execution_profile
+            .request_interceptor
+            .as_ref()
+            .or(self.request_interceptor.as_ref())
+    }
+
+    /// Returns the request throttler to use with the given execution profile:
+    /// the profile's one if set, or the session's one otherwise.
+    fn request_throttler<'a>(
+        &'a self,
+        execution_profile: &'a ExecutionProfileInner,
+    ) -> Option<&'a Arc<dyn RequestThrottler>> {
+        execution_profile
+            .request_throttler
+            .as_ref()
+            .or(self.request_throttler.as_ref())
+    }
+
+    /// Waits for a permit of the request throttler, if there is one.
+    async fn acquire_throttling_permit(
+        &self,
+        execution_profile: &ExecutionProfileInner,
+    ) -> Result<Option<ThrottlingPermit>, ExecutionError> {
+        let Some(request_throttler) = self.request_throttler(execution_profile) else {
+            return Ok(None);
+        };
+        let permit = throttler::acquire_permit(
+            request_throttler.as_ref(),
+            #[cfg(feature = "metrics")]
+            &self.metrics,
+        )
+        .await?;
+        Ok(Some(permit))
+    }
 }
 
 // run_request, run_request_speculative_fiber, etc have a template type called ResT.
@@ -2241,7 +2884,7 @@ impl AllowedRunRequestResTType for Uuid {}
 impl AllowedRunRequestResTType for QueryResult {}
 impl AllowedRunRequestResTType for NonErrorQueryResponse {}
 
-struct ExecuteRequestContext<'a> {
+struct ExecuteRequestContext<'a, 'r> {
     is_idempotent: bool,
     consistency_set_on_statement: Option<Consistency>,
     retry_session: Box<dyn RetrySession>,
@@ -2249,6 +2892,40 @@ struct ExecuteRequestContext<'a> {
     load_balancing_policy: &'a dyn load_balancing::LoadBalancingPolicy,
     query_info: &'a load_balancing::RoutingInfo<'a>,
     request_span: &'a RequestSpan,
+    request_template: &'a RequestTemplate<'r>,
+    request_interceptor: Option<&'a dyn RequestInterceptor>,
+    deadline: Option<RequestDeadline>,
+    is_speculative: bool,
+}
+
+/// The point in time at which a request times out, shared by all its attempts.
+#[derive(Clone, Copy)]
+struct RequestDeadline {
+    timeout: Duration,
+    deadline: tokio::time::Instant,
+    min_attempt_budget: Option<Duration>,
+}
+
+impl RequestDeadline {
+    fn new(timeout: Duration, min_attempt_budget: Option<Duration>) -> Self {
+        Self {
+            timeout,
+            deadline: tokio::time::Instant::now() + timeout,
+            min_attempt_budget,
+        }
+    }
+
+    fn remaining(&self) -> Duration {
+        self.deadline
+            .saturating_duration_since(tokio::time::Instant::now())
+    }
+
+    /// Returns false if there is too little time left to start another attempt.
+    fn allows_attempt(&self) -> bool {
+        self.min_attempt_budget.map_or(true, |min_attempt_budget| {
+            self.remaining() >= min_attempt_budget
+        })
+    }
 }
 
 struct HistoryData<'a> {
@@ -2257,7 +2934,7 @@ struct HistoryData<'a> {
     speculative_id: Option<history::SpeculativeId>,
 }
 
-impl ExecuteRequestContext<'_> {
+impl ExecuteRequestContext<'_, '_> {
     fn log_attempt_start(&self, node_addr: SocketAddr) -> Option<history::AttemptId> {
         self.history_data.as_ref().map(|hd| {
             hd.listener
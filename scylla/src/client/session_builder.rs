@@ -4,7 +4,7 @@
 use super::execution_profile::ExecutionProfile;
 use super::execution_profile::ExecutionProfileHandle;
 use super::session::{Session, SessionConfig};
-use super::{Compression, PoolSize, SelfIdentity, WriteCoalescingDelay};
+use super::{Compression, PoolSize, ProtocolVersion, SelfIdentity, WriteCoalescingDelay};
 use crate::authentication::{AuthenticatorProvider, PlainTextAuthenticator};
 use crate::client::session::TlsContext;
 #[cfg(feature = "unstable-cloud")]
@@ -12,6 +12,8 @@ use crate::cloud::{CloudConfig, CloudConfigError, CloudTlsProvider};
 use crate::errors::NewSessionError;
 use crate::policies::address_translator::AddressTranslator;
 use crate::policies::host_filter::HostFilter;
+use crate::policies::interceptor::{RequestInterceptor, RequestInterceptorChain};
+use crate::policies::throttler::RequestThrottler;
 use crate::policies::timestamp_generator::TimestampGenerator;
 use crate::routing::ShardAwarePortRange;
 use crate::statement::Consistency;
@@ -472,6 +474,36 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
         self
     }
 
+    /// Set the version of the CQL native protocol to use.
+    ///
+    /// By default, the version is negotiated with the cluster: the driver starts
+    /// with the highest version it implements and downgrades if a node rejects it.
+    /// Setting the version explicitly disables the negotiation, so connecting
+    /// to nodes which don't support the given version fails.
+    ///
+    /// Since protocol v5, frames are wrapped in checksummed segments
+    /// and only LZ4 compression is supported. If Snappy compression is requested
+    /// together with protocol v5, Session will fall back to no compression.
+    ///
+    /// # Example
+    /// ```
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use scylla::client::ProtocolVersion;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .protocol_version(ProtocolVersion::V5)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn protocol_version(mut self, protocol_version: ProtocolVersion) -> Self {
+        self.config.protocol_version = Some(protocol_version);
+        self
+    }
+
     /// Set the delay for schema agreement check. How often driver should ask if schema is in agreement
     /// The default is 200 milliseconds.
     ///
@@ -939,6 +971,82 @@ impl<K: SessionBuilderKind> GenericSessionBuilder<K> {
         self
     }
 
+    /// Adds a request interceptor, which can inspect and modify requests
+    /// executed by the session, and their outcomes.
+    ///
+    /// Calling this method multiple times chains the interceptors
+    /// in order of registration, see [`RequestInterceptorChain`].
+    /// The interceptor can be overridden per execution profile with
+    /// [`ExecutionProfileBuilder::request_interceptor`](crate::client::execution_profile::ExecutionProfileBuilder::request_interceptor).
+    ///
+    /// # Example
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use scylla::policies::interceptor::{InterceptedRequest, InterceptorDecision, RequestInterceptor};
+    /// # use bytes::Bytes;
+    /// #[derive(Debug)]
+    /// struct TenantTagger;
+    ///
+    /// impl RequestInterceptor for TenantTagger {
+    ///     fn before_attempt(&self, request: &mut InterceptedRequest<'_>) -> InterceptorDecision {
+    ///         request
+    ///             .custom_payload_mut()
+    ///             .insert("tenant".to_owned(), Bytes::from_static(b"acme"));
+    ///         InterceptorDecision::Proceed
+    ///     }
+    /// }
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .request_interceptor(Arc::new(TenantTagger))
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn request_interceptor(mut self, interceptor: Arc<dyn RequestInterceptor>) -> Self {
+        self.config.request_interceptor = Some(match self.config.request_interceptor.take() {
+            Some(previous) => Arc::new(RequestInterceptorChain::new(vec![previous, interceptor])),
+            None => interceptor,
+        });
+        self
+    }
+
+    /// Sets the request throttler, which bounds the concurrency and the rate of
+    /// requests executed by the session. By default, requests are not throttled.
+    ///
+    /// The throttler can be overridden per execution profile with
+    /// [`ExecutionProfileBuilder::request_throttler`](crate::client::execution_profile::ExecutionProfileBuilder::request_throttler).
+    ///
+    /// # Example
+    /// ```
+    /// # use std::time::Duration;
+    /// # use scylla::client::session::Session;
+    /// # use scylla::client::session_builder::SessionBuilder;
+    /// # use scylla::policies::throttler::DefaultRequestThrottler;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session: Session = SessionBuilder::new()
+    ///     .known_node("127.0.0.1:9042")
+    ///     .request_throttler(
+    ///         DefaultRequestThrottler::builder()
+    ///             .max_concurrent_requests(1024)
+    ///             .max_queue_length(4096)
+    ///             .max_queue_wait(Duration::from_secs(1))
+    ///             .build(),
+    ///     )
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn request_throttler(mut self, throttler: Arc<dyn RequestThrottler>) -> Self {
+        self.config.request_throttler = Some(throttler);
+        self
+    }
+
     /// Set the refresh metadata on schema agreement flag.
     /// The default is true.
     ///
@@ -1171,7 +1279,7 @@ mod tests {
     use scylla_cql::frame::types::SerialConsistency;
     use scylla_cql::Consistency;
 
-    use super::super::Compression;
+    use super::super::{Compression, ProtocolVersion};
     use super::SessionBuilder;
     use crate::client::execution_profile::{defaults, ExecutionProfile};
     use crate::cluster::node::KnownNode;
@@ -1264,6 +1372,19 @@ mod tests {
         assert_eq!(builder.config.compression, None);
     }
 
+    #[test]
+    fn protocol_version() {
+        setup_tracing();
+        let mut builder = SessionBuilder::new();
+        assert_eq!(builder.config.protocol_version, None);
+
+        builder = builder.protocol_version(ProtocolVersion::V5);
+        assert_eq!(builder.config.protocol_version, Some(ProtocolVersion::V5));
+
+        builder = builder.protocol_version(ProtocolVersion::V3);
+        assert_eq!(builder.config.protocol_version, Some(ProtocolVersion::V3));
+    }
+
     #[test]
     fn tcp_nodelay() {
         setup_tracing();
@@ -0,0 +1,220 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{watch, Notify};
+
+/// Describes the outcome of [`Session::shutdown`](crate::client::session::Session::shutdown).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ShutdownReport {
+    /// Number of requests which were in flight when the shutdown started.
+    pub in_flight_requests: usize,
+
+    /// Number of pagers which were in flight when the shutdown started.
+    pub in_flight_pagers: usize,
+
+    /// Number of requests which did not complete before the deadline
+    /// and were cancelled with [`ExecutionError::SessionShutDown`](crate::errors::ExecutionError::SessionShutDown).
+    pub cancelled_requests: usize,
+
+    /// Number of pagers which did not complete before the deadline.
+    /// Their next page fails with [`RequestError::SessionShutDown`](crate::errors::RequestError::SessionShutDown).
+    pub cancelled_pagers: usize,
+
+    /// Time it took to drain the requests and close the connections.
+    pub elapsed: Duration,
+}
+
+impl ShutdownReport {
+    /// Returns true if all in-flight requests and pagers completed before the deadline.
+    pub fn is_clean(&self) -> bool {
+        self.cancelled_requests == 0 && self.cancelled_pagers == 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum InFlightKind {
+    Request,
+    Pager,
+}
+
+/// Keeps track of requests and pagers executed by a `Session`,
+/// so that the session can drain them when it is shut down.
+#[derive(Debug)]
+pub(crate) struct RequestTracker {
+    accepting: AtomicBool,
+    in_flight_requests: AtomicUsize,
+    in_flight_pagers: AtomicUsize,
+    // Notified when the last in-flight request or pager completes after the shutdown started.
+    drained: Notify,
+    // Set to true when the requests still in flight at the deadline are to be cancelled.
+    cancel: watch::Sender<bool>,
+}
+
+impl RequestTracker {
+    pub(crate) fn new() -> Self {
+        Self {
+            accepting: AtomicBool::new(true),
+            in_flight_requests: AtomicUsize::new(0),
+            in_flight_pagers: AtomicUsize::new(0),
+            drained: Notify::new(),
+            cancel: watch::channel(false).0,
+        }
+    }
+
+    fn counter(&self, kind: InFlightKind) -> &AtomicUsize {
+        match kind {
+            InFlightKind::Request => &self.in_flight_requests,
+            InFlightKind::Pager => &self.in_flight_pagers,
+        }
+    }
+
+    fn in_flight(&self) -> (usize, usize) {
+        (
+            self.in_flight_requests.load(Ordering::SeqCst),
+            self.in_flight_pagers.load(Ordering::SeqCst),
+        )
+    }
+
+    /// Registers a new request or pager.
+    /// Returns None if the session is being shut down.
+    pub(crate) fn track(self: &Arc<Self>, kind: InFlightKind) -> Option<InFlightGuard> {
+        // The counter is incremented before checking the flag, so that
+        // `wait_until_drained` never misses a request that got through.
+        self.counter(kind).fetch_add(1, Ordering::SeqCst);
+        let guard = InFlightGuard {
+            tracker: Arc::clone(self),
+            kind,
+            cancel: self.cancel.subscribe(),
+        };
+        self.accepting.load(Ordering::SeqCst).then_some(guard)
+    }
+
+    /// Runs a request, unless the session is being shut down.
+    /// The request is cancelled if it is still running at the shutdown deadline.
+    pub(crate) async fn run<T, E>(
+        self: &Arc<Self>,
+        request: impl Future<Output = Result<T, E>>,
+        shut_down: impl Fn() -> E,
+    ) -> Result<T, E> {
+        let mut guard = self.track(InFlightKind::Request).ok_or_else(&shut_down)?;
+        tokio::select! {
+            result = request => result,
+            () = guard.cancelled() => Err(shut_down()),
+        }
+    }
+
+    /// Stops accepting new requests and returns the number of requests
+    /// and pagers in flight.
+    pub(crate) fn stop_accepting(&self) -> (usize, usize) {
+        self.accepting.store(false, Ordering::SeqCst);
+        self.in_flight()
+    }
+
+    /// Waits until there are no requests nor pagers in flight.
+    /// Must be called after `stop_accepting`.
+    pub(crate) async fn wait_until_drained(&self) {
+        loop {
+            let notified = self.drained.notified();
+            tokio::pin!(notified);
+            // Register for the notification before checking the counters,
+            // so that we don't miss it.
+            notified.as_mut().enable();
+            if self.in_flight() == (0, 0) {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Cancels the requests and pagers in flight and returns their number.
+    pub(crate) fn cancel(&self) -> (usize, usize) {
+        self.cancel.send_replace(true);
+        self.in_flight()
+    }
+}
+
+/// Represents a request or a pager in flight.
+/// Must be kept alive until the request completes.
+#[derive(Debug)]
+pub(crate) struct InFlightGuard {
+    tracker: Arc<RequestTracker>,
+    kind: InFlightKind,
+    cancel: watch::Receiver<bool>,
+}
+
+impl InFlightGuard {
+    /// Completes when the shutdown deadline passes.
+    pub(crate) async fn cancelled(&mut self) {
+        // The sender is owned by the tracker, which is kept alive by this guard.
+        let _ = self.cancel.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let previous = self
+            .tracker
+            .counter(self.kind)
+            .fetch_sub(1, Ordering::SeqCst);
+        if previous == 1 && !self.tracker.accepting.load(Ordering::SeqCst) {
+            self.tracker.drained.notify_waiters();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::{InFlightKind, RequestTracker};
+
+    #[tokio::test]
+    async fn tracker_drains_in_flight_requests() {
+        let tracker = Arc::new(RequestTracker::new());
+        let request = tracker.track(InFlightKind::Request).unwrap();
+        let pager = tracker.track(InFlightKind::Pager).unwrap();
+
+        assert_eq!(tracker.stop_accepting(), (1, 1));
+        assert!(tracker.track(InFlightKind::Request).is_none());
+        assert_eq!(tracker.in_flight(), (1, 1));
+
+        let drained = tokio::spawn({
+            let tracker = Arc::clone(&tracker);
+            async move { tracker.wait_until_drained().await }
+        });
+        drop(request);
+        tokio::task::yield_now().await;
+        assert!(!drained.is_finished());
+        drop(pager);
+        tokio::time::timeout(Duration::from_secs(1), drained)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn tracker_cancels_requests_at_deadline() {
+        let tracker = Arc::new(RequestTracker::new());
+        let running = tokio::spawn({
+            let tracker = Arc::clone(&tracker);
+            async move {
+                tracker
+                    .run(std::future::pending::<Result<(), ()>>(), || ())
+                    .await
+            }
+        });
+        while tracker.in_flight() != (1, 0) {
+            tokio::task::yield_now().await;
+        }
+
+        assert_eq!(tracker.stop_accepting(), (1, 0));
+        assert_eq!(tracker.run(async { Ok(()) }, || ()).await, Err(()));
+        assert_eq!(tracker.cancel(), (1, 0));
+        assert_eq!(running.await.unwrap(), Err(()));
+        tracker.wait_until_drained().await;
+    }
+}
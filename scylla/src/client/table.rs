@@ -0,0 +1,677 @@
+//! Typed CRUD operations on a single table.
+//!
+//! The [Table] trait describes how a Rust struct maps onto the rows of a table:
+//! its name, its columns and its primary key. Based on that, it provides typed
+//! `insert`, `get`, `update` and `delete` methods which generate the appropriate
+//! CQL statements and execute them through a [CachingSession], so that each
+//! statement is prepared only once.
+//!
+//! The trait is not meant to be implemented by hand - use `#[derive(Table)]`
+//! (see the [`Table`](crate::Table) derive macro) instead.
+
+use std::future::Future;
+use std::hash::BuildHasher;
+
+use scylla_cql::deserialize::row::{DeserializeRow, RowSelectors};
+use scylla_cql::frame::response::result::ColumnType;
+use scylla_cql::serialize::row::{
+    BuiltinSerializationError, BuiltinSerializationErrorKind, BuiltinTypeCheckError,
+    BuiltinTypeCheckErrorKind, RowSerializationContext, SerializeRow,
+};
+use scylla_cql::serialize::value::SerializeValue;
+use scylla_cql::serialize::writers::WrittenCellProof;
+use scylla_cql::serialize::{CellWriter, RowWriter, SerializationError};
+use scylla_cql::utils::quote_identifier;
+use thiserror::Error;
+
+use crate::client::caching_session::CachingSession;
+use crate::errors::ExecutionError;
+use crate::response::query_result::{IntoRowsResultError, MaybeFirstRowError, QueryResult};
+use crate::statement::unprepared::Statement;
+
+/// Options of an `INSERT` statement issued by [Table::insert_with].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InsertOptions {
+    ttl: Option<i32>,
+    timestamp: Option<i64>,
+    if_not_exists: bool,
+}
+
+impl InsertOptions {
+    /// Creates options of a plain `INSERT`, without TTL, timestamp or condition.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the time to live of the inserted values, in seconds (`USING TTL`).
+    pub fn ttl(mut self, ttl: i32) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Sets the write timestamp of the inserted values, in microseconds.
+    ///
+    /// The timestamp is sent along with the statement, in the same way as
+    /// [Statement::set_timestamp] does.
+    pub fn timestamp(mut self, timestamp: i64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Makes the insert conditional (`IF NOT EXISTS`).
+    ///
+    /// Such an insert is a lightweight transaction. Whether the row was
+    /// inserted can be read from the `[applied]` column of the result.
+    pub fn if_not_exists(mut self, if_not_exists: bool) -> Self {
+        self.if_not_exists = if_not_exists;
+        self
+    }
+}
+
+/// Options of an `UPDATE` statement issued by [Table::update_with].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UpdateOptions {
+    ttl: Option<i32>,
+    timestamp: Option<i64>,
+}
+
+impl UpdateOptions {
+    /// Creates options of a plain `UPDATE`, without TTL or timestamp.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the time to live of the updated values, in seconds (`USING TTL`).
+    pub fn ttl(mut self, ttl: i32) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Sets the write timestamp of the updated values, in microseconds.
+    ///
+    /// The timestamp is sent along with the statement, in the same way as
+    /// [Statement::set_timestamp] does.
+    pub fn timestamp(mut self, timestamp: i64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+}
+
+/// An error returned by [Table::get].
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum TableGetError {
+    /// Failed to execute the `SELECT` statement.
+    #[error(transparent)]
+    Execution(#[from] ExecutionError),
+
+    /// The response to the `SELECT` statement was not of Rows kind.
+    #[error(transparent)]
+    IntoRowsResult(#[from] IntoRowsResultError),
+
+    /// Failed to type check or deserialize the returned row.
+    #[error(transparent)]
+    MaybeFirstRow(#[from] MaybeFirstRowError),
+}
+
+/// A Rust type that represents rows of a single table.
+///
+/// Provides typed CRUD methods which execute statements generated from
+/// the table description through a [CachingSession]. The statements
+/// are prepared on first use and then served from the cache.
+///
+/// This trait should be implemented with `#[derive(Table)]`, see
+/// the [`Table`](crate::Table) derive macro for the supported attributes.
+pub trait Table: Sized {
+    /// The values of the primary key columns: partition key columns
+    /// followed by clustering key columns, in declaration order.
+    type PrimaryKey: SerializeRow + Sync;
+
+    /// Keyspace of the table. If `None`, the session's current keyspace is used.
+    const KEYSPACE: Option<&'static str>;
+
+    /// Name of the table.
+    const NAME: &'static str;
+
+    /// Names of all columns of the table that are represented in the Rust type.
+    const COLUMNS: &'static [&'static str];
+
+    /// Names of the partition key columns, in order.
+    const PARTITION_KEY: &'static [&'static str];
+
+    /// Names of the clustering key columns, in order.
+    const CLUSTERING_KEY: &'static [&'static str];
+
+    /// Serializes the value of the `index`-th column from [Table::COLUMNS].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not smaller than the number of columns.
+    fn serialize_column<'b>(
+        &self,
+        index: usize,
+        typ: &ColumnType,
+        writer: CellWriter<'b>,
+    ) -> Result<WrittenCellProof<'b>, SerializationError>;
+
+    /// Returns the `INSERT` statement used by [Table::insert_with].
+    fn insert_statement(options: &InsertOptions) -> String {
+        let columns = Self::COLUMNS
+            .iter()
+            .map(|column| quote_identifier(column))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let markers = vec!["?"; Self::COLUMNS.len()].join(", ");
+        let mut statement = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            qualified_name::<Self>(),
+            columns,
+            markers
+        );
+        if options.if_not_exists {
+            statement.push_str(" IF NOT EXISTS");
+        }
+        if options.ttl.is_some() {
+            statement.push_str(" USING TTL ?");
+        }
+        statement
+    }
+
+    /// Returns the `UPDATE` statement used by [Table::update_with].
+    fn update_statement(options: &UpdateOptions) -> String {
+        let using = if options.ttl.is_some() {
+            " USING TTL ?"
+        } else {
+            ""
+        };
+        let assignments = regular_columns::<Self>()
+            .map(|index| format!("{} = ?", quote_identifier(Self::COLUMNS[index])))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "UPDATE {}{} SET {} WHERE {}",
+            qualified_name::<Self>(),
+            using,
+            assignments,
+            key_restrictions::<Self>()
+        )
+    }
+
+    /// Returns the `SELECT` statement used by [Table::get].
+    fn select_statement() -> String
+    where
+        Self: RowSelectors,
+    {
+        format!(
+            "SELECT {} FROM {} WHERE {}",
+            Self::selector_list(),
+            qualified_name::<Self>(),
+            key_restrictions::<Self>()
+        )
+    }
+
+    /// Returns the `DELETE` statement used by [Table::delete].
+    fn delete_statement() -> String {
+        format!(
+            "DELETE FROM {} WHERE {}",
+            qualified_name::<Self>(),
+            key_restrictions::<Self>()
+        )
+    }
+
+    /// Inserts the row into the table.
+    fn insert<S>(
+        &self,
+        session: &CachingSession<S>,
+    ) -> impl Future<Output = Result<QueryResult, ExecutionError>> + Send
+    where
+        Self: Sync,
+        S: BuildHasher + Clone + Send + Sync,
+    {
+        self.insert_with(session, InsertOptions::new())
+    }
+
+    /// Inserts the row into the table, using the given TTL, timestamp and condition.
+    fn insert_with<S>(
+        &self,
+        session: &CachingSession<S>,
+        options: InsertOptions,
+    ) -> impl Future<Output = Result<QueryResult, ExecutionError>> + Send
+    where
+        Self: Sync,
+        S: BuildHasher + Clone + Send + Sync,
+    {
+        let statement = make_statement(Self::insert_statement(&options), options.timestamp);
+        let mut values: Vec<_> = (0..Self::COLUMNS.len()).map(BoundValue::Column).collect();
+        values.extend(options.ttl.map(BoundValue::Ttl));
+        let values = BoundValues { row: self, values };
+        async move { session.execute_unpaged(statement, values).await }
+    }
+
+    /// Sets all non-key columns of the row identified by the primary key of `self`.
+    ///
+    /// The table must have at least one column that is not a part of the primary key.
+    fn update<S>(
+        &self,
+        session: &CachingSession<S>,
+    ) -> impl Future<Output = Result<QueryResult, ExecutionError>> + Send
+    where
+        Self: Sync,
+        S: BuildHasher + Clone + Send + Sync,
+    {
+        self.update_with(session, UpdateOptions::new())
+    }
+
+    /// Same as [Table::update], but uses the given TTL and timestamp.
+    fn update_with<S>(
+        &self,
+        session: &CachingSession<S>,
+        options: UpdateOptions,
+    ) -> impl Future<Output = Result<QueryResult, ExecutionError>> + Send
+    where
+        Self: Sync,
+        S: BuildHasher + Clone + Send + Sync,
+    {
+        let statement = make_statement(Self::update_statement(&options), options.timestamp);
+        let values = options
+            .ttl
+            .map(BoundValue::Ttl)
+            .into_iter()
+            .chain(regular_columns::<Self>().map(BoundValue::Column))
+            .chain(key_columns::<Self>().map(BoundValue::Column))
+            .collect();
+        let values = BoundValues { row: self, values };
+        async move { session.execute_unpaged(statement, values).await }
+    }
+
+    /// Fetches the row with the given primary key, if it exists.
+    fn get<S>(
+        session: &CachingSession<S>,
+        key: &Self::PrimaryKey,
+    ) -> impl Future<Output = Result<Option<Self>, TableGetError>> + Send
+    where
+        Self: RowSelectors + for<'frame, 'metadata> DeserializeRow<'frame, 'metadata>,
+        S: BuildHasher + Clone + Send + Sync,
+    {
+        let statement = Statement::new(Self::select_statement());
+        async move {
+            let result = session.execute_unpaged(statement, key).await?;
+            Ok(result.into_rows_result()?.maybe_first_row::<Self>()?)
+        }
+    }
+
+    /// Deletes the row with the given primary key.
+    fn delete<S>(
+        session: &CachingSession<S>,
+        key: &Self::PrimaryKey,
+    ) -> impl Future<Output = Result<QueryResult, ExecutionError>> + Send
+    where
+        S: BuildHasher + Clone + Send + Sync,
+    {
+        let statement = Statement::new(Self::delete_statement());
+        async move { session.execute_unpaged(statement, key).await }
+    }
+}
+
+fn make_statement(contents: String, timestamp: Option<i64>) -> Statement {
+    let mut statement = Statement::new(contents);
+    statement.set_timestamp(timestamp);
+    statement
+}
+
+fn qualified_name<T: Table>() -> String {
+    match T::KEYSPACE {
+        Some(keyspace) => format!(
+            "{}.{}",
+            quote_identifier(keyspace),
+            quote_identifier(T::NAME)
+        ),
+        None => quote_identifier(T::NAME),
+    }
+}
+
+fn is_key_column<T: Table>(name: &str) -> bool {
+    T::PARTITION_KEY.contains(&name) || T::CLUSTERING_KEY.contains(&name)
+}
+
+// Indices of the primary key columns in `T::COLUMNS`, in the primary key order.
+fn key_columns<T: Table>() -> impl Iterator<Item = usize> {
+    T::PARTITION_KEY
+        .iter()
+        .chain(T::CLUSTERING_KEY)
+        .map(|key| T::COLUMNS.iter().position(|column| column == key).unwrap())
+}
+
+// Indices of the columns in `T::COLUMNS` that are not a part of the primary key.
+fn regular_columns<T: Table>() -> impl Iterator<Item = usize> {
+    (0..T::COLUMNS.len()).filter(|&index| !is_key_column::<T>(T::COLUMNS[index]))
+}
+
+fn key_restrictions<T: Table>() -> String {
+    T::PARTITION_KEY
+        .iter()
+        .chain(T::CLUSTERING_KEY)
+        .map(|key| format!("{} = ?", quote_identifier(key)))
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+enum BoundValue {
+    Column(usize),
+    Ttl(i32),
+}
+
+// Binds the columns of a row (and other parameters, like TTL) to the markers
+// of a generated statement, in the order in which they appear there.
+struct BoundValues<'r, T> {
+    row: &'r T,
+    values: Vec<BoundValue>,
+}
+
+impl<T: Table> SerializeRow for BoundValues<'_, T> {
+    fn serialize(
+        &self,
+        ctx: &RowSerializationContext<'_>,
+        writer: &mut RowWriter,
+    ) -> Result<(), SerializationError> {
+        let columns = ctx.columns();
+        if columns.len() != self.values.len() {
+            return Err(SerializationError::new(BuiltinTypeCheckError {
+                rust_name: std::any::type_name::<T>(),
+                kind: BuiltinTypeCheckErrorKind::WrongColumnCount {
+                    rust_cols: self.values.len(),
+                    cql_cols: columns.len(),
+                },
+            }));
+        }
+
+        for (spec, value) in columns.iter().zip(&self.values) {
+            let cell_writer = writer.make_cell_writer();
+            let result = match *value {
+                BoundValue::Column(index) => {
+                    self.row.serialize_column(index, spec.typ(), cell_writer)
+                }
+                BoundValue::Ttl(ttl) => ttl.serialize(spec.typ(), cell_writer),
+            };
+            result.map_err(|err| {
+                SerializationError::new(BuiltinSerializationError {
+                    rust_name: std::any::type_name::<T>(),
+                    kind: BuiltinSerializationErrorKind::ColumnSerializationFailed {
+                        name: spec.name().to_owned(),
+                        err,
+                    },
+                })
+            })?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// ```compile_fail
+///
+/// #[derive(scylla::Table)]
+/// #[scylla(table = "t")]
+/// struct TestTable {
+///     a: i32,
+///     b: i32,
+/// }
+/// ```
+fn _test_table_requires_partition_key() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla::Table)]
+/// struct TestTable {
+///     #[scylla(partition_key)]
+///     a: i32,
+/// }
+/// ```
+fn _test_table_requires_table_name() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla::Table)]
+/// #[scylla(table = "t")]
+/// struct TestTable {
+///     #[scylla(partition_key, clustering_key)]
+///     a: i32,
+/// }
+/// ```
+fn _test_table_key_cannot_be_both_partition_and_clustering() {}
+
+/// ```compile_fail
+///
+/// mod as_text {
+///     pub(super) use scylla::_macro_internal::{CellWriter, ColumnType, SerializationError, WrittenCellProof};
+///     pub(super) fn serialize<'b>(
+///         v: &i32,
+///         typ: &ColumnType,
+///         writer: CellWriter<'b>,
+///     ) -> Result<WrittenCellProof<'b>, SerializationError> {
+///         unimplemented!()
+///     }
+/// }
+///
+/// #[derive(scylla::Table)]
+/// #[scylla(table = "t")]
+/// struct TestTable {
+///     #[scylla(partition_key, with = "as_text")]
+///     a: i32,
+/// }
+/// ```
+fn _test_table_with_not_allowed_on_keys() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla::Table)]
+/// #[scylla(table = "t")]
+/// struct TestTable {
+///     #[scylla(partition_key)]
+///     a: i32,
+///     #[scylla(partition_key, writetime_of = "a")]
+///     a_writetime: i64,
+/// }
+/// ```
+fn _test_table_key_cannot_be_writetime() {}
+
+/// ```compile_fail
+///
+/// #[derive(scylla::SerializeRow)]
+/// struct Inner {
+///     b: i32,
+/// }
+///
+/// #[derive(scylla::Table)]
+/// #[scylla(table = "t")]
+/// struct TestTable {
+///     #[scylla(partition_key)]
+///     a: i32,
+///     #[scylla(flatten)]
+///     inner: Inner,
+/// }
+/// ```
+fn _test_table_flatten_not_supported() {}
+
+#[cfg(test)]
+mod tests {
+    use scylla_cql::frame::response::result::{ColumnSpec, ColumnType, NativeType, TableSpec};
+    use scylla_cql::serialize::row::{RowSerializationContext, SerializeRow};
+    use scylla_cql::serialize::RowWriter;
+
+    use crate::client::caching_session::CachingSession;
+    use crate::deserialize::row::RowSelectors;
+
+    use super::{BoundValue, BoundValues, InsertOptions, Table, UpdateOptions};
+
+    #[derive(crate::Table, crate::DeserializeRow)]
+    #[scylla(crate = crate, keyspace = "ks", table = "users")]
+    struct User {
+        #[scylla(partition_key)]
+        id: i32,
+        #[scylla(clustering_key, rename = "Name")]
+        name: String,
+        email: Option<String>,
+        #[scylla(writetime_of = "email")]
+        #[allow(dead_code)]
+        email_writetime: Option<i64>,
+        age: i32,
+        #[scylla(skip)]
+        #[allow(dead_code)]
+        cached: bool,
+    }
+
+    #[derive(crate::Table)]
+    #[scylla(crate = crate, table = "counters")]
+    struct KeyOnly {
+        #[scylla(partition_key)]
+        a: i32,
+        #[scylla(partition_key)]
+        b: i32,
+    }
+
+    // Reserved keywords used as names.
+    #[derive(crate::Table, crate::DeserializeRow)]
+    #[scylla(crate = crate, table = "order")]
+    struct Keywords {
+        #[scylla(partition_key)]
+        from: i32,
+        #[scylla(clustering_key)]
+        to: i32,
+        limit: i32,
+    }
+
+    #[test]
+    fn test_table_description() {
+        assert_eq!(User::KEYSPACE, Some("ks"));
+        assert_eq!(User::NAME, "users");
+        assert_eq!(User::COLUMNS, &["id", "Name", "email", "age"]);
+        assert_eq!(User::PARTITION_KEY, &["id"]);
+        assert_eq!(User::CLUSTERING_KEY, &["Name"]);
+
+        assert_eq!(KeyOnly::KEYSPACE, None);
+        assert_eq!(KeyOnly::PARTITION_KEY, &["a", "b"]);
+        assert!(KeyOnly::CLUSTERING_KEY.is_empty());
+    }
+
+    #[test]
+    fn test_table_statements() {
+        assert_eq!(
+            User::insert_statement(&InsertOptions::new()),
+            r#"INSERT INTO "ks"."users" ("id", "Name", "email", "age") VALUES (?, ?, ?, ?)"#
+        );
+        assert_eq!(
+            User::insert_statement(
+                &InsertOptions::new()
+                    .ttl(60)
+                    .timestamp(1)
+                    .if_not_exists(true)
+            ),
+            r#"INSERT INTO "ks"."users" ("id", "Name", "email", "age") VALUES (?, ?, ?, ?) IF NOT EXISTS USING TTL ?"#
+        );
+        assert_eq!(
+            User::update_statement(&UpdateOptions::new()),
+            r#"UPDATE "ks"."users" SET "email" = ?, "age" = ? WHERE "id" = ? AND "Name" = ?"#
+        );
+        assert_eq!(
+            User::update_statement(&UpdateOptions::new().ttl(60)),
+            r#"UPDATE "ks"."users" USING TTL ? SET "email" = ?, "age" = ? WHERE "id" = ? AND "Name" = ?"#
+        );
+        assert_eq!(
+            User::select_statement(),
+            r#"SELECT "id", "Name", "email", writetime("email"), "age" FROM "ks"."users" WHERE "id" = ? AND "Name" = ?"#
+        );
+        assert_eq!(
+            User::delete_statement(),
+            r#"DELETE FROM "ks"."users" WHERE "id" = ? AND "Name" = ?"#
+        );
+        assert_eq!(
+            KeyOnly::delete_statement(),
+            r#"DELETE FROM "counters" WHERE "a" = ? AND "b" = ?"#
+        );
+        assert_eq!(User::selectors().len(), 5);
+
+        assert_eq!(
+            Keywords::insert_statement(&InsertOptions::new()),
+            r#"INSERT INTO "order" ("from", "to", "limit") VALUES (?, ?, ?)"#
+        );
+        assert_eq!(
+            Keywords::update_statement(&UpdateOptions::new()),
+            r#"UPDATE "order" SET "limit" = ? WHERE "from" = ? AND "to" = ?"#
+        );
+        assert_eq!(
+            Keywords::select_statement(),
+            r#"SELECT "from", "to", "limit" FROM "order" WHERE "from" = ? AND "to" = ?"#
+        );
+    }
+
+    #[test]
+    fn test_table_bound_values() {
+        let user = User {
+            id: 1,
+            name: "John".to_owned(),
+            email: None,
+            email_writetime: None,
+            age: 42,
+            cached: false,
+        };
+        let spec = |name: &'static str, typ: ColumnType<'static>| {
+            ColumnSpec::borrowed(name, typ, TableSpec::borrowed("ks", "users"))
+        };
+        // The order of an UPDATE: TTL, regular columns, primary key.
+        let specs = [
+            spec("[ttl]", ColumnType::Native(NativeType::Int)),
+            spec("email", ColumnType::Native(NativeType::Text)),
+            spec("age", ColumnType::Native(NativeType::Int)),
+            spec("id", ColumnType::Native(NativeType::Int)),
+            spec("Name", ColumnType::Native(NativeType::Text)),
+        ];
+        let ctx = RowSerializationContext::from_specs(&specs);
+
+        let values = BoundValues {
+            row: &user,
+            values: vec![
+                BoundValue::Ttl(60),
+                BoundValue::Column(2),
+                BoundValue::Column(3),
+                BoundValue::Column(0),
+                BoundValue::Column(1),
+            ],
+        };
+        let mut data = Vec::new();
+        let mut writer = RowWriter::new(&mut data);
+        values.serialize(&ctx, &mut writer).unwrap();
+        assert_eq!(writer.value_count(), 5);
+
+        let mut expected = Vec::new();
+        let mut expected_writer = RowWriter::new(&mut expected);
+        (60, None::<String>, 42, 1, "John")
+            .serialize(&ctx, &mut expected_writer)
+            .unwrap();
+        assert_eq!(data, expected);
+
+        // Too few values for the statement
+        let values = BoundValues {
+            row: &user,
+            values: vec![BoundValue::Column(0)],
+        };
+        let mut data = Vec::new();
+        values
+            .serialize(&ctx, &mut RowWriter::new(&mut data))
+            .unwrap_err();
+    }
+
+    // Typed CRUD futures must be usable from multi-threaded runtimes.
+    #[allow(dead_code)]
+    fn test_table_futures_are_send(session: &CachingSession, user: &User) {
+        fn assert_send<T: Send>(_: T) {}
+        assert_send(user.insert(session));
+        assert_send(user.update_with(session, UpdateOptions::new().timestamp(1)));
+        assert_send(User::get(session, &(1, "John".to_owned())));
+        assert_send(User::delete(session, &(1, "John".to_owned())));
+    }
+}
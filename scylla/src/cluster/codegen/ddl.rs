@@ -0,0 +1,163 @@
+//! Reading schema metadata from CQL DDL statements.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use scylla_cql::frame::response::result::{ColumnSpec, ColumnType, TableSpec, UserDefinedType};
+use scylla_ddl::lexer::{line_and_column, tokenize, Cursor, ParseResult};
+use scylla_ddl::schema::{self, parse_schema, Schema};
+use thiserror::Error;
+
+use crate::cluster::metadata::{
+    parse_column_type, strategy_from_string_map, Column, ColumnKind, Keyspace, Strategy, Table,
+};
+
+/// An error returned by [parse_ddl](super::parse_ddl).
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{message} (at line {line}, column {column})")]
+#[non_exhaustive]
+pub struct DdlParseError {
+    /// Line of the DDL at which the error occurred, counted from 1.
+    pub line: usize,
+    /// Column of the DDL at which the error occurred, counted from 1.
+    pub column: usize,
+    /// Description of the error.
+    pub message: String,
+}
+
+pub(super) fn parse_ddl(ddl: &str) -> Result<HashMap<String, Keyspace>, DdlParseError> {
+    let make_error = |(offset, message)| {
+        let (line, column) = line_and_column(ddl, offset);
+        DdlParseError {
+            line,
+            column,
+            message,
+        }
+    };
+    let tokens = tokenize(ddl).map_err(make_error)?;
+    let schema = parse_schema(&mut Cursor::new(&tokens, ddl.len())).map_err(make_error)?;
+    keyspaces(&schema).map_err(make_error)
+}
+
+// Converts the parsed schema into the metadata fetched from the schema tables.
+fn keyspaces(schema: &Schema) -> ParseResult<HashMap<String, Keyspace>> {
+    let mut keyspaces = HashMap::new();
+
+    for keyspace in &schema.keyspaces {
+        let strategy = strategy_from_string_map(keyspace.replication.clone())
+            .map_err(|err| (keyspace.offset, err.to_string()))?;
+        keyspace_entry(&mut keyspaces, &keyspace.name).strategy = strategy;
+    }
+
+    // Types may only use the types defined before them, so they are
+    // converted in the order of definition.
+    for udt in &schema.udts {
+        let keyspace_name = resolve_keyspace(&udt.keyspace, udt.offset)?;
+        let keyspace = keyspace_entry(&mut keyspaces, keyspace_name);
+        let field_types = udt
+            .fields
+            .iter()
+            .map(|(name, typ)| {
+                let typ = column_type(typ, keyspace_name, keyspace, udt.offset)?;
+                Ok((name.clone().into(), typ))
+            })
+            .collect::<ParseResult<_>>()?;
+        let udt_metadata = UserDefinedType {
+            name: udt.name.clone().into(),
+            keyspace: keyspace_name.clone().into(),
+            field_types,
+        };
+        keyspace
+            .user_defined_types
+            .insert(udt.name.clone(), Arc::new(udt_metadata));
+    }
+
+    for table in &schema.tables {
+        let keyspace_name = resolve_keyspace(&table.keyspace, table.offset)?;
+        let keyspace = keyspace_entry(&mut keyspaces, keyspace_name);
+        let table_metadata = self::table(table, keyspace_name, keyspace)?;
+        keyspace.tables.insert(table.name.clone(), table_metadata);
+    }
+
+    Ok(keyspaces)
+}
+
+// The type is printed in its canonical form, e.g. `frozen<map<text, int>>`,
+// and then parsed in the same way as types fetched from the schema tables.
+fn column_type(
+    typ: &schema::CqlType,
+    keyspace_name: &String,
+    keyspace: &Keyspace,
+    offset: usize,
+) -> ParseResult<ColumnType<'static>> {
+    parse_column_type(&typ.cql_name(), keyspace_name, &keyspace.user_defined_types)
+        .map_err(|err| (offset, err))
+}
+
+fn table(table: &schema::Table, keyspace_name: &String, keyspace: &Keyspace) -> ParseResult<Table> {
+    let mut columns = HashMap::new();
+    let mut partition_key = Vec::new();
+    let mut clustering_key = Vec::new();
+
+    for column in &table.columns {
+        let kind = match column.kind {
+            schema::ColumnKind::PartitionKey => {
+                partition_key.push(column.name.clone());
+                ColumnKind::PartitionKey
+            }
+            schema::ColumnKind::Clustering => {
+                clustering_key.push(column.name.clone());
+                ColumnKind::Clustering
+            }
+            schema::ColumnKind::Static => ColumnKind::Static,
+            schema::ColumnKind::Regular => ColumnKind::Regular,
+        };
+        let typ = column_type(&column.typ, keyspace_name, keyspace, table.offset)?;
+        columns.insert(column.name.clone(), Column { typ, kind });
+    }
+
+    let pk_column_specs = partition_key
+        .iter()
+        .map(|key| {
+            ColumnSpec::owned(
+                key.clone(),
+                columns[key].typ.clone(),
+                TableSpec::owned(keyspace_name.clone(), table.name.clone()),
+            )
+        })
+        .collect();
+
+    Ok(Table {
+        columns,
+        partition_key,
+        clustering_key,
+        partitioner: None,
+        pk_column_specs,
+    })
+}
+
+fn resolve_keyspace(keyspace: &Option<String>, offset: usize) -> ParseResult<&String> {
+    keyspace.as_ref().ok_or((
+        offset,
+        "the name is not qualified with a keyspace, and no keyspace is selected with `USE`"
+            .to_owned(),
+    ))
+}
+
+// Keyspaces which are used, but not created in the DDL, have an unknown strategy.
+fn keyspace_entry<'k>(
+    keyspaces: &'k mut HashMap<String, Keyspace>,
+    name: &str,
+) -> &'k mut Keyspace {
+    keyspaces
+        .entry(name.to_owned())
+        .or_insert_with(|| Keyspace {
+            strategy: Strategy::Other {
+                name: String::new(),
+                data: HashMap::new(),
+            },
+            tables: HashMap::new(),
+            views: HashMap::new(),
+            user_defined_types: HashMap::new(),
+        })
+}
@@ -0,0 +1,691 @@
+//! Generating Rust types from the schema metadata.
+//!
+//! [generate_keyspace] emits the source code of a Rust struct for every table
+//! and user defined type of a [Keyspace]. Structs representing tables derive
+//! [SerializeRow](crate::SerializeRow) and [DeserializeRow](crate::DeserializeRow),
+//! and structs representing user defined types derive
+//! [SerializeValue](crate::SerializeValue) and [DeserializeValue](crate::DeserializeValue).
+//!
+//! The metadata can come either from a live cluster, through
+//! [ClusterState::get_keyspace](crate::cluster::ClusterState::get_keyspace),
+//! or from a file with CQL DDL statements, through [parse_ddl].
+//!
+//! ```
+//! # use scylla::cluster::codegen::{generate_keyspace, parse_ddl, CodegenOptions};
+//! let keyspaces = parse_ddl(
+//!     "CREATE TABLE ks.users (id int PRIMARY KEY, name text, emails list<text>);",
+//! )
+//! .unwrap();
+//! let code = generate_keyspace("ks", &keyspaces["ks"], &CodegenOptions::new());
+//! assert!(code.contains("pub struct Users {"));
+//! assert!(code.contains("pub emails: Option<Vec<String>>,"));
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+
+use scylla_cql::frame::response::result::{
+    CollectionType, ColumnType, NativeType, UserDefinedType,
+};
+
+use crate::cluster::metadata::{ColumnKind, Keyspace, Table};
+
+mod ddl;
+pub use ddl::DdlParseError;
+
+/// Reads schema metadata from CQL DDL statements, e.g. from the output of `DESCRIBE SCHEMA`.
+///
+/// `USE`, `CREATE KEYSPACE`, `CREATE TYPE` and `CREATE TABLE` statements are
+/// taken into account, and all other statements are skipped. The returned map
+/// is keyed by keyspace names. Keyspaces which are referred to, but not
+/// created in the DDL, have an unknown replication strategy.
+pub fn parse_ddl(ddl: &str) -> Result<HashMap<String, Keyspace>, DdlParseError> {
+    ddl::parse_ddl(ddl)
+}
+
+/// Options of the generated code.
+#[derive(Clone, Debug)]
+pub struct CodegenOptions {
+    crate_path: String,
+    derive_table: bool,
+}
+
+impl CodegenOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the path under which the driver crate is available to the generated code.
+    ///
+    /// Defaults to `scylla`.
+    pub fn crate_path(mut self, crate_path: impl Into<String>) -> Self {
+        self.crate_path = crate_path.into();
+        self
+    }
+
+    /// Sets whether structs representing tables should derive [Table](crate::Table)
+    /// as well, together with the attributes describing the primary key.
+    ///
+    /// Defaults to `false`.
+    pub fn derive_table(mut self, derive_table: bool) -> Self {
+        self.derive_table = derive_table;
+        self
+    }
+}
+
+impl Default for CodegenOptions {
+    fn default() -> Self {
+        Self {
+            crate_path: "scylla".to_owned(),
+            derive_table: false,
+        }
+    }
+}
+
+/// Generates Rust structs for all user defined types and tables of the keyspace.
+///
+/// Structs are named after the types and tables in `UpperCamelCase`. Their fields
+/// are named after the fields and columns in `snake_case`, and names which can't
+/// be used as is are mapped back with `#[scylla(rename = "...")]`.
+/// Names which collide after the conversion, e.g. of columns `a-b` and `a_b`,
+/// get a numeric suffix, and so do struct names which would shadow the types
+/// used in the generated fields. Tables named in the same way as types get a `Row` suffix.
+///
+/// Columns of the primary key are represented by plain Rust types, while other
+/// columns, as well as fields of user defined types, are wrapped in `Option`,
+/// because they may be null. Maps whose keys can't be hashed are represented
+/// by [CqlValue](crate::value::CqlValue).
+pub fn generate_keyspace(
+    keyspace_name: &str,
+    keyspace: &Keyspace,
+    options: &CodegenOptions,
+) -> String {
+    let mut generator = Generator::new(keyspace_name, keyspace, options);
+
+    let mut udt_names: Vec<&String> = keyspace.user_defined_types.keys().collect();
+    udt_names.sort();
+    // Types are emitted after the types they depend on.
+    let mut emitted = HashSet::new();
+    for name in udt_names {
+        generator.udt_with_dependencies(&keyspace.user_defined_types[name], &mut emitted);
+    }
+
+    let mut table_names: Vec<&String> = keyspace.tables.keys().collect();
+    table_names.sort();
+    for name in table_names {
+        generator.table(name, &keyspace.tables[name]);
+    }
+
+    generator.code
+}
+
+struct Generator<'a> {
+    keyspace_name: &'a str,
+    options: &'a CodegenOptions,
+    udt_struct_names: HashMap<&'a str, String>,
+    // Names of the structs emitted so far, together with the names which must not be used.
+    used_struct_names: HashSet<String>,
+    code: String,
+}
+
+impl<'a> Generator<'a> {
+    fn new(keyspace_name: &'a str, keyspace: &'a Keyspace, options: &'a CodegenOptions) -> Self {
+        let mut used_struct_names: HashSet<String> = RESERVED_STRUCT_NAMES
+            .iter()
+            .map(|name| (*name).to_owned())
+            .collect();
+        let mut udt_names: Vec<&String> = keyspace.user_defined_types.keys().collect();
+        udt_names.sort();
+        let udt_struct_names = udt_names
+            .into_iter()
+            .map(|name| {
+                let struct_name = unique_name(struct_name(name), "", &mut used_struct_names);
+                (name.as_str(), struct_name)
+            })
+            .collect();
+        Self {
+            keyspace_name,
+            options,
+            udt_struct_names,
+            used_struct_names,
+            code: format!("// Generated from the schema of keyspace `{keyspace_name}`.\n"),
+        }
+    }
+
+    fn udt_with_dependencies(&mut self, udt: &UserDefinedType<'_>, emitted: &mut HashSet<String>) {
+        if !emitted.insert(udt.name.to_string()) {
+            return;
+        }
+        for (_, typ) in &udt.field_types {
+            for_each_udt(typ, &mut |dependency| {
+                self.udt_with_dependencies(dependency, emitted)
+            });
+        }
+        self.udt(udt);
+    }
+
+    fn udt(&mut self, udt: &UserDefinedType<'_>) {
+        let name = self.udt_struct_names[&*udt.name].clone();
+        let fields = udt
+            .field_types
+            .iter()
+            .map(|(field_name, typ)| Field {
+                name: field_name,
+                typ: format!("Option<{}>", self.rust_type(typ)),
+                key: None,
+            })
+            .collect::<Vec<_>>();
+
+        self.code.push('\n');
+        let _ = writeln!(
+            self.code,
+            "/// User defined type `{}.{}`.",
+            self.keyspace_name, udt.name
+        );
+        self.struct_header(&name, &["SerializeValue", "DeserializeValue"], None);
+        self.fields(&fields);
+    }
+
+    fn table(&mut self, table_name: &str, table: &Table) {
+        let mut name = struct_name(table_name);
+        if self.udt_struct_names.values().any(|udt| *udt == name) {
+            name.push_str("Row");
+        }
+        let name = unique_name(name, "", &mut self.used_struct_names);
+
+        // The columns are ordered in the same way as in the result of `SELECT *`.
+        let mut regular_columns: Vec<&String> = table
+            .columns
+            .iter()
+            .filter(|(_, column)| matches!(column.kind, ColumnKind::Regular | ColumnKind::Static))
+            .map(|(name, _)| name)
+            .collect();
+        regular_columns.sort();
+        let key_columns = table
+            .partition_key
+            .iter()
+            .map(|name| (name, Some("partition_key")))
+            .chain(
+                table
+                    .clustering_key
+                    .iter()
+                    .map(|name| (name, Some("clustering_key"))),
+            );
+        let fields = key_columns
+            .chain(regular_columns.into_iter().map(|name| (name, None)))
+            .map(|(column_name, key)| {
+                let typ = self.rust_type(&table.columns[column_name].typ);
+                Field {
+                    name: column_name,
+                    typ: if key.is_some() {
+                        typ
+                    } else {
+                        format!("Option<{typ}>")
+                    },
+                    key: key.filter(|_| self.options.derive_table),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        self.code.push('\n');
+        let _ = writeln!(
+            self.code,
+            "/// Row of table `{}.{}`.",
+            self.keyspace_name, table_name
+        );
+        if self.options.derive_table {
+            self.struct_header(
+                &name,
+                &["SerializeRow", "DeserializeRow", "Table"],
+                Some(format!(
+                    "keyspace = {:?}, table = {:?}",
+                    self.keyspace_name, table_name
+                )),
+            );
+        } else {
+            self.struct_header(&name, &["SerializeRow", "DeserializeRow"], None);
+        }
+        self.fields(&fields);
+    }
+
+    fn struct_header(&mut self, name: &str, derives: &[&str], attributes: Option<String>) {
+        let crate_path = &self.options.crate_path;
+        let _ = write!(self.code, "#[derive(Debug, Clone, PartialEq");
+        for derive in derives {
+            let _ = write!(self.code, ", {crate_path}::{derive}");
+        }
+        self.code.push_str(")]\n");
+
+        let mut attributes: Vec<String> = attributes.into_iter().collect();
+        if crate_path != "scylla" {
+            attributes.insert(0, format!("crate = {crate_path}"));
+        }
+        if !attributes.is_empty() {
+            let _ = writeln!(self.code, "#[scylla({})]", attributes.join(", "));
+        }
+        let _ = writeln!(self.code, "pub struct {name} {{");
+    }
+
+    fn fields(&mut self, fields: &[Field<'_>]) {
+        // Names of the columns are reserved first, so that a column which maps
+        // to a Rust name unchanged keeps it even if another column collides with it.
+        let mut used_names: HashSet<String> = fields
+            .iter()
+            .map(|field| field.name.to_owned())
+            .filter(|name| field_name(name) == *name)
+            .collect();
+        for field in fields {
+            let name = field_name(field.name);
+            let name = if name == field.name {
+                name
+            } else {
+                unique_name(name, "_", &mut used_names)
+            };
+
+            let mut attributes = Vec::new();
+            if name != field.name {
+                attributes.push(format!("rename = {:?}", field.name));
+            }
+            attributes.extend(field.key.map(str::to_owned));
+            if !attributes.is_empty() {
+                let _ = writeln!(self.code, "    #[scylla({})]", attributes.join(", "));
+            }
+            let _ = writeln!(self.code, "    pub {name}: {},", field.typ);
+        }
+        self.code.push_str("}\n");
+    }
+
+    fn rust_type(&self, typ: &ColumnType<'_>) -> String {
+        let crate_path = &self.options.crate_path;
+        match typ {
+            ColumnType::Native(native) => match native {
+                NativeType::TinyInt => "i8".to_owned(),
+                NativeType::SmallInt => "i16".to_owned(),
+                NativeType::Int => "i32".to_owned(),
+                NativeType::BigInt => "i64".to_owned(),
+                NativeType::Float => "f32".to_owned(),
+                NativeType::Double => "f64".to_owned(),
+                NativeType::Boolean => "bool".to_owned(),
+                NativeType::Ascii | NativeType::Text => "String".to_owned(),
+                NativeType::Blob => "Vec<u8>".to_owned(),
+                NativeType::Uuid => "uuid::Uuid".to_owned(),
+                NativeType::Inet => "std::net::IpAddr".to_owned(),
+                NativeType::Timeuuid => format!("{crate_path}::value::CqlTimeuuid"),
+                NativeType::Date => format!("{crate_path}::value::CqlDate"),
+                NativeType::Timestamp => format!("{crate_path}::value::CqlTimestamp"),
+                NativeType::Time => format!("{crate_path}::value::CqlTime"),
+                NativeType::Decimal => format!("{crate_path}::value::CqlDecimal"),
+                NativeType::Varint => format!("{crate_path}::value::CqlVarint"),
+                NativeType::Counter => format!("{crate_path}::value::Counter"),
+                NativeType::Duration => format!("{crate_path}::value::CqlDuration"),
+                _ => format!("{crate_path}::value::CqlValue"),
+            },
+            ColumnType::Collection { typ, .. } => match typ {
+                CollectionType::List(element) | CollectionType::Set(element) => {
+                    format!("Vec<{}>", self.rust_type(element))
+                }
+                CollectionType::Map(key, value) if is_hashable(key) => format!(
+                    "std::collections::HashMap<{}, {}>",
+                    self.rust_type(key),
+                    self.rust_type(value)
+                ),
+                // There is no natural Rust counterpart of maps with keys which can't be hashed.
+                _ => format!("{crate_path}::value::CqlValue"),
+            },
+            ColumnType::Vector { typ, .. } => format!("Vec<{}>", self.rust_type(typ)),
+            ColumnType::UserDefinedType { definition, .. } => self
+                .udt_struct_names
+                .get(&*definition.name)
+                .cloned()
+                .unwrap_or_else(|| format!("{crate_path}::value::CqlValue")),
+            ColumnType::Tuple(elements) => {
+                // Elements of tuples may be null.
+                let elements = elements
+                    .iter()
+                    .map(|element| format!("Option<{}>", self.rust_type(element)))
+                    .collect::<Vec<_>>();
+                match elements.as_slice() {
+                    [element] => format!("({element},)"),
+                    _ => format!("({})", elements.join(", ")),
+                }
+            }
+            _ => format!("{crate_path}::value::CqlValue"),
+        }
+    }
+}
+
+struct Field<'a> {
+    // Name of the column or the UDT field.
+    name: &'a str,
+    typ: String,
+    // Attribute marking the column as a part of the primary key.
+    key: Option<&'static str>,
+}
+
+// Calls `f` for each user defined type nested in the type.
+fn for_each_udt(typ: &ColumnType<'_>, f: &mut impl FnMut(&UserDefinedType<'_>)) {
+    match typ {
+        ColumnType::Collection { typ, .. } => match typ {
+            CollectionType::List(element) | CollectionType::Set(element) => {
+                for_each_udt(element, f)
+            }
+            CollectionType::Map(key, value) => {
+                for_each_udt(key, f);
+                for_each_udt(value, f);
+            }
+            _ => {}
+        },
+        ColumnType::Vector { typ, .. } => for_each_udt(typ, f),
+        ColumnType::UserDefinedType { definition, .. } => f(definition),
+        ColumnType::Tuple(elements) => elements.iter().for_each(|e| for_each_udt(e, f)),
+        _ => {}
+    }
+}
+
+// Whether the Rust counterpart of the CQL type implements `Hash` and `Eq`.
+fn is_hashable(typ: &ColumnType<'_>) -> bool {
+    match typ {
+        ColumnType::Native(native) => matches!(
+            native,
+            NativeType::TinyInt
+                | NativeType::SmallInt
+                | NativeType::Int
+                | NativeType::BigInt
+                | NativeType::Boolean
+                | NativeType::Ascii
+                | NativeType::Text
+                | NativeType::Blob
+                | NativeType::Uuid
+                | NativeType::Timeuuid
+                | NativeType::Inet
+                | NativeType::Varint
+        ),
+        ColumnType::Collection {
+            typ: CollectionType::List(element) | CollectionType::Set(element),
+            ..
+        } => is_hashable(element),
+        ColumnType::Tuple(elements) => elements.iter().all(is_hashable),
+        _ => false,
+    }
+}
+
+fn words(name: &str) -> impl Iterator<Item = &str> {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+}
+
+// `user_profiles` -> `UserProfiles`
+fn struct_name(name: &str) -> String {
+    let mut struct_name = String::new();
+    for word in words(name) {
+        let mut chars = word.chars();
+        struct_name.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+        struct_name.push_str(chars.as_str());
+    }
+    if !struct_name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        struct_name.insert(0, 'T');
+    }
+    struct_name
+}
+
+// Returns the name, or the name with the lowest numeric suffix, which is not used yet,
+// and marks it as used. Distinct CQL names may map to the same Rust name,
+// e.g. columns `"Name"` and `name`, or tables `user_profile` and `"UserProfile"`.
+fn unique_name(name: String, separator: &str, used: &mut HashSet<String>) -> String {
+    let name = if used.contains(&name) {
+        (2..)
+            .map(|n| format!("{name}{separator}{n}"))
+            .find(|candidate| !used.contains(candidate))
+            .unwrap()
+    } else {
+        name
+    };
+    used.insert(name.clone());
+    name
+}
+
+// `"First Name"` -> `first_name`, `type` -> `type_`
+fn field_name(name: &str) -> String {
+    let mut field_name = words(name)
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("_");
+    if field_name.is_empty() {
+        // `_` alone is not a valid field name.
+        field_name.push_str("field");
+    } else if !field_name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        field_name.insert(0, '_');
+    }
+    if RUST_KEYWORDS.contains(&field_name.as_str()) {
+        field_name.push('_');
+    }
+    field_name
+}
+
+// Names which would clash with a keyword or shadow the types used in the generated fields.
+const RESERVED_STRUCT_NAMES: &[&str] = &["Self", "Option", "String", "Vec"];
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{generate_keyspace, parse_ddl, CodegenOptions};
+    use crate::cluster::metadata::{ColumnKind, ColumnType, NativeType, Strategy};
+
+    const SCHEMA: &str = r#"
+        CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy', 'replication_factor': 3}
+            AND durable_writes = true;
+        USE ks;
+
+        -- Types are defined before they are used.
+        CREATE TYPE point (x double, y double);
+        CREATE TYPE IF NOT EXISTS ks.route (name text, points frozen<list<frozen<point>>>);
+
+        CREATE TABLE routes (
+            "Region" text,
+            day date,
+            id timeuuid,
+            route frozen<route>,
+            tags set<text>,
+            lengths map<int, float>,
+            by_point map<frozen<point>, int>,
+            "type" tinyint,
+            owner text STATIC,
+            PRIMARY KEY (("Region", day), id)
+        ) WITH CLUSTERING ORDER BY (id DESC);
+
+        /* A table named in the same way as a type. */
+        CREATE TABLE ks.point (id int PRIMARY KEY, coordinates tuple<int, int>);
+        CREATE INDEX ON routes (owner);
+    "#;
+
+    #[test]
+    fn test_parse_ddl() {
+        let keyspaces = parse_ddl(SCHEMA).unwrap();
+        let ks = &keyspaces["ks"];
+        assert!(matches!(
+            &ks.strategy,
+            Strategy::SimpleStrategy {
+                replication_factor: 3
+            }
+        ));
+        assert_eq!(ks.user_defined_types.len(), 2);
+        assert_eq!(ks.user_defined_types["route"].field_types.len(), 2);
+
+        let routes = &ks.tables["routes"];
+        assert_eq!(routes.partition_key, vec!["Region", "day"]);
+        assert_eq!(routes.clustering_key, vec!["id"]);
+        assert_eq!(routes.pk_column_specs.len(), 2);
+        assert_eq!(routes.columns["owner"].kind, ColumnKind::Static);
+        assert_eq!(routes.columns["id"].kind, ColumnKind::Clustering);
+        assert_eq!(
+            routes.columns["type"].typ,
+            ColumnType::Native(NativeType::TinyInt)
+        );
+        assert!(matches!(
+            &routes.columns["route"].typ,
+            ColumnType::UserDefinedType { frozen: true, definition } if definition.name == "route"
+        ));
+    }
+
+    #[test]
+    fn test_parse_ddl_errors() {
+        let err = parse_ddl("CREATE TABLE t (a int PRIMARY KEY);").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 14));
+
+        let err = parse_ddl("USE ks;\nCREATE TABLE t (a int, b text);").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert!(err.message.contains("no primary key"), "{}", err.message);
+
+        let err = parse_ddl("USE ks;\nCREATE TABLE t (a int PRIMARY KEY, b frozen<missing>);")
+            .unwrap_err();
+        assert_eq!((err.line, err.column), (2, 45));
+        assert!(err.message.contains("`missing`"), "{}", err.message);
+
+        let err = parse_ddl("USE ks;\nCREATE TABLE t (a int, PRIMARY KEY (b));").unwrap_err();
+        assert!(err.message.contains("`b`"), "{}", err.message);
+    }
+
+    #[test]
+    fn test_generate_keyspace() {
+        let keyspaces = parse_ddl(SCHEMA).unwrap();
+        let code = generate_keyspace("ks", &keyspaces["ks"], &CodegenOptions::new());
+        let expected = r#"// Generated from the schema of keyspace `ks`.
+
+/// User defined type `ks.point`.
+#[derive(Debug, Clone, PartialEq, scylla::SerializeValue, scylla::DeserializeValue)]
+pub struct Point {
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+}
+
+/// User defined type `ks.route`.
+#[derive(Debug, Clone, PartialEq, scylla::SerializeValue, scylla::DeserializeValue)]
+pub struct Route {
+    pub name: Option<String>,
+    pub points: Option<Vec<Point>>,
+}
+
+/// Row of table `ks.point`.
+#[derive(Debug, Clone, PartialEq, scylla::SerializeRow, scylla::DeserializeRow)]
+pub struct PointRow {
+    pub id: i32,
+    pub coordinates: Option<(Option<i32>, Option<i32>)>,
+}
+
+/// Row of table `ks.routes`.
+#[derive(Debug, Clone, PartialEq, scylla::SerializeRow, scylla::DeserializeRow)]
+pub struct Routes {
+    #[scylla(rename = "Region")]
+    pub region: String,
+    pub day: scylla::value::CqlDate,
+    pub id: scylla::value::CqlTimeuuid,
+    pub by_point: Option<scylla::value::CqlValue>,
+    pub lengths: Option<std::collections::HashMap<i32, f32>>,
+    pub owner: Option<String>,
+    pub route: Option<Route>,
+    pub tags: Option<Vec<String>>,
+    #[scylla(rename = "type")]
+    pub type_: Option<i8>,
+}
+"#;
+        assert_eq!(code, expected);
+    }
+
+    #[test]
+    fn test_generate_keyspace_with_colliding_names() {
+        let keyspaces = parse_ddl(
+            r#"USE ks;
+            CREATE TYPE user_profile (id int);
+            CREATE TYPE "UserProfile" (id int);
+            CREATE TYPE string (id int);
+            CREATE TABLE "UserProfileRow" (id int PRIMARY KEY);
+            CREATE TABLE user_profile (
+                id int PRIMARY KEY,
+                "a-b" text,
+                a_b text,
+                "A_B" text,
+                "a_b_2" text,
+                "_" text,
+                name frozen<string>
+            );"#,
+        )
+        .unwrap();
+        let code = generate_keyspace("ks", &keyspaces["ks"], &CodegenOptions::new());
+        let expected = r#"// Generated from the schema of keyspace `ks`.
+
+/// User defined type `ks.UserProfile`.
+#[derive(Debug, Clone, PartialEq, scylla::SerializeValue, scylla::DeserializeValue)]
+pub struct UserProfile {
+    pub id: Option<i32>,
+}
+
+/// User defined type `ks.string`.
+#[derive(Debug, Clone, PartialEq, scylla::SerializeValue, scylla::DeserializeValue)]
+pub struct String2 {
+    pub id: Option<i32>,
+}
+
+/// User defined type `ks.user_profile`.
+#[derive(Debug, Clone, PartialEq, scylla::SerializeValue, scylla::DeserializeValue)]
+pub struct UserProfile2 {
+    pub id: Option<i32>,
+}
+
+/// Row of table `ks.UserProfileRow`.
+#[derive(Debug, Clone, PartialEq, scylla::SerializeRow, scylla::DeserializeRow)]
+pub struct UserProfileRow {
+    pub id: i32,
+}
+
+/// Row of table `ks.user_profile`.
+#[derive(Debug, Clone, PartialEq, scylla::SerializeRow, scylla::DeserializeRow)]
+pub struct UserProfileRow2 {
+    pub id: i32,
+    #[scylla(rename = "A_B")]
+    pub a_b_3: Option<String>,
+    #[scylla(rename = "_")]
+    pub field: Option<String>,
+    #[scylla(rename = "a-b")]
+    pub a_b_4: Option<String>,
+    pub a_b: Option<String>,
+    pub a_b_2: Option<String>,
+    pub name: Option<String2>,
+}
+"#;
+        assert_eq!(code, expected);
+    }
+
+    #[test]
+    fn test_generate_keyspace_with_table_derive() {
+        let keyspaces = parse_ddl(
+            "CREATE TABLE ks.events (id int, at timestamp, payload blob, PRIMARY KEY (id, at));",
+        )
+        .unwrap();
+        let options = CodegenOptions::new()
+            .crate_path("::driver")
+            .derive_table(true);
+        let code = generate_keyspace("ks", &keyspaces["ks"], &options);
+        let expected = r#"// Generated from the schema of keyspace `ks`.
+
+/// Row of table `ks.events`.
+#[derive(Debug, Clone, PartialEq, ::driver::SerializeRow, ::driver::DeserializeRow, ::driver::Table)]
+#[scylla(crate = ::driver, keyspace = "ks", table = "events")]
+pub struct Events {
+    #[scylla(partition_key)]
+    pub id: i32,
+    #[scylla(clustering_key)]
+    pub at: ::driver::value::CqlTimestamp,
+    pub payload: Option<Vec<u8>>,
+}
+"#;
+        assert_eq!(code, expected);
+    }
+}
@@ -494,6 +494,11 @@ impl MetadataReader {
         })
     }
 
+    /// Closes the control connection. Metadata can no longer be fetched afterwards.
+    pub(crate) fn close(&self) {
+        self.control_connection.close();
+    }
+
     /// Fetches current metadata from the cluster
     pub(crate) async fn read_metadata(&mut self, initial: bool) -> Result<Metadata, MetadataError> {
         let mut result = self.fetch_metadata(initial).await;
@@ -1776,6 +1781,20 @@ fn map_string_to_cql_type(typ: &str) -> Result<PreColumnType, InvalidCqlType> {
     }
 }
 
+/// Parses a column type written in CQL (e.g. in a `CREATE TABLE` statement),
+/// resolving user defined types among the given ones.
+pub(crate) fn parse_column_type(
+    typ: &str,
+    keyspace_name: &String,
+    keyspace_udts: &HashMap<String, Arc<UserDefinedType<'static>>>,
+) -> Result<ColumnType<'static>, String> {
+    let pre_cql_type = map_string_to_cql_type(typ)
+        .map_err(|err| format!("invalid type `{}`: {}", err.typ, err.reason))?;
+    pre_cql_type
+        .into_cql_type(keyspace_name, keyspace_udts)
+        .map_err(|err| err.to_string())
+}
+
 fn parse_cql_type(p: ParserState<'_>) -> ParseResult<(PreColumnType, ParserState<'_>)> {
     if let Ok(p) = p.accept("frozen<") {
         let (inner_type, p) = parse_cql_type(p)?;
@@ -1972,7 +1991,7 @@ impl ControlConnection {
     }
 }
 
-fn strategy_from_string_map(
+pub(crate) fn strategy_from_string_map(
     mut strategy_map: HashMap<String, String>,
 ) -> Result<Strategy, KeyspaceStrategyError> {
     let strategy_name: String = strategy_map
@@ -11,6 +11,8 @@
 //! - [ClusterState], which is a snapshot of the cluster's state.
 //!   - [ClusterState] is replaced atomically upon a metadata refresh,
 //!     preventing any issues arising from mutability, including races.
+//! - [codegen] of Rust types from the schema metadata,
+//!   and [validation] of Rust types against it.
 //  - [ControlConnection](control_connection::ControlConnection), which
 //    is the single connection used to fetch metadata and receive events
 //    from the cluster.
@@ -27,3 +29,7 @@ pub use node::{KnownNode, Node, NodeAddr, NodeRef};
 mod control_connection;
 
 pub mod metadata;
+
+pub mod codegen;
+
+pub mod validation;
@@ -4,6 +4,7 @@ use tracing::warn;
 use uuid::Uuid;
 
 use crate::errors::{ConnectionPoolError, UseKeyspaceError};
+use crate::frame::ProtocolVersion;
 use crate::network::Connection;
 use crate::network::VerifiedKeyspaceName;
 use crate::network::{NodeConnectionPool, PoolConfig};
@@ -145,6 +146,15 @@ impl Node {
         self.pool.as_ref()?.sharder()
     }
 
+    /// Returns the protocol version negotiated with this node.
+    ///
+    /// Returns `None` if the driver has no open connections to the node.
+    /// If the connections use different versions (which may happen while
+    /// the version is being negotiated), the lowest one is returned.
+    pub fn protocol_version(&self) -> Option<ProtocolVersion> {
+        self.pool.as_ref()?.protocol_version()
+    }
+
     /// Get a connection targetting the given shard
     /// If such connection is broken, get any random connection to this `Node`
     pub(crate) async fn connection_for_shard(
@@ -196,6 +206,13 @@ impl Node {
         }
     }
 
+    /// Closes the connection pool of this node, see [`NodeConnectionPool::close`].
+    pub(crate) fn close_pool(&self) {
+        if let Some(pool) = &self.pool {
+            pool.close();
+        }
+    }
+
     fn get_pool(&self) -> Result<&NodeConnectionPool, ConnectionPoolError> {
         self.pool
             .as_ref()
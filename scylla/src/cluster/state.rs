@@ -1,5 +1,6 @@
 use crate::errors::{ClusterStateTokenError, ConnectionPoolError};
-use crate::network::{Connection, PoolConfig, VerifiedKeyspaceName};
+use crate::frame::ProtocolVersion;
+use crate::network::{Connection, PoolConfig, SharedProtocolVersion, VerifiedKeyspaceName};
 #[cfg(feature = "metrics")]
 use crate::observability::metrics::Metrics;
 use crate::policies::host_filter::HostFilter;
@@ -42,6 +43,9 @@ pub struct ClusterState {
     /// for a given (token, replication strategy, table) tuple.
     /// It relies on both topology and schema metadata.
     pub(crate) locator: ReplicaLocator,
+
+    /// Protocol version negotiated with the cluster, shared by all connections.
+    pub(crate) protocol_version: Arc<SharedProtocolVersion>,
 }
 
 /// Enables printing [ClusterState] struct in a neat way, skipping the clutter involved by
@@ -213,6 +217,7 @@ impl ClusterState {
             known_peers: new_known_peers,
             keyspaces,
             locator,
+            protocol_version: pool_config.connection_config.protocol_version.clone(),
         }
     }
 
@@ -231,6 +236,16 @@ impl ClusterState {
         &self.all_nodes
     }
 
+    /// Returns the protocol version negotiated with the cluster.
+    ///
+    /// New connections are opened with this version. Unless a version was set
+    /// explicitly in the session config, it is the highest version supported
+    /// by all nodes the driver has connected to so far.
+    /// Use [`Node::protocol_version`] to check the version used with a particular node.
+    pub fn protocol_version(&self) -> ProtocolVersion {
+        self.protocol_version.get()
+    }
+
     /// Compute token of a table partition key
     ///
     /// `partition_key` argument contains the values of all partition key
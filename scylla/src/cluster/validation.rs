@@ -0,0 +1,758 @@
+//! Validating Rust types against the schema metadata.
+//!
+//! The driver type-checks rows and values only when a statement is executed,
+//! so a Rust type which doesn't match its table is usually noticed when the first
+//! request fails. The functions of this module, exposed as
+//! [Session::validate_row_type](crate::client::session::Session::validate_row_type) and similar
+//! methods, check a type against the schema fetched from the cluster upfront, and report
+//! every mismatch at once, e.g. so that a service can fail its readiness check after a deploy.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::sync::Arc;
+
+use scylla_cql::deserialize::row::{
+    BuiltinTypeCheckError as DeserRowTypeCheckError,
+    BuiltinTypeCheckErrorKind as DeserRowTypeCheckErrorKind, DeserializeRow,
+};
+use scylla_cql::deserialize::value::{
+    BuiltinTypeCheckError as DeserValueTypeCheckError,
+    BuiltinTypeCheckErrorKind as DeserValueTypeCheckErrorKind, DeserializeValue,
+    UdtTypeCheckErrorKind as DeserUdtTypeCheckErrorKind,
+};
+use scylla_cql::frame::response::result::{
+    ColumnSpec, ColumnType, NativeType, TableSpec, UserDefinedType,
+};
+use scylla_cql::serialize::row::{
+    BuiltinSerializationError as SerRowSerializationError,
+    BuiltinSerializationErrorKind as SerRowSerializationErrorKind,
+    BuiltinTypeCheckError as SerRowTypeCheckError,
+    BuiltinTypeCheckErrorKind as SerRowTypeCheckErrorKind, RowSerializationContext, SerializeRow,
+};
+use scylla_cql::serialize::value::{
+    BuiltinSerializationError as SerValueSerializationError,
+    BuiltinSerializationErrorKind as SerValueSerializationErrorKind,
+    BuiltinTypeCheckError as SerValueTypeCheckError,
+    BuiltinTypeCheckErrorKind as SerValueTypeCheckErrorKind, SerializeValue,
+    UdtSerializationErrorKind as SerUdtSerializationErrorKind,
+    UdtTypeCheckErrorKind as SerUdtTypeCheckErrorKind,
+};
+use scylla_cql::serialize::writers::{CellWriter, RowWriter};
+use thiserror::Error;
+
+use crate::cluster::metadata::{ColumnKind, Keyspace, Table};
+
+/// An error returned by the schema validation of a Rust type.
+#[derive(Error, Debug, Clone)]
+#[non_exhaustive]
+pub enum SchemaValidationError {
+    /// The keyspace is not present in the schema metadata.
+    ///
+    /// Note that the metadata is empty if schema fetching is disabled in the session config.
+    #[error("Keyspace {keyspace} is not present in the schema metadata")]
+    KeyspaceNotFound { keyspace: String },
+
+    /// The table is not present in the schema metadata.
+    #[error("Table {keyspace}.{table} is not present in the schema metadata")]
+    TableNotFound { keyspace: String, table: String },
+
+    /// The user defined type is not present in the schema metadata.
+    #[error("User defined type {keyspace}.{type_name} is not present in the schema metadata")]
+    UdtNotFound { keyspace: String, type_name: String },
+
+    /// The Rust type doesn't match the table or the user defined type.
+    #[error(transparent)]
+    Mismatch(#[from] SchemaMismatchReport),
+}
+
+/// All mismatches found between a Rust type and a table or a user defined type.
+#[derive(Error, Debug, Clone)]
+#[non_exhaustive]
+pub struct SchemaMismatchReport {
+    /// Name of the Rust type.
+    pub rust_name: &'static str,
+    /// Keyspace of the table or the user defined type.
+    pub keyspace: String,
+    /// Name of the table or the user defined type.
+    pub name: String,
+    /// The found mismatches. Never empty.
+    pub mismatches: Vec<SchemaMismatch>,
+}
+
+impl Display for SchemaMismatchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Rust type {} does not match {}.{}: ",
+            self.rust_name, self.keyspace, self.name
+        )?;
+        for (i, mismatch) in self.mismatches.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{mismatch}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A single mismatch between a Rust type and a table or a user defined type.
+///
+/// Names refer to columns of a table, or to fields of a user defined type.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum SchemaMismatch {
+    /// The Rust type requires a column which is not present in the schema.
+    NotInSchema { name: String },
+
+    /// The schema contains a column which the Rust type doesn't accept.
+    NotInRustType { name: String },
+
+    /// A column of the primary key is not provided by the Rust type,
+    /// so the type can't be used to write rows of the table.
+    KeyColumnMissing { name: String },
+
+    /// The Rust type can't represent the CQL type of the column.
+    TypeMismatch {
+        name: String,
+        cql_type: ColumnType<'static>,
+        error: Arc<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// The Rust type doesn't match the schema in some other way, e.g. a tuple
+    /// has a different number of elements than the table has columns.
+    Other(Arc<dyn std::error::Error + Send + Sync>),
+}
+
+impl Display for SchemaMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaMismatch::NotInSchema { name } => {
+                write!(
+                    f,
+                    "{name} is required by the Rust type, but not present in the schema"
+                )
+            }
+            SchemaMismatch::NotInRustType { name } => {
+                write!(
+                    f,
+                    "{name} is present in the schema, but not accepted by the Rust type"
+                )
+            }
+            SchemaMismatch::KeyColumnMissing { name } => {
+                write!(
+                    f,
+                    "primary key column {name} is not provided by the Rust type"
+                )
+            }
+            SchemaMismatch::TypeMismatch {
+                name,
+                cql_type,
+                error,
+            } => write!(f, "{name} of CQL type {cql_type:?} does not match: {error}"),
+            SchemaMismatch::Other(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+// The outcome of a single check of a Rust type against a set of columns.
+enum Check {
+    Passed,
+    // The column is not used by the Rust type.
+    Unused(String),
+    NotInRustType(String),
+    TypeMismatch {
+        name: String,
+        error: Arc<dyn std::error::Error + Send + Sync>,
+    },
+    // The Rust type requires these columns.
+    Missing(Vec<String>),
+    Other(Arc<dyn std::error::Error + Send + Sync>),
+}
+
+type Columns = Vec<(String, ColumnType<'static>)>;
+
+// The type checks of the driver stop at the first error. In order to report all
+// the mismatches, the Rust type is checked repeatedly, each time without the columns
+// which were already found not to match.
+//
+// `resolve` provides the types of columns which are not a part of the schema, but
+// can still be requested by the Rust type, e.g. `writetime(col)`. Returns the found
+// mismatches, and the columns which are not used by the Rust type.
+fn find_mismatches(
+    mut columns: Columns,
+    mut check: impl FnMut(&Columns) -> Check,
+    resolve: impl Fn(&str) -> Option<ColumnType<'static>>,
+) -> (Vec<SchemaMismatch>, Vec<String>) {
+    let mut mismatches = Vec::new();
+    let mut unused = Vec::new();
+    let mut resolved = HashSet::new();
+
+    let remove = |columns: &mut Columns, name: &str| {
+        let position = columns.iter().position(|(column, _)| column == name)?;
+        Some(columns.remove(position).1)
+    };
+
+    loop {
+        match check(&columns) {
+            Check::Passed => break,
+            Check::Unused(name) => {
+                if remove(&mut columns, &name).is_none() {
+                    break;
+                }
+                unused.push(name);
+            }
+            Check::NotInRustType(name) => {
+                if remove(&mut columns, &name).is_none() {
+                    break;
+                }
+                mismatches.push(SchemaMismatch::NotInRustType { name });
+            }
+            Check::TypeMismatch { name, error } => {
+                let Some(cql_type) = remove(&mut columns, &name) else {
+                    mismatches.push(SchemaMismatch::Other(error));
+                    break;
+                };
+                mismatches.push(SchemaMismatch::TypeMismatch {
+                    name,
+                    cql_type,
+                    error,
+                });
+            }
+            Check::Missing(names) => {
+                let mut resolved_any = false;
+                for name in names {
+                    if let Some(typ) = resolve(&name).filter(|_| resolved.insert(name.clone())) {
+                        columns.push((name, typ));
+                        resolved_any = true;
+                    } else if !mismatches.iter().any(|mismatch| {
+                        matches!(mismatch, SchemaMismatch::TypeMismatch { name: n, .. } if *n == name)
+                    }) {
+                        // Columns whose types don't match are also reported as missing,
+                        // as they were removed.
+                        mismatches.push(SchemaMismatch::NotInSchema { name });
+                    }
+                }
+                if !resolved_any {
+                    break;
+                }
+            }
+            Check::Other(error) => {
+                mismatches.push(SchemaMismatch::Other(error));
+                break;
+            }
+        }
+    }
+
+    (mismatches, unused)
+}
+
+fn get_table<'a>(
+    keyspaces: &'a HashMap<String, Keyspace>,
+    keyspace: &str,
+    table: &str,
+) -> Result<&'a Table, SchemaValidationError> {
+    keyspaces
+        .get(keyspace)
+        .ok_or_else(|| SchemaValidationError::KeyspaceNotFound {
+            keyspace: keyspace.to_owned(),
+        })?
+        .tables
+        .get(table)
+        .ok_or_else(|| SchemaValidationError::TableNotFound {
+            keyspace: keyspace.to_owned(),
+            table: table.to_owned(),
+        })
+}
+
+fn get_udt<'a>(
+    keyspaces: &'a HashMap<String, Keyspace>,
+    keyspace: &str,
+    type_name: &str,
+) -> Result<&'a Arc<UserDefinedType<'static>>, SchemaValidationError> {
+    keyspaces
+        .get(keyspace)
+        .ok_or_else(|| SchemaValidationError::KeyspaceNotFound {
+            keyspace: keyspace.to_owned(),
+        })?
+        .user_defined_types
+        .get(type_name)
+        .ok_or_else(|| SchemaValidationError::UdtNotFound {
+            keyspace: keyspace.to_owned(),
+            type_name: type_name.to_owned(),
+        })
+}
+
+// Columns of the table, in the same order as in the result of `SELECT *`.
+fn table_columns(table: &Table) -> Columns {
+    let mut regular_columns: Vec<_> = table
+        .columns
+        .iter()
+        .filter(|(_, column)| matches!(column.kind, ColumnKind::Regular | ColumnKind::Static))
+        .map(|(name, column)| (name.clone(), column.typ.clone()))
+        .collect();
+    regular_columns.sort_by(|(a, _), (b, _)| a.cmp(b));
+    table
+        .partition_key
+        .iter()
+        .chain(table.clustering_key.iter())
+        .map(|name| (name.clone(), table.columns[name].typ.clone()))
+        .chain(regular_columns)
+        .collect()
+}
+
+fn column_specs<'a>(columns: &'a Columns, table_spec: &TableSpec<'a>) -> Vec<ColumnSpec<'a>> {
+    columns
+        .iter()
+        .map(|(name, typ)| ColumnSpec::borrowed(name, typ.clone(), table_spec.clone()))
+        .collect()
+}
+
+fn udt_type(udt: &UserDefinedType<'static>, fields: &Columns) -> ColumnType<'static> {
+    ColumnType::UserDefinedType {
+        frozen: false,
+        definition: Arc::new(UserDefinedType {
+            name: udt.name.clone(),
+            keyspace: udt.keyspace.clone(),
+            field_types: fields
+                .iter()
+                .map(|(name, typ)| (name.clone().into(), typ.clone()))
+                .collect(),
+        }),
+    }
+}
+
+fn into_result(
+    mismatches: Vec<SchemaMismatch>,
+    rust_name: &'static str,
+    keyspace: &str,
+    name: &str,
+) -> Result<(), SchemaValidationError> {
+    if mismatches.is_empty() {
+        return Ok(());
+    }
+    Err(SchemaMismatchReport {
+        rust_name,
+        keyspace: keyspace.to_owned(),
+        name: name.to_owned(),
+        mismatches,
+    }
+    .into())
+}
+
+pub(crate) fn validate_row_type<RowT>(
+    keyspaces: &HashMap<String, Keyspace>,
+    keyspace: &str,
+    table_name: &str,
+) -> Result<(), SchemaValidationError>
+where
+    RowT: for<'frame, 'metadata> DeserializeRow<'frame, 'metadata>,
+{
+    let table = get_table(keyspaces, keyspace, table_name)?;
+    let table_spec = TableSpec::borrowed(keyspace, table_name);
+
+    // Selectors of write timestamps and TTLs of the regular columns.
+    let resolve = |name: &str| {
+        let (selector, column) = name.strip_suffix(')')?.split_once('(')?;
+        let column = table.columns.get(column)?;
+        let is_regular = matches!(column.kind, ColumnKind::Regular | ColumnKind::Static);
+        let is_multi_cell = matches!(
+            column.typ,
+            ColumnType::Collection { frozen: false, .. }
+                | ColumnType::UserDefinedType { frozen: false, .. }
+        );
+        let typ = match selector {
+            "writetime" => NativeType::BigInt,
+            "ttl" => NativeType::Int,
+            _ => return None,
+        };
+        (is_regular && !is_multi_cell).then_some(ColumnType::Native(typ))
+    };
+
+    let (mismatches, _) = find_mismatches(
+        table_columns(table),
+        |columns| {
+            let specs = column_specs(columns, &table_spec);
+            let Err(err) = RowT::type_check(&specs) else {
+                return Check::Passed;
+            };
+            match err
+                .downcast_ref::<DeserRowTypeCheckError>()
+                .map(|e| &e.kind)
+            {
+                Some(DeserRowTypeCheckErrorKind::ColumnWithUnknownName { column_name, .. }) => {
+                    Check::Unused(column_name.clone())
+                }
+                Some(DeserRowTypeCheckErrorKind::ColumnTypeCheckFailed {
+                    column_name,
+                    err,
+                    ..
+                }) => Check::TypeMismatch {
+                    name: column_name.clone(),
+                    error: Arc::new(err.clone()),
+                },
+                Some(DeserRowTypeCheckErrorKind::ValuesMissingForColumns { column_names }) => {
+                    Check::Missing(column_names.iter().map(|name| name.to_string()).collect())
+                }
+                _ => Check::Other(Arc::new(err)),
+            }
+        },
+        resolve,
+    );
+
+    into_result(
+        mismatches,
+        std::any::type_name::<RowT>(),
+        keyspace,
+        table_name,
+    )
+}
+
+pub(crate) fn validate_row_values<RowT: SerializeRow + ?Sized>(
+    keyspaces: &HashMap<String, Keyspace>,
+    keyspace: &str,
+    table_name: &str,
+    values: &RowT,
+) -> Result<(), SchemaValidationError> {
+    let table = get_table(keyspaces, keyspace, table_name)?;
+    let table_spec = TableSpec::borrowed(keyspace, table_name);
+
+    let (mut mismatches, unused) = find_mismatches(
+        table_columns(table),
+        |columns| {
+            let specs = column_specs(columns, &table_spec);
+            let ctx = RowSerializationContext::from_specs(&specs);
+            let mut data = Vec::new();
+            let Err(err) = values.serialize(&ctx, &mut RowWriter::new(&mut data)) else {
+                return Check::Passed;
+            };
+            if let Some(e) = err.downcast_ref::<SerRowTypeCheckError>() {
+                match &e.kind {
+                    SerRowTypeCheckErrorKind::ValueMissingForColumn { name } => {
+                        return Check::Unused(name.clone())
+                    }
+                    SerRowTypeCheckErrorKind::NoColumnWithName { name } => {
+                        return Check::Missing(vec![name.clone()])
+                    }
+                    _ => {}
+                }
+            }
+            if let Some(SerRowSerializationError {
+                kind: SerRowSerializationErrorKind::ColumnSerializationFailed { name, err },
+                ..
+            }) = err.downcast_ref::<SerRowSerializationError>()
+            {
+                return Check::TypeMismatch {
+                    name: name.clone(),
+                    error: Arc::new(err.clone()),
+                };
+            }
+            Check::Other(Arc::new(err))
+        },
+        |_| None,
+    );
+
+    mismatches.extend(
+        unused
+            .into_iter()
+            .filter(|name| table.columns[name].kind != ColumnKind::Regular)
+            .filter(|name| table.columns[name].kind != ColumnKind::Static)
+            .map(|name| SchemaMismatch::KeyColumnMissing { name }),
+    );
+
+    into_result(
+        mismatches,
+        std::any::type_name::<RowT>(),
+        keyspace,
+        table_name,
+    )
+}
+
+pub(crate) fn validate_udt<T>(
+    keyspaces: &HashMap<String, Keyspace>,
+    keyspace: &str,
+    type_name: &str,
+) -> Result<(), SchemaValidationError>
+where
+    T: for<'frame, 'metadata> DeserializeValue<'frame, 'metadata>,
+{
+    let udt = get_udt(keyspaces, keyspace, type_name)?;
+
+    let (mismatches, _) = find_mismatches(
+        udt.field_types
+            .iter()
+            .map(|(name, typ)| (name.to_string(), typ.clone()))
+            .collect(),
+        |fields| {
+            let Err(err) = T::type_check(&udt_type(udt, fields)) else {
+                return Check::Passed;
+            };
+            let kind = match err.downcast_ref::<DeserValueTypeCheckError>() {
+                Some(DeserValueTypeCheckError {
+                    kind: DeserValueTypeCheckErrorKind::UdtError(kind),
+                    ..
+                }) => kind,
+                _ => return Check::Other(Arc::new(err)),
+            };
+            match kind {
+                DeserUdtTypeCheckErrorKind::ExcessFieldInUdt { db_field_name } => {
+                    Check::NotInRustType(db_field_name.clone())
+                }
+                DeserUdtTypeCheckErrorKind::FieldTypeCheckFailed { field_name, err } => {
+                    Check::TypeMismatch {
+                        name: field_name.clone(),
+                        error: Arc::new(err.clone()),
+                    }
+                }
+                DeserUdtTypeCheckErrorKind::ValuesMissingForUdtFields { field_names } => {
+                    Check::Missing(field_names.iter().map(|name| name.to_string()).collect())
+                }
+                _ => Check::Other(Arc::new(err)),
+            }
+        },
+        |_| None,
+    );
+
+    into_result(mismatches, std::any::type_name::<T>(), keyspace, type_name)
+}
+
+pub(crate) fn validate_udt_value<T: SerializeValue + ?Sized>(
+    keyspaces: &HashMap<String, Keyspace>,
+    keyspace: &str,
+    type_name: &str,
+    value: &T,
+) -> Result<(), SchemaValidationError> {
+    let udt = get_udt(keyspaces, keyspace, type_name)?;
+
+    let (mismatches, _) = find_mismatches(
+        udt.field_types
+            .iter()
+            .map(|(name, typ)| (name.to_string(), typ.clone()))
+            .collect(),
+        |fields| {
+            let mut data = Vec::new();
+            let Err(err) = value.serialize(&udt_type(udt, fields), CellWriter::new(&mut data))
+            else {
+                return Check::Passed;
+            };
+            if let Some(SerValueTypeCheckError {
+                kind: SerValueTypeCheckErrorKind::UdtError(kind),
+                ..
+            }) = err.downcast_ref::<SerValueTypeCheckError>()
+            {
+                match kind {
+                    SerUdtTypeCheckErrorKind::NoSuchFieldInUdt { field_name } => {
+                        return Check::NotInRustType(field_name.clone())
+                    }
+                    SerUdtTypeCheckErrorKind::ValueMissingForUdtField { field_name } => {
+                        return Check::Missing(vec![field_name.clone()])
+                    }
+                    _ => {}
+                }
+            }
+            if let Some(SerValueSerializationError {
+                kind:
+                    SerValueSerializationErrorKind::UdtError(
+                        SerUdtSerializationErrorKind::FieldSerializationFailed { field_name, err },
+                    ),
+                ..
+            }) = err.downcast_ref::<SerValueSerializationError>()
+            {
+                return Check::TypeMismatch {
+                    name: field_name.clone(),
+                    error: Arc::new(err.clone()),
+                };
+            }
+            Check::Other(Arc::new(err))
+        },
+        |_| None,
+    );
+
+    into_result(mismatches, std::any::type_name::<T>(), keyspace, type_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use scylla_cql::frame::response::result::{ColumnType, NativeType};
+    use scylla_cql::value::CqlValue;
+
+    use super::{
+        find_mismatches, validate_row_type, validate_row_values, validate_udt, validate_udt_value,
+        SchemaMismatch, SchemaValidationError,
+    };
+    use crate::cluster::codegen::parse_ddl;
+    use crate::cluster::metadata::Keyspace;
+    use crate::{DeserializeRow, DeserializeValue, SerializeRow, SerializeValue};
+
+    fn keyspaces() -> HashMap<String, Keyspace> {
+        parse_ddl(
+            "CREATE TYPE ks.address (street text, number int);
+             CREATE TABLE ks.users (
+                 id int, name text, age int, address frozen<address>, tags set<text>,
+                 PRIMARY KEY (id, name)
+             );",
+        )
+        .unwrap()
+    }
+
+    fn mismatches(result: Result<(), SchemaValidationError>) -> Vec<String> {
+        match result {
+            Err(SchemaValidationError::Mismatch(report)) => report
+                .mismatches
+                .iter()
+                .map(|mismatch| match mismatch {
+                    SchemaMismatch::NotInSchema { name } => format!("not in schema: {name}"),
+                    SchemaMismatch::NotInRustType { name } => format!("not in type: {name}"),
+                    SchemaMismatch::KeyColumnMissing { name } => format!("key missing: {name}"),
+                    SchemaMismatch::TypeMismatch { name, .. } => format!("type: {name}"),
+                    SchemaMismatch::Other(_) => "other".to_owned(),
+                })
+                .collect(),
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[derive(DeserializeValue, SerializeValue)]
+    #[scylla(crate = crate)]
+    struct Address {
+        street: Option<String>,
+        number: Option<i32>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(DeserializeRow)]
+    #[scylla(crate = crate)]
+    struct User {
+        id: i32,
+        name: String,
+        address: Option<Address>,
+        #[scylla(writetime_of = "age")]
+        age_writetime: Option<i64>,
+    }
+
+    #[derive(SerializeRow)]
+    #[scylla(crate = crate)]
+    struct NewUser {
+        id: i32,
+        name: String,
+        address: Option<Address>,
+    }
+
+    #[derive(DeserializeRow, SerializeRow)]
+    #[scylla(crate = crate)]
+    struct BrokenUser {
+        id: i64,
+        age: Option<i32>,
+        tags: Option<Vec<i32>>,
+        email: Option<String>,
+        phone: Option<String>,
+    }
+
+    #[derive(DeserializeValue, SerializeValue)]
+    #[scylla(crate = crate, forbid_excess_udt_fields)]
+    struct BrokenAddress {
+        street: Option<i32>,
+        city: Option<String>,
+    }
+
+    #[test]
+    fn test_validate_row_type() {
+        let state = keyspaces();
+        validate_row_type::<User>(&state, "ks", "users").unwrap();
+        validate_row_type::<(i32, String, Option<i32>)>(&state, "ks", "users").unwrap_err();
+
+        assert_eq!(
+            mismatches(validate_row_type::<BrokenUser>(&state, "ks", "users")),
+            vec![
+                "type: id",
+                "type: tags",
+                "not in schema: email",
+                "not in schema: phone"
+            ]
+        );
+        assert_matches::assert_matches!(
+            validate_row_type::<User>(&state, "ks", "missing"),
+            Err(SchemaValidationError::TableNotFound { .. })
+        );
+        assert_matches::assert_matches!(
+            validate_row_type::<User>(&state, "missing", "users"),
+            Err(SchemaValidationError::KeyspaceNotFound { .. })
+        );
+    }
+
+    #[test]
+    fn test_validate_row_values() {
+        let state = keyspaces();
+        let user = NewUser {
+            id: 1,
+            name: "John".to_owned(),
+            address: Some(Address {
+                street: None,
+                number: Some(1),
+            }),
+        };
+        validate_row_values(&state, "ks", "users", &user).unwrap();
+
+        let values = HashMap::from([("id", CqlValue::Int(1))]);
+        assert_eq!(
+            mismatches(validate_row_values(&state, "ks", "users", &values)),
+            vec!["key missing: name"]
+        );
+
+        let user = BrokenUser {
+            id: 1,
+            age: Some(30),
+            tags: Some(vec![1]),
+            email: None,
+            phone: None,
+        };
+        assert_eq!(
+            mismatches(validate_row_values(&state, "ks", "users", &user)),
+            // Serialization stops at the first field without a column, which is
+            // `id` in this case, so `email` is not reported.
+            vec!["type: id", "type: tags", "key missing: name"]
+        );
+    }
+
+    #[test]
+    fn test_validate_udt() {
+        let state = keyspaces();
+        validate_udt::<Address>(&state, "ks", "address").unwrap();
+        assert_eq!(
+            mismatches(validate_udt::<BrokenAddress>(&state, "ks", "address")),
+            vec!["type: street", "not in type: number", "not in schema: city"]
+        );
+        assert_matches::assert_matches!(
+            validate_udt::<Address>(&state, "ks", "missing"),
+            Err(SchemaValidationError::UdtNotFound { .. })
+        );
+
+        let address = Address {
+            street: Some("Main".to_owned()),
+            number: None,
+        };
+        validate_udt_value(&state, "ks", "address", &address).unwrap();
+        let address = BrokenAddress {
+            street: Some(1),
+            city: None,
+        };
+        assert_eq!(
+            mismatches(validate_udt_value(&state, "ks", "address", &address)),
+            vec!["type: street", "not in type: number"]
+        );
+    }
+
+    #[test]
+    fn test_find_mismatches_terminates() {
+        // A check which keeps reporting a column that is not there.
+        let (mismatches, unused) = find_mismatches(
+            vec![("a".to_owned(), ColumnType::Native(NativeType::Int))],
+            |_| super::Check::Unused("b".to_owned()),
+            |_| None,
+        );
+        assert!(mismatches.is_empty());
+        assert!(unused.is_empty());
+    }
+}
@@ -1,5 +1,7 @@
 use crate::client::session::TABLET_CHANNEL_SIZE;
-use crate::errors::{MetadataError, NewSessionError, RequestAttemptError, UseKeyspaceError};
+use crate::errors::{
+    ConnectionPoolError, MetadataError, NewSessionError, RequestAttemptError, UseKeyspaceError,
+};
 use crate::frame::response::event::Event;
 use crate::network::{PoolConfig, VerifiedKeyspaceName};
 #[cfg(feature = "metrics")]
@@ -29,6 +31,7 @@ pub(crate) struct Cluster {
 
     refresh_channel: tokio::sync::mpsc::Sender<RefreshRequest>,
     use_keyspace_channel: tokio::sync::mpsc::Sender<UseKeyspaceRequest>,
+    shutdown_channel: tokio::sync::mpsc::Sender<ShutdownRequest>,
 
     _worker_handle: RemoteHandle<()>,
 }
@@ -60,6 +63,9 @@ struct ClusterWorker {
     // Channel used to receive use keyspace requests
     use_keyspace_channel: tokio::sync::mpsc::Receiver<UseKeyspaceRequest>,
 
+    // Channel used to receive shutdown requests
+    shutdown_channel: tokio::sync::mpsc::Receiver<ShutdownRequest>,
+
     // Channel used to receive server events
     server_events_channel: tokio::sync::mpsc::Receiver<Event>,
 
@@ -96,6 +102,11 @@ struct UseKeyspaceRequest {
     response_chan: tokio::sync::oneshot::Sender<Result<(), UseKeyspaceError>>,
 }
 
+#[derive(Debug)]
+struct ShutdownRequest {
+    response_chan: tokio::sync::oneshot::Sender<()>,
+}
+
 impl Cluster {
     #[expect(clippy::too_many_arguments)]
     pub(crate) async fn new(
@@ -111,6 +122,7 @@ impl Cluster {
     ) -> Result<Cluster, NewSessionError> {
         let (refresh_sender, refresh_receiver) = tokio::sync::mpsc::channel(32);
         let (use_keyspace_sender, use_keyspace_receiver) = tokio::sync::mpsc::channel(32);
+        let (shutdown_sender, shutdown_receiver) = tokio::sync::mpsc::channel(1);
         let (server_events_sender, server_events_receiver) = tokio::sync::mpsc::channel(32);
         let (control_connection_repair_sender, control_connection_repair_receiver) =
             tokio::sync::broadcast::channel(32);
@@ -160,6 +172,8 @@ impl Cluster {
             use_keyspace_channel: use_keyspace_receiver,
             used_keyspace: None,
 
+            shutdown_channel: shutdown_receiver,
+
             host_filter,
             cluster_metadata_refresh_interval,
 
@@ -174,6 +188,7 @@ impl Cluster {
             state: cluster_state,
             refresh_channel: refresh_sender,
             use_keyspace_channel: use_keyspace_sender,
+            shutdown_channel: shutdown_sender,
             _worker_handle: worker_handle,
         };
 
@@ -218,6 +233,25 @@ impl Cluster {
 
         response_receiver.await.unwrap() // ClusterWorker always responds
     }
+
+    /// Closes the control connection and all connection pools, and stops
+    /// refreshing the metadata. Afterwards, no new connections are opened.
+    pub(crate) async fn shutdown(&self) {
+        let (response_sender, response_receiver) = tokio::sync::oneshot::channel();
+
+        self.shutdown_channel
+            .send(ShutdownRequest {
+                response_chan: response_sender,
+            })
+            .await
+            .expect("Bug in Cluster::shutdown sending");
+        // Other end of this channel is in ClusterWorker, can't be dropped while we have &self to Cluster with _worker_handle
+
+        response_receiver
+            .await
+            .expect("Bug in Cluster::shutdown receiving")
+        // ClusterWorker always responds
+    }
 }
 
 impl ClusterWorker {
@@ -323,6 +357,16 @@ impl ClusterWorker {
 
                     continue; // Don't go to refreshing, wait for the next event
                 }
+                recv_res = self.shutdown_channel.recv() => {
+                    match recv_res {
+                        Some(request) => {
+                            self.close_connections();
+                            let _ = request.response_chan.send(());
+                            return self.work_after_shutdown().await;
+                        }
+                        None => return, // If shutdown_channel was closed then cluster was dropped, we can stop working
+                    }
+                }
                 recv_res = self.control_connection_repair_channel.recv() => {
                     match recv_res {
                         Ok(()) => {
@@ -360,6 +404,61 @@ impl ClusterWorker {
         }
     }
 
+    fn close_connections(&mut self) {
+        debug!("Shutting down the cluster worker, closing all connections");
+        self.metadata_reader.close();
+        for node in self.cluster_state.load().known_peers.values() {
+            node.close_pool();
+        }
+    }
+
+    // After shutdown, the worker no longer refreshes the metadata, so that
+    // no connections are reopened. It still serves the requests sent by `Cluster`,
+    // because the callers expect a response.
+    async fn work_after_shutdown(mut self) {
+        let mut tablets = Vec::new();
+        loop {
+            tokio::select! {
+                recv_res = self.refresh_channel.recv() => {
+                    match recv_res {
+                        Some(request) => {
+                            let _ = request
+                                .response_chan
+                                .send(Err(MetadataError::ConnectionPoolError(ConnectionPoolError::Closed)));
+                        }
+                        None => return,
+                    }
+                }
+                recv_res = self.use_keyspace_channel.recv() => {
+                    match recv_res {
+                        Some(request) => {
+                            // All pools are closed, so this only waits for the responses from them.
+                            let cluster_state = self.cluster_state.load_full();
+                            tokio::spawn(Self::handle_use_keyspace_request(cluster_state, request));
+                        }
+                        None => return,
+                    }
+                }
+                recv_res = self.shutdown_channel.recv() => {
+                    match recv_res {
+                        Some(request) => {
+                            let _ = request.response_chan.send(());
+                        }
+                        None => return,
+                    }
+                }
+                // Requests cancelled by the shutdown may still be sending tablet
+                // information, don't let them block on a full channel.
+                tablets_count = self.tablets_channel.recv_many(&mut tablets, TABLET_CHANNEL_SIZE) => {
+                    if tablets_count == 0 {
+                        return;
+                    }
+                    tablets.clear();
+                }
+            }
+        }
+    }
+
     async fn handle_use_keyspace_request(
         cluster_state: Arc<ClusterState>,
         request: UseKeyspaceRequest,
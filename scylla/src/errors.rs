@@ -8,6 +8,7 @@ use std::sync::Arc;
 use thiserror::Error;
 
 use crate::frame::response;
+use crate::frame::ProtocolVersion;
 
 // Re-export error types from pager module.
 pub use crate::client::pager::{NextPageError, NextRowError};
@@ -31,7 +32,7 @@ pub use scylla_cql::frame::frame_errors::{
     CqlAuthChallengeParseError, CqlAuthSuccessParseError, CqlAuthenticateParseError,
     CqlErrorParseError, CqlEventParseError, CqlRequestSerializationError, CqlResponseParseError,
     CqlResultParseError, CqlSupportedParseError, FrameBodyExtensionsParseError,
-    FrameHeaderParseError,
+    FrameHeaderParseError, SegmentParseError, SegmentSerializationError,
 };
 pub use scylla_cql::frame::request::CqlRequestKind;
 pub use scylla_cql::frame::response::error::{DbError, OperationType, WriteType};
@@ -75,6 +76,22 @@ pub enum ExecutionError {
     )]
     RequestTimeout(std::time::Duration),
 
+    /// A [`RequestInterceptor`](crate::policies::interceptor::RequestInterceptor)
+    /// rejected the request.
+    #[error("Request rejected by an interceptor: {0}")]
+    RejectedByInterceptor(Arc<dyn Error + Send + Sync>),
+
+    /// A [`RequestThrottler`](crate::policies::throttler::RequestThrottler)
+    /// did not let the request through.
+    #[error("Request rejected by the throttler: {0}")]
+    RequestThrottled(#[from] ThrottlingError),
+
+    /// The session was shut down with [`Session::shutdown`](crate::client::session::Session::shutdown)
+    /// before the request was started, or the request was cancelled because it did not complete
+    /// before the shutdown deadline.
+    #[error("The session has been shut down")]
+    SessionShutDown,
+
     /// 'USE KEYSPACE <>' request failed.
     #[error("'USE KEYSPACE <>' request failed: {0}")]
     UseKeyspaceError(#[from] UseKeyspaceError),
@@ -477,6 +494,10 @@ pub enum ConnectionPoolError {
     /// A corresponding node was disabled by a host filter.
     #[error("The node has been disabled by a host filter")]
     NodeDisabledByHostFilter,
+
+    /// A connection pool has been closed, because the session was shut down.
+    #[error("The pool has been closed because the session was shut down")]
+    Closed,
 }
 
 /// An error that appeared on a connection level.
@@ -531,6 +552,48 @@ impl ConnectionError {
 
         false
     }
+
+    /// Checks if the error means that the node rejected `requested`,
+    /// the protocol version used to open the connection.
+    ///
+    /// Nodes respond with a protocol error to the first request sent
+    /// in an unsupported version. The error is only considered a rejection
+    /// of the version if its message names the requested version.
+    pub(crate) fn is_unsupported_protocol_version(&self, requested: ProtocolVersion) -> bool {
+        if let ConnectionError::ConnectionSetupRequestError(ConnectionSetupRequestError {
+            error: ConnectionSetupRequestErrorKind::DbError(DbError::ProtocolError, message),
+            ..
+        }) = self
+        {
+            return rejected_protocol_version(message) == Some(requested.as_u8());
+        }
+
+        false
+    }
+}
+
+/// Messages of the protocol errors sent in response to a request
+/// in an unsupported protocol version, up to the number of the version.
+const UNSUPPORTED_PROTOCOL_VERSION_MESSAGE_PREFIXES: [&str; 3] = [
+    // ScyllaDB: "Invalid or unsupported protocol version: 5"
+    "Invalid or unsupported protocol version: ",
+    // Cassandra: "Invalid or unsupported protocol version (6); supported versions are (...)"
+    "Invalid or unsupported protocol version (",
+    // Cassandra, if the version is in beta: "Beta version of the protocol used (5/v5-beta), but USE_BETA flag is unset"
+    "Beta version of the protocol used (",
+];
+
+/// Returns the protocol version which a node reported as unsupported
+/// in the message of a protocol error, or `None` if the error is not
+/// about an unsupported protocol version.
+fn rejected_protocol_version(message: &str) -> Option<u8> {
+    let version = UNSUPPORTED_PROTOCOL_VERSION_MESSAGE_PREFIXES
+        .iter()
+        .find_map(|prefix| message.strip_prefix(prefix))?;
+    let digits_len = version
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(version.len());
+    version[..digits_len].parse().ok()
 }
 
 /// Error caused by failed address translation done before establishing connection
@@ -681,6 +744,14 @@ pub enum BrokenConnectionErrorKind {
     #[error("Failed to deserialize frame: {0}")]
     FrameHeaderParseError(FrameHeaderParseError),
 
+    /// Failed to read a segment (protocol v5 and later).
+    #[error("Failed to read segment: {0}")]
+    SegmentParseError(SegmentParseError),
+
+    /// Failed to wrap a request frame into segments (protocol v5 and later).
+    #[error("Failed to serialize segment: {0}")]
+    SegmentSerializationError(SegmentSerializationError),
+
     /// Failed to handle a CQL event (server response received on stream -1).
     #[error("Failed to handle server event: {0}")]
     CqlEventHandlingError(#[from] CqlEventHandlingError),
@@ -772,6 +843,20 @@ pub enum RequestError {
         )]
     RequestTimeout(std::time::Duration),
 
+    /// A [`RequestInterceptor`](crate::policies::interceptor::RequestInterceptor)
+    /// rejected the request.
+    #[error("Request rejected by an interceptor: {0}")]
+    RejectedByInterceptor(Arc<dyn Error + Send + Sync>),
+
+    /// A [`RequestThrottler`](crate::policies::throttler::RequestThrottler)
+    /// did not let the request through.
+    #[error("Request rejected by the throttler: {0}")]
+    RequestThrottled(#[from] ThrottlingError),
+
+    /// The session has been shut down.
+    #[error("The session has been shut down")]
+    SessionShutDown,
+
     /// Failed to execute request.
     #[error(transparent)]
     LastAttemptError(#[from] RequestAttemptError),
@@ -783,11 +868,35 @@ impl RequestError {
             RequestError::EmptyPlan => ExecutionError::EmptyPlan,
             RequestError::ConnectionPoolError(e) => e.into(),
             RequestError::RequestTimeout(dur) => ExecutionError::RequestTimeout(dur),
+            RequestError::RejectedByInterceptor(e) => ExecutionError::RejectedByInterceptor(e),
+            RequestError::RequestThrottled(e) => ExecutionError::RequestThrottled(e),
+            RequestError::SessionShutDown => ExecutionError::SessionShutDown,
             RequestError::LastAttemptError(e) => ExecutionError::LastAttemptError(e),
         }
     }
 }
 
+/// An error returned by a [`RequestThrottler`](crate::policies::throttler::RequestThrottler)
+/// which did not let a request through.
+#[derive(Error, Debug, Clone)]
+#[non_exhaustive]
+pub enum ThrottlingError {
+    /// The queue of requests waiting to be let through is full.
+    #[error("The throttling queue is full (max length: {max_queue_length})")]
+    QueueFull { max_queue_length: usize },
+
+    /// The request waited in the queue for too long.
+    #[error(
+        "Request waited in the throttling queue for more than {}ms",
+        std::time::Duration::as_millis(.0)
+    )]
+    QueueTimeout(std::time::Duration),
+
+    /// An error returned by a custom throttler.
+    #[error("{0}")]
+    Other(Arc<dyn Error + Send + Sync>),
+}
+
 /// An error that occurred during a single attempt of:
 /// - `QUERY`
 /// - `PREPARE`
@@ -960,7 +1069,12 @@ pub enum ClusterStateTokenError {
 mod tests {
     use scylla_cql::Consistency;
 
-    use super::{DbError, ExecutionError, RequestAttemptError, WriteType};
+    use super::{
+        rejected_protocol_version, ConnectionError, ConnectionSetupRequestError,
+        ConnectionSetupRequestErrorKind, CqlRequestKind, DbError, ExecutionError,
+        RequestAttemptError, WriteType,
+    };
+    use crate::frame::ProtocolVersion;
 
     #[test]
     fn write_type_from_str() {
@@ -1017,4 +1131,69 @@ mod tests {
 
         assert_eq!(execution_error_displayed, expected_execution_err_msg);
     }
+
+    #[test]
+    fn rejected_protocol_version_from_message() {
+        let test_cases: [(&str, Option<u8>); 6] = [
+            // ScyllaDB
+            ("Invalid or unsupported protocol version: 5", Some(5)),
+            // Cassandra
+            (
+                "Invalid or unsupported protocol version (5); supported versions are (3/v3, 4/v4)",
+                Some(5),
+            ),
+            (
+                "Invalid or unsupported protocol version (6); supported versions are (3/v3, 4/v4, 5/v5, 6/v6-beta)",
+                Some(6),
+            ),
+            (
+                "Beta version of the protocol used (5/v5-beta), but USE_BETA flag is unset",
+                Some(5),
+            ),
+            // Other protocol errors
+            ("Unknown opcode 42", None),
+            ("Invalid or unsupported protocol version: ", None),
+        ];
+
+        for (message, expected_version) in test_cases {
+            assert_eq!(
+                rejected_protocol_version(message),
+                expected_version,
+                "{message}"
+            );
+        }
+    }
+
+    #[test]
+    fn unsupported_protocol_version_error() {
+        let make_error = |error, message: &str| {
+            ConnectionError::ConnectionSetupRequestError(ConnectionSetupRequestError::new(
+                CqlRequestKind::Options,
+                ConnectionSetupRequestErrorKind::DbError(error, message.to_owned()),
+            ))
+        };
+
+        let rejection = make_error(
+            DbError::ProtocolError,
+            "Invalid or unsupported protocol version: 5",
+        );
+        assert!(rejection.is_unsupported_protocol_version(ProtocolVersion::V5));
+        // The node rejected another version than the one requested.
+        assert!(!rejection.is_unsupported_protocol_version(ProtocolVersion::V4));
+
+        // Other protocol errors don't mean that the version is unsupported,
+        // even if they mention a protocol version.
+        let other = make_error(
+            DbError::ProtocolError,
+            "Protocol version 5 requires a larger frame",
+        );
+        assert!(!other.is_unsupported_protocol_version(ProtocolVersion::V5));
+
+        // Neither do errors other than protocol errors.
+        let other = make_error(
+            DbError::ServerError,
+            "Invalid or unsupported protocol version: 5",
+        );
+        assert!(!other.is_unsupported_protocol_version(ProtocolVersion::V5));
+    }
 }
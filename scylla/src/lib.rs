@@ -97,9 +97,14 @@
 #[doc(hidden)]
 pub mod _macro_internal {
     pub use scylla_cql::_macro_internal::*;
+
+    pub use crate::client::table::Table;
+    pub use crate::statement::checked::CheckedStatement;
 }
 
-pub use scylla_cql::{DeserializeRow, DeserializeValue, SerializeRow, SerializeValue};
+pub use scylla_cql::{
+    query, DeserializeRow, DeserializeValue, SerializeRow, SerializeValue, Table,
+};
 
 pub mod value {
     // Every `pub` item is re-exported here, apart from `deser_cql_value`.
@@ -107,13 +112,28 @@ pub mod value {
         Counter, CqlDate, CqlDecimal, CqlDecimalBorrowed, CqlDuration, CqlTime, CqlTimestamp,
         CqlTimeuuid, CqlValue, CqlVarint, CqlVarintBorrowed, MaybeUnset, Row, Unset, ValueOverflow,
     };
+
+    /// Conversion between [`CqlValue`]s and the CQL literal syntax.
+    pub mod literal {
+        pub use scylla_cql::utils::parse::ParseErrorCause;
+        pub use scylla_cql::value::literal::{
+            format_literal, parse_literal, CqlLiteralFormatError, CqlLiteralParseError,
+        };
+    }
+
+    /// Ordering and hashing of [`CqlValue`]s, consistent with the database.
+    pub mod ordering {
+        pub use scylla_cql::value::ordering::{
+            compare_values, hash_value, CqlValueTypeMismatch, OrderedCqlValue,
+        };
+    }
 }
 
 pub mod frame {
-    pub use scylla_cql::frame::{frame_errors, Authenticator, Compression};
+    pub use scylla_cql::frame::{frame_errors, Authenticator, Compression, ProtocolVersion};
     pub(crate) use scylla_cql::frame::{
-        parse_response_body_extensions, protocol_features, read_response_frame, request,
-        server_event_type, FrameParams, SerializedRequest,
+        parse_response_body_extensions, parse_response_frame, protocol_features,
+        read_response_frame, request, segment, server_event_type, FrameParams, SerializedRequest,
     };
 
     pub mod types {
@@ -151,6 +171,12 @@ pub mod serialize {
         };
     }
 
+    /// Contains the [SerializeAs][checked::SerializeAs] trait used by the [query!](crate::query)
+    /// macro to check bind values at compile time.
+    pub mod checked {
+        pub use scylla_cql::serialize::checked::{cql, SerializeAs};
+    }
+
     /// Contains the [SerializeRow][row::SerializeRow] trait and its implementations.
     pub mod row {
         // Main types
@@ -184,11 +210,19 @@ pub mod serialize {
             CellOverflowError, CellValueBuilder, CellWriter, RowWriter, WrittenCellProof,
         };
     }
+
+    /// Serializing values of types implementing `serde::Serialize`.
+    #[cfg(feature = "serde")]
+    pub mod serde_bridge {
+        pub use scylla_cql::serialize::serde_bridge::{Serde, SerdeSerializationError};
+    }
 }
 
 /// Deserializing DB response containing CQL query results.
 pub mod deserialize {
-    pub use scylla_cql::deserialize::{DeserializationError, FrameSlice, TypeCheckError};
+    pub use scylla_cql::deserialize::{
+        DeserializationError, FrameSlice, GetByNameError, TypeCheckError,
+    };
 
     /// Deserializing the whole query result contents.
     pub mod result {
@@ -199,7 +233,8 @@ pub mod deserialize {
     pub mod row {
         pub use scylla_cql::deserialize::row::{
             BuiltinDeserializationError, BuiltinDeserializationErrorKind, BuiltinTypeCheckError,
-            BuiltinTypeCheckErrorKind, ColumnIterator, DeserializeRow, RawColumn,
+            BuiltinTypeCheckErrorKind, ColumnIterator, DeserializeRow, NamedRow, RawColumn,
+            RowSelectors,
         };
     }
 
@@ -207,14 +242,29 @@ pub mod deserialize {
     pub mod value {
         pub use scylla_cql::deserialize::value::{
             BuiltinDeserializationError, BuiltinDeserializationErrorKind, BuiltinTypeCheckError,
-            BuiltinTypeCheckErrorKind, DeserializeValue, Emptiable, ListlikeIterator,
-            MapDeserializationErrorKind, MapIterator, MapTypeCheckErrorKind, MaybeEmpty,
-            SetOrListDeserializationErrorKind, SetOrListTypeCheckErrorKind,
-            TupleDeserializationErrorKind, TupleTypeCheckErrorKind, UdtIterator,
-            UdtTypeCheckErrorKind,
+            BuiltinTypeCheckErrorKind, DeserializeValue, Emptiable, FloatVectorIterator,
+            FloatVectorView, ListlikeIterator, MapDeserializationErrorKind, MapIterator,
+            MapTypeCheckErrorKind, MaybeEmpty, NamedUdt, SetOrListDeserializationErrorKind,
+            SetOrListTypeCheckErrorKind, TupleDeserializationErrorKind, TupleTypeCheckErrorKind,
+            UdtIterator, UdtTypeCheckErrorKind,
         };
     }
 
+    /// Converting query results into Apache Arrow record batches.
+    #[cfg(feature = "arrow-53")]
+    pub mod arrow {
+        pub use scylla_cql::deserialize::arrow::{
+            arrow_data_type, arrow_schema, record_batch_from_rows, ArrowConversionError,
+            RecordBatchBuilder,
+        };
+    }
+
+    /// Deserializing rows and values into types implementing `serde::Deserialize`.
+    #[cfg(feature = "serde")]
+    pub mod serde_bridge {
+        pub use scylla_cql::deserialize::serde_bridge::{Serde, SerdeDeserializationError};
+    }
+
     // Shorthands for better readability.
     pub(crate) trait DeserializeOwnedRow:
         for<'frame, 'metadata> row::DeserializeRow<'frame, 'metadata>
@@ -16,8 +16,9 @@ use crate::frame::{
     self,
     request::{self, batch, execute, query, register, SerializableRequest},
     response::{event::Event, result, Response, ResponseOpcode},
+    segment::{self, SegmentDecoder, SegmentEncoder},
     server_event_type::EventType,
-    FrameParams, SerializedRequest,
+    FrameParams, ProtocolVersion, SerializedRequest,
 };
 use crate::policies::address_translator::{AddressTranslator, UntranslatedPeer};
 use crate::policies::timestamp_generator::TimestampGenerator;
@@ -50,7 +51,7 @@ use std::collections::{BTreeSet, HashMap, HashSet};
 use std::convert::TryFrom;
 use std::net::{IpAddr, SocketAddr};
 use std::num::NonZeroU64;
-use std::sync::atomic::AtomicU64;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8};
 use std::sync::Arc;
 use std::sync::Mutex as StdMutex;
 use std::time::Duration;
@@ -108,6 +109,10 @@ pub(crate) struct Connection {
 struct RouterHandle {
     submit_channel: mpsc::Sender<Task>,
 
+    // Protocol version used to serialize requests sent on the connection.
+    protocol_version: ProtocolVersion,
+    framing: Arc<FramingState>,
+
     // Each request send by `Connection::send_request` needs a unique request id.
     // This field is a monotonic generator of such ids.
     request_id_generator: AtomicU64,
@@ -130,8 +135,15 @@ impl RouterHandle {
         request: &impl SerializableRequest,
         compression: Option<Compression>,
         tracing: bool,
+        custom_payload: Option<&HashMap<String, Bytes>>,
     ) -> Result<TaskResponse, InternalRequestError> {
-        let serialized_request = SerializedRequest::make(request, compression, tracing)?;
+        let serialized_request = SerializedRequest::make(
+            request,
+            self.protocol_version,
+            compression,
+            tracing,
+            custom_payload,
+        )?;
         let request_id = self.allocate_request_id();
 
         let (response_sender, receiver) = oneshot::channel();
@@ -167,6 +179,39 @@ impl RouterHandle {
     }
 }
 
+/// Framing of the frames exchanged on the connection.
+///
+/// Since protocol v5, frames are wrapped in segments once the server
+/// responds to STARTUP with READY or AUTHENTICATE. Both the reader and
+/// the writer need to switch at the same point, so the state is shared.
+#[derive(Default)]
+struct FramingState {
+    segments_enabled: AtomicBool,
+    // Compression negotiated for the connection. In protocol v5 it is
+    // applied to segments instead of frames.
+    segment_compression: StdMutex<Option<Compression>>,
+}
+
+impl FramingState {
+    fn segments_enabled(&self) -> bool {
+        self.segments_enabled
+            .load(std::sync::atomic::Ordering::Acquire)
+    }
+
+    fn enable_segments(&self) {
+        self.segments_enabled
+            .store(true, std::sync::atomic::Ordering::Release)
+    }
+
+    fn segment_compression(&self) -> Option<Compression> {
+        *self.segment_compression.lock().unwrap()
+    }
+
+    fn set_segment_compression(&self, compression: Option<Compression>) {
+        *self.segment_compression.lock().unwrap() = compression;
+    }
+}
+
 #[derive(Default)]
 pub(crate) struct ConnectionFeatures {
     shard_info: Option<ShardInfo>,
@@ -266,6 +311,65 @@ impl<'id: 'map, 'map> SelfIdentity<'id> {
     }
 }
 
+/// Protocol version used to open new connections,
+/// shared by all connections of a session.
+///
+/// Unless the version was explicitly chosen by the user, it starts at
+/// the highest version implemented by the driver and is lowered whenever
+/// a node rejects it. This way, once the version supported by the cluster
+/// has been negotiated, subsequent connections use it right away.
+///
+/// Downgrading is one-way and applies to the whole session: the version is
+/// never raised again, even after the node which rejected the higher version
+/// has been upgraded. A new session has to be created to use it.
+#[derive(Debug)]
+pub(crate) struct SharedProtocolVersion {
+    version: AtomicU8,
+    // False if the version was explicitly chosen by the user.
+    negotiable: bool,
+}
+
+impl SharedProtocolVersion {
+    /// Starts at the highest version implemented by the driver
+    /// and allows downgrading it.
+    pub(crate) fn negotiable() -> Self {
+        Self {
+            version: AtomicU8::new(ProtocolVersion::HIGHEST.as_u8()),
+            negotiable: true,
+        }
+    }
+
+    /// Always uses the given version, failing if a node doesn't support it.
+    pub(crate) fn fixed(version: ProtocolVersion) -> Self {
+        Self {
+            version: AtomicU8::new(version.as_u8()),
+            negotiable: false,
+        }
+    }
+
+    /// Returns the version that new connections should start with.
+    pub(crate) fn get(&self) -> ProtocolVersion {
+        // Only valid protocol versions are ever stored.
+        ProtocolVersion::try_from(self.version.load(std::sync::atomic::Ordering::Relaxed))
+            .expect("invalid protocol version stored")
+    }
+
+    /// Called when a node rejected the `rejected` version.
+    ///
+    /// Returns the version that should be tried next, or `None`
+    /// if downgrading is not possible. The lowered version is remembered
+    /// for subsequent connections.
+    fn downgrade(&self, rejected: ProtocolVersion) -> Option<ProtocolVersion> {
+        if !self.negotiable {
+            return None;
+        }
+        let lower = rejected.lower()?;
+        self.version
+            .fetch_min(lower.as_u8(), std::sync::atomic::Ordering::Relaxed);
+        Some(lower)
+    }
+}
+
 /// Configuration used for new connections.
 ///
 /// Before being used for a particular connection, should be customized
@@ -275,6 +379,7 @@ impl<'id: 'map, 'map> SelfIdentity<'id> {
 pub(crate) struct ConnectionConfig {
     pub(crate) local_ip_address: Option<IpAddr>,
     pub(crate) shard_aware_local_port_range: ShardAwarePortRange,
+    pub(crate) protocol_version: Arc<SharedProtocolVersion>,
     pub(crate) compression: Option<Compression>,
     pub(crate) tcp_nodelay: bool,
     pub(crate) tcp_keepalive_interval: Option<Duration>,
@@ -309,6 +414,7 @@ impl ConnectionConfig {
         HostConnectionConfig {
             local_ip_address: self.local_ip_address,
             shard_aware_local_port_range: self.shard_aware_local_port_range.clone(),
+            protocol_version: self.protocol_version.clone(),
             compression: self.compression,
             tcp_nodelay: self.tcp_nodelay,
             tcp_keepalive_interval: self.tcp_keepalive_interval,
@@ -335,6 +441,7 @@ impl ConnectionConfig {
 pub(crate) struct HostConnectionConfig {
     pub(crate) local_ip_address: Option<IpAddr>,
     pub(crate) shard_aware_local_port_range: ShardAwarePortRange,
+    pub(crate) protocol_version: Arc<SharedProtocolVersion>,
     pub(crate) compression: Option<Compression>,
     pub(crate) tcp_nodelay: bool,
     pub(crate) tcp_keepalive_interval: Option<Duration>,
@@ -361,6 +468,7 @@ impl Default for HostConnectionConfig {
         Self {
             local_ip_address: None,
             shard_aware_local_port_range: ShardAwarePortRange::EPHEMERAL_PORT_RANGE,
+            protocol_version: Arc::new(SharedProtocolVersion::fixed(ProtocolVersion::V4)),
             compression: None,
             tcp_nodelay: true,
             tcp_keepalive_interval: None,
@@ -390,6 +498,7 @@ impl Default for ConnectionConfig {
         Self {
             local_ip_address: None,
             shard_aware_local_port_range: ShardAwarePortRange::EPHEMERAL_PORT_RANGE,
+            protocol_version: Arc::new(SharedProtocolVersion::fixed(ProtocolVersion::V4)),
             compression: None,
             tcp_nodelay: true,
             tcp_keepalive_interval: None,
@@ -429,6 +538,7 @@ impl Connection {
     async fn new(
         connect_address: SocketAddr,
         source_port: Option<u16>,
+        protocol_version: ProtocolVersion,
         config: HostConnectionConfig,
     ) -> Result<(Self, ErrorReceiver), ConnectionError> {
         let stream_connector = tokio::time::timeout(
@@ -456,6 +566,8 @@ impl Connection {
 
         let router_handle = Arc::new(RouterHandle {
             submit_channel: sender,
+            protocol_version,
+            framing: Arc::new(FramingState::default()),
             request_id_generator: AtomicU64::new(0),
             orphan_notification_sender,
         });
@@ -543,7 +655,7 @@ impl Connection {
         };
 
         let req_result = self
-            .send_request(&request::Startup { options }, false, false, None)
+            .send_request(&request::Startup { options }, false, false, None, None)
             .await;
 
         // Extract the response to STARTUP request and tidy up the errors.
@@ -592,7 +704,7 @@ impl Connection {
         };
 
         let req_result = self
-            .send_request(&request::Options {}, false, false, None)
+            .send_request(&request::Options {}, false, false, None, None)
             .await;
 
         // Extract the supported options and tidy up the errors.
@@ -643,9 +755,11 @@ impl Connection {
             .send_request(
                 &request::Prepare {
                     query: &statement.contents,
+                    keyspace: statement.get_keyspace().map(Cow::Borrowed),
                 },
                 true,
                 statement.config.tracing,
+                statement.get_custom_payload(),
                 None,
             )
             .await?;
@@ -685,10 +799,14 @@ impl Connection {
 
     async fn reprepare(
         &self,
-        query: impl Into<Statement>,
         previous_prepared: &PreparedStatement,
     ) -> Result<(), RequestAttemptError> {
-        let reprepare_query: Statement = query.into();
+        let mut reprepare_query = Statement::new(previous_prepared.get_statement());
+        reprepare_query.set_keyspace(
+            previous_prepared
+                .get_prepare_keyspace()
+                .map(ToOwned::to_owned),
+        );
         let prepared_response = self.prepare_raw(&reprepare_query).await?.prepared_response;
 
         // Reprepared statement should keep its id - it's the md5 sum
@@ -762,7 +880,13 @@ impl Connection {
         };
 
         let req_result = self
-            .send_request(&request::AuthResponse { response }, false, false, None)
+            .send_request(
+                &request::AuthResponse { response },
+                false,
+                false,
+                None,
+                None,
+            )
             .await;
 
         // Extract non-error response to AUTH_RESPONSE request and tidy up errors.
@@ -839,6 +963,7 @@ impl Connection {
             statement.config.serial_consistency.flatten(),
             None,
             PagingState::start(),
+            statement.get_custom_payload(),
         )
         .await
     }
@@ -850,6 +975,7 @@ impl Connection {
         serial_consistency: Option<SerialConsistency>,
         page_size: Option<PageSize>,
         paging_state: PagingState,
+        custom_payload: Option<&HashMap<String, Bytes>>,
     ) -> Result<QueryResponse, RequestAttemptError> {
         let get_timestamp_from_gen = || {
             self.config
@@ -869,11 +995,18 @@ impl Connection {
                 paging_state,
                 skip_metadata: false,
                 timestamp,
+                keyspace: statement.get_keyspace().map(Cow::Borrowed),
             },
         };
 
         let response = self
-            .send_request(&query_frame, true, statement.config.tracing, None)
+            .send_request(
+                &query_frame,
+                true,
+                statement.config.tracing,
+                custom_payload,
+                None,
+            )
             .await?;
 
         Ok(response)
@@ -895,10 +1028,12 @@ impl Connection {
             prepared.config.serial_consistency.flatten(),
             None,
             PagingState::start(),
+            prepared.get_custom_payload(),
         )
         .await
     }
 
+    #[expect(clippy::too_many_arguments)]
     pub(crate) async fn execute_raw_with_consistency(
         &self,
         prepared_statement: &PreparedStatement,
@@ -907,6 +1042,7 @@ impl Connection {
         serial_consistency: Option<SerialConsistency>,
         page_size: Option<PageSize>,
         paging_state: PagingState,
+        custom_payload: Option<&HashMap<String, Bytes>>,
     ) -> Result<QueryResponse, RequestAttemptError> {
         let get_timestamp_from_gen = || {
             self.config
@@ -918,8 +1054,22 @@ impl Connection {
             .get_timestamp()
             .or_else(get_timestamp_from_gen);
 
+        // Load the id and the metadata once, so that they are consistent
+        // even if the metadata is concurrently refreshed.
+        let cached_result_metadata = prepared_statement.get_cached_result_metadata();
+
+        // The id may have been cached from a node which uses result metadata ids,
+        // but it can only be sent if this connection uses them as well.
+        let result_metadata_id = self
+            .features
+            .protocol_features
+            .uses_result_metadata_id(self.protocol_version())
+            .then(|| cached_result_metadata.id.clone())
+            .flatten();
+
         let execute_frame = execute::Execute {
             id: prepared_statement.get_id().to_owned(),
+            result_metadata_id,
             parameters: query::QueryParameters {
                 consistency,
                 serial_consistency,
@@ -928,18 +1078,21 @@ impl Connection {
                 timestamp,
                 skip_metadata: prepared_statement.get_use_cached_result_metadata(),
                 paging_state,
+                // The keyspace is determined when the statement is prepared.
+                keyspace: None,
             },
         };
 
         let cached_metadata = prepared_statement
             .get_use_cached_result_metadata()
-            .then(|| prepared_statement.get_result_metadata());
+            .then_some(&cached_result_metadata.metadata);
 
         let query_response = self
             .send_request(
                 &execute_frame,
                 true,
                 prepared_statement.config.tracing,
+                custom_payload,
                 cached_metadata,
             )
             .await?;
@@ -960,13 +1113,13 @@ impl Connection {
             }) => {
                 debug!("Connection::execute: Got DbError::Unprepared - repreparing statement with id {:?}", statement_id);
                 // Repreparation of a statement is needed
-                self.reprepare(prepared_statement.get_statement(), prepared_statement)
-                    .await?;
+                self.reprepare(prepared_statement).await?;
                 let new_response = self
                     .send_request(
                         &execute_frame,
                         true,
                         prepared_statement.config.tracing,
+                        custom_payload,
                         cached_metadata,
                     )
                     .await?;
@@ -980,9 +1133,51 @@ impl Connection {
                     }
                 }
 
+                Self::refresh_result_metadata(prepared_statement, &new_response);
+
                 Ok(new_response)
             }
-            _ => Ok(query_response),
+            _ => {
+                Self::refresh_result_metadata(prepared_statement, &query_response);
+
+                Ok(query_response)
+            }
+        }
+    }
+
+    /// Updates the cached result metadata of the statement if the server
+    /// reported that it has changed (protocol v5 or ScyllaDB's metadata id extension).
+    fn refresh_result_metadata(prepared_statement: &PreparedStatement, response: &QueryResponse) {
+        let Response::Result(result::Result::Rows((raw_rows, _))) = &response.response else {
+            return;
+        };
+        let Some(new_metadata_id) = raw_rows.new_metadata_id() else {
+            return;
+        };
+
+        match raw_rows.deserialize_owned_metadata() {
+            Ok(Some(new_metadata)) => {
+                debug!(
+                    "Connection::execute: result metadata of statement with id {:?} changed, new metadata id: {:?}",
+                    prepared_statement.get_id(),
+                    new_metadata_id
+                );
+                prepared_statement
+                    .update_result_metadata(Arc::new(new_metadata), new_metadata_id.clone());
+            }
+            Ok(None) => {
+                warn!(
+                    "Connection::execute: server reported changed result metadata of statement with id {:?}, but did not send it",
+                    prepared_statement.get_id()
+                );
+            }
+            Err(err) => {
+                warn!(
+                    "Connection::execute: failed to deserialize changed result metadata of statement with id {:?}: {}",
+                    prepared_statement.get_id(),
+                    err
+                );
+            }
         }
     }
 
@@ -1031,6 +1226,7 @@ impl Connection {
         values: impl BatchValues,
         consistency: Consistency,
         serial_consistency: Option<SerialConsistency>,
+        custom_payload: Option<&HashMap<String, Bytes>>,
     ) -> Result<QueryResponse, RequestAttemptError> {
         let batch = self.prepare_batch(init_batch, &values).await?;
 
@@ -1058,11 +1254,18 @@ impl Connection {
             consistency,
             serial_consistency,
             timestamp,
+            keyspace: batch.get_keyspace().map(Cow::Borrowed),
         };
 
         loop {
             let query_response = self
-                .send_request(&batch_frame, true, batch.config.tracing, None)
+                .send_request(
+                    &batch_frame,
+                    true,
+                    batch.config.tracing,
+                    custom_payload,
+                    None,
+                )
                 .await
                 .map_err(RequestAttemptError::from)?;
 
@@ -1077,7 +1280,7 @@ impl Connection {
                             _ => None,
                         });
                         if let Some(p) = prepared_statement {
-                            self.reprepare(p.get_statement(), p).await?;
+                            self.reprepare(p).await?;
                             continue;
                         } else {
                             return Err(RequestAttemptError::RepreparedIdMissingInBatch);
@@ -1120,7 +1323,9 @@ impl Connection {
         let mut prepared_queries = HashMap::<&str, PreparedStatement>::new();
 
         for query in &to_prepare {
-            let prepared = self.prepare(&Statement::new(query.to_string())).await?;
+            let mut statement = Statement::new(query.to_string());
+            statement.set_keyspace(init_batch.get_keyspace().map(ToOwned::to_owned));
+            let prepared = self.prepare(&statement).await?;
             prepared_queries.insert(query, prepared);
         }
 
@@ -1199,7 +1404,10 @@ impl Connection {
         };
 
         // Extract the response and tidy up the errors.
-        match self.send_request(&register_frame, true, false, None).await {
+        match self
+            .send_request(&register_frame, true, false, None, None)
+            .await
+        {
             Ok(r) => match r.response {
                 Response::Ready => Ok(()),
                 Response::Error(Error { error, reason }) => {
@@ -1244,9 +1452,11 @@ impl Connection {
         request: &impl SerializableRequest,
         compress: bool,
         tracing: bool,
+        custom_payload: Option<&HashMap<String, Bytes>>,
         cached_metadata: Option<&Arc<ResultMetadata<'static>>>,
     ) -> Result<QueryResponse, InternalRequestError> {
-        let compression = if compress {
+        // Since protocol v5, compression is applied to segments, not to frames.
+        let compression = if compress && !self.protocol_version().uses_segments() {
             self.config.compression
         } else {
             None
@@ -1254,11 +1464,12 @@ impl Connection {
 
         let task_response = self
             .router_handle
-            .send_request(request, compression, tracing)
+            .send_request(request, compression, tracing, custom_payload)
             .await?;
 
         let response = Self::parse_response(
             task_response,
+            self.protocol_version(),
             self.config.compression,
             &self.features.protocol_features,
             cached_metadata,
@@ -1269,6 +1480,7 @@ impl Connection {
 
     fn parse_response(
         task_response: TaskResponse,
+        version: ProtocolVersion,
         compression: Option<Compression>,
         features: &ProtocolFeatures,
         cached_metadata: Option<&Arc<ResultMetadata<'static>>>,
@@ -1288,6 +1500,7 @@ impl Connection {
 
         let response = Response::deserialize(
             features,
+            version,
             task_response.opcode,
             body_with_ext.body,
             cached_metadata,
@@ -1420,6 +1633,8 @@ impl Connection {
         let handler_map = StdMutex::new(ResponseHandlerMap::new());
 
         let write_coalescing_delay = config.write_coalescing_delay;
+        let framing = router_handle.framing.clone();
+        let protocol_version = router_handle.protocol_version;
 
         let k = Self::keepaliver(
             router_handle,
@@ -1432,13 +1647,16 @@ impl Connection {
             BufReader::with_capacity(8192, read_half),
             &handler_map,
             config.event_sender,
+            protocol_version,
             config.compression,
+            &framing,
         );
         let w = Self::writer(
             BufWriter::with_capacity(8192, write_half),
             &handler_map,
             receiver,
             write_coalescing_delay,
+            &framing,
         );
         let o = Self::orphaner(&handler_map, orphan_notification_receiver);
 
@@ -1466,66 +1684,128 @@ impl Connection {
         mut read_half: (impl AsyncRead + Unpin),
         handler_map: &StdMutex<ResponseHandlerMap>,
         event_sender: Option<mpsc::Sender<Event>>,
+        version: ProtocolVersion,
         compression: Option<Compression>,
+        framing: &FramingState,
     ) -> Result<(), BrokenConnectionError> {
         loop {
             let (params, opcode, body) = frame::read_response_frame(&mut read_half)
                 .await
                 .map_err(BrokenConnectionErrorKind::FrameHeaderParseError)?;
+
+            // Since protocol v5, the server switches to segment framing right after
+            // sending READY or AUTHENTICATE. The flag must be set before the response
+            // is dispatched, so that the following requests are already sent in segments.
+            let switch_to_segments = version.uses_segments()
+                && matches!(opcode, ResponseOpcode::Ready | ResponseOpcode::Authenticate);
+            if switch_to_segments {
+                framing.enable_segments();
+            }
+
             let response = TaskResponse {
                 params,
                 opcode,
                 body,
             };
+            Self::handle_response(
+                response,
+                handler_map,
+                event_sender.as_ref(),
+                version,
+                compression,
+            )
+            .await?;
 
-            match params.stream.cmp(&-1) {
-                Ordering::Less => {
-                    // The spec reserves negative-numbered streams for server-generated
-                    // events. As of writing this driver, there are no other negative
-                    // streams used apart from -1, so ignore it.
-                    continue;
-                }
-                Ordering::Equal => {
-                    if let Some(event_sender) = event_sender.as_ref() {
-                        Self::handle_event(response, compression, event_sender)
-                            .await
-                            .map_err(BrokenConnectionErrorKind::CqlEventHandlingError)?
-                    }
-                    continue;
-                }
-                _ => {}
+            if switch_to_segments {
+                break;
             }
+        }
 
-            let handler_lookup_res = {
-                // We are guaranteed here that handler_map will not be locked
-                // by anybody else, so we can do try_lock().unwrap()
-                let mut handler_map_guard = handler_map.try_lock().unwrap();
-                handler_map_guard.lookup(params.stream)
-            };
+        let segment_compression = framing.segment_compression();
+        let mut decoder = SegmentDecoder::new();
+        let mut frames = Vec::new();
+        loop {
+            let segment = segment::read_segment(&mut read_half, segment_compression)
+                .await
+                .map_err(BrokenConnectionErrorKind::SegmentParseError)?;
+            decoder
+                .push_segment(segment, &mut frames)
+                .map_err(BrokenConnectionErrorKind::SegmentParseError)?;
+
+            for frame in frames.drain(..) {
+                let (params, opcode, body) = frame::parse_response_frame(frame)
+                    .map_err(BrokenConnectionErrorKind::FrameHeaderParseError)?;
+                let response = TaskResponse {
+                    params,
+                    opcode,
+                    body,
+                };
+                Self::handle_response(
+                    response,
+                    handler_map,
+                    event_sender.as_ref(),
+                    version,
+                    compression,
+                )
+                .await?;
+            }
+        }
+    }
 
-            use HandlerLookupResult::*;
-            match handler_lookup_res {
-                Handler(handler) => {
-                    // Don't care if sending of the response fails. This must
-                    // mean that the receiver side was impatient and is not
-                    // waiting for the result anymore.
-                    let _ = handler.response_sender.send(Ok(response));
-                }
-                Missing => {
-                    // Unsolicited frame. This should not happen and indicates
-                    // a bug either in the driver, or in the database
-                    debug!(
-                        "Received response with unexpected StreamId {}",
-                        params.stream
-                    );
-                    return Err(BrokenConnectionErrorKind::UnexpectedStreamId(params.stream).into());
-                }
-                Orphaned => {
-                    // Do nothing, handler was freed because this stream_id has
-                    // been marked as orphaned
+    async fn handle_response(
+        response: TaskResponse,
+        handler_map: &StdMutex<ResponseHandlerMap>,
+        event_sender: Option<&mpsc::Sender<Event>>,
+        version: ProtocolVersion,
+        compression: Option<Compression>,
+    ) -> Result<(), BrokenConnectionError> {
+        let stream = response.params.stream;
+        match stream.cmp(&-1) {
+            Ordering::Less => {
+                // The spec reserves negative-numbered streams for server-generated
+                // events. As of writing this driver, there are no other negative
+                // streams used apart from -1, so ignore it.
+                return Ok(());
+            }
+            Ordering::Equal => {
+                if let Some(event_sender) = event_sender {
+                    Self::handle_event(response, version, compression, event_sender)
+                        .await
+                        .map_err(BrokenConnectionErrorKind::CqlEventHandlingError)?
                 }
+                return Ok(());
             }
+            _ => {}
         }
+
+        let handler_lookup_res = {
+            // We are guaranteed here that handler_map will not be locked
+            // by anybody else, so we can do try_lock().unwrap()
+            let mut handler_map_guard = handler_map.try_lock().unwrap();
+            handler_map_guard.lookup(stream)
+        };
+
+        use HandlerLookupResult::*;
+        match handler_lookup_res {
+            Handler(handler) => {
+                // Don't care if sending of the response fails. This must
+                // mean that the receiver side was impatient and is not
+                // waiting for the result anymore.
+                let _ = handler.response_sender.send(Ok(response));
+            }
+            Missing => {
+                // Unsolicited frame. This should not happen and indicates
+                // a bug either in the driver, or in the database
+                debug!("Received response with unexpected StreamId {}", stream);
+                return Err(BrokenConnectionErrorKind::UnexpectedStreamId(stream).into());
+            }
+            Orphaned => {
+                // Do nothing, handler was freed because this stream_id has
+                // been marked as orphaned
+            }
+        }
+
+        Ok(())
     }
 
     fn alloc_stream_id(
@@ -1552,11 +1832,20 @@ impl Connection {
         handler_map: &StdMutex<ResponseHandlerMap>,
         mut task_receiver: mpsc::Receiver<Task>,
         write_coalescing_delay: Option<WriteCoalescingDelay>,
+        framing: &FramingState,
     ) -> Result<(), BrokenConnectionError> {
+        // Set once the connection switches to segment framing (protocol v5+).
+        let mut segment_encoder: Option<SegmentEncoder> = None;
+        let mut segments_buf = Vec::new();
+
         // When the Connection object is dropped, the sender half
         // of the channel will be dropped, this task will return an error
         // and the whole worker will be stopped
         while let Some(mut task) = task_receiver.recv().await {
+            if segment_encoder.is_none() && framing.segments_enabled() {
+                segment_encoder = Some(SegmentEncoder::new(framing.segment_compression()));
+            }
+
             let mut num_requests = 0;
             let mut total_sent = 0;
             while let Some(stream_id) = Self::alloc_stream_id(handler_map, task.response_handler) {
@@ -1565,8 +1854,18 @@ impl Connection {
                 let req_data: &[u8] = req.get_data();
                 total_sent += req_data.len();
                 num_requests += 1;
+                let data_to_write = match segment_encoder.as_mut() {
+                    Some(encoder) => {
+                        segments_buf.clear();
+                        encoder
+                            .push_frame(req_data, &mut segments_buf)
+                            .map_err(BrokenConnectionErrorKind::SegmentSerializationError)?;
+                        &segments_buf[..]
+                    }
+                    None => req_data,
+                };
                 write_half
-                    .write_all(req_data)
+                    .write_all(data_to_write)
                     .await
                     .map_err(BrokenConnectionErrorKind::WriteError)?;
                 task = match task_receiver.try_recv() {
@@ -1593,6 +1892,16 @@ impl Connection {
                     },
                 }
             }
+            if let Some(encoder) = segment_encoder.as_mut() {
+                segments_buf.clear();
+                encoder
+                    .flush(&mut segments_buf)
+                    .map_err(BrokenConnectionErrorKind::SegmentSerializationError)?;
+                write_half
+                    .write_all(&segments_buf)
+                    .await
+                    .map_err(BrokenConnectionErrorKind::WriteError)?;
+            }
             trace!("Sending {} requests; {} bytes", num_requests, total_sent);
             write_half
                 .flush()
@@ -1652,7 +1961,7 @@ impl Connection {
             router_handle: &RouterHandle,
         ) -> Result<(), BrokenConnectionError> {
             router_handle
-                .send_request(&Options, None, false)
+                .send_request(&Options, None, false, None)
                 .await
                 .map(|_| ())
                 .map_err(|req_err| {
@@ -1708,6 +2017,7 @@ impl Connection {
 
     async fn handle_event(
         task_response: TaskResponse,
+        version: ProtocolVersion,
         compression: Option<Compression>,
         event_sender: &mpsc::Sender<Event>,
     ) -> Result<(), CqlEventHandlingError> {
@@ -1722,7 +2032,8 @@ impl Connection {
         // future implementers.
         let features = ProtocolFeatures::default(); // TODO: Use the right features
 
-        let event = match Self::parse_response(task_response, compression, &features, None) {
+        let event = match Self::parse_response(task_response, version, compression, &features, None)
+        {
             Ok(r) => match r.response {
                 Response::Event(event) => event,
                 _ => {
@@ -1768,6 +2079,11 @@ impl Connection {
         self.connect_address
     }
 
+    /// Returns the protocol version spoken on this connection.
+    pub(crate) fn protocol_version(&self) -> ProtocolVersion {
+        self.router_handle.protocol_version
+    }
+
     async fn update_tablets_from_response(
         &self,
         table: &TableSpec<'_>,
@@ -1839,6 +2155,10 @@ async fn maybe_translated_addr(
 /// - registers for all event types using REGISTER request (if this is control connection).
 ///
 /// At the beginning, translates node's address, if it is subject to address translation.
+///
+/// The connection starts with the protocol version shared by the session.
+/// If the node rejects it and the version is negotiable, the connection
+/// is reopened with lower versions until the node accepts one.
 pub(crate) async fn open_connection(
     endpoint: &UntranslatedEndpoint,
     source_port: Option<u16>,
@@ -1847,9 +2167,35 @@ pub(crate) async fn open_connection(
     /* Translate the address, if applicable. */
     let addr = maybe_translated_addr(endpoint, config.address_translator.as_deref()).await?;
 
+    let mut protocol_version = config.protocol_version.get();
+    loop {
+        match open_connection_with_version(addr, source_port, protocol_version, config).await {
+            Err(err) if err.is_unsupported_protocol_version(protocol_version) => {
+                match config.protocol_version.downgrade(protocol_version) {
+                    Some(lower_version) => {
+                        debug!(
+                            "[{}] Node rejected protocol {}: {}. Retrying with protocol {}",
+                            addr, protocol_version, err, lower_version
+                        );
+                        protocol_version = lower_version;
+                    }
+                    None => return Err(err),
+                }
+            }
+            result => return result,
+        }
+    }
+}
+
+async fn open_connection_with_version(
+    addr: SocketAddr,
+    source_port: Option<u16>,
+    protocol_version: ProtocolVersion,
+    config: &HostConnectionConfig,
+) -> Result<(Connection, ErrorReceiver), ConnectionError> {
     /* Setup connection on TCP level and prepare for sending/receiving CQL frames. */
     let (mut connection, error_receiver) =
-        Connection::new(addr, source_port, config.clone()).await?;
+        Connection::new(addr, source_port, protocol_version, config.clone()).await?;
 
     /* Perform OPTIONS/SUPPORTED/STARTUP handshake. */
 
@@ -1918,7 +2264,15 @@ pub(crate) async fn open_connection(
     // Optional compression.
     if let Some(compression) = &config.compression {
         let compression_str = compression.as_str();
-        if supported_compression.iter().any(|c| c == compression_str) {
+        if protocol_version.uses_segments() && *compression == Compression::Snappy {
+            // Protocol v5 only allows LZ4 compression of segments.
+            tracing::warn!(
+                "Compression <{}> is not supported in protocol {}. Falling back to no compression",
+                compression_str,
+                protocol_version
+            );
+            connection.config.compression = None;
+        } else if supported_compression.iter().any(|c| c == compression_str) {
             // Compression is reported to be supported by the server,
             // request it from the server
             options.insert(
@@ -1935,6 +2289,13 @@ pub(crate) async fn open_connection(
         }
     }
 
+    // Segments are compressed with the negotiated compression once the
+    // connection switches to segment framing (protocol v5 and later).
+    connection
+        .router_handle
+        .framing
+        .set_segment_compression(connection.config.compression);
+
     /* Send the STARTUP frame with all the requested options. */
     let startup_result = connection.startup(options).await?;
     match startup_result {
@@ -2574,6 +2935,553 @@ mod tests {
         )
     }
 
+    /// Checks that a connection is reopened with a lower protocol version when
+    /// a node rejects the requested one, and that the lowered version is remembered.
+    /// If the version was set explicitly, no downgrade happens.
+    #[tokio::test]
+    #[ntest::timeout(20000)]
+    async fn open_connection_downgrades_protocol_version() {
+        use super::SharedProtocolVersion;
+        use crate::frame::ProtocolVersion;
+
+        setup_tracing();
+
+        let proxy_addr = SocketAddr::new(scylla_proxy::get_exclusive_local_address(), 9042);
+
+        // The proxy only supports protocol v4, and rejects requests in newer
+        // versions the same way as ScyllaDB does.
+        let rules = vec![
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Options),
+                RequestReaction::forge_response(Arc::new(|frame: RequestFrame| {
+                    ResponseFrame::forged_supported(frame.params, &HashMap::new()).unwrap()
+                })),
+            ),
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Startup),
+                RequestReaction::forge_response(Arc::new(|frame: RequestFrame| {
+                    ResponseFrame::forged_ready(frame.params)
+                })),
+            ),
+        ];
+
+        let proxy = Proxy::builder()
+            .with_node(
+                Node::builder()
+                    .proxy_address(proxy_addr)
+                    .request_rules(rules)
+                    .build_dry_mode(),
+            )
+            .build()
+            .run()
+            .await
+            .unwrap();
+
+        let endpoint = UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
+            address: proxy_addr,
+            datacenter: None,
+        });
+
+        let config = HostConnectionConfig {
+            protocol_version: Arc::new(SharedProtocolVersion::negotiable()),
+            ..Default::default()
+        };
+        assert_eq!(config.protocol_version.get(), ProtocolVersion::V5);
+        let (connection, _) = open_connection(&endpoint, None, &config).await.unwrap();
+        assert_eq!(connection.protocol_version(), ProtocolVersion::V4);
+        assert_eq!(config.protocol_version.get(), ProtocolVersion::V4);
+
+        // Subsequent connections start with the remembered version.
+        let (connection, _) = open_connection(&endpoint, None, &config).await.unwrap();
+        assert_eq!(connection.protocol_version(), ProtocolVersion::V4);
+
+        let config = HostConnectionConfig {
+            protocol_version: Arc::new(SharedProtocolVersion::fixed(ProtocolVersion::V5)),
+            ..Default::default()
+        };
+        let Err(err) = open_connection(&endpoint, None, &config).await else {
+            panic!("connection with an unsupported protocol version succeeded");
+        };
+        assert!(err.is_unsupported_protocol_version(ProtocolVersion::V5));
+        assert_eq!(config.protocol_version.get(), ProtocolVersion::V5);
+
+        let _ = proxy.finish().await;
+    }
+
+    /// Checks that a custom payload set on a statement is sent in the request,
+    /// and that the payload sent back by the server is exposed on the result.
+    #[tokio::test]
+    #[cfg_attr(scylla_cloud_tests, ignore)]
+    async fn custom_payload_round_trip() {
+        use bytes::{BufMut, Bytes, BytesMut};
+        use scylla_cql::frame::flag;
+        use scylla_proxy::ResponseOpcode;
+
+        setup_tracing();
+
+        let proxy_addr = SocketAddr::new(scylla_proxy::get_exclusive_local_address(), 9042);
+
+        // Echoes the custom payload of a QUERY back in a RESULT::Void response.
+        let rules = vec![
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Options),
+                RequestReaction::forge_response(Arc::new(|frame: RequestFrame| {
+                    ResponseFrame::forged_supported(frame.params, &HashMap::new()).unwrap()
+                })),
+            ),
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Startup),
+                RequestReaction::forge_response(Arc::new(|frame: RequestFrame| {
+                    ResponseFrame::forged_ready(frame.params)
+                })),
+            ),
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Query),
+                RequestReaction::forge_response(Arc::new(|frame: RequestFrame| {
+                    assert_ne!(frame.params.flags & flag::CUSTOM_PAYLOAD, 0);
+                    let payload = types::read_bytes_map(&mut &frame.body[..]).unwrap();
+                    let mut body = BytesMut::new();
+                    types::write_bytes_map(&payload, &mut body).unwrap();
+                    // RESULT::Void
+                    body.put_i32(0x0001);
+                    ResponseFrame {
+                        params: frame.params.for_response(),
+                        opcode: ResponseOpcode::Result,
+                        body: body.freeze(),
+                    }
+                })),
+            ),
+        ];
+
+        let proxy = Proxy::builder()
+            .with_node(
+                Node::builder()
+                    .proxy_address(proxy_addr)
+                    .request_rules(rules)
+                    .build_dry_mode(),
+            )
+            .build()
+            .run()
+            .await
+            .unwrap();
+
+        let endpoint = UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
+            address: proxy_addr,
+            datacenter: None,
+        });
+        let (connection, _) = open_connection(&endpoint, None, &HostConnectionConfig::default())
+            .await
+            .unwrap();
+
+        let payload = HashMap::from([("key".to_owned(), Bytes::from_static(b"value"))]);
+        let mut statement = Statement::new("SELECT * FROM ks.t");
+        statement.set_custom_payload(Some(payload.clone()));
+
+        let result = connection.query_unpaged(statement).await.unwrap();
+        assert_eq!(result.custom_payload(), Some(&payload));
+
+        let _ = proxy.finish().await;
+    }
+
+    /// Checks that, with ScyllaDB's metadata id extension negotiated, the id of the result
+    /// metadata is sent in EXECUTE requests, and that the cached result metadata is refreshed
+    /// when the server reports that it has changed.
+    #[tokio::test]
+    #[cfg_attr(scylla_cloud_tests, ignore)]
+    async fn result_metadata_is_refreshed_on_metadata_id_change() {
+        use bytes::Bytes;
+        use scylla_cql::frame::protocol_features::{ProtocolFeatures, SCYLLA_USE_METADATA_ID_KEY};
+        use scylla_cql::frame::request::query::PagingStateResponse;
+        use scylla_cql::frame::response::result::{
+            ColumnSpec, ColumnType, NativeType, PartitionKeyIndex, Prepared, PreparedMetadata,
+            RawMetadataAndRawRows, Result as CqlResult, ResultMetadata, TableSpec,
+        };
+        use scylla_cql::frame::response::Response;
+        use scylla_cql::serialize::row::SerializedValues;
+
+        setup_tracing();
+
+        let proxy_addr = SocketAddr::new(scylla_proxy::get_exclusive_local_address(), 9042);
+        let mut features = ProtocolFeatures::default();
+        features.scylla_metadata_id_supported = true;
+        let table_spec = TableSpec::borrowed("ks", "t");
+        let old_metadata = ResultMetadata::new(vec![ColumnSpec::borrowed(
+            "a",
+            ColumnType::Native(NativeType::Int),
+            table_spec.clone(),
+        )]);
+        let new_metadata = ResultMetadata::new(vec![
+            ColumnSpec::borrowed("a", ColumnType::Native(NativeType::Int), table_spec.clone()),
+            ColumnSpec::borrowed("b", ColumnType::Native(NativeType::Text), table_spec),
+        ]);
+        let old_metadata_id = Bytes::from_static(&[1]);
+        let new_metadata_id = Bytes::from_static(&[2]);
+
+        let (metadata_id_tx, mut metadata_id_rx) = mpsc::unbounded_channel();
+
+        let rules = {
+            let old_metadata = old_metadata.clone();
+            let new_metadata = new_metadata.clone();
+            let old_metadata_id = old_metadata_id.clone();
+            let new_metadata_id = new_metadata_id.clone();
+            vec![
+                RequestRule(
+                    Condition::RequestOpcode(RequestOpcode::Options),
+                    RequestReaction::forge_response(Arc::new(|frame: RequestFrame| {
+                        let options = HashMap::from([(
+                            SCYLLA_USE_METADATA_ID_KEY.to_owned(),
+                            Vec::<String>::new(),
+                        )]);
+                        ResponseFrame::forged_supported(frame.params, &options).unwrap()
+                    })),
+                ),
+                RequestRule(
+                    Condition::RequestOpcode(RequestOpcode::Startup),
+                    RequestReaction::forge_response(Arc::new(|frame: RequestFrame| {
+                        ResponseFrame::forged_ready(frame.params)
+                    })),
+                ),
+                RequestRule(
+                    Condition::RequestOpcode(RequestOpcode::Prepare),
+                    RequestReaction::forge_response(Arc::new(move |frame: RequestFrame| {
+                        let prepared = Prepared {
+                            id: Bytes::from_static(b"stmt"),
+                            result_metadata_id: Some(old_metadata_id.clone()),
+                            prepared_metadata: PreparedMetadata {
+                                flags: 0,
+                                col_count: 0,
+                                pk_indexes: Vec::<PartitionKeyIndex>::new(),
+                                col_specs: Vec::new(),
+                            },
+                            result_metadata: old_metadata.clone(),
+                        };
+                        let response = Response::Result(CqlResult::Prepared(prepared));
+                        ResponseFrame::forged(frame.params, &response, &features).unwrap()
+                    })),
+                ),
+                RequestRule(
+                    Condition::RequestOpcode(RequestOpcode::Execute),
+                    RequestReaction::forge_response(Arc::new(move |frame: RequestFrame| {
+                        let buf = &mut &frame.body[..];
+                        let _id = types::read_short_bytes(buf).unwrap();
+                        let metadata_id =
+                            Bytes::copy_from_slice(types::read_short_bytes(buf).unwrap());
+                        let _ = metadata_id_tx.send(metadata_id.clone());
+
+                        let mut raw_rows =
+                            RawMetadataAndRawRows::new(&new_metadata, 0, &[]).unwrap();
+                        if metadata_id != new_metadata_id {
+                            raw_rows = raw_rows.with_new_metadata_id(new_metadata_id.clone());
+                        }
+                        let response = Response::Result(CqlResult::Rows((
+                            raw_rows,
+                            PagingStateResponse::NoMorePages,
+                        )));
+                        ResponseFrame::forged(frame.params, &response, &features).unwrap()
+                    })),
+                ),
+            ]
+        };
+
+        let proxy = Proxy::builder()
+            .with_node(
+                Node::builder()
+                    .proxy_address(proxy_addr)
+                    .request_rules(rules)
+                    .build_dry_mode(),
+            )
+            .build()
+            .run()
+            .await
+            .unwrap();
+
+        let endpoint = UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
+            address: proxy_addr,
+            datacenter: None,
+        });
+        let (connection, _) = open_connection(&endpoint, None, &HostConnectionConfig::default())
+            .await
+            .unwrap();
+        assert!(
+            connection
+                .features
+                .protocol_features
+                .scylla_metadata_id_supported
+        );
+
+        let prepared = connection
+            .prepare(&Statement::new("SELECT * FROM ks.t"))
+            .await
+            .unwrap();
+        // A copy shares the cached result metadata with the original statement.
+        let prepared_copy = prepared.clone();
+        assert_eq!(prepared.get_current_result_set_col_specs().get().len(), 1);
+
+        // The server reports that the metadata has changed.
+        let result = connection
+            .execute_raw_unpaged(&prepared, SerializedValues::new())
+            .await
+            .unwrap()
+            .into_non_error_query_response()
+            .unwrap()
+            .into_query_result_with_unknown_coordinator()
+            .unwrap();
+        assert!(result.result_metadata_changed());
+        assert_eq!(metadata_id_rx.recv().await.unwrap(), old_metadata_id);
+
+        let current_col_specs = prepared_copy.get_current_result_set_col_specs();
+        let names = current_col_specs
+            .get()
+            .iter()
+            .map(|spec| spec.name().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["a", "b"]);
+        // Column specs from the time of preparation are still accessible.
+        assert_eq!(prepared_copy.get_result_set_col_specs().len(), 1);
+
+        // The next execution carries the new metadata id.
+        let result = connection
+            .execute_raw_unpaged(&prepared, SerializedValues::new())
+            .await
+            .unwrap()
+            .into_non_error_query_response()
+            .unwrap()
+            .into_query_result_with_unknown_coordinator()
+            .unwrap();
+        assert!(!result.result_metadata_changed());
+        assert_eq!(metadata_id_rx.recv().await.unwrap(), new_metadata_id);
+
+        let _ = proxy.finish().await;
+    }
+
+    /// Checks that a result metadata id cached for a prepared statement, for example
+    /// by a node which uses the metadata id extension, is not sent in EXECUTE requests
+    /// over a protocol v4 connection on which the extension was not negotiated.
+    #[tokio::test]
+    #[ntest::timeout(20000)]
+    #[cfg_attr(scylla_cloud_tests, ignore)]
+    async fn result_metadata_id_is_not_sent_without_extension() {
+        use bytes::Bytes;
+        use scylla_cql::frame::protocol_features::ProtocolFeatures;
+        use scylla_cql::frame::request::Execute;
+        use scylla_cql::frame::response::result::{
+            PartitionKeyIndex, Prepared, PreparedMetadata, Result as CqlResult, ResultMetadata,
+        };
+        use scylla_cql::frame::response::Response;
+        use scylla_cql::frame::ProtocolVersion;
+        use scylla_cql::serialize::row::SerializedValues;
+
+        setup_tracing();
+
+        let proxy_addr = SocketAddr::new(scylla_proxy::get_exclusive_local_address(), 9042);
+        let features = ProtocolFeatures::default();
+
+        let (execute_tx, mut execute_rx) = mpsc::unbounded_channel();
+
+        let rules = vec![
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Options),
+                RequestReaction::forge_response(Arc::new(|frame: RequestFrame| {
+                    ResponseFrame::forged_supported(frame.params, &HashMap::new()).unwrap()
+                })),
+            ),
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Startup),
+                RequestReaction::forge_response(Arc::new(|frame: RequestFrame| {
+                    ResponseFrame::forged_ready(frame.params)
+                })),
+            ),
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Prepare),
+                RequestReaction::forge_response(Arc::new(move |frame: RequestFrame| {
+                    let prepared = Prepared {
+                        id: Bytes::from_static(b"stmt"),
+                        result_metadata_id: None,
+                        prepared_metadata: PreparedMetadata {
+                            flags: 0,
+                            col_count: 0,
+                            pk_indexes: Vec::<PartitionKeyIndex>::new(),
+                            col_specs: Vec::new(),
+                        },
+                        result_metadata: ResultMetadata::mock_empty(),
+                    };
+                    let response = Response::Result(CqlResult::Prepared(prepared));
+                    ResponseFrame::forged(frame.params, &response, &features).unwrap()
+                })),
+            ),
+            RequestRule(
+                Condition::RequestOpcode(RequestOpcode::Execute),
+                RequestReaction::forge_response(Arc::new(move |frame: RequestFrame| {
+                    let mut body = &frame.body[..];
+                    let parsed = Execute::deserialize(&mut body, ProtocolVersion::V4, &features)
+                        .ok()
+                        .map(|execute| (execute.result_metadata_id, body.is_empty()));
+                    let _ = execute_tx.send(parsed);
+                    let response = Response::Result(CqlResult::Void);
+                    ResponseFrame::forged(frame.params, &response, &features).unwrap()
+                })),
+            ),
+        ];
+
+        let proxy = Proxy::builder()
+            .with_node(
+                Node::builder()
+                    .proxy_address(proxy_addr)
+                    .request_rules(rules)
+                    .build_dry_mode(),
+            )
+            .build()
+            .run()
+            .await
+            .unwrap();
+
+        let endpoint = UntranslatedEndpoint::ContactPoint(ResolvedContactPoint {
+            address: proxy_addr,
+            datacenter: None,
+        });
+        let (connection, _) = open_connection(&endpoint, None, &HostConnectionConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(connection.protocol_version(), ProtocolVersion::V4);
+        assert!(
+            !connection
+                .features
+                .protocol_features
+                .scylla_metadata_id_supported
+        );
+
+        let prepared = connection
+            .prepare(&Statement::new("INSERT INTO ks.t (a) VALUES (1)"))
+            .await
+            .unwrap();
+        // Mimics an id cached by a copy of the statement prepared on another node.
+        prepared.update_result_metadata(
+            Arc::new(ResultMetadata::mock_empty()),
+            Bytes::from_static(&[1]),
+        );
+        assert!(prepared.get_cached_result_metadata().id.is_some());
+
+        connection
+            .execute_raw_unpaged(&prepared, SerializedValues::new())
+            .await
+            .unwrap();
+        // The request was parsed as a v4 request without the extension,
+        // which consumed the whole body.
+        assert_eq!(execute_rx.recv().await.unwrap(), Some((None, true)));
+
+        let _ = proxy.finish().await;
+    }
+
+    /// Checks that in protocol v5 the router uses legacy framing for the handshake,
+    /// and switches to segments (in both directions) after the server sends READY.
+    #[tokio::test]
+    #[ntest::timeout(20000)]
+    async fn router_switches_to_segments_after_ready() {
+        use super::{Connection, FramingState, RouterHandle};
+        use crate::frame::request::{Options, Startup};
+        use crate::frame::response::ResponseOpcode;
+        use crate::frame::segment::{self, SegmentDecoder, SegmentEncoder};
+        use crate::frame::ProtocolVersion;
+        use futures::FutureExt;
+        use std::net::{IpAddr, Ipv4Addr};
+        use std::sync::atomic::AtomicU64;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        setup_tracing();
+
+        fn response_frame(stream: i16, opcode: ResponseOpcode, body: &[u8]) -> Vec<u8> {
+            let mut frame = vec![0x85, 0x00];
+            frame.extend_from_slice(&stream.to_be_bytes());
+            frame.push(opcode as u8);
+            frame.extend_from_slice(&(body.len() as u32).to_be_bytes());
+            frame.extend_from_slice(body);
+            frame
+        }
+
+        fn frame_stream_and_opcode(frame: &[u8]) -> (i16, u8) {
+            assert_eq!(frame[0], 0x05);
+            (i16::from_be_bytes([frame[2], frame[3]]), frame[4])
+        }
+
+        let (client, mut server) = tokio::io::duplex(1 << 20);
+
+        let (submit_channel, receiver) = mpsc::channel(16);
+        let (error_sender, _error_receiver) = tokio::sync::oneshot::channel();
+        let (orphan_notification_sender, orphan_notification_receiver) = mpsc::unbounded_channel();
+        let router_handle = Arc::new(RouterHandle {
+            submit_channel,
+            protocol_version: ProtocolVersion::V5,
+            framing: Arc::new(FramingState::default()),
+            request_id_generator: AtomicU64::new(0),
+            orphan_notification_sender,
+        });
+        let (router, _router_handle) = Connection::router(
+            HostConnectionConfig::default(),
+            client,
+            receiver,
+            error_sender,
+            orphan_notification_receiver,
+            router_handle.clone(),
+            IpAddr::V4(Ipv4Addr::LOCALHOST),
+        )
+        .remote_handle();
+        tokio::spawn(router);
+
+        let server = async move {
+            // STARTUP is sent in a legacy frame.
+            let mut header = [0u8; 9];
+            server.read_exact(&mut header).await.unwrap();
+            let (stream, opcode) = frame_stream_and_opcode(&header);
+            assert_eq!(opcode, RequestOpcode::Startup as u8);
+            let body_len = u32::from_be_bytes(header[5..9].try_into().unwrap());
+            server
+                .read_exact(&mut vec![0u8; body_len as usize])
+                .await
+                .unwrap();
+            let ready = response_frame(stream, ResponseOpcode::Ready, &[]);
+            server.write_all(&ready).await.unwrap();
+
+            // From now on, frames are wrapped in segments.
+            let segment = segment::read_segment(&mut server, None).await.unwrap();
+            let mut frames = Vec::new();
+            SegmentDecoder::new()
+                .push_segment(segment, &mut frames)
+                .unwrap();
+            assert_eq!(frames.len(), 1);
+            let (stream, opcode) = frame_stream_and_opcode(&frames[0]);
+            assert_eq!(opcode, RequestOpcode::Options as u8);
+
+            let mut supported_body = Vec::new();
+            types::write_string_multimap(&HashMap::new(), &mut supported_body).unwrap();
+            let supported = response_frame(stream, ResponseOpcode::Supported, &supported_body);
+            let mut encoder = SegmentEncoder::new(None);
+            let mut segments = Vec::new();
+            encoder.push_frame(&supported, &mut segments).unwrap();
+            encoder.flush(&mut segments).unwrap();
+            server.write_all(&segments).await.unwrap();
+            server
+        };
+
+        let client = async {
+            let startup = Startup {
+                options: HashMap::new(),
+            };
+            let response = router_handle
+                .send_request(&startup, None, false, None)
+                .await
+                .unwrap();
+            assert_eq!(response.opcode, ResponseOpcode::Ready);
+
+            let response = router_handle
+                .send_request(&Options, None, false, None)
+                .await
+                .unwrap();
+            assert_eq!(response.opcode, ResponseOpcode::Supported);
+        };
+
+        let (_server, ()) = futures::join!(server, client);
+    }
+
     #[tokio::test]
     #[ntest::timeout(20000)]
     #[cfg_attr(scylla_cloud_tests, ignore)]
@@ -6,6 +6,7 @@ use super::connection::{
 use crate::errors::{
     BrokenConnectionErrorKind, ConnectionError, ConnectionPoolError, UseKeyspaceError,
 };
+use crate::frame::ProtocolVersion;
 use crate::routing::{Shard, ShardCount, Sharder};
 
 use crate::cluster::metadata::{PeerEndpoint, UntranslatedEndpoint};
@@ -110,6 +111,9 @@ enum MaybePoolConnections {
 
     // The pool has some connections which are usable (or will be removed soon)
     Ready(PoolConnections),
+
+    // The pool has been closed and will not open any more connections
+    Closed,
 }
 
 impl std::fmt::Debug for MaybePoolConnections {
@@ -118,6 +122,7 @@ impl std::fmt::Debug for MaybePoolConnections {
             MaybePoolConnections::Initializing => write!(f, "Initializing"),
             MaybePoolConnections::Broken(err) => write!(f, "Broken({:?})", err),
             MaybePoolConnections::Ready(conns) => write!(f, "{:?}", conns),
+            MaybePoolConnections::Closed => write!(f, "Closed"),
         }
     }
 }
@@ -180,6 +185,7 @@ pub(crate) struct NodeConnectionPool {
     use_keyspace_request_sender: mpsc::Sender<UseKeyspaceRequest>,
     _refiller_handle: Arc<RemoteHandle<()>>,
     pool_updated_notify: Arc<Notify>,
+    close_notify: Arc<Notify>,
     endpoint: Arc<RwLock<UntranslatedEndpoint>>,
 }
 
@@ -212,6 +218,7 @@ impl NodeConnectionPool {
     ) -> Self {
         let (use_keyspace_request_sender, use_keyspace_request_receiver) = mpsc::channel(1);
         let pool_updated_notify = Arc::new(Notify::new());
+        let close_notify = Arc::new(Notify::new());
 
         let host_pool_config = pool_config.to_host_pool_config(&endpoint);
 
@@ -228,7 +235,9 @@ impl NodeConnectionPool {
         );
 
         let conns = refiller.get_shared_connections();
-        let (fut, refiller_handle) = refiller.run(use_keyspace_request_receiver).remote_handle();
+        let (fut, refiller_handle) = refiller
+            .run(use_keyspace_request_receiver, close_notify.clone())
+            .remote_handle();
         tokio::spawn(fut);
 
         Self {
@@ -236,6 +245,7 @@ impl NodeConnectionPool {
             use_keyspace_request_sender,
             _refiller_handle: Arc::new(refiller_handle),
             pool_updated_notify,
+            close_notify,
             endpoint: arced_endpoint,
         }
     }
@@ -245,6 +255,7 @@ impl NodeConnectionPool {
         match maybe_conns.as_ref() {
             MaybePoolConnections::Initializing => false,
             MaybePoolConnections::Broken(_) => false,
+            MaybePoolConnections::Closed => false,
             // Here we use the assumption that _pool_connections is always non-empty.
             MaybePoolConnections::Ready(_pool_connections) => true,
         }
@@ -262,6 +273,21 @@ impl NodeConnectionPool {
         .unwrap_or(None)
     }
 
+    /// Returns the lowest protocol version used by the pool's connections.
+    pub(crate) fn protocol_version(&self) -> Option<ProtocolVersion> {
+        self.with_connections(|pool_conns| match pool_conns {
+            PoolConnections::NotSharded(conns) => {
+                conns.iter().map(|conn| conn.protocol_version()).min()
+            }
+            PoolConnections::Sharded { connections, .. } => connections
+                .iter()
+                .flatten()
+                .map(|conn| conn.protocol_version())
+                .min(),
+        })
+        .unwrap_or(None)
+    }
+
     pub(crate) fn connection_for_shard(
         &self,
         shard: Shard,
@@ -369,6 +395,12 @@ impl NodeConnectionPool {
         response_receiver.await.unwrap() // PoolRefiller always responds
     }
 
+    // Drops all connections of the pool and stops refilling it.
+    // Requests which are already running on the pool's connections are not interrupted.
+    pub(crate) fn close(&self) {
+        self.close_notify.notify_one();
+    }
+
     // Waits until the pool becomes initialized.
     // The pool is considered initialized either if the first connection has been
     // established or after first filling ends, whichever comes first.
@@ -422,6 +454,7 @@ impl NodeConnectionPool {
                 last_connection_error: err.clone(),
             }),
             MaybePoolConnections::Initializing => Err(ConnectionPoolError::Initializing),
+            MaybePoolConnections::Closed => Err(ConnectionPoolError::Closed),
         }
     }
 }
@@ -514,6 +547,9 @@ struct PoolRefiller {
     // Signaled when the connection pool becomes empty
     pool_empty_notifier: broadcast::Sender<()>,
 
+    // Set when the pool is closed; a closed pool does not open new connections
+    closed: bool,
+
     #[cfg(feature = "metrics")]
     metrics: Arc<Metrics>,
 }
@@ -561,6 +597,8 @@ impl PoolRefiller {
             pool_updated_notify,
             pool_empty_notifier,
 
+            closed: false,
+
             #[cfg(feature = "metrics")]
             metrics,
         }
@@ -578,6 +616,7 @@ impl PoolRefiller {
     pub(crate) async fn run(
         mut self,
         mut use_keyspace_request_receiver: mpsc::Receiver<UseKeyspaceRequest>,
+        close_notify: Arc<Notify>,
     ) {
         debug!(
             "[{}] Started asynchronous pool worker",
@@ -627,13 +666,18 @@ impl PoolRefiller {
                         return;
                     }
                 }
+
+                _ = close_notify.notified(), if !self.closed => {
+                    self.close();
+                    refill_scheduled = false;
+                }
             }
             trace!(
                 pool_state = ?ShardedConnectionVectorWrapper(&self.conns)
             );
 
             // Schedule refilling here
-            if !refill_scheduled && self.need_filling() {
+            if !refill_scheduled && !self.closed && self.need_filling() {
                 if self.had_error_since_last_refill {
                     self.refill_delay_strategy.on_fill_error();
                 } else {
@@ -652,6 +696,22 @@ impl PoolRefiller {
         }
     }
 
+    // Drops all connections and makes the pool report that it is closed.
+    // Keyspace change requests are still handled, but they have no connections to act on.
+    fn close(&mut self) {
+        debug!("[{}] Closing the pool", self.endpoint_description());
+        self.closed = true;
+
+        self.conns.iter_mut().for_each(Vec::clear);
+        self.excess_connections.clear();
+        self.ready_connections = FuturesUnordered::new();
+        self.connection_errors = FuturesUnordered::new();
+
+        self.shared_conns
+            .store(Arc::new(MaybePoolConnections::Closed));
+        self.pool_updated_notify.notify_waiters();
+    }
+
     fn is_filling(&self) -> bool {
         !self.ready_connections.is_empty()
     }
@@ -9,7 +9,9 @@ mod connection;
 #[cfg(test)]
 pub(crate) use connection::open_connection;
 
-pub(crate) use connection::{Connection, ConnectionConfig, VerifiedKeyspaceName};
+pub(crate) use connection::{
+    Connection, ConnectionConfig, SharedProtocolVersion, VerifiedKeyspaceName,
+};
 
 mod connection_pool;
 
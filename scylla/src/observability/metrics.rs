@@ -1,7 +1,10 @@
+use dashmap::DashMap;
 use histogram::{AtomicHistogram, Histogram};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use thiserror::Error;
+use uuid::Uuid;
 
 const ORDER_TYPE: Ordering = Ordering::Relaxed;
 
@@ -212,6 +215,11 @@ pub struct Metrics {
     total_connections: AtomicU64,
     connection_timeouts: AtomicU64,
     request_timeouts: AtomicU64,
+    rate_limit_errors_num: AtomicU64,
+    rate_limit_errors_per_node: DashMap<Uuid, AtomicU64>,
+    throttled_requests_num: AtomicU64,
+    throttler_queue_length: AtomicU64,
+    throttling_rejections_num: AtomicU64,
 }
 
 impl Metrics {
@@ -239,6 +247,11 @@ impl Metrics {
             total_connections: AtomicU64::new(0),
             connection_timeouts: AtomicU64::new(0),
             request_timeouts: AtomicU64::new(0),
+            rate_limit_errors_num: AtomicU64::new(0),
+            rate_limit_errors_per_node: DashMap::new(),
+            throttled_requests_num: AtomicU64::new(0),
+            throttler_queue_length: AtomicU64::new(0),
+            throttling_rejections_num: AtomicU64::new(0),
         }
     }
 
@@ -292,6 +305,29 @@ impl Metrics {
         self.request_timeouts.fetch_add(1, ORDER_TYPE);
     }
 
+    /// Increments counters for requests rejected because of exceeding
+    /// a per-partition rate limit (Scylla-specific), in total and for the given node.
+    pub(crate) fn inc_rate_limit_errors(&self, host_id: Uuid) {
+        self.rate_limit_errors_num.fetch_add(1, ORDER_TYPE);
+        self.rate_limit_errors_per_node
+            .entry(host_id)
+            .or_default()
+            .fetch_add(1, ORDER_TYPE);
+    }
+
+    /// Records a request which has to wait for a permit of a request throttler.
+    /// The request is counted in the throttler queue length until the returned entry is dropped.
+    pub(crate) fn throttler_queue_entry(&self) -> ThrottlerQueueEntry<'_> {
+        self.throttled_requests_num.fetch_add(1, ORDER_TYPE);
+        self.throttler_queue_length.fetch_add(1, ORDER_TYPE);
+        ThrottlerQueueEntry { metrics: self }
+    }
+
+    /// Increments counter for requests rejected by a request throttler.
+    pub(crate) fn inc_throttling_rejections(&self) {
+        self.throttling_rejections_num.fetch_add(1, ORDER_TYPE);
+    }
+
     /// Saves to histogram latency of completing single query.
     /// For paged queries it should log latency for every page.
     ///
@@ -422,6 +458,43 @@ impl Metrics {
         self.request_timeouts.load(ORDER_TYPE)
     }
 
+    /// Returns counter for requests rejected because of exceeding a per-partition rate limit
+    pub fn get_rate_limit_errors_num(&self) -> u64 {
+        self.rate_limit_errors_num.load(ORDER_TYPE)
+    }
+
+    /// Returns counter for requests rejected because of exceeding a per-partition rate limit
+    /// by the node with the given host id
+    pub fn get_rate_limit_errors_num_for_node(&self, host_id: Uuid) -> u64 {
+        self.rate_limit_errors_per_node
+            .get(&host_id)
+            .map_or(0, |counter| counter.load(ORDER_TYPE))
+    }
+
+    /// Returns counters for requests rejected because of exceeding a per-partition rate limit,
+    /// keyed by host ids of the nodes which rejected them
+    pub fn get_rate_limit_errors_num_per_node(&self) -> HashMap<Uuid, u64> {
+        self.rate_limit_errors_per_node
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().load(ORDER_TYPE)))
+            .collect()
+    }
+
+    /// Returns counter for requests which had to wait for a permit of a request throttler
+    pub fn get_throttled_requests_num(&self) -> u64 {
+        self.throttled_requests_num.load(ORDER_TYPE)
+    }
+
+    /// Returns the number of requests currently waiting for a permit of a request throttler
+    pub fn get_throttler_queue_length(&self) -> u64 {
+        self.throttler_queue_length.load(ORDER_TYPE)
+    }
+
+    /// Returns counter for requests rejected by a request throttler
+    pub fn get_throttling_rejections_num(&self) -> u64 {
+        self.throttling_rejections_num.load(ORDER_TYPE)
+    }
+
     // Metric implementations
 
     // histogram crate used to implement Histogram::mean() method. Why did they remove it?
@@ -510,6 +583,17 @@ impl Metrics {
     }
 }
 
+/// Decrements the throttler queue length when the request stops waiting.
+pub(crate) struct ThrottlerQueueEntry<'a> {
+    metrics: &'a Metrics,
+}
+
+impl Drop for ThrottlerQueueEntry<'_> {
+    fn drop(&mut self) {
+        self.metrics.throttler_queue_length.fetch_sub(1, ORDER_TYPE);
+    }
+}
+
 #[cfg(test)]
 impl Default for Metrics {
     fn default() -> Self {
@@ -531,6 +615,14 @@ impl std::fmt::Debug for Metrics {
             .field("total_connections", &self.total_connections)
             .field("connection_timeouts", &self.connection_timeouts)
             .field("request_timeouts", &self.request_timeouts)
+            .field("rate_limit_errors_num", &self.rate_limit_errors_num)
+            .field(
+                "rate_limit_errors_per_node",
+                &self.rate_limit_errors_per_node,
+            )
+            .field("throttled_requests_num", &self.throttled_requests_num)
+            .field("throttler_queue_length", &self.throttler_queue_length)
+            .field("throttling_rejections_num", &self.throttling_rejections_num)
             .finish()
     }
 }
@@ -0,0 +1,436 @@
+//! Request interceptors.
+//!
+//! A [`RequestInterceptor`] is a hook around execution of `QUERY`, `EXECUTE`
+//! and `BATCH` requests. Before each attempt of a request it can inspect
+//! and modify what is about to be sent, fail the attempt or reject the whole
+//! request. After each attempt, and after the whole request, it can inspect
+//! or replace the outcome.
+//!
+//! Interceptors are registered on the [`Session`](crate::client::session::Session)
+//! with [`SessionBuilder::request_interceptor`](crate::client::session_builder::GenericSessionBuilder::request_interceptor),
+//! and can be overridden per [`ExecutionProfile`](crate::client::execution_profile::ExecutionProfile)
+//! with [`ExecutionProfileBuilder::request_interceptor`](crate::client::execution_profile::ExecutionProfileBuilder::request_interceptor).
+//!
+//! ```
+//! # use scylla::policies::interceptor::{
+//! #     InterceptedRequest, InterceptedStatement, InterceptorDecision, RequestInterceptor,
+//! # };
+//! /// Rejects `TRUNCATE` statements and adds `USING TIMEOUT` to `SELECT`s.
+//! #[derive(Debug)]
+//! struct Guard;
+//!
+//! impl RequestInterceptor for Guard {
+//!     fn before_attempt(&self, request: &mut InterceptedRequest<'_>) -> InterceptorDecision {
+//!         let Some(contents) = request.contents_mut() else {
+//!             return InterceptorDecision::Proceed;
+//!         };
+//!         let uppercase = contents.trim_start().to_uppercase();
+//!         if uppercase.starts_with("TRUNCATE") {
+//!             return InterceptorDecision::reject("TRUNCATE is not allowed");
+//!         }
+//!         if uppercase.starts_with("SELECT") && !uppercase.contains("USING TIMEOUT") {
+//!             contents.push_str(" USING TIMEOUT 500ms");
+//!         }
+//!         InterceptorDecision::Proceed
+//!     }
+//! }
+//! ```
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use scylla_cql::frame::response::result::{ColumnType, NativeType};
+use scylla_cql::frame::types::{RawValue, SerialConsistency};
+use scylla_cql::serialize::row::SerializedValues;
+use scylla_cql::serialize::value::SerializeValue;
+use scylla_cql::value::CqlDuration;
+use scylla_cql::Consistency;
+
+use crate::errors::{ExecutionError, RequestAttemptError};
+use crate::response::query_result::QueryResult;
+use crate::response::{Coordinator, PagingStateResponse};
+use crate::statement::batch::Batch;
+use crate::statement::prepared::PreparedStatement;
+use crate::statement::unprepared::Statement;
+
+/// A hook around execution of requests.
+///
+/// All methods have default implementations which leave the request
+/// and its outcome intact, so an interceptor only implements the hooks it needs.
+///
+/// Interceptors are called for requests sent by
+/// [`Session`](crate::client::session::Session) methods executing statements
+/// and batches, including pages fetched by [`QueryPager`](crate::client::pager::QueryPager).
+/// They are not called for `PREPARE` requests and for requests sent by the driver
+/// internally, e.g. to fetch cluster metadata.
+pub trait RequestInterceptor: std::fmt::Debug + Send + Sync {
+    /// Called before each attempt of a request, including retries
+    /// and speculative executions.
+    ///
+    /// The interceptor can modify the `request`, e.g. its consistency
+    /// or custom payload. Modifications apply only to this attempt.
+    fn before_attempt(&self, request: &mut InterceptedRequest<'_>) -> InterceptorDecision {
+        let _ = request;
+        InterceptorDecision::Proceed
+    }
+
+    /// Called after each attempt of a request, before the retry policy
+    /// decides what to do next.
+    ///
+    /// Returns the outcome of the attempt, which is `result` by default.
+    /// Returning an error for a successful attempt discards the response
+    /// and fails the attempt with the error. A failed attempt can't be
+    /// turned into a successful one: returning `Ok(())` for it keeps the original error.
+    fn after_attempt(
+        &self,
+        request: &InterceptedRequest<'_>,
+        result: Result<(), RequestAttemptError>,
+    ) -> Result<(), RequestAttemptError> {
+        let _ = request;
+        result
+    }
+
+    /// Called once after a whole request, executed with
+    /// [`Session::query_unpaged`](crate::client::session::Session::query_unpaged),
+    /// [`Session::execute_unpaged`](crate::client::session::Session::execute_unpaged),
+    /// [`Session::batch`](crate::client::session::Session::batch) or their
+    /// single page counterparts, completes.
+    ///
+    /// Returns the result passed to the caller, which is `result` by default.
+    /// Not called for pages fetched by [`QueryPager`](crate::client::pager::QueryPager).
+    // The signature mirrors the one of `Session` methods, hence `allow(clippy::result_large_err)`.
+    #[allow(clippy::result_large_err)]
+    fn after_request(
+        &self,
+        statement: InterceptedStatement<'_>,
+        result: Result<QueryResult, ExecutionError>,
+    ) -> Result<QueryResult, ExecutionError> {
+        let _ = statement;
+        result
+    }
+}
+
+/// Decision of a [`RequestInterceptor`] about an attempt of a request.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum InterceptorDecision {
+    /// Send the request.
+    Proceed,
+    /// Don't send the request, fail the attempt with the given error instead.
+    /// The retry policy decides about the next attempt as if the error was
+    /// returned by the node.
+    FailAttempt(RequestAttemptError),
+    /// Don't send the request and fail the whole request without retrying it.
+    /// The request fails with [`ExecutionError::RejectedByInterceptor`].
+    Reject(Arc<dyn Error + Send + Sync>),
+}
+
+impl InterceptorDecision {
+    /// Creates [`InterceptorDecision::Reject`] with the given reason.
+    pub fn reject(reason: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        Self::Reject(Arc::from(reason.into()))
+    }
+}
+
+/// The statement executed by an intercepted request.
+#[derive(Clone, Copy)]
+#[non_exhaustive]
+pub enum InterceptedStatement<'a> {
+    /// An unprepared statement, sent in a `QUERY` request.
+    Unprepared(&'a Statement),
+    /// A prepared statement, sent in an `EXECUTE` request.
+    Prepared(&'a PreparedStatement),
+    /// A batch, sent in a `BATCH` request.
+    Batch(&'a Batch),
+}
+
+/// An attempt of a request, as seen by a [`RequestInterceptor`].
+#[derive(Clone)]
+pub struct InterceptedRequest<'a> {
+    pub(crate) statement: InterceptedStatement<'a>,
+    contents: Option<String>,
+    pub(crate) values: Option<Cow<'a, SerializedValues>>,
+    pub(crate) consistency: Consistency,
+    pub(crate) serial_consistency: Option<SerialConsistency>,
+    pub(crate) custom_payload: Option<Arc<HashMap<String, Bytes>>>,
+    coordinator: Coordinator,
+}
+
+impl<'a> InterceptedRequest<'a> {
+    /// The executed statement, as passed by the caller.
+    /// Modifications of the request are not reflected in it.
+    pub fn statement(&self) -> InterceptedStatement<'a> {
+        self.statement
+    }
+
+    /// Contents of the executed statement, including modifications made
+    /// with [`InterceptedRequest::contents_mut`].
+    ///
+    /// Returns `None` for batches.
+    pub fn contents(&self) -> Option<&str> {
+        match self.statement {
+            InterceptedStatement::Unprepared(statement) => {
+                Some(self.contents.as_deref().unwrap_or(&statement.contents))
+            }
+            InterceptedStatement::Prepared(prepared) => Some(prepared.get_statement()),
+            InterceptedStatement::Batch(_) => None,
+        }
+    }
+
+    /// Allows to modify contents of an unprepared statement,
+    /// e.g. to add `USING TIMEOUT` to it.
+    ///
+    /// If the statement has bound values, the modified statement is prepared
+    /// before being executed. Returns `None` for prepared statements and batches,
+    /// whose contents can't be modified. Note that an unprepared statement with
+    /// bound values executed with [`Session::query_iter`](crate::client::session::Session::query_iter)
+    /// is prepared beforehand, so it is intercepted as a prepared statement.
+    pub fn contents_mut(&mut self) -> Option<&mut String> {
+        match self.statement {
+            InterceptedStatement::Unprepared(statement) => Some(
+                self.contents
+                    .get_or_insert_with(|| statement.contents.clone()),
+            ),
+            InterceptedStatement::Prepared(_) | InterceptedStatement::Batch(_) => None,
+        }
+    }
+
+    /// Values bound to a prepared statement, including the server-side timeout
+    /// bound by the driver (see [`ExecutionProfileBuilder::propagate_timeout_to_server`](crate::client::execution_profile::ExecutionProfileBuilder::propagate_timeout_to_server)).
+    ///
+    /// Names and types of the values are available through
+    /// [`PreparedStatement::get_variable_col_specs`]. Returns `None`
+    /// for unprepared statements and batches.
+    pub fn values(&self) -> Option<&SerializedValues> {
+        self.values.as_deref()
+    }
+
+    /// Consistency of this attempt.
+    pub fn consistency(&self) -> Consistency {
+        self.consistency
+    }
+
+    /// Changes consistency of this attempt.
+    pub fn set_consistency(&mut self, consistency: Consistency) {
+        self.consistency = consistency;
+    }
+
+    /// Serial consistency of this attempt.
+    pub fn serial_consistency(&self) -> Option<SerialConsistency> {
+        self.serial_consistency
+    }
+
+    /// Changes serial consistency of this attempt.
+    pub fn set_serial_consistency(&mut self, serial_consistency: Option<SerialConsistency>) {
+        self.serial_consistency = serial_consistency;
+    }
+
+    /// Custom payload sent with this attempt.
+    pub fn custom_payload(&self) -> Option<&HashMap<String, Bytes>> {
+        self.custom_payload.as_deref()
+    }
+
+    /// Allows to modify custom payload sent with this attempt.
+    /// An empty payload is created if there was none.
+    pub fn custom_payload_mut(&mut self) -> &mut HashMap<String, Bytes> {
+        Arc::make_mut(self.custom_payload.get_or_insert_with(Default::default))
+    }
+
+    /// The node and shard this attempt is sent to.
+    pub fn coordinator(&self) -> &Coordinator {
+        &self.coordinator
+    }
+
+    /// Binds `timeout` to the `USING TIMEOUT ?` marker of a prepared statement,
+    /// replacing the value bound by the caller. Does nothing if there is no such marker.
+    pub(crate) fn bind_server_side_timeout(&mut self, timeout: Duration) {
+        let (InterceptedStatement::Prepared(prepared), Some(values)) =
+            (self.statement, &self.values)
+        else {
+            return;
+        };
+        let Some((timeout_idx, _)) = prepared
+            .get_variable_col_specs()
+            .get_by_name(SERVER_SIDE_TIMEOUT_MARKER)
+            .filter(|(_, spec)| *spec.typ() == ColumnType::Native(NativeType::Duration))
+        else {
+            return;
+        };
+
+        // The timeout is rounded up to whole milliseconds, which is the precision
+        // of server-side timeouts, so that it doesn't become zero.
+        let millis = timeout.as_nanos().div_ceil(1_000_000).max(1);
+        let timeout = CqlDuration {
+            months: 0,
+            days: 0,
+            nanoseconds: i64::try_from(millis * 1_000_000).unwrap_or(i64::MAX),
+        };
+        let (values, ()) = SerializedValues::from_closure(|writer| {
+            for (idx, value) in values.iter().enumerate() {
+                let cell = writer.make_cell_writer();
+                if idx == timeout_idx {
+                    timeout.serialize(&ColumnType::Native(NativeType::Duration), cell)?;
+                    continue;
+                }
+                match value {
+                    RawValue::Null => cell.set_null(),
+                    RawValue::Unset => cell.set_unset(),
+                    RawValue::Value(bytes) => cell.set_value(bytes).unwrap(),
+                };
+            }
+            Ok(())
+        })
+        // The values were already serialized once, and a duration
+        // is a valid value of the marker's type.
+        .expect("rebinding serialized values failed");
+        self.values = Some(Cow::Owned(values));
+    }
+
+    /// The unprepared statement with contents modified by an interceptor,
+    /// or `None` if they were not modified.
+    pub(crate) fn modified_statement(&self) -> Option<Statement> {
+        match (self.statement, &self.contents) {
+            (InterceptedStatement::Unprepared(statement), Some(contents)) => {
+                let mut modified = statement.clone();
+                modified.contents = contents.clone();
+                Some(modified)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Name given by ScyllaDB to the bind marker of a `USING TIMEOUT ?` clause.
+const SERVER_SIDE_TIMEOUT_MARKER: &str = "[timeout]";
+
+/// Parts of a request which are common for all its attempts.
+#[derive(Clone)]
+pub(crate) struct RequestTemplate<'a> {
+    pub(crate) statement: InterceptedStatement<'a>,
+    pub(crate) values: Option<&'a SerializedValues>,
+    pub(crate) serial_consistency: Option<SerialConsistency>,
+    pub(crate) custom_payload: Option<Arc<HashMap<String, Bytes>>>,
+}
+
+impl<'a> RequestTemplate<'a> {
+    pub(crate) fn attempt(
+        &self,
+        consistency: Consistency,
+        coordinator: Coordinator,
+    ) -> InterceptedRequest<'a> {
+        InterceptedRequest {
+            statement: self.statement,
+            contents: None,
+            values: self.values.map(Cow::Borrowed),
+            consistency,
+            serial_consistency: self.serial_consistency,
+            custom_payload: self.custom_payload.clone(),
+            coordinator,
+        }
+    }
+}
+
+/// Passes the outcome of an attempt through [`RequestInterceptor::after_attempt`].
+pub(crate) fn intercept_attempt_result<T>(
+    interceptor: &dyn RequestInterceptor,
+    request: &InterceptedRequest<'_>,
+    result: Result<T, RequestAttemptError>,
+) -> Result<T, RequestAttemptError> {
+    match result {
+        Ok(response) => interceptor
+            .after_attempt(request, Ok(()))
+            .map(|()| response),
+        Err(err) => Err(interceptor
+            .after_attempt(request, Err(err.clone()))
+            .err()
+            .unwrap_or(err)),
+    }
+}
+
+/// Passes the result of a single page request through [`RequestInterceptor::after_request`].
+#[allow(clippy::result_large_err)]
+pub(crate) fn intercept_page_result(
+    interceptor: &dyn RequestInterceptor,
+    statement: InterceptedStatement<'_>,
+    result: Result<(QueryResult, PagingStateResponse), ExecutionError>,
+) -> Result<(QueryResult, PagingStateResponse), ExecutionError> {
+    match result {
+        Ok((result, paging_state_response)) => interceptor
+            .after_request(statement, Ok(result))
+            .map(|result| (result, paging_state_response)),
+        Err(err) => interceptor
+            .after_request(statement, Err(err))
+            .map(|result| (result, PagingStateResponse::NoMorePages)),
+    }
+}
+
+/// A chain of interceptors, called in order of registration.
+///
+/// [`RequestInterceptor::before_attempt`] hooks are called in order, until
+/// one of them decides not to proceed. [`RequestInterceptor::after_attempt`]
+/// and [`RequestInterceptor::after_request`] hooks are called in reverse order,
+/// each receiving the outcome returned by the previous one.
+#[derive(Debug, Clone, Default)]
+pub struct RequestInterceptorChain {
+    interceptors: Vec<Arc<dyn RequestInterceptor>>,
+}
+
+impl RequestInterceptorChain {
+    /// Creates a chain of the given interceptors.
+    pub fn new(interceptors: Vec<Arc<dyn RequestInterceptor>>) -> Self {
+        Self { interceptors }
+    }
+
+    /// Appends an interceptor to the end of the chain.
+    pub fn push(&mut self, interceptor: Arc<dyn RequestInterceptor>) {
+        self.interceptors.push(interceptor);
+    }
+
+    /// Interceptors in the chain.
+    pub fn interceptors(&self) -> &[Arc<dyn RequestInterceptor>] {
+        &self.interceptors
+    }
+}
+
+impl RequestInterceptor for RequestInterceptorChain {
+    fn before_attempt(&self, request: &mut InterceptedRequest<'_>) -> InterceptorDecision {
+        for interceptor in &self.interceptors {
+            match interceptor.before_attempt(request) {
+                InterceptorDecision::Proceed => {}
+                decision => return decision,
+            }
+        }
+        InterceptorDecision::Proceed
+    }
+
+    fn after_attempt(
+        &self,
+        request: &InterceptedRequest<'_>,
+        result: Result<(), RequestAttemptError>,
+    ) -> Result<(), RequestAttemptError> {
+        self.interceptors
+            .iter()
+            .rev()
+            .fold(result, |result, interceptor| {
+                interceptor.after_attempt(request, result)
+            })
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn after_request(
+        &self,
+        statement: InterceptedStatement<'_>,
+        result: Result<QueryResult, ExecutionError>,
+    ) -> Result<QueryResult, ExecutionError> {
+        self.interceptors
+            .iter()
+            .rev()
+            .fold(result, |result, interceptor| {
+                interceptor.after_request(statement, result)
+            })
+    }
+}
@@ -168,6 +168,8 @@ mod tests {
 
     use crate::{
         cluster::{Node, NodeAddr},
+        frame::ProtocolVersion,
+        network::SharedProtocolVersion,
         routing::locator::test::{create_locator, mock_metadata_for_token_aware_tests},
         test_utils::setup_tracing,
     };
@@ -230,6 +232,7 @@ mod tests {
             all_nodes: Default::default(),
             keyspaces: Default::default(),
             locator,
+            protocol_version: Arc::new(SharedProtocolVersion::fixed(ProtocolVersion::V4)),
         };
         let routing_info = RoutingInfo::default();
         let plan = Plan::new(&policy, &routing_info, &cluster_state);
@@ -13,11 +13,15 @@
 //! - SpeculativeExecutionPolicy, which decides if the driver will send speculative
 //!   requests to the next hosts when the current host takes too long to respond.
 //! - RetryPolicy, which decides whether and how to retry a request.
+//! - RequestInterceptor, which can inspect and modify requests and their outcomes.
+//! - RequestThrottler, which bounds the concurrency and the rate of requests.
 //! - TODO
 
 pub mod address_translator;
 pub mod host_filter;
+pub mod interceptor;
 pub mod load_balancing;
 pub mod retry;
 pub mod speculative_execution;
+pub mod throttler;
 pub mod timestamp_generator;
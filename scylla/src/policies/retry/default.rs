@@ -1,4 +1,4 @@
-use scylla_cql::frame::response::error::{DbError, WriteType};
+use scylla_cql::frame::response::error::{DbError, OperationType, WriteType};
 
 use crate::errors::RequestAttemptError;
 
@@ -6,6 +6,10 @@ use super::{RequestInfo, RetryDecision, RetryPolicy, RetrySession};
 
 /// Default retry policy - retries when there is a high chance that a retry might help.\
 /// Behaviour based on [DataStax Java Driver](https://docs.datastax.com/en/developer/java-driver/4.10/manual/core/retries/)
+///
+/// Requests rejected because of exceeding a per-partition rate limit (Scylla-specific)
+/// are retried at most once, never on the same node. To wait before such retries,
+/// wrap the policy in a [RateLimitBackoffRetryPolicy](super::RateLimitBackoffRetryPolicy).
 #[derive(Debug)]
 pub struct DefaultRetryPolicy;
 
@@ -31,6 +35,7 @@ pub struct DefaultRetrySession {
     was_unavailable_retry: bool,
     was_read_timeout_retry: bool,
     was_write_timeout_retry: bool,
+    was_rate_limit_retry: bool,
 }
 
 impl DefaultRetrySession {
@@ -39,10 +44,35 @@ impl DefaultRetrySession {
             was_unavailable_retry: false,
             was_read_timeout_retry: false,
             was_write_timeout_retry: false,
+            was_rate_limit_retry: false,
         }
     }
 }
 
+/// Decides about retrying a request rejected because of exceeding a per-partition rate limit.
+///
+/// Retrying on the same node is pointless, as it would most likely reject the request again.
+/// Reads and writes rejected by the coordinator are guaranteed not to have been applied,
+/// so they are retried regardless of idempotence; other writes only if idempotent.
+/// Retries at most once.
+pub(super) fn decide_should_retry_rate_limited(
+    op_type: &OperationType,
+    rejected_by_coordinator: bool,
+    is_idempotent: bool,
+    was_retry: &mut bool,
+) -> RetryDecision {
+    let is_safe_to_retry = match op_type {
+        OperationType::Read => true,
+        OperationType::Write => rejected_by_coordinator || is_idempotent,
+        OperationType::Other(_) => is_idempotent,
+    };
+    if *was_retry || !is_safe_to_retry {
+        return RetryDecision::DontRetry;
+    }
+    *was_retry = true;
+    RetryDecision::RetryNextTarget(None)
+}
+
 impl Default for DefaultRetrySession {
     fn default() -> DefaultRetrySession {
         DefaultRetrySession::new()
@@ -121,6 +151,20 @@ impl RetrySession for DefaultRetrySession {
             RequestAttemptError::DbError(DbError::IsBootstrapping, _) => {
                 RetryDecision::RetryNextTarget(None)
             }
+            // Per-partition rate limit was exceeded (Scylla-specific).
+            // Retry at most once, on another node.
+            RequestAttemptError::DbError(
+                DbError::RateLimitReached {
+                    op_type,
+                    rejected_by_coordinator,
+                },
+                _,
+            ) => decide_should_retry_rate_limited(
+                op_type,
+                *rejected_by_coordinator,
+                request_info.is_idempotent,
+                &mut self.was_rate_limit_retry,
+            ),
             // Connection to the contacted node is overloaded, try another one
             RequestAttemptError::UnableToAllocStreamId => RetryDecision::RetryNextTarget(None),
             // In all other cases propagate the error to the user
@@ -142,12 +186,14 @@ mod tests {
     use crate::test_utils::setup_tracing;
     use bytes::Bytes;
     use scylla_cql::frame::frame_errors::{BatchSerializationError, CqlRequestSerializationError};
+    use scylla_cql::frame::response::error::OperationType;
 
     fn make_request_info(error: &RequestAttemptError, is_idempotent: bool) -> RequestInfo<'_> {
         RequestInfo {
             error,
             is_idempotent,
             consistency: Consistency::One,
+            remaining_time: None,
         }
     }
 
@@ -457,4 +503,50 @@ mod tests {
             RetryDecision::DontRetry
         );
     }
+
+    fn rate_limit_error(
+        op_type: OperationType,
+        rejected_by_coordinator: bool,
+    ) -> RequestAttemptError {
+        RequestAttemptError::DbError(
+            DbError::RateLimitReached {
+                op_type,
+                rejected_by_coordinator,
+            },
+            String::new(),
+        )
+    }
+
+    // On RateLimitReached we retry once on the next node, if the request is safe to retry
+    #[test]
+    fn default_rate_limit_reached() {
+        setup_tracing();
+        let always_retried = [
+            rate_limit_error(OperationType::Read, false),
+            rate_limit_error(OperationType::Read, true),
+            rate_limit_error(OperationType::Write, true),
+        ];
+        for error in always_retried {
+            for is_idempotent in [false, true] {
+                let mut policy = DefaultRetryPolicy::new().new_session();
+                assert_eq!(
+                    policy.decide_should_retry(make_request_info(&error, is_idempotent)),
+                    RetryDecision::RetryNextTarget(None)
+                );
+                assert_eq!(
+                    policy.decide_should_retry(make_request_info(&error, is_idempotent)),
+                    RetryDecision::DontRetry
+                );
+            }
+        }
+
+        // The write might have been applied on some replicas
+        let idempotent_only = [
+            rate_limit_error(OperationType::Write, false),
+            rate_limit_error(OperationType::Other(7), true),
+        ];
+        for error in idempotent_only {
+            default_policy_assert_idempotent_next(error);
+        }
+    }
 }
@@ -1,6 +1,7 @@
 use scylla_cql::Consistency;
 use tracing::debug;
 
+use super::default::decide_should_retry_rate_limited;
 use super::{RequestInfo, RetryDecision, RetryPolicy, RetrySession};
 use crate::errors::{DbError, RequestAttemptError, WriteType};
 
@@ -9,6 +10,9 @@ use crate::errors::{DbError, RequestAttemptError, WriteType};
 /// when it believes that the initial CL is reachable.
 /// Behaviour based on [DataStax Java Driver]\
 ///(<https://docs.datastax.com/en/drivers/java/3.11/com/datastax/driver/core/policies/DowngradingConsistencyRetryPolicy.html>)
+///
+/// Requests rejected because of exceeding a per-partition rate limit are handled
+/// the same way as in [DefaultRetryPolicy](crate::policies::retry::DefaultRetryPolicy).
 #[derive(Debug)]
 pub struct DowngradingConsistencyRetryPolicy;
 
@@ -32,11 +36,15 @@ impl RetryPolicy for DowngradingConsistencyRetryPolicy {
 
 pub struct DowngradingConsistencyRetrySession {
     was_retry: bool,
+    was_rate_limit_retry: bool,
 }
 
 impl DowngradingConsistencyRetrySession {
     pub fn new() -> DowngradingConsistencyRetrySession {
-        DowngradingConsistencyRetrySession { was_retry: false }
+        DowngradingConsistencyRetrySession {
+            was_retry: false,
+            was_rate_limit_retry: false,
+        }
     }
 }
 
@@ -163,6 +171,20 @@ impl RetrySession for DowngradingConsistencyRetrySession {
             RequestAttemptError::DbError(DbError::IsBootstrapping, _) => {
                 RetryDecision::RetryNextTarget(None)
             }
+            // Per-partition rate limit was exceeded (Scylla-specific).
+            // Retry at most once, on another node.
+            RequestAttemptError::DbError(
+                DbError::RateLimitReached {
+                    op_type,
+                    rejected_by_coordinator,
+                },
+                _,
+            ) => decide_should_retry_rate_limited(
+                op_type,
+                *rejected_by_coordinator,
+                request_info.is_idempotent,
+                &mut self.was_rate_limit_retry,
+            ),
             // Connection to the contacted node is overloaded, try another one
             RequestAttemptError::UnableToAllocStreamId => RetryDecision::RetryNextTarget(None),
             // In all other cases propagate the error to the user
@@ -180,7 +202,7 @@ mod tests {
     use bytes::Bytes;
     use scylla_cql::frame::frame_errors::{BatchSerializationError, CqlRequestSerializationError};
 
-    use crate::errors::{BrokenConnectionErrorKind, RequestAttemptError};
+    use crate::errors::{BrokenConnectionErrorKind, OperationType, RequestAttemptError};
     use crate::test_utils::setup_tracing;
 
     use super::*;
@@ -206,6 +228,7 @@ mod tests {
             error,
             is_idempotent,
             consistency: cl,
+            remaining_time: None,
         }
     }
 
@@ -692,4 +715,55 @@ mod tests {
             }
         }
     }
+
+    // On RateLimitReached we retry once on the next node without downgrading consistency,
+    // if the request is safe to retry
+    #[test]
+    fn downgrading_consistency_rate_limit_reached() {
+        setup_tracing();
+        let rejected_write = RequestAttemptError::DbError(
+            DbError::RateLimitReached {
+                op_type: OperationType::Write,
+                rejected_by_coordinator: true,
+            },
+            String::new(),
+        );
+        let maybe_applied_write = RequestAttemptError::DbError(
+            DbError::RateLimitReached {
+                op_type: OperationType::Write,
+                rejected_by_coordinator: false,
+            },
+            String::new(),
+        );
+
+        for &cl in CONSISTENCY_LEVELS {
+            let mut policy = DowngradingConsistencyRetryPolicy::new().new_session();
+            assert_eq!(
+                policy.decide_should_retry(make_request_info_with_cl(&rejected_write, false, cl)),
+                RetryDecision::RetryNextTarget(None)
+            );
+            assert_eq!(
+                policy.decide_should_retry(make_request_info_with_cl(&rejected_write, false, cl)),
+                RetryDecision::DontRetry
+            );
+
+            let mut policy = DowngradingConsistencyRetryPolicy::new().new_session();
+            assert_eq!(
+                policy.decide_should_retry(make_request_info_with_cl(
+                    &maybe_applied_write,
+                    false,
+                    cl
+                )),
+                RetryDecision::DontRetry
+            );
+            assert_eq!(
+                policy.decide_should_retry(make_request_info_with_cl(
+                    &maybe_applied_write,
+                    true,
+                    cl
+                )),
+                RetryDecision::RetryNextTarget(None)
+            );
+        }
+    }
 }
@@ -1,6 +1,7 @@
 mod default;
 mod downgrading_consistency;
 mod fallthrough;
+mod rate_limit_backoff;
 mod retry_policy;
 
 pub use default::{DefaultRetryPolicy, DefaultRetrySession};
@@ -8,4 +9,5 @@ pub use downgrading_consistency::{
     DowngradingConsistencyRetryPolicy, DowngradingConsistencyRetrySession,
 };
 pub use fallthrough::{FallthroughRetryPolicy, FallthroughRetrySession};
+pub use rate_limit_backoff::{RateLimitBackoffRetryPolicy, RateLimitBackoffRetrySession};
 pub use retry_policy::{RequestInfo, RetryDecision, RetryPolicy, RetrySession};
@@ -0,0 +1,139 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use scylla_cql::frame::response::error::DbError;
+
+use crate::errors::RequestAttemptError;
+
+use super::{RequestInfo, RetryDecision, RetryPolicy, RetrySession};
+
+/// Wraps another retry policy and waits before retrying requests rejected
+/// because of exceeding a per-partition rate limit (Scylla-specific).
+///
+/// Whether such a request is retried is decided by the wrapped policy;
+/// this policy only delays the retries on the next target.
+#[derive(Debug)]
+pub struct RateLimitBackoffRetryPolicy {
+    inner: Arc<dyn RetryPolicy>,
+    backoff: Duration,
+}
+
+impl RateLimitBackoffRetryPolicy {
+    /// Creates a policy which waits for `backoff` before the retries of rate limited
+    /// requests decided by `inner`.
+    pub fn new(inner: Arc<dyn RetryPolicy>, backoff: Duration) -> RateLimitBackoffRetryPolicy {
+        RateLimitBackoffRetryPolicy { inner, backoff }
+    }
+}
+
+impl RetryPolicy for RateLimitBackoffRetryPolicy {
+    fn new_session(&self) -> Box<dyn RetrySession> {
+        Box::new(RateLimitBackoffRetrySession {
+            inner: self.inner.new_session(),
+            backoff: self.backoff,
+        })
+    }
+}
+
+pub struct RateLimitBackoffRetrySession {
+    inner: Box<dyn RetrySession>,
+    backoff: Duration,
+}
+
+impl RetrySession for RateLimitBackoffRetrySession {
+    fn decide_should_retry(&mut self, request_info: RequestInfo) -> RetryDecision {
+        let is_rate_limited = matches!(
+            request_info.error,
+            RequestAttemptError::DbError(DbError::RateLimitReached { .. }, _)
+        );
+        match self.inner.decide_should_retry(request_info) {
+            RetryDecision::RetryNextTarget(cl) if is_rate_limited => {
+                RetryDecision::RetryNextTargetAfter(cl, self.backoff)
+            }
+            decision => decision,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use scylla_cql::frame::response::error::{DbError, OperationType};
+
+    use super::RateLimitBackoffRetryPolicy;
+    use crate::errors::RequestAttemptError;
+    use crate::policies::retry::{
+        DefaultRetryPolicy, DowngradingConsistencyRetryPolicy, RequestInfo, RetryDecision,
+        RetryPolicy,
+    };
+    use crate::statement::Consistency;
+    use crate::test_utils::setup_tracing;
+
+    fn make_request_info(error: &RequestAttemptError) -> RequestInfo<'_> {
+        RequestInfo {
+            error,
+            is_idempotent: false,
+            consistency: Consistency::Quorum,
+            remaining_time: None,
+        }
+    }
+
+    #[test]
+    fn rate_limit_backoff() {
+        setup_tracing();
+        let backoff = Duration::from_millis(50);
+        let rate_limited = RequestAttemptError::DbError(
+            DbError::RateLimitReached {
+                op_type: OperationType::Write,
+                rejected_by_coordinator: true,
+            },
+            String::new(),
+        );
+        let inner_policies: [Arc<dyn RetryPolicy>; 2] = [
+            Arc::new(DefaultRetryPolicy::new()),
+            Arc::new(DowngradingConsistencyRetryPolicy::new()),
+        ];
+
+        for inner in inner_policies {
+            let policy = RateLimitBackoffRetryPolicy::new(inner, backoff);
+            let mut session = policy.new_session();
+            assert_eq!(
+                session.decide_should_retry(make_request_info(&rate_limited)),
+                RetryDecision::RetryNextTargetAfter(None, backoff)
+            );
+            // The wrapped policy retries at most once.
+            assert_eq!(
+                session.decide_should_retry(make_request_info(&rate_limited)),
+                RetryDecision::DontRetry
+            );
+            session.reset();
+            assert_eq!(
+                session.decide_should_retry(make_request_info(&rate_limited)),
+                RetryDecision::RetryNextTargetAfter(None, backoff)
+            );
+
+            // Decisions about other errors are not delayed.
+            let unavailable = RequestAttemptError::DbError(
+                DbError::Unavailable {
+                    consistency: Consistency::Quorum,
+                    required: 2,
+                    alive: 1,
+                },
+                String::new(),
+            );
+            let decision = policy
+                .new_session()
+                .decide_should_retry(make_request_info(&unavailable));
+            assert!(!matches!(
+                decision,
+                RetryDecision::RetryNextTargetAfter(..) | RetryDecision::DontRetry
+            ));
+        }
+    }
+}
@@ -2,6 +2,8 @@
 //! To decide when to retry a request the `Session` can use any object which implements
 //! the `RetryPolicy` trait
 
+use std::time::Duration;
+
 use crate::errors::RequestAttemptError;
 use crate::frame::types::Consistency;
 
@@ -16,6 +18,9 @@ pub struct RequestInfo<'a> {
     pub is_idempotent: bool,
     /// Consistency with which the request failed
     pub consistency: Consistency,
+    /// Time left until the request timeout, or None if the request has no timeout.
+    /// A retry started with little time left is likely to time out.
+    pub remaining_time: Option<Duration>,
 }
 
 /// Returned by implementations of RetryPolicy. Instructs the driver on what
@@ -27,6 +32,9 @@ pub enum RetryDecision {
     RetrySameTarget(Option<Consistency>), // None means that the same consistency should be used as before
     /// Request will be sent to the next target generated by load balancing policy.
     RetryNextTarget(Option<Consistency>), // ditto
+    /// Request will be sent to the next target generated by load balancing policy,
+    /// after waiting for the given duration.
+    RetryNextTargetAfter(Option<Consistency>, Duration), // ditto
     /// Fails the whole request.
     DontRetry,
     /// Will cause the driver to return an empty successful response.
@@ -107,6 +107,15 @@ fn can_be_ignored<ResT>(result: &Result<ResT, RequestError>) -> bool {
             // Request execution timed out.
             RequestError::RequestTimeout(_) => false,
 
+            // Rejected requests are not sent anywhere.
+            RequestError::RejectedByInterceptor(_) => false,
+
+            // The throttler does not let more requests through.
+            RequestError::RequestThrottled(_) => false,
+
+            // The whole session is going down.
+            RequestError::SessionShutDown => false,
+
             // Can try on another node.
             RequestError::ConnectionPoolError { .. } => true,
 
@@ -0,0 +1,410 @@
+//! Client-side request throttling.
+//!
+//! A [`RequestThrottler`] is asked for a [`ThrottlingPermit`] before a request
+//! is executed, and the permit is held until the request completes. It can make the request
+//! wait, e.g. until other requests complete, or reject it with a [`ThrottlingError`],
+//! which is returned to the caller as [`ExecutionError::RequestThrottled`](crate::errors::ExecutionError::RequestThrottled).
+//!
+//! Unpaged requests acquire one permit for all their attempts, including retries
+//! and speculative executions. Pagers acquire a permit for every attempt to fetch a page.
+//!
+//! [`DefaultRequestThrottler`] provides a concurrency limit, a token-bucket
+//! rate limit and a bounded queue of waiting requests.
+
+use std::any::Any;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use tokio::sync::{Semaphore, TryAcquireError};
+use tokio::time::{Duration, Instant};
+
+use crate::errors::ThrottlingError;
+#[cfg(feature = "metrics")]
+use crate::observability::metrics::Metrics;
+
+/// Decides when requests can be sent.
+///
+/// Requests are throttled by the throttler of their execution profile or,
+/// if the profile doesn't set one, by the throttler of the session.
+#[async_trait]
+pub trait RequestThrottler: Debug + Send + Sync {
+    /// Waits until a request can be sent and returns a permit to be held while
+    /// the request is executed, or returns an error if the request is rejected.
+    async fn acquire(&self) -> Result<ThrottlingPermit, ThrottlingError>;
+}
+
+/// Permits a request to be executed. Releases the capacity taken by the request when dropped.
+pub struct ThrottlingPermit {
+    _release: Option<Box<dyn Any + Send + Sync>>,
+}
+
+impl ThrottlingPermit {
+    /// Creates a permit which drops `release` when the request completes.
+    pub fn new(release: impl Any + Send + Sync) -> Self {
+        Self {
+            _release: Some(Box::new(release)),
+        }
+    }
+
+    /// Creates a permit which doesn't need to release anything.
+    pub fn unbounded() -> Self {
+        Self { _release: None }
+    }
+}
+
+impl Debug for ThrottlingPermit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ThrottlingPermit").finish_non_exhaustive()
+    }
+}
+
+/// Acquires a permit from the throttler, recording in metrics whether the request
+/// had to wait and whether it was rejected.
+pub(crate) async fn acquire_permit(
+    throttler: &dyn RequestThrottler,
+    #[cfg(feature = "metrics")] metrics: &Metrics,
+) -> Result<ThrottlingPermit, ThrottlingError> {
+    let acquire = throttler.acquire();
+    tokio::pin!(acquire);
+
+    let result = match futures::poll!(acquire.as_mut()) {
+        std::task::Poll::Ready(result) => result,
+        std::task::Poll::Pending => {
+            #[cfg(feature = "metrics")]
+            let _queued = metrics.throttler_queue_entry();
+            acquire.await
+        }
+    };
+
+    #[cfg(feature = "metrics")]
+    if result.is_err() {
+        metrics.inc_throttling_rejections();
+    }
+
+    result
+}
+
+/// A throttler which limits the number of concurrent requests and the rate
+/// at which requests are sent.
+///
+/// Requests which can't be sent immediately wait in a queue, whose length and
+/// the time spent in it can be bounded. By default neither the concurrency
+/// nor the rate is limited, and the queue is unbounded.
+///
+/// ```
+/// # use std::time::Duration;
+/// use scylla::policies::throttler::DefaultRequestThrottler;
+///
+/// let throttler = DefaultRequestThrottler::builder()
+///     .max_concurrent_requests(1024)
+///     .rate_limit(10_000, 1000)
+///     .max_queue_length(4096)
+///     .max_queue_wait(Duration::from_millis(500))
+///     .build();
+/// ```
+#[derive(Debug)]
+pub struct DefaultRequestThrottler {
+    concurrency_limit: Option<Arc<Semaphore>>,
+    rate_limit: Option<Mutex<TokenBucket>>,
+    max_queue_length: usize,
+    max_queue_wait: Option<Duration>,
+    queue_length: AtomicUsize,
+}
+
+impl DefaultRequestThrottler {
+    /// Creates a builder used to customise configuration of a new DefaultRequestThrottler.
+    pub fn builder() -> DefaultRequestThrottlerBuilder {
+        DefaultRequestThrottlerBuilder::new()
+    }
+
+    /// Returns the number of requests waiting in the queue.
+    pub fn queue_length(&self) -> usize {
+        self.queue_length.load(Ordering::Relaxed)
+    }
+
+    fn enter_queue(&self) -> Result<QueueEntry<'_>, ThrottlingError> {
+        self.queue_length
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |length| {
+                (length < self.max_queue_length).then_some(length + 1)
+            })
+            .map_err(|_| ThrottlingError::QueueFull {
+                max_queue_length: self.max_queue_length,
+            })?;
+        Ok(QueueEntry {
+            queue_length: &self.queue_length,
+        })
+    }
+
+    fn queue_timeout(&self) -> ThrottlingError {
+        ThrottlingError::QueueTimeout(self.max_queue_wait.unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl RequestThrottler for DefaultRequestThrottler {
+    async fn acquire(&self) -> Result<ThrottlingPermit, ThrottlingError> {
+        let deadline = self.max_queue_wait.map(|wait| Instant::now() + wait);
+        // Set when the request has to wait, either for the rate limit or for the concurrency limit.
+        let mut queue_entry = None;
+
+        if let Some(rate_limit) = &self.rate_limit {
+            let ready_at = {
+                let mut bucket = rate_limit.lock().unwrap();
+                let now = Instant::now();
+                let ready_at = bucket.next_token_at(now);
+                if ready_at > now {
+                    queue_entry = Some(self.enter_queue()?);
+                    if deadline.is_some_and(|deadline| ready_at > deadline) {
+                        return Err(self.queue_timeout());
+                    }
+                }
+                // The token is reserved now, so that requests get tokens in the order of arrival.
+                bucket.take();
+                ready_at
+            };
+            tokio::time::sleep_until(ready_at).await;
+        }
+
+        let concurrency_permit = match &self.concurrency_limit {
+            Some(semaphore) => Some(match Arc::clone(semaphore).try_acquire_owned() {
+                Ok(permit) => permit,
+                Err(TryAcquireError::NoPermits) => {
+                    if queue_entry.is_none() {
+                        queue_entry = Some(self.enter_queue()?);
+                    }
+                    let acquire = Arc::clone(semaphore).acquire_owned();
+                    let acquired = match deadline {
+                        Some(deadline) => tokio::time::timeout_at(deadline, acquire)
+                            .await
+                            .map_err(|_| self.queue_timeout())?,
+                        None => acquire.await,
+                    };
+                    // The semaphore is never closed.
+                    acquired.unwrap()
+                }
+                Err(TryAcquireError::Closed) => unreachable!("The semaphore is never closed"),
+            }),
+            None => None,
+        };
+        drop(queue_entry);
+
+        Ok(match concurrency_permit {
+            Some(permit) => ThrottlingPermit::new(permit),
+            None => ThrottlingPermit::unbounded(),
+        })
+    }
+}
+
+/// Builds a [`DefaultRequestThrottler`].
+#[derive(Clone, Debug)]
+pub struct DefaultRequestThrottlerBuilder {
+    max_concurrent_requests: Option<usize>,
+    rate_limit: Option<(u32, u32)>,
+    max_queue_length: usize,
+    max_queue_wait: Option<Duration>,
+}
+
+impl DefaultRequestThrottlerBuilder {
+    /// Creates a builder of a throttler which doesn't limit anything.
+    pub fn new() -> Self {
+        Self {
+            max_concurrent_requests: None,
+            rate_limit: None,
+            max_queue_length: usize::MAX,
+            max_queue_wait: None,
+        }
+    }
+
+    /// Limits the number of requests executed concurrently.
+    pub fn max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.max_concurrent_requests = Some(max_concurrent_requests);
+        self
+    }
+
+    /// Limits the rate of requests to `requests_per_second`, allowing bursts
+    /// of up to `burst` requests (at least 1).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `requests_per_second` is 0.
+    pub fn rate_limit(mut self, requests_per_second: u32, burst: u32) -> Self {
+        assert!(requests_per_second > 0, "The rate limit must be positive");
+        self.rate_limit = Some((requests_per_second, burst.max(1)));
+        self
+    }
+
+    /// Limits the number of requests waiting to be let through.
+    /// Requests which don't fit in the queue are rejected with [`ThrottlingError::QueueFull`].
+    /// Setting it to 0 makes the throttler reject requests instead of queueing them.
+    pub fn max_queue_length(mut self, max_queue_length: usize) -> Self {
+        self.max_queue_length = max_queue_length;
+        self
+    }
+
+    /// Limits the time a request can wait to be let through.
+    /// Requests which wait longer are rejected with [`ThrottlingError::QueueTimeout`].
+    pub fn max_queue_wait(mut self, max_queue_wait: Duration) -> Self {
+        self.max_queue_wait = Some(max_queue_wait);
+        self
+    }
+
+    /// Builds a new DefaultRequestThrottler with the previously set configuration.
+    pub fn build(self) -> Arc<DefaultRequestThrottler> {
+        Arc::new(DefaultRequestThrottler {
+            concurrency_limit: self
+                .max_concurrent_requests
+                .map(|max| Arc::new(Semaphore::new(max))),
+            rate_limit: self.rate_limit.map(|(requests_per_second, burst)| {
+                Mutex::new(TokenBucket::new(requests_per_second, burst))
+            }),
+            max_queue_length: self.max_queue_length,
+            max_queue_wait: self.max_queue_wait,
+            queue_length: AtomicUsize::new(0),
+        })
+    }
+}
+
+impl Default for DefaultRequestThrottlerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct QueueEntry<'a> {
+    queue_length: &'a AtomicUsize,
+}
+
+impl Drop for QueueEntry<'_> {
+    fn drop(&mut self) {
+        self.queue_length.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+// The number of tokens may become negative, which means that
+// the tokens which will be refilled next are already reserved.
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    capacity: f64,
+    tokens_per_second: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(requests_per_second: u32, burst: u32) -> Self {
+        Self {
+            tokens: burst as f64,
+            capacity: burst as f64,
+            tokens_per_second: requests_per_second as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn next_token_at(&mut self, now: Instant) -> Instant {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * self.tokens_per_second).min(self.capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            now
+        } else {
+            now + Duration::from_secs_f64((1.0 - self.tokens) / self.tokens_per_second)
+        }
+    }
+
+    fn take(&mut self) {
+        self.tokens -= 1.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tokio::time::{Duration, Instant};
+
+    use super::{DefaultRequestThrottler, RequestThrottler};
+    use crate::errors::ThrottlingError;
+
+    #[tokio::test(start_paused = true)]
+    async fn concurrency_limit_queues_and_rejects() {
+        let throttler = DefaultRequestThrottler::builder()
+            .max_concurrent_requests(1)
+            .max_queue_length(1)
+            .max_queue_wait(Duration::from_secs(1))
+            .build();
+
+        let permit = throttler.acquire().await.unwrap();
+
+        let queued = tokio::spawn({
+            let throttler = Arc::clone(&throttler);
+            async move { throttler.acquire().await }
+        });
+        while throttler.queue_length() != 1 {
+            tokio::task::yield_now().await;
+        }
+        assert!(matches!(
+            throttler.acquire().await,
+            Err(ThrottlingError::QueueFull {
+                max_queue_length: 1
+            })
+        ));
+
+        drop(permit);
+        let permit = queued.await.unwrap().unwrap();
+        assert_eq!(throttler.queue_length(), 0);
+
+        let start = Instant::now();
+        assert!(matches!(
+            throttler.acquire().await,
+            Err(ThrottlingError::QueueTimeout(_))
+        ));
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+        drop(permit);
+        throttler.acquire().await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rate_limit_spaces_requests() {
+        let throttler = DefaultRequestThrottler::builder()
+            .rate_limit(10, 2)
+            .max_queue_wait(Duration::from_millis(250))
+            .build();
+
+        let start = Instant::now();
+        // The burst goes through immediately.
+        throttler.acquire().await.unwrap();
+        throttler.acquire().await.unwrap();
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        throttler.acquire().await.unwrap();
+        assert_eq!(start.elapsed(), Duration::from_millis(100));
+
+        // Requests arriving at once get the next tokens in order.
+        let first = tokio::spawn({
+            let throttler = Arc::clone(&throttler);
+            async move { throttler.acquire().await }
+        });
+        let second = tokio::spawn({
+            let throttler = Arc::clone(&throttler);
+            async move { throttler.acquire().await }
+        });
+        first.await.unwrap().unwrap();
+        second.await.unwrap().unwrap();
+        assert_eq!(start.elapsed(), Duration::from_millis(300));
+
+        // Requests which would have to wait longer than allowed are rejected.
+        let (first, second, third) = futures::join!(
+            throttler.acquire(),
+            throttler.acquire(),
+            throttler.acquire()
+        );
+        first.unwrap();
+        second.unwrap();
+        assert!(matches!(third, Err(ThrottlingError::QueueTimeout(_))));
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,335 @@
+//! Typed results of lightweight transactions (LWT).
+//!
+//! The first column of the response to a conditional statement is the boolean
+//! `[applied]` column. If the statement was not applied, it is followed by the
+//! current values of the row, which [LwtResult] deserializes into a typed row.
+
+use scylla_cql::deserialize::row::{
+    mk_deser_err, mk_typck_err, BuiltinDeserializationErrorKind, BuiltinTypeCheckErrorKind,
+    ColumnIterator, DeserializeRow, RawColumn,
+};
+use scylla_cql::deserialize::value::DeserializeValue;
+use scylla_cql::deserialize::{DeserializationError, TypeCheckError};
+use scylla_cql::frame::response::result::ColumnSpec;
+use thiserror::Error;
+
+use crate::errors::{DbError, ExecutionError, RequestAttemptError, WriteType};
+use crate::response::query_result::{
+    FirstRowError, IntoRowsResultError, MaybeFirstRowError, QueryResult,
+};
+
+/// Name of the column telling whether a conditional statement was applied.
+const APPLIED_COLUMN: &str = "[applied]";
+
+/// The outcome of a lightweight transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LwtResult<T> {
+    /// The condition was met and the statement was applied.
+    Applied,
+
+    /// The condition was not met. Holds the current values of the row,
+    /// or None if the row does not exist.
+    NotApplied(Option<T>),
+}
+
+impl<T> LwtResult<T> {
+    /// Returns true if the statement was applied.
+    pub fn is_applied(&self) -> bool {
+        matches!(self, LwtResult::Applied)
+    }
+}
+
+impl<T> LwtResult<T>
+where
+    T: for<'frame, 'metadata> DeserializeRow<'frame, 'metadata>,
+{
+    /// Reads the outcome of a lightweight transaction from its result.
+    ///
+    /// The columns of the current row, following the `[applied]` column,
+    /// are deserialized into `T`. For a batch, the first row of the result is read.
+    // The error is large because `IntoRowsResultError` gives back the original `QueryResult`.
+    #[expect(clippy::result_large_err)]
+    pub fn from_query_result(result: QueryResult) -> Result<Self, LwtError> {
+        let rows_result = result.into_rows_result()?;
+        let LwtRow(lwt_result) = rows_result.first_row::<LwtRow<T>>()?;
+        Ok(lwt_result)
+    }
+}
+
+/// Reads whether a lightweight transaction was applied from its result,
+/// without deserializing the current values of the row.
+// The error is large because `IntoRowsResultError` gives back the original `QueryResult`.
+#[expect(clippy::result_large_err)]
+pub(crate) fn is_applied(result: QueryResult) -> Result<bool, LwtError> {
+    LwtResult::<SkippedRow>::from_query_result(result).map(|result| result.is_applied())
+}
+
+/// An error returned by [`Session::execute_lwt`](crate::client::session::Session::execute_lwt)
+/// and [LwtResult::from_query_result].
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum LwtError {
+    /// Failed to execute the statement.
+    #[error(transparent)]
+    Execution(#[from] ExecutionError),
+
+    /// The response to the statement was not of Rows kind.
+    #[error(transparent)]
+    IntoRowsResult(#[from] IntoRowsResultError),
+
+    /// The response has no `[applied]` column, or the current row
+    /// failed to type check or deserialize.
+    #[error(transparent)]
+    FirstRow(#[from] FirstRowError),
+}
+
+/// An error returned by [`Session::compare_and_set`](crate::client::session::Session::compare_and_set).
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum CasError {
+    /// Failed to execute the read of the current row.
+    #[error("Failed to read the current row: {0}")]
+    ReadExecution(ExecutionError),
+
+    /// The response to the read was not of Rows kind.
+    #[error("Failed to read the current row: {0}")]
+    ReadIntoRowsResult(IntoRowsResultError),
+
+    /// The current row failed to type check or deserialize.
+    #[error("Failed to read the current row: {0}")]
+    ReadRow(MaybeFirstRowError),
+
+    /// Failed to execute the conditional update.
+    #[error("Failed to execute the conditional update: {0}")]
+    Update(#[from] LwtError),
+
+    /// The row was modified concurrently during each of the attempts.
+    #[error("The row was modified concurrently during each of {0} attempts")]
+    AttemptsExhausted(usize),
+}
+
+/// Options of [`Session::compare_and_set`](crate::client::session::Session::compare_and_set).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CasOptions {
+    max_attempts: usize,
+}
+
+impl CasOptions {
+    /// Creates the default options, allowing up to 10 attempts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximal number of executions of the conditional update.
+    pub fn max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub(crate) fn get_max_attempts(&self) -> usize {
+        self.max_attempts
+    }
+}
+
+impl Default for CasOptions {
+    fn default() -> Self {
+        Self { max_attempts: 10 }
+    }
+}
+
+/// The outcome of [`Session::compare_and_set`](crate::client::session::Session::compare_and_set).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CasOutcome<R> {
+    /// The row was updated. Holds the row as written.
+    Applied(R),
+
+    /// The update function declined to update the row.
+    /// Holds the current row, or None if it does not exist.
+    Aborted(Option<R>),
+}
+
+/// Returns true if the outcome of a lightweight transaction is unknown,
+/// because the coordinator timed out while performing its Paxos round.
+pub(crate) fn is_cas_write_timeout(error: &ExecutionError) -> bool {
+    matches!(
+        error,
+        ExecutionError::LastAttemptError(RequestAttemptError::DbError(
+            DbError::WriteTimeout {
+                write_type: WriteType::Cas,
+                ..
+            },
+            _
+        ))
+    )
+}
+
+/// Deserializes a row of an LWT result: the `[applied]` column
+/// followed by the current values of the row.
+struct LwtRow<T>(LwtResult<T>);
+
+impl<'frame, 'metadata, T> DeserializeRow<'frame, 'metadata> for LwtRow<T>
+where
+    T: DeserializeRow<'frame, 'metadata>,
+{
+    fn type_check(specs: &[ColumnSpec]) -> Result<(), TypeCheckError> {
+        let column_types_iter = || specs.iter().map(|spec| spec.typ().clone().into_owned());
+        let Some((applied, current_row)) = specs
+            .split_first()
+            .filter(|(applied, _)| applied.name() == APPLIED_COLUMN)
+        else {
+            return Err(mk_typck_err::<Self>(
+                column_types_iter(),
+                BuiltinTypeCheckErrorKind::ValuesMissingForColumns {
+                    column_names: vec![APPLIED_COLUMN],
+                },
+            ));
+        };
+        <bool as DeserializeValue>::type_check(applied.typ()).map_err(|err| {
+            mk_typck_err::<Self>(
+                column_types_iter(),
+                BuiltinTypeCheckErrorKind::ColumnTypeCheckFailed {
+                    column_index: 0,
+                    column_name: APPLIED_COLUMN.to_owned(),
+                    err,
+                },
+            )
+        })?;
+        // Cassandra returns only the `[applied]` column if the row does not exist.
+        if !current_row.is_empty() {
+            T::type_check(current_row)?;
+        }
+        Ok(())
+    }
+
+    fn deserialize(
+        mut row: ColumnIterator<'frame, 'metadata>,
+    ) -> Result<Self, DeserializationError> {
+        let applied = row.next().unwrap_or_else(|| {
+            unreachable!("Typecheck should have prevented this scenario! Missing [applied] column")
+        })?;
+        let is_applied = <bool as DeserializeValue>::deserialize(applied.spec.typ(), applied.slice)
+            .map_err(|err| {
+                mk_deser_err::<Self>(
+                    BuiltinDeserializationErrorKind::ColumnDeserializationFailed {
+                        column_index: applied.index,
+                        column_name: APPLIED_COLUMN.to_owned(),
+                        err,
+                    },
+                )
+            })?;
+        if is_applied {
+            return Ok(LwtRow(LwtResult::Applied));
+        }
+
+        // ScyllaDB returns nulls in place of the values of a row which does not exist.
+        let row_exists = row
+            .clone()
+            .any(|column| !matches!(column, Ok(RawColumn { slice: None, .. })));
+        let current_row = if row_exists {
+            Some(T::deserialize(row)?)
+        } else {
+            None
+        };
+        Ok(LwtRow(LwtResult::NotApplied(current_row)))
+    }
+}
+
+/// A row whose columns are skipped instead of being deserialized.
+struct SkippedRow;
+
+impl<'frame, 'metadata> DeserializeRow<'frame, 'metadata> for SkippedRow {
+    fn type_check(_specs: &[ColumnSpec]) -> Result<(), TypeCheckError> {
+        Ok(())
+    }
+
+    fn deserialize(_row: ColumnIterator<'frame, 'metadata>) -> Result<Self, DeserializationError> {
+        Ok(SkippedRow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::{BufMut, Bytes, BytesMut};
+    use scylla_cql::deserialize::row::{ColumnIterator, DeserializeRow};
+    use scylla_cql::deserialize::FrameSlice;
+    use scylla_cql::frame::response::result::{ColumnSpec, ColumnType, NativeType, TableSpec};
+
+    use super::{LwtResult, LwtRow, SkippedRow};
+
+    fn spec(name: &'static str, typ: NativeType) -> ColumnSpec<'static> {
+        ColumnSpec::borrowed(
+            name,
+            ColumnType::Native(typ),
+            TableSpec::borrowed("ks", "t"),
+        )
+    }
+
+    fn serialize_row(columns: &[Option<&[u8]>]) -> Bytes {
+        let mut buf = BytesMut::new();
+        for column in columns {
+            match column {
+                Some(value) => {
+                    buf.put_i32(value.len() as i32);
+                    buf.put_slice(value);
+                }
+                None => buf.put_i32(-1),
+            }
+        }
+        buf.freeze()
+    }
+
+    fn deserialize<T>(
+        specs: &[ColumnSpec<'_>],
+        columns: &[Option<&[u8]>],
+    ) -> Result<LwtResult<T>, String>
+    where
+        T: for<'frame, 'metadata> DeserializeRow<'frame, 'metadata>,
+    {
+        LwtRow::<T>::type_check(specs).map_err(|err| err.to_string())?;
+        let bytes = serialize_row(columns);
+        let iter = ColumnIterator::new(specs, FrameSlice::new(&bytes));
+        LwtRow::<T>::deserialize(iter)
+            .map(|LwtRow(result)| result)
+            .map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn lwt_row_deserialization() {
+        let applied = spec("[applied]", NativeType::Boolean);
+        let value = spec("v", NativeType::Int);
+        let row_specs = [applied.clone(), value];
+
+        assert_eq!(
+            deserialize::<(i32,)>(&row_specs, &[Some(&[1]), None]),
+            Ok(LwtResult::Applied)
+        );
+        assert_eq!(
+            deserialize::<(i32,)>(&row_specs, &[Some(&[0]), Some(&7_i32.to_be_bytes())]),
+            Ok(LwtResult::NotApplied(Some((7,))))
+        );
+        // A row which does not exist, as returned by ScyllaDB and by Cassandra.
+        assert_eq!(
+            deserialize::<(i32,)>(&row_specs, &[Some(&[0]), None]),
+            Ok(LwtResult::NotApplied(None))
+        );
+        assert_eq!(
+            deserialize::<(i32,)>(&[applied], &[Some(&[0])]),
+            Ok(LwtResult::NotApplied(None))
+        );
+
+        // The result of a statement which is not an LWT.
+        assert!(deserialize::<(i32,)>(&[spec("v", NativeType::Int)], &[Some(&[0; 4])]).is_err());
+        // The current row does not match the Rust type.
+        assert!(deserialize::<(String,)>(&row_specs, &[Some(&[0]), None]).is_err());
+
+        // The current row is skipped when only the outcome is needed.
+        assert!(matches!(
+            deserialize::<SkippedRow>(&row_specs, &[Some(&[0]), Some(&7_i32.to_be_bytes())]),
+            Ok(LwtResult::NotApplied(Some(SkippedRow)))
+        ));
+        assert!(matches!(
+            deserialize::<SkippedRow>(&row_specs, &[Some(&[1]), None]),
+            Ok(LwtResult::Applied)
+        ));
+    }
+}
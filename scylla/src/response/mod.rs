@@ -6,8 +6,11 @@
 //! - [QueryResult](query_result::QueryResult) - a result of a CQL QUERY/EXECUTE/BATCH request.
 //! - [QueryRowsResult](query_result::QueryRowsResult) - a result of CQL QUERY/EXECUTE/BATCH
 //!   request that contains some rows, which can be deserialized by the user.
+//! - [LwtResult](lwt::LwtResult) - the outcome of a lightweight transaction, read from
+//!   the `[applied]` column of its result.
 
 mod coordinator;
+pub mod lwt;
 pub mod query_result;
 mod request_response;
 
@@ -1,14 +1,21 @@
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::sync::Arc;
 
+use bytes::Bytes;
 use thiserror::Error;
 use uuid::Uuid;
 
+#[cfg(feature = "arrow-53")]
+use arrow_array_53::RecordBatch;
+#[cfg(feature = "arrow-53")]
+use scylla_cql::deserialize::arrow::ArrowConversionError;
 use scylla_cql::deserialize::result::TypedRowIterator;
 use scylla_cql::deserialize::row::DeserializeRow;
 use scylla_cql::deserialize::{DeserializationError, TypeCheckError};
 use scylla_cql::frame::frame_errors::ResultMetadataAndRowsCountParseError;
 use scylla_cql::frame::response::result::{
-    ColumnSpec, DeserializedMetadataAndRawRows, RawMetadataAndRawRows,
+    ColumnSpec, DeserializedMetadataAndRawRows, RawMetadataAndRawRows, ResultMetadata,
 };
 
 use crate::response::Coordinator;
@@ -60,6 +67,25 @@ impl<'slice, 'spec> ColumnSpecs<'slice, 'spec> {
     }
 }
 
+/// An owned handle to specification of columns returned by the database,
+/// e.g. the current result metadata of a prepared statement, which may be
+/// replaced while the handle is held.
+#[derive(Debug, Clone)]
+pub struct ColumnSpecsGuard {
+    result_metadata: Arc<ResultMetadata<'static>>,
+}
+
+impl ColumnSpecsGuard {
+    pub(crate) fn new(result_metadata: Arc<ResultMetadata<'static>>) -> Self {
+        Self { result_metadata }
+    }
+
+    /// Returns a view over the column specifications.
+    pub fn get(&self) -> ColumnSpecs<'_, 'static> {
+        ColumnSpecs::new(self.result_metadata.col_specs())
+    }
+}
+
 /// Result of a single request to the database. It represents any kind of Result frame.
 ///
 /// The received rows and metadata, which are present if the frame is of Result:Rows kind,
@@ -77,6 +103,7 @@ pub struct QueryResult {
     raw_metadata_and_rows: Option<RawMetadataAndRawRows>,
     tracing_id: Option<Uuid>,
     warnings: Vec<String>,
+    custom_payload: Option<HashMap<String, Bytes>>,
 }
 
 impl QueryResult {
@@ -85,12 +112,14 @@ impl QueryResult {
         raw_rows: Option<RawMetadataAndRawRows>,
         tracing_id: Option<Uuid>,
         warnings: Vec<String>,
+        custom_payload: Option<HashMap<String, Bytes>>,
     ) -> Self {
         Self {
             request_coordinator: Some(request_coordinator),
             raw_metadata_and_rows: raw_rows,
             tracing_id,
             warnings,
+            custom_payload,
         }
     }
 
@@ -106,12 +135,14 @@ impl QueryResult {
         raw_rows: Option<RawMetadataAndRawRows>,
         tracing_id: Option<Uuid>,
         warnings: Vec<String>,
+        custom_payload: Option<HashMap<String, Bytes>>,
     ) -> Self {
         Self {
             request_coordinator: None,
             raw_metadata_and_rows: raw_rows,
             tracing_id,
             warnings,
+            custom_payload,
         }
     }
 
@@ -123,6 +154,7 @@ impl QueryResult {
             raw_metadata_and_rows: None,
             tracing_id: None,
             warnings: Vec::new(),
+            custom_payload: None,
         }
     }
 
@@ -150,6 +182,24 @@ impl QueryResult {
         self.tracing_id
     }
 
+    /// Custom payload sent back by the database along with the response, if any.
+    #[inline]
+    pub fn custom_payload(&self) -> Option<&HashMap<String, Bytes>> {
+        self.custom_payload.as_ref()
+    }
+
+    /// Returns a bool indicating that the server reported a change of the result
+    /// metadata of the executed prepared statement (e.g. after the table was altered).
+    ///
+    /// The driver has already updated the cached result metadata of the statement,
+    /// see [`PreparedStatement::get_current_result_set_col_specs`](crate::statement::prepared::PreparedStatement::get_current_result_set_col_specs).
+    #[inline]
+    pub fn result_metadata_changed(&self) -> bool {
+        self.raw_metadata_and_rows
+            .as_ref()
+            .is_some_and(|raw| raw.new_metadata_id().is_some())
+    }
+
     /// Returns a bool indicating the current response is of Rows type.
     #[inline]
     pub fn is_rows(&self) -> bool {
@@ -215,6 +265,7 @@ impl QueryResult {
         };
         let tracing_id = self.tracing_id;
         let warnings = self.warnings;
+        let custom_payload = self.custom_payload;
         let request_coordinator = self.request_coordinator;
 
         let raw_rows_with_metadata = raw_metadata_and_rows.deserialize_metadata()?;
@@ -223,6 +274,7 @@ impl QueryResult {
             raw_rows_with_metadata,
             warnings,
             tracing_id,
+            custom_payload,
         })
     }
 }
@@ -264,6 +316,7 @@ pub struct QueryRowsResult {
     raw_rows_with_metadata: DeserializedMetadataAndRawRows,
     tracing_id: Option<Uuid>,
     warnings: Vec<String>,
+    custom_payload: Option<HashMap<String, Bytes>>,
 }
 
 impl QueryRowsResult {
@@ -279,6 +332,12 @@ impl QueryRowsResult {
         self.tracing_id
     }
 
+    /// Custom payload sent back by the database along with the response, if any.
+    #[inline]
+    pub fn custom_payload(&self) -> Option<&HashMap<String, Bytes>> {
+        self.custom_payload.as_ref()
+    }
+
     /// The node+shard that served the request.
     #[inline]
     pub fn request_coordinator(&self) -> &Coordinator {
@@ -305,6 +364,15 @@ impl QueryRowsResult {
         ColumnSpecs::new(self.raw_rows_with_metadata.metadata().col_specs())
     }
 
+    /// Converts the received rows into an Arrow [RecordBatch].
+    ///
+    /// The rows are decoded directly from the response, see
+    /// [crate::deserialize::arrow] for the mapping of CQL types to Arrow types.
+    #[cfg(feature = "arrow-53")]
+    pub fn to_record_batch(&self) -> Result<RecordBatch, ArrowConversionError> {
+        scylla_cql::deserialize::arrow::record_batch_from_rows(&self.raw_rows_with_metadata)
+    }
+
     /// Returns an iterator over the received rows.
     ///
     /// Returns an error if the rows in the response are of incorrect type.
@@ -394,6 +462,7 @@ impl QueryRowsResult {
             tracing_id,
             warnings,
             request_coordinator,
+            custom_payload: _,
         } = self;
 
         (
@@ -547,7 +616,8 @@ mod tests {
         // Check tracing ID
         for tracing_id in [None, Some(Uuid::from_u128(0x_feed_dead))] {
             for raw_rows in [None, Some(sample_raw_rows(7, 6))] {
-                let qr = QueryResult::new_with_unknown_coordinator(raw_rows, tracing_id, vec![]);
+                let qr =
+                    QueryResult::new_with_unknown_coordinator(raw_rows, tracing_id, vec![], None);
                 assert_eq!(qr.tracing_id(), tracing_id);
             }
         }
@@ -559,15 +629,33 @@ mod tests {
                 raw_rows,
                 None,
                 warnings.iter().copied().map(String::from).collect(),
+                None,
             );
             assert_eq!(qr.warnings().collect_vec(), warnings);
         }
 
+        // Check custom payload
+        for raw_rows in [None, Some(sample_raw_rows(7, 6))] {
+            let is_rows = raw_rows.is_some();
+            let payload = HashMap::from([("key".to_owned(), Bytes::from_static(b"value"))]);
+            let qr = QueryResult::new_with_unknown_coordinator(
+                raw_rows,
+                None,
+                Vec::new(),
+                Some(payload.clone()),
+            );
+            assert_eq!(qr.custom_payload(), Some(&payload));
+            if is_rows {
+                let rows_result = qr.into_rows_result().unwrap();
+                assert_eq!(rows_result.custom_payload(), Some(&payload));
+            }
+        }
+
         // Check col specs
         {
             // Not RESULT::Rows response -> no column specs
             {
-                let rqr = QueryResult::new_with_unknown_coordinator(None, None, Vec::new());
+                let rqr = QueryResult::new_with_unknown_coordinator(None, None, Vec::new(), None);
                 let qr = rqr.into_rows_result();
                 assert_matches!(qr, Err(IntoRowsResultError::ResultNotRows(_)));
             }
@@ -578,7 +666,8 @@ mod tests {
                 let metadata = sample_result_metadata(n);
                 let rr = RawMetadataAndRawRows::new_for_test(None, Some(metadata), false, 0, &[])
                     .unwrap();
-                let rqr = QueryResult::new_with_unknown_coordinator(Some(rr), None, Vec::new());
+                let rqr =
+                    QueryResult::new_with_unknown_coordinator(Some(rr), None, Vec::new(), None);
                 let qr = rqr.into_rows_result().unwrap();
                 let column_specs = qr.column_specs();
                 assert_eq!(column_specs.len(), n);
@@ -622,7 +711,7 @@ mod tests {
         {
             // Not RESULT::Rows
             {
-                let rqr = QueryResult::new_with_unknown_coordinator(None, None, Vec::new());
+                let rqr = QueryResult::new_with_unknown_coordinator(None, None, Vec::new(), None);
                 let qr = rqr.into_rows_result();
                 assert_matches!(qr, Err(IntoRowsResultError::ResultNotRows(_)));
             }
@@ -630,7 +719,8 @@ mod tests {
             // RESULT::Rows with 0 rows
             {
                 let rr = sample_raw_rows(1, 0);
-                let rqr = QueryResult::new_with_unknown_coordinator(Some(rr), None, Vec::new());
+                let rqr =
+                    QueryResult::new_with_unknown_coordinator(Some(rr), None, Vec::new(), None);
                 assert_matches!(rqr.result_not_rows(), Err(ResultNotRowsError));
 
                 let qr = rqr.into_rows_result().unwrap();
@@ -672,10 +762,18 @@ mod tests {
             {
                 let rr_good_data = sample_raw_rows(2, 1);
                 let rr_bad_data = sample_raw_rows_invalid_bytes(2, 1);
-                let rqr_good_data =
-                    QueryResult::new_with_unknown_coordinator(Some(rr_good_data), None, Vec::new());
-                let rqr_bad_data =
-                    QueryResult::new_with_unknown_coordinator(Some(rr_bad_data), None, Vec::new());
+                let rqr_good_data = QueryResult::new_with_unknown_coordinator(
+                    Some(rr_good_data),
+                    None,
+                    Vec::new(),
+                    None,
+                );
+                let rqr_bad_data = QueryResult::new_with_unknown_coordinator(
+                    Some(rr_bad_data),
+                    None,
+                    Vec::new(),
+                    None,
+                );
 
                 for rqr in [&rqr_good_data, &rqr_bad_data] {
                     assert_matches!(rqr.result_not_rows(), Err(ResultNotRowsError));
@@ -735,7 +833,8 @@ mod tests {
             // RESULT::Rows with 2 rows
             {
                 let rr = sample_raw_rows(2, 2);
-                let rqr = QueryResult::new_with_unknown_coordinator(Some(rr), None, Vec::new());
+                let rqr =
+                    QueryResult::new_with_unknown_coordinator(Some(rr), None, Vec::new(), None);
                 assert_matches!(rqr.result_not_rows(), Err(ResultNotRowsError));
 
                 let qr = rqr.into_rows_result().unwrap();
@@ -775,11 +874,48 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "arrow-53")]
+    #[test]
+    fn test_to_record_batch() {
+        use arrow_array_53::cast::AsArray;
+        use arrow_array_53::types::Float32Type;
+        use arrow_array_53::Array;
+
+        let metadata =
+            ResultMetadata::new_for_test(3, column_spec_infinite_iter().take(3).collect());
+        let mut bytes = BytesMut::new();
+        for cell in [
+            Some(&b"foo"[..]),
+            Some(&[1]),
+            Some(&1.5_f32.to_be_bytes()),
+            None,
+            Some(&[0]),
+            None,
+        ] {
+            types::write_bytes_opt(cell, &mut bytes).unwrap();
+        }
+        let rr =
+            RawMetadataAndRawRows::new_for_test(None, Some(metadata), false, 2, &bytes).unwrap();
+        let qr = QueryResult::new_with_unknown_coordinator(Some(rr), None, Vec::new(), None)
+            .into_rows_result()
+            .unwrap();
+
+        let batch = qr.to_record_batch().unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.schema().field(0).name(), "col_0");
+        assert_eq!(batch.column(0).as_string::<i32>().value(0), "foo");
+        assert!(batch.column(0).is_null(1));
+        assert!(batch.column(1).as_boolean().value(0));
+        assert!(!batch.column(1).as_boolean().value(1));
+        assert_eq!(batch.column(2).as_primitive::<Float32Type>().value(0), 1.5);
+        assert!(batch.column(2).is_null(1));
+    }
+
     #[test]
     fn test_query_result_returns_self_if_not_rows() {
         // Check tracing ID
         for tracing_id in [None, Some(Uuid::from_u128(0x_feed_dead))] {
-            let qr = QueryResult::new_with_unknown_coordinator(None, tracing_id, vec![]);
+            let qr = QueryResult::new_with_unknown_coordinator(None, tracing_id, vec![], None);
             let err = qr.into_rows_result().unwrap_err();
             match err {
                 IntoRowsResultError::ResultNotRows(query_result) => {
@@ -798,6 +934,7 @@ mod tests {
                 None,
                 None,
                 warnings.iter().copied().map(String::from).collect(),
+                None,
             );
             let err = qr.into_rows_result().unwrap_err();
             match err {
@@ -11,14 +11,13 @@ use crate::errors::RequestAttemptError;
 use crate::frame::response::{self, result};
 use crate::response::query_result::QueryResult;
 use crate::response::Coordinator;
-use crate::statement::prepared::PreparedStatement;
+use crate::statement::prepared::{CachedResultMetadata, PreparedStatement};
 use crate::statement::Statement;
 
 pub(crate) struct QueryResponse {
     pub(crate) response: Response,
     pub(crate) tracing_id: Option<Uuid>,
     pub(crate) warnings: Vec<String>,
-    // This is not exposed to user (yet?)
     pub(crate) custom_payload: Option<HashMap<String, Bytes>>,
 }
 
@@ -27,6 +26,7 @@ pub(crate) struct NonErrorQueryResponse {
     pub(crate) response: NonErrorResponse,
     pub(crate) tracing_id: Option<Uuid>,
     pub(crate) warnings: Vec<String>,
+    pub(crate) custom_payload: Option<HashMap<String, Bytes>>,
 }
 
 impl QueryResponse {
@@ -37,6 +37,7 @@ impl QueryResponse {
             response: self.response.into_non_error_response()?,
             tracing_id: self.tracing_id,
             warnings: self.warnings,
+            custom_payload: self.custom_payload,
         })
     }
 }
@@ -64,6 +65,7 @@ impl NonErrorQueryResponse {
             response,
             tracing_id,
             warnings,
+            custom_payload,
         } = self;
         let (raw_rows, paging_state_response) = match response {
             NonErrorResponse::Result(result::Result::Rows((rs, paging_state_response))) => {
@@ -79,8 +81,15 @@ impl NonErrorQueryResponse {
 
         Ok((
             match request_coordinator {
-                Some(coordinator) => QueryResult::new(coordinator, raw_rows, tracing_id, warnings),
-                None => QueryResult::new_with_unknown_coordinator(raw_rows, tracing_id, warnings),
+                Some(coordinator) => {
+                    QueryResult::new(coordinator, raw_rows, tracing_id, warnings, custom_payload)
+                }
+                None => QueryResult::new_with_unknown_coordinator(
+                    raw_rows,
+                    tracing_id,
+                    warnings,
+                    custom_payload,
+                ),
             },
             paging_state_response,
         ))
@@ -188,8 +197,12 @@ impl RawPreparedStatement<'_> {
             prepared_response.id,
             is_lwt,
             prepared_response.prepared_metadata,
-            Arc::new(prepared_response.result_metadata),
+            CachedResultMetadata::new_shared(
+                Arc::new(prepared_response.result_metadata),
+                prepared_response.result_metadata_id,
+            ),
             statement.contents.clone(),
+            statement.get_keyspace().map(ToOwned::to_owned),
             statement.get_validated_page_size(),
             statement.config.clone(),
         );
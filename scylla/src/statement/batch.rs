@@ -1,6 +1,9 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use bytes::Bytes;
+
 use crate::client::execution_profile::ExecutionProfileHandle;
 use crate::observability::history::HistoryListener;
 use crate::policies::load_balancing::LoadBalancingPolicy;
@@ -21,6 +24,7 @@ pub struct Batch {
 
     pub statements: Vec<BatchStatement>,
     batch_type: BatchType,
+    keyspace: Option<String>,
 }
 
 impl Batch {
@@ -39,6 +43,7 @@ impl Batch {
         Batch {
             batch_type,
             config,
+            keyspace: batch.keyspace.clone(),
             ..Default::default()
         }
     }
@@ -123,6 +128,53 @@ impl Batch {
         self.config.timestamp
     }
 
+    /// Sets the custom payload sent along with this batch.
+    ///
+    /// The payload is a map of opaque byte values, interpreted by custom
+    /// server-side query handlers. Entries set here override the ones with
+    /// the same key set on the execution profile.
+    pub fn set_custom_payload(&mut self, custom_payload: Option<HashMap<String, Bytes>>) {
+        self.config.custom_payload = custom_payload.map(Arc::new);
+    }
+
+    /// Gets the custom payload sent along with this batch.
+    pub fn get_custom_payload(&self) -> Option<&HashMap<String, Bytes>> {
+        self.config.custom_payload.as_deref()
+    }
+
+    /// Sets the keyspace in which this batch is executed, overriding
+    /// the keyspace set on the session with `USE`.
+    ///
+    /// The keyspace applies to the unprepared statements in the batch
+    /// which do not set their own, and is used when the driver prepares
+    /// them. Keyspaces set on the individual statements are not sent in
+    /// BATCH requests.
+    /// This requires protocol v5; executing the batch on a connection
+    /// using an older protocol version fails.
+    pub fn set_keyspace(&mut self, keyspace: Option<String>) {
+        self.keyspace = keyspace;
+    }
+
+    /// Gets the keyspace in which this batch is executed, if it was set.
+    pub fn get_keyspace(&self) -> Option<&str> {
+        self.keyspace.as_deref()
+    }
+
+    /// Sets the keyspace of this batch on its unprepared statements which
+    /// do not have their own keyspace, so that they are prepared in it.
+    pub(crate) fn apply_keyspace_to_statements(&mut self) {
+        let Some(keyspace) = self.keyspace.as_deref() else {
+            return;
+        };
+        for statement in &mut self.statements {
+            if let BatchStatement::Query(query) = statement {
+                if query.get_keyspace().is_none() {
+                    query.set_keyspace(Some(keyspace.to_owned()));
+                }
+            }
+        }
+    }
+
     /// Set the retry policy for this batch, overriding the one from execution profile if not None.
     #[inline]
     pub fn set_retry_policy(&mut self, retry_policy: Option<Arc<dyn RetryPolicy>>) {
@@ -186,6 +238,7 @@ impl Default for Batch {
             statements: Vec::new(),
             batch_type: BatchType::Logged,
             config: Default::default(),
+            keyspace: None,
         }
     }
 }
@@ -377,3 +430,31 @@ pub(crate) mod batch_values {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Batch, BatchStatement, BatchType};
+    use crate::statement::unprepared::Statement;
+
+    #[test]
+    fn batch_keyspace_does_not_override_statement_keyspace() {
+        let mut own_keyspace = Statement::new("INSERT INTO t (a) VALUES (1)");
+        own_keyspace.set_keyspace(Some("ks_statement".to_owned()));
+
+        let mut batch = Batch::new(BatchType::Logged);
+        batch.append_statement(own_keyspace);
+        batch.append_statement("INSERT INTO t (a) VALUES (2)");
+        batch.set_keyspace(Some("ks_batch".to_owned()));
+        batch.apply_keyspace_to_statements();
+
+        let keyspaces: Vec<Option<&str>> = batch
+            .statements
+            .iter()
+            .map(|statement| match statement {
+                BatchStatement::Query(query) => query.get_keyspace(),
+                BatchStatement::PreparedStatement(_) => unreachable!(),
+            })
+            .collect();
+        assert_eq!(keyspaces, [Some("ks_statement"), Some("ks_batch")]);
+    }
+}
@@ -0,0 +1,479 @@
+//! Statements checked against the database schema at compile time.
+//!
+//! A [CheckedStatement] is created by the [`query!`](crate::query) macro, which
+//! parses the CQL statement, resolves the tables and columns it refers to in
+//! a schema file, and verifies that the bound values are of compatible types.
+//! The statement carries the bound values and knows the Rust type of the
+//! returned rows, so executing it requires no further type annotations.
+//!
+//! Since the schema file may get out of date, the CQL types derived from it are
+//! verified once more against the metadata returned by the database when the
+//! statement is prepared.
+
+use std::fmt;
+use std::hash::BuildHasher;
+use std::marker::PhantomData;
+
+use scylla_cql::deserialize::row::DeserializeRow;
+use scylla_cql::deserialize::DeserializationError;
+use scylla_cql::frame::response::result::{CollectionType, ColumnType};
+use scylla_cql::serialize::row::SerializeRow;
+use thiserror::Error;
+
+use crate::client::caching_session::CachingSession;
+use crate::errors::{ExecutionError, PrepareError};
+use crate::response::query_result::{
+    IntoRowsResultError, MaybeFirstRowError, QueryResult, RowsError,
+};
+use crate::statement::prepared::PreparedStatement;
+use crate::statement::unprepared::Statement;
+
+/// A statement checked by the [`query!`](crate::query) macro, together with
+/// its bound values.
+///
+/// `V` is the type of the bound values (a tuple), and `R` is the type of the
+/// rows returned by the statement (also a tuple, `()` for statements other
+/// than `SELECT`).
+///
+/// The statement is executed through a [CachingSession], so it is prepared
+/// only once. Its execution options can be adjusted with [CheckedStatement::statement_mut].
+pub struct CheckedStatement<V, R> {
+    statement: Statement,
+    values: V,
+    bind_types: fn() -> Vec<ColumnType<'static>>,
+    result_types: Option<fn() -> Vec<ColumnType<'static>>>,
+    _row: PhantomData<fn() -> R>,
+}
+
+impl<V, R> CheckedStatement<V, R> {
+    /// Creates a checked statement.
+    ///
+    /// Implementation detail of the `query!` macro.
+    #[doc(hidden)]
+    pub fn new(
+        statement: impl Into<Statement>,
+        values: V,
+        bind_types: fn() -> Vec<ColumnType<'static>>,
+        result_types: Option<fn() -> Vec<ColumnType<'static>>>,
+    ) -> Self {
+        Self {
+            statement: statement.into(),
+            values,
+            bind_types,
+            result_types,
+            _row: PhantomData,
+        }
+    }
+
+    /// Returns the underlying statement.
+    pub fn statement(&self) -> &Statement {
+        &self.statement
+    }
+
+    /// Returns the underlying statement, which allows to change its execution
+    /// options, e.g. consistency or page size.
+    pub fn statement_mut(&mut self) -> &mut Statement {
+        &mut self.statement
+    }
+
+    /// Returns the bound values.
+    pub fn values(&self) -> &V {
+        &self.values
+    }
+
+    /// Returns the CQL types of the bind markers, as resolved from the schema file.
+    pub fn bind_types(&self) -> Vec<ColumnType<'static>> {
+        (self.bind_types)()
+    }
+
+    /// Returns the CQL types of the returned columns, as resolved from the schema file,
+    /// or `None` if the statement does not return rows.
+    pub fn result_types(&self) -> Option<Vec<ColumnType<'static>>> {
+        self.result_types.map(|result_types| result_types())
+    }
+
+    /// Verifies that the metadata of the prepared statement matches the types
+    /// resolved from the schema file.
+    ///
+    /// Frozenness of the types is not compared. User defined types are compared
+    /// by name and fields; their keyspace is compared only if it was known
+    /// from the schema file.
+    pub fn verify(&self, prepared: &PreparedStatement) -> Result<(), SchemaMismatchError> {
+        let bind_types = self.bind_types();
+        let variables = prepared.get_variable_col_specs();
+        if variables.len() != bind_types.len() {
+            return Err(SchemaMismatchError::BindMarkerCount {
+                expected: bind_types.len(),
+                actual: variables.len(),
+            });
+        }
+        for (index, (expected, spec)) in bind_types.iter().zip(variables.iter()).enumerate() {
+            if !types_match(expected, spec.typ()) {
+                return Err(SchemaMismatchError::BindMarkerType {
+                    index,
+                    name: spec.name().to_owned(),
+                    expected: expected.clone(),
+                    actual: spec.typ().clone().into_owned(),
+                });
+            }
+        }
+
+        if let Some(result_types) = self.result_types() {
+            let columns = prepared.get_result_set_col_specs();
+            // The result metadata might have been skipped by the database.
+            if columns.len() == 0 {
+                return Ok(());
+            }
+            if columns.len() != result_types.len() {
+                return Err(SchemaMismatchError::ResultColumnCount {
+                    expected: result_types.len(),
+                    actual: columns.len(),
+                });
+            }
+            for (index, (expected, spec)) in result_types.iter().zip(columns.iter()).enumerate() {
+                if !types_match(expected, spec.typ()) {
+                    return Err(SchemaMismatchError::ResultColumnType {
+                        index,
+                        name: spec.name().to_owned(),
+                        expected: expected.clone(),
+                        actual: spec.typ().clone().into_owned(),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Prepares the statement (or fetches it from the session's cache),
+    /// and verifies its metadata with [CheckedStatement::verify].
+    pub async fn prepare<S>(
+        &self,
+        session: &CachingSession<S>,
+    ) -> Result<PreparedStatement, CheckedStatementError>
+    where
+        S: BuildHasher + Clone,
+    {
+        let prepared = session.add_prepared_statement(&self.statement).await?;
+        self.verify(&prepared)?;
+        Ok(prepared)
+    }
+
+    /// Executes the statement with the bound values, without paging.
+    pub async fn execute<S>(
+        &self,
+        session: &CachingSession<S>,
+    ) -> Result<QueryResult, CheckedStatementError>
+    where
+        V: SerializeRow,
+        S: BuildHasher + Clone,
+    {
+        let prepared = self.prepare(session).await?;
+        let result = session
+            .get_session()
+            .execute_unpaged(&prepared, &self.values)
+            .await?;
+        Ok(result)
+    }
+
+    /// Executes the statement and returns all the returned rows.
+    pub async fn rows<S>(
+        &self,
+        session: &CachingSession<S>,
+    ) -> Result<Vec<R>, CheckedStatementError>
+    where
+        V: SerializeRow,
+        R: for<'frame, 'metadata> DeserializeRow<'frame, 'metadata>,
+        S: BuildHasher + Clone,
+    {
+        let rows_result = self.execute(session).await?.into_rows_result()?;
+        let rows = rows_result.rows::<R>()?.collect::<Result<_, _>>()?;
+        Ok(rows)
+    }
+
+    /// Executes the statement and returns the first returned row, if any.
+    pub async fn maybe_first_row<S>(
+        &self,
+        session: &CachingSession<S>,
+    ) -> Result<Option<R>, CheckedStatementError>
+    where
+        V: SerializeRow,
+        R: for<'frame, 'metadata> DeserializeRow<'frame, 'metadata>,
+        S: BuildHasher + Clone,
+    {
+        let rows_result = self.execute(session).await?.into_rows_result()?;
+        Ok(rows_result.maybe_first_row::<R>()?)
+    }
+}
+
+impl<V: fmt::Debug, R> fmt::Debug for CheckedStatement<V, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CheckedStatement")
+            .field("contents", &self.statement.contents)
+            .field("values", &self.values)
+            .finish()
+    }
+}
+
+// Compares a type resolved from the schema file with the type reported by the database.
+fn types_match(expected: &ColumnType, actual: &ColumnType) -> bool {
+    match (expected, actual) {
+        (ColumnType::Native(expected), ColumnType::Native(actual)) => expected == actual,
+        (
+            ColumnType::Collection { typ: expected, .. },
+            ColumnType::Collection { typ: actual, .. },
+        ) => match (expected, actual) {
+            (CollectionType::List(expected), CollectionType::List(actual))
+            | (CollectionType::Set(expected), CollectionType::Set(actual)) => {
+                types_match(expected, actual)
+            }
+            (
+                CollectionType::Map(expected_key, expected_value),
+                CollectionType::Map(actual_key, actual_value),
+            ) => types_match(expected_key, actual_key) && types_match(expected_value, actual_value),
+            _ => false,
+        },
+        (
+            ColumnType::Vector {
+                typ: expected,
+                dimensions: expected_dimensions,
+            },
+            ColumnType::Vector {
+                typ: actual,
+                dimensions: actual_dimensions,
+            },
+        ) => expected_dimensions == actual_dimensions && types_match(expected, actual),
+        (
+            ColumnType::UserDefinedType {
+                definition: expected,
+                ..
+            },
+            ColumnType::UserDefinedType {
+                definition: actual, ..
+            },
+        ) => {
+            expected.name == actual.name
+                && (expected.keyspace.is_empty() || expected.keyspace == actual.keyspace)
+                && expected.field_types.len() == actual.field_types.len()
+                && expected
+                    .field_types
+                    .iter()
+                    .zip(actual.field_types.iter())
+                    .all(
+                        |((expected_name, expected_type), (actual_name, actual_type))| {
+                            expected_name == actual_name && types_match(expected_type, actual_type)
+                        },
+                    )
+        }
+        (ColumnType::Tuple(expected), ColumnType::Tuple(actual)) => {
+            expected.len() == actual.len()
+                && expected
+                    .iter()
+                    .zip(actual.iter())
+                    .all(|(expected, actual)| types_match(expected, actual))
+        }
+        _ => false,
+    }
+}
+
+/// The metadata of a prepared [CheckedStatement] does not match the schema
+/// file the statement was checked against.
+#[derive(Error, Debug, Clone)]
+#[non_exhaustive]
+pub enum SchemaMismatchError {
+    /// The number of bind markers differs.
+    #[error("The statement has {actual} bind markers, but {expected} were expected from the schema file")]
+    BindMarkerCount {
+        /// The number of bind markers according to the schema file.
+        expected: usize,
+        /// The number of bind markers reported by the database.
+        actual: usize,
+    },
+
+    /// The type of a bind marker differs.
+    #[error("Bind marker {index} ({name}) is of type {actual:?}, but {expected:?} was expected from the schema file")]
+    BindMarkerType {
+        /// Index of the bind marker.
+        index: usize,
+        /// Name of the bind marker, as reported by the database.
+        name: String,
+        /// The type according to the schema file.
+        expected: ColumnType<'static>,
+        /// The type reported by the database.
+        actual: ColumnType<'static>,
+    },
+
+    /// The number of returned columns differs.
+    #[error(
+        "The statement returns {actual} columns, but {expected} were expected from the schema file"
+    )]
+    ResultColumnCount {
+        /// The number of columns according to the schema file.
+        expected: usize,
+        /// The number of columns reported by the database.
+        actual: usize,
+    },
+
+    /// The type of a returned column differs.
+    #[error("Returned column {index} ({name}) is of type {actual:?}, but {expected:?} was expected from the schema file")]
+    ResultColumnType {
+        /// Index of the column.
+        index: usize,
+        /// Name of the column, as reported by the database.
+        name: String,
+        /// The type according to the schema file.
+        expected: ColumnType<'static>,
+        /// The type reported by the database.
+        actual: ColumnType<'static>,
+    },
+}
+
+/// An error returned by the methods of [CheckedStatement].
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum CheckedStatementError {
+    /// Failed to prepare the statement.
+    #[error(transparent)]
+    Prepare(#[from] PrepareError),
+
+    /// The prepared statement does not match the schema file.
+    #[error(transparent)]
+    SchemaMismatch(#[from] SchemaMismatchError),
+
+    /// Failed to execute the statement.
+    #[error(transparent)]
+    Execution(#[from] ExecutionError),
+
+    /// The response was not of Rows kind.
+    #[error(transparent)]
+    IntoRowsResult(#[from] IntoRowsResultError),
+
+    /// Failed to type check the returned rows.
+    #[error(transparent)]
+    Rows(#[from] RowsError),
+
+    /// Failed to type check or deserialize the first returned row.
+    #[error(transparent)]
+    MaybeFirstRow(#[from] MaybeFirstRowError),
+
+    /// Failed to deserialize a returned row.
+    #[error(transparent)]
+    Deserialization(#[from] DeserializationError),
+}
+
+/// ```
+///
+/// let statement = scylla::query!(
+///     schema = "tests/integration/macros/schema.cql",
+///     "SELECT ck, value FROM query_macro WHERE pk = ?",
+///     1
+/// );
+/// let _: &scylla::statement::checked::CheckedStatement<_, (String, Option<i64>)> = &statement;
+/// ```
+fn _test_query_accepts_valid_statement() {}
+
+/// ```compile_fail
+///
+/// scylla::query!(
+///     schema = "tests/integration/macros/schema.cql",
+///     "SELECT ck, no_such_column FROM query_macro WHERE pk = ?",
+///     1
+/// );
+/// ```
+fn _test_query_unknown_column() {}
+
+/// ```compile_fail
+///
+/// scylla::query!(
+///     schema = "tests/integration/macros/schema.cql",
+///     "SELECT ck FROM no_such_table WHERE pk = ?",
+///     1
+/// );
+/// ```
+fn _test_query_unknown_table() {}
+
+/// ```compile_fail
+///
+/// scylla::query!(
+///     schema = "tests/integration/macros/schema.cql",
+///     "SELECT ck FROM query_macro WHERE pk = ?",
+///     "not an int"
+/// );
+/// ```
+fn _test_query_wrong_bind_type() {}
+
+/// ```compile_fail
+///
+/// scylla::query!(
+///     schema = "tests/integration/macros/schema.cql",
+///     "SELECT ck FROM query_macro WHERE pk = ? AND ck = ?",
+///     1
+/// );
+/// ```
+fn _test_query_wrong_value_count() {}
+
+/// ```compile_fail
+///
+/// let statement = scylla::query!(
+///     schema = "tests/integration/macros/schema.cql",
+///     "SELECT ck, value FROM query_macro WHERE pk = ?",
+///     1
+/// );
+/// // `value` is not a part of the primary key, so it may be null.
+/// let _: &scylla::statement::checked::CheckedStatement<_, (String, i64)> = &statement;
+/// ```
+fn _test_query_result_type() {}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::sync::Arc;
+
+    use scylla_cql::frame::response::result::{
+        CollectionType, ColumnType, NativeType, UserDefinedType,
+    };
+
+    use super::types_match;
+
+    fn udt(keyspace: &'static str, field_type: NativeType) -> ColumnType<'static> {
+        ColumnType::UserDefinedType {
+            frozen: true,
+            definition: Arc::new(UserDefinedType {
+                name: Cow::Borrowed("address"),
+                keyspace: Cow::Borrowed(keyspace),
+                field_types: vec![(Cow::Borrowed("street"), ColumnType::Native(field_type))],
+            }),
+        }
+    }
+
+    #[test]
+    fn test_types_match() {
+        let list = |frozen| ColumnType::Collection {
+            frozen,
+            typ: CollectionType::List(Box::new(ColumnType::Native(NativeType::Int))),
+        };
+        assert!(types_match(&list(false), &list(true)));
+        assert!(!types_match(
+            &list(false),
+            &ColumnType::Native(NativeType::Int)
+        ));
+
+        // The keyspace of a UDT is compared only if known from the schema file.
+        assert!(types_match(
+            &udt("", NativeType::Text),
+            &udt("ks", NativeType::Text)
+        ));
+        assert!(types_match(
+            &udt("ks", NativeType::Text),
+            &udt("ks", NativeType::Text)
+        ));
+        assert!(!types_match(
+            &udt("other", NativeType::Text),
+            &udt("ks", NativeType::Text)
+        ));
+        assert!(!types_match(
+            &udt("ks", NativeType::Ascii),
+            &udt("ks", NativeType::Text)
+        ));
+    }
+}
@@ -5,8 +5,9 @@
 //! - PreparedStatement,
 //! - Batch.
 
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
+use bytes::Bytes;
 use thiserror::Error;
 
 use crate::client::execution_profile::ExecutionProfileHandle;
@@ -15,6 +16,7 @@ use crate::policies::load_balancing::LoadBalancingPolicy;
 use crate::policies::retry::RetryPolicy;
 
 pub mod batch;
+pub mod checked;
 pub mod prepared;
 pub mod unprepared;
 
@@ -35,6 +37,7 @@ pub(crate) struct StatementConfig {
     pub(crate) tracing: bool,
     pub(crate) timestamp: Option<i64>,
     pub(crate) request_timeout: Option<Duration>,
+    pub(crate) custom_payload: Option<Arc<HashMap<String, Bytes>>>,
 
     pub(crate) history_listener: Option<Arc<dyn HistoryListener>>,
 
@@ -49,6 +52,29 @@ impl StatementConfig {
     pub(crate) fn determine_consistency(&self, default_consistency: Consistency) -> Consistency {
         self.consistency.unwrap_or(default_consistency)
     }
+
+    /// Determines the custom payload of a request.
+    /// Entries set on the statement take precedence over the ones
+    /// set on the execution profile.
+    #[must_use]
+    pub(crate) fn determine_custom_payload(
+        &self,
+        profile_custom_payload: Option<&Arc<HashMap<String, Bytes>>>,
+    ) -> Option<Arc<HashMap<String, Bytes>>> {
+        match (profile_custom_payload, &self.custom_payload) {
+            (None, None) => None,
+            (Some(payload), None) | (None, Some(payload)) => Some(Arc::clone(payload)),
+            (Some(profile_payload), Some(statement_payload)) => {
+                let mut payload = HashMap::clone(profile_payload);
+                payload.extend(
+                    statement_payload
+                        .iter()
+                        .map(|(key, value)| (key.clone(), value.clone())),
+                );
+                Some(Arc::new(payload))
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Error)]
@@ -101,3 +127,42 @@ impl From<PageSize> for i32 {
         page_size.inner()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use bytes::Bytes;
+
+    use super::StatementConfig;
+
+    #[test]
+    fn custom_payload_statement_entries_take_precedence() {
+        let payload = |entries: &[(&str, &'static [u8])]| {
+            entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), Bytes::from_static(value)))
+                .collect::<HashMap<_, _>>()
+        };
+        let profile_payload = Arc::new(payload(&[("a", b"profile"), ("b", b"profile")]));
+
+        let mut config = StatementConfig::default();
+        assert_eq!(config.determine_custom_payload(None), None);
+
+        let determined = config.determine_custom_payload(Some(&profile_payload));
+        assert!(Arc::ptr_eq(determined.as_ref().unwrap(), &profile_payload));
+
+        config.custom_payload = Some(Arc::new(payload(&[("b", b"statement")])));
+        assert_eq!(
+            config.determine_custom_payload(None).as_deref(),
+            Some(&payload(&[("b", b"statement")]))
+        );
+        assert_eq!(
+            config
+                .determine_custom_payload(Some(&profile_payload))
+                .as_deref(),
+            Some(&payload(&[("a", b"profile"), ("b", b"statement")]))
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use arc_swap::ArcSwap;
 use bytes::{Bytes, BytesMut};
 use scylla_cql::frame::response::result::{
     ColumnSpec, PartitionKeyIndex, ResultMetadata, TableSpec,
@@ -6,6 +7,7 @@ use scylla_cql::frame::types::RawValue;
 use scylla_cql::serialize::row::{RowSerializationContext, SerializeRow, SerializedValues};
 use scylla_cql::serialize::SerializationError;
 use smallvec::{smallvec, SmallVec};
+use std::collections::HashMap;
 use std::convert::TryInto;
 use std::sync::Arc;
 use std::time::Duration;
@@ -20,7 +22,7 @@ use crate::frame::types::{Consistency, SerialConsistency};
 use crate::observability::history::HistoryListener;
 use crate::policies::load_balancing::LoadBalancingPolicy;
 use crate::policies::retry::RetryPolicy;
-use crate::response::query_result::ColumnSpecs;
+use crate::response::query_result::{ColumnSpecs, ColumnSpecsGuard};
 use crate::routing::partitioner::{Partitioner, PartitionerHasher, PartitionerName};
 use crate::routing::Token;
 
@@ -70,22 +72,29 @@ use crate::routing::Token;
 /// There are two reasons for this:
 ///
 /// ### CQL v4 protocol limitations
-/// The driver only supports CQL version 4.
-///
-/// In multi-client scenario, only the first client which reprepares the statement
-/// will receive the updated metadata from the server.
-/// The rest of the clients will still hold on the outdated metadata.
-/// In version 4 of CQL protocol there is currently no way for the server to notify other
+/// The driver uses CQL protocol version 4 by default. In multi-client scenario,
+/// only the first client which reprepares the statement will receive the updated
+/// metadata from the server. The rest of the clients will still hold on the outdated metadata.
+/// In version 4 of CQL protocol there is no way for the server to notify other
 /// clients about prepared statement's metadata update.
 ///
+/// Protocol version 5 (see
+/// [`SessionBuilder::protocol_version`](crate::client::session_builder::SessionBuilder::protocol_version)),
+/// as well as ScyllaDB's metadata id extension to older versions, introduce result metadata ids.
+/// The driver sends the id of its cached result metadata along with each execution,
+/// and if the server finds it outdated, it responds with the new result metadata.
+/// The driver then updates the cached result metadata, which is shared by all copies
+/// of the statement, and reports the change with [`QueryResult::result_metadata_changed`](crate::response::query_result::QueryResult::result_metadata_changed).
+/// This makes [`PreparedStatement::set_use_cached_result_metadata`] safe to use
+/// even if the table is altered.
+///
 /// ### Client-side metadata immutability
-/// The decision was made to keep client-side metadata immutable.
-/// Mainly because of the CQLv4 limitations mentioned above. This means
-/// that metadata is not updated during statement repreparation.
+/// Apart from the refresh of result metadata described above, client-side metadata
+/// is kept immutable. This means that metadata is not updated during statement repreparation.
 /// This raises two issues:
 /// * bound values serialization errors - since [`PreparedMetadata`] is not updated
-/// * result deserialization errors - when [`PreparedStatement::set_use_cached_result_metadata`] is enabled,
-///   since [`ResultMetadata`] is not updated
+/// * result deserialization errors - when [`PreparedStatement::set_use_cached_result_metadata`] is enabled
+///   and the server does not support result metadata ids, since [`ResultMetadata`] is not updated
 ///
 /// So, to mitigate those issues, drop the outdated [`PreparedStatement`] manually
 /// and prepare it again against the new schema.
@@ -104,8 +113,36 @@ pub struct PreparedStatement {
 #[derive(Debug)]
 struct PreparedStatementSharedData {
     metadata: PreparedMetadata,
-    result_metadata: Arc<ResultMetadata<'static>>,
+    // Result metadata received when the statement was prepared.
+    initial_result_metadata: Arc<ResultMetadata<'static>>,
+    result_metadata: SharedResultMetadata,
     statement: String,
+    // Keyspace sent in the PREPARE request, needed to reprepare the statement.
+    keyspace: Option<String>,
+}
+
+/// Result metadata of a prepared statement together with its id.
+///
+/// The id is sent by the server since protocol v5 (or earlier, if ScyllaDB's
+/// extension is negotiated), and sent back in EXECUTE requests, so that
+/// the server can tell whether the client's metadata is up to date.
+#[derive(Debug)]
+pub(crate) struct CachedResultMetadata {
+    pub(crate) metadata: Arc<ResultMetadata<'static>>,
+    pub(crate) id: Option<Bytes>,
+}
+
+/// [CachedResultMetadata] shared by all copies of a prepared statement,
+/// so that a refresh is visible to all of them.
+pub(crate) type SharedResultMetadata = Arc<ArcSwap<CachedResultMetadata>>;
+
+impl CachedResultMetadata {
+    pub(crate) fn new_shared(
+        metadata: Arc<ResultMetadata<'static>>,
+        id: Option<Bytes>,
+    ) -> SharedResultMetadata {
+        Arc::new(ArcSwap::from_pointee(Self { metadata, id }))
+    }
 }
 
 impl Clone for PreparedStatement {
@@ -123,21 +160,26 @@ impl Clone for PreparedStatement {
 }
 
 impl PreparedStatement {
+    #[expect(clippy::too_many_arguments)]
     pub(crate) fn new(
         id: Bytes,
         is_lwt: bool,
         metadata: PreparedMetadata,
-        result_metadata: Arc<ResultMetadata<'static>>,
+        result_metadata: SharedResultMetadata,
         statement: String,
+        keyspace: Option<String>,
         page_size: PageSize,
         config: StatementConfig,
     ) -> Self {
+        let initial_result_metadata = result_metadata.load().metadata.clone();
         Self {
             id,
             shared: Arc::new(PreparedStatementSharedData {
                 metadata,
+                initial_result_metadata,
                 result_metadata,
                 statement,
+                keyspace,
             }),
             prepare_tracing_ids: Vec::new(),
             page_size,
@@ -151,10 +193,38 @@ impl PreparedStatement {
         &self.id
     }
 
+    /// Returns the current result metadata of this statement along with its id,
+    /// if the server provided one.
+    pub(crate) fn get_cached_result_metadata(&self) -> Arc<CachedResultMetadata> {
+        self.shared.result_metadata.load_full()
+    }
+
+    /// Returns the result metadata cell, shared by all copies of this statement.
+    pub(crate) fn get_shared_result_metadata(&self) -> &SharedResultMetadata {
+        &self.shared.result_metadata
+    }
+
+    /// Replaces the cached result metadata, after the server reported
+    /// that it has changed.
+    pub(crate) fn update_result_metadata(&self, metadata: Arc<ResultMetadata<'static>>, id: Bytes) {
+        self.shared
+            .result_metadata
+            .store(Arc::new(CachedResultMetadata {
+                metadata,
+                id: Some(id),
+            }));
+    }
+
     pub fn get_statement(&self) -> &str {
         &self.shared.statement
     }
 
+    /// Returns the keyspace the statement was prepared in, if it was set
+    /// explicitly with [`Statement::set_keyspace`](crate::statement::unprepared::Statement::set_keyspace).
+    pub fn get_prepare_keyspace(&self) -> Option<&str> {
+        self.shared.keyspace.as_deref()
+    }
+
     /// Sets the page size for this CQL query.
     ///
     /// Panics if given number is nonpositive.
@@ -389,6 +459,20 @@ impl PreparedStatement {
         self.config.request_timeout
     }
 
+    /// Sets the custom payload sent along with this statement.
+    ///
+    /// The payload is a map of opaque byte values, interpreted by custom
+    /// server-side query handlers. Entries set here override the ones with
+    /// the same key set on the execution profile.
+    pub fn set_custom_payload(&mut self, custom_payload: Option<HashMap<String, Bytes>>) {
+        self.config.custom_payload = custom_payload.map(Arc::new);
+    }
+
+    /// Gets the custom payload sent along with this statement.
+    pub fn get_custom_payload(&self) -> Option<&HashMap<String, Bytes>> {
+        self.config.custom_payload.as_deref()
+    }
+
     /// Sets the name of the partitioner used for this statement.
     pub(crate) fn set_partitioner_name(&mut self, partitioner_name: PartitionerName) {
         self.partitioner_name = partitioner_name;
@@ -409,14 +493,20 @@ impl PreparedStatement {
         &self.shared.metadata.pk_indexes
     }
 
-    /// Access metadata about the result of prepared statement returned by the database
-    pub(crate) fn get_result_metadata(&self) -> &Arc<ResultMetadata<'static>> {
-        &self.shared.result_metadata
+    /// Access column specifications of the result set returned after the execution of this statement,
+    /// as received when the statement was prepared.
+    ///
+    /// If the server reported that the result metadata has changed since then,
+    /// the specifications returned here are outdated. Use
+    /// [`PreparedStatement::get_current_result_set_col_specs`] to get the up-to-date ones.
+    pub fn get_result_set_col_specs(&self) -> ColumnSpecs<'_, 'static> {
+        ColumnSpecs::new(self.shared.initial_result_metadata.col_specs())
     }
 
-    /// Access column specifications of the result set returned after the execution of this statement
-    pub fn get_result_set_col_specs(&self) -> ColumnSpecs<'_, 'static> {
-        ColumnSpecs::new(self.shared.result_metadata.col_specs())
+    /// Access column specifications of the result set returned after the execution of this statement,
+    /// taking into account result metadata refreshes reported by the server.
+    pub fn get_current_result_set_col_specs(&self) -> ColumnSpecsGuard {
+        ColumnSpecsGuard::new(self.shared.result_metadata.load().metadata.clone())
     }
 
     /// Get the name of the partitioner used for this statement.
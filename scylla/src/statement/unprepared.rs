@@ -4,6 +4,8 @@ use crate::frame::types::{Consistency, SerialConsistency};
 use crate::observability::history::HistoryListener;
 use crate::policies::load_balancing::LoadBalancingPolicy;
 use crate::policies::retry::RetryPolicy;
+use bytes::Bytes;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -16,6 +18,7 @@ pub struct Statement {
 
     pub contents: String,
     page_size: PageSize,
+    keyspace: Option<String>,
 }
 
 impl Statement {
@@ -25,6 +28,7 @@ impl Statement {
             contents: query_text.into(),
             page_size: PageSize::default(),
             config: Default::default(),
+            keyspace: None,
         }
     }
 
@@ -55,6 +59,30 @@ impl Statement {
         self.page_size.inner()
     }
 
+    /// Returns self with the keyspace set to the given value.
+    ///
+    /// See [`Statement::set_keyspace`].
+    pub fn with_keyspace(mut self, keyspace: impl Into<String>) -> Self {
+        self.set_keyspace(Some(keyspace.into()));
+        self
+    }
+
+    /// Sets the keyspace in which this statement is executed (and prepared),
+    /// overriding the keyspace set on the session with `USE`.
+    ///
+    /// The keyspace is sent in the request frame, so statements targeting
+    /// different keyspaces can be executed concurrently on the same session.
+    /// This requires protocol v5; executing the statement on a connection
+    /// using an older protocol version fails.
+    pub fn set_keyspace(&mut self, keyspace: Option<String>) {
+        self.keyspace = keyspace;
+    }
+
+    /// Gets the keyspace in which this statement is executed, if it was set.
+    pub fn get_keyspace(&self) -> Option<&str> {
+        self.keyspace.as_deref()
+    }
+
     /// Sets the consistency to be used when executing this statement.
     pub fn set_consistency(&mut self, c: Consistency) {
         self.config.consistency = Some(c);
@@ -130,6 +158,20 @@ impl Statement {
         self.config.request_timeout
     }
 
+    /// Sets the custom payload sent along with this statement.
+    ///
+    /// The payload is a map of opaque byte values, interpreted by custom
+    /// server-side query handlers. Entries set here override the ones with
+    /// the same key set on the execution profile.
+    pub fn set_custom_payload(&mut self, custom_payload: Option<HashMap<String, Bytes>>) {
+        self.config.custom_payload = custom_payload.map(Arc::new);
+    }
+
+    /// Gets the custom payload sent along with this statement.
+    pub fn get_custom_payload(&self) -> Option<&HashMap<String, Bytes>> {
+        self.config.custom_payload.as_deref()
+    }
+
     /// Set the retry policy for this statement, overriding the one from execution profile if not None.
     #[inline]
     pub fn set_retry_policy(&mut self, retry_policy: Option<Arc<dyn RetryPolicy>>) {
@@ -1,2 +1,4 @@
 mod complex_pk;
 mod hygiene;
+mod query;
+mod table;
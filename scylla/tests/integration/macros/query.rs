@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use scylla::client::caching_session::CachingSession;
+use scylla::cluster::metadata::{CollectionType, ColumnType, NativeType};
+use scylla::statement::checked::CheckedStatement;
+use scylla::value::CqlValue;
+
+use crate::utils::{
+    create_new_session_builder, setup_tracing, unique_keyspace_name, PerformDDL as _,
+};
+
+const SCHEMA: &str = include_str!("schema.cql");
+
+// Checks the statement against the schema file of these tests.
+macro_rules! checked {
+    ($($args:tt)*) => {
+        scylla::query!(schema = "tests/integration/macros/schema.cql", $($args)*)
+    };
+}
+
+fn native(typ: NativeType) -> ColumnType<'static> {
+    ColumnType::Native(typ)
+}
+
+fn collection(typ: CollectionType<'static>) -> ColumnType<'static> {
+    ColumnType::Collection { frozen: false, typ }
+}
+
+type SelectedRow = (i32, String, Option<i64>, Option<Vec<String>>);
+
+// `SELECT *` returns the primary key, then the other columns sorted by name.
+type WholeRow = (
+    i32,
+    String,
+    Option<String>,
+    Option<CqlValue>,
+    Option<Vec<i32>>,
+    Option<HashMap<String, i32>>,
+    Option<Vec<String>>,
+    Option<i64>,
+);
+
+#[test]
+fn test_query_macro_resolves_types() {
+    let select: CheckedStatement<_, SelectedRow> = checked!(
+        "SELECT pk, ck, value, tags FROM query_macro WHERE pk = ? AND ck IN ? LIMIT ?",
+        1,
+        vec!["a", "b"],
+        10
+    );
+    assert_eq!(
+        select.bind_types(),
+        vec![
+            native(NativeType::Int),
+            collection(CollectionType::List(Box::new(native(NativeType::Text)))),
+            native(NativeType::Int),
+        ]
+    );
+    assert_eq!(
+        select.result_types().unwrap(),
+        vec![
+            native(NativeType::Int),
+            native(NativeType::Text),
+            native(NativeType::BigInt),
+            collection(CollectionType::Set(Box::new(native(NativeType::Text)))),
+        ]
+    );
+
+    let select_all: CheckedStatement<_, WholeRow> =
+        checked!("SELECT * FROM query_macro WHERE pk = ?", 1);
+    assert_eq!(select_all.result_types().unwrap().len(), 8);
+
+    let metadata: CheckedStatement<_, (Option<i64>, Option<i32>, i64)> = checked!(
+        "SELECT writetime(value), ttl(value), count(*) FROM query_macro WHERE pk = ?",
+        1
+    );
+    assert_eq!(metadata.bind_types(), vec![native(NativeType::Int)]);
+
+    let insert: CheckedStatement<_, ()> = checked!(
+        "INSERT INTO query_macro (pk, ck, value) VALUES (?, ?, 42) IF NOT EXISTS USING TTL ?",
+        1,
+        "a".to_owned(),
+        3600
+    );
+    assert_eq!(
+        insert.bind_types(),
+        vec![
+            native(NativeType::Int),
+            native(NativeType::Text),
+            native(NativeType::Int),
+        ]
+    );
+    assert_eq!(insert.result_types(), None);
+
+    let update = checked!(
+        "UPDATE query_macro USING TIMESTAMP ? SET scores[?] = ?, tags = tags + ?, history = ? + history WHERE pk = ? AND ck = ? IF value = ?",
+        100_i64,
+        "math",
+        5,
+        vec!["x".to_owned()],
+        [1, 2],
+        1,
+        "a",
+        Some(42_i64)
+    );
+    assert_eq!(
+        update.bind_types(),
+        vec![
+            native(NativeType::BigInt),
+            native(NativeType::Text),
+            native(NativeType::Int),
+            collection(CollectionType::Set(Box::new(native(NativeType::Text)))),
+            collection(CollectionType::List(Box::new(native(NativeType::Int)))),
+            native(NativeType::Int),
+            native(NativeType::Text),
+            native(NativeType::BigInt),
+        ]
+    );
+
+    let delete = checked!(
+        "DELETE scores[?], history[?] FROM query_macro WHERE pk = ? AND ck = ?",
+        "math",
+        0,
+        1,
+        "a"
+    );
+    assert_eq!(
+        delete.bind_types(),
+        vec![
+            native(NativeType::Text),
+            native(NativeType::Int),
+            native(NativeType::Int),
+            native(NativeType::Text),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_query_macro_statements() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+    let ks = unique_keyspace_name();
+
+    session.ddl(format!("CREATE KEYSPACE IF NOT EXISTS {} WITH REPLICATION = {{'class' : 'NetworkTopologyStrategy', 'replication_factor' : 1}}", ks)).await.unwrap();
+    session.use_keyspace(ks, true).await.unwrap();
+    for statement in SCHEMA.split(';').filter(|s| s.contains("CREATE")) {
+        session.ddl(statement).await.unwrap();
+    }
+    let session: CachingSession = CachingSession::from(session, 100);
+
+    checked!(
+        "INSERT INTO query_macro (pk, ck, value, tags, scores) VALUES (?, ?, ?, ?, ?)",
+        1,
+        "a",
+        10_i64,
+        vec!["x", "y"],
+        HashMap::from([("math", 5)])
+    )
+    .execute(&session)
+    .await
+    .unwrap();
+    checked!(
+        "UPDATE query_macro SET scores = scores - ?, note = ? WHERE pk = ? AND ck = ?",
+        vec!["math"],
+        "static",
+        1,
+        "a"
+    )
+    .execute(&session)
+    .await
+    .unwrap();
+
+    let rows = checked!(
+        "SELECT ck, value, note, scores, tags FROM query_macro WHERE pk = ?",
+        1
+    )
+    .rows(&session)
+    .await
+    .unwrap();
+    assert_eq!(
+        rows,
+        vec![(
+            "a".to_owned(),
+            Some(10),
+            Some("static".to_owned()),
+            None,
+            Some(vec!["x".to_owned(), "y".to_owned()])
+        )]
+    );
+
+    let count = checked!("SELECT count(*) FROM query_macro WHERE pk = ?", 1)
+        .maybe_first_row(&session)
+        .await
+        .unwrap();
+    assert_eq!(count, Some((1,)));
+
+    checked!("DELETE FROM query_macro WHERE pk = ? AND ck = ?", 1, "a")
+        .execute(&session)
+        .await
+        .unwrap();
+    let row = checked!("SELECT ck FROM query_macro WHERE pk = ? AND ck = ?", 1, "a")
+        .maybe_first_row(&session)
+        .await
+        .unwrap();
+    assert_eq!(row, None);
+}
@@ -0,0 +1,84 @@
+use scylla::client::caching_session::CachingSession;
+use scylla::client::table::{InsertOptions, Table, UpdateOptions};
+
+use crate::utils::{
+    create_new_session_builder, setup_tracing, unique_keyspace_name, PerformDDL as _,
+};
+
+#[derive(scylla::Table, scylla::DeserializeRow, PartialEq, Debug, Clone)]
+#[scylla(table = "table_derive")]
+struct Event {
+    #[scylla(partition_key)]
+    source: String,
+    #[scylla(partition_key)]
+    day: i32,
+    #[scylla(clustering_key)]
+    seq: i32,
+    payload: Option<String>,
+    #[scylla(writetime_of = "payload")]
+    payload_writetime: Option<i64>,
+    #[scylla(ttl_of = "payload")]
+    payload_ttl: Option<i32>,
+}
+
+#[tokio::test]
+async fn test_table_derive_crud() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+    let ks = unique_keyspace_name();
+
+    session.ddl(format!("CREATE KEYSPACE IF NOT EXISTS {} WITH REPLICATION = {{'class' : 'NetworkTopologyStrategy', 'replication_factor' : 1}}", ks)).await.unwrap();
+    session.use_keyspace(ks, true).await.unwrap();
+    session
+        .ddl("CREATE TABLE IF NOT EXISTS table_derive (source text, day int, seq int, payload text, PRIMARY KEY ((source, day), seq))")
+        .await
+        .unwrap();
+    let session: CachingSession = CachingSession::from(session, 100);
+
+    let key = ("sensor".to_owned(), 20, 1);
+    let mut event = Event {
+        source: key.0.clone(),
+        day: key.1,
+        seq: key.2,
+        payload: Some("first".to_owned()),
+        payload_writetime: None,
+        payload_ttl: None,
+    };
+    assert_eq!(Event::get(&session, &key).await.unwrap(), None);
+
+    event
+        .insert_with(&session, InsertOptions::new().timestamp(100).ttl(1000))
+        .await
+        .unwrap();
+    let fetched = Event::get(&session, &key).await.unwrap().unwrap();
+    assert_eq!(fetched.payload.as_deref(), Some("first"));
+    assert_eq!(fetched.payload_writetime, Some(100));
+    assert!(fetched
+        .payload_ttl
+        .is_some_and(|ttl| ttl > 0 && ttl <= 1000));
+
+    // A conditional insert does not overwrite the existing row.
+    let other = Event {
+        payload: Some("other".to_owned()),
+        ..event.clone()
+    };
+    other
+        .insert_with(&session, InsertOptions::new().if_not_exists(true))
+        .await
+        .unwrap();
+    let fetched = Event::get(&session, &key).await.unwrap().unwrap();
+    assert_eq!(fetched.payload.as_deref(), Some("first"));
+
+    event.payload = Some("second".to_owned());
+    event
+        .update_with(&session, UpdateOptions::new().timestamp(200))
+        .await
+        .unwrap();
+    let fetched = Event::get(&session, &key).await.unwrap().unwrap();
+    assert_eq!(fetched.payload.as_deref(), Some("second"));
+    assert_eq!(fetched.payload_writetime, Some(200));
+    assert_eq!(fetched.payload_ttl, None);
+
+    Event::delete(&session, &key).await.unwrap();
+    assert_eq!(Event::get(&session, &key).await.unwrap(), None);
+}
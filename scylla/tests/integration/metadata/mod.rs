@@ -1,2 +1,3 @@
 mod configuration;
 mod contents;
+mod validation;
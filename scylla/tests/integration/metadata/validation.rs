@@ -0,0 +1,80 @@
+use assert_matches::assert_matches;
+use scylla::cluster::validation::{SchemaMismatch, SchemaValidationError};
+use scylla::{DeserializeRow, DeserializeValue, SerializeRow, SerializeValue};
+
+use crate::utils::{
+    create_new_session_builder, setup_tracing, unique_keyspace_name, PerformDDL as _,
+};
+
+#[derive(DeserializeValue, SerializeValue)]
+struct Address {
+    street: Option<String>,
+    number: Option<i32>,
+}
+
+#[derive(DeserializeRow, SerializeRow)]
+struct User {
+    id: i32,
+    name: String,
+    address: Option<Address>,
+}
+
+#[allow(dead_code)]
+#[derive(DeserializeRow)]
+struct OutdatedUser {
+    id: i32,
+    name: Option<i64>,
+    email: Option<String>,
+}
+
+#[tokio::test]
+async fn test_schema_validation() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+    let ks = unique_keyspace_name();
+
+    session.ddl(format!("CREATE KEYSPACE {} WITH REPLICATION = {{'class' : 'NetworkTopologyStrategy', 'replication_factor' : 1}}", ks)).await.unwrap();
+    session
+        .ddl(format!(
+            "CREATE TYPE {}.address (street text, number int)",
+            ks
+        ))
+        .await
+        .unwrap();
+    session
+        .ddl(format!(
+            "CREATE TABLE {}.users (id int, name text, address frozen<address>, age int, PRIMARY KEY (id, name))",
+            ks
+        ))
+        .await
+        .unwrap();
+    session.refresh_metadata().await.unwrap();
+
+    session.validate_udt::<Address>(&ks, "address").unwrap();
+    session.validate_row_type::<User>(&ks, "users").unwrap();
+    let user = User {
+        id: 1,
+        name: "John".to_owned(),
+        address: None,
+    };
+    session.validate_row_values(&ks, "users", &user).unwrap();
+
+    let err = session
+        .validate_row_type::<OutdatedUser>(&ks, "users")
+        .unwrap_err();
+    let SchemaValidationError::Mismatch(report) = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert_matches!(
+        report.mismatches.as_slice(),
+        [
+            SchemaMismatch::TypeMismatch { name, .. },
+            SchemaMismatch::NotInSchema { name: missing },
+        ] if name == "name" && missing == "email"
+    );
+
+    assert_matches!(
+        session.validate_row_type::<User>(&ks, "no_such_table"),
+        Err(SchemaValidationError::TableNotFound { .. })
+    );
+}
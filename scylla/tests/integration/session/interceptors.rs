@@ -0,0 +1,127 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::TryStreamExt;
+use scylla::client::execution_profile::ExecutionProfile;
+use scylla::errors::{DbError, ExecutionError, RequestAttemptError};
+use scylla::policies::interceptor::{
+    InterceptedRequest, InterceptedStatement, InterceptorDecision, RequestInterceptor,
+};
+use scylla::response::query_result::QueryResult;
+use scylla::statement::unprepared::Statement;
+use scylla::statement::Consistency;
+
+use crate::utils::{create_new_session_builder, setup_tracing};
+
+const LOCAL_QUERY: &str = "SELECT host_id FROM system.local WHERE key = 'local'";
+
+/// Expands `$local` to the condition selecting the local row,
+/// rejects `TRUNCATE`s and counts calls of the hooks.
+#[derive(Debug, Default)]
+struct Expander {
+    attempts: AtomicUsize,
+    requests: AtomicUsize,
+}
+
+impl RequestInterceptor for Expander {
+    fn before_attempt(&self, request: &mut InterceptedRequest<'_>) -> InterceptorDecision {
+        self.attempts.fetch_add(1, Ordering::Relaxed);
+        request.set_consistency(Consistency::One);
+        request
+            .custom_payload_mut()
+            .insert("tenant".to_owned(), Bytes::from_static(b"test"));
+        if let Some(contents) = request.contents_mut() {
+            if contents.starts_with("TRUNCATE") {
+                return InterceptorDecision::reject("TRUNCATE is not allowed");
+            }
+            *contents = contents.replace("$local", "key = 'local'");
+        }
+        InterceptorDecision::Proceed
+    }
+
+    fn after_request(
+        &self,
+        _statement: InterceptedStatement<'_>,
+        result: Result<QueryResult, ExecutionError>,
+    ) -> Result<QueryResult, ExecutionError> {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        result
+    }
+}
+
+/// Fails every attempt with `Overloaded` error.
+#[derive(Debug)]
+struct Overloader;
+
+impl RequestInterceptor for Overloader {
+    fn before_attempt(&self, _request: &mut InterceptedRequest<'_>) -> InterceptorDecision {
+        InterceptorDecision::FailAttempt(RequestAttemptError::DbError(
+            DbError::Overloaded,
+            "Injected".to_owned(),
+        ))
+    }
+}
+
+#[tokio::test]
+async fn test_request_interceptor() {
+    setup_tracing();
+    let expander = Arc::new(Expander::default());
+    let session = create_new_session_builder()
+        .request_interceptor(expander.clone())
+        .build()
+        .await
+        .unwrap();
+
+    // Contents of unprepared statements are modified.
+    let rows = session
+        .query_unpaged("SELECT host_id FROM system.local WHERE $local", &[])
+        .await
+        .unwrap()
+        .into_rows_result()
+        .unwrap();
+    assert_eq!(rows.rows_num(), 1);
+    assert_eq!(expander.attempts.load(Ordering::Relaxed), 1);
+    assert_eq!(expander.requests.load(Ordering::Relaxed), 1);
+
+    // Rejected requests are not retried.
+    let err = session
+        .query_unpaged("TRUNCATE system.local", &[])
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ExecutionError::RejectedByInterceptor(_)));
+    assert_eq!(expander.attempts.load(Ordering::Relaxed), 2);
+    assert_eq!(expander.requests.load(Ordering::Relaxed), 2);
+
+    // Prepared statements and pages of pagers are intercepted as well.
+    let prepared = session.prepare(LOCAL_QUERY).await.unwrap();
+    session.execute_unpaged(&prepared, ()).await.unwrap();
+    assert_eq!(expander.attempts.load(Ordering::Relaxed), 3);
+    assert_eq!(expander.requests.load(Ordering::Relaxed), 3);
+
+    let rows = session
+        .query_iter("SELECT host_id FROM system.local WHERE $local", ())
+        .await
+        .unwrap()
+        .rows_stream::<(uuid::Uuid,)>()
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(expander.attempts.load(Ordering::Relaxed), 4);
+    assert_eq!(expander.requests.load(Ordering::Relaxed), 3);
+
+    // Execution profiles override the session's interceptor.
+    let profile = ExecutionProfile::builder()
+        .request_interceptor(Some(Arc::new(Overloader)))
+        .build();
+    let mut statement = Statement::new(LOCAL_QUERY);
+    statement.set_execution_profile_handle(Some(profile.into_handle()));
+    let err = session.query_unpaged(statement, &[]).await.unwrap_err();
+    assert!(matches!(
+        err,
+        ExecutionError::LastAttemptError(RequestAttemptError::DbError(DbError::Overloaded, _))
+    ));
+    assert_eq!(expander.attempts.load(Ordering::Relaxed), 4);
+}
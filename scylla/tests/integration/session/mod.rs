@@ -2,9 +2,12 @@ mod authenticate;
 mod caching_session;
 mod db_errors;
 mod history;
+mod interceptors;
 mod new_session;
 mod pager;
 mod retries;
 mod self_identity;
+mod shutdown;
+mod throttling;
 mod tracing;
 mod use_keyspace;
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use futures::TryStreamExt;
+use scylla::errors::{
+    ExecutionError, NextPageError, NextRowError, PagerExecutionError, RequestError,
+};
+use scylla::statement::unprepared::Statement;
+
+use crate::utils::{create_new_session_builder, setup_tracing};
+
+const QUERY: &str = "SELECT keyspace_name FROM system_schema.tables";
+
+#[tokio::test]
+async fn test_session_shutdown_without_requests_in_flight() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+    session.query_unpaged(QUERY, &[]).await.unwrap();
+
+    let report = session.shutdown(Duration::from_secs(10)).await;
+    assert!(report.is_clean());
+    assert_eq!(report.in_flight_requests, 0);
+    assert_eq!(report.in_flight_pagers, 0);
+
+    let err = session.query_unpaged(QUERY, &[]).await.unwrap_err();
+    assert!(matches!(err, ExecutionError::SessionShutDown));
+    let Err(err) = session.query_iter(QUERY, &[]).await else {
+        panic!("Pager created after shutdown");
+    };
+    assert!(matches!(
+        err,
+        PagerExecutionError::NextPageError(NextPageError::RequestFailure(
+            RequestError::SessionShutDown
+        ))
+    ));
+    session.prepare(QUERY).await.unwrap_err();
+    session.refresh_metadata().await.unwrap_err();
+
+    let cluster_state = session.get_cluster_state();
+    assert!(cluster_state
+        .get_nodes_info()
+        .iter()
+        .all(|node| !node.is_connected()));
+
+    // Shutting down again is a no-op.
+    assert!(session.shutdown(Duration::from_secs(10)).await.is_clean());
+}
+
+#[tokio::test]
+async fn test_session_shutdown_cancels_pagers_after_deadline() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+
+    // The pager fetches pages ahead of the consumer, so a pager which is not
+    // consumed stays in flight.
+    let mut statement = Statement::new(QUERY);
+    statement.set_page_size(1);
+    let pager = session.query_iter(statement, &[]).await.unwrap();
+
+    let report = session.shutdown(Duration::from_millis(100)).await;
+    assert_eq!(report.in_flight_requests, 0);
+    assert_eq!(report.in_flight_pagers, 1);
+    assert_eq!(report.cancelled_pagers, 1);
+    assert!(!report.is_clean());
+
+    let err = pager
+        .rows_stream::<(String,)>()
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        NextRowError::NextPageError(NextPageError::RequestFailure(RequestError::SessionShutDown))
+    ));
+}
@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use futures::TryStreamExt;
+use scylla::client::execution_profile::ExecutionProfile;
+use scylla::errors::{
+    ExecutionError, NextPageError, PagerExecutionError, RequestError, ThrottlingError,
+};
+use scylla::policies::throttler::{DefaultRequestThrottler, RequestThrottler};
+use scylla::statement::unprepared::Statement;
+
+use crate::utils::{create_new_session_builder, setup_tracing};
+
+const LOCAL_QUERY: &str = "SELECT host_id FROM system.local WHERE key = 'local'";
+
+#[tokio::test]
+async fn test_request_throttler_rejects_when_queue_is_full() {
+    setup_tracing();
+    let throttler = DefaultRequestThrottler::builder()
+        .max_concurrent_requests(1)
+        .max_queue_length(0)
+        .build();
+    let session = create_new_session_builder()
+        .request_throttler(throttler.clone())
+        .build()
+        .await
+        .unwrap();
+
+    // Requests are executed while there is a free permit.
+    session.query_unpaged(LOCAL_QUERY, &[]).await.unwrap();
+    let prepared = session.prepare(LOCAL_QUERY).await.unwrap();
+    session.execute_unpaged(&prepared, ()).await.unwrap();
+
+    // Take the only permit, so that requests of the session can't be queued.
+    let permit = throttler.acquire().await.unwrap();
+
+    let err = session.query_unpaged(LOCAL_QUERY, &[]).await.unwrap_err();
+    assert!(matches!(
+        err,
+        ExecutionError::RequestThrottled(ThrottlingError::QueueFull {
+            max_queue_length: 0
+        })
+    ));
+    let err = session.execute_unpaged(&prepared, ()).await.unwrap_err();
+    assert!(matches!(
+        err,
+        ExecutionError::RequestThrottled(ThrottlingError::QueueFull { .. })
+    ));
+
+    // Pages of pagers are throttled as well.
+    let Err(err) = session.query_iter(LOCAL_QUERY, ()).await else {
+        panic!("Expected the first page to be throttled");
+    };
+    assert!(matches!(
+        err,
+        PagerExecutionError::NextPageError(NextPageError::RequestFailure(
+            RequestError::RequestThrottled(ThrottlingError::QueueFull { .. })
+        ))
+    ));
+
+    // Execution profiles override the session's throttler.
+    let unbounded = DefaultRequestThrottler::builder().build();
+    let profile = ExecutionProfile::builder()
+        .request_throttler(Some(unbounded))
+        .build();
+    let mut statement = Statement::new(LOCAL_QUERY);
+    statement.set_execution_profile_handle(Some(profile.into_handle()));
+    session.query_unpaged(statement, &[]).await.unwrap();
+
+    drop(permit);
+    let rows = session
+        .execute_iter(prepared, ())
+        .await
+        .unwrap()
+        .rows_stream::<(uuid::Uuid,)>()
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(throttler.queue_length(), 0);
+}
+
+#[tokio::test]
+async fn test_request_throttler_queue_timeout() {
+    setup_tracing();
+    let throttler = DefaultRequestThrottler::builder()
+        .max_concurrent_requests(1)
+        .max_queue_wait(Duration::from_millis(100))
+        .build();
+    let session = create_new_session_builder()
+        .request_throttler(throttler.clone())
+        .build()
+        .await
+        .unwrap();
+
+    let permit = throttler.acquire().await.unwrap();
+    let err = session.query_unpaged(LOCAL_QUERY, &[]).await.unwrap_err();
+    assert!(matches!(
+        err,
+        ExecutionError::RequestThrottled(ThrottlingError::QueueTimeout(_))
+    ));
+
+    // A queued request proceeds as soon as the permit is released.
+    let (result, ()) = tokio::join!(session.query_unpaged(LOCAL_QUERY, &[]), async {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        drop(permit);
+    });
+    result.unwrap();
+}
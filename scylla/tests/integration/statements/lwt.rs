@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use assert_matches::assert_matches;
+use futures::future::try_join_all;
+use scylla::client::session::Session;
+use scylla::response::lwt::{CasError, CasOptions, CasOutcome, LwtResult};
+
+use crate::utils::{
+    create_new_session_builder, scylla_supports_tablets, setup_tracing, unique_keyspace_name,
+    PerformDDL as _,
+};
+
+async fn create_lwt_table(session: &Session) {
+    let ks = unique_keyspace_name();
+    let mut create_ks = format!("CREATE KEYSPACE IF NOT EXISTS {} WITH REPLICATION = {{'class' : 'NetworkTopologyStrategy', 'replication_factor' : 1}}", ks);
+    if scylla_supports_tablets(session).await {
+        create_ks += " and TABLETS = { 'enabled': false}";
+    }
+    session.ddl(create_ks).await.unwrap();
+    session.use_keyspace(ks, false).await.unwrap();
+    session
+        .ddl("CREATE TABLE t (a int primary key, b int)")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_execute_lwt() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+    create_lwt_table(&session).await;
+
+    let insert = session
+        .prepare("INSERT INTO t (a, b) VALUES (?, ?) IF NOT EXISTS")
+        .await
+        .unwrap();
+    let update = session
+        .prepare("UPDATE t SET b = ? WHERE a = ? IF b = ?")
+        .await
+        .unwrap();
+
+    let result = session
+        .execute_lwt::<(i32, i32)>(&insert, (1, 10))
+        .await
+        .unwrap();
+    assert_eq!(result, LwtResult::Applied);
+
+    // The current row is returned when the condition is not met.
+    let result = session
+        .execute_lwt::<(i32, i32)>(&insert, (1, 20))
+        .await
+        .unwrap();
+    assert_eq!(result, LwtResult::NotApplied(Some((1, 10))));
+
+    let result = session
+        .execute_lwt::<(i32,)>(&update, (11, 1, 20))
+        .await
+        .unwrap();
+    assert_eq!(result, LwtResult::NotApplied(Some((10,))));
+
+    let result = session
+        .execute_lwt::<(i32,)>(&update, (11, 1, 10))
+        .await
+        .unwrap();
+    assert!(result.is_applied());
+
+    // The row does not exist.
+    let result = session
+        .execute_lwt::<(i32,)>(&update, (11, 2, 10))
+        .await
+        .unwrap();
+    assert_eq!(result, LwtResult::NotApplied(None));
+
+    // Statements which are not LWTs have no `[applied]` column.
+    let select = session
+        .prepare("SELECT b FROM t WHERE a = ?")
+        .await
+        .unwrap();
+    assert!(session.execute_lwt::<(i32,)>(&select, (1,)).await.is_err());
+}
+
+#[tokio::test]
+async fn test_compare_and_set() {
+    setup_tracing();
+    let session = Arc::new(create_new_session_builder().build().await.unwrap());
+    create_lwt_table(&session).await;
+
+    let read = session
+        .prepare("SELECT b FROM t WHERE a = ?")
+        .await
+        .unwrap();
+    let insert = session
+        .prepare("INSERT INTO t (a, b) VALUES (?, ?) IF NOT EXISTS")
+        .await
+        .unwrap();
+    let update = session
+        .prepare("UPDATE t SET b = ? WHERE a = ? IF b = ?")
+        .await
+        .unwrap();
+
+    // Increments of the counter, running concurrently, are not lost.
+    session
+        .execute_lwt::<(i32, i32)>(&insert, (1, 0))
+        .await
+        .unwrap();
+    let increments = (0..5).map(|_| {
+        let (session, read, update) = (Arc::clone(&session), read.clone(), update.clone());
+        async move {
+            session
+                .compare_and_set(
+                    &read,
+                    (1,),
+                    &update,
+                    CasOptions::new().max_attempts(100),
+                    |current: Option<&(i32,)>| {
+                        let &(b,) = current.unwrap();
+                        Some(((b + 1,), (b + 1, 1, b)))
+                    },
+                )
+                .await
+        }
+    });
+    let outcomes = try_join_all(increments).await.unwrap();
+    assert!(outcomes
+        .iter()
+        .all(|outcome| matches!(outcome, CasOutcome::Applied(_))));
+    let (b,) = session
+        .execute_unpaged(&read, (1,))
+        .await
+        .unwrap()
+        .into_rows_result()
+        .unwrap()
+        .single_row::<(i32,)>()
+        .unwrap();
+    assert_eq!(b, 5);
+
+    // The row is left unchanged if the update function declines to update it.
+    let outcome = session
+        .compare_and_set(
+            &read,
+            (2,),
+            &update,
+            CasOptions::new().max_attempts(1),
+            |current: Option<&(i32,)>| current.map(|&(b,)| ((b + 1,), (b + 1, 2, b))),
+        )
+        .await
+        .unwrap();
+    assert_eq!(outcome, CasOutcome::Aborted(None));
+
+    // The update never applies if the condition never holds.
+    let err = session
+        .compare_and_set(
+            &read,
+            (1,),
+            &update,
+            CasOptions::new().max_attempts(3),
+            |current: Option<&(i32,)>| {
+                let &(b,) = current.unwrap();
+                Some(((b + 1,), (b + 1, 1, b - 1)))
+            },
+        )
+        .await
+        .unwrap_err();
+    assert_matches!(err, CasError::AttemptsExhausted(3));
+}
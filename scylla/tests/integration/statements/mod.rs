@@ -2,6 +2,7 @@ mod batch;
 mod consistency;
 mod enforce_coordinator;
 mod execution_profiles;
+mod lwt;
 mod named_bind_markers;
 mod prepared;
 mod request_timeout;
@@ -1,11 +1,28 @@
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use assert_matches::assert_matches;
+use bytes::Bytes;
+use scylla::client::session::Session;
+use scylla::client::session_builder::SessionBuilder;
+use scylla::cluster::metadata::{ColumnType, NativeType};
+use scylla::deserialize::value::DeserializeValue;
+use scylla::deserialize::FrameSlice;
+use scylla::errors::{DbError, RequestAttemptError};
+use scylla::policies::retry::{RequestInfo, RetryDecision, RetryPolicy, RetrySession};
+use scylla::value::CqlDuration;
 use scylla::{
     client::execution_profile::ExecutionProfile, errors::ExecutionError, statement::Statement,
 };
+use scylla_cql::frame::request::Request;
+use scylla_cql::frame::types::RawValue;
+use scylla_proxy::{
+    Condition, ProxyError, Reaction, RequestFrame, RequestOpcode, RequestReaction, RequestRule,
+    ShardAwareness, WorkerError,
+};
+use tokio::sync::mpsc;
 
-use crate::utils::{create_new_session_builder, setup_tracing};
+use crate::utils::{create_new_session_builder, setup_tracing, test_with_3_node_cluster};
 
 #[ignore = "works on remote Scylla instances only (local ones are too fast)"]
 #[tokio::test]
@@ -73,3 +90,248 @@ async fn test_request_timeout() {
         timeouting_session.execute_unpaged(&prepared, &[]).await.expect("the prepared query should have not failed, because no client-side timeout was specified");
     }
 }
+
+#[tokio::test]
+async fn test_min_attempt_budget() {
+    setup_tracing();
+    let session = create_new_session_builder().build().await.unwrap();
+
+    // The whole budget is shorter than the one required for an attempt.
+    let profile = ExecutionProfile::builder()
+        .request_timeout(Some(Duration::from_secs(1)))
+        .min_attempt_budget(Some(Duration::from_secs(2)))
+        .build();
+    let mut query = Statement::new("SELECT host_id FROM system.local");
+    query.set_execution_profile_handle(Some(profile.into_handle()));
+    assert_matches!(
+        session.query_unpaged(query.clone(), &[]).await,
+        Err(ExecutionError::RequestTimeout(timeout)) if timeout == Duration::from_secs(1)
+    );
+
+    query.set_request_timeout(Some(Duration::from_secs(10)));
+    session.query_unpaged(query, &[]).await.unwrap();
+}
+
+/// Records the time left reported to the retry policy and retries on the next node.
+#[derive(Debug, Clone, Default)]
+struct RemainingTimeRecorder(Arc<Mutex<Vec<Option<Duration>>>>);
+
+impl RetryPolicy for RemainingTimeRecorder {
+    fn new_session(&self) -> Box<dyn RetrySession> {
+        Box::new(self.clone())
+    }
+}
+
+impl RetrySession for RemainingTimeRecorder {
+    fn decide_should_retry(&mut self, request_info: RequestInfo) -> RetryDecision {
+        let mut recorded = self.0.lock().unwrap();
+        recorded.push(request_info.remaining_time);
+        if recorded.len() < 3 {
+            RetryDecision::RetryNextTarget(None)
+        } else {
+            RetryDecision::DontRetry
+        }
+    }
+
+    fn reset(&mut self) {}
+}
+
+#[tokio::test]
+#[ntest::timeout(20000)]
+#[cfg_attr(scylla_cloud_tests, ignore)]
+async fn test_request_deadline_is_passed_to_retry_policy() {
+    setup_tracing();
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    let res = test_with_3_node_cluster(
+        ShardAwareness::QueryNode,
+        |proxy_uris, translation_map, mut running_proxy| async move {
+            let session: Session = SessionBuilder::new()
+                .known_node(proxy_uris[0].as_str())
+                .address_translator(Arc::new(translation_map))
+                .build()
+                .await
+                .unwrap();
+
+            // Every node reports to be overloaded.
+            let (feedback_tx, mut feedback_rx) = mpsc::unbounded_channel();
+            for node in running_proxy.running_nodes.iter_mut() {
+                node.change_request_rules(Some(vec![RequestRule(
+                    Condition::RequestOpcode(RequestOpcode::Query).and(
+                        Condition::BodyContainsCaseSensitive(Box::new(*b"deadline_test")),
+                    ),
+                    RequestReaction::forge()
+                        .overloaded()
+                        .with_feedback_when_performed(feedback_tx.clone()),
+                )]));
+            }
+
+            for request_timeout in [Some(TIMEOUT), None] {
+                let recorder = RemainingTimeRecorder::default();
+                let profile = ExecutionProfile::builder()
+                    .request_timeout(request_timeout)
+                    .retry_policy(Arc::new(recorder.clone()))
+                    .build();
+                let mut statement =
+                    Statement::new("SELECT host_id AS deadline_test FROM system.local");
+                statement.set_execution_profile_handle(Some(profile.into_handle()));
+
+                let err = session.query_unpaged(statement, &[]).await.unwrap_err();
+                assert_matches!(
+                    err,
+                    ExecutionError::LastAttemptError(RequestAttemptError::DbError(
+                        DbError::Overloaded,
+                        _
+                    ))
+                );
+
+                let recorded = recorder.0.lock().unwrap().clone();
+                assert_eq!(recorded.len(), 3);
+                match request_timeout {
+                    // The retry policy is aware of the time left, which decreases with each attempt.
+                    Some(timeout) => {
+                        let remaining: Vec<Duration> =
+                            recorded.into_iter().map(Option::unwrap).collect();
+                        assert!(remaining[0] < timeout);
+                        assert!(remaining.windows(2).all(|w| w[1] < w[0]), "{remaining:?}");
+                    }
+                    // There is no deadline without a request timeout.
+                    None => assert!(recorded.iter().all(Option::is_none), "{recorded:?}"),
+                }
+
+                // The statement text is the same in each attempt.
+                let mut bodies = Vec::new();
+                for _ in 0..3 {
+                    let (frame, _shard) = feedback_rx.recv().await.unwrap();
+                    bodies.push(frame.body);
+                }
+                assert!(bodies.windows(2).all(|w| w[0] == w[1]));
+            }
+
+            running_proxy
+        },
+    )
+    .await;
+
+    match res {
+        Ok(()) => (),
+        Err(ProxyError::Worker(WorkerError::DriverDisconnected(_))) => (),
+        Err(err) => panic!("{}", err),
+    }
+}
+
+// Reads the value bound to the `USING TIMEOUT ?` marker, which is the second one.
+fn bound_timeout(frame: &RequestFrame) -> CqlDuration {
+    let Request::Execute(execute) = frame.deserialize().unwrap() else {
+        panic!("expected an EXECUTE request");
+    };
+    let values: Vec<RawValue> = execute.parameters.values.iter().collect();
+    assert_eq!(values.len(), 2);
+    let RawValue::Value(timeout) = values[1] else {
+        panic!("the timeout is not bound");
+    };
+    let timeout = Bytes::copy_from_slice(timeout);
+    CqlDuration::deserialize(
+        &ColumnType::Native(NativeType::Duration),
+        Some(FrameSlice::new(&timeout)),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+#[ntest::timeout(20000)]
+#[cfg_attr(scylla_cloud_tests, ignore)]
+async fn test_request_deadline_is_propagated_to_server() {
+    setup_tracing();
+    const TIMEOUT: Duration = Duration::from_secs(5);
+    // Bound by the caller, and sent only when the request has no deadline.
+    const BOUND_TIMEOUT: CqlDuration = CqlDuration {
+        months: 0,
+        days: 0,
+        nanoseconds: 3_600_000_000_000,
+    };
+
+    let res = test_with_3_node_cluster(
+        ShardAwareness::QueryNode,
+        |proxy_uris, translation_map, mut running_proxy| async move {
+            let session: Session = SessionBuilder::new()
+                .known_node(proxy_uris[0].as_str())
+                .address_translator(Arc::new(translation_map))
+                .build()
+                .await
+                .unwrap();
+            let prepared = session
+                .prepare("SELECT host_id FROM system.local WHERE key = ? USING TIMEOUT ?")
+                .await
+                .unwrap();
+
+            // Every node reports to be overloaded.
+            let (feedback_tx, mut feedback_rx) = mpsc::unbounded_channel();
+            for node in running_proxy.running_nodes.iter_mut() {
+                node.change_request_rules(Some(vec![RequestRule(
+                    Condition::RequestOpcode(RequestOpcode::Execute),
+                    RequestReaction::forge()
+                        .overloaded()
+                        .with_feedback_when_performed(feedback_tx.clone()),
+                )]));
+            }
+
+            for request_timeout in [Some(TIMEOUT), None] {
+                let recorder = RemainingTimeRecorder::default();
+                let profile = ExecutionProfile::builder()
+                    .request_timeout(request_timeout)
+                    .propagate_timeout_to_server(true)
+                    .retry_policy(Arc::new(recorder.clone()))
+                    .build();
+                let mut prepared = prepared.clone();
+                prepared.set_execution_profile_handle(Some(profile.into_handle()));
+
+                session
+                    .execute_unpaged(&prepared, ("local", BOUND_TIMEOUT))
+                    .await
+                    .unwrap_err();
+
+                let mut bound_timeouts = Vec::new();
+                for _ in 0..3 {
+                    let (frame, _shard) = feedback_rx.recv().await.unwrap();
+                    bound_timeouts.push(bound_timeout(&frame));
+                }
+                let recorded = recorder.0.lock().unwrap().clone();
+                assert_eq!(recorded.len(), 3);
+
+                match request_timeout {
+                    // Each attempt carries the time left when it was started, rounded up
+                    // to whole milliseconds, instead of the value bound by the caller.
+                    Some(timeout) => {
+                        for (bound, remaining) in bound_timeouts.iter().zip(&recorded) {
+                            assert_eq!((bound.months, bound.days), (0, 0));
+                            assert_eq!(bound.nanoseconds % 1_000_000, 0);
+                            let bound = Duration::from_nanos(bound.nanoseconds as u64);
+                            assert!(bound <= timeout);
+                            assert!(bound >= remaining.unwrap());
+                        }
+                        assert!(
+                            bound_timeouts
+                                .windows(2)
+                                .all(|w| w[1].nanoseconds <= w[0].nanoseconds),
+                            "{bound_timeouts:?}"
+                        );
+                    }
+                    None => assert!(
+                        bound_timeouts.iter().all(|bound| *bound == BOUND_TIMEOUT),
+                        "{bound_timeouts:?}"
+                    ),
+                }
+            }
+
+            running_proxy
+        },
+    )
+    .await;
+
+    match res {
+        Ok(()) => (),
+        Err(ProxyError::Worker(WorkerError::DriverDisconnected(_))) => (),
+        Err(err) => panic!("{}", err),
+    }
+}